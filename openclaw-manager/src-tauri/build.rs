@@ -1,3 +1,7 @@
 fn main() {
-    tauri_build::build()
+    // 自定义 Windows 应用清单：在默认清单基础上启用 longPathAware（支持超过 260 字符的路径）
+    let windows = tauri_build::WindowsAttributes::new()
+        .app_manifest(include_str!("windows-app-manifest.xml"));
+    tauri_build::try_build(tauri_build::Attributes::new().windows_attributes(windows))
+        .expect("failed to run tauri-build");
 }
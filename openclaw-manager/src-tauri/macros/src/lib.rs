@@ -0,0 +1,64 @@
+//! OpenClaw Manager 的过程宏
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{
+    parse_macro_input, parse_quote, GenericArgument, ItemFn, PathArguments, ReturnType, Type,
+};
+
+/// 为前端命令加上 panic 保护：函数体在 `panic_guard::guard` 中执行，
+/// 返回类型 `Result<T, String>` 改为 `Result<T, CommandError>`（错误带命令名和请求编号）
+///
+/// 写在 `#[command]` 之前：
+///
+/// ```ignore
+/// #[guarded]
+/// #[command]
+/// pub async fn get_config() -> Result<Value, String> { ... }
+/// ```
+#[proc_macro_attribute]
+pub fn guarded(attr: TokenStream, item: TokenStream) -> TokenStream {
+    if !attr.is_empty() {
+        return syn::Error::new(proc_macro2::Span::call_site(), "#[guarded] 不接受参数")
+            .to_compile_error()
+            .into();
+    }
+    let mut function = parse_macro_input!(item as ItemFn);
+    if function.sig.asyncness.is_none() {
+        return syn::Error::new_spanned(&function.sig, "#[guarded] 只能用于 async 命令")
+            .to_compile_error()
+            .into();
+    }
+    if let Err(e) = replace_error_type(&mut function.sig.output) {
+        return e.to_compile_error().into();
+    }
+
+    let name = function.sig.ident.to_string();
+    let body = &function.block;
+    function.block = parse_quote!({
+        crate::utils::panic_guard::guard(#name, async move #body).await
+    });
+    quote!(#function).into()
+}
+
+/// 将 `Result<T, String>` 的错误类型替换为 `CommandError`
+fn replace_error_type(output: &mut ReturnType) -> syn::Result<()> {
+    let error = || syn::Error::new_spanned(&*output, "#[guarded] 命令需要返回 Result<T, String>");
+    let ReturnType::Type(_, ty) = &*output else {
+        return Err(error());
+    };
+    let Type::Path(path) = &**ty else {
+        return Err(error());
+    };
+    let Some(segment) = path.path.segments.last().filter(|s| s.ident == "Result") else {
+        return Err(error());
+    };
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return Err(error());
+    };
+    let Some(GenericArgument::Type(ok)) = args.args.first().filter(|_| args.args.len() == 2) else {
+        return Err(error());
+    };
+    let ok = ok.clone();
+    *output = parse_quote!(-> ::std::result::Result<#ok, crate::utils::panic_guard::CommandError>);
+    Ok(())
+}
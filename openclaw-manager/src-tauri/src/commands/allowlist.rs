@@ -0,0 +1,341 @@
+//! 渠道联系人 / 群组访问控制：从 Gateway 读取通讯录，管理谁可以和 Agent 对话，
+//! 保存到 openclaw.json 的 channels.<渠道> 下（allowFrom / groups / denyFrom）
+use crate::commands::capabilities::{self, Feature};
+use crate::commands::{config, diagnostics};
+use crate::utils::{audit, shell};
+use log::info;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::HashSet;
+use openclaw_macros::guarded;
+use tauri::command;
+
+const DM_POLICIES: &[&str] = &["pairing", "allowlist", "open", "disabled"];
+const GROUP_POLICIES: &[&str] = &["open", "allowlist", "disabled"];
+
+/// 通讯录条目
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChannelContact {
+    pub id: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    /// user / group
+    pub kind: String,
+}
+
+/// 渠道访问控制
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ChannelAccessList {
+    pub channel: String,
+    /// 私聊策略（pairing / allowlist / open / disabled，为空时使用 Gateway 默认值）
+    #[serde(default)]
+    pub dm_policy: Option<String>,
+    /// 群聊策略（open / allowlist / disabled）
+    #[serde(default)]
+    pub group_policy: Option<String>,
+    /// 允许私聊的联系人（`*` 表示所有人）
+    #[serde(default)]
+    pub allow_from: Vec<String>,
+    /// 允许的群组（群聊策略为 allowlist 时生效）
+    #[serde(default)]
+    pub allowed_groups: Vec<String>,
+    /// 拒绝的联系人 / 群组（优先于允许列表）
+    #[serde(default)]
+    pub deny_from: Vec<String>,
+}
+
+fn ids(value: Option<&Value>) -> Vec<String> {
+    match value {
+        Some(Value::Array(items)) => items
+            .iter()
+            .filter_map(|v| match v {
+                Value::String(s) => Some(s.clone()),
+                // Telegram 等渠道的用户 ID 可能写成数字
+                Value::Number(n) => Some(n.to_string()),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// 从配置读取渠道访问控制
+fn read_access(cfg: &Value, channel: &str) -> ChannelAccessList {
+    let section = cfg.pointer(&format!("/channels/{}", channel));
+    let field = |name: &str| section.and_then(|s| s.get(name));
+    ChannelAccessList {
+        channel: channel.to_string(),
+        dm_policy: field("dmPolicy")
+            .and_then(Value::as_str)
+            .map(str::to_string),
+        group_policy: field("groupPolicy")
+            .and_then(Value::as_str)
+            .map(str::to_string),
+        allow_from: ids(field("allowFrom")),
+        allowed_groups: field("groups")
+            .and_then(Value::as_object)
+            .map(|groups| groups.keys().cloned().collect())
+            .unwrap_or_default(),
+        deny_from: ids(field("denyFrom")),
+    }
+}
+
+/// 检查访问控制，返回所有问题
+fn validate(access: &ChannelAccessList) -> Vec<String> {
+    let mut errors = Vec::new();
+    if let Some(policy) = access.dm_policy.as_deref() {
+        if !DM_POLICIES.contains(&policy) {
+            errors.push(format!("未知的私聊策略: {}", policy));
+        }
+    }
+    if let Some(policy) = access.group_policy.as_deref() {
+        if !GROUP_POLICIES.contains(&policy) {
+            errors.push(format!("未知的群聊策略: {}", policy));
+        }
+    }
+    for (label, list) in [
+        ("允许列表", &access.allow_from),
+        ("群组列表", &access.allowed_groups),
+        ("拒绝列表", &access.deny_from),
+    ] {
+        let mut seen = HashSet::new();
+        for id in list {
+            if id.trim().is_empty() || id.trim() != id {
+                errors.push(format!("{}中有空白或首尾含空格的 ID: {:?}", label, id));
+            } else if !seen.insert(id) {
+                errors.push(format!("{}中 {} 重复", label, id));
+            }
+        }
+    }
+    let allow_all = access.allow_from.iter().any(|id| id == "*");
+    if allow_all && access.allow_from.len() > 1 {
+        errors.push("允许列表包含 * 时不需要再列出具体联系人".to_string());
+    }
+    if access.deny_from.iter().any(|id| id == "*") {
+        errors.push("拒绝所有人请将私聊策略设为 disabled".to_string());
+    }
+    for id in &access.deny_from {
+        if access.allow_from.contains(id) || access.allowed_groups.contains(id) {
+            errors.push(format!("{} 同时出现在允许和拒绝列表中", id));
+        }
+    }
+    match access.dm_policy.as_deref() {
+        // Gateway 要求 open 策略显式允许所有人
+        Some("open") if !allow_all => errors.push("私聊策略为 open 时允许列表必须为 *".to_string()),
+        Some("allowlist") if access.allow_from.is_empty() => {
+            errors.push("私聊策略为 allowlist 时至少需要一个联系人".to_string())
+        }
+        _ => {}
+    }
+    if access.group_policy.as_deref() == Some("allowlist") && access.allowed_groups.is_empty() {
+        errors.push(
+            "群聊策略为 allowlist 时至少需要一个群组（不允许任何群组请设为 disabled）".to_string(),
+        );
+    }
+    errors
+}
+
+fn set_or_remove(section: &mut Map<String, Value>, key: &str, value: Option<Value>) {
+    match value {
+        Some(value) => {
+            section.insert(key.to_string(), value);
+        }
+        None => {
+            section.remove(key);
+        }
+    }
+}
+
+fn id_list(list: &[String]) -> Option<Value> {
+    (!list.is_empty()).then(|| json!(list))
+}
+
+/// 写入渠道访问控制，保留群组已有的其他设置（如 requireMention）
+fn apply_access(cfg: &mut Value, access: &ChannelAccessList) -> Result<(), String> {
+    let section = cfg
+        .pointer_mut(&format!("/channels/{}", access.channel))
+        .and_then(Value::as_object_mut)
+        .ok_or_else(|| format!("渠道 {} 尚未配置", access.channel))?;
+    let mut groups = section
+        .get("groups")
+        .and_then(Value::as_object)
+        .cloned()
+        .unwrap_or_default();
+    groups.retain(|id, _| access.allowed_groups.contains(id));
+    for id in &access.allowed_groups {
+        groups.entry(id.clone()).or_insert_with(|| json!({}));
+    }
+    set_or_remove(
+        section,
+        "dmPolicy",
+        access.dm_policy.clone().map(Value::from),
+    );
+    set_or_remove(
+        section,
+        "groupPolicy",
+        access.group_policy.clone().map(Value::from),
+    );
+    set_or_remove(section, "allowFrom", id_list(&access.allow_from));
+    set_or_remove(
+        section,
+        "groups",
+        (!groups.is_empty()).then_some(Value::Object(groups)),
+    );
+    set_or_remove(section, "denyFrom", id_list(&access.deny_from));
+    Ok(())
+}
+
+/// 解析 openclaw directory 的输出（数组或 { entries / peers / groups: [...] }）
+fn parse_contacts(value: &Value, kind: &str) -> Vec<ChannelContact> {
+    let items = match value {
+        Value::Array(items) => items,
+        _ => match ["entries", "peers", "groups", "items"]
+            .iter()
+            .find_map(|key| value.get(*key).and_then(Value::as_array))
+        {
+            Some(items) => items,
+            None => return Vec::new(),
+        },
+    };
+    items
+        .iter()
+        .filter_map(|item| {
+            let text = |names: &[&str]| {
+                names
+                    .iter()
+                    .find_map(|n| item.get(*n).and_then(Value::as_str))
+                    .map(str::to_string)
+            };
+            let id = text(&["id", "peerId", "groupId"]).or_else(|| {
+                item.get("id")
+                    .and_then(Value::as_i64)
+                    .map(|n| n.to_string())
+            })?;
+            Some(ChannelContact {
+                id,
+                name: text(&["name", "displayName", "title", "handle"]),
+                kind: kind.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// 从 Gateway 读取渠道的联系人或群组
+fn fetch_contacts(channel: &str, kind: &str) -> Result<Vec<ChannelContact>, String> {
+    capabilities::require(Feature::Directory)?;
+    let scope = if kind == "group" { "groups" } else { "peers" };
+    let output =
+        shell::run_openclaw(&["directory", scope, "list", "--channel", channel, "--json"])?;
+    let json = diagnostics::extract_json_from_output(&output)
+        .ok_or_else(|| format!("无法解析 {} 的通讯录: {}", channel, output.trim()))?;
+    let value: Value =
+        serde_json::from_str(&json).map_err(|e| format!("解析 {} 的通讯录失败: {}", channel, e))?;
+    Ok(parse_contacts(&value, kind))
+}
+
+/// 获取渠道的联系人和群组（`kind` 为 user / group，为空时两者都获取）
+#[guarded]
+#[command]
+pub async fn list_channel_contacts(
+    channel: String,
+    kind: Option<String>,
+) -> Result<Vec<ChannelContact>, String> {
+    let kinds: Vec<&str> = match kind.as_deref() {
+        Some(kind @ ("user" | "group")) => vec![kind],
+        Some(other) => return Err(format!("未知的类型: {}", other)),
+        None => vec!["user", "group"],
+    };
+    let mut contacts = Vec::new();
+    for kind in kinds {
+        contacts.extend(fetch_contacts(&channel, kind)?);
+    }
+    info!(
+        "[访问控制] {} 返回 {} 个联系人/群组",
+        channel,
+        contacts.len()
+    );
+    Ok(contacts)
+}
+
+/// 读取渠道访问控制
+#[guarded]
+#[command]
+pub async fn get_channel_access(channel: String) -> Result<ChannelAccessList, String> {
+    Ok(read_access(&config::load_openclaw_config()?, &channel))
+}
+
+/// 检查渠道访问控制，返回问题列表（为空表示可以保存）
+#[guarded]
+#[command]
+pub async fn validate_channel_access(access: ChannelAccessList) -> Result<Vec<String>, String> {
+    Ok(validate(&access))
+}
+
+/// 保存渠道访问控制到 openclaw.json
+#[guarded]
+#[command]
+pub async fn save_channel_access(access: ChannelAccessList) -> Result<String, String> {
+    let errors = validate(&access);
+    if !errors.is_empty() {
+        return Err(format!("访问控制有误: {}", errors.join("；")));
+    }
+    let mut cfg = config::load_openclaw_config()?;
+    apply_access(&mut cfg, &access)?;
+    config::save_config(cfg).await?;
+    audit::record(
+        "save_channel_access",
+        &access.channel,
+        true,
+        json!({
+            "dm_policy": access.dm_policy,
+            "group_policy": access.group_policy,
+            "allow_from": access.allow_from.len(),
+            "allowed_groups": access.allowed_groups.len(),
+            "deny_from": access.deny_from.len(),
+        }),
+    );
+    info!("[访问控制] ✓ 已保存 {} 的访问控制", access.channel);
+    Ok(format!("{} 的访问控制已保存", access.channel))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validates_and_round_trips_access_lists() {
+        let mut cfg = json!({ "channels": { "telegram": {
+            "botToken": "123",
+            "allowFrom": [10001],
+            "groups": { "-100": { "requireMention": true }, "-200": {} }
+        }}});
+        let mut access = read_access(&cfg, "telegram");
+        assert_eq!(access.allow_from, vec!["10001"]);
+
+        access.group_policy = Some("allowlist".to_string());
+        access.allowed_groups = vec!["-100".to_string(), "-300".to_string()];
+        access.deny_from = vec!["10001".to_string()];
+        assert_eq!(validate(&access).len(), 1);
+
+        access.deny_from = vec!["666".to_string()];
+        assert!(validate(&access).is_empty());
+        apply_access(&mut cfg, &access).unwrap();
+        let section = &cfg["channels"]["telegram"];
+        assert_eq!(section["groups"]["-100"]["requireMention"], true);
+        assert!(section["groups"].get("-200").is_none());
+        assert_eq!(section["groupPolicy"], "allowlist");
+        assert_eq!(section["botToken"], "123");
+        assert_eq!(read_access(&cfg, "telegram"), access);
+
+        access.dm_policy = Some("open".to_string());
+        assert!(!validate(&access).is_empty());
+        assert!(apply_access(
+            &mut cfg,
+            &ChannelAccessList {
+                channel: "slack".to_string(),
+                ..Default::default()
+            }
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,602 @@
+use crate::utils::{platform, time};
+use log::{info, warn};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use openclaw_macros::guarded;
+use tauri::command;
+
+/// 会话事件中与统计相关的部分
+#[derive(Debug, Clone, PartialEq)]
+struct EventRecord {
+    /// user / assistant / tool
+    role: String,
+    /// 毫秒时间戳
+    ts: i64,
+    is_error: bool,
+    /// 本条消息调用的工具 / 技能
+    tools: Vec<String>,
+    /// 生成回复的 AI Provider
+    provider: Option<String>,
+    /// 本条消息的费用（美元，来自 usage.cost）
+    cost: f64,
+}
+
+/// 每个 Agent 的统计
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentStats {
+    pub agent: String,
+    pub sessions: u64,
+    pub user_messages: u64,
+    pub assistant_messages: u64,
+    /// 平均响应延迟（用户消息到助手回复）
+    pub avg_latency_ms: Option<f64>,
+    pub errors: u64,
+    /// 错误回复占助手消息的比例
+    pub error_rate: f64,
+}
+
+/// 技能 / 工具调用次数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkillUsage {
+    pub name: String,
+    pub count: u64,
+}
+
+/// 每日消息数（用于趋势图）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailyCount {
+    pub date: String,
+    pub messages: u64,
+}
+
+/// 会话统计
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConversationStats {
+    pub range: String,
+    pub agents: Vec<AgentStats>,
+    pub top_skills: Vec<SkillUsage>,
+    pub daily: Vec<DailyCount>,
+}
+
+/// 某时刻以来的用量汇总
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UsageSummary {
+    pub sessions: u64,
+    pub user_messages: u64,
+    pub assistant_messages: u64,
+    pub errors: u64,
+    /// 费用（美元）
+    pub cost: f64,
+}
+
+/// 解析时间戳：RFC3339 字符串或毫秒数字
+pub fn parse_ts(value: Option<&Value>) -> Option<i64> {
+    match value? {
+        Value::String(s) => chrono::DateTime::parse_from_rfc3339(s)
+            .ok()
+            .map(|t| t.timestamp_millis()),
+        Value::Number(n) => n.as_i64(),
+        _ => None,
+    }
+}
+
+/// 解析会话 JSONL 的一行（非消息事件返回 None）
+fn parse_event(line: &str) -> Option<EventRecord> {
+    let event: Value = serde_json::from_str(line).ok()?;
+    let message = event.get("message").unwrap_or(&event);
+    let role = message.get("role")?.as_str()?.to_string();
+    let ts = parse_ts(event.get("timestamp")).or_else(|| parse_ts(message.get("timestamp")))?;
+
+    let is_error = message.get("stopReason").and_then(|v| v.as_str()) == Some("error")
+        || message.get("errorMessage").is_some()
+        || message.get("isError").and_then(|v| v.as_bool()) == Some(true);
+
+    let tools = message
+        .get("content")
+        .and_then(|c| c.as_array())
+        .map(|parts| {
+            parts
+                .iter()
+                .filter(|p| {
+                    matches!(
+                        p.get("type").and_then(|t| t.as_str()),
+                        Some("toolCall") | Some("tool_use")
+                    )
+                })
+                .filter_map(|p| p.get("name").and_then(|n| n.as_str()))
+                .map(|n| n.to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let provider = message
+        .get("provider")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string());
+    let cost = message
+        .pointer("/usage/cost/total")
+        .or_else(|| message.pointer("/usage/cost"))
+        .and_then(|v| v.as_f64())
+        .unwrap_or(0.0);
+
+    Some(EventRecord {
+        role,
+        ts,
+        is_error,
+        tools,
+        provider,
+        cost,
+    })
+}
+
+/// 数据库结构版本，升级时清空已导入数据重新导入
+const SCHEMA_VERSION: i64 = 1;
+
+fn db_path() -> PathBuf {
+    Path::new(&platform::get_manager_data_dir()).join("analytics.db")
+}
+
+fn open_db(path: &Path) -> Result<Connection, String> {
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    let conn = Connection::open(path).map_err(|e| format!("打开统计数据库失败: {}", e))?;
+    init_schema(&conn)?;
+    Ok(conn)
+}
+
+fn init_schema(conn: &Connection) -> Result<(), String> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS scanned_files (
+            path TEXT PRIMARY KEY,
+            offset INTEGER NOT NULL,
+            last_user_ts INTEGER
+        );
+        CREATE TABLE IF NOT EXISTS messages (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            path TEXT NOT NULL,
+            agent TEXT NOT NULL,
+            session TEXT NOT NULL,
+            role TEXT NOT NULL,
+            ts INTEGER NOT NULL,
+            latency_ms INTEGER,
+            is_error INTEGER NOT NULL DEFAULT 0
+        );
+        CREATE INDEX IF NOT EXISTS idx_messages_ts ON messages(ts);
+        CREATE TABLE IF NOT EXISTS tool_calls (
+            path TEXT NOT NULL,
+            agent TEXT NOT NULL,
+            name TEXT NOT NULL,
+            ts INTEGER NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_tool_calls_ts ON tool_calls(ts);
+        CREATE TABLE IF NOT EXISTS usage (
+            path TEXT NOT NULL,
+            provider TEXT NOT NULL,
+            ts INTEGER NOT NULL,
+            cost REAL NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_usage_ts ON usage(ts);
+        CREATE TABLE IF NOT EXISTS gateway_status (
+            ts INTEGER NOT NULL,
+            state TEXT NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_gateway_status_ts ON gateway_status(ts);",
+    )
+    .map_err(|e| format!("初始化统计数据库失败: {}", e))?;
+
+    // 旧版本没有记录费用，清空后重新导入
+    let version: i64 = conn
+        .query_row("PRAGMA user_version", [], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+    if version < SCHEMA_VERSION {
+        conn.execute_batch(&format!(
+            "DELETE FROM scanned_files; DELETE FROM messages; DELETE FROM tool_calls;
+             DELETE FROM usage; PRAGMA user_version = {};",
+            SCHEMA_VERSION
+        ))
+        .map_err(|e| format!("升级统计数据库失败: {}", e))?;
+    }
+    Ok(())
+}
+
+/// 会话文件列表：(agent, session, path)
+fn session_files(config_dir: &Path) -> Vec<(String, String, PathBuf)> {
+    let mut files = Vec::new();
+    let Ok(agents) = std::fs::read_dir(config_dir.join("agents")) else {
+        return files;
+    };
+    for agent in agents.flatten() {
+        let agent_name = agent.file_name().to_string_lossy().to_string();
+        let Ok(sessions) = std::fs::read_dir(agent.path().join("sessions")) else {
+            continue;
+        };
+        for session in sessions.flatten() {
+            let path = session.path();
+            if path.extension().map(|e| e == "jsonl").unwrap_or(false) {
+                let id = path
+                    .file_stem()
+                    .map(|s| s.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                files.push((agent_name.clone(), id, path));
+            }
+        }
+    }
+    files
+}
+
+/// 增量导入一个会话文件：只读取上次导入位置之后追加的内容
+/// 文件变小（被截断或重写）时删除旧数据重新导入
+fn ingest_file(
+    conn: &mut Connection,
+    agent: &str,
+    session: &str,
+    path: &Path,
+) -> Result<usize, String> {
+    let key = path.to_string_lossy().to_string();
+    let len = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    let state: Option<(i64, Option<i64>)> = conn
+        .query_row(
+            "SELECT offset, last_user_ts FROM scanned_files WHERE path = ?1",
+            params![key],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?;
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let (mut offset, mut last_user_ts) = match state {
+        Some((offset, ts)) if offset as u64 <= len => (offset as u64, ts),
+        Some(_) => {
+            tx.execute("DELETE FROM messages WHERE path = ?1", params![key])
+                .map_err(|e| e.to_string())?;
+            tx.execute("DELETE FROM tool_calls WHERE path = ?1", params![key])
+                .map_err(|e| e.to_string())?;
+            tx.execute("DELETE FROM usage WHERE path = ?1", params![key])
+                .map_err(|e| e.to_string())?;
+            (0, None)
+        }
+        None => (0, None),
+    };
+    if offset == len {
+        tx.commit().map_err(|e| e.to_string())?;
+        return Ok(0);
+    }
+
+    let mut file = std::fs::File::open(path).map_err(|e| e.to_string())?;
+    file.seek(SeekFrom::Start(offset))
+        .map_err(|e| e.to_string())?;
+    let mut reader = BufReader::new(file);
+    let mut imported = 0;
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        let read = reader
+            .read_until(b'\n', &mut line)
+            .map_err(|e| e.to_string())?;
+        // 最后一行尚未写完（没有换行）时留到下次导入
+        if read == 0 || !line.ends_with(b"\n") {
+            break;
+        }
+        offset += read as u64;
+
+        let Some(event) = parse_event(&String::from_utf8_lossy(&line)) else {
+            continue;
+        };
+        let latency = match event.role.as_str() {
+            "user" => {
+                last_user_ts = Some(event.ts);
+                None
+            }
+            "assistant" => last_user_ts
+                .take()
+                .map(|t| event.ts - t)
+                .filter(|l| *l >= 0),
+            _ => None,
+        };
+        tx.execute(
+            "INSERT INTO messages (path, agent, session, role, ts, latency_ms, is_error)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                key,
+                agent,
+                session,
+                event.role,
+                event.ts,
+                latency,
+                event.is_error
+            ],
+        )
+        .map_err(|e| e.to_string())?;
+        for tool in &event.tools {
+            tx.execute(
+                "INSERT INTO tool_calls (path, agent, name, ts) VALUES (?1, ?2, ?3, ?4)",
+                params![key, agent, tool, event.ts],
+            )
+            .map_err(|e| e.to_string())?;
+        }
+        if let Some(provider) = event.provider.as_deref().filter(|_| event.cost > 0.0) {
+            tx.execute(
+                "INSERT INTO usage (path, provider, ts, cost) VALUES (?1, ?2, ?3, ?4)",
+                params![key, provider, event.ts, event.cost],
+            )
+            .map_err(|e| e.to_string())?;
+        }
+        imported += 1;
+    }
+
+    tx.execute(
+        "INSERT INTO scanned_files (path, offset, last_user_ts) VALUES (?1, ?2, ?3)
+         ON CONFLICT(path) DO UPDATE SET offset = excluded.offset, last_user_ts = excluded.last_user_ts",
+        params![key, offset as i64, last_user_ts],
+    )
+    .map_err(|e| e.to_string())?;
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(imported)
+}
+
+/// 导入所有会话文件的新增内容
+fn ingest_all(conn: &mut Connection, config_dir: &Path) -> usize {
+    let mut total = 0;
+    for (agent, session, path) in session_files(config_dir) {
+        match ingest_file(conn, &agent, &session, &path) {
+            Ok(n) => total += n,
+            Err(e) => warn!("[会话统计] 导入 {:?} 失败: {}", path, e),
+        }
+    }
+    total
+}
+
+/// 统计范围对应的起始时间（毫秒）
+pub fn range_start(range: &str, now_ms: i64) -> Result<i64, String> {
+    let hours = match range {
+        "24h" => 24,
+        "7d" => 24 * 7,
+        "30d" => 24 * 30,
+        "all" => return Ok(0),
+        _ => {
+            return Err(format!(
+                "不支持的统计范围: {}（可选 24h / 7d / 30d / all）",
+                range
+            ))
+        }
+    };
+    Ok(now_ms - hours * 60 * 60 * 1000)
+}
+
+fn query_stats(conn: &Connection, range: &str, since: i64) -> Result<ConversationStats, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT agent,
+                    COUNT(DISTINCT session),
+                    SUM(role = 'user'),
+                    SUM(role = 'assistant'),
+                    AVG(latency_ms),
+                    SUM(is_error)
+             FROM messages WHERE ts >= ?1
+             GROUP BY agent ORDER BY agent",
+        )
+        .map_err(|e| e.to_string())?;
+    let agents = stmt
+        .query_map(params![since], |row| {
+            let assistant_messages: i64 = row.get(3)?;
+            let errors: i64 = row.get(5)?;
+            Ok(AgentStats {
+                agent: row.get(0)?,
+                sessions: row.get::<_, i64>(1)? as u64,
+                user_messages: row.get::<_, i64>(2)? as u64,
+                assistant_messages: assistant_messages as u64,
+                avg_latency_ms: row.get(4)?,
+                errors: errors as u64,
+                error_rate: if assistant_messages > 0 {
+                    errors as f64 / assistant_messages as f64
+                } else {
+                    0.0
+                },
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT name, COUNT(*) AS n FROM tool_calls WHERE ts >= ?1
+             GROUP BY name ORDER BY n DESC, name LIMIT 10",
+        )
+        .map_err(|e| e.to_string())?;
+    let top_skills = stmt
+        .query_map(params![since], |row| {
+            Ok(SkillUsage {
+                name: row.get(0)?,
+                count: row.get::<_, i64>(1)? as u64,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT date(ts / 1000, 'unixepoch', ?2) AS d, COUNT(*)
+             FROM messages WHERE ts >= ?1 AND role IN ('user', 'assistant')
+             GROUP BY d ORDER BY d",
+        )
+        .map_err(|e| e.to_string())?;
+    let daily = stmt
+        .query_map(params![since, time::sqlite_offset()], |row| {
+            Ok(DailyCount {
+                date: row.get(0)?,
+                messages: row.get::<_, i64>(1)? as u64,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(ConversationStats {
+        range: range.to_string(),
+        agents,
+        top_skills,
+        daily,
+    })
+}
+
+/// 各 Provider 自某时刻起的费用（美元）
+fn query_spend(conn: &Connection, since: i64) -> Result<HashMap<String, f64>, String> {
+    let mut stmt = conn
+        .prepare("SELECT provider, SUM(cost) FROM usage WHERE ts >= ?1 GROUP BY provider")
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![since], |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<HashMap<String, f64>, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(rows)
+}
+
+/// 打开统计数据库并导入新增会话（报表导出等需要直接查询时使用）
+pub fn open_store() -> Result<Connection, String> {
+    let mut conn = open_db(&db_path())?;
+    ingest_all(&mut conn, Path::new(&platform::get_config_dir()));
+    Ok(conn)
+}
+
+fn query_usage(conn: &Connection, since: i64) -> Result<UsageSummary, String> {
+    let (sessions, user_messages, assistant_messages, errors): (i64, i64, i64, i64) = conn
+        .query_row(
+            "SELECT COUNT(DISTINCT agent || '/' || session),
+                    COALESCE(SUM(role = 'user'), 0),
+                    COALESCE(SUM(role = 'assistant'), 0),
+                    COALESCE(SUM(is_error), 0)
+             FROM messages WHERE ts >= ?1",
+            params![since],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )
+        .map_err(|e| e.to_string())?;
+    Ok(UsageSummary {
+        sessions: sessions as u64,
+        user_messages: user_messages as u64,
+        assistant_messages: assistant_messages as u64,
+        errors: errors as u64,
+        cost: query_spend(conn, since)?.values().sum(),
+    })
+}
+
+/// 导入新增会话后，汇总某时刻以来的会话、消息和费用（首页概览使用）
+pub fn usage_since(since_ms: i64) -> Result<UsageSummary, String> {
+    query_usage(&open_store()?, since_ms)
+}
+
+/// 导入新增会话后，统计各 Provider 自某时刻起的费用（预算检查使用）
+pub fn provider_spend(since_ms: i64) -> Result<HashMap<String, f64>, String> {
+    query_spend(&open_store()?, since_ms)
+}
+
+/// 记录 Gateway 状态采样（running / stopped / crashed），用于统计可用率
+pub fn record_gateway_state(state: &str) {
+    let result = open_db(&db_path()).and_then(|conn| {
+        conn.execute(
+            "INSERT INTO gateway_status (ts, state) VALUES (?1, ?2)",
+            params![chrono::Utc::now().timestamp_millis(), state],
+        )
+        .map_err(|e| e.to_string())
+    });
+    if let Err(e) = result {
+        warn!("[会话统计] 记录 Gateway 状态失败: {}", e);
+    }
+}
+
+/// 获取会话统计（消息数、平均响应延迟、常用技能、错误率）
+/// 会话文件增量导入 SQLite，只处理上次统计后新增的内容
+#[guarded]
+#[command]
+pub async fn get_conversation_stats(range: Option<String>) -> Result<ConversationStats, String> {
+    let range = range.unwrap_or_else(|| "7d".to_string());
+    let since = range_start(&range, chrono::Utc::now().timestamp_millis())?;
+
+    let mut conn = open_db(&db_path())?;
+    let imported = ingest_all(&mut conn, Path::new(&platform::get_config_dir()));
+    if imported > 0 {
+        info!("[会话统计] 新导入 {} 条消息", imported);
+    }
+    query_stats(&conn, &range, since)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn make_temp_dir(prefix: &str) -> PathBuf {
+        let mut base = std::env::temp_dir();
+        let suffix = format!(
+            "{}_{}",
+            prefix,
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_nanos()
+        );
+        base.push(suffix);
+        std::fs::create_dir_all(&base).unwrap();
+        base
+    }
+
+    #[test]
+    fn sessions_are_ingested_incrementally() {
+        let dir = make_temp_dir("openclaw_analytics");
+        let sessions = dir.join("agents").join("main").join("sessions");
+        std::fs::create_dir_all(&sessions).unwrap();
+        let file = sessions.join("s1.jsonl");
+        std::fs::write(
+            &file,
+            concat!(
+                r#"{"type":"session","id":"s1"}"#, "\n",
+                r#"{"type":"message","timestamp":"2026-01-01T10:00:00Z","message":{"role":"user","content":"hi"}}"#, "\n",
+                r#"{"type":"message","timestamp":"2026-01-01T10:00:02Z","message":{"role":"assistant","provider":"anthropic","usage":{"cost":{"total":0.25}},"content":[{"type":"toolCall","name":"weather"}]}}"#, "\n",
+            ),
+        )
+        .unwrap();
+
+        let mut conn = Connection::open_in_memory().unwrap();
+        init_schema(&conn).unwrap();
+        assert_eq!(ingest_all(&mut conn, &dir), 2);
+        // 未变化的文件不会重复导入
+        assert_eq!(ingest_all(&mut conn, &dir), 0);
+
+        let mut f = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&file)
+            .unwrap();
+        writeln!(
+            f,
+            r#"{{"type":"message","timestamp":"2026-01-01T10:01:00Z","message":{{"role":"user","content":"again"}}}}"#
+        )
+        .unwrap();
+        writeln!(
+            f,
+            r#"{{"type":"message","timestamp":"2026-01-01T10:01:04Z","message":{{"role":"assistant","provider":"anthropic","usage":{{"cost":{{"total":0.5}}}},"stopReason":"error","content":[]}}}}"#
+        )
+        .unwrap();
+        assert_eq!(ingest_all(&mut conn, &dir), 2);
+
+        let stats = query_stats(&conn, "all", 0).unwrap();
+        let main = &stats.agents[0];
+        assert_eq!(
+            (main.user_messages, main.assistant_messages, main.errors),
+            (2, 2, 1)
+        );
+        assert_eq!(main.avg_latency_ms, Some(3000.0));
+        assert_eq!(stats.top_skills[0].name, "weather");
+        assert_eq!(query_spend(&conn, 0).unwrap().get("anthropic"), Some(&0.75));
+        let usage = query_usage(&conn, 0).unwrap();
+        assert_eq!((usage.sessions, usage.errors, usage.cost), (1, 1, 0.75));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,1409 @@
+//! 远程备份：将 OpenClaw 配置目录（配置、Agent 记忆、会话）打包加密后上传到 WebDAV / S3，
+//! 按保留规则清理旧备份，并支持从远程备份恢复，避免磁盘损坏时丢失数据
+//!
+//! 备份文件格式（加密前）：`OCB3` | 清单长度 (u32 BE) | 清单 JSON；
+//! 文件按内容切分为数据块（见 `utils::chunking`），清单记录每个文件由哪些块组成及其 SHA-256。
+//! 数据块依次拼接为约 8 MB 的数据包，逐个加密后边打包边上传到 `<备份文件>.d/<序号>`，
+//! 最后上传清单，内存中最多只保留几个数据包；恢复时逐个下载数据包写入临时文件，逐块、逐文件校验。
+//! 差异备份只保存最近一次完整备份中没有的数据块，恢复时同时下载所依据的完整备份
+use crate::commands::{policy, probe_cache, profiles, service, settings};
+use crate::utils::remote_storage::{RemoteObject, RemoteTarget};
+use crate::utils::{audit, chunking, crypto, file, platform, secrets, time};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Component, Path, PathBuf};
+use openclaw_macros::guarded;
+use tauri::command;
+
+const MAGIC: &[u8; 4] = b"OCB3";
+/// 数据包大小（达到后加密上传）
+const PACK_SIZE: usize = 8 * 1024 * 1024;
+/// 远程存储中的备份目录（默认档案；其他档案使用其下以档案名命名的子目录）
+const REMOTE_DIR: &str = "backups";
+/// 备份文件名前缀和扩展名（文件名中的时间用于保留规则）
+const NAME_PREFIX: &str = "openclaw-";
+const NAME_SUFFIX: &str = ".ocb";
+const NAME_TIME_FORMAT: &str = "%Y%m%d-%H%M%S";
+/// 差异备份文件名：`openclaw-<时间>-diff-<完整备份时间>.ocb`
+const DIFF_MARKER: &str = "-diff-";
+/// 备份口令在钥匙串中的账户名
+const PASSPHRASE_ACCOUNT: &str = "backup:passphrase";
+/// 远程存储密钥在钥匙串中的账户名前缀
+const REMOTE_ACCOUNT: &str = "backup:remote";
+/// 不备份的文件和目录（日志、依赖、运行时文件）
+const EXCLUDED: &[&str] = &["logs", "node_modules", "gateway.pid", "gateway.lock"];
+
+static BACKUP_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+/// 远程备份的保留规则：始终保留最近 keep_last 个，更早的备份超过 keep_days 天后删除
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetentionRules {
+    pub keep_last: usize,
+    /// 0 表示只按个数保留
+    pub keep_days: u32,
+}
+
+impl Default for RetentionRules {
+    fn default() -> Self {
+        Self {
+            keep_last: 7,
+            keep_days: 30,
+        }
+    }
+}
+
+/// 备份方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackupMode {
+    /// 每次上传完整备份
+    #[default]
+    Full,
+    /// 只上传相对最近一次完整备份变化的数据块，定期自动完整备份
+    Differential,
+}
+
+/// 远程备份设置（保存在 Manager 设置中）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BackupSettings {
+    pub remote: Option<RemoteTarget>,
+    /// 备份加密口令（钥匙串引用），恢复时需要相同的口令
+    pub passphrase: String,
+    pub retention: RetentionRules,
+    /// 自动备份间隔（小时，0 表示只手动备份）
+    pub interval_hours: u64,
+    pub mode: BackupMode,
+    /// 差异备份模式下，距上次完整备份超过天数时重新完整备份（0 表示不限）
+    pub full_every_days: u32,
+    /// 差异备份模式下，差异备份达到个数时重新完整备份（0 表示不限）
+    pub full_every_backups: u32,
+}
+
+impl Default for BackupSettings {
+    fn default() -> Self {
+        Self {
+            remote: None,
+            passphrase: String::new(),
+            retention: RetentionRules::default(),
+            interval_hours: 0,
+            mode: BackupMode::Full,
+            full_every_days: 7,
+            full_every_backups: 10,
+        }
+    }
+}
+
+/// 清单中的文件（内容为各数据块按顺序拼接）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ArchivedFile {
+    /// 相对于配置目录的路径（以 / 分隔）
+    path: String,
+    size: u64,
+    sha256: String,
+    /// 数据块的 SHA-256
+    chunks: Vec<String>,
+}
+
+/// 本备份中保存的数据块（偏移相对于所在数据包）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredChunk {
+    hash: String,
+    pack: u32,
+    offset: u64,
+    len: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Manifest {
+    created_at: String,
+    kind: BackupMode,
+    /// 差异备份所依据的完整备份名
+    #[serde(default)]
+    base: Option<String>,
+    files: Vec<ArchivedFile>,
+    chunks: Vec<StoredChunk>,
+    /// 数据包个数
+    packs: u32,
+}
+
+/// 打包结果
+struct Packed {
+    /// 备份文件（只含清单，数据块已通过数据包输出）
+    archive: Vec<u8>,
+    /// 目录中所有文件用到的数据块（完整备份时写入索引）
+    chunks: Vec<String>,
+    /// 本次实际保存的数据块数
+    stored: usize,
+    packs: u32,
+}
+
+/// 远程存储中的一个备份
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteBackup {
+    pub key: String,
+    pub name: String,
+    pub created_at: Option<String>,
+    pub size: Option<u64>,
+    pub kind: BackupMode,
+    /// 差异备份所依据的完整备份 key
+    pub base: Option<String>,
+}
+
+/// 本机备份状态（保存在 Manager 数据目录的 backup_state.json）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct BackupState {
+    last_backup_at: Option<String>,
+    last_error: Option<String>,
+}
+
+/// 最近一次完整备份的数据块索引（保存在 Manager 数据目录的 backup_index.json，
+/// 非默认档案为 backup_index-<档案名>.json），差异备份据此跳过已上传的数据块
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct FullIndex {
+    /// 远程存储描述，更换存储位置后重新完整备份
+    target: String,
+    name: String,
+    chunks: Vec<String>,
+    /// 之后已上传的差异备份个数
+    differentials: u32,
+}
+
+/// 远程备份状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupStatus {
+    pub settings: BackupSettings,
+    /// 远程存储描述（不含密钥）
+    pub target: Option<String>,
+    pub last_backup_at: Option<String>,
+    pub last_error: Option<String>,
+    /// 差异备份所依据的完整备份名
+    pub full_backup: Option<String>,
+    /// 该完整备份之后的差异备份个数
+    pub differentials: u32,
+}
+
+fn state_path() -> PathBuf {
+    Path::new(&platform::get_manager_data_dir()).join("backup_state.json")
+}
+
+fn load_state() -> BackupState {
+    std::fs::read_to_string(state_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_state(state: &BackupState) {
+    let result = serde_json::to_vec_pretty(state)
+        .map_err(|e| e.to_string())
+        .and_then(|content| file::write_atomic(&state_path(), &content).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+        warn!("[远程备份] 保存备份状态失败: {}", e);
+    }
+}
+
+/// 当前档案的数据块索引
+fn index_path() -> PathBuf {
+    let profile = profiles::active_profile();
+    let file = if profile == profiles::DEFAULT_PROFILE {
+        "backup_index.json".to_string()
+    } else {
+        format!("backup_index-{}.json", profile)
+    };
+    Path::new(&platform::get_manager_data_dir()).join(file)
+}
+
+fn load_index() -> Option<FullIndex> {
+    std::fs::read_to_string(index_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+}
+
+fn save_index(index: &FullIndex) {
+    let result = serde_json::to_vec(index)
+        .map_err(|e| e.to_string())
+        .and_then(|content| file::write_atomic(&index_path(), &content).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+        warn!("[远程备份] 保存数据块索引失败: {}", e);
+    }
+}
+
+/// 收集需要备份的文件（跳过符号链接和排除项）
+fn collect_files(root: &Path, dir: &Path, out: &mut Vec<(String, PathBuf)>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        if EXCLUDED.contains(&name.as_str()) {
+            continue;
+        }
+        let file_type = entry.file_type()?;
+        let path = entry.path();
+        if file_type.is_dir() {
+            collect_files(root, &path, out)?;
+        } else if file_type.is_file() {
+            let relative = path
+                .strip_prefix(root)
+                .unwrap_or(&path)
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().to_string())
+                .collect::<Vec<_>>()
+                .join("/");
+            out.push((relative, path));
+        }
+    }
+    Ok(())
+}
+
+/// 打包目录；指定 `base`（完整备份名及其数据块）时生成差异备份，跳过完整备份中已有的数据块
+/// 数据块拼接为约 `pack_size` 字节的数据包，每满一个就交给 `emit`（参数为序号和内容）
+fn pack(
+    root: &Path,
+    base: Option<(&str, &HashSet<String>)>,
+    pack_size: usize,
+    mut emit: impl FnMut(u32, Vec<u8>) -> Result<(), String>,
+) -> Result<Packed, String> {
+    let mut sources = Vec::new();
+    collect_files(root, root, &mut sources).map_err(|e| format!("读取配置目录失败: {}", e))?;
+    sources.sort();
+
+    let mut seen = HashSet::new();
+    let mut all_chunks = Vec::new();
+    let mut stored = Vec::new();
+    let mut data = Vec::new();
+    let mut packs = 0u32;
+    let mut files = Vec::new();
+    for (path, source) in sources {
+        let reader =
+            std::fs::File::open(&source).map_err(|e| format!("读取 {} 失败: {}", path, e))?;
+        let mut hasher = Sha256::new();
+        let mut size = 0u64;
+        let mut hashes = Vec::new();
+        for chunk in chunking::split_reader(reader) {
+            let chunk = chunk.map_err(|e| format!("读取 {} 失败: {}", path, e))?;
+            hasher.update(&chunk);
+            size += chunk.len() as u64;
+            let hash = hex::encode(Sha256::digest(&chunk));
+            if seen.insert(hash.clone()) {
+                all_chunks.push(hash.clone());
+                if !base.is_some_and(|(_, chunks)| chunks.contains(&hash)) {
+                    stored.push(StoredChunk {
+                        hash: hash.clone(),
+                        pack: packs,
+                        offset: data.len() as u64,
+                        len: chunk.len() as u64,
+                    });
+                    data.extend_from_slice(&chunk);
+                    if data.len() >= pack_size {
+                        emit(packs, std::mem::take(&mut data))?;
+                        packs += 1;
+                    }
+                }
+            }
+            hashes.push(hash);
+        }
+        files.push(ArchivedFile {
+            path,
+            size,
+            sha256: hex::encode(hasher.finalize()),
+            chunks: hashes,
+        });
+    }
+    if !data.is_empty() {
+        emit(packs, data)?;
+        packs += 1;
+    }
+    let stored_count = stored.len();
+    let manifest = serde_json::to_vec(&Manifest {
+        created_at: time::now_rfc3339(),
+        kind: if base.is_some() {
+            BackupMode::Differential
+        } else {
+            BackupMode::Full
+        },
+        base: base.map(|(name, _)| name.to_string()),
+        files,
+        chunks: stored,
+        packs,
+    })
+    .map_err(|e| e.to_string())?;
+
+    let mut out = Vec::with_capacity(8 + manifest.len());
+    out.extend_from_slice(MAGIC);
+    out.extend_from_slice(&(manifest.len() as u32).to_be_bytes());
+    out.extend_from_slice(&manifest);
+    Ok(Packed {
+        archive: out,
+        chunks: all_chunks,
+        stored: stored_count,
+        packs,
+    })
+}
+
+/// 清单中的路径是否安全（不能跳出目标目录）
+fn is_safe_path(path: &str) -> bool {
+    !path.is_empty()
+        && Path::new(path)
+            .components()
+            .all(|c| matches!(c, Component::Normal(_)))
+}
+
+/// 解析备份文件，返回清单（数据在单独的数据包中）
+fn parse_archive(archive: &[u8]) -> Result<Manifest, String> {
+    if !archive.starts_with(MAGIC) || archive.len() < 8 {
+        return Err("不是有效的备份文件".to_string());
+    }
+    let manifest_len =
+        u32::from_be_bytes([archive[4], archive[5], archive[6], archive[7]]) as usize;
+    let manifest_end = 8usize
+        .checked_add(manifest_len)
+        .filter(|end| *end <= archive.len())
+        .ok_or("备份文件已损坏（清单不完整）")?;
+    serde_json::from_slice(&archive[8..manifest_end])
+        .map_err(|e| format!("备份清单格式不正确: {}", e))
+}
+
+/// 恢复时数据包所属的备份
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Origin {
+    /// 要恢复的备份
+    Own,
+    /// 差异备份所依据的完整备份
+    Base,
+}
+
+/// 解包好的数据包在临时目录中的路径
+fn pack_file(scratch: &Path, origin: Origin, pack: u32) -> PathBuf {
+    let prefix = match origin {
+        Origin::Own => "own",
+        Origin::Base => "base",
+    };
+    scratch.join(format!("{}-{}", prefix, pack))
+}
+
+/// 清单中文件用到的每个数据块所在的位置（本备份中没有的数据块取自完整备份）
+fn locate_chunks<'a>(
+    manifest: &'a Manifest,
+    base: Option<&'a Manifest>,
+) -> Result<HashMap<&'a str, (Origin, &'a StoredChunk)>, String> {
+    if manifest.base.is_some() != base.is_some() {
+        return Err(match &manifest.base {
+            Some(name) => format!("差异备份需要完整备份 {} 才能恢复", name),
+            None => "完整备份不依据其他备份".to_string(),
+        });
+    }
+    let mut locations = HashMap::new();
+    if let Some(base) = base {
+        if base.base.is_some() {
+            return Err("差异备份所依据的备份不是完整备份".to_string());
+        }
+        for chunk in &base.chunks {
+            locations.insert(chunk.hash.as_str(), (Origin::Base, chunk));
+        }
+    }
+    for chunk in &manifest.chunks {
+        locations.insert(chunk.hash.as_str(), (Origin::Own, chunk));
+    }
+    for entry in &manifest.files {
+        if !is_safe_path(&entry.path) {
+            return Err(format!("备份中包含不安全的路径: {}", entry.path));
+        }
+        if entry
+            .chunks
+            .iter()
+            .any(|hash| !locations.contains_key(hash.as_str()))
+        {
+            return Err(format!("备份文件已损坏（{} 缺少数据块）", entry.path));
+        }
+    }
+    Ok(locations)
+}
+
+/// 恢复需要的数据包（完整备份中只下载包含所用数据块的数据包）
+fn needed_packs(
+    manifest: &Manifest,
+    locations: &HashMap<&str, (Origin, &StoredChunk)>,
+) -> Vec<(Origin, u32)> {
+    let mut packs: Vec<(Origin, u32)> = manifest
+        .files
+        .iter()
+        .flat_map(|entry| &entry.chunks)
+        .map(|hash| {
+            let (origin, chunk) = locations[hash.as_str()];
+            (origin, chunk.pack)
+        })
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    packs.sort_by_key(|(origin, pack)| (*origin == Origin::Base, *pack));
+    packs
+}
+
+/// 从临时目录中的数据包还原文件，逐块、逐文件校验，返回文件数
+fn assemble(
+    manifest: &Manifest,
+    locations: &HashMap<&str, (Origin, &StoredChunk)>,
+    scratch: &Path,
+    dest: &Path,
+) -> Result<usize, String> {
+    let mut packs: HashMap<(Origin, u32), std::fs::File> = HashMap::new();
+    let mut buf = Vec::new();
+    for entry in &manifest.files {
+        let target = dest.join(&entry.path);
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| format!("创建目录失败: {}", e))?;
+        }
+        let mut out = std::fs::File::create(&target)
+            .map_err(|e| format!("写入 {} 失败: {}", entry.path, e))?;
+        let mut hasher = Sha256::new();
+        let mut size = 0u64;
+        for hash in &entry.chunks {
+            let (origin, chunk) = locations[hash.as_str()];
+            let pack = match packs.entry((origin, chunk.pack)) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => entry.insert(
+                    std::fs::File::open(pack_file(scratch, origin, chunk.pack))
+                        .map_err(|_| "备份文件已损坏（数据包不完整）".to_string())?,
+                ),
+            };
+            let pack_len = pack.metadata().map_err(|e| e.to_string())?.len();
+            if chunk
+                .offset
+                .checked_add(chunk.len)
+                .is_none_or(|end| end > pack_len)
+            {
+                return Err("备份文件已损坏（数据块不完整）".to_string());
+            }
+            buf.resize(chunk.len as usize, 0);
+            pack.seek(SeekFrom::Start(chunk.offset))
+                .and_then(|_| pack.read_exact(&mut buf))
+                .map_err(|e| format!("读取数据包失败: {}", e))?;
+            if hex::encode(Sha256::digest(&buf)) != chunk.hash {
+                return Err("备份文件已损坏（数据块校验失败）".to_string());
+            }
+            hasher.update(&buf);
+            size += buf.len() as u64;
+            out.write_all(&buf)
+                .map_err(|e| format!("写入 {} 失败: {}", entry.path, e))?;
+        }
+        if size != entry.size || hex::encode(hasher.finalize()) != entry.sha256 {
+            return Err(format!("备份文件已损坏（{} 校验失败）", entry.path));
+        }
+    }
+    Ok(manifest.files.len())
+}
+
+/// 档案在远程存储中的备份目录，各档案的备份和保留规则互不影响
+fn remote_dir(profile: &str) -> String {
+    if profile == profiles::DEFAULT_PROFILE {
+        REMOTE_DIR.to_string()
+    } else {
+        format!("{}/{}", REMOTE_DIR, profile)
+    }
+}
+
+fn remote_key(dir: &str, name: &str) -> String {
+    format!("{}/{}", dir, name)
+}
+
+/// 与 `key` 同一目录下的备份
+fn sibling_key(key: &str, name: &str) -> String {
+    match key.rsplit_once('/') {
+        Some((dir, _)) => remote_key(dir, name),
+        None => name.to_string(),
+    }
+}
+
+/// 备份的数据包目录（列出备份时会跳过子目录）
+fn pack_dir(key: &str) -> String {
+    format!("{}.d", key)
+}
+
+fn pack_key(key: &str, pack: u32) -> String {
+    format!("{}/{:05}", pack_dir(key), pack)
+}
+
+/// 删除备份及其数据包（先删除备份文件，中途失败时不会留下无法恢复的备份）
+async fn delete_backup(target: &RemoteTarget, key: &str) -> Result<(), String> {
+    target.delete(key).await?;
+    let dir = pack_dir(key);
+    for object in target.list(&dir).await? {
+        target.delete(&object.key).await?;
+    }
+    // WebDAV 删除空目录；S3 没有目录，删除不存在的对象不会出错
+    target.delete(&dir).await
+}
+
+/// 备份文件名；`base` 为差异备份所依据的完整备份名
+fn backup_name(now: chrono::DateTime<chrono::Utc>, base: Option<&str>) -> String {
+    let stamp = now.format(NAME_TIME_FORMAT);
+    match base.and_then(|base| base.strip_prefix(NAME_PREFIX)?.strip_suffix(NAME_SUFFIX)) {
+        Some(base_stamp) => format!(
+            "{}{}{}{}{}",
+            NAME_PREFIX, stamp, DIFF_MARKER, base_stamp, NAME_SUFFIX
+        ),
+        None => format!("{}{}{}", NAME_PREFIX, stamp, NAME_SUFFIX),
+    }
+}
+
+fn parse_stamp(stamp: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    chrono::NaiveDateTime::parse_from_str(stamp, NAME_TIME_FORMAT)
+        .ok()
+        .map(|t| t.and_utc())
+}
+
+/// 从文件名解析备份时间（UTC），差异备份同时返回所依据的完整备份名
+fn parse_backup_name(name: &str) -> Option<(chrono::DateTime<chrono::Utc>, Option<String>)> {
+    let stem = name.strip_prefix(NAME_PREFIX)?.strip_suffix(NAME_SUFFIX)?;
+    match stem.split_once(DIFF_MARKER) {
+        Some((stamp, base_stamp)) => {
+            parse_stamp(base_stamp)?;
+            let base = format!("{}{}{}", NAME_PREFIX, base_stamp, NAME_SUFFIX);
+            Some((parse_stamp(stamp)?, Some(base)))
+        }
+        None => Some((parse_stamp(stem)?, None)),
+    }
+}
+
+fn parse_backup_time(name: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    parse_backup_name(name).map(|(time, _)| time)
+}
+
+/// 按保留规则需要删除的备份（`backups` 中为备份名）
+/// 仍保留的差异备份所依据的完整备份不删除；完整备份已不存在的差异备份无法恢复，一并删除
+fn retention_victims(
+    backups: &[String],
+    rules: &RetentionRules,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Vec<String> {
+    let mut dated: Vec<(chrono::DateTime<chrono::Utc>, &String)> = backups
+        .iter()
+        .filter_map(|name| parse_backup_time(name).map(|t| (t, name)))
+        .collect();
+    dated.sort_by_key(|(t, _)| std::cmp::Reverse(*t));
+    let expired: Vec<&String> = dated
+        .into_iter()
+        .skip(rules.keep_last.max(1))
+        .filter(|(t, _)| {
+            rules.keep_days == 0
+                || now.signed_duration_since(*t).num_days() >= rules.keep_days as i64
+        })
+        .map(|(_, name)| name)
+        .collect();
+
+    let base_of = |name: &String| parse_backup_name(name).and_then(|(_, base)| base);
+    let needed: HashSet<String> = backups
+        .iter()
+        .filter(|name| !expired.contains(name))
+        .filter_map(base_of)
+        .collect();
+    let orphans: Vec<&String> = backups
+        .iter()
+        .filter(|name| {
+            !expired.contains(name) && base_of(name).is_some_and(|base| !backups.contains(&base))
+        })
+        .collect();
+    expired
+        .into_iter()
+        .filter(|name| !needed.contains(*name))
+        .chain(orphans)
+        .cloned()
+        .collect()
+}
+
+/// 是否需要完整备份：完整备份模式、没有最近完整备份的索引（或属于其他存储位置、已不在远程），
+/// 或距上次完整备份的天数、差异备份个数达到设置值
+fn needs_full(
+    backup: &BackupSettings,
+    index: Option<&FullIndex>,
+    target: &str,
+    existing: &[String],
+    now: chrono::DateTime<chrono::Utc>,
+) -> bool {
+    if backup.mode == BackupMode::Full {
+        return true;
+    }
+    let Some(index) = index.filter(|i| i.target == target && existing.contains(&i.name)) else {
+        return true;
+    };
+    let too_old = backup.full_every_days > 0
+        && parse_backup_time(&index.name).is_none_or(|t| {
+            now.signed_duration_since(t).num_days() >= backup.full_every_days as i64
+        });
+    too_old || (backup.full_every_backups > 0 && index.differentials >= backup.full_every_backups)
+}
+
+fn to_remote_backup(object: RemoteObject) -> Option<RemoteBackup> {
+    let name = object.key.rsplit('/').next()?.to_string();
+    let (created_at, base) = parse_backup_name(&name)?;
+    Some(RemoteBackup {
+        created_at: Some(time::in_zone(time::user_timezone(), created_at).to_rfc3339()),
+        name,
+        size: object.size,
+        kind: if base.is_some() {
+            BackupMode::Differential
+        } else {
+            BackupMode::Full
+        },
+        base: base.map(|base| sibling_key(&object.key, &base)),
+        key: object.key,
+    })
+}
+
+/// 读取备份设置并解析口令
+fn load_backup_settings() -> Result<(BackupSettings, RemoteTarget, String), String> {
+    let backup = settings::load_settings().backup;
+    let target = backup.remote.clone().ok_or("尚未配置远程备份位置")?;
+    if backup.passphrase.is_empty() {
+        return Err("尚未设置备份口令".to_string());
+    }
+    let passphrase = secrets::resolve_secret(&backup.passphrase)?;
+    Ok((backup, target, passphrase))
+}
+
+async fn list_backups(target: &RemoteTarget, dir: &str) -> Result<Vec<RemoteBackup>, String> {
+    let mut backups: Vec<RemoteBackup> = target
+        .list(dir)
+        .await?
+        .into_iter()
+        .filter_map(to_remote_backup)
+        .collect();
+    backups.sort_by(|a, b| b.name.cmp(&a.name));
+    Ok(backups)
+}
+
+/// 备份大小加上其数据包的大小（列出失败时只计备份文件）
+async fn add_pack_sizes(target: &RemoteTarget, backups: &mut [RemoteBackup]) {
+    for backup in backups {
+        match target.list(&pack_dir(&backup.key)).await {
+            Ok(packs) => {
+                let packs: u64 = packs.iter().filter_map(|p| p.size).sum();
+                backup.size = Some(backup.size.unwrap_or(0) + packs);
+            }
+            Err(e) => warn!("[远程备份] 列出 {} 的数据包失败: {}", backup.key, e),
+        }
+    }
+}
+
+/// 边打包边加密上传数据包，最后上传备份文件（清单），然后按保留规则清理旧备份
+/// 差异备份模式下只上传最近一次完整备份中没有的数据块，需要时自动改为完整备份
+async fn run_backup() -> Result<RemoteBackup, String> {
+    let _guard = BACKUP_LOCK.lock().await;
+    let (backup, target, passphrase) = load_backup_settings()?;
+    let config_dir = PathBuf::from(platform::get_config_dir());
+    if !config_dir.exists() {
+        return Err(format!("配置目录不存在: {}", config_dir.display()));
+    }
+    let now = chrono::Utc::now();
+    let description = target.describe();
+    let dir = remote_dir(&profiles::active_profile());
+    let mut existing: Vec<String> = list_backups(&target, &dir)
+        .await?
+        .into_iter()
+        .map(|b| b.name)
+        .collect();
+    let index = load_index();
+    let full = needs_full(&backup, index.as_ref(), &description, &existing, now);
+    let base = index.filter(|_| !full);
+    let base_name = base.as_ref().map(|index| index.name.clone());
+
+    info!(
+        "[远程备份] 打包 {}（{}）...",
+        config_dir.display(),
+        if full { "完整备份" } else { "差异备份" }
+    );
+    let name = backup_name(now, base_name.as_deref());
+    let key = remote_key(&dir, &name);
+
+    // 打包线程每满一个数据包就加密后交给上传循环，通道容量为 1，内存中最多保留几个数据包
+    let (sender, mut receiver) = tokio::sync::mpsc::channel::<(u32, Vec<u8>)>(1);
+    let packer = tokio::task::spawn_blocking(move || {
+        let sealer = crypto::Sealer::new(&passphrase);
+        let base = base.map(|index| (index.name, index.chunks.into_iter().collect::<HashSet<_>>()));
+        let packed = pack(
+            &config_dir,
+            base.as_ref().map(|(name, chunks)| (name.as_str(), chunks)),
+            PACK_SIZE,
+            |index, data| {
+                sender
+                    .blocking_send((index, sealer.seal(&data)))
+                    .map_err(|_| "上传已中止".to_string())
+            },
+        )?;
+        Ok::<_, String>((sealer.seal(&packed.archive), packed))
+    });
+    let mut size = 0u64;
+    let mut uploaded = Ok(());
+    while let Some((index, sealed)) = receiver.recv().await {
+        size += sealed.len() as u64;
+        uploaded = target.put(&pack_key(&key, index), sealed).await;
+        if uploaded.is_err() {
+            break;
+        }
+    }
+    drop(receiver);
+    let packed = packer.await.map_err(|e| e.to_string());
+    let result = match (uploaded, packed) {
+        (Err(e), _) | (Ok(()), Err(e)) | (Ok(()), Ok(Err(e))) => Err(e),
+        (Ok(()), Ok(Ok((sealed, packed)))) => {
+            size += sealed.len() as u64;
+            target.put(&key, sealed).await.map(|_| packed)
+        }
+    };
+    let Packed {
+        chunks,
+        stored,
+        packs,
+        ..
+    } = match result {
+        Ok(packed) => packed,
+        Err(e) => {
+            if let Err(cleanup) = delete_backup(&target, &key).await {
+                warn!("[远程备份] 清理未完成的备份 {} 失败: {}", key, cleanup);
+            }
+            return Err(e);
+        }
+    };
+    info!(
+        "[远程备份] ✓ 已上传 {} ({} 字节，{} 个数据块，{} 个数据包)",
+        key, size, stored, packs
+    );
+    if full {
+        save_index(&FullIndex {
+            target: description.clone(),
+            name: name.clone(),
+            chunks,
+            differentials: 0,
+        });
+    } else if let Some(mut index) = load_index() {
+        index.differentials += 1;
+        save_index(&index);
+    }
+
+    existing.push(name.clone());
+    for victim in retention_victims(&existing, &backup.retention, now) {
+        match delete_backup(&target, &remote_key(&dir, &victim)).await {
+            Ok(()) => info!("[远程备份] 按保留规则删除 {}", victim),
+            Err(e) => warn!("[远程备份] 删除旧备份 {} 失败: {}", victim, e),
+        }
+    }
+
+    let kind = if full {
+        BackupMode::Full
+    } else {
+        BackupMode::Differential
+    };
+    audit::record(
+        "create_remote_backup",
+        &description,
+        true,
+        json!({ "key": key, "size": size, "kind": kind, "chunks": stored, "packs": packs }),
+    );
+    Ok(RemoteBackup {
+        created_at: Some(time::in_zone(time::user_timezone(), now).to_rfc3339()),
+        key,
+        name,
+        size: Some(size),
+        kind,
+        base: base_name.map(|base| remote_key(&dir, &base)),
+    })
+}
+
+/// 备份并记录结果
+async fn backup_and_record() -> Result<RemoteBackup, String> {
+    let result = run_backup().await;
+    let mut state = load_state();
+    match &result {
+        Ok(_) => {
+            state.last_backup_at = Some(time::now_rfc3339());
+            state.last_error = None;
+        }
+        Err(e) => {
+            warn!("[远程备份] 备份失败: {}", e);
+            state.last_error = Some(e.clone());
+        }
+    }
+    save_state(&state);
+    result
+}
+
+/// 后台监控定期调用：开启自动备份且距上次备份超过间隔时执行备份
+pub async fn tick() {
+    let backup = settings::load_settings().backup;
+    if backup.remote.is_none() || backup.interval_hours == 0 {
+        return;
+    }
+    let due = load_state()
+        .last_backup_at
+        .and_then(|t| chrono::DateTime::parse_from_rfc3339(&t).ok())
+        .is_none_or(|last| {
+            time::now().signed_duration_since(last).num_hours() >= backup.interval_hours as i64
+        });
+    if due {
+        let _ = backup_and_record().await;
+    }
+}
+
+/// 将原目录中不备份的文件和目录（任意层级）移到恢复后的目录中的相同位置，
+/// 恢复后的目录中已有同名项或所在目录已不存在时跳过
+fn carry_excluded(from: &Path, to: &Path) {
+    let Ok(entries) = std::fs::read_dir(from) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        let target = to.join(&name);
+        if EXCLUDED.contains(&name.to_string_lossy().as_ref()) {
+            if target.symlink_metadata().is_err() {
+                if let Err(e) = std::fs::rename(entry.path(), &target) {
+                    warn!("[远程备份] 保留 {} 失败: {}", entry.path().display(), e);
+                }
+            }
+        } else if entry.file_type().is_ok_and(|t| t.is_dir()) && target.is_dir() {
+            carry_excluded(&entry.path(), &target);
+        }
+    }
+}
+
+/// 用解包好的目录替换配置目录，原目录保留为 `<目录>.before-restore-<时间>`；
+/// 备份中不包含的依赖、日志等从原目录移到新目录
+fn swap_config_dir(
+    config_dir: &Path,
+    staging: &Path,
+    stamp: &str,
+) -> Result<Option<PathBuf>, String> {
+    let previous = if config_dir.exists() {
+        let previous = PathBuf::from(format!("{}.before-restore-{}", config_dir.display(), stamp));
+        std::fs::rename(config_dir, &previous)
+            .map_err(|e| format!("移动当前配置目录失败: {}", e))?;
+        Some(previous)
+    } else {
+        None
+    };
+    if let Err(e) = std::fs::rename(staging, config_dir) {
+        if let Some(previous) = &previous {
+            let _ = std::fs::rename(previous, config_dir);
+        }
+        return Err(format!("替换配置目录失败: {}", e));
+    }
+    if let Some(previous) = &previous {
+        carry_excluded(previous, config_dir);
+    }
+    Ok(previous)
+}
+
+fn status() -> BackupStatus {
+    let settings = settings::load_settings().backup;
+    let state = load_state();
+    let target = settings.remote.as_ref().map(RemoteTarget::describe);
+    let index = load_index().filter(|index| target.as_ref() == Some(&index.target));
+    BackupStatus {
+        target,
+        settings,
+        last_backup_at: state.last_backup_at,
+        last_error: state.last_error,
+        full_backup: index.as_ref().map(|index| index.name.clone()),
+        differentials: index.map_or(0, |index| index.differentials),
+    }
+}
+
+/// 获取远程备份设置和最近一次备份结果
+#[guarded]
+#[command]
+pub async fn get_backup_status() -> Result<BackupStatus, String> {
+    Ok(status())
+}
+
+/// 保存远程备份设置；`passphrase` 不为空时更新备份口令（保存在系统钥匙串中）
+#[guarded]
+#[command]
+pub async fn configure_remote_backup(
+    mut backup: BackupSettings,
+    passphrase: Option<String>,
+) -> Result<BackupStatus, String> {
+    let current = settings::load_settings();
+    match passphrase.filter(|p| !p.is_empty()) {
+        Some(passphrase) => {
+            if passphrase.chars().count() < 8 {
+                return Err("备份口令至少需要 8 个字符".to_string());
+            }
+            backup.passphrase = secrets::store_secret(PASSPHRASE_ACCOUNT, &passphrase)?;
+        }
+        None => backup.passphrase = current.backup.passphrase.clone(),
+    }
+    if backup.remote.is_some() && backup.passphrase.is_empty() {
+        return Err("远程备份需要设置备份口令".to_string());
+    }
+    if let Some(target) = backup.remote.as_mut() {
+        target.secure_secrets(REMOTE_ACCOUNT);
+    }
+    let description = backup.remote.as_ref().map(RemoteTarget::describe);
+    let interval_hours = backup.interval_hours;
+    settings::update(|current| {
+        current.backup = backup;
+        Ok(())
+    })?;
+    audit::record(
+        "configure_remote_backup",
+        description.as_deref().unwrap_or("-"),
+        true,
+        json!({ "interval_hours": interval_hours }),
+    );
+    info!("[远程备份] ✓ 备份设置已保存");
+    Ok(status())
+}
+
+/// 列出远程备份（按时间从新到旧）
+#[guarded]
+#[command]
+pub async fn list_remote_backups() -> Result<Vec<RemoteBackup>, String> {
+    let target = settings::load_settings()
+        .backup
+        .remote
+        .ok_or("尚未配置远程备份位置")?;
+    let mut backups = list_backups(&target, &remote_dir(&profiles::active_profile())).await?;
+    add_pack_sizes(&target, &mut backups).await;
+    Ok(backups)
+}
+
+/// 立即创建远程备份
+#[guarded]
+#[command]
+pub async fn create_remote_backup() -> Result<RemoteBackup, String> {
+    info!("[远程备份] 开始备份...");
+    backup_and_record().await
+}
+
+/// 下载并解密备份文件
+async fn download_archive(
+    target: &RemoteTarget,
+    opener: &std::sync::Arc<std::sync::Mutex<crypto::Opener>>,
+    key: &str,
+    missing: String,
+) -> Result<Vec<u8>, String> {
+    info!("[远程备份] 下载 {} ...", key);
+    let sealed = target.get(key).await?.ok_or(missing)?;
+    let opener = opener.clone();
+    tokio::task::spawn_blocking(move || {
+        opener
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .open(&sealed)
+            .map_err(|e| format!("无法解密备份（{}），请确认备份口令", e))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 下载备份（差异备份同时下载所依据的完整备份）的清单和用到的数据包，
+/// 数据包逐个解密后写入 `scratch`，再还原到 `staging`，返回文件数
+async fn download_and_unpack(
+    target: &RemoteTarget,
+    passphrase: &str,
+    key: &str,
+    base_key: Option<&str>,
+    scratch: &Path,
+    staging: &Path,
+) -> Result<usize, String> {
+    let opener = std::sync::Arc::new(std::sync::Mutex::new(crypto::Opener::new(passphrase)));
+    let archive =
+        download_archive(target, &opener, key, format!("远程备份 {} 不存在", key)).await?;
+    let base_archive = match base_key {
+        Some(base_key) => Some(
+            download_archive(
+                target,
+                &opener,
+                base_key,
+                format!("差异备份所依据的完整备份 {} 已不存在，无法恢复", base_key),
+            )
+            .await?,
+        ),
+        None => None,
+    };
+
+    std::fs::create_dir_all(scratch).map_err(|e| format!("创建临时目录失败: {}", e))?;
+    let manifest = parse_archive(&archive)?;
+    let base_manifest = base_archive.as_deref().map(parse_archive).transpose()?;
+    drop((archive, base_archive));
+    let packs = needed_packs(
+        &manifest,
+        &locate_chunks(&manifest, base_manifest.as_ref())?,
+    );
+
+    for (origin, pack) in packs {
+        let owner = match origin {
+            Origin::Own => key,
+            Origin::Base => base_key.unwrap_or_default(),
+        };
+        let pack_key = pack_key(owner, pack);
+        let sealed = target
+            .get(&pack_key)
+            .await?
+            .ok_or_else(|| format!("备份文件已损坏（数据包 {} 不存在）", pack_key))?;
+        let opener = opener.clone();
+        let path = pack_file(scratch, origin, pack);
+        tokio::task::spawn_blocking(move || {
+            let data = opener
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .open(&sealed)
+                .map_err(|e| format!("无法解密数据包（{}），请确认备份口令", e))?;
+            std::fs::write(&path, data).map_err(|e| format!("写入临时文件失败: {}", e))
+        })
+        .await
+        .map_err(|e| e.to_string())??;
+    }
+
+    let staging = staging.to_path_buf();
+    let scratch = scratch.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        let locations = locate_chunks(&manifest, base_manifest.as_ref())?;
+        assemble(&manifest, &locations, &scratch, &staging)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 从远程备份恢复配置目录（需要确认令牌）
+/// 先逐个下载数据包并校验全部文件（差异备份同时下载所依据的完整备份），再停止 Gateway 替换目录；原目录保留以便回退，之前在运行的 Gateway 会重新启动
+#[guarded]
+#[command]
+pub async fn restore_remote_backup(
+    key: String,
+    confirm_token: Option<String>,
+) -> Result<String, String> {
+    policy::require_confirmation("restore_remote_backup", confirm_token.as_deref())?;
+    let _guard = BACKUP_LOCK.lock().await;
+    let (_, target, passphrase) = load_backup_settings()?;
+    let base_key = parse_backup_name(key.rsplit('/').next().unwrap_or(&key))
+        .and_then(|(_, base)| base)
+        .map(|base| sibling_key(&key, &base));
+    let config_dir = PathBuf::from(platform::get_config_dir());
+    let stamp = time::now().format(NAME_TIME_FORMAT).to_string();
+    let staging = PathBuf::from(format!("{}.restore-{}", config_dir.display(), stamp));
+    let scratch = PathBuf::from(format!("{}.packs", staging.display()));
+    let unpacked = download_and_unpack(
+        &target,
+        &passphrase,
+        &key,
+        base_key.as_deref(),
+        &scratch,
+        &staging,
+    )
+    .await;
+    let _ = std::fs::remove_dir_all(&scratch);
+    let count = match unpacked {
+        Ok(count) => count,
+        Err(e) => {
+            let _ = std::fs::remove_dir_all(&staging);
+            return Err(e);
+        }
+    };
+
+    let was_running = matches!(service::get_service_status().await, Ok(s) if s.running);
+    if was_running {
+        service::stop_service().await?;
+    }
+    let previous = match swap_config_dir(&config_dir, &staging, &stamp) {
+        Ok(previous) => previous,
+        Err(e) => {
+            let _ = std::fs::remove_dir_all(&staging);
+            return Err(e);
+        }
+    };
+    probe_cache::invalidate(None);
+
+    let mut message = format!("已从 {} 恢复 {} 个文件", key, count);
+    if let Some(previous) = &previous {
+        message.push_str(&format!("，原配置目录已保留在 {}", previous.display()));
+    }
+    if was_running {
+        if let Err(e) = service::start_service(None).await {
+            warn!("[远程备份] 恢复后启动 Gateway 失败: {}", e);
+            message.push_str(&format!("；Gateway 启动失败: {}", e));
+        }
+    }
+    audit::record(
+        "restore_remote_backup",
+        &key,
+        true,
+        json!({
+            "files": count,
+            "base": base_key,
+            "previous": previous.map(|p| p.display().to_string()),
+        }),
+    );
+    info!("[远程备份] ✓ {}", message);
+    Ok(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(days_ago: i64) -> chrono::DateTime<chrono::Utc> {
+        chrono::NaiveDate::from_ymd_opt(2026, 10, 16)
+            .unwrap()
+            .and_hms_opt(12, 0, 0)
+            .unwrap()
+            .and_utc()
+            - chrono::Duration::days(days_ago)
+    }
+
+    /// 打包到内存，返回打包结果和各数据包
+    fn pack_to_memory(
+        root: &Path,
+        base: Option<(&str, &HashSet<String>)>,
+        pack_size: usize,
+    ) -> (Packed, Vec<Vec<u8>>) {
+        let mut packs = Vec::new();
+        let packed = pack(root, base, pack_size, |index, data| {
+            assert_eq!(index as usize, packs.len());
+            packs.push(data);
+            Ok(())
+        })
+        .unwrap();
+        (packed, packs)
+    }
+
+    /// 按恢复流程还原：数据包写入临时目录，再还原文件
+    fn restore(
+        archive: &[u8],
+        packs: &[Vec<u8>],
+        base: Option<(&[u8], &[Vec<u8>])>,
+        dest: &Path,
+    ) -> Result<usize, String> {
+        let scratch = dest.with_extension("packs");
+        std::fs::create_dir_all(&scratch).unwrap();
+        let write = |origin, packs: &[Vec<u8>]| {
+            for (index, data) in packs.iter().enumerate() {
+                std::fs::write(pack_file(&scratch, origin, index as u32), data).unwrap();
+            }
+        };
+        let manifest = parse_archive(archive)?;
+        write(Origin::Own, packs);
+        let base_manifest = match base {
+            Some((archive, packs)) => {
+                let manifest = parse_archive(archive)?;
+                write(Origin::Base, packs);
+                Some(manifest)
+            }
+            None => None,
+        };
+        let result = locate_chunks(&manifest, base_manifest.as_ref())
+            .and_then(|locations| assemble(&manifest, &locations, &scratch, dest));
+        std::fs::remove_dir_all(&scratch).unwrap();
+        result
+    }
+
+    #[test]
+    fn packs_verifies_and_applies_retention() {
+        let root = std::env::temp_dir().join(format!("backups-{}", uuid::Uuid::new_v4().simple()));
+        let source = root.join("src");
+        std::fs::create_dir_all(source.join("agents/main/sessions")).unwrap();
+        std::fs::create_dir_all(source.join("logs")).unwrap();
+        std::fs::write(source.join("openclaw.json"), "{}").unwrap();
+        std::fs::write(source.join("agents/main/sessions/a.jsonl"), "hello").unwrap();
+        std::fs::write(source.join("logs/gateway.log"), "skip").unwrap();
+
+        let (packed, packs) = pack_to_memory(&source, None, PACK_SIZE);
+        assert_eq!((packed.packs, packs.len()), (1, 1));
+        let dest = root.join("dest");
+        assert_eq!(restore(&packed.archive, &packs, None, &dest).unwrap(), 2);
+        assert_eq!(
+            std::fs::read_to_string(dest.join("agents/main/sessions/a.jsonl")).unwrap(),
+            "hello"
+        );
+        assert!(!dest.join("logs").exists());
+
+        let mut corrupted = packs.clone();
+        let last = corrupted[0].len() - 1;
+        corrupted[0][last] ^= 1;
+        assert!(
+            restore(&packed.archive, &corrupted, None, &root.join("bad"))
+                .unwrap_err()
+                .contains("校验失败")
+        );
+
+        assert!(!is_safe_path("../etc/passwd"));
+        assert!(!is_safe_path("/etc/passwd"));
+        std::fs::remove_dir_all(&root).unwrap();
+
+        let now = utc(0);
+        let names: Vec<String> = [1, 2, 3, 40, 50]
+            .iter()
+            .map(|days| backup_name(utc(*days), None))
+            .collect();
+        let rules = RetentionRules {
+            keep_last: 2,
+            keep_days: 30,
+        };
+        // 最近 2 个始终保留，第 3 个未满 30 天也保留
+        assert_eq!(
+            retention_victims(&names, &rules, now),
+            vec![names[3].clone(), names[4].clone()]
+        );
+        assert_eq!(parse_backup_time(&names[0]), Some(utc(1)));
+    }
+
+    #[test]
+    fn restore_keeps_excluded_entries_and_profiles_use_own_dir() {
+        let root = std::env::temp_dir().join(format!("backups-{}", uuid::Uuid::new_v4().simple()));
+        let config_dir = root.join(".openclaw");
+        let staging = root.join("staging");
+        std::fs::create_dir_all(config_dir.join("extensions/foo/node_modules/dep")).unwrap();
+        std::fs::create_dir_all(config_dir.join("logs")).unwrap();
+        std::fs::write(config_dir.join("logs/gateway.log"), "old log").unwrap();
+        std::fs::write(
+            config_dir.join("extensions/foo/node_modules/dep/index.js"),
+            "",
+        )
+        .unwrap();
+        std::fs::write(config_dir.join("openclaw.json"), "{\"old\":true}").unwrap();
+        std::fs::create_dir_all(staging.join("extensions/foo")).unwrap();
+        std::fs::write(staging.join("openclaw.json"), "{}").unwrap();
+
+        let previous = swap_config_dir(&config_dir, &staging, "20261016-120000")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(config_dir.join("openclaw.json")).unwrap(),
+            "{}"
+        );
+        assert_eq!(
+            std::fs::read_to_string(config_dir.join("logs/gateway.log")).unwrap(),
+            "old log"
+        );
+        assert!(config_dir
+            .join("extensions/foo/node_modules/dep/index.js")
+            .exists());
+        assert!(previous.join("openclaw.json").exists());
+        std::fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(remote_dir(profiles::DEFAULT_PROFILE), "backups");
+        assert_eq!(remote_dir("work"), "backups/work");
+        let base = backup_name(utc(2), None);
+        assert_eq!(
+            sibling_key("backups/work/openclaw-x.ocb", &base),
+            format!("backups/work/{}", base)
+        );
+    }
+
+    #[test]
+    fn differential_backups_store_changed_chunks_only() {
+        let root = std::env::temp_dir().join(format!("backups-{}", uuid::Uuid::new_v4().simple()));
+        let source = root.join("src");
+        std::fs::create_dir_all(source.join("agents/main/sessions")).unwrap();
+        let session = source.join("agents/main/sessions/a.jsonl");
+        let mut history: String = (0..20_000)
+            .map(|i| format!("{{\"turn\":{},\"text\":\"message {}\"}}\n", i, i * 7919))
+            .collect();
+        std::fs::write(&session, &history).unwrap();
+        std::fs::write(source.join("openclaw.json"), "{}").unwrap();
+
+        let full_name = backup_name(utc(2), None);
+        let (full, full_packs) = pack_to_memory(&source, None, 64 * 1024);
+        assert!(full.packs > 2);
+        assert!(full_packs.iter().all(|p| p.len() < 64 * 1024 + 256 * 1024));
+        let base_chunks: HashSet<String> = full.chunks.iter().cloned().collect();
+
+        history.push_str("{\"turn\":-1}\n");
+        std::fs::write(&session, &history).unwrap();
+        std::fs::write(source.join("openclaw.json"), "{\"changed\":true}").unwrap();
+        let (diff, diff_packs) =
+            pack_to_memory(&source, Some((&full_name, &base_chunks)), 64 * 1024);
+        assert!(diff.stored <= 3);
+        assert!(diff_packs.concat().len() * 4 < full_packs.concat().len());
+
+        let dest = root.join("dest");
+        assert!(restore(&diff.archive, &diff_packs, None, &dest).is_err());
+        assert_eq!(
+            restore(
+                &diff.archive,
+                &diff_packs,
+                Some((&full.archive, &full_packs)),
+                &dest
+            )
+            .unwrap(),
+            2
+        );
+        assert_eq!(
+            std::fs::read_to_string(dest.join("agents/main/sessions/a.jsonl")).unwrap(),
+            history
+        );
+        assert!(restore(
+            &diff.archive,
+            &diff_packs,
+            Some((&diff.archive, &diff_packs)),
+            &root.join("bad")
+        )
+        .is_err());
+        std::fs::remove_dir_all(&root).unwrap();
+
+        // 仍保留的差异备份所依据的完整备份不删除，完整备份已不存在的差异备份一并删除
+        let diff_names: Vec<String> = [1, 5]
+            .iter()
+            .map(|days| backup_name(utc(*days), Some(&full_name)))
+            .collect();
+        let orphan = backup_name(utc(3), Some(&backup_name(utc(9), None)));
+        assert_eq!(
+            parse_backup_name(&diff_names[0]),
+            Some((utc(1), Some(full_name.clone())))
+        );
+        let names = vec![
+            full_name.clone(),
+            diff_names[0].clone(),
+            diff_names[1].clone(),
+            orphan.clone(),
+        ];
+        let rules = RetentionRules {
+            keep_last: 1,
+            keep_days: 0,
+        };
+        assert_eq!(
+            retention_victims(&names, &rules, utc(0)),
+            vec![orphan.clone(), diff_names[1].clone()]
+        );
+
+        let settings = BackupSettings {
+            mode: BackupMode::Differential,
+            ..Default::default()
+        };
+        let index = FullIndex {
+            target: "webdav".to_string(),
+            name: full_name.clone(),
+            chunks: full.chunks,
+            differentials: 3,
+        };
+        assert!(!needs_full(
+            &settings,
+            Some(&index),
+            "webdav",
+            &names,
+            utc(0)
+        ));
+        assert!(needs_full(&settings, Some(&index), "s3", &names, utc(0)));
+        assert!(needs_full(
+            &settings,
+            Some(&index),
+            "webdav",
+            &names[1..],
+            utc(0)
+        ));
+        assert!(needs_full(
+            &settings,
+            Some(&index),
+            "webdav",
+            &names,
+            utc(-7)
+        ));
+        assert!(needs_full(&settings, None, "webdav", &names, utc(0)));
+    }
+}
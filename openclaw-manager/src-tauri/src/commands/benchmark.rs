@@ -0,0 +1,267 @@
+use crate::commands::{config, privacy};
+use crate::utils::{file, http, platform, secrets, time};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use std::time::Instant;
+use openclaw_macros::guarded;
+use tauri::command;
+
+/// 单次测试的最大迭代次数
+const MAX_ITERATIONS: u32 = 20;
+/// 本地保留的测试结果数
+const MAX_RESULTS: usize = 100;
+/// 每次请求生成的最大 token 数
+const MAX_OUTPUT_TOKENS: u32 = 256;
+
+/// 一次请求的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchmarkSample {
+    pub latency_ms: u64,
+    pub output_tokens: Option<u64>,
+    pub error: Option<String>,
+}
+
+/// 一个 Provider / 模型的测试结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchmarkResult {
+    pub id: String,
+    /// provider/model
+    pub profile: String,
+    pub api: String,
+    pub created_at: String,
+    pub iterations: u32,
+    pub succeeded: u32,
+    pub p50_ms: Option<u64>,
+    pub p95_ms: Option<u64>,
+    /// 成功请求的平均输出速度
+    pub tokens_per_sec: Option<f64>,
+    pub samples: Vec<BenchmarkSample>,
+}
+
+/// 测试请求的目标
+struct Target {
+    profile: String,
+    model: String,
+    api: String,
+    base_url: String,
+    api_key: String,
+}
+
+fn results_path() -> PathBuf {
+    PathBuf::from(platform::get_manager_data_dir()).join("benchmarks.json")
+}
+
+fn load_results() -> Vec<BenchmarkResult> {
+    std::fs::read_to_string(results_path())
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+fn save_results(results: &[BenchmarkResult]) -> Result<(), String> {
+    let content = serde_json::to_vec_pretty(results).map_err(|e| e.to_string())?;
+    file::write_atomic(&results_path(), &content).map_err(|e| format!("保存测试结果失败: {}", e))
+}
+
+/// 百分位数（最近秩法），输入需已排序
+fn percentile(sorted: &[u64], p: f64) -> Option<u64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    Some(sorted[rank.clamp(1, sorted.len()) - 1])
+}
+
+/// 从 openclaw.json 解析 provider/model
+fn resolve_target(cfg: &Value, profile: &str) -> Result<Target, String> {
+    let (provider, model) = profile
+        .split_once('/')
+        .filter(|(p, m)| !p.is_empty() && !m.is_empty())
+        .ok_or_else(|| format!("格式应为 provider/model: {}", profile))?;
+    let entry = cfg
+        .pointer(&format!("/models/providers/{}", provider))
+        .ok_or_else(|| format!("未配置 Provider: {}", provider))?;
+    let base_url = entry
+        .get("baseUrl")
+        .and_then(|v| v.as_str())
+        .filter(|u| !u.is_empty())
+        .ok_or_else(|| format!("Provider {} 未配置 baseUrl", provider))?;
+    let api = entry
+        .get("models")
+        .and_then(|m| m.as_array())
+        .and_then(|models| {
+            models
+                .iter()
+                .find(|m| m.get("id").and_then(|v| v.as_str()) == Some(model))
+        })
+        .and_then(|m| m.get("api"))
+        .or_else(|| entry.get("api"))
+        .and_then(|v| v.as_str())
+        .unwrap_or("openai-completions");
+    let api_key = entry.get("apiKey").and_then(|v| v.as_str()).unwrap_or("");
+    Ok(Target {
+        profile: profile.to_string(),
+        model: model.to_string(),
+        api: api.to_string(),
+        base_url: base_url.trim_end_matches('/').to_string(),
+        api_key: secrets::resolve_secret(api_key)?,
+    })
+}
+
+/// 发送一次请求，返回输出 token 数
+async fn run_once(
+    client: &reqwest::Client,
+    target: &Target,
+    prompt: &str,
+) -> Result<Option<u64>, String> {
+    let request = if target.api == "anthropic-messages" {
+        client
+            .post(format!("{}/v1/messages", target.base_url))
+            .header("x-api-key", &target.api_key)
+            .header("anthropic-version", "2023-06-01")
+            .json(&json!({
+                "model": target.model,
+                "max_tokens": MAX_OUTPUT_TOKENS,
+                "messages": [{ "role": "user", "content": prompt }],
+            }))
+    } else {
+        client
+            .post(format!("{}/chat/completions", target.base_url))
+            .bearer_auth(&target.api_key)
+            .json(&json!({
+                "model": target.model,
+                "max_tokens": MAX_OUTPUT_TOKENS,
+                "messages": [{ "role": "user", "content": prompt }],
+            }))
+    };
+    let resp = request.send().await.map_err(|e| e.to_string())?;
+    let status = resp.status();
+    let body: Value = resp.json().await.unwrap_or(Value::Null);
+    if !status.is_success() {
+        let message = body
+            .pointer("/error/message")
+            .and_then(|v| v.as_str())
+            .unwrap_or("");
+        return Err(format!("HTTP {} {}", status.as_u16(), message)
+            .trim()
+            .to_string());
+    }
+    Ok(body
+        .pointer("/usage/output_tokens")
+        .or_else(|| body.pointer("/usage/completion_tokens"))
+        .and_then(|v| v.as_u64()))
+}
+
+fn summarize(target: &Target, samples: Vec<BenchmarkSample>) -> BenchmarkResult {
+    let ok: Vec<&BenchmarkSample> = samples.iter().filter(|s| s.error.is_none()).collect();
+    let mut latencies: Vec<u64> = ok.iter().map(|s| s.latency_ms).collect();
+    latencies.sort_unstable();
+    let (tokens, millis) = ok
+        .iter()
+        .filter_map(|s| s.output_tokens.map(|t| (t, s.latency_ms)))
+        .fold((0u64, 0u64), |(t, m), (tokens, ms)| (t + tokens, m + ms));
+    let now = time::now();
+    BenchmarkResult {
+        id: now.format("%Y%m%d-%H%M%S%3f").to_string(),
+        profile: target.profile.clone(),
+        api: target.api.clone(),
+        created_at: now.to_rfc3339(),
+        iterations: samples.len() as u32,
+        succeeded: ok.len() as u32,
+        p50_ms: percentile(&latencies, 50.0),
+        p95_ms: percentile(&latencies, 95.0),
+        tokens_per_sec: (millis > 0).then(|| tokens as f64 * 1000.0 / millis as f64),
+        samples,
+    }
+}
+
+/// 测试 Provider / 模型的端到端延迟（p50 / p95）和输出速度，结果保存在本地供对比
+#[guarded]
+#[command]
+pub async fn benchmark_provider(
+    profile: String,
+    prompt: String,
+    iterations: u32,
+) -> Result<BenchmarkResult, String> {
+    let cfg = config::load_openclaw_config()?;
+    let target = resolve_target(&cfg, profile.trim())?;
+    if privacy::is_enabled() && !privacy::is_local_url(&target.base_url) {
+        privacy::guard("测试远程 Provider")?;
+    }
+    let prompt = if prompt.trim().is_empty() {
+        "用一句话介绍你自己。".to_string()
+    } else {
+        prompt
+    };
+    let iterations = iterations.clamp(1, MAX_ITERATIONS);
+    info!(
+        "[性能测试] 测试 {}（{}，{} 次）",
+        target.profile, target.api, iterations
+    );
+
+    let client = http::client()?;
+    let mut samples = Vec::new();
+    for i in 0..iterations {
+        let started = Instant::now();
+        let result = run_once(&client, &target, &prompt).await;
+        let latency_ms = started.elapsed().as_millis() as u64;
+        if let Err(e) = &result {
+            warn!("[性能测试] 第 {} 次请求失败: {}", i + 1, e);
+        }
+        samples.push(BenchmarkSample {
+            latency_ms,
+            output_tokens: result.as_ref().ok().copied().flatten(),
+            error: result.err(),
+        });
+    }
+
+    let result = summarize(&target, samples);
+    if result.succeeded == 0 {
+        let error = result.samples[0].error.clone().unwrap_or_default();
+        return Err(format!("{} 所有请求均失败: {}", target.profile, error));
+    }
+    let mut results = load_results();
+    results.insert(0, result.clone());
+    results.truncate(MAX_RESULTS);
+    save_results(&results)?;
+    info!(
+        "[性能测试] ✓ {} p50 {}ms p95 {}ms",
+        result.profile,
+        result.p50_ms.unwrap_or(0),
+        result.p95_ms.unwrap_or(0)
+    );
+    Ok(result)
+}
+
+/// 已保存的测试结果（最新的在前）
+#[guarded]
+#[command]
+pub async fn list_benchmark_results() -> Result<Vec<BenchmarkResult>, String> {
+    Ok(load_results())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percentiles_and_target_resolution() {
+        let latencies: Vec<u64> = (1..=20).map(|i| i * 100).collect();
+        assert_eq!(percentile(&latencies, 50.0), Some(1000));
+        assert_eq!(percentile(&latencies, 95.0), Some(1900));
+        assert_eq!(percentile(&[], 50.0), None);
+
+        let cfg = json!({"models": {"providers": {"anthropic": {
+            "baseUrl": "https://api.anthropic.com/",
+            "apiKey": "sk-test",
+            "models": [{"id": "claude-sonnet-4-5", "api": "anthropic-messages"}]
+        }}}});
+        let target = resolve_target(&cfg, "anthropic/claude-sonnet-4-5").unwrap();
+        assert_eq!(target.api, "anthropic-messages");
+        assert_eq!(target.base_url, "https://api.anthropic.com");
+        assert!(resolve_target(&cfg, "openai/gpt-4o").is_err());
+        assert!(resolve_target(&cfg, "anthropic").is_err());
+    }
+}
@@ -0,0 +1,402 @@
+use crate::commands::{config, events, inventory, privacy, resources, settings, shutdown};
+use crate::utils::{audit, encoding, platform, shell, temp};
+use log::{info, warn};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use openclaw_macros::guarded;
+use tauri::{command, AppHandle};
+
+/// 浏览器运行时安装进度事件
+pub const BROWSER_RUNTIME_PROGRESS_EVENT: &str = "browser-runtime-progress";
+/// 安装 Chromium 需要的磁盘空间（下载包 + 解压后）
+const REQUIRED_SPACE_BYTES: u64 = 600 * 1024 * 1024;
+/// Playwright 浏览器国内镜像
+const PLAYWRIGHT_MIRROR: &str = "https://cdn.npmmirror.com/binaries/playwright";
+
+/// 浏览器运行时来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BrowserSource {
+    /// 系统安装的 Chrome / Chromium / Edge / Brave
+    System,
+    /// Playwright 下载的 Chromium
+    Playwright,
+}
+
+/// 检测到的浏览器运行时
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BrowserRuntime {
+    pub source: BrowserSource,
+    pub name: String,
+    /// 可执行文件路径
+    pub path: String,
+}
+
+/// 浏览器技能的运行时状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BrowserRuntimeStatus {
+    pub runtimes: Vec<BrowserRuntime>,
+    /// openclaw.json 中 `browser.executablePath` 的值
+    pub configured_path: Option<String>,
+    /// Playwright 浏览器缓存目录
+    pub playwright_cache: String,
+}
+
+/// 安装进度（逐行推送到前端）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BrowserRuntimeProgress {
+    pub line: String,
+    /// 下载百分比（从 Playwright 输出中解析）
+    pub percent: Option<u8>,
+}
+
+/// Playwright 浏览器缓存目录（`PLAYWRIGHT_BROWSERS_PATH` 优先）
+fn playwright_cache_dir() -> PathBuf {
+    if let Some(path) = std::env::var_os("PLAYWRIGHT_BROWSERS_PATH").filter(|p| !p.is_empty()) {
+        return PathBuf::from(path);
+    }
+    let home = dirs::home_dir().unwrap_or_default();
+    if platform::is_windows() {
+        dirs::data_local_dir()
+            .unwrap_or_else(|| home.join("AppData").join("Local"))
+            .join("ms-playwright")
+    } else if platform::is_macos() {
+        home.join("Library").join("Caches").join("ms-playwright")
+    } else {
+        home.join(".cache").join("ms-playwright")
+    }
+}
+
+/// Playwright 缓存中 chromium-<revision> 目录下的可执行文件
+fn playwright_executable(dir: &Path) -> Option<PathBuf> {
+    let candidates: &[&str] = if platform::is_windows() {
+        &["chrome-win64/chrome.exe", "chrome-win/chrome.exe"]
+    } else if platform::is_macos() {
+        &[
+            "chrome-mac-arm64/Chromium.app/Contents/MacOS/Chromium",
+            "chrome-mac/Chromium.app/Contents/MacOS/Chromium",
+            "chrome-mac-x64/Google Chrome for Testing.app/Contents/MacOS/Google Chrome for Testing",
+            "chrome-mac-arm64/Google Chrome for Testing.app/Contents/MacOS/Google Chrome for Testing",
+        ]
+    } else {
+        &["chrome-linux64/chrome", "chrome-linux/chrome"]
+    };
+    candidates.iter().map(|c| dir.join(c)).find(|p| p.is_file())
+}
+
+/// Playwright 下载的 Chromium（新版本在前）
+fn detect_playwright(cache: &Path) -> Vec<BrowserRuntime> {
+    let mut dirs: Vec<(u32, PathBuf)> = std::fs::read_dir(cache)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|e| {
+            let name = e.file_name().to_string_lossy().to_string();
+            let revision = name.strip_prefix("chromium-")?.parse().ok()?;
+            Some((revision, e.path()))
+        })
+        .collect();
+    dirs.sort_by_key(|d| std::cmp::Reverse(d.0));
+    dirs.into_iter()
+        .filter_map(|(revision, dir)| {
+            Some(BrowserRuntime {
+                source: BrowserSource::Playwright,
+                name: format!("Chromium (Playwright r{})", revision),
+                path: playwright_executable(&dir)?.to_string_lossy().to_string(),
+            })
+        })
+        .collect()
+}
+
+/// 系统安装的 Chromium 内核浏览器
+fn detect_system() -> Vec<BrowserRuntime> {
+    let mut found: Vec<(String, PathBuf)> = Vec::new();
+    if platform::is_windows() {
+        let roots: Vec<PathBuf> = ["ProgramFiles", "ProgramFiles(x86)", "LOCALAPPDATA"]
+            .iter()
+            .filter_map(std::env::var_os)
+            .map(PathBuf::from)
+            .collect();
+        for (name, relative) in [
+            ("Google Chrome", "Google/Chrome/Application/chrome.exe"),
+            ("Microsoft Edge", "Microsoft/Edge/Application/msedge.exe"),
+            ("Chromium", "Chromium/Application/chrome.exe"),
+            ("Brave", "BraveSoftware/Brave-Browser/Application/brave.exe"),
+        ] {
+            if let Some(path) = roots.iter().map(|r| r.join(relative)).find(|p| p.is_file()) {
+                found.push((name.to_string(), path));
+            }
+        }
+    } else if platform::is_macos() {
+        for (name, app) in [
+            ("Google Chrome", "Google Chrome"),
+            ("Chromium", "Chromium"),
+            ("Microsoft Edge", "Microsoft Edge"),
+            ("Brave", "Brave Browser"),
+        ] {
+            let path = PathBuf::from(format!("/Applications/{0}.app/Contents/MacOS/{0}", app));
+            if path.is_file() {
+                found.push((name.to_string(), path));
+            }
+        }
+    } else {
+        for (name, cmd) in [
+            ("Google Chrome", "google-chrome-stable"),
+            ("Google Chrome", "google-chrome"),
+            ("Chromium", "chromium"),
+            ("Chromium", "chromium-browser"),
+            ("Microsoft Edge", "microsoft-edge"),
+            ("Brave", "brave-browser"),
+        ] {
+            if found.iter().any(|(n, _)| n == name) {
+                continue;
+            }
+            if let Some(path) = inventory::which(cmd) {
+                found.push((name.to_string(), PathBuf::from(path)));
+            }
+        }
+    }
+    found
+        .into_iter()
+        .map(|(name, path)| BrowserRuntime {
+            source: BrowserSource::System,
+            name,
+            path: path.to_string_lossy().to_string(),
+        })
+        .collect()
+}
+
+fn runtime_status(cfg: &Value) -> BrowserRuntimeStatus {
+    let cache = playwright_cache_dir();
+    let mut runtimes = detect_playwright(&cache);
+    runtimes.extend(detect_system());
+    BrowserRuntimeStatus {
+        runtimes,
+        configured_path: cfg
+            .pointer("/browser/executablePath")
+            .and_then(|v| v.as_str())
+            .filter(|p| !p.is_empty())
+            .map(String::from),
+        playwright_cache: cache.to_string_lossy().to_string(),
+    }
+}
+
+/// 解析 Playwright 的下载进度行，如 `|■■■■■■■■      |  10% of 164.7 MiB`
+fn parse_percent(line: &str) -> Option<u8> {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    let re = RE.get_or_init(|| Regex::new(r"(\d{1,3})% of [\d.]+ ?[KMG]i?B").unwrap());
+    re.captures(line)?.get(1)?.as_str().parse().ok()
+}
+
+/// 写入 `browser.executablePath`，浏览器技能据此启动浏览器
+async fn configure_runtime(path: &str) -> Result<(), String> {
+    let mut cfg = config::load_openclaw_config()?;
+    if !cfg.is_object() {
+        cfg = json!({});
+    }
+    if !cfg["browser"].is_object() {
+        cfg["browser"] = json!({});
+    }
+    cfg["browser"]["executablePath"] = json!(path);
+    config::save_config(cfg).await?;
+    info!("[浏览器运行时] ✓ 浏览器技能使用: {}", path);
+    Ok(())
+}
+
+fn emit_progress(app: &AppHandle, line: &str) {
+    info!("[浏览器运行时] {}", line);
+    let _ = events::emit(
+        app,
+        BROWSER_RUNTIME_PROGRESS_EVENT,
+        BrowserRuntimeProgress {
+            line: line.to_string(),
+            percent: parse_percent(line),
+        },
+    );
+}
+
+/// 执行 `npx playwright install chromium`，输出逐行推送
+fn run_playwright_install(app: &AppHandle) -> Result<(), String> {
+    let settings = settings::load_settings();
+    let npx = if platform::is_windows() {
+        "npx.cmd"
+    } else {
+        "npx"
+    };
+    let cwd = temp::run_dir().map_err(|e| format!("创建临时目录失败: {}", e))?;
+    let mut command =
+        shell::piped_command(npx, &["--yes", "playwright", "install", "chromium"], &cwd);
+    shell::apply_overlay(&mut command, &settings.env_overrides.npm);
+    if !settings.npm_registry.trim().is_empty() {
+        command.env("npm_config_registry", settings.npm_registry.trim());
+    }
+    if settings.npm_registry.contains("npmmirror") {
+        command.env("PLAYWRIGHT_DOWNLOAD_HOST", PLAYWRIGHT_MIRROR);
+    }
+    if let Some(proxy) = settings.proxy.as_deref().filter(|p| !p.trim().is_empty()) {
+        command
+            .env("HTTPS_PROXY", proxy.trim())
+            .env("HTTP_PROXY", proxy.trim());
+    }
+    emit_progress(app, "$ npx playwright install chromium");
+    let mut child = command
+        .spawn()
+        .map_err(|e| format!("启动 npx 失败: {}", e))?;
+    // Manager 退出时结束下载进程
+    let operation = shutdown::begin_operation("安装浏览器运行时");
+    operation.attach_pid(child.id());
+
+    let forward = |reader: Box<dyn Read + Send>| -> Vec<String> {
+        let mut lines = Vec::new();
+        for chunk in BufReader::new(reader).split(b'\n') {
+            let Ok(chunk) = chunk else { break };
+            // 进度条在终端中用 \r 刷新，逐段推送
+            let text = encoding::decode_output(&chunk).to_string();
+            for line in text.split('\r').map(str::trim).filter(|l| !l.is_empty()) {
+                emit_progress(app, line);
+                lines.push(line.to_string());
+            }
+        }
+        lines
+    };
+    let stderr = child.stderr.take();
+    let stdout = child.stdout.take();
+    let stderr_lines = std::thread::scope(|scope| {
+        let handle = scope.spawn(|| stderr.map(|s| forward(Box::new(s))).unwrap_or_default());
+        if let Some(stdout) = stdout {
+            forward(Box::new(stdout));
+        }
+        handle.join().unwrap_or_default()
+    });
+    let status = child
+        .wait()
+        .map_err(|e| format!("等待 npx 结束失败: {}", e))?;
+    if status.success() {
+        Ok(())
+    } else {
+        let tail = &stderr_lines[stderr_lines.len().saturating_sub(10)..];
+        Err(format!(
+            "下载 Chromium 失败 (退出码 {:?}): {}",
+            status.code(),
+            tail.join("\n")
+        ))
+    }
+}
+
+/// 检测已安装的浏览器和 Playwright 缓存中的 Chromium
+#[guarded]
+#[command]
+pub async fn detect_browser_runtimes() -> Result<BrowserRuntimeStatus, String> {
+    let cfg = config::load_openclaw_config().unwrap_or(Value::Null);
+    tokio::task::spawn_blocking(move || runtime_status(&cfg))
+        .await
+        .map_err(|e| format!("检测浏览器失败: {}", e))
+}
+
+/// 下载 Playwright Chromium 并配置给浏览器技能，进度通过 `browser-runtime-progress` 事件推送
+#[guarded]
+#[command]
+pub async fn install_browser_runtime(app: AppHandle) -> Result<BrowserRuntime, String> {
+    privacy::guard("下载浏览器运行时")?;
+    if !shell::command_exists("npx") {
+        return Err("安装浏览器运行时需要 Node.js（npx），请先安装".to_string());
+    }
+    let cache = playwright_cache_dir();
+    if let Some(free) = resources::available_space(&cache) {
+        if free < REQUIRED_SPACE_BYTES {
+            return Err(format!(
+                "磁盘空间不足：{} 所在磁盘剩余 {}MB，安装 Chromium 至少需要 {}MB",
+                cache.display(),
+                free / 1024 / 1024,
+                REQUIRED_SPACE_BYTES / 1024 / 1024
+            ));
+        }
+    } else {
+        warn!(
+            "[浏览器运行时] 无法获取 {} 的可用空间，跳过检查",
+            cache.display()
+        );
+    }
+
+    info!("[浏览器运行时] 开始下载 Chromium 到 {}", cache.display());
+    let progress_app = app.clone();
+    let result = tokio::task::spawn_blocking(move || run_playwright_install(&progress_app))
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|r| r)
+        .and_then(|_| {
+            detect_playwright(&cache)
+                .into_iter()
+                .next()
+                .ok_or_else(|| format!("下载完成但在 {} 中找不到 Chromium", cache.display()))
+        });
+    audit::record(
+        "install_browser_runtime",
+        "chromium",
+        result.is_ok(),
+        json!({
+            "path": result.as_ref().ok().map(|r| r.path.clone()),
+            "error": result.as_ref().err(),
+        }),
+    );
+    let runtime = result?;
+    configure_runtime(&runtime.path).await?;
+    Ok(runtime)
+}
+
+/// 指定浏览器技能使用的浏览器（传入检测结果中的路径）
+#[guarded]
+#[command]
+pub async fn set_browser_runtime(path: String) -> Result<(), String> {
+    let path = path.trim().to_string();
+    if !Path::new(&path).is_file() {
+        return Err(format!("浏览器可执行文件不存在: {}", path));
+    }
+    configure_runtime(&path).await?;
+    audit::record("set_browser_runtime", &path, true, json!({}));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_progress_and_finds_playwright_chromium() {
+        assert_eq!(
+            parse_percent("|■■■■■■■■                                                                        |  10% of 164.7 MiB"),
+            Some(10)
+        );
+        assert_eq!(
+            parse_percent("|■■■■■■■■■■■■■■■■| 100% of 2.3 MiB"),
+            Some(100)
+        );
+        assert_eq!(
+            parse_percent("Chromium 131.0.6778.33 (playwright build v1148) downloaded to ..."),
+            None
+        );
+
+        let cache = std::env::temp_dir().join(format!("pw-test-{}", uuid::Uuid::new_v4().simple()));
+        let exe = if platform::is_windows() {
+            "chrome-win/chrome.exe"
+        } else if platform::is_macos() {
+            "chrome-mac/Chromium.app/Contents/MacOS/Chromium"
+        } else {
+            "chrome-linux/chrome"
+        };
+        for revision in ["1140", "1148"] {
+            let path = cache.join(format!("chromium-{}", revision)).join(exe);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, b"").unwrap();
+        }
+        std::fs::create_dir_all(cache.join("ffmpeg-1010")).unwrap();
+        let found = detect_playwright(&cache);
+        assert_eq!(found.len(), 2);
+        assert!(found[0].name.contains("r1148"));
+        let _ = std::fs::remove_dir_all(&cache);
+    }
+}
@@ -0,0 +1,367 @@
+use crate::commands::notifications::{self, NotificationTrigger};
+use crate::commands::{analytics, config, events, service, settings};
+use crate::utils::{audit, file, platform, secrets, time};
+use chrono::{DateTime, Datelike, Duration, FixedOffset, Utc};
+use chrono_tz::Tz;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use openclaw_macros::guarded;
+use tauri::{command, AppHandle};
+
+/// 预算提醒事件名
+pub const BUDGET_ALERT_EVENT: &str = "budget://alert";
+/// 预警阈值（百分比）
+const WARN_PERCENT: u8 = 80;
+/// 达到预算
+const CAP_PERCENT: u8 = 100;
+
+/// 预算周期
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetPeriod {
+    /// 每周（周一开始）
+    Weekly,
+    /// 每月（1 日开始）
+    Monthly,
+}
+
+/// 单个 Provider 的费用预算
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderBudget {
+    pub provider: String,
+    pub period: BudgetPeriod,
+    /// 预算金额（美元）
+    pub limit_usd: f64,
+    /// 达到预算时暂停该 Provider（清空 API Key，下个周期自动恢复），需要用户明确开启
+    #[serde(default)]
+    pub pause_at_cap: bool,
+}
+
+/// 预算使用情况
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BudgetStatus {
+    pub provider: String,
+    pub period: BudgetPeriod,
+    pub period_start: String,
+    pub limit_usd: f64,
+    pub spent_usd: f64,
+    pub percent: f64,
+    /// 已达到的阈值（80 / 100）
+    pub threshold: Option<u8>,
+    /// Provider 是否因超出预算被暂停
+    pub paused: bool,
+}
+
+/// 被暂停的 Provider
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PausedProvider {
+    /// 原 API Key（钥匙串可用时为钥匙串引用）
+    api_key: String,
+    /// 暂停时所在周期的开始时间（毫秒）
+    period_start: i64,
+    paused_at: String,
+}
+
+/// 预算检查状态（避免重复提醒）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct BudgetState {
+    /// provider:周期开始时间 -> 已提醒的最高阈值
+    #[serde(default)]
+    alerted: HashMap<String, u8>,
+    #[serde(default)]
+    paused: HashMap<String, PausedProvider>,
+}
+
+fn state_path() -> PathBuf {
+    Path::new(&platform::get_manager_data_dir()).join("budget_state.json")
+}
+
+fn load_state() -> BudgetState {
+    std::fs::read_to_string(state_path())
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+fn save_state(state: &BudgetState) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(state).map_err(|e| e.to_string())?;
+    file::write_atomic(&state_path(), content.as_bytes())
+        .map_err(|e| format!("保存预算状态失败: {}", e))
+}
+
+/// 当前周期的开始时间
+fn period_start(
+    period: BudgetPeriod,
+    now: DateTime<FixedOffset>,
+    tz: Option<Tz>,
+) -> DateTime<FixedOffset> {
+    let date = now.date_naive();
+    let start = match period {
+        BudgetPeriod::Weekly => date - Duration::days(date.weekday().num_days_from_monday() as i64),
+        BudgetPeriod::Monthly => date.with_day(1).unwrap_or(date),
+    };
+    time::start_of_day(tz, start).unwrap_or(now)
+}
+
+/// 已达到的阈值
+fn threshold(spent: f64, limit: f64) -> Option<u8> {
+    if limit <= 0.0 {
+        return None;
+    }
+    let percent = spent / limit * 100.0;
+    if percent >= CAP_PERCENT as f64 {
+        Some(CAP_PERCENT)
+    } else if percent >= WARN_PERCENT as f64 {
+        Some(WARN_PERCENT)
+    } else {
+        None
+    }
+}
+
+/// 计算所有预算的使用情况
+fn evaluate(state: &BudgetState) -> Result<Vec<BudgetStatus>, String> {
+    let budgets = settings::load_settings().budgets;
+    if budgets.is_empty() {
+        return Ok(Vec::new());
+    }
+    let tz = time::user_timezone();
+    let now = time::in_zone(tz, Utc::now());
+    let mut spend_by_start: HashMap<i64, HashMap<String, f64>> = HashMap::new();
+    let mut statuses = Vec::new();
+    for budget in budgets {
+        let start = period_start(budget.period, now, tz);
+        let start_ms = start.timestamp_millis();
+        let spend = match spend_by_start.entry(start_ms) {
+            Entry::Occupied(e) => e.into_mut(),
+            Entry::Vacant(e) => e.insert(analytics::provider_spend(start_ms)?),
+        };
+        let spent = spend.get(&budget.provider).copied().unwrap_or(0.0);
+        statuses.push(BudgetStatus {
+            percent: if budget.limit_usd > 0.0 {
+                spent / budget.limit_usd * 100.0
+            } else {
+                0.0
+            },
+            threshold: threshold(spent, budget.limit_usd),
+            paused: state.paused.contains_key(&budget.provider),
+            period_start: start.to_rfc3339(),
+            provider: budget.provider,
+            period: budget.period,
+            limit_usd: budget.limit_usd,
+            spent_usd: spent,
+        });
+    }
+    Ok(statuses)
+}
+
+/// 暂停 Provider：保存原 API Key 后清空，Gateway 重启后不再产生费用
+async fn pause_provider(
+    state: &mut BudgetState,
+    provider: &str,
+    period_start: i64,
+) -> Result<(), String> {
+    let mut cfg = config::load_openclaw_config()?;
+    let pointer = format!("/models/providers/{}/apiKey", provider);
+    let Some(api_key) = cfg
+        .pointer(&pointer)
+        .and_then(|v| v.as_str())
+        .filter(|k| !k.is_empty())
+        .map(|k| k.to_string())
+    else {
+        return Err(format!("Provider {} 未配置 API Key", provider));
+    };
+    // 优先存入钥匙串，不可用时保留原值（与 openclaw.json 中的明文相同）
+    let saved = match secrets::store_secret(&format!("budget-paused:{}", provider), &api_key) {
+        Ok(reference) => reference,
+        Err(e) => {
+            warn!("[预算] 无法将 {} 的 API Key 存入钥匙串: {}", provider, e);
+            api_key
+        }
+    };
+    if let Some(v) = cfg.pointer_mut(&pointer) {
+        *v = json!("");
+    }
+    state.paused.insert(
+        provider.to_string(),
+        PausedProvider {
+            api_key: saved,
+            period_start,
+            paused_at: time::now_rfc3339(),
+        },
+    );
+    save_state(state)?;
+    config::write_openclaw_config(&cfg)?;
+    service::restart_if_running().await;
+    audit::record(
+        "pause_provider",
+        provider,
+        true,
+        json!({ "reason": "budget" }),
+    );
+    info!("[预算] ✓ 已暂停 Provider {}", provider);
+    Ok(())
+}
+
+/// 恢复被暂停的 Provider
+async fn resume_provider(state: &mut BudgetState, provider: &str) -> Result<(), String> {
+    let Some(paused) = state.paused.get(provider).cloned() else {
+        return Ok(());
+    };
+    let api_key = secrets::resolve_secret(&paused.api_key)?;
+    let mut cfg = config::load_openclaw_config()?;
+    let pointer = format!("/models/providers/{}", provider);
+    match cfg.pointer_mut(&pointer).and_then(|v| v.as_object_mut()) {
+        Some(entry) => {
+            entry.insert("apiKey".to_string(), json!(api_key));
+            config::write_openclaw_config(&cfg)?;
+            service::restart_if_running().await;
+        }
+        None => warn!("[预算] Provider {} 已被删除，不再恢复", provider),
+    }
+    state.paused.remove(provider);
+    save_state(state)?;
+    audit::record("resume_provider", provider, true, json!({}));
+    info!("[预算] ✓ 已恢复 Provider {}", provider);
+    Ok(())
+}
+
+/// 周期开始时间（毫秒）
+fn start_millis(status: &BudgetStatus) -> i64 {
+    DateTime::parse_from_rfc3339(&status.period_start)
+        .map(|t| t.timestamp_millis())
+        .unwrap_or(0)
+}
+
+/// 后台监控定期调用：检查预算，达到 80% / 100% 时提醒，开启后在超出预算时暂停 Provider
+pub async fn check(app: &AppHandle) {
+    let mut state = load_state();
+    let statuses = match evaluate(&state) {
+        Ok(s) => s,
+        Err(e) => {
+            warn!("[预算] 统计费用失败: {}", e);
+            return;
+        }
+    };
+    let budgets = settings::load_settings().budgets;
+
+    // 进入新周期或取消预算后恢复被暂停的 Provider
+    let paused: Vec<(String, i64)> = state
+        .paused
+        .iter()
+        .map(|(p, v)| (p.clone(), v.period_start))
+        .collect();
+    for (provider, paused_start) in paused {
+        let current = statuses.iter().find(|s| s.provider == provider);
+        let rolled_over = current
+            .map(|s| start_millis(s) != paused_start)
+            .unwrap_or(true);
+        if rolled_over {
+            if let Err(e) = resume_provider(&mut state, &provider).await {
+                warn!("[预算] 恢复 Provider {} 失败: {}", provider, e);
+            }
+        }
+    }
+
+    // 只保留当前周期的提醒记录
+    let current_keys: Vec<String> = statuses
+        .iter()
+        .map(|s| format!("{}:{}", s.provider, start_millis(s)))
+        .collect();
+    state.alerted.retain(|k, _| current_keys.contains(k));
+
+    for (status, key) in statuses.iter().zip(current_keys) {
+        let Some(reached) = status.threshold else {
+            continue;
+        };
+        if state.alerted.get(&key).copied().unwrap_or(0) >= reached {
+            continue;
+        }
+        state.alerted.insert(key, reached);
+        if let Err(e) = save_state(&state) {
+            warn!("[预算] {}", e);
+        }
+
+        warn!(
+            "[预算] {} 已使用 {:.0}% (${:.2} / ${:.2})",
+            status.provider, status.percent, status.spent_usd, status.limit_usd
+        );
+        let _ = events::emit(app, BUDGET_ALERT_EVENT, status);
+        notifications::notify(
+            NotificationTrigger::BudgetAlert,
+            &format!("{}:{}", status.provider, reached),
+            &format!("{} 费用已达预算的 {}%", status.provider, reached),
+            &format!(
+                "本周期已花费 ${:.2}，预算 ${:.2}",
+                status.spent_usd, status.limit_usd
+            ),
+        );
+
+        let pause = budgets
+            .iter()
+            .any(|b| b.provider == status.provider && b.pause_at_cap);
+        if reached >= CAP_PERCENT && pause && !state.paused.contains_key(&status.provider) {
+            if let Err(e) = pause_provider(&mut state, &status.provider, start_millis(status)).await
+            {
+                warn!("[预算] ✗ 暂停 Provider {} 失败: {}", status.provider, e);
+            }
+        }
+    }
+}
+
+/// 获取各 Provider 的预算使用情况
+#[guarded]
+#[command]
+pub async fn get_budget_status() -> Result<Vec<BudgetStatus>, String> {
+    let state = load_state();
+    tokio::task::spawn_blocking(move || evaluate(&state))
+        .await
+        .map_err(|e| format!("统计费用失败: {}", e))?
+}
+
+/// 手动恢复因超出预算被暂停的 Provider（本周期内不会再次自动暂停）
+#[guarded]
+#[command]
+pub async fn resume_budget_provider(provider: String) -> Result<String, String> {
+    let mut state = load_state();
+    if !state.paused.contains_key(&provider) {
+        return Ok(format!("Provider {} 未被暂停", provider));
+    }
+    resume_provider(&mut state, &provider).await?;
+    Ok(format!("Provider {} 已恢复", provider))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn periods_start_on_monday_and_first_of_month() {
+        use chrono::{NaiveTime, TimeZone};
+
+        let tz = Some(Tz::Asia__Shanghai);
+        let now = Tz::Asia__Shanghai
+            .with_ymd_and_hms(2026, 3, 12, 15, 30, 0)
+            .unwrap()
+            .fixed_offset();
+        let weekly = period_start(BudgetPeriod::Weekly, now, tz);
+        assert_eq!(weekly.date_naive().to_string(), "2026-03-09");
+        assert_eq!(weekly.time(), NaiveTime::MIN);
+        assert_eq!(weekly.offset().local_minus_utc(), 8 * 3600);
+        let monthly = period_start(BudgetPeriod::Monthly, now, tz);
+        assert_eq!(monthly.date_naive().to_string(), "2026-03-01");
+
+        // 周日 20:00 UTC 在上海已是周一，周期从上海时间的周一零点开始
+        let sunday_utc = Utc.with_ymd_and_hms(2026, 3, 8, 20, 0, 0).unwrap();
+        let weekly = period_start(BudgetPeriod::Weekly, time::in_zone(tz, sunday_utc), tz);
+        assert_eq!(weekly.to_rfc3339(), "2026-03-09T00:00:00+08:00");
+
+        assert_eq!(threshold(7.9, 10.0), None);
+        assert_eq!(threshold(8.0, 10.0), Some(WARN_PERCENT));
+        assert_eq!(threshold(12.0, 10.0), Some(CAP_PERCENT));
+        assert_eq!(threshold(5.0, 0.0), None);
+    }
+}
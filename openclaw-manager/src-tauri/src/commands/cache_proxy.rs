@@ -0,0 +1,688 @@
+use crate::commands::sidecar::{self, HealthProbe, RestartPolicy, SidecarSpec};
+use crate::commands::{config, service, settings};
+use crate::utils::{audit, file, http, platform};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use openclaw_macros::guarded;
+use tauri::command;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// 以缓存代理模式启动 Manager 可执行文件的参数
+pub const PROXY_ARG: &str = "--cache-proxy";
+/// 代理统计接口
+const STATS_PATH: &str = "/__cache/stats";
+/// 清空缓存接口
+const CLEAR_PATH: &str = "/__cache/clear";
+/// 内存中最多缓存的响应数
+const MAX_ENTRIES: usize = 500;
+/// 请求头最大长度
+const MAX_HEAD_BYTES: usize = 64 * 1024;
+/// 转发到 Provider 的超时（生成长回复可能需要几分钟）
+const UPSTREAM_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// 本地缓存代理设置（测试时缓存相同的请求，节省费用）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CacheProxySettings {
+    pub enabled: bool,
+    pub port: u16,
+    /// 缓存有效期（秒）
+    pub ttl_secs: u64,
+}
+
+impl Default for CacheProxySettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 18791,
+            ttl_secs: 3600,
+        }
+    }
+}
+
+/// 代理路由（Manager 写入，代理进程启动时读取）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct ProxyRoutes {
+    port: u16,
+    ttl_secs: u64,
+    /// Provider 名称 -> 原始 baseUrl
+    routes: BTreeMap<String, String>,
+    /// 转发使用的 HTTP 代理
+    proxy: Option<String>,
+}
+
+/// 缓存命中统计
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CacheStats {
+    pub entries: usize,
+    pub hits: u64,
+    pub misses: u64,
+}
+
+/// 缓存代理状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheProxyStatus {
+    pub enabled: bool,
+    pub running: bool,
+    pub port: u16,
+    pub ttl_secs: u64,
+    pub pid: Option<u32>,
+    /// 经过代理的 Provider 及其原始 baseUrl
+    pub routes: BTreeMap<String, String>,
+    pub stats: Option<CacheStats>,
+}
+
+fn routes_path() -> PathBuf {
+    Path::new(&platform::get_manager_data_dir()).join("cache_proxy_routes.json")
+}
+
+fn load_routes(path: &Path) -> ProxyRoutes {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+fn proxy_base_url(port: u16, provider: &str) -> String {
+    format!("http://127.0.0.1:{}/{}", port, provider)
+}
+
+// ---------------------------------------------------------------------------
+// 代理进程
+// ---------------------------------------------------------------------------
+
+/// 收到的 HTTP 请求
+#[derive(Debug)]
+struct ProxyRequest {
+    method: String,
+    target: String,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl ProxyRequest {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+struct CachedResponse {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+    stored_at: Instant,
+}
+
+struct ProxyState {
+    routes: ProxyRoutes,
+    client: reqwest::Client,
+    cache: Mutex<HashMap<String, CachedResponse>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+/// 解析请求行和请求头（请求体另行读取）
+fn parse_head(head: &str) -> Result<ProxyRequest, String> {
+    let mut lines = head.split("\r\n");
+    let request_line = lines.next().unwrap_or_default();
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Err(format!("无效的请求行: {}", request_line));
+    };
+    let headers = lines
+        .filter_map(|l| l.split_once(':'))
+        .map(|(k, v)| (k.trim().to_string(), v.trim().to_string()))
+        .collect();
+    Ok(ProxyRequest {
+        method: method.to_string(),
+        target: target.to_string(),
+        headers,
+        body: Vec::new(),
+    })
+}
+
+/// 解码 chunked 请求体，数据不完整时返回 None
+fn decode_chunked(data: &[u8]) -> Option<Vec<u8>> {
+    let mut body = Vec::new();
+    let mut pos = 0;
+    loop {
+        let line_end = pos + data[pos..].windows(2).position(|w| w == b"\r\n")?;
+        let size_str = String::from_utf8_lossy(&data[pos..line_end]);
+        let size = usize::from_str_radix(size_str.split(';').next()?.trim(), 16).ok()?;
+        pos = line_end + 2;
+        if size == 0 {
+            return Some(body);
+        }
+        if data.len() < pos + size + 2 {
+            return None;
+        }
+        body.extend_from_slice(&data[pos..pos + size]);
+        pos += size + 2;
+    }
+}
+
+/// "/openai/v1/chat/completions" -> ("openai", "/v1/chat/completions")
+fn split_route(target: &str) -> Option<(&str, &str)> {
+    let rest = target.strip_prefix('/')?;
+    match rest.find(['/', '?']) {
+        Some(i) => Some((&rest[..i], &rest[i..])),
+        None => Some((rest, "")),
+    }
+    .filter(|(provider, _)| !provider.is_empty())
+}
+
+/// 缓存键：方法、Provider、路径和请求体（不含鉴权头）
+fn cache_key(method: &str, provider: &str, path: &str, body: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    for part in [method.as_bytes(), provider.as_bytes(), path.as_bytes()] {
+        hasher.update(part);
+        hasher.update([0]);
+    }
+    hasher.update(body);
+    hex::encode(hasher.finalize())
+}
+
+async fn read_request(stream: &mut TcpStream) -> Result<ProxyRequest, String> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 8192];
+    let head_end = loop {
+        if let Some(i) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break i;
+        }
+        if buf.len() > MAX_HEAD_BYTES {
+            return Err("请求头过长".to_string());
+        }
+        let n = stream.read(&mut chunk).await.map_err(|e| e.to_string())?;
+        if n == 0 {
+            return Err("连接已关闭".to_string());
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    };
+    let mut request = parse_head(&String::from_utf8_lossy(&buf[..head_end]))?;
+    request.body = buf.split_off(head_end + 4);
+
+    let chunked = request
+        .header("transfer-encoding")
+        .map(|v| v.eq_ignore_ascii_case("chunked"))
+        .unwrap_or(false);
+    if chunked {
+        let mut raw = std::mem::take(&mut request.body);
+        request.body = loop {
+            if let Some(body) = decode_chunked(&raw) {
+                break body;
+            }
+            let n = stream.read(&mut chunk).await.map_err(|e| e.to_string())?;
+            if n == 0 {
+                return Err("请求体不完整".to_string());
+            }
+            raw.extend_from_slice(&chunk[..n]);
+        };
+    } else {
+        let length: usize = request
+            .header("content-length")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(0);
+        while request.body.len() < length {
+            let n = stream.read(&mut chunk).await.map_err(|e| e.to_string())?;
+            if n == 0 {
+                return Err("请求体不完整".to_string());
+            }
+            request.body.extend_from_slice(&chunk[..n]);
+        }
+        request.body.truncate(length);
+    }
+    Ok(request)
+}
+
+async fn write_response(
+    stream: &mut TcpStream,
+    status: u16,
+    headers: &[(String, String)],
+    body: &[u8],
+    cache: &str,
+) -> std::io::Result<()> {
+    let reason = reqwest::StatusCode::from_u16(status)
+        .ok()
+        .and_then(|s| s.canonical_reason())
+        .unwrap_or("");
+    let mut head = format!("HTTP/1.1 {} {}\r\n", status, reason);
+    for (k, v) in headers {
+        if matches!(
+            k.to_ascii_lowercase().as_str(),
+            "content-length" | "transfer-encoding" | "connection"
+        ) {
+            continue;
+        }
+        head.push_str(&format!("{}: {}\r\n", k, v));
+    }
+    head.push_str(&format!(
+        "x-openclaw-cache: {}\r\ncontent-length: {}\r\nconnection: close\r\n\r\n",
+        cache,
+        body.len()
+    ));
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(body).await?;
+    stream.flush().await
+}
+
+fn json_header() -> Vec<(String, String)> {
+    vec![("content-type".to_string(), "application/json".to_string())]
+}
+
+async fn handle(mut stream: TcpStream, state: Arc<ProxyState>) {
+    let request = match read_request(&mut stream).await {
+        Ok(r) => r,
+        Err(e) => {
+            let _ = write_response(&mut stream, 400, &[], e.as_bytes(), "BYPASS").await;
+            return;
+        }
+    };
+
+    if request.target == STATS_PATH || request.target == CLEAR_PATH {
+        let entries = {
+            let mut cache = state.cache.lock().unwrap_or_else(|e| e.into_inner());
+            if request.target == CLEAR_PATH {
+                cache.clear();
+            }
+            cache.len()
+        };
+        let stats = CacheStats {
+            entries,
+            hits: state.hits.load(Ordering::Relaxed),
+            misses: state.misses.load(Ordering::Relaxed),
+        };
+        let body = serde_json::to_vec(&stats).unwrap_or_default();
+        let _ = write_response(&mut stream, 200, &json_header(), &body, "BYPASS").await;
+        return;
+    }
+
+    let Some((provider, path)) = split_route(&request.target) else {
+        let _ = write_response(&mut stream, 404, &[], b"unknown route", "BYPASS").await;
+        return;
+    };
+    let Some(upstream) = state.routes.routes.get(provider) else {
+        let body = format!("provider {} is not proxied", provider);
+        let _ = write_response(&mut stream, 404, &[], body.as_bytes(), "BYPASS").await;
+        return;
+    };
+
+    let cacheable = matches!(request.method.as_str(), "GET" | "POST");
+    let key = cache_key(&request.method, provider, path, &request.body);
+    let ttl = Duration::from_secs(state.routes.ttl_secs);
+    if cacheable {
+        let hit = {
+            let mut cache = state.cache.lock().unwrap_or_else(|e| e.into_inner());
+            cache.retain(|_, c| c.stored_at.elapsed() < ttl);
+            cache
+                .get(&key)
+                .map(|c| (c.status, c.headers.clone(), c.body.clone()))
+        };
+        if let Some((status, headers, body)) = hit {
+            state.hits.fetch_add(1, Ordering::Relaxed);
+            let _ = write_response(&mut stream, status, &headers, &body, "HIT").await;
+            return;
+        }
+    }
+    state.misses.fetch_add(1, Ordering::Relaxed);
+
+    let url = format!("{}{}", upstream.trim_end_matches('/'), path);
+    let method =
+        reqwest::Method::from_bytes(request.method.as_bytes()).unwrap_or(reqwest::Method::POST);
+    let mut upstream_request = state.client.request(method, &url);
+    for (k, v) in &request.headers {
+        if matches!(
+            k.to_ascii_lowercase().as_str(),
+            "host" | "content-length" | "connection" | "transfer-encoding" | "accept-encoding"
+        ) {
+            continue;
+        }
+        upstream_request = upstream_request.header(k, v);
+    }
+    let response = match upstream_request.body(request.body).send().await {
+        Ok(r) => r,
+        Err(e) => {
+            let body = format!("upstream request failed: {}", e);
+            let _ = write_response(&mut stream, 502, &[], body.as_bytes(), "MISS").await;
+            return;
+        }
+    };
+    let status = response.status().as_u16();
+    let headers: Vec<(String, String)> = response
+        .headers()
+        .iter()
+        .filter_map(|(k, v)| Some((k.to_string(), v.to_str().ok()?.to_string())))
+        .collect();
+    let body = match response.bytes().await {
+        Ok(b) => b.to_vec(),
+        Err(e) => {
+            let body = format!("upstream response failed: {}", e);
+            let _ = write_response(&mut stream, 502, &[], body.as_bytes(), "MISS").await;
+            return;
+        }
+    };
+
+    if cacheable && (200..300).contains(&status) {
+        let mut cache = state.cache.lock().unwrap_or_else(|e| e.into_inner());
+        if cache.len() >= MAX_ENTRIES {
+            if let Some(oldest) = cache
+                .iter()
+                .min_by_key(|(_, c)| c.stored_at)
+                .map(|(k, _)| k.clone())
+            {
+                cache.remove(&oldest);
+            }
+        }
+        cache.insert(
+            key,
+            CachedResponse {
+                status,
+                headers: headers.clone(),
+                body: body.clone(),
+                stored_at: Instant::now(),
+            },
+        );
+    }
+    let _ = write_response(&mut stream, status, &headers, &body, "MISS").await;
+}
+
+async fn serve(routes: ProxyRoutes) -> Result<(), String> {
+    let client = http::build_client(routes.proxy.as_deref(), UPSTREAM_TIMEOUT)?;
+    let listener = TcpListener::bind(("127.0.0.1", routes.port))
+        .await
+        .map_err(|e| format!("监听端口 {} 失败: {}", routes.port, e))?;
+    info!(
+        "[缓存代理] 监听 127.0.0.1:{}，{} 个 Provider",
+        routes.port,
+        routes.routes.len()
+    );
+    let state = Arc::new(ProxyState {
+        routes,
+        client,
+        cache: Mutex::new(HashMap::new()),
+        hits: AtomicU64::new(0),
+        misses: AtomicU64::new(0),
+    });
+    loop {
+        let (stream, _) = listener.accept().await.map_err(|e| e.to_string())?;
+        tokio::spawn(handle(stream, state.clone()));
+    }
+}
+
+/// 命令行带 --cache-proxy <routes.json> 时以代理进程运行，返回退出码
+pub fn run_from_args() -> Option<i32> {
+    let args: Vec<String> = std::env::args().collect();
+    let index = args.iter().position(|a| a == PROXY_ARG)?;
+    let routes = load_routes(Path::new(args.get(index + 1)?));
+    let runtime = match tokio::runtime::Runtime::new() {
+        Ok(r) => r,
+        Err(e) => {
+            warn!("[缓存代理] 创建运行时失败: {}", e);
+            return Some(1);
+        }
+    };
+    match runtime.block_on(serve(routes)) {
+        Ok(()) => Some(0),
+        Err(e) => {
+            warn!("[缓存代理] ✗ {}", e);
+            Some(1)
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Manager 侧：启动 / 停止代理并改写 Provider 地址
+// ---------------------------------------------------------------------------
+
+/// 缓存代理在辅助进程管理器中的名称
+const SIDECAR_NAME: &str = "cache-proxy";
+
+/// 以辅助进程方式启动代理（异常退出时自动重启）
+fn spawn_proxy(port: u16) -> Result<u32, String> {
+    let exe = std::env::current_exe().map_err(|e| format!("获取 Manager 路径失败: {}", e))?;
+    sidecar::register(SidecarSpec {
+        name: SIDECAR_NAME.to_string(),
+        program: exe.to_string_lossy().to_string(),
+        args: vec![
+            PROXY_ARG.to_string(),
+            routes_path().to_string_lossy().to_string(),
+        ],
+        env: Vec::new(),
+        health: Some(HealthProbe::Http {
+            url: format!("http://127.0.0.1:{}{}", port, STATS_PATH),
+        }),
+        restart: RestartPolicy::OnFailure { max_restarts: 3 },
+    });
+    sidecar::start(SIDECAR_NAME)
+}
+
+fn stop_proxy() {
+    sidecar::stop(SIDECAR_NAME);
+}
+
+async fn fetch_stats(port: u16) -> Option<CacheStats> {
+    let client = http::build_client(None, Duration::from_secs(2)).ok()?;
+    client
+        .get(format!("http://127.0.0.1:{}{}", port, STATS_PATH))
+        .send()
+        .await
+        .ok()?
+        .json()
+        .await
+        .ok()
+}
+
+/// 把配置了 baseUrl 的 Provider 指向代理，返回原始地址
+fn route_providers(port: u16) -> Result<BTreeMap<String, String>, String> {
+    let mut cfg = config::load_openclaw_config()?;
+    let previous = load_routes(&routes_path()).routes;
+    let mut routes = BTreeMap::new();
+    if let Some(providers) = cfg
+        .pointer_mut("/models/providers")
+        .and_then(|v| v.as_object_mut())
+    {
+        for (name, provider) in providers.iter_mut() {
+            let Some(base_url) = provider.get("baseUrl").and_then(|v| v.as_str()) else {
+                continue;
+            };
+            let proxied = proxy_base_url(port, name);
+            // 已经指向代理时沿用之前记录的原始地址
+            let original = if base_url == proxied {
+                match previous.get(name) {
+                    Some(o) => o.clone(),
+                    None => continue,
+                }
+            } else {
+                base_url.to_string()
+            };
+            routes.insert(name.clone(), original);
+            provider["baseUrl"] = json!(proxied);
+        }
+    }
+    config::write_openclaw_config(&cfg)?;
+    Ok(routes)
+}
+
+/// 恢复 Provider 的原始 baseUrl（用户在此期间修改过的地址保持不变）
+fn unroute_providers(port: u16, routes: &BTreeMap<String, String>) -> Result<(), String> {
+    let mut cfg = config::load_openclaw_config()?;
+    for (name, original) in routes {
+        let pointer = format!("/models/providers/{}/baseUrl", name);
+        if let Some(v) = cfg.pointer_mut(&pointer) {
+            if v.as_str() == Some(proxy_base_url(port, name).as_str()) {
+                *v = json!(original);
+            }
+        }
+    }
+    config::write_openclaw_config(&cfg)
+}
+
+async fn restart_gateway_if_running() {
+    if matches!(service::get_service_status().await, Ok(s) if s.running) {
+        if let Err(e) = service::restart_service(None).await {
+            warn!("[缓存代理] 重启 Gateway 失败: {}", e);
+        }
+    }
+}
+
+async fn status() -> CacheProxyStatus {
+    let proxy = settings::load_settings().cache_proxy;
+    let pid = sidecar::pid(SIDECAR_NAME);
+    let stats = match pid {
+        Some(_) => fetch_stats(proxy.port).await,
+        None => None,
+    };
+    CacheProxyStatus {
+        enabled: proxy.enabled,
+        running: pid.is_some(),
+        port: proxy.port,
+        ttl_secs: proxy.ttl_secs,
+        pid,
+        routes: load_routes(&routes_path()).routes,
+        stats,
+    }
+}
+
+/// Manager 启动时调用：已开启缓存代理时重新启动代理进程
+pub fn start_on_launch() {
+    let proxy = settings::load_settings().cache_proxy;
+    if !proxy.enabled {
+        return;
+    }
+    if let Err(e) = spawn_proxy(proxy.port) {
+        warn!("[缓存代理] ✗ {}", e);
+    }
+}
+
+/// 获取缓存代理状态
+#[guarded]
+#[command]
+pub async fn get_cache_proxy_status() -> Result<CacheProxyStatus, String> {
+    Ok(status().await)
+}
+
+/// 开启 / 关闭缓存代理：开启时启动代理并把 Provider 的 baseUrl 指向代理，关闭时恢复
+#[guarded]
+#[command]
+pub async fn set_cache_proxy(
+    enabled: bool,
+    ttl_secs: Option<u64>,
+) -> Result<CacheProxyStatus, String> {
+    let current = settings::load_settings();
+    let port = current.cache_proxy.port;
+    if enabled {
+        info!("[缓存代理] 开启缓存代理 (端口 {})", port);
+        let ttl_secs = ttl_secs.map_or(current.cache_proxy.ttl_secs, |ttl| ttl.max(1));
+        let routes = route_providers(port)?;
+        if routes.is_empty() {
+            return Err("没有配置了 baseUrl 的 Provider，无法使用缓存代理".to_string());
+        }
+        let proxy_routes = ProxyRoutes {
+            port,
+            ttl_secs,
+            routes: routes.clone(),
+            proxy: current.proxy.clone().filter(|p| !p.is_empty()),
+        };
+        let content = serde_json::to_string_pretty(&proxy_routes).map_err(|e| e.to_string())?;
+        file::write_atomic(&routes_path(), content.as_bytes())
+            .map_err(|e| format!("保存代理路由失败: {}", e))?;
+        let started = match spawn_proxy(port) {
+            Ok(_) => sidecar::wait_healthy(SIDECAR_NAME, Duration::from_secs(5)).await,
+            Err(e) => {
+                warn!("[缓存代理] ✗ {}", e);
+                false
+            }
+        };
+        if !started {
+            stop_proxy();
+            unroute_providers(port, &routes)?;
+            return Err(format!("缓存代理未能启动，请检查端口 {} 是否被占用", port));
+        }
+        settings::update(|current| {
+            current.cache_proxy.enabled = true;
+            current.cache_proxy.ttl_secs = ttl_secs;
+            Ok(())
+        })?;
+        audit::record(
+            "enable_cache_proxy",
+            &port.to_string(),
+            true,
+            json!({ "providers": routes.keys().collect::<Vec<_>>(), "ttl_secs": ttl_secs }),
+        );
+    } else {
+        info!("[缓存代理] 关闭缓存代理");
+        let routes = load_routes(&routes_path()).routes;
+        unroute_providers(port, &routes)?;
+        stop_proxy();
+        let _ = std::fs::remove_file(routes_path());
+        settings::update(|current| {
+            current.cache_proxy.enabled = false;
+            Ok(())
+        })?;
+        audit::record("disable_cache_proxy", &port.to_string(), true, json!({}));
+    }
+    restart_gateway_if_running().await;
+    Ok(status().await)
+}
+
+/// 清空代理中的缓存
+#[guarded]
+#[command]
+pub async fn clear_cache_proxy() -> Result<CacheStats, String> {
+    let port = settings::load_settings().cache_proxy.port;
+    let client = http::build_client(None, Duration::from_secs(5))?;
+    client
+        .post(format!("http://127.0.0.1:{}{}", port, CLEAR_PATH))
+        .send()
+        .await
+        .map_err(|e| format!("缓存代理未运行: {}", e))?
+        .json()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requests_are_parsed_and_routed() {
+        let request = parse_head(
+            "POST /openai/v1/chat/completions?x=1 HTTP/1.1\r\nHost: 127.0.0.1\r\nContent-Length: 2",
+        )
+        .unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.header("content-length"), Some("2"));
+        assert_eq!(
+            split_route(&request.target),
+            Some(("openai", "/v1/chat/completions?x=1"))
+        );
+        assert_eq!(split_route("/ollama"), Some(("ollama", "")));
+        assert_eq!(split_route("/"), None);
+
+        assert_eq!(
+            decode_chunked(b"4\r\nabcd\r\n3;ext\r\nefg\r\n0\r\n\r\n"),
+            Some(b"abcdefg".to_vec())
+        );
+        assert_eq!(decode_chunked(b"4\r\nab"), None);
+
+        assert_eq!(
+            cache_key("POST", "openai", "/v1", b"{}"),
+            cache_key("POST", "openai", "/v1", b"{}")
+        );
+        assert_ne!(
+            cache_key("POST", "openai", "/v1", b"{}"),
+            cache_key("POST", "openrouter", "/v1", b"{}")
+        );
+    }
+}
@@ -0,0 +1,296 @@
+//! OpenClaw 能力检测：不同版本的 OpenClaw 支持的子命令和参数不同，首次使用时读取
+//! `openclaw --help` 和相关子命令的帮助，确定当前版本支持哪些功能（按版本缓存）。
+//! 不支持的功能直接返回 NotSupportedByVersion 错误，而不是让 CLI 报出难以理解的错误
+use crate::commands::{installer, probe_cache};
+use crate::utils::{shell, time};
+use log::{debug, info};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use openclaw_macros::guarded;
+use tauri::command;
+
+/// 能力检测结果的缓存键（键后附加 OpenClaw 版本）
+pub const CAPABILITIES: &str = "openclaw_capabilities";
+/// 同一版本的能力不会变化，缓存时间较长（安装、更新后版本号变化即重新检测）
+const CAPABILITIES_TTL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+/// 不支持时的错误前缀（前端据此提示更新 OpenClaw）
+pub const NOT_SUPPORTED_PREFIX: &str = "NotSupportedByVersion";
+
+/// Manager 依赖的 OpenClaw 功能
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Feature {
+    /// 对话（openclaw agent --message）
+    Agent,
+    /// 本地模式对话（openclaw agent --local，用于测试 AI 连接）
+    AgentLocal,
+    /// 渠道通讯录（openclaw directory ... --json）
+    Directory,
+    /// 重建知识库索引（openclaw memory index --force）
+    MemoryIndex,
+    /// 读取 Gateway 日志（openclaw logs --lines）
+    Logs,
+    /// 插件管理（openclaw plugins）
+    Plugins,
+    /// 技能管理（openclaw skill）
+    Skills,
+    /// 健康检查（openclaw doctor）
+    Doctor,
+}
+
+impl Feature {
+    pub const ALL: [Feature; 8] = [
+        Feature::Agent,
+        Feature::AgentLocal,
+        Feature::Directory,
+        Feature::MemoryIndex,
+        Feature::Logs,
+        Feature::Plugins,
+        Feature::Skills,
+        Feature::Doctor,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Agent => "对话",
+            Self::AgentLocal => "本地模式对话",
+            Self::Directory => "渠道通讯录",
+            Self::MemoryIndex => "重建知识库索引",
+            Self::Logs => "读取 Gateway 日志",
+            Self::Plugins => "插件管理",
+            Self::Skills => "技能管理",
+            Self::Doctor => "健康检查",
+        }
+    }
+
+    /// (子命令, 需要的参数)
+    fn usage(&self) -> (&'static [&'static str], &'static [&'static str]) {
+        match self {
+            Self::Agent => (&["agent"], &["--message"]),
+            Self::AgentLocal => (&["agent"], &["--local", "--message"]),
+            Self::Directory => (&["directory"], &[]),
+            Self::MemoryIndex => (&["memory", "index"], &["--force"]),
+            Self::Logs => (&["logs"], &["--lines"]),
+            Self::Plugins => (&["plugins"], &[]),
+            Self::Skills => (&["skill"], &[]),
+            Self::Doctor => (&["doctor"], &[]),
+        }
+    }
+}
+
+/// 单个功能的支持情况
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeatureSupport {
+    pub feature: Feature,
+    pub supported: bool,
+    /// 不支持的原因（缺少的子命令或参数）
+    pub missing: Option<String>,
+}
+
+/// 当前 OpenClaw 版本的能力
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Capabilities {
+    pub version: String,
+    /// 顶层子命令
+    pub commands: Vec<String>,
+    pub features: Vec<FeatureSupport>,
+    pub detected_at: String,
+}
+
+impl Capabilities {
+    fn support(&self, feature: Feature) -> Option<&FeatureSupport> {
+        self.features.iter().find(|f| f.feature == feature)
+    }
+}
+
+/// 从帮助输出中解析子命令列表（commander 风格：`Commands:` 段落中每行第一个词）
+fn parse_commands(help: &str) -> Vec<String> {
+    let mut commands = Vec::new();
+    let mut in_section = false;
+    for line in help.lines() {
+        let trimmed = line.trim();
+        if !line.starts_with(' ') && trimmed.ends_with(':') {
+            in_section = trimmed.eq_ignore_ascii_case("commands:");
+            continue;
+        }
+        if !in_section || trimmed.is_empty() {
+            continue;
+        }
+        let Some(name) = trimmed.split_whitespace().next() else {
+            continue;
+        };
+        // 别名写作 `skill|skills`
+        for alias in name.split('|') {
+            if alias.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') && alias != "help" {
+                commands.push(alias.to_string());
+            }
+        }
+    }
+    commands
+}
+
+/// 帮助输出中是否包含参数
+fn has_flag(help: &str, flag: &str) -> bool {
+    help.split(|c: char| c.is_whitespace() || c == ',' || c == '=' || c == '[' || c == '<')
+        .any(|word| word == flag)
+}
+
+/// 按帮助输出判断功能是否支持，`sub_help` 读取子命令的帮助（读取不到或格式无法识别时不限制）
+fn evaluate(
+    feature: Feature,
+    commands: &[String],
+    sub_help: &mut impl FnMut(&[&str]) -> Option<String>,
+) -> FeatureSupport {
+    let (path, flags) = feature.usage();
+    let mut check = || {
+        if !commands.iter().any(|c| c == path[0]) {
+            return Some(format!("缺少子命令 openclaw {}", path[0]));
+        }
+        if path.len() > 1 {
+            let subs = parse_commands(&sub_help(&path[..1])?);
+            if !subs.is_empty() && !subs.iter().any(|c| c == path[1]) {
+                return Some(format!("缺少子命令 openclaw {}", path.join(" ")));
+            }
+        }
+        if flags.is_empty() {
+            return None;
+        }
+        let help = sub_help(path)?;
+        let absent: Vec<&str> = flags
+            .iter()
+            .copied()
+            .filter(|f| !has_flag(&help, f))
+            .collect();
+        (!absent.is_empty())
+            .then(|| format!("openclaw {} 缺少参数 {}", path.join(" "), absent.join(" ")))
+    };
+    let missing = check();
+    FeatureSupport {
+        feature,
+        supported: missing.is_none(),
+        missing,
+    }
+}
+
+fn detect(version: &str) -> Result<Capabilities, String> {
+    info!("[能力检测] 检测 OpenClaw {} 支持的功能...", version);
+    let help = shell::run_openclaw(&["--help"])?;
+    let commands = parse_commands(&help);
+    if commands.is_empty() {
+        return Err("无法从 openclaw --help 解析子命令".to_string());
+    }
+    let mut sub_help = |path: &[&str]| {
+        let mut args = path.to_vec();
+        args.push("--help");
+        shell::run_openclaw(&args).ok()
+    };
+    let features: Vec<FeatureSupport> = Feature::ALL
+        .iter()
+        .map(|f| evaluate(*f, &commands, &mut sub_help))
+        .collect();
+    for f in features.iter().filter(|f| !f.supported) {
+        info!(
+            "[能力检测] 不支持 {:?}: {}",
+            f.feature,
+            f.missing.as_deref().unwrap_or_default()
+        );
+    }
+    Ok(Capabilities {
+        version: version.to_string(),
+        commands,
+        features,
+        detected_at: time::now_rfc3339(),
+    })
+}
+
+/// 当前版本的能力（按版本缓存），未安装或检测失败时返回错误
+pub fn current() -> Result<Capabilities, String> {
+    let version = installer::cached_openclaw_version().ok_or("未安装 OpenClaw")?;
+    probe_cache::get_or_probe(
+        &format!("{}:{}", CAPABILITIES, version),
+        CAPABILITIES_TTL,
+        || detect(&version),
+    )
+}
+
+fn not_supported(version: &str, feature: Feature, missing: &str) -> String {
+    format!(
+        "{}: 当前 OpenClaw {} 不支持{}（{}），请更新 OpenClaw",
+        NOT_SUPPORTED_PREFIX,
+        version,
+        feature.label(),
+        missing
+    )
+}
+
+/// 使用功能前检查当前版本是否支持；检测失败时不限制使用（由 CLI 自行报错）
+pub fn require(feature: Feature) -> Result<(), String> {
+    let caps = match current() {
+        Ok(caps) => caps,
+        Err(e) => {
+            debug!("[能力检测] 跳过 {:?} 检查: {}", feature, e);
+            return Ok(());
+        }
+    };
+    match caps.support(feature) {
+        Some(FeatureSupport {
+            supported: false,
+            missing,
+            ..
+        }) => Err(not_supported(
+            &caps.version,
+            feature,
+            missing.as_deref().unwrap_or_default(),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// 获取当前 OpenClaw 版本支持的功能（`refresh` 为 true 时重新检测）
+#[guarded]
+#[command]
+pub async fn get_capabilities(refresh: Option<bool>) -> Result<Capabilities, String> {
+    if refresh.unwrap_or(false) {
+        probe_cache::invalidate(Some(probe_cache::OPENCLAW_VERSION));
+        probe_cache::invalidate(Some(CAPABILITIES));
+    }
+    tauri::async_runtime::spawn_blocking(current)
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_missing_commands_and_flags_from_help() {
+        let help = "Usage: openclaw [options] [command]\n\nOptions:\n  -V, --version  output the version number\n\nCommands:\n  gateway [options]        Run the gateway\n  agent [options]          Talk to an agent\n  skill|skills <action>    Manage skills\n  logs [options]           Show gateway logs\n  help [command]           display help for command\n";
+        let commands = parse_commands(help);
+        assert_eq!(
+            commands,
+            vec!["gateway", "agent", "skill", "skills", "logs"]
+        );
+
+        let mut sub_help = |path: &[&str]| match path {
+            ["agent"] => Some("Options:\n  --to <number>\n  --message <text>\n".to_string()),
+            ["logs"] => Some("Options:\n  -n, --lines <count>\n".to_string()),
+            ["skill"] => Some("Commands:\n  list\n  install <name>\n".to_string()),
+            _ => None,
+        };
+        let mut support = |f| evaluate(f, &commands, &mut sub_help);
+        assert!(support(Feature::Agent).supported);
+        assert!(support(Feature::Logs).supported);
+        assert!(support(Feature::Skills).supported);
+        let local = support(Feature::AgentLocal);
+        assert!(!local.supported);
+        assert_eq!(
+            local.missing.as_deref(),
+            Some("openclaw agent 缺少参数 --local")
+        );
+        assert!(!support(Feature::Directory).supported);
+
+        let error = not_supported("1.2.0", Feature::Directory, "缺少子命令 openclaw directory");
+        assert!(error.starts_with("NotSupportedByVersion: 当前 OpenClaw 1.2.0 不支持渠道通讯录"));
+    }
+}
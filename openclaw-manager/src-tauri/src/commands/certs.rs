@@ -0,0 +1,191 @@
+use crate::commands::settings;
+use crate::utils::{audit, file, platform};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use openclaw_macros::guarded;
+use tauri::command;
+
+/// Node.js 额外信任的 CA 证书环境变量
+pub const NODE_EXTRA_CA_CERTS: &str = "NODE_EXTRA_CA_CERTS";
+
+const PEM_BEGIN: &str = "-----BEGIN CERTIFICATE-----";
+const PEM_END: &str = "-----END CERTIFICATE-----";
+
+/// 已导入的企业根证书
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaCertificateStatus {
+    pub installed: bool,
+    /// Manager 数据目录中的证书副本
+    pub path: Option<String>,
+    /// 证书数量（一个文件可以包含证书链）
+    pub count: usize,
+    /// 文件的 SHA-256，用于确认导入的是哪个证书
+    pub sha256: Option<String>,
+}
+
+/// 证书保存位置
+fn cert_path() -> PathBuf {
+    Path::new(&platform::get_manager_data_dir())
+        .join("certs")
+        .join("corporate-ca.pem")
+}
+
+/// 从 PEM 文本中提取证书块（忽略证书前后的说明文字）
+fn pem_blocks(content: &str) -> Vec<String> {
+    let mut blocks = Vec::new();
+    let mut rest = content;
+    while let Some(start) = rest.find(PEM_BEGIN) {
+        let Some(len) = rest[start..].find(PEM_END) else {
+            break;
+        };
+        let end = start + len + PEM_END.len();
+        blocks.push(rest[start..end].to_string());
+        rest = &rest[end..];
+    }
+    blocks
+}
+
+/// 已导入证书的路径（设置中记录且文件存在）
+pub fn configured_path() -> Option<String> {
+    settings::load_settings()
+        .ca_certificate
+        .filter(|p| Path::new(p).exists())
+}
+
+/// Manager 的 HTTP 客户端额外信任的根证书
+pub fn root_certificates() -> Vec<reqwest::Certificate> {
+    let Some(path) = configured_path() else {
+        return Vec::new();
+    };
+    match std::fs::read(&path).map(|pem| reqwest::Certificate::from_pem_bundle(&pem)) {
+        Ok(Ok(certs)) => certs,
+        Ok(Err(e)) => {
+            warn!("[证书] 解析 {} 失败: {}", path, e);
+            Vec::new()
+        }
+        Err(e) => {
+            warn!("[证书] 读取 {} 失败: {}", path, e);
+            Vec::new()
+        }
+    }
+}
+
+/// 让 Manager 启动的 npm / openclaw 进程信任已导入的证书（启动时和导入后调用）
+pub fn apply_env() {
+    match configured_path() {
+        Some(path) => {
+            info!("[证书] {}={}", NODE_EXTRA_CA_CERTS, path);
+            std::env::set_var(NODE_EXTRA_CA_CERTS, path);
+        }
+        None => std::env::remove_var(NODE_EXTRA_CA_CERTS),
+    }
+}
+
+fn status() -> CaCertificateStatus {
+    let Some(path) = configured_path() else {
+        return CaCertificateStatus {
+            installed: false,
+            path: None,
+            count: 0,
+            sha256: None,
+        };
+    };
+    let content = std::fs::read(&path).unwrap_or_default();
+    CaCertificateStatus {
+        installed: true,
+        count: pem_blocks(&String::from_utf8_lossy(&content)).len(),
+        sha256: Some(hex::encode(Sha256::digest(&content))),
+        path: Some(path),
+    }
+}
+
+/// 获取已导入的企业根证书
+#[guarded]
+#[command]
+pub async fn get_ca_certificate() -> Result<CaCertificateStatus, String> {
+    Ok(status())
+}
+
+/// 导入企业根证书（PEM / Base-64 编码的 .cer）
+/// 证书会被 Manager 的 HTTP 客户端信任，并通过 NODE_EXTRA_CA_CERTS 传给 npm 和网关
+#[guarded]
+#[command]
+pub async fn import_ca_certificate(path: String) -> Result<CaCertificateStatus, String> {
+    info!("[证书] 导入根证书: {}", path);
+    let content = std::fs::read(&path).map_err(|e| format!("读取证书失败: {}", e))?;
+    let blocks = pem_blocks(&String::from_utf8_lossy(&content));
+    if blocks.is_empty() {
+        return Err(
+            "未找到 PEM 格式的证书，请导出为 Base-64 编码的 X.509 证书（.pem / .cer）".to_string(),
+        );
+    }
+    let pem = blocks.join("\n") + "\n";
+    reqwest::Certificate::from_pem_bundle(pem.as_bytes())
+        .map_err(|e| format!("证书无效: {}", e))?;
+
+    let target = cert_path();
+    if let Some(parent) = target.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("创建证书目录失败: {}", e))?;
+    }
+    file::write_atomic(&target, pem.as_bytes()).map_err(|e| format!("保存证书失败: {}", e))?;
+    let target = target.to_string_lossy().to_string();
+
+    settings::update(|current| {
+        current.ca_certificate = Some(target.clone());
+        Ok(())
+    })?;
+
+    // 网关以守护进程运行时不继承 Manager 的环境变量，同时写入 OpenClaw 环境文件
+    let env_path = platform::get_env_file_path();
+    if let Err(e) = file::set_env_value(&env_path, NODE_EXTRA_CA_CERTS, &target) {
+        warn!("[证书] 写入环境文件失败: {}", e);
+    }
+    apply_env();
+    audit::record(
+        "import_ca_certificate",
+        &path,
+        true,
+        json!({ "count": blocks.len() }),
+    );
+    info!("[证书] ✓ 已导入 {} 个证书", blocks.len());
+    Ok(status())
+}
+
+/// 移除已导入的企业根证书
+#[guarded]
+#[command]
+pub async fn remove_ca_certificate() -> Result<String, String> {
+    let Some(path) = settings::update(|current| Ok(current.ca_certificate.take()))? else {
+        return Ok("未导入证书".to_string());
+    };
+    let env_path = platform::get_env_file_path();
+    if file::read_env_value(&env_path, NODE_EXTRA_CA_CERTS).as_deref() == Some(path.as_str()) {
+        let _ = file::remove_env_value(&env_path, NODE_EXTRA_CA_CERTS);
+    }
+    let _ = std::fs::remove_file(&path);
+    apply_env();
+    audit::record("remove_ca_certificate", &path, true, json!({}));
+    info!("[证书] ✓ 已移除根证书");
+    Ok("已移除根证书".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pem_blocks_skip_surrounding_text() {
+        let content = format!(
+            "subject=CN=Corp Root\n{}\nMIIB\n{}\nissuer=CN=Corp\n{}\nMIIC\n{}\n{}\ntruncated",
+            PEM_BEGIN, PEM_END, PEM_BEGIN, PEM_END, PEM_BEGIN
+        );
+        let blocks = pem_blocks(&content);
+        assert_eq!(blocks.len(), 2);
+        assert!(blocks[0].starts_with(PEM_BEGIN) && blocks[0].ends_with(PEM_END));
+        assert!(blocks[1].contains("MIIC"));
+        assert!(pem_blocks("not a certificate").is_empty());
+    }
+}
@@ -0,0 +1,258 @@
+//! 剪贴板：复制密钥和脱敏配置都在后端完成，前端只拿到掩码，不接触明文密钥。
+//! 复制的密钥在一段时间后自动清除（只有剪贴板内容仍是该密钥时才清除，不会覆盖用户之后复制的内容）
+use crate::commands::secret_handles::is_secret_key;
+use crate::commands::{config, sessions};
+use crate::utils::{audit, secrets};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::sync::Mutex;
+use std::time::Duration;
+use openclaw_macros::guarded;
+use tauri::{command, AppHandle};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+/// 默认在复制后多久清除剪贴板（秒）
+const DEFAULT_CLEAR_SECS: u64 = 30;
+/// 允许的清除时间范围（秒）
+const CLEAR_SECS_RANGE: (u64, u64) = (5, 600);
+/// 脱敏配置中替换密钥的占位符
+const REDACTED: &str = "<redacted>";
+
+/// 配置中的密钥（不含明文）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SecretInfo {
+    /// 配置路径，如 models/providers/openai/apiKey
+    pub id: String,
+    pub masked: String,
+}
+
+/// 复制脱敏配置的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SanitizedConfigExport {
+    /// 替换的密钥数
+    pub redacted: usize,
+    pub bytes: usize,
+}
+
+/// Manager 最近放到剪贴板的密钥摘要（用于判断剪贴板是否仍归 Manager 所有）
+static OWNED: Mutex<Option<String>> = Mutex::new(None);
+
+fn digest(text: &str) -> String {
+    hex::encode(Sha256::digest(text.as_bytes()))
+}
+
+fn mask(secret: &str) -> String {
+    let chars: Vec<char> = secret.chars().collect();
+    if chars.len() > 8 {
+        let head: String = chars[..4].iter().collect();
+        let tail: String = chars[chars.len() - 4..].iter().collect();
+        format!("{}...{}", head, tail)
+    } else {
+        "****".to_string()
+    }
+}
+
+/// 收集配置中的密钥（路径, 值）
+fn collect_secrets(value: &Value, path: &str, out: &mut Vec<(String, String)>) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map {
+                let child_path = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}/{}", path, key)
+                };
+                match child.as_str() {
+                    Some(s) if is_secret_key(key) && !s.is_empty() => {
+                        out.push((child_path, s.to_string()))
+                    }
+                    _ => collect_secrets(child, &child_path, out),
+                }
+            }
+        }
+        Value::Array(items) => {
+            for (i, item) in items.iter().enumerate() {
+                collect_secrets(item, &format!("{}/{}", path, i), out);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// 替换密钥字段，其它字符串按默认规则脱敏（邮箱、用户目录等），返回替换的密钥数
+fn sanitize(value: &mut Value) -> usize {
+    match value {
+        Value::Object(map) => map
+            .iter_mut()
+            .map(|(key, child)| match child {
+                Value::String(s) if is_secret_key(key) && !s.is_empty() => {
+                    *child = json!(REDACTED);
+                    1
+                }
+                _ => sanitize(child),
+            })
+            .sum(),
+        Value::Array(items) => items.iter_mut().map(sanitize).sum(),
+        Value::String(s) => {
+            *s = sessions::redact_text(s);
+            0
+        }
+        _ => 0,
+    }
+}
+
+fn find_secret(cfg: &Value, id: &str) -> Result<String, String> {
+    let mut found = Vec::new();
+    collect_secrets(cfg, "", &mut found);
+    let (_, value) = found
+        .into_iter()
+        .find(|(path, _)| path == id)
+        .ok_or_else(|| format!("配置中没有密钥: {}", id))?;
+    secrets::resolve_secret(&value)
+}
+
+/// 剪贴板内容仍是 Manager 复制的密钥时清除（`expected` 指定时只清除该密钥）
+fn clear_if_owned(app: &AppHandle, expected: Option<&str>) {
+    let mut owned = OWNED.lock().unwrap_or_else(|e| e.into_inner());
+    let Some(current) = owned.clone() else {
+        return;
+    };
+    if expected.is_some_and(|e| e != current) {
+        return;
+    }
+    let still_owned = app
+        .clipboard()
+        .read_text()
+        .is_ok_and(|text| digest(&text) == current);
+    if still_owned {
+        match app.clipboard().clear() {
+            Ok(_) => info!("[剪贴板] ✓ 已清除复制的密钥"),
+            Err(e) => warn!("[剪贴板] 清除剪贴板失败: {}", e),
+        }
+    }
+    *owned = None;
+}
+
+/// Manager 退出时清除仍在剪贴板中的密钥
+pub fn clear_on_exit(app: &AppHandle) {
+    clear_if_owned(app, None);
+}
+
+/// 列出配置中的密钥（只返回掩码）
+#[guarded]
+#[command]
+pub async fn list_secrets() -> Result<Vec<SecretInfo>, String> {
+    let cfg = config::load_openclaw_config()?;
+    let mut found = Vec::new();
+    collect_secrets(&cfg, "", &mut found);
+    Ok(found
+        .into_iter()
+        .map(|(id, value)| SecretInfo {
+            id,
+            masked: if secrets::is_keychain_ref(&value) {
+                "(钥匙串)".to_string()
+            } else {
+                mask(&value)
+            },
+        })
+        .collect())
+}
+
+/// 把密钥复制到剪贴板，`clear_after_secs` 秒后（默认 30 秒）自动清除，返回实际的清除时间
+#[guarded]
+#[command]
+pub async fn copy_secret_to_clipboard(
+    app: AppHandle,
+    id: String,
+    clear_after_secs: Option<u64>,
+) -> Result<u64, String> {
+    let (min, max) = CLEAR_SECS_RANGE;
+    let secs = clear_after_secs
+        .unwrap_or(DEFAULT_CLEAR_SECS)
+        .clamp(min, max);
+    let cfg = config::load_openclaw_config()?;
+    let secret = find_secret(&cfg, &id)?;
+    app.clipboard()
+        .write_text(secret.as_str())
+        .map_err(|e| format!("写入剪贴板失败: {}", e))?;
+    let hash = digest(&secret);
+    *OWNED.lock().unwrap_or_else(|e| e.into_inner()) = Some(hash.clone());
+    audit::record(
+        "copy_secret_to_clipboard",
+        &id,
+        true,
+        json!({ "clear_after_secs": secs }),
+    );
+    info!("[剪贴板] ✓ 已复制密钥 {}，{} 秒后清除", id, secs);
+
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(Duration::from_secs(secs)).await;
+        clear_if_owned(&app, Some(&hash));
+    });
+    Ok(secs)
+}
+
+/// 把脱敏后的 openclaw.json 复制到剪贴板，用于分享配置或提交问题
+#[guarded]
+#[command]
+pub async fn export_sanitized_config(app: AppHandle) -> Result<SanitizedConfigExport, String> {
+    let mut cfg = config::load_openclaw_config()?;
+    let redacted = sanitize(&mut cfg);
+    let content = serde_json::to_string_pretty(&cfg).map_err(|e| e.to_string())?;
+    app.clipboard()
+        .write_text(content.as_str())
+        .map_err(|e| format!("写入剪贴板失败: {}", e))?;
+    // 新内容覆盖了之前复制的密钥
+    *OWNED.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    info!("[剪贴板] ✓ 已复制脱敏配置（替换 {} 个密钥）", redacted);
+    Ok(SanitizedConfigExport {
+        redacted,
+        bytes: content.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_and_redacts_secrets() {
+        let mut cfg = json!({
+            "models": { "providers": { "openai": {
+                "apiKey": "sk-1234567890abcdef",
+                "baseUrl": "https://api.openai.com/v1",
+                "models": [{ "id": "gpt-4o", "maxTokens": 4096 }]
+            }}},
+            "channels": { "telegram": { "botToken": "123:abc", "allowFrom": ["ops@example.com"] } },
+            "env": { "OPENAI_API_KEY": "sk-env" },
+            "agents": { "defaults": { "contextTokens": 200000 } }
+        });
+        let mut found = Vec::new();
+        collect_secrets(&cfg, "", &mut found);
+        let ids: Vec<&str> = found.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(
+            ids,
+            vec![
+                "channels/telegram/botToken",
+                "env/OPENAI_API_KEY",
+                "models/providers/openai/apiKey"
+            ]
+        );
+        assert_eq!(mask(&found[2].1), "sk-1...cdef");
+        assert_eq!(mask(&found[0].1), "****");
+
+        assert_eq!(sanitize(&mut cfg), 3);
+        assert_eq!(cfg["models"]["providers"]["openai"]["apiKey"], REDACTED);
+        assert_eq!(
+            cfg["models"]["providers"]["openai"]["baseUrl"],
+            "https://api.openai.com/v1"
+        );
+        assert_eq!(cfg["agents"]["defaults"]["contextTokens"], 200000);
+        assert_ne!(
+            cfg["channels"]["telegram"]["allowFrom"][0],
+            "ops@example.com"
+        );
+    }
+}
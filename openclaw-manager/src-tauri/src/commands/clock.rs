@@ -0,0 +1,279 @@
+//! 时间同步检查：系统时间偏差过大时，Provider API 签名校验和 TLS 证书校验都会失败，
+//! 报错信息往往看不出与时间有关。诊断时通过 SNTP（失败时使用 HTTP 响应的 Date 头）
+//! 测量本机时间偏差，超出阈值时给出对应平台的同步命令，可通过 apply_fix 一键同步
+use crate::commands::privacy;
+use crate::models::DiagnosticResult;
+use crate::utils::{http, platform, shell};
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use std::net::UdpSocket;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// 同步系统时间的修复项 ID
+pub const SYNC_CLOCK_FIX: &str = "sync_clock";
+/// 允许的最大偏差（超过后部分 Provider 的请求签名开始失效）
+const MAX_SKEW_MS: i64 = 60_000;
+/// SNTP 服务器（依次尝试）
+const NTP_SERVERS: &[&str] = &["ntp.aliyun.com", "pool.ntp.org", "time.windows.com"];
+/// HTTP Date 头来源（使用 http，时间偏差很大时 HTTPS 证书校验本身就会失败）
+const HTTP_SOURCES: &[&str] = &["http://www.baidu.com", "http://www.apple.com"];
+/// 同步命令使用的 NTP 服务器
+const SYNC_SERVER: &str = "ntp.aliyun.com";
+const NTP_TIMEOUT: Duration = Duration::from_secs(3);
+/// NTP 时间戳（1900 年起）与 Unix 时间戳的差值（秒）
+const NTP_UNIX_OFFSET: u64 = 2_208_988_800;
+
+/// 参考时间来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimeSource {
+    Ntp,
+    /// HTTP 响应的 Date 头（精度为秒）
+    Http,
+}
+
+/// 本机时间偏差
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClockSkew {
+    pub source: TimeSource,
+    pub server: String,
+    /// 本机时间减去参考时间（毫秒，正数表示本机时间偏快）
+    pub skew_ms: i64,
+    pub round_trip_ms: u64,
+}
+
+fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+/// SNTP 响应中的发送时间戳（Unix 毫秒）
+fn parse_ntp_response(packet: &[u8]) -> Option<i64> {
+    let bytes = packet.get(40..48)?;
+    let seconds = u32::from_be_bytes(bytes[..4].try_into().ok()?) as u64;
+    let fraction = u32::from_be_bytes(bytes[4..].try_into().ok()?) as u64;
+    let unix = seconds.checked_sub(NTP_UNIX_OFFSET)?;
+    Some((unix * 1000 + ((fraction * 1000) >> 32)) as i64)
+}
+
+/// 本机时间相对参考时间的偏差：参考时间取往返的中点
+fn skew(sent_ms: i64, received_ms: i64, server_ms: i64) -> i64 {
+    sent_ms + (received_ms - sent_ms) / 2 - server_ms
+}
+
+fn query_ntp(server: &str) -> Result<ClockSkew, String> {
+    let socket = UdpSocket::bind("0.0.0.0:0").map_err(|e| e.to_string())?;
+    socket
+        .set_read_timeout(Some(NTP_TIMEOUT))
+        .map_err(|e| e.to_string())?;
+    socket
+        .connect((server, 123))
+        .map_err(|e| format!("{}: {}", server, e))?;
+    // LI = 0，版本 3，模式 3（客户端）
+    let mut request = [0u8; 48];
+    request[0] = 0x1b;
+    let started = Instant::now();
+    let sent = now_ms();
+    socket.send(&request).map_err(|e| e.to_string())?;
+    let mut response = [0u8; 48];
+    let len = socket
+        .recv(&mut response)
+        .map_err(|e| format!("{}: {}", server, e))?;
+    let received = now_ms();
+    let server_ms = parse_ntp_response(&response[..len]).ok_or("NTP 响应格式无效")?;
+    Ok(ClockSkew {
+        source: TimeSource::Ntp,
+        server: server.to_string(),
+        skew_ms: skew(sent, received, server_ms),
+        round_trip_ms: started.elapsed().as_millis() as u64,
+    })
+}
+
+async fn query_http(url: &str) -> Result<ClockSkew, String> {
+    let started = Instant::now();
+    let sent = now_ms();
+    let response = http::client()?
+        .head(url)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    let received = now_ms();
+    let date = response
+        .headers()
+        .get(reqwest::header::DATE)
+        .and_then(|v| v.to_str().ok())
+        .ok_or("响应中没有 Date 头")?;
+    let server_ms = chrono::DateTime::parse_from_rfc2822(date)
+        .map_err(|e| format!("Date 头格式无效: {}", e))?
+        .timestamp_millis();
+    Ok(ClockSkew {
+        source: TimeSource::Http,
+        server: url.to_string(),
+        // Date 头只精确到秒，取该秒的中点
+        skew_ms: skew(sent, received, server_ms + 500),
+        round_trip_ms: started.elapsed().as_millis() as u64,
+    })
+}
+
+/// 测量本机时间偏差：依次尝试 SNTP 服务器，均失败时使用 HTTP Date 头
+pub async fn measure() -> Result<ClockSkew, String> {
+    privacy::guard("时间同步检查")?;
+    let ntp = tauri::async_runtime::spawn_blocking(|| {
+        let mut errors = Vec::new();
+        for server in NTP_SERVERS {
+            match query_ntp(server) {
+                Ok(skew) => return Ok(skew),
+                Err(e) => errors.push(e),
+            }
+        }
+        Err(errors.join("; "))
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+    let ntp_error = match ntp {
+        Ok(skew) => return Ok(skew),
+        Err(e) => e,
+    };
+    debug!("[时间同步] SNTP 不可用，改用 HTTP Date 头: {}", ntp_error);
+    let mut errors = vec![ntp_error];
+    for url in HTTP_SOURCES {
+        match query_http(url).await {
+            Ok(skew) => return Ok(skew),
+            Err(e) => errors.push(format!("{}: {}", url, e)),
+        }
+    }
+    Err(format!("无法获取参考时间: {}", errors.join("; ")))
+}
+
+/// 偏差的中文描述，如「快了 3 分 12 秒」
+fn describe(skew_ms: i64) -> String {
+    let direction = if skew_ms >= 0 { "快了" } else { "慢了" };
+    let secs = skew_ms.unsigned_abs() / 1000;
+    let amount = if secs < 60 {
+        format!("{} 秒", secs)
+    } else if secs < 3600 {
+        format!("{} 分 {} 秒", secs / 60, secs % 60)
+    } else if secs < 86400 {
+        format!("{} 小时 {} 分", secs / 3600, secs % 3600 / 60)
+    } else {
+        format!("{} 天 {} 小时", secs / 86400, secs % 86400 / 3600)
+    };
+    format!("{} {}", direction, amount)
+}
+
+/// 当前平台手动同步时间的命令
+fn sync_command(os: &str) -> String {
+    match os {
+        "windows" => "net start w32time & w32tm /resync /force".to_string(),
+        "macos" => format!("sudo sntp -sS {}", SYNC_SERVER),
+        _ => format!(
+            "sudo sntp -sS {}（或 sudo timedatectl set-ntp true）",
+            SYNC_SERVER
+        ),
+    }
+}
+
+/// 诊断项：系统时间偏差（隐私模式开启或无法获取参考时间时跳过）
+pub async fn diagnostic() -> Option<DiagnosticResult> {
+    let skew = match measure().await {
+        Ok(skew) => skew,
+        Err(e) => {
+            debug!("[时间同步] 跳过时间偏差检查: {}", e);
+            return None;
+        }
+    };
+    let passed = skew.skew_ms.abs() <= MAX_SKEW_MS;
+    info!(
+        "[时间同步] 本机时间{}（参考 {}）",
+        describe(skew.skew_ms),
+        skew.server
+    );
+    Some(DiagnosticResult {
+        name: "系统时间".to_string(),
+        passed,
+        message: format!(
+            "本机时间{}（参考: {}）",
+            describe(skew.skew_ms),
+            skew.server
+        ),
+        suggestion: if passed {
+            None
+        } else {
+            Some(format!(
+                "时间偏差过大会导致 API 签名和 HTTPS 证书校验失败，请同步系统时间: {}",
+                sync_command(platform::get_os().as_str())
+            ))
+        },
+        fix: (!passed).then(|| SYNC_CLOCK_FIX.to_string()),
+    })
+}
+
+/// 以管理员权限同步系统时间（阻塞）
+fn run_sync() -> Result<(), String> {
+    if platform::is_windows() {
+        let script = "$p = Start-Process -FilePath 'cmd.exe' -ArgumentList '/c', 'net start w32time & w32tm /resync /force' -Wait -PassThru -Verb RunAs -WindowStyle Hidden; exit $p.ExitCode";
+        shell::run_powershell_output(script).map(|_| ())
+    } else if platform::is_macos() {
+        let script = format!(
+            "do shell script \"sntp -sS {}\" with administrator privileges",
+            SYNC_SERVER
+        );
+        shell::run_command_output("osascript", &["-e", &script]).map(|_| ())
+    } else if !shell::command_exists("pkexec") {
+        Err(format!(
+            "需要管理员权限，请在终端执行: {}",
+            sync_command("linux")
+        ))
+    } else if shell::command_exists("sntp") {
+        shell::run_command_output("pkexec", &["sntp", "-sS", SYNC_SERVER]).map(|_| ())
+    } else {
+        shell::run_command_output("pkexec", &["timedatectl", "set-ntp", "true"]).map(|_| ())
+    }
+}
+
+/// 同步系统时间并重新测量，返回同步后的偏差描述
+pub async fn sync_clock() -> Result<String, String> {
+    info!("[时间同步] 同步系统时间...");
+    tauri::async_runtime::spawn_blocking(run_sync)
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| format!("同步系统时间失败: {}", e))?;
+    match measure().await {
+        Ok(skew) if skew.skew_ms.abs() <= MAX_SKEW_MS => {
+            info!("[时间同步] ✓ 已同步，本机时间{}", describe(skew.skew_ms));
+            Ok(format!("系统时间已同步（{}）", describe(skew.skew_ms)))
+        }
+        Ok(skew) => {
+            warn!("[时间同步] 同步后仍有偏差: {}", describe(skew.skew_ms));
+            Err(format!(
+                "同步命令已执行，但本机时间仍{}，请检查时区设置或手动同步",
+                describe(skew.skew_ms)
+            ))
+        }
+        Err(_) => Ok("已执行时间同步命令".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_ntp_timestamp_and_measures_skew() {
+        // 2024-01-01T00:00:00.5Z
+        let mut packet = [0u8; 48];
+        packet[40..44].copy_from_slice(&((1_704_067_200 + NTP_UNIX_OFFSET) as u32).to_be_bytes());
+        packet[44..48].copy_from_slice(&(1u32 << 31).to_be_bytes());
+        assert_eq!(parse_ntp_response(&packet), Some(1_704_067_200_500));
+        assert_eq!(parse_ntp_response(&packet[..40]), None);
+
+        assert_eq!(skew(10_000, 10_200, 10_100), 0);
+        assert_eq!(skew(100_000, 100_200, 10_100), 90_000);
+        assert_eq!(describe(90_000), "快了 1 分 30 秒");
+        assert_eq!(describe(-5_400_000), "慢了 1 小时 30 分");
+        assert_eq!(describe(-999), "慢了 0 秒");
+        assert!(sync_command("windows").contains("w32tm /resync"));
+    }
+}
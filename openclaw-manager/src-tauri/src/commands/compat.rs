@@ -0,0 +1,318 @@
+//! Manager 与 OpenClaw 的版本兼容表：每个 Manager 版本能驱动的 OpenClaw 版本范围。
+//! 优先使用远程的最新兼容表（Manager 发布后新出的 OpenClaw 版本也能及时标记），
+//! 获取失败时依次使用上次下载的副本和随 Manager 打包的兼容表。
+//! 更新前检查目标版本：超出支持范围时阻止，高于已测试版本时提示
+use crate::commands::{installer, privacy, settings};
+use crate::utils::{file, http, platform};
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use openclaw_macros::guarded;
+use tauri::command;
+
+/// 远程兼容表地址（配置了 GitHub 加速代理时通过代理访问）
+const REMOTE_URL: &str =
+    "https://raw.githubusercontent.com/gzxh-ll/linkclaw/main/openclaw-manager/compatibility.json";
+/// 随 Manager 打包的兼容表
+const BUNDLED: &str = include_str!("../../../compatibility.json");
+/// 远程兼容表在内存中的缓存时间
+const REFRESH_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+/// 获取远程兼容表失败后，多久内不再重试
+const RETRY_INTERVAL: Duration = Duration::from_secs(10 * 60);
+/// 当前 Manager 版本
+const MANAGER_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// 一个 Manager 版本范围支持的 OpenClaw 版本（均为闭区间，为空表示不限）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CompatibilityRange {
+    pub manager_min: Option<String>,
+    pub manager_max: Option<String>,
+    /// 最低支持的 OpenClaw 版本
+    pub openclaw_min: Option<String>,
+    /// 已测试的最高 OpenClaw 版本（更高的版本可以安装，但会提示）
+    pub openclaw_tested: Option<String>,
+    /// 最高支持的 OpenClaw 版本
+    pub openclaw_max: Option<String>,
+    #[serde(default)]
+    pub notes: Option<String>,
+}
+
+/// 兼容表
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CompatibilityMatrix {
+    pub schema: u32,
+    pub updated_at: String,
+    pub releases: Vec<CompatibilityRange>,
+}
+
+/// 兼容表来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatrixSource {
+    Remote,
+    /// 上次下载的副本
+    Cached,
+    Bundled,
+}
+
+/// 兼容性结论
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompatibilityStatus {
+    Compatible,
+    /// 高于已测试的版本，或兼容表中没有当前 Manager 版本
+    Untested,
+    /// 超出支持范围，阻止安装
+    Unsupported,
+}
+
+/// 某个 OpenClaw 版本的兼容性检查结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompatibilityCheck {
+    pub manager_version: String,
+    pub openclaw_version: String,
+    pub status: CompatibilityStatus,
+    pub message: Option<String>,
+    pub range: Option<CompatibilityRange>,
+    pub source: MatrixSource,
+    pub matrix_updated_at: String,
+}
+
+/// 当前使用的兼容表及其来源
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoadedMatrix {
+    pub source: MatrixSource,
+    pub matrix: CompatibilityMatrix,
+}
+
+/// 最近加载的兼容表
+static LOADED: Mutex<Option<(Instant, LoadedMatrix)>> = Mutex::new(None);
+
+fn cache_path() -> PathBuf {
+    Path::new(&platform::get_manager_data_dir()).join("compatibility.json")
+}
+
+fn parse(content: &str) -> Option<CompatibilityMatrix> {
+    serde_json::from_str::<CompatibilityMatrix>(content)
+        .ok()
+        .filter(|m| m.schema == 1 && !m.releases.is_empty())
+}
+
+fn bundled() -> CompatibilityMatrix {
+    parse(BUNDLED).expect("打包的 compatibility.json 格式无效")
+}
+
+/// a < b
+fn older(a: &str, b: &str) -> bool {
+    installer::compare_versions(a, b)
+}
+
+fn within(version: &str, min: Option<&str>, max: Option<&str>) -> bool {
+    min.is_none_or(|min| !older(version, min)) && max.is_none_or(|max| !older(max, version))
+}
+
+/// 兼容表中适用于某个 Manager 版本的范围
+fn range_for<'a>(matrix: &'a CompatibilityMatrix, manager: &str) -> Option<&'a CompatibilityRange> {
+    matrix
+        .releases
+        .iter()
+        .find(|r| within(manager, r.manager_min.as_deref(), r.manager_max.as_deref()))
+}
+
+/// 判断 OpenClaw 版本能否由当前 Manager 驱动
+fn evaluate(
+    range: Option<&CompatibilityRange>,
+    openclaw: &str,
+) -> (CompatibilityStatus, Option<String>) {
+    let Some(range) = range else {
+        return (
+            CompatibilityStatus::Untested,
+            Some(format!(
+                "兼容表中没有 Manager {} 的记录，无法确认能否驱动 OpenClaw {}",
+                MANAGER_VERSION, openclaw
+            )),
+        );
+    };
+    if !within(
+        openclaw,
+        range.openclaw_min.as_deref(),
+        range.openclaw_max.as_deref(),
+    ) {
+        let bounds = format!(
+            "{} ~ {}",
+            range.openclaw_min.as_deref().unwrap_or("不限"),
+            range.openclaw_max.as_deref().unwrap_or("不限")
+        );
+        return (
+            CompatibilityStatus::Unsupported,
+            Some(format!(
+                "当前 Manager {} 支持的 OpenClaw 版本为 {}，无法驱动 {}，请先更新 Manager",
+                MANAGER_VERSION, bounds, openclaw
+            )),
+        );
+    }
+    match range.openclaw_tested.as_deref() {
+        Some(tested) if older(tested, openclaw) => (
+            CompatibilityStatus::Untested,
+            Some(format!(
+                "OpenClaw {} 高于已测试的版本 {}，部分功能可能无法使用",
+                openclaw, tested
+            )),
+        ),
+        _ => (CompatibilityStatus::Compatible, None),
+    }
+}
+
+async fn fetch_remote() -> Result<CompatibilityMatrix, String> {
+    privacy::guard("获取兼容表")?;
+    let url = settings::load_settings()
+        .github_proxied_url(REMOTE_URL)
+        .unwrap_or_else(|| REMOTE_URL.to_string());
+    let response = http::client()?
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    if !response.status().is_success() {
+        return Err(format!("HTTP {}", response.status()));
+    }
+    let content = response.text().await.map_err(|e| e.to_string())?;
+    let matrix = parse(&content).ok_or("兼容表格式无效")?;
+    if let Err(e) = file::write_atomic(&cache_path(), content.as_bytes()) {
+        debug!("[兼容表] 保存副本失败: {}", e);
+    }
+    Ok(matrix)
+}
+
+/// 获取兼容表：远程（缓存 6 小时）> 上次下载的副本 > 打包的兼容表
+pub async fn load(refresh: bool) -> LoadedMatrix {
+    let cached = LOADED
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+        .filter(|(at, loaded)| {
+            let ttl = if loaded.source == MatrixSource::Remote {
+                REFRESH_INTERVAL
+            } else {
+                RETRY_INTERVAL
+            };
+            !refresh && at.elapsed() < ttl
+        });
+    if let Some((_, loaded)) = cached {
+        return loaded;
+    }
+    let loaded = match fetch_remote().await {
+        Ok(matrix) => {
+            info!("[兼容表] ✓ 已获取远程兼容表 ({})", matrix.updated_at);
+            LoadedMatrix {
+                source: MatrixSource::Remote,
+                matrix,
+            }
+        }
+        Err(e) => {
+            warn!("[兼容表] 获取远程兼容表失败，使用本地兼容表: {}", e);
+            let saved = std::fs::read_to_string(cache_path())
+                .ok()
+                .and_then(|content| parse(&content));
+            match saved {
+                Some(matrix) => LoadedMatrix {
+                    source: MatrixSource::Cached,
+                    matrix,
+                },
+                None => LoadedMatrix {
+                    source: MatrixSource::Bundled,
+                    matrix: bundled(),
+                },
+            }
+        }
+    };
+    *LOADED.lock().unwrap_or_else(|e| e.into_inner()) = Some((Instant::now(), loaded.clone()));
+    loaded
+}
+
+/// 检查 OpenClaw 版本与当前 Manager 的兼容性
+pub async fn check(openclaw_version: &str) -> CompatibilityCheck {
+    let LoadedMatrix { source, matrix } = load(false).await;
+    let range = range_for(&matrix, MANAGER_VERSION).cloned();
+    let (status, message) = evaluate(range.as_ref(), openclaw_version);
+    if let Some(message) = &message {
+        warn!("[兼容表] {}", message);
+    }
+    CompatibilityCheck {
+        manager_version: MANAGER_VERSION.to_string(),
+        openclaw_version: openclaw_version.to_string(),
+        status,
+        message,
+        range,
+        source,
+        matrix_updated_at: matrix.updated_at,
+    }
+}
+
+/// 安装前检查：不支持的版本返回错误
+pub async fn ensure_supported(openclaw_version: &str) -> Result<CompatibilityCheck, String> {
+    let result = check(openclaw_version).await;
+    match (result.status, &result.message) {
+        (CompatibilityStatus::Unsupported, Some(message)) => Err(message.clone()),
+        _ => Ok(result),
+    }
+}
+
+/// 获取兼容表（`refresh` 为 true 时重新获取远程兼容表）
+#[guarded]
+#[command]
+pub async fn get_compatibility_matrix(refresh: Option<bool>) -> Result<LoadedMatrix, String> {
+    Ok(load(refresh.unwrap_or(false)).await)
+}
+
+/// 检查某个 OpenClaw 版本（为空时检查已安装的版本）能否由当前 Manager 驱动
+#[guarded]
+#[command]
+pub async fn check_openclaw_compatibility(
+    version: Option<String>,
+) -> Result<CompatibilityCheck, String> {
+    let version = match version {
+        Some(version) => version,
+        None => installer::cached_openclaw_version()
+            .and_then(|v| v.split_whitespace().last().map(String::from))
+            .ok_or("未安装 OpenClaw")?,
+    };
+    Ok(check(version.trim_start_matches('v')).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundled_matrix_covers_this_manager_and_enforces_bounds() {
+        let matrix = bundled();
+        assert!(range_for(&matrix, MANAGER_VERSION).is_some());
+
+        let range = CompatibilityRange {
+            manager_min: Some("0.0.1".to_string()),
+            manager_max: Some("0.1.0".to_string()),
+            openclaw_min: Some("2026.1.0".to_string()),
+            openclaw_tested: Some("2026.3.0".to_string()),
+            openclaw_max: Some("2026.12.99".to_string()),
+            notes: None,
+        };
+        let status = |v| evaluate(Some(&range), v).0;
+        assert_eq!(status("2026.2.14"), CompatibilityStatus::Compatible);
+        assert_eq!(status("2026.3.0"), CompatibilityStatus::Compatible);
+        assert_eq!(status("2026.4.1"), CompatibilityStatus::Untested);
+        assert_eq!(status("2025.12.1"), CompatibilityStatus::Unsupported);
+        assert_eq!(status("2027.1.0"), CompatibilityStatus::Unsupported);
+        assert_eq!(evaluate(None, "2026.2.0").0, CompatibilityStatus::Untested);
+
+        let custom = CompatibilityMatrix {
+            schema: 1,
+            updated_at: "2026-10-01".to_string(),
+            releases: vec![range],
+        };
+        assert!(range_for(&custom, "0.0.5").is_some());
+        assert!(range_for(&custom, "0.2.0").is_none());
+    }
+}
@@ -3,42 +3,77 @@ use crate::models::{
     ModelConfig, ModelCostConfig, OfficialProvider, OpenClawConfig,
     ProviderConfig, SuggestedModel,
 };
-use crate::utils::{file, platform, shell};
+use crate::commands::capabilities::{self, Feature};
+use crate::commands::{config_conflict, policy, secret_handles, watcher};
+use crate::utils::{file, platform, shell, time};
 use log::{debug, error, info, warn};
 use serde_json::{json, Value};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use openclaw_macros::guarded;
 use tauri::command;
 
 /// 获取 openclaw.json 配置
-fn load_openclaw_config() -> Result<Value, String> {
+pub fn load_openclaw_config() -> Result<Value, String> {
+    let content = read_config_content()?;
+    
+    if content.trim().is_empty() {
+        let config = json!({});
+        config_conflict::record_read(&content, &config);
+        return Ok(config);
+    }
+    
+    let config: Value =
+        serde_json::from_str(&content).map_err(|e| format!("解析配置文件失败: {}", e))?;
+    config_conflict::record_read(&content, &config);
+    Ok(config)
+}
+
+/// 读取 openclaw.json 原始内容（文件不存在时返回空字符串）
+pub fn read_config_content() -> Result<String, String> {
     let config_path = platform::get_config_file_path();
     
     if !file::file_exists(&config_path) {
-        return Ok(json!({}));
+        return Ok(String::new());
     }
     
-    let content =
-        file::read_file(&config_path).map_err(|e| format!("读取配置文件失败: {}", e))?;
-    
-    serde_json::from_str(&content).map_err(|e| format!("解析配置文件失败: {}", e))
+    file::read_file(&config_path).map_err(|e| format!("读取配置文件失败: {}", e))
 }
 
 /// 保存 openclaw.json 配置
+/// 上次读取后文件被外部修改时返回冲突错误，不会覆盖外部修改
 fn save_openclaw_config(config: &Value) -> Result<(), String> {
+    config_conflict::check_write(&read_config_content()?, config)?;
+    write_openclaw_config(config)
+}
+
+/// 直接写入 openclaw.json 配置（不做冲突检测），并更新冲突检测基准
+pub fn write_openclaw_config(config: &Value) -> Result<(), String> {
     let config_path = platform::get_config_file_path();
     
     let content =
         serde_json::to_string_pretty(config).map_err(|e| format!("序列化配置失败: {}", e))?;
     
-    file::write_file(&config_path, &content).map_err(|e| format!("写入配置文件失败: {}", e))
+    watcher::note_internal_write(&config_path);
+    file::write_file(&config_path, &content).map_err(|e| format!("写入配置文件失败: {}", e))?;
+    config_conflict::record_read(&content, config);
+    Ok(())
+}
+
+/// 当前保存的配置（用于把句柄换回密钥，不更新冲突检测基准）
+fn saved_config() -> Option<Value> {
+    serde_json::from_str(&read_config_content().ok()?).ok()
 }
 
-/// 获取完整配置
+/// 获取完整配置（密钥字段替换为句柄）
+#[guarded]
 #[command]
 pub async fn get_config() -> Result<Value, String> {
     info!("[获取配置] 读取 openclaw.json 配置...");
-    let result = load_openclaw_config();
+    let result = load_openclaw_config().map(|mut config| {
+        secret_handles::mask_value(&mut config, "");
+        config
+    });
     match &result {
         Ok(_) => info!("[获取配置] ✓ 配置读取成功"),
         Err(e) => error!("[获取配置] ✗ 配置读取失败: {}", e),
@@ -46,10 +81,12 @@ pub async fn get_config() -> Result<Value, String> {
     result
 }
 
-/// 保存配置
+/// 保存配置（密钥句柄换回真实值后写入）
+#[guarded]
 #[command]
-pub async fn save_config(config: Value) -> Result<String, String> {
+pub async fn save_config(mut config: Value) -> Result<String, String> {
     info!("[保存配置] 保存 openclaw.json 配置...");
+    secret_handles::unmask_value(&mut config, "", saved_config().as_ref())?;
     debug!(
         "[保存配置] 配置内容: {}",
         serde_json::to_string_pretty(&config).unwrap_or_default()
@@ -75,21 +112,29 @@ fn copy_dir_all(src: &std::path::Path, dst: &std::path::Path) -> std::io::Result
         if ty.is_dir() {
             copy_dir_all(&entry.path(), &dst.join(entry.file_name()))?;
         } else {
-            std::fs::copy(entry.path(), dst.join(entry.file_name()))?;
+            file::copy_atomic(&entry.path(), &dst.join(entry.file_name()))?;
         }
     }
     Ok(())
 }
 
-fn backup_openclaw_dir(home: &std::path::Path) -> Result<Option<std::path::PathBuf>, String> {
-    let openclaw_dir = home.join(".openclaw");
+/// 备份配置目录到同级的 `<目录名>_backups/<时间>`（默认档案为 ~/.openclaw_backups）
+fn backup_openclaw_dir(
+    openclaw_dir: &std::path::Path,
+) -> Result<Option<std::path::PathBuf>, String> {
     if !openclaw_dir.exists() {
         warn!("[配置备份] 配置目录不存在: {:?}", openclaw_dir);
         return Ok(None);
     }
 
-    let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S").to_string();
-    let backup_dir = home.join(".openclaw_backups").join(&timestamp);
+    let timestamp = time::now().format("%Y%m%d_%H%M%S").to_string();
+    let dir_name = openclaw_dir
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| ".openclaw".to_string());
+    let backup_dir = openclaw_dir
+        .with_file_name(format!("{}_backups", dir_name))
+        .join(&timestamp);
 
     info!("[配置备份] 备份目标: {:?}", backup_dir);
 
@@ -98,7 +143,7 @@ fn backup_openclaw_dir(home: &std::path::Path) -> Result<Option<std::path::PathB
         return Err(format!("创建备份目录失败: {}", e));
     }
 
-    if let Err(e) = copy_dir_all(&openclaw_dir, &backup_dir) {
+    if let Err(e) = copy_dir_all(openclaw_dir, &backup_dir) {
         error!("[配置备份] 备份失败: {}", e);
         return Err(format!("备份失败: {}", e));
     }
@@ -107,13 +152,14 @@ fn backup_openclaw_dir(home: &std::path::Path) -> Result<Option<std::path::PathB
 }
 
 /// 备份用户配置
+#[guarded]
 #[command]
 pub async fn backup_user_config() -> Result<String, String> {
     info!("[配置备份] 开始备份用户配置...");
-    
-    // 获取 home 目录
-    let home = dirs::home_dir().ok_or("无法获取用户主目录")?;
-    match backup_openclaw_dir(&home)? {
+
+    // 当前配置档案的目录
+    let config_dir = std::path::PathBuf::from(platform::get_config_dir());
+    match backup_openclaw_dir(&config_dir)? {
         Some(backup_dir) => {
             info!("[配置备份] ✓ 备份完成");
             Ok(format!("配置已备份至: {:?}", backup_dir))
@@ -145,7 +191,7 @@ mod tests {
     #[test]
     fn backup_skips_when_openclaw_dir_missing() {
         let home = make_temp_dir("openclaw_home_missing");
-        let out = backup_openclaw_dir(&home).unwrap();
+        let out = backup_openclaw_dir(&home.join(".openclaw")).unwrap();
         assert!(out.is_none());
         let _ = std::fs::remove_dir_all(&home);
     }
@@ -157,7 +203,8 @@ mod tests {
         std::fs::create_dir_all(&openclaw_dir).unwrap();
         std::fs::write(openclaw_dir.join("test.txt"), "hello").unwrap();
 
-        let backup_dir = backup_openclaw_dir(&home).unwrap().unwrap();
+        let backup_dir = backup_openclaw_dir(&openclaw_dir).unwrap().unwrap();
+        assert!(backup_dir.starts_with(home.join(".openclaw_backups")));
         assert!(backup_dir.exists());
         assert!(backup_dir.join("test.txt").exists());
 
@@ -166,6 +213,7 @@ mod tests {
 }
 
 /// 获取环境变量值
+#[guarded]
 #[command]
 pub async fn get_env_value(key: String) -> Result<Option<String>, String> {
     info!("[获取环境变量] 读取环境变量: {}", key);
@@ -179,16 +227,30 @@ pub async fn get_env_value(key: String) -> Result<Option<String>, String> {
         ),
         None => debug!("[获取环境变量] {} 不存在", key),
     }
-    Ok(value)
+    // 密钥只返回句柄
+    Ok(value.map(|v| {
+        if secret_handles::is_secret_key(&key) {
+            secret_handles::handle_for(&format!("env:{}", key))
+        } else {
+            v
+        }
+    }))
 }
 
 /// 保存环境变量值
+#[guarded]
 #[command]
 pub async fn save_env_value(key: String, value: String) -> Result<String, String> {
     info!("[保存环境变量] 保存环境变量: {}", key);
     let env_path = platform::get_env_file_path();
     debug!("[保存环境变量] 环境文件路径: {}", env_path);
-    
+    let value = secret_handles::resolve(
+        &value,
+        &format!("env:{}", key),
+        file::read_env_value(&env_path, &key).as_deref(),
+    )?;
+
+    watcher::note_internal_write(&env_path);
     match file::set_env_value(&env_path, &key, &value) {
         Ok(_) => {
             info!("[保存环境变量] ✓ 环境变量 {} 保存成功", key);
@@ -221,13 +283,12 @@ fn generate_token() -> String {
     )
 }
 
-/// 获取或生成 Gateway Token
-#[command]
-pub async fn get_or_create_gateway_token() -> Result<String, String> {
+/// 获取或生成 Gateway Token（返回真实值，只在后端使用）
+fn ensure_gateway_token() -> Result<String, String> {
     info!("[Gateway Token] 获取或创建 Gateway Token...");
-    
+
     let mut config = load_openclaw_config()?;
-    
+
     // 检查是否已有 token
     if let Some(token) = config
         .pointer("/gateway/auth/token")
@@ -238,11 +299,11 @@ pub async fn get_or_create_gateway_token() -> Result<String, String> {
             return Ok(token.to_string());
         }
     }
-    
+
     // 生成新 token
     let new_token = generate_token();
     info!("[Gateway Token] 生成新 Token: {}...", &new_token[..8]);
-    
+
     // 确保路径存在
     if config.get("gateway").is_none() {
         config["gateway"] = json!({});
@@ -250,34 +311,45 @@ pub async fn get_or_create_gateway_token() -> Result<String, String> {
     if config["gateway"].get("auth").is_none() {
         config["gateway"]["auth"] = json!({});
     }
-    
+
     // 设置 token 和 mode
     config["gateway"]["auth"]["token"] = json!(new_token);
     config["gateway"]["auth"]["mode"] = json!("token");
     config["gateway"]["mode"] = json!("local");
-    
+
     // 保存配置
     save_openclaw_config(&config)?;
-    
+
     info!("[Gateway Token] ✓ Token 已保存到配置");
     Ok(new_token)
 }
 
-/// 获取 Dashboard URL（带 token）
+/// 获取或生成 Gateway Token（返回句柄）
+#[guarded]
 #[command]
-pub async fn get_dashboard_url() -> Result<String, String> {
-    info!("[Dashboard URL] 获取 Dashboard URL...");
-    
-    let token = get_or_create_gateway_token().await?;
+pub async fn get_or_create_gateway_token() -> Result<String, String> {
+    ensure_gateway_token()?;
+    Ok(secret_handles::handle_for("gateway/auth/token"))
+}
+
+/// 用默认浏览器打开 Dashboard（带 token 的地址不经过前端）
+#[guarded]
+#[command]
+pub async fn open_dashboard() -> Result<(), String> {
+    info!("[Dashboard] 打开 Dashboard...");
+
+    let token = ensure_gateway_token()?;
     let url = format!("http://localhost:18789?token={}", token);
-    
-    info!("[Dashboard URL] ✓ URL: {}...", &url[..50.min(url.len())]);
-    Ok(url)
+    open::that(&url).map_err(|e| format!("打开浏览器失败: {}", e))?;
+
+    info!("[Dashboard] ✓ 已在浏览器中打开");
+    Ok(())
 }
 
 // ============ AI 配置相关命令 ============
 
 /// 获取官方 Provider 列表（预设模板）
+#[guarded]
 #[command]
 pub async fn get_official_providers() -> Result<Vec<OfficialProvider>, String> {
     info!("[官方 Provider] 获取官方 Provider 预设列表...");
@@ -523,6 +595,7 @@ pub async fn get_official_providers() -> Result<Vec<OfficialProvider>, String> {
 }
 
 /// 获取 AI 配置概览
+#[guarded]
 #[command]
 pub async fn get_ai_config() -> Result<AIConfigOverview, String> {
     info!("[AI 配置] 获取 AI 配置概览...");
@@ -556,10 +629,10 @@ pub async fn get_ai_config() -> Result<AIConfigOverview, String> {
 
     if let Some(providers) = providers_value.and_then(|v| v.as_object()) {
         info!("[AI 配置] 找到 {} 个 Provider", providers.len());
-        
+    
         for (provider_name, provider_config) in providers {
             info!("[AI 配置] 解析 Provider: {}", provider_name);
-            
+        
             let base_url = provider_config
                 .get("baseUrl")
                 .and_then(|v| v.as_str())
@@ -582,7 +655,7 @@ pub async fn get_ai_config() -> Result<AIConfigOverview, String> {
             // 解析模型列表
             let models_array = provider_config.get("models").and_then(|v| v.as_array());
             info!("[AI 配置] Provider {} 的 models 数组: {:?}", provider_name, models_array.map(|a| a.len()));
-            
+        
             let models: Vec<ConfiguredModel> = models_array
                 .map(|arr| {
                     arr.iter()
@@ -647,6 +720,7 @@ pub async fn get_ai_config() -> Result<AIConfigOverview, String> {
 }
 
 /// 添加或更新 Provider
+#[guarded]
 #[command]
 pub async fn save_provider(
     provider_name: String,
@@ -729,7 +803,12 @@ pub async fn save_provider(
     // 处理 API Key：如果传入了新的非空 key，使用新的；否则保留原有的
     if let Some(key) = api_key {
         if !key.is_empty() {
-            // 使用新传入的 API Key
+            // 使用新传入的 API Key（句柄换回真实值）
+            let location = format!("models/providers/{}/apiKey", provider_name);
+            let existing = config
+                .pointer(&format!("/{}", location))
+                .and_then(|v| v.as_str());
+            let key = secret_handles::resolve(&key, &location, existing)?;
             provider_config["apiKey"] = json!(key);
             info!("[保存 Provider] 使用新的 API Key");
         } else {
@@ -776,8 +855,13 @@ pub async fn save_provider(
 }
 
 /// 删除 Provider
+#[guarded]
 #[command]
-pub async fn delete_provider(provider_name: String) -> Result<String, String> {
+pub async fn delete_provider(
+    provider_name: String,
+    confirm_token: Option<String>,
+) -> Result<String, String> {
+    policy::require_confirmation("delete_provider", confirm_token.as_deref())?;
     info!("[删除 Provider] 删除 Provider: {}", provider_name);
 
     let mut config = load_openclaw_config()?;
@@ -823,6 +907,7 @@ pub async fn delete_provider(provider_name: String) -> Result<String, String> {
 }
 
 /// 设置主模型
+#[guarded]
 #[command]
 pub async fn set_primary_model(model_id: String) -> Result<String, String> {
     info!("[设置主模型] 设置主模型: {}", model_id);
@@ -850,6 +935,7 @@ pub async fn set_primary_model(model_id: String) -> Result<String, String> {
 }
 
 /// 添加模型到可用列表
+#[guarded]
 #[command]
 pub async fn add_available_model(model_id: String) -> Result<String, String> {
     info!("[添加模型] 添加模型到可用列表: {}", model_id);
@@ -877,6 +963,7 @@ pub async fn add_available_model(model_id: String) -> Result<String, String> {
 }
 
 /// 从可用列表移除模型
+#[guarded]
 #[command]
 pub async fn remove_available_model(model_id: String) -> Result<String, String> {
     info!("[移除模型] 从可用列表移除模型: {}", model_id);
@@ -899,6 +986,7 @@ pub async fn remove_available_model(model_id: String) -> Result<String, String>
 // ============ 旧版兼容 ============
 
 /// 获取所有支持的 AI Provider（旧版兼容）
+#[guarded]
 #[command]
 pub async fn get_ai_providers() -> Result<Vec<crate::models::AIProviderOption>, String> {
     info!("[AI Provider] 获取支持的 AI Provider 列表（旧版）...");
@@ -931,17 +1019,18 @@ pub async fn get_ai_providers() -> Result<Vec<crate::models::AIProviderOption>,
 // ============ 渠道配置 ============
 
 /// 获取渠道配置 - 从 openclaw.json 和 env 文件读取
+#[guarded]
 #[command]
 pub async fn get_channels_config() -> Result<Vec<ChannelConfig>, String> {
     info!("[渠道配置] 获取渠道配置列表...");
-    
+
     let config = load_openclaw_config()?;
     let channels_obj = config.get("channels").cloned().unwrap_or(json!({}));
     let env_path = platform::get_env_file_path();
     debug!("[渠道配置] 环境文件路径: {}", env_path);
-    
+
     let mut channels = Vec::new();
-    
+
     // 支持的渠道类型列表及其测试字段
     let channel_types = vec![
         ("telegram", "telegram", vec!["userId"]),
@@ -953,15 +1042,15 @@ pub async fn get_channels_config() -> Result<Vec<ChannelConfig>, String> {
         ("wechat", "wechat", vec![]),
         ("dingtalk", "dingtalk", vec![]),
     ];
-    
+
     for (channel_id, channel_type, test_fields) in channel_types {
         let channel_config = channels_obj.get(channel_id);
-        
+    
         let enabled = channel_config
             .and_then(|c| c.get("enabled"))
             .and_then(|v| v.as_bool())
             .unwrap_or(false);
-        
+    
         // 将渠道配置转换为 HashMap
         let mut config_map: HashMap<String, Value> = if let Some(cfg) = channel_config {
             if let Some(obj) = cfg.as_object() {
@@ -975,7 +1064,18 @@ pub async fn get_channels_config() -> Result<Vec<ChannelConfig>, String> {
         } else {
             HashMap::new()
         };
-        
+    
+        // 密钥只返回句柄
+        for (key, value) in config_map.iter_mut() {
+            let location = format!("channels/{}/{}", channel_id, key);
+            match value.as_str() {
+                Some(v) if !v.is_empty() && secret_handles::is_secret_key(key) => {
+                    *value = json!(secret_handles::handle_for(&location));
+                }
+                _ => secret_handles::mask_value(value, &location),
+            }
+        }
+    
         // 从 env 文件读取测试字段
         for field in test_fields {
             let env_key = format!(
@@ -987,10 +1087,10 @@ pub async fn get_channels_config() -> Result<Vec<ChannelConfig>, String> {
                 config_map.insert(field.to_string(), json!(value));
             }
         }
-        
+    
         // 判断是否已配置（有任何非空配置项）
         let has_config = !config_map.is_empty() || enabled;
-        
+    
         channels.push(ChannelConfig {
             id: channel_id.to_string(),
             channel_type: channel_type.to_string(),
@@ -998,7 +1098,7 @@ pub async fn get_channels_config() -> Result<Vec<ChannelConfig>, String> {
             config: config_map,
         });
     }
-    
+
     info!("[渠道配置] ✓ 返回 {} 个渠道配置", channels.len());
     for ch in &channels {
         debug!("[渠道配置] - {}: enabled={}", ch.id, ch.enabled);
@@ -1007,22 +1107,23 @@ pub async fn get_channels_config() -> Result<Vec<ChannelConfig>, String> {
 }
 
 /// 保存渠道配置 - 保存到 openclaw.json
+#[guarded]
 #[command]
 pub async fn save_channel_config(channel: ChannelConfig) -> Result<String, String> {
     info!(
         "[保存渠道配置] 保存渠道配置: {} ({})",
         channel.id, channel.channel_type
     );
-    
+
     let mut config = load_openclaw_config()?;
     let env_path = platform::get_env_file_path();
     debug!("[保存渠道配置] 环境文件路径: {}", env_path);
-    
+
     // 确保 channels 对象存在
     if config.get("channels").is_none() {
         config["channels"] = json!({});
     }
-    
+
     // 确保 plugins 对象存在
     if config.get("plugins").is_none() {
         config["plugins"] = json!({
@@ -1036,15 +1137,15 @@ pub async fn save_channel_config(channel: ChannelConfig) -> Result<String, Strin
     if config["plugins"].get("entries").is_none() {
         config["plugins"]["entries"] = json!({});
     }
-    
+
     // 这些字段只用于测试，不保存到 openclaw.json，而是保存到 env 文件
     let test_only_fields = vec!["userId", "testChatId", "testChannelId"];
-    
+
     // 构建渠道配置
     let mut channel_obj = json!({
         "enabled": true
     });
-    
+
     // 添加渠道特定配置
     for (key, value) in &channel.config {
         if test_only_fields.contains(&key.as_str()) {
@@ -1055,17 +1156,24 @@ pub async fn save_channel_config(channel: ChannelConfig) -> Result<String, Strin
                 key.to_uppercase()
             );
             if let Some(val_str) = value.as_str() {
+                watcher::note_internal_write(&env_path);
                 let _ = file::set_env_value(&env_path, &env_key, val_str);
             }
         } else {
-            // 保存到 openclaw.json
-            channel_obj[key] = value.clone();
+            // 保存到 openclaw.json（密钥句柄换回真实值）
+            let mut value = value.clone();
+            secret_handles::unmask_value(
+                &mut value,
+                &format!("channels/{}/{}", channel.id, key),
+                config.pointer(&format!("/channels/{}/{}", channel.id, key)),
+            )?;
+            channel_obj[key] = value;
         }
     }
-    
+
     // 更新 channels 配置
     config["channels"][&channel.id] = channel_obj;
-    
+
     // 更新 plugins.allow 数组 - 确保渠道在白名单中
     if let Some(allow_arr) = config["plugins"]["allow"].as_array_mut() {
         let channel_id_val = json!(&channel.id);
@@ -1073,12 +1181,12 @@ pub async fn save_channel_config(channel: ChannelConfig) -> Result<String, Strin
             allow_arr.push(channel_id_val);
         }
     }
-    
+
     // 更新 plugins.entries - 确保插件已启用
     config["plugins"]["entries"][&channel.id] = json!({
         "enabled": true
     });
-    
+
     // 保存配置
     info!("[保存渠道配置] 写入配置文件...");
     match save_openclaw_config(&config) {
@@ -1097,31 +1205,36 @@ pub async fn save_channel_config(channel: ChannelConfig) -> Result<String, Strin
 }
 
 /// 清空渠道配置 - 从 openclaw.json 中删除指定渠道的配置
+#[guarded]
 #[command]
-pub async fn clear_channel_config(channel_id: String) -> Result<String, String> {
+pub async fn clear_channel_config(
+    channel_id: String,
+    confirm_token: Option<String>,
+) -> Result<String, String> {
+    policy::require_confirmation("clear_channel_config", confirm_token.as_deref())?;
     info!("[清空渠道配置] 清空渠道配置: {}", channel_id);
-    
+
     let mut config = load_openclaw_config()?;
     let env_path = platform::get_env_file_path();
-    
+
     // 从 channels 对象中删除该渠道
     if let Some(channels) = config.get_mut("channels").and_then(|v| v.as_object_mut()) {
         channels.remove(&channel_id);
         info!("[清空渠道配置] 已从 channels 中删除: {}", channel_id);
     }
-    
+
     // 从 plugins.allow 数组中删除
     if let Some(allow_arr) = config.pointer_mut("/plugins/allow").and_then(|v| v.as_array_mut()) {
         allow_arr.retain(|v| v.as_str() != Some(&channel_id));
         info!("[清空渠道配置] 已从 plugins.allow 中删除: {}", channel_id);
     }
-    
+
     // 从 plugins.entries 中删除
     if let Some(entries) = config.pointer_mut("/plugins/entries").and_then(|v| v.as_object_mut()) {
         entries.remove(&channel_id);
         info!("[清空渠道配置] 已从 plugins.entries 中删除: {}", channel_id);
     }
-    
+
     // 清除相关的环境变量
     let env_prefixes = vec![
         format!("OPENCLAW_{}_USERID", channel_id.to_uppercase()),
@@ -1129,9 +1242,10 @@ pub async fn clear_channel_config(channel_id: String) -> Result<String, String>
         format!("OPENCLAW_{}_TESTCHANNELID", channel_id.to_uppercase()),
     ];
     for env_key in env_prefixes {
+        watcher::note_internal_write(&env_path);
         let _ = file::remove_env_value(&env_path, &env_key);
     }
-    
+
     // 保存配置
     match save_openclaw_config(&config) {
         Ok(_) => {
@@ -1156,24 +1270,25 @@ pub struct FeishuPluginStatus {
 }
 
 /// 检查飞书插件是否已安装
+#[guarded]
 #[command]
 pub async fn check_feishu_plugin() -> Result<FeishuPluginStatus, String> {
     info!("[飞书插件] 检查飞书插件安装状态...");
-    
+
     // 执行 openclaw plugins list 命令
     match shell::run_openclaw(&["plugins", "list"]) {
         Ok(output) => {
             debug!("[飞书插件] plugins list 输出: {}", output);
-            
+        
             // 查找包含 feishu 的行（不区分大小写）
             let lines: Vec<&str> = output.lines().collect();
             let feishu_line = lines.iter().find(|line| {
                 line.to_lowercase().contains("feishu")
             });
-            
+        
             if let Some(line) = feishu_line {
                 info!("[飞书插件] ✓ 飞书插件已安装: {}", line);
-                
+            
                 // 尝试解析版本号（通常格式为 "name@version" 或 "name version"）
                 let version = if line.contains('@') {
                     line.split('@').last().map(|s| s.trim().to_string())
@@ -1184,7 +1299,7 @@ pub async fn check_feishu_plugin() -> Result<FeishuPluginStatus, String> {
                         .find(|p| p.chars().next().map(|c| c.is_ascii_digit()).unwrap_or(false))
                         .map(|s| s.to_string())
                 };
-                
+            
                 Ok(FeishuPluginStatus {
                     installed: true,
                     version,
@@ -1212,24 +1327,26 @@ pub async fn check_feishu_plugin() -> Result<FeishuPluginStatus, String> {
 }
 
 /// 安装飞书插件
+#[guarded]
 #[command]
 pub async fn install_feishu_plugin() -> Result<String, String> {
     info!("[飞书插件] 开始安装飞书插件...");
-    
+
     // 先检查是否已安装
     let status = check_feishu_plugin().await?;
     if status.installed {
         info!("[飞书插件] 飞书插件已安装，跳过");
         return Ok(format!("飞书插件已安装: {}", status.plugin_name.unwrap_or_default()));
     }
-    
+
     // 安装飞书插件
+    capabilities::require(Feature::Plugins)?;
     // 注意：使用 @m1heng-clawd/feishu 包名
     info!("[飞书插件] 执行 openclaw plugins install @m1heng-clawd/feishu ...");
     match shell::run_openclaw(&["plugins", "install", "@m1heng-clawd/feishu"]) {
         Ok(output) => {
             info!("[飞书插件] 安装输出: {}", output);
-            
+        
             // 验证安装结果
             let verify_status = check_feishu_plugin().await?;
             if verify_status.installed {
@@ -0,0 +1,240 @@
+use crate::commands::{config, policy};
+use crate::utils::time;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use openclaw_macros::guarded;
+use tauri::command;
+
+/// 写入冲突错误前缀，前端据此弹出合并对话框
+pub const CONFLICT_ERROR_PREFIX: &str = "CONFIG_CONFLICT";
+
+/// 上次读取 / 写入时的配置文件状态（冲突检测的基准）
+struct Snapshot {
+    hash: String,
+    value: Value,
+}
+
+static LAST_READ: Mutex<Option<Snapshot>> = Mutex::new(None);
+static PENDING: Mutex<Option<ConfigConflict>> = Mutex::new(None);
+
+/// 三方差异中的一项（JSON Pointer 路径，null 表示不存在）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConfigDiffEntry {
+    pub path: String,
+    pub base: Option<Value>,
+    /// Manager 要写入的值
+    pub ours: Option<Value>,
+    /// 外部（如 openclaw CLI）修改后的值
+    pub theirs: Option<Value>,
+    /// 双方都修改了且结果不同
+    pub conflict: bool,
+}
+
+/// 配置写入冲突
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigConflict {
+    pub detected_at: String,
+    pub diff: Vec<ConfigDiffEntry>,
+    pub base: Value,
+    pub ours: Value,
+    pub theirs: Value,
+    #[serde(skip)]
+    theirs_hash: String,
+}
+
+/// 冲突处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MergeStrategy {
+    /// 使用 Manager 的版本覆盖外部修改
+    Ours,
+    /// 保留外部修改，放弃 Manager 的这次写入
+    Theirs,
+    /// 使用前端手动合并后的配置
+    Manual,
+}
+
+/// 配置内容的 SHA-256
+pub fn content_hash(content: &str) -> String {
+    hex::encode(Sha256::digest(content.as_bytes()))
+}
+
+/// 记录读取到的配置内容（文件不存在时 content 为空）
+pub fn record_read(content: &str, value: &Value) {
+    if let Ok(mut last) = LAST_READ.lock() {
+        *last = Some(Snapshot {
+            hash: content_hash(content),
+            value: value.clone(),
+        });
+    }
+}
+
+/// 把配置展开为 JSON Pointer -> 叶子值（数组整体视为一个值）
+fn flatten(value: &Value, prefix: &str, out: &mut BTreeMap<String, Value>) {
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            for (key, child) in map {
+                let escaped = key.replace('~', "~0").replace('/', "~1");
+                flatten(child, &format!("{}/{}", prefix, escaped), out);
+            }
+        }
+        _ => {
+            out.insert(prefix.to_string(), value.clone());
+        }
+    }
+}
+
+/// 计算 base / ours / theirs 三方差异，只返回至少一方有修改的路径
+pub fn three_way_diff(base: &Value, ours: &Value, theirs: &Value) -> Vec<ConfigDiffEntry> {
+    let (mut b, mut o, mut t) = (BTreeMap::new(), BTreeMap::new(), BTreeMap::new());
+    flatten(base, "", &mut b);
+    flatten(ours, "", &mut o);
+    flatten(theirs, "", &mut t);
+
+    let mut paths: Vec<&String> = b.keys().chain(o.keys()).chain(t.keys()).collect();
+    paths.sort();
+    paths.dedup();
+
+    paths
+        .into_iter()
+        .filter_map(|path| {
+            let (base, ours, theirs) = (b.get(path), o.get(path), t.get(path));
+            let ours_changed = ours != base;
+            let theirs_changed = theirs != base;
+            if !ours_changed && !theirs_changed {
+                return None;
+            }
+            Some(ConfigDiffEntry {
+                path: path.clone(),
+                base: base.cloned(),
+                ours: ours.cloned(),
+                theirs: theirs.cloned(),
+                conflict: ours_changed && theirs_changed && ours != theirs,
+            })
+        })
+        .collect()
+}
+
+/// 写入前检查配置文件是否在上次读取后被外部修改
+/// 被修改时记录冲突并返回 `CONFIG_CONFLICT` 错误，而不是直接覆盖
+pub fn check_write(current_content: &str, ours: &Value) -> Result<(), String> {
+    let current_hash = content_hash(current_content);
+    let base = match LAST_READ.lock() {
+        Ok(last) => match last.as_ref() {
+            Some(snapshot) if snapshot.hash != current_hash => snapshot.value.clone(),
+            // 从未读取过或文件未变化
+            _ => return Ok(()),
+        },
+        Err(_) => return Ok(()),
+    };
+
+    let theirs: Value = if current_content.trim().is_empty() {
+        Value::Object(Default::default())
+    } else {
+        serde_json::from_str(current_content).unwrap_or(Value::Null)
+    };
+    if &theirs == ours {
+        return Ok(());
+    }
+
+    let diff = three_way_diff(&base, ours, &theirs);
+    let conflicts = diff.iter().filter(|d| d.conflict).count();
+    warn!(
+        "[配置冲突] 配置文件已被外部修改，{} 处差异，其中 {} 处冲突",
+        diff.len(),
+        conflicts
+    );
+    if let Ok(mut pending) = PENDING.lock() {
+        *pending = Some(ConfigConflict {
+            detected_at: time::now_rfc3339(),
+            diff,
+            base,
+            ours: ours.clone(),
+            theirs,
+            theirs_hash: current_hash,
+        });
+    }
+    Err(format!(
+        "{}: 配置文件已被外部修改（如 openclaw CLI），为避免覆盖已取消保存，请选择合并方式",
+        CONFLICT_ERROR_PREFIX
+    ))
+}
+
+/// 获取待处理的配置冲突
+#[guarded]
+#[command]
+pub async fn get_config_conflict() -> Result<Option<ConfigConflict>, String> {
+    Ok(PENDING.lock().map_err(|e| e.to_string())?.clone())
+}
+
+/// 处理配置冲突：ours 覆盖外部修改 / theirs 保留外部修改 / manual 写入手动合并的配置
+#[guarded]
+#[command]
+pub async fn resolve_config_conflict(
+    strategy: MergeStrategy,
+    merged: Option<Value>,
+    confirm_token: Option<String>,
+) -> Result<String, String> {
+    policy::require_confirmation("resolve_config_conflict", confirm_token.as_deref())?;
+    let conflict = PENDING
+        .lock()
+        .map_err(|e| e.to_string())?
+        .clone()
+        .ok_or("没有待处理的配置冲突")?;
+    info!("[配置冲突] 处理冲突，方式: {:?}", strategy);
+
+    let resolved = match strategy {
+        MergeStrategy::Ours => Some(conflict.ours),
+        MergeStrategy::Theirs => None,
+        MergeStrategy::Manual => Some(merged.ok_or("手动合并需要提供合并后的配置")?),
+    };
+
+    // 处理期间文件又被修改时，重新生成冲突
+    let current = config::read_config_content()?;
+    if content_hash(&current) != conflict.theirs_hash {
+        if let Some(value) = &resolved {
+            check_write(&current, value)?;
+        }
+    }
+
+    match resolved {
+        Some(value) => config::write_openclaw_config(&value)?,
+        None => {
+            // 以外部版本作为新的基准
+            let value = config::load_openclaw_config()?;
+            record_read(&current, &value);
+        }
+    }
+    if let Ok(mut pending) = PENDING.lock() {
+        *pending = None;
+    }
+    info!("[配置冲突] ✓ 冲突已处理");
+    Ok("配置冲突已处理".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn three_way_diff_marks_only_divergent_edits_as_conflicts() {
+        let base = json!({"gateway": {"port": 18789, "mode": "local"}, "agents": {"model": "a"}});
+        let ours = json!({"gateway": {"port": 18790, "mode": "local"}, "agents": {"model": "b"}});
+        let theirs =
+            json!({"gateway": {"port": 18789, "mode": "remote"}, "agents": {"model": "c"}});
+
+        let diff = three_way_diff(&base, &ours, &theirs);
+        let find = |p: &str| diff.iter().find(|d| d.path == p).unwrap();
+
+        assert_eq!(diff.len(), 3);
+        assert!(!find("/gateway/port").conflict);
+        assert!(!find("/gateway/mode").conflict);
+        assert!(find("/agents/model").conflict);
+        assert_eq!(find("/agents/model").theirs, Some(json!("c")));
+    }
+}
@@ -0,0 +1,417 @@
+use crate::commands::config;
+use crate::models::DiagnosticResult;
+use crate::utils::audit;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use openclaw_macros::guarded;
+use tauri::command;
+
+/// 检查结果类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LintKind {
+    /// 键已改名，可自动迁移到新位置
+    Renamed,
+    /// 功能已移除，需要手动处理
+    Removed,
+}
+
+/// 规则：路径用 `.` 分隔，`*` 匹配任意键（如渠道名），替换路径中的 `*` 按顺序取匹配到的键
+struct LintRule {
+    path: &'static str,
+    kind: LintKind,
+    replacement: Option<&'static str>,
+    /// 废弃的 OpenClaw 版本
+    since: &'static str,
+    message: &'static str,
+}
+
+/// 废弃配置规则表，OpenClaw 废弃新的键时追加到末尾
+const RULES: &[LintRule] = &[
+    LintRule {
+        path: "agent.model",
+        kind: LintKind::Renamed,
+        replacement: Some("agents.defaults.model.primary"),
+        since: "2026.1",
+        message: "默认模型改为在 agents.defaults 中配置",
+    },
+    LintRule {
+        path: "agent.models",
+        kind: LintKind::Renamed,
+        replacement: Some("agents.defaults.models"),
+        since: "2026.1",
+        message: "可用模型列表改为在 agents.defaults 中配置",
+    },
+    LintRule {
+        path: "agent.workspace",
+        kind: LintKind::Renamed,
+        replacement: Some("agents.defaults.workspace"),
+        since: "2026.1",
+        message: "工作区目录改为在 agents.defaults 中配置",
+    },
+    LintRule {
+        path: "gateway.token",
+        kind: LintKind::Renamed,
+        replacement: Some("gateway.auth.token"),
+        since: "2026.1",
+        message: "网关令牌移到 gateway.auth 下",
+    },
+    LintRule {
+        path: "telegram",
+        kind: LintKind::Renamed,
+        replacement: Some("channels.telegram"),
+        since: "2026.1",
+        message: "渠道配置统一移到 channels 下",
+    },
+    LintRule {
+        path: "discord",
+        kind: LintKind::Renamed,
+        replacement: Some("channels.discord"),
+        since: "2026.1",
+        message: "渠道配置统一移到 channels 下",
+    },
+    LintRule {
+        path: "slack",
+        kind: LintKind::Renamed,
+        replacement: Some("channels.slack"),
+        since: "2026.1",
+        message: "渠道配置统一移到 channels 下",
+    },
+    LintRule {
+        path: "whatsapp",
+        kind: LintKind::Renamed,
+        replacement: Some("channels.whatsapp"),
+        since: "2026.1",
+        message: "渠道配置统一移到 channels 下",
+    },
+    LintRule {
+        path: "channels.*.dm.policy",
+        kind: LintKind::Renamed,
+        replacement: Some("channels.*.dmPolicy"),
+        since: "2026.2",
+        message: "私聊策略改为渠道下的 dmPolicy",
+    },
+    LintRule {
+        path: "identity",
+        kind: LintKind::Removed,
+        replacement: None,
+        since: "2026.1",
+        message: "全局 identity 已移除，请在 agents.list 中为每个 Agent 配置 identity",
+    },
+    LintRule {
+        path: "browser.controlUrl",
+        kind: LintKind::Removed,
+        replacement: None,
+        since: "2026.2",
+        message: "独立的浏览器控制服务已移除，浏览器由网关直接管理，该配置不再生效",
+    },
+];
+
+/// 检查到的废弃配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConfigLintWarning {
+    /// 配置中的实际路径（如 `channels.telegram.dm.policy`）
+    pub path: String,
+    pub kind: LintKind,
+    /// 迁移后的路径
+    pub replacement: Option<String>,
+    pub since: String,
+    pub message: String,
+    /// 是否可以自动迁移（新路径已有值时需要手动合并）
+    pub auto_migrate: bool,
+}
+
+/// 自动迁移结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigMigrationResult {
+    /// 已迁移的路径
+    pub migrated: Vec<String>,
+    /// 迁移后仍存在的问题
+    pub remaining: Vec<ConfigLintWarning>,
+}
+
+fn get<'a>(value: &'a Value, path: &[String]) -> Option<&'a Value> {
+    path.iter().try_fold(value, |v, key| v.get(key))
+}
+
+/// 展开规则路径中的 `*`，返回配置中实际存在的路径和每个 `*` 匹配到的键
+fn expand(config: &Value, pattern: &str) -> Vec<(Vec<String>, Vec<String>)> {
+    let mut matches = vec![(Vec::new(), Vec::new())];
+    for segment in pattern.split('.') {
+        matches = matches
+            .into_iter()
+            .flat_map(|(path, captures): (Vec<String>, Vec<String>)| {
+                let Some(Value::Object(map)) = get(config, &path) else {
+                    return Vec::new();
+                };
+                let keys: Vec<&String> = if segment == "*" {
+                    map.keys().collect()
+                } else {
+                    map.keys().filter(|k| k.as_str() == segment).collect()
+                };
+                keys.into_iter()
+                    .map(|key| {
+                        let mut path = path.clone();
+                        path.push(key.clone());
+                        let mut captures = captures.clone();
+                        if segment == "*" {
+                            captures.push(key.clone());
+                        }
+                        (path, captures)
+                    })
+                    .collect()
+            })
+            .collect();
+    }
+    matches
+}
+
+fn substitute(replacement: &str, captures: &[String]) -> Vec<String> {
+    let mut captures = captures.iter();
+    replacement
+        .split('.')
+        .map(|segment| match segment {
+            "*" => captures.next().cloned().unwrap_or_default(),
+            _ => segment.to_string(),
+        })
+        .collect()
+}
+
+/// 检查配置中的废弃键
+pub fn lint(config: &Value) -> Vec<ConfigLintWarning> {
+    let mut warnings = Vec::new();
+    for rule in RULES {
+        for (path, captures) in expand(config, rule.path) {
+            let replacement = rule.replacement.map(|r| substitute(r, &captures));
+            let auto_migrate = replacement
+                .as_ref()
+                .is_some_and(|target| get(config, target).is_none());
+            warnings.push(ConfigLintWarning {
+                path: path.join("."),
+                kind: rule.kind,
+                replacement: replacement.map(|r| r.join(".")),
+                since: rule.since.to_string(),
+                message: rule.message.to_string(),
+                auto_migrate,
+            });
+        }
+    }
+    warnings
+}
+
+/// 删除路径上的值，并清理因此变空的父对象
+fn remove(config: &mut Value, path: &[String]) -> Option<Value> {
+    let (last, parents) = path.split_last()?;
+    let mut parent = &mut *config;
+    for key in parents {
+        parent = parent.get_mut(key)?;
+    }
+    let removed = parent.as_object_mut()?.remove(last)?;
+    if !parents.is_empty()
+        && get(config, parents).is_some_and(|v| v.as_object().is_some_and(Map::is_empty))
+    {
+        remove(config, parents);
+    }
+    Some(removed)
+}
+
+/// 在路径上写入值（自动创建中间对象），路径上存在非对象的值时返回 false
+pub fn insert(config: &mut Value, path: &[String], value: Value) -> bool {
+    let Some((last, parents)) = path.split_last() else {
+        return false;
+    };
+    let mut current = config;
+    for key in parents {
+        let Some(map) = current.as_object_mut() else {
+            return false;
+        };
+        current = map.entry(key.clone()).or_insert_with(|| json!({}));
+    }
+    match current.as_object_mut() {
+        Some(map) => {
+            map.insert(last.clone(), value);
+            true
+        }
+        None => false,
+    }
+}
+
+/// 迁移可自动处理的废弃键，`paths` 为空时迁移全部，返回已迁移的路径
+pub fn migrate(config: &mut Value, paths: Option<&[String]>) -> Vec<String> {
+    let mut migrated = Vec::new();
+    let mut attempted = Vec::new();
+    // 每次迁移后重新检查：父键迁移后子键的路径会变化
+    while let Some(warning) = lint(config).into_iter().find(|w| {
+        w.auto_migrate && paths.is_none_or(|p| p.contains(&w.path)) && !attempted.contains(&w.path)
+    }) {
+        attempted.push(warning.path.clone());
+        let from: Vec<String> = warning.path.split('.').map(String::from).collect();
+        let to: Vec<String> = warning
+            .replacement
+            .as_deref()
+            .unwrap_or_default()
+            .split('.')
+            .map(String::from)
+            .collect();
+        let Some(value) = remove(config, &from) else {
+            continue;
+        };
+        if !insert(config, &to, value.clone()) {
+            // 新路径的父级不是对象（如 agents 被写成了字符串），放回原处
+            warn!("[配置检查] 无法迁移 {} → {}", warning.path, to.join("."));
+            insert(config, &from, value);
+            continue;
+        }
+        info!("[配置检查] 迁移 {} → {}", warning.path, to.join("."));
+        migrated.push(warning.path);
+    }
+    migrated
+}
+
+/// 网关启动前检查：只记录日志，不阻止启动
+pub fn log_warnings() {
+    let Ok(cfg) = config::load_openclaw_config() else {
+        return;
+    };
+    for warning in lint(&cfg) {
+        warn!(
+            "[配置检查] {} 已废弃（{}）: {}",
+            warning.path, warning.since, warning.message
+        );
+    }
+}
+
+/// 诊断：配置中存在废弃键时给出警告
+pub fn diagnostic() -> Option<DiagnosticResult> {
+    let cfg = config::load_openclaw_config().ok()?;
+    let warnings = lint(&cfg);
+    let passed = warnings.is_empty();
+    Some(DiagnosticResult {
+        name: "配置兼容性".to_string(),
+        passed,
+        message: if passed {
+            "未使用已废弃的配置项".to_string()
+        } else {
+            warnings
+                .iter()
+                .map(|w| format!("{}: {}", w.path, w.message))
+                .collect::<Vec<_>>()
+                .join("\n")
+        },
+        suggestion: if warnings.iter().any(|w| w.auto_migrate) {
+            Some("在诊断页面执行自动迁移，将废弃的配置项移到新位置".to_string())
+        } else if passed {
+            None
+        } else {
+            Some("请按提示手动修改 openclaw.json".to_string())
+        },
+        fix: None,
+    })
+}
+
+/// 检查 openclaw.json 中的废弃配置
+#[guarded]
+#[command]
+pub async fn lint_config() -> Result<Vec<ConfigLintWarning>, String> {
+    Ok(lint(&config::load_openclaw_config()?))
+}
+
+/// 自动迁移废弃配置（`paths` 为空时迁移全部可自动处理的项）
+#[guarded]
+#[command]
+pub async fn migrate_config_keys(
+    paths: Option<Vec<String>>,
+) -> Result<ConfigMigrationResult, String> {
+    let mut cfg = config::load_openclaw_config()?;
+    let migrated = migrate(&mut cfg, paths.as_deref());
+    if !migrated.is_empty() {
+        config::save_config(cfg.clone()).await?;
+        audit::record(
+            "migrate_config_keys",
+            "openclaw.json",
+            true,
+            json!({ "migrated": migrated }),
+        );
+        info!("[配置检查] ✓ 已迁移 {} 个废弃配置项", migrated.len());
+    }
+    Ok(ConfigMigrationResult {
+        migrated,
+        remaining: lint(&cfg),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lints_and_migrates_deprecated_keys() {
+        let mut cfg = json!({
+            "agent": { "model": "anthropic/claude-sonnet-4-5", "workspace": "~/work" },
+            "gateway": { "token": "abc", "auth": { "token": "new" } },
+            "telegram": { "botToken": "123", "dm": { "policy": "pairing" } },
+            "channels": { "discord": { "dm": { "policy": "open" }, "token": "x" } },
+            "browser": { "controlUrl": "http://127.0.0.1:18791", "enabled": true }
+        });
+        let warnings = lint(&cfg);
+        let paths: Vec<&str> = warnings.iter().map(|w| w.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "agent.model",
+                "agent.workspace",
+                "gateway.token",
+                "telegram",
+                "channels.discord.dm.policy",
+                "browser.controlUrl"
+            ]
+        );
+        // 新位置已有值时不自动迁移
+        assert!(!warnings[2].auto_migrate);
+        assert_eq!(
+            warnings[4].replacement.as_deref(),
+            Some("channels.discord.dmPolicy")
+        );
+
+        let migrated = migrate(&mut cfg, None);
+        assert_eq!(
+            migrated,
+            vec![
+                "agent.model",
+                "agent.workspace",
+                "telegram",
+                "channels.discord.dm.policy",
+                "channels.telegram.dm.policy"
+            ]
+        );
+        assert_eq!(
+            cfg,
+            json!({
+                "agents": { "defaults": {
+                    "model": { "primary": "anthropic/claude-sonnet-4-5" },
+                    "workspace": "~/work"
+                } },
+                "gateway": { "token": "abc", "auth": { "token": "new" } },
+                "channels": {
+                    "discord": { "dmPolicy": "open", "token": "x" },
+                    "telegram": { "botToken": "123", "dmPolicy": "pairing" }
+                },
+                "browser": { "controlUrl": "http://127.0.0.1:18791", "enabled": true }
+            })
+        );
+        let remaining: Vec<String> = lint(&cfg).into_iter().map(|w| w.path).collect();
+        assert_eq!(remaining, vec!["gateway.token", "browser.controlUrl"]);
+
+        // 只迁移选中的项
+        let mut cfg = json!({ "agent": { "model": "a", "workspace": "w" } });
+        assert_eq!(
+            migrate(&mut cfg, Some(&["agent.workspace".to_string()])),
+            vec!["agent.workspace"]
+        );
+        assert_eq!(
+            cfg,
+            json!({ "agent": { "model": "a" }, "agents": { "defaults": { "workspace": "w" } } })
+        );
+    }
+}
@@ -0,0 +1,312 @@
+//! 独立控制台窗口：日志跟随和测试对话可以在单独的窗口中打开，配置渠道时保持日志可见。
+//! 每个窗口的事件只发送给该窗口，主窗口关闭时一并关闭
+use crate::commands::logs::{self, LogEntry, LogLevel};
+use crate::commands::capabilities::{self, Feature};
+use crate::commands::{window_state, workspace};
+use crate::utils::{shell, time};
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Mutex;
+use openclaw_macros::guarded;
+use tauri::{command, AppHandle, Emitter, Manager, WebviewUrl, WebviewWindow, Window, WindowEvent};
+
+/// 日志控制台收到新日志事件（只发送给对应窗口）
+pub const CONSOLE_LOG_EVENT: &str = "console-log-lines";
+/// 对话控制台收到回复事件（只发送给对应窗口）
+pub const CONSOLE_CHAT_EVENT: &str = "console-chat-reply";
+
+/// 控制台窗口标签前缀（capabilities/default.json 按此前缀授权）
+const LABEL_PREFIX: &str = "console-";
+/// 同时打开的控制台窗口上限
+const MAX_CONSOLES: usize = 6;
+
+/// 控制台类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConsoleKind {
+    /// 实时跟随 Gateway 日志
+    Logs,
+    /// 测试对话
+    Chat,
+}
+
+impl ConsoleKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Logs => "logs",
+            Self::Chat => "chat",
+        }
+    }
+
+    fn title(&self) -> &'static str {
+        match self {
+            Self::Logs => "Gateway 日志",
+            Self::Chat => "测试对话",
+        }
+    }
+}
+
+/// 控制台选项
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConsoleOptions {
+    /// 日志控制台：只显示不低于此级别的日志
+    pub min_level: Option<LogLevel>,
+    /// 日志控制台：只显示此模块的日志（如 telegram）
+    pub module: Option<String>,
+    /// 对话控制台：对话的 Agent（为空时使用默认 Agent）
+    pub agent: Option<String>,
+    /// 窗口置顶
+    pub always_on_top: bool,
+}
+
+/// 已打开的控制台窗口
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsoleWindow {
+    pub label: String,
+    pub kind: ConsoleKind,
+    pub options: ConsoleOptions,
+    pub opened_at: String,
+}
+
+/// 对话控制台的回复
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsoleChatReply {
+    /// `console_send_message` 返回的请求编号
+    pub request_id: u32,
+    pub reply: Option<String>,
+    pub error: Option<String>,
+}
+
+static CONSOLES: Mutex<Vec<ConsoleWindow>> = Mutex::new(Vec::new());
+static NEXT_ID: AtomicU32 = AtomicU32::new(1);
+
+fn consoles() -> std::sync::MutexGuard<'static, Vec<ConsoleWindow>> {
+    CONSOLES.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn find(label: &str) -> Result<ConsoleWindow, String> {
+    consoles()
+        .iter()
+        .find(|c| c.label == label)
+        .cloned()
+        .ok_or_else(|| format!("控制台窗口不存在: {}", label))
+}
+
+/// 日志是否符合控制台的过滤条件
+fn matches(options: &ConsoleOptions, entry: &LogEntry) -> bool {
+    options.min_level.is_none_or(|level| entry.level >= level)
+        && options
+            .module
+            .as_deref()
+            .filter(|m| !m.is_empty())
+            .is_none_or(|m| {
+                entry
+                    .module
+                    .as_deref()
+                    .is_some_and(|em| em.eq_ignore_ascii_case(m))
+            })
+}
+
+/// 把日志跟随读到的新日志转发给打开的日志控制台
+pub fn forward_log_lines(app: &AppHandle, lines: &[String]) {
+    let targets: Vec<ConsoleWindow> = consoles()
+        .iter()
+        .filter(|c| c.kind == ConsoleKind::Logs)
+        .cloned()
+        .collect();
+    if targets.is_empty() {
+        return;
+    }
+    let entries: Vec<LogEntry> = lines.iter().filter_map(|l| logs::parse_line(l)).collect();
+    for console in targets {
+        let selected: Vec<&LogEntry> = entries
+            .iter()
+            .filter(|e| matches(&console.options, e))
+            .collect();
+        if selected.is_empty() {
+            continue;
+        }
+        if let Err(e) = app.emit_to(console.label.as_str(), CONSOLE_LOG_EVENT, &selected) {
+            debug!("[控制台] 发送日志到 {} 失败: {}", console.label, e);
+        }
+    }
+}
+
+/// 窗口事件：控制台关闭后移除记录，主窗口关闭时关闭全部控制台
+pub fn on_window_event(window: &Window, event: &WindowEvent) {
+    match event {
+        WindowEvent::Destroyed if window.label().starts_with(LABEL_PREFIX) => {
+            consoles().retain(|c| c.label != window.label());
+            info!("[控制台] 已关闭: {}", window.label());
+        }
+        WindowEvent::CloseRequested { .. } if window.label() == window_state::MAIN_WINDOW => {
+            let labels: Vec<String> = consoles().iter().map(|c| c.label.clone()).collect();
+            for label in labels {
+                if let Some(console) = window.app_handle().get_webview_window(&label) {
+                    let _ = console.close();
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn console_window(app: &AppHandle, label: &str) -> Result<WebviewWindow, String> {
+    app.get_webview_window(label)
+        .ok_or_else(|| format!("控制台窗口不存在: {}", label))
+}
+
+/// 打开控制台窗口（前端根据地址中的 console 参数渲染控制台界面）
+#[guarded]
+#[command]
+pub async fn open_console(
+    app: AppHandle,
+    kind: ConsoleKind,
+    options: Option<ConsoleOptions>,
+) -> Result<ConsoleWindow, String> {
+    let options = options.unwrap_or_default();
+    if let Some(agent) = options.agent.as_deref() {
+        workspace::validate_agent(agent)?;
+    }
+    if consoles().len() >= MAX_CONSOLES {
+        return Err(format!(
+            "最多同时打开 {} 个控制台窗口，请先关闭不用的窗口",
+            MAX_CONSOLES
+        ));
+    }
+    let label = format!(
+        "{}{}-{}",
+        LABEL_PREFIX,
+        kind.as_str(),
+        NEXT_ID.fetch_add(1, Ordering::Relaxed)
+    );
+    let url = format!("index.html?console={}&label={}", kind.as_str(), label);
+    WebviewWindow::builder(&app, label.as_str(), WebviewUrl::App(url.into()))
+        .title(format!("OpenClaw Manager - {}", kind.title()))
+        .inner_size(900.0, 600.0)
+        .min_inner_size(480.0, 320.0)
+        .always_on_top(options.always_on_top)
+        .build()
+        .map_err(|e| format!("打开控制台窗口失败: {}", e))?;
+    let console = ConsoleWindow {
+        label,
+        kind,
+        options,
+        opened_at: time::now_rfc3339(),
+    };
+    consoles().push(console.clone());
+    info!("[控制台] ✓ 已打开: {}", console.label);
+    Ok(console)
+}
+
+/// 已打开的控制台窗口
+#[guarded]
+#[command]
+pub async fn list_consoles() -> Result<Vec<ConsoleWindow>, String> {
+    Ok(consoles().clone())
+}
+
+/// 修改控制台选项（如日志过滤条件），立即生效
+#[guarded]
+#[command]
+pub async fn update_console_options(
+    app: AppHandle,
+    label: String,
+    options: ConsoleOptions,
+) -> Result<ConsoleWindow, String> {
+    if let Some(agent) = options.agent.as_deref() {
+        workspace::validate_agent(agent)?;
+    }
+    let window = console_window(&app, &label)?;
+    let mut list = consoles();
+    let console = list
+        .iter_mut()
+        .find(|c| c.label == label)
+        .ok_or_else(|| format!("控制台窗口不存在: {}", label))?;
+    if console.options.always_on_top != options.always_on_top {
+        let _ = window.set_always_on_top(options.always_on_top);
+    }
+    console.options = options;
+    Ok(console.clone())
+}
+
+/// 关闭控制台窗口
+#[guarded]
+#[command]
+pub async fn close_console(app: AppHandle, label: String) -> Result<(), String> {
+    find(&label)?;
+    console_window(&app, &label)?
+        .close()
+        .map_err(|e| format!("关闭控制台窗口失败: {}", e))
+}
+
+/// 从对话控制台发送测试消息，立即返回请求编号，回复通过 console-chat-reply 事件发送给该窗口
+#[guarded]
+#[command]
+pub async fn console_send_message(
+    app: AppHandle,
+    label: String,
+    message: String,
+) -> Result<u32, String> {
+    let console = find(&label)?;
+    if console.kind != ConsoleKind::Chat {
+        return Err("只能从对话控制台发送消息".to_string());
+    }
+    if message.trim().is_empty() {
+        return Err("消息不能为空".to_string());
+    }
+    capabilities::require(Feature::Agent)?;
+    let request_id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut args = vec!["agent"];
+        if let Some(agent) = console.options.agent.as_deref() {
+            args.extend(["--agent", agent]);
+        }
+        args.extend(["--message", message.as_str()]);
+        let result = shell::run_openclaw(&args);
+        if let Err(e) = &result {
+            warn!("[控制台] {} 发送消息失败: {}", label, e);
+        }
+        let (reply, error) = match result {
+            Ok(reply) => (Some(reply), None),
+            Err(e) => (None, Some(e)),
+        };
+        let payload = ConsoleChatReply {
+            request_id,
+            reply,
+            error,
+        };
+        // 窗口已关闭时丢弃回复
+        let _ = app.emit_to(label.as_str(), CONSOLE_CHAT_EVENT, payload);
+    });
+    Ok(request_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filters_log_lines_per_console() {
+        let entry = |line: &str| logs::parse_line(line).unwrap();
+        let error = entry("2026-03-01T10:00:00Z [telegram] error: sendMessage failed: 403");
+        let info = entry("2026-03-01T10:00:01Z [gateway] info: listening on 18789");
+
+        let all = ConsoleOptions::default();
+        assert!(matches(&all, &error) && matches(&all, &info));
+
+        let errors_only = ConsoleOptions {
+            min_level: Some(LogLevel::Warn),
+            ..Default::default()
+        };
+        assert!(matches(&errors_only, &error) && !matches(&errors_only, &info));
+
+        let telegram = ConsoleOptions {
+            module: Some("Telegram".to_string()),
+            ..Default::default()
+        };
+        assert!(matches(&telegram, &error) && !matches(&telegram, &info));
+    }
+}
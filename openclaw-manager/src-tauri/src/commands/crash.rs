@@ -0,0 +1,374 @@
+use crate::commands::{privacy, sessions, settings};
+use crate::utils::{audit, file, http, panic_guard, platform, time};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::VecDeque;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use openclaw_macros::guarded;
+use tauri::command;
+
+/// 崩溃报告附带的日志行数
+const LOG_LINES: usize = 200;
+/// 本地最多保留的崩溃报告数
+const MAX_REPORTS: usize = 20;
+/// Manager 日志文件超过该大小时清空重写
+const MAX_LOG_BYTES: u64 = 2 * 1024 * 1024;
+
+/// 最近的日志（内存环形缓冲）
+static RECENT_LOGS: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+/// Manager 日志文件（install 后写入，上次运行异常退出时从中读取日志）
+static LOG_FILE: OnceLock<Mutex<std::fs::File>> = OnceLock::new();
+
+/// 崩溃类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CrashKind {
+    /// Rust panic
+    Panic,
+    /// 上次运行未正常退出（段错误、被系统结束等原生崩溃）
+    UncleanExit,
+}
+
+/// 崩溃报告（消息和日志在生成时已脱敏）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrashReport {
+    pub id: String,
+    pub kind: CrashKind,
+    pub created_at: String,
+    pub manager_version: String,
+    pub os: String,
+    pub arch: String,
+    pub message: String,
+    /// panic 位置（文件:行）
+    pub location: Option<String>,
+    pub thread: Option<String>,
+    /// panic 发生时正在执行的命令及其错误编号（与返回给前端的 InternalError 一致）
+    pub command: Option<String>,
+    pub correlation_id: Option<String>,
+    pub backtrace: Option<String>,
+    /// 崩溃前最后的日志
+    pub logs: Vec<String>,
+    pub submitted: bool,
+}
+
+/// 报告列表项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrashReportSummary {
+    pub id: String,
+    pub kind: CrashKind,
+    pub created_at: String,
+    pub message: String,
+    pub submitted: bool,
+}
+
+/// 运行标记：存在说明上次运行没有走正常退出流程
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SessionMarker {
+    pid: u32,
+    started_at: String,
+}
+
+fn reports_dir() -> PathBuf {
+    PathBuf::from(platform::get_manager_data_dir()).join("crash-reports")
+}
+
+fn marker_path() -> PathBuf {
+    PathBuf::from(platform::get_manager_data_dir()).join("running.json")
+}
+
+/// Manager 自身的日志文件
+pub fn log_path() -> PathBuf {
+    PathBuf::from(platform::get_manager_data_dir()).join("manager.log")
+}
+
+/// 日志输出：写 stderr，同时保留最近的日志用于崩溃报告
+pub struct LogTee;
+
+impl Write for LogTee {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let text = String::from_utf8_lossy(buf);
+        if let Ok(mut logs) = RECENT_LOGS.try_lock() {
+            for line in text.lines().filter(|l| !l.is_empty()) {
+                if logs.len() >= LOG_LINES {
+                    logs.pop_front();
+                }
+                logs.push_back(line.to_string());
+            }
+        }
+        if let Some(Ok(mut f)) = LOG_FILE.get().map(|f| f.try_lock()) {
+            let _ = f.write_all(buf);
+        }
+        io::stderr().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if let Some(Ok(mut f)) = LOG_FILE.get().map(|f| f.try_lock()) {
+            let _ = f.flush();
+        }
+        io::stderr().flush()
+    }
+}
+
+fn recent_logs() -> Vec<String> {
+    RECENT_LOGS
+        .try_lock()
+        .map(|logs| logs.iter().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// 日志文件的最后 N 行
+fn tail_lines(content: &str, n: usize) -> Vec<String> {
+    let lines: Vec<&str> = content.lines().filter(|l| !l.is_empty()).collect();
+    lines[lines.len().saturating_sub(n)..]
+        .iter()
+        .map(|l| l.to_string())
+        .collect()
+}
+
+fn new_report(kind: CrashKind, message: &str, logs: Vec<String>) -> CrashReport {
+    let now = time::now();
+    CrashReport {
+        id: format!(
+            "{}-{}",
+            now.format("%Y%m%d-%H%M%S"),
+            &uuid::Uuid::new_v4().simple().to_string()[..6]
+        ),
+        kind,
+        created_at: now.to_rfc3339(),
+        manager_version: env!("CARGO_PKG_VERSION").to_string(),
+        os: platform::get_os(),
+        arch: std::env::consts::ARCH.to_string(),
+        message: sessions::redact_text(message),
+        location: None,
+        thread: None,
+        command: None,
+        correlation_id: None,
+        backtrace: None,
+        logs: logs.iter().map(|l| sessions::redact_text(l)).collect(),
+        submitted: false,
+    }
+}
+
+fn save_report(report: &CrashReport) -> Result<(), String> {
+    let content = serde_json::to_vec_pretty(report).map_err(|e| e.to_string())?;
+    file::write_atomic(&reports_dir().join(format!("{}.json", report.id)), &content)
+        .map_err(|e| format!("保存崩溃报告失败: {}", e))
+}
+
+fn load_report(id: &str) -> Result<CrashReport, String> {
+    if id.is_empty() || id.contains(['/', '\\']) || id.contains("..") {
+        return Err(format!("无效的报告 ID: {}", id));
+    }
+    let content = std::fs::read_to_string(reports_dir().join(format!("{}.json", id)))
+        .map_err(|_| format!("找不到崩溃报告: {}", id))?;
+    serde_json::from_str(&content).map_err(|e| format!("解析崩溃报告失败: {}", e))
+}
+
+fn load_reports() -> Vec<CrashReport> {
+    let mut reports: Vec<CrashReport> = std::fs::read_dir(reports_dir())
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|e| std::fs::read_to_string(e.path()).ok())
+        .filter_map(|c| serde_json::from_str(&c).ok())
+        .collect();
+    reports.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    reports
+}
+
+/// 查找某次请求中 panic 生成的崩溃报告
+pub fn find_by_correlation_id(id: &str) -> Option<String> {
+    load_reports()
+        .into_iter()
+        .find(|r| r.correlation_id.as_deref() == Some(id))
+        .map(|r| r.id)
+}
+
+/// 只保留最近的报告
+fn prune_reports() {
+    for report in load_reports().into_iter().skip(MAX_REPORTS) {
+        let _ = std::fs::remove_file(reports_dir().join(format!("{}.json", report.id)));
+    }
+}
+
+/// 打开 Manager 日志文件：保留上次运行的日志，超过大小上限时清空
+fn open_log_file(path: &Path) -> io::Result<std::fs::File> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let too_large = std::fs::metadata(path).is_ok_and(|m| m.len() > MAX_LOG_BYTES);
+    std::fs::OpenOptions::new()
+        .create(true)
+        .append(!too_large)
+        .write(true)
+        .truncate(too_large)
+        .open(path)
+}
+
+/// 启动时调用：检查上次运行是否异常退出，写入运行标记，并安装 panic 钩子
+///
+/// 原生崩溃（段错误、被系统结束）无法在进程内可靠地写入报告，
+/// 通过下次启动时仍存在的运行标记检测，并附上日志文件中上次运行的最后日志
+pub fn install() {
+    let marker = marker_path();
+    if let Some(previous) = std::fs::read_to_string(&marker)
+        .ok()
+        .and_then(|c| serde_json::from_str::<SessionMarker>(&c).ok())
+    {
+        let logs = std::fs::read_to_string(log_path())
+            .map(|c| tail_lines(&c, LOG_LINES))
+            .unwrap_or_default();
+        let report = new_report(
+            CrashKind::UncleanExit,
+            &format!(
+                "上次运行（PID {}，启动于 {}）未正常退出",
+                previous.pid, previous.started_at
+            ),
+            logs,
+        );
+        match save_report(&report) {
+            Ok(()) => warn!(
+                "[崩溃报告] 检测到上次运行异常退出，已生成报告 {}",
+                report.id
+            ),
+            Err(e) => warn!("[崩溃报告] {}", e),
+        }
+        prune_reports();
+    }
+
+    match open_log_file(&log_path()) {
+        Ok(f) => {
+            let _ = LOG_FILE.set(Mutex::new(f));
+        }
+        Err(e) => warn!("[崩溃报告] 打开日志文件失败: {}", e),
+    }
+    let current = SessionMarker {
+        pid: std::process::id(),
+        started_at: time::now_rfc3339(),
+    };
+    if let Ok(content) = serde_json::to_vec(&current) {
+        let _ = file::write_atomic(&marker, &content);
+    }
+
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |panic_info| {
+        let message = panic_info
+            .payload()
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| panic_info.payload().downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "未知 panic".to_string());
+        let mut report = new_report(CrashKind::Panic, &message, recent_logs());
+        report.location = panic_info
+            .location()
+            .map(|l| format!("{}:{}", l.file(), l.line()));
+        report.thread = std::thread::current().name().map(String::from);
+        if let Some((command, correlation_id)) = panic_guard::current() {
+            report.command = Some(command.to_string());
+            report.correlation_id = Some(correlation_id);
+        }
+        report.backtrace = Some(sessions::redact_text(
+            &std::backtrace::Backtrace::force_capture().to_string(),
+        ));
+        if let Err(e) = save_report(&report) {
+            eprintln!("[崩溃报告] {}", e);
+        }
+        default_hook(panic_info);
+    }));
+    info!("[崩溃报告] 已启用崩溃报告");
+}
+
+/// 正常退出时删除运行标记（shutdown 流程调用）
+pub fn mark_clean_exit() {
+    let _ = std::fs::remove_file(marker_path());
+}
+
+/// 列出本地崩溃报告（最新的在前）
+#[guarded]
+#[command]
+pub async fn list_crash_reports() -> Result<Vec<CrashReportSummary>, String> {
+    Ok(load_reports()
+        .into_iter()
+        .map(|r| CrashReportSummary {
+            id: r.id,
+            kind: r.kind,
+            created_at: r.created_at,
+            message: r.message,
+            submitted: r.submitted,
+        })
+        .collect())
+}
+
+/// 查看崩溃报告的完整内容（提交前供用户检查）
+#[guarded]
+#[command]
+pub async fn get_crash_report(id: String) -> Result<CrashReport, String> {
+    load_report(&id)
+}
+
+/// 删除崩溃报告
+#[guarded]
+#[command]
+pub async fn delete_crash_report(id: String) -> Result<(), String> {
+    load_report(&id)?;
+    std::fs::remove_file(reports_dir().join(format!("{}.json", id)))
+        .map_err(|e| format!("删除崩溃报告失败: {}", e))
+}
+
+/// 提交崩溃报告到设置中的 crash_report_endpoint
+#[guarded]
+#[command]
+pub async fn submit_crash_report(id: String) -> Result<CrashReport, String> {
+    privacy::guard("提交崩溃报告")?;
+    let endpoint = settings::load_settings().crash_report_endpoint;
+    let endpoint = endpoint.trim();
+    if endpoint.is_empty() {
+        return Err("未设置崩溃报告提交地址（设置 crash_report_endpoint）".to_string());
+    }
+    let mut report = load_report(&id)?;
+    info!("[崩溃报告] 提交报告 {}", id);
+    let resp = http::client()?
+        .post(endpoint)
+        .json(&report)
+        .send()
+        .await
+        .map_err(|e| format!("提交失败: {}", e))?;
+    let ok = resp.status().is_success();
+    audit::record(
+        "submit_crash_report",
+        &id,
+        ok,
+        json!({ "status": resp.status().as_u16() }),
+    );
+    if !ok {
+        return Err(format!("提交失败: HTTP {}", resp.status()));
+    }
+    report.submitted = true;
+    save_report(&report)?;
+    info!("[崩溃报告] ✓ 已提交 {}", id);
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_keep_log_tail_and_redact_secrets() {
+        let content: String = (0..300).map(|i| format!("line {}\n", i)).collect();
+        let tail = tail_lines(&content, LOG_LINES);
+        assert_eq!(tail.len(), LOG_LINES);
+        assert_eq!(tail[0], "line 100");
+
+        let report = new_report(
+            CrashKind::Panic,
+            "failed with key sk-abcdefghijklmnopqrstuvwx",
+            vec!["open /Users/alice/.openclaw/openclaw.json".to_string()],
+        );
+        assert!(!report.message.contains("sk-abcdefghij"));
+        assert!(!report.logs[0].contains("alice"));
+    }
+}
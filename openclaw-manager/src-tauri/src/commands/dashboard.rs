@@ -0,0 +1,201 @@
+//! 首页概览：并发汇总服务状态、版本更新、近期错误、今日用量、渠道和任务队列，
+//! 首页只需调用一次命令
+use crate::commands::analytics::{self, UsageSummary};
+use crate::commands::installer::{self, UpdateInfo};
+use crate::commands::jobs::{self, Job};
+use crate::commands::logs::{self, LogIssue, LogLevel};
+use crate::commands::{config, monitor, service};
+use crate::models::ServiceStatus;
+use crate::utils::time;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::future::Future;
+use openclaw_macros::guarded;
+use tauri::command;
+
+/// 首页展示的主要问题数
+const TOP_ISSUES: usize = 3;
+/// 近期错误的统计范围
+const ERROR_WINDOW_MS: i64 = 24 * 60 * 60 * 1000;
+
+/// 概览中的一个板块：获取失败时 data 为空，updated_at 为数据的获取时间（毫秒）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DashboardSection<T> {
+    pub data: Option<T>,
+    pub error: Option<String>,
+    pub updated_at: i64,
+}
+
+impl<T> DashboardSection<T> {
+    fn new(result: Result<T, String>, updated_at: i64) -> Self {
+        match result {
+            Ok(data) => Self {
+                data: Some(data),
+                error: None,
+                updated_at,
+            },
+            Err(error) => Self {
+                data: None,
+                error: Some(error),
+                updated_at,
+            },
+        }
+    }
+}
+
+/// 近期（24 小时）错误
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecentErrors {
+    pub errors: usize,
+    pub warnings: usize,
+    pub issues: Vec<LogIssue>,
+}
+
+/// 渠道概况
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChannelSummary {
+    pub id: String,
+    pub enabled: bool,
+    /// Gateway 报告的异常（Gateway 未运行时不检查）
+    pub problem: Option<String>,
+}
+
+/// 首页概览
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DashboardSnapshot {
+    pub service: DashboardSection<ServiceStatus>,
+    pub update: DashboardSection<UpdateInfo>,
+    pub errors: DashboardSection<RecentErrors>,
+    /// 今日（本地时间 0 点起）用量
+    pub usage_today: DashboardSection<UsageSummary>,
+    pub channels: DashboardSection<Vec<ChannelSummary>>,
+    /// 排队中和执行中的任务
+    pub jobs: DashboardSection<Vec<Job>>,
+    pub generated_at: i64,
+}
+
+fn now_ms() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
+
+/// 在阻塞线程中执行并记录完成时间
+async fn blocking<T, F>(f: F) -> DashboardSection<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, String> + Send + 'static,
+{
+    let result = tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|r| r);
+    DashboardSection::new(result, now_ms())
+}
+
+async fn section<T, E: Into<String>>(
+    future: impl Future<Output = Result<T, E>>,
+) -> DashboardSection<T> {
+    let result = future.await.map_err(Into::into);
+    DashboardSection::new(result, now_ms())
+}
+
+/// 配置中的渠道，并标记 Gateway 报告异常的渠道
+fn summarize_channels(cfg: &Value, problems: &[(String, String)]) -> Vec<ChannelSummary> {
+    let Some(channels) = cfg.get("channels").and_then(Value::as_object) else {
+        return Vec::new();
+    };
+    channels
+        .iter()
+        .filter(|(_, section)| section.is_object())
+        .map(|(id, section)| ChannelSummary {
+            id: id.clone(),
+            enabled: section.get("enabled").and_then(Value::as_bool) != Some(false),
+            problem: problems
+                .iter()
+                .find(|(channel, _)| channel.eq_ignore_ascii_case(id))
+                .map(|(_, detail)| detail.clone()),
+        })
+        .collect()
+}
+
+fn load_channels() -> Result<Vec<ChannelSummary>, String> {
+    let cfg = config::load_openclaw_config()?;
+    let problems = if service::gateway_pid().is_some() {
+        monitor::channel_problems()?
+    } else {
+        Vec::new()
+    };
+    Ok(summarize_channels(&cfg, &problems))
+}
+
+fn load_errors() -> Result<RecentErrors, String> {
+    let since = now_ms() - ERROR_WINDOW_MS;
+    let (_, entries) = logs::read_entries();
+    let entries: Vec<_> = entries
+        .into_iter()
+        .filter(|e| logs::in_range(e, since))
+        .collect();
+    let mut issues = logs::summarize(&entries);
+    issues.truncate(TOP_ISSUES);
+    Ok(RecentErrors {
+        errors: entries
+            .iter()
+            .filter(|e| e.level >= LogLevel::Error)
+            .count(),
+        warnings: entries.iter().filter(|e| e.level == LogLevel::Warn).count(),
+        issues,
+    })
+}
+
+/// 版本更新：优先使用后台监控最近一次检查的结果，尚未检查时立即检查
+async fn load_update() -> DashboardSection<UpdateInfo> {
+    match monitor::last_update_check() {
+        Some((checked_at, info)) => DashboardSection::new(Ok(info), checked_at),
+        None => section(installer::check_openclaw_update()).await,
+    }
+}
+
+/// 获取首页概览（各板块并发获取，单个板块失败不影响其它板块）
+#[guarded]
+#[command]
+pub async fn get_dashboard_snapshot() -> Result<DashboardSnapshot, String> {
+    let (service, update, errors, usage_today, channels, jobs) = tokio::join!(
+        section(service::get_service_status()),
+        load_update(),
+        blocking(load_errors),
+        blocking(|| analytics::usage_since(time::today_start_ms())),
+        blocking(load_channels),
+        blocking(jobs::active_jobs),
+    );
+    Ok(DashboardSnapshot {
+        service,
+        update,
+        errors,
+        usage_today,
+        channels,
+        jobs,
+        generated_at: now_ms(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn summarizes_configured_channels() {
+        let cfg = json!({ "channels": {
+            "telegram": { "botToken": "x" },
+            "slack": { "enabled": false },
+            "defaults": "ignored"
+        }});
+        let problems = vec![("Telegram".to_string(), "Telegram: error 401".to_string())];
+        let channels = summarize_channels(&cfg, &problems);
+        assert_eq!(channels.len(), 2);
+        let telegram = channels.iter().find(|c| c.id == "telegram").unwrap();
+        assert!(telegram.enabled);
+        assert_eq!(telegram.problem.as_deref(), Some("Telegram: error 401"));
+        let slack = channels.iter().find(|c| c.id == "slack").unwrap();
+        assert!(!slack.enabled && slack.problem.is_none());
+    }
+}
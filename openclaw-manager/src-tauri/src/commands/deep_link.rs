@@ -0,0 +1,142 @@
+//! 深度链接：向系统注册 openclaw-manager:// 协议，通知或外部页面中的链接
+//! 可直接打开 Manager 的对应页面（如 openclaw-manager://update、openclaw-manager://diagnostics/run）。
+//! 链接只负责导航，不会直接执行安装、更新等操作
+use crate::commands::{events, window_state};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use openclaw_macros::guarded;
+use tauri::{command, AppHandle};
+use tauri_plugin_deep_link::DeepLinkExt;
+
+/// 深度链接协议
+pub const SCHEME: &str = "openclaw-manager";
+/// 打开深度链接事件
+pub const DEEP_LINK_EVENT: &str = "deep-link";
+
+/// 可通过链接打开的页面及其允许的动作
+const ROUTES: &[(&str, &[&str])] = &[
+    ("dashboard", &[]),
+    ("ai", &[]),
+    ("channels", &[]),
+    ("testing", &[]),
+    ("logs", &[]),
+    ("settings", &[]),
+    ("update", &[]),
+    ("diagnostics", &["run"]),
+];
+
+/// 链接解析后的页面
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeepLinkRoute {
+    pub view: String,
+    pub action: Option<String>,
+    pub params: BTreeMap<String, String>,
+    pub url: String,
+}
+
+/// 前端尚未取走的链接（启动 Manager 的链接在页面加载完成前到达）
+static PENDING: Mutex<Option<DeepLinkRoute>> = Mutex::new(None);
+
+/// 解析 openclaw-manager://<页面>[/<动作>][?参数]
+pub fn parse(url: &str) -> Result<DeepLinkRoute, String> {
+    let parsed = reqwest::Url::parse(url.trim()).map_err(|e| format!("链接无效: {}", e))?;
+    if parsed.scheme() != SCHEME {
+        return Err(format!("不支持的链接协议: {}", parsed.scheme()));
+    }
+    let view = parsed.host_str().unwrap_or_default().to_ascii_lowercase();
+    let mut segments = parsed
+        .path_segments()
+        .into_iter()
+        .flatten()
+        .filter(|s| !s.is_empty());
+    let action = segments.next().map(|s| s.to_ascii_lowercase());
+    if segments.next().is_some() {
+        return Err(format!("不支持的链接: {}", url));
+    }
+    let Some((_, actions)) = ROUTES.iter().find(|(name, _)| *name == view) else {
+        return Err(format!("未知页面: {}", view));
+    };
+    if let Some(action) = action.as_deref().filter(|a| !actions.contains(a)) {
+        return Err(format!("页面 {} 不支持动作: {}", view, action));
+    }
+    Ok(DeepLinkRoute {
+        view,
+        action,
+        params: parsed.query_pairs().into_owned().collect(),
+        url: parsed.to_string(),
+    })
+}
+
+/// 生成指向某个页面的链接
+pub fn link(view: &str, action: Option<&str>) -> String {
+    match action {
+        Some(action) => format!("{}://{}/{}", SCHEME, view, action),
+        None => format!("{}://{}", SCHEME, view),
+    }
+}
+
+/// 打开链接：显示主窗口并通知前端切换页面
+fn open(app: &AppHandle, urls: &[String]) {
+    for url in urls {
+        match parse(url) {
+            Ok(route) => {
+                info!("[深度链接] 打开: {}", route.url);
+                window_state::show_main(app);
+                *PENDING.lock().unwrap_or_else(|e| e.into_inner()) = Some(route.clone());
+                let _ = events::emit(app, DEEP_LINK_EVENT, &route);
+            }
+            Err(e) => warn!("[深度链接] 忽略链接 {}: {}", url, e),
+        }
+    }
+}
+
+/// 注册协议并处理启动 Manager 的链接和运行期间打开的链接
+pub fn init(app: &AppHandle) {
+    let deep_link = app.deep_link();
+    // 安装包会写入协议注册，开发模式和免安装版本在运行时注册
+    #[cfg(any(windows, target_os = "linux"))]
+    if let Err(e) = deep_link.register_all() {
+        warn!("[深度链接] 注册 {}:// 协议失败: {}", SCHEME, e);
+    }
+    if let Ok(Some(urls)) = deep_link.get_current() {
+        let urls: Vec<String> = urls.iter().map(|u| u.to_string()).collect();
+        open(app, &urls);
+    }
+    let handle = app.clone();
+    deep_link.on_open_url(move |event| {
+        let urls: Vec<String> = event.urls().iter().map(|u| u.to_string()).collect();
+        open(&handle, &urls);
+    });
+}
+
+/// 取走尚未处理的链接（前端加载完成后调用，没有时按保存的窗口状态打开最后的页面）
+#[guarded]
+#[command]
+pub async fn take_pending_deep_link() -> Result<Option<DeepLinkRoute>, String> {
+    Ok(PENDING.lock().unwrap_or_else(|e| e.into_inner()).take())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_known_routes_only() {
+        let route = parse("openclaw-manager://diagnostics/run").unwrap();
+        assert_eq!(route.view, "diagnostics");
+        assert_eq!(route.action.as_deref(), Some("run"));
+
+        let route = parse("openclaw-manager://Logs?level=error").unwrap();
+        assert_eq!(route.view, "logs");
+        assert_eq!(route.action, None);
+        assert_eq!(route.params.get("level").map(String::as_str), Some("error"));
+
+        assert_eq!(parse(&link("update", None)).unwrap().view, "update");
+        assert!(parse("openclaw-manager://update/install").is_err());
+        assert!(parse("openclaw-manager://diagnostics/run/extra").is_err());
+        assert!(parse("openclaw-manager://unknown").is_err());
+        assert!(parse("https://update").is_err());
+    }
+}
@@ -0,0 +1,263 @@
+use crate::commands::inventory::{self, Inventory};
+use crate::commands::service;
+use crate::utils::{platform, shell};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use openclaw_macros::guarded;
+use tauri::command;
+
+/// 卸载对象
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UninstallTarget {
+    Node,
+    Openclaw,
+}
+
+/// 依赖卸载对象的组件
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Dependent {
+    /// gateway / daemon / cron / dev_skill / openclaw
+    pub kind: String,
+    pub name: String,
+    pub detail: String,
+    /// 是否可以在卸载时一并清理（级联卸载）
+    pub cascadable: bool,
+}
+
+/// 卸载前检查结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UninstallCheck {
+    pub target: UninstallTarget,
+    /// 阻止直接卸载的依赖，为空时可以安全卸载
+    pub blocking: Vec<Dependent>,
+    /// 所有依赖都可级联清理
+    pub can_cascade: bool,
+}
+
+/// 已启用的 OpenClaw 定时任务（~/.openclaw/cron/jobs.json）
+fn enabled_cron_jobs(config_dir: &Path) -> Vec<String> {
+    let Ok(content) = std::fs::read_to_string(config_dir.join("cron").join("jobs.json")) else {
+        return Vec::new();
+    };
+    let Ok(value) = serde_json::from_str::<serde_json::Value>(&content) else {
+        return Vec::new();
+    };
+    value
+        .get("jobs")
+        .and_then(|j| j.as_array())
+        .map(|jobs| {
+            jobs.iter()
+                .filter(|job| job.get("enabled").and_then(|e| e.as_bool()) != Some(false))
+                .map(|job| {
+                    job.get("name")
+                        .or_else(|| job.get("id"))
+                        .and_then(|n| n.as_str())
+                        .unwrap_or("unnamed")
+                        .to_string()
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// 计算依赖卸载对象的组件
+fn compute_dependents(
+    target: UninstallTarget,
+    inventory: &Inventory,
+    gateway_pid: Option<u32>,
+    cron_jobs: &[String],
+) -> Vec<Dependent> {
+    let mut dependents = Vec::new();
+    if inventory.openclaw.is_none() {
+        return dependents;
+    }
+
+    if target == UninstallTarget::Node {
+        let openclaw = inventory.openclaw.as_ref().map(|o| o.version.clone());
+        dependents.push(Dependent {
+            kind: "openclaw".to_string(),
+            name: "OpenClaw".to_string(),
+            detail: format!(
+                "OpenClaw {} 依赖 Node.js 运行，请先卸载 OpenClaw",
+                openclaw.unwrap_or_default()
+            ),
+            cascadable: false,
+        });
+    }
+
+    if let Some(pid) = gateway_pid {
+        dependents.push(Dependent {
+            kind: "gateway".to_string(),
+            name: "OpenClaw Gateway".to_string(),
+            detail: format!("网关正在运行 (PID {})，将先停止", pid),
+            cascadable: true,
+        });
+    }
+    if inventory.daemon.registered {
+        dependents.push(Dependent {
+            kind: "daemon".to_string(),
+            name: inventory.daemon.kind.clone(),
+            detail: format!(
+                "网关已注册为开机自启服务 ({})，将执行 openclaw gateway uninstall 移除",
+                inventory.daemon.location.clone().unwrap_or_default()
+            ),
+            cascadable: true,
+        });
+    }
+    if !cron_jobs.is_empty() {
+        dependents.push(Dependent {
+            kind: "cron".to_string(),
+            name: format!("{} 个定时任务", cron_jobs.len()),
+            detail: format!(
+                "定时任务 {} 将停止运行（配置保留在 ~/.openclaw/cron）",
+                cron_jobs.join(", ")
+            ),
+            cascadable: true,
+        });
+    }
+    for skill in inventory.skills.iter().filter(|s| s.linked) {
+        dependents.push(Dependent {
+            kind: "dev_skill".to_string(),
+            name: skill.name.clone(),
+            detail: format!(
+                "技能以开发链接模式安装 ({})，将移除链接，源码目录保留",
+                skill.path
+            ),
+            cascadable: true,
+        });
+    }
+    dependents
+}
+
+/// 卸载前检查依赖（卸载流程也会调用）
+pub fn check(target: UninstallTarget) -> UninstallCheck {
+    let inventory = inventory::collect_inventory();
+    let config_dir = platform::get_config_dir();
+    let cron_jobs = enabled_cron_jobs(Path::new(&config_dir));
+    let blocking = compute_dependents(target, &inventory, service::gateway_pid(), &cron_jobs);
+    let can_cascade = blocking.iter().all(|d| d.cascadable);
+    UninstallCheck {
+        target,
+        blocking,
+        can_cascade,
+    }
+}
+
+/// 级联清理依赖：移除守护进程注册和开发链接的技能
+/// 网关进程由卸载流程统一停止
+pub fn cascade(check: &UninstallCheck) -> Result<(), String> {
+    if !check.can_cascade {
+        return Err("存在无法自动清理的依赖".to_string());
+    }
+    for dependent in &check.blocking {
+        match dependent.kind.as_str() {
+            "daemon" => {
+                info!("[卸载检查] 移除网关守护进程注册...");
+                shell::run_openclaw(&["gateway", "uninstall"])
+                    .map_err(|e| format!("移除网关守护进程失败: {}", e))?;
+            }
+            "dev_skill" => {
+                let path = Path::new(&platform::get_config_dir())
+                    .join("skills")
+                    .join(&dependent.name);
+                info!("[卸载检查] 移除开发链接技能: {:?}", path);
+                remove_link(&path)
+                    .map_err(|e| format!("移除技能链接 {} 失败: {}", dependent.name, e))?;
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// 只删除符号链接本身，不删除链接指向的目录
+fn remove_link(path: &Path) -> std::io::Result<()> {
+    // Windows 上目录符号链接 / junction 需要用 remove_dir 删除
+    std::fs::remove_file(path).or_else(|_| std::fs::remove_dir(path))
+}
+
+/// 卸载 Node.js / OpenClaw 前检查依赖，返回阻止卸载的原因
+#[guarded]
+#[command]
+pub async fn check_uninstall(target: UninstallTarget) -> Result<UninstallCheck, String> {
+    info!("[卸载检查] 检查 {:?} 的依赖...", target);
+    let result = tokio::task::spawn_blocking(move || check(target))
+        .await
+        .map_err(|e| format!("检查依赖失败: {}", e))?;
+    if !result.blocking.is_empty() {
+        warn!(
+            "[卸载检查] 发现 {} 个依赖: {}",
+            result.blocking.len(),
+            result
+                .blocking
+                .iter()
+                .map(|d| d.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::inventory::{
+        DaemonRegistration, InstallMethod, OpenClawComponent, SkillComponent,
+    };
+
+    fn inventory_with_openclaw() -> Inventory {
+        Inventory {
+            generated_at: String::new(),
+            node: None,
+            openclaw: Some(OpenClawComponent {
+                path: None,
+                version: "2026.1.0".to_string(),
+                install_method: InstallMethod::Npm,
+                commit: None,
+            }),
+            skills: vec![
+                SkillComponent {
+                    name: "weather".to_string(),
+                    version: None,
+                    path: "/x/skills/weather".to_string(),
+                    linked: false,
+                },
+                SkillComponent {
+                    name: "my-skill".to_string(),
+                    version: None,
+                    path: "/x/skills/my-skill".to_string(),
+                    linked: true,
+                },
+            ],
+            daemon: DaemonRegistration {
+                kind: "launchd".to_string(),
+                registered: true,
+                location: None,
+            },
+            tunnels: Vec::new(),
+            managed_runtimes: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn node_uninstall_is_blocked_by_openclaw_but_openclaw_can_cascade() {
+        let inventory = inventory_with_openclaw();
+        let cron = vec!["daily-report".to_string()];
+
+        let openclaw = compute_dependents(UninstallTarget::Openclaw, &inventory, Some(42), &cron);
+        let kinds: Vec<_> = openclaw.iter().map(|d| d.kind.as_str()).collect();
+        assert_eq!(kinds, vec!["gateway", "daemon", "cron", "dev_skill"]);
+        assert!(openclaw.iter().all(|d| d.cascadable));
+
+        let node = compute_dependents(UninstallTarget::Node, &inventory, None, &[]);
+        assert_eq!(node[0].kind, "openclaw");
+        assert!(!node[0].cascadable);
+
+        let mut empty = inventory;
+        empty.openclaw = None;
+        assert!(compute_dependents(UninstallTarget::Node, &empty, None, &[]).is_empty());
+    }
+}
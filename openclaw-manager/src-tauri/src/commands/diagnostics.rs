@@ -1,5 +1,9 @@
+use crate::commands::capabilities::{self, Feature};
+use crate::commands::{clock, config_lint, orphans, policy, privacy, shell_policy};
 use crate::models::{AITestResult, ChannelTestResult, DiagnosticResult, SystemInfo};
-use crate::utils::{platform, shell};
+use crate::utils::{audit, platform, shell, temp, time};
+use serde_json::json;
+use openclaw_macros::guarded;
 use tauri::command;
 use log::{info, warn, error, debug};
 
@@ -30,7 +34,7 @@ fn strip_ansi_codes(input: &str) -> String {
 }
 
 /// 从混合输出中提取 JSON 内容
-fn extract_json_from_output(output: &str) -> Option<String> {
+pub fn extract_json_from_output(output: &str) -> Option<String> {
     // 先去除 ANSI 颜色代码
     let clean_output = strip_ansi_codes(output);
     
@@ -82,11 +86,12 @@ fn extract_json_from_output(output: &str) -> Option<String> {
 }
 
 /// 运行诊断
+#[guarded]
 #[command]
 pub async fn run_doctor() -> Result<Vec<DiagnosticResult>, String> {
     info!("[诊断] 开始运行系统诊断...");
     let mut results = Vec::new();
-    
+
     // 检查 OpenClaw 是否安装
     info!("[诊断] 检查 OpenClaw 安装状态...");
     let openclaw_installed = shell::get_openclaw_path().is_some();
@@ -104,8 +109,9 @@ pub async fn run_doctor() -> Result<Vec<DiagnosticResult>, String> {
         } else {
             Some("运行: npm install -g openclaw".to_string())
         },
+        fix: None,
     });
-    
+
     // 检查 Node.js
     let node_check = shell::run_command_output("node", &["--version"]);
     results.push(DiagnosticResult {
@@ -119,8 +125,9 @@ pub async fn run_doctor() -> Result<Vec<DiagnosticResult>, String> {
         } else {
             None
         },
+        fix: None,
     });
-    
+
     // 检查配置文件
     let config_path = platform::get_config_file_path();
     let config_exists = std::path::Path::new(&config_path).exists();
@@ -137,8 +144,9 @@ pub async fn run_doctor() -> Result<Vec<DiagnosticResult>, String> {
         } else {
             Some("运行 openclaw 初始化配置".to_string())
         },
+        fix: None,
     });
-    
+
     // 检查环境变量文件
     let env_path = platform::get_env_file_path();
     let env_exists = std::path::Path::new(&env_path).exists();
@@ -155,8 +163,9 @@ pub async fn run_doctor() -> Result<Vec<DiagnosticResult>, String> {
         } else {
             Some("请配置 AI API Key".to_string())
         },
+        fix: None,
     });
-    
+
     // 运行 openclaw doctor
     if openclaw_installed {
         let doctor_result = shell::run_openclaw(&["doctor"]);
@@ -165,27 +174,75 @@ pub async fn run_doctor() -> Result<Vec<DiagnosticResult>, String> {
             passed: doctor_result.is_ok() && !doctor_result.as_ref().unwrap().contains("invalid"),
             message: doctor_result.unwrap_or_else(|e| e),
             suggestion: None,
+            fix: None,
         });
     }
-    
+
+    // 配置兼容性：检查已废弃的配置项
+    if let Some(result) = config_lint::diagnostic() {
+        results.push(result);
+    }
+
+    // Shell 技能：检查命令策略是否形同虚设
+    if let Some(result) = shell_policy::diagnostic() {
+        results.push(result);
+    }
+
+    // 隐私模式：检查是否仍有组件向外连接
+    if let Some(result) = privacy::diagnostic().await {
+        results.push(result);
+    }
+
+    // 系统时间：偏差过大时 API 签名和 TLS 校验会失败
+    if let Some(result) = clock::diagnostic().await {
+        results.push(result);
+    }
+
+    // 残留进程：之前的 Manager 会话留下的 Gateway 占用端口
+    if let Ok(Some(result)) = tokio::task::spawn_blocking(orphans::diagnostic).await {
+        results.push(result);
+    }
+
     Ok(results)
 }
 
+/// 执行诊断结果中的自动修复（`fix` 为诊断结果的修复项 ID）
+#[guarded]
+#[command]
+pub async fn apply_fix(fix: String, confirm_token: Option<String>) -> Result<String, String> {
+    policy::require_confirmation("apply_fix", confirm_token.as_deref())?;
+    info!("[诊断] 执行修复: {}", fix);
+    let result = match fix.as_str() {
+        clock::SYNC_CLOCK_FIX => clock::sync_clock().await,
+        orphans::CLEANUP_ORPHANS_FIX => orphans::cleanup_all().await,
+        _ => Err(format!("未知的修复项: {}", fix)),
+    };
+    audit::record(
+        "apply_fix",
+        &fix,
+        result.is_ok(),
+        json!({ "error": result.as_ref().err() }),
+    );
+    result
+}
+
 /// 测试 AI 连接
+#[guarded]
 #[command]
 pub async fn test_ai_connection() -> Result<AITestResult, String> {
     info!("[AI测试] 开始测试 AI 连接...");
-    
+
     // 获取当前配置的 provider
     let start = std::time::Instant::now();
-    
+
     // 使用 openclaw 命令测试连接
+    capabilities::require(Feature::AgentLocal)?;
     info!("[AI测试] 执行: openclaw agent --local --to +1234567890 --message 回复 OK");
     let result = shell::run_openclaw(&["agent", "--local", "--to", "+1234567890", "--message", "回复 OK"]);
-    
+
     let latency = start.elapsed().as_millis() as u64;
     info!("[AI测试] 命令执行完成, 耗时: {}ms", latency);
-    
+
     match result {
         Ok(output) => {
             debug!("[AI测试] 原始输出: {}", output);
@@ -195,17 +252,17 @@ pub async fn test_ai_connection() -> Result<AITestResult, String> {
                 .filter(|l: &&str| !l.contains("ExperimentalWarning"))
                 .collect::<Vec<&str>>()
                 .join("\n");
-            
+        
             let success = !filtered.to_lowercase().contains("error")
                 && !filtered.contains("401")
                 && !filtered.contains("403");
-            
+        
             if success {
                 info!("[AI测试] ✓ AI 连接测试成功");
             } else {
                 warn!("[AI测试] ✗ AI 连接测试失败: {}", filtered);
             }
-            
+        
             Ok(AITestResult {
                 success,
                 provider: "current".to_string(),
@@ -282,28 +339,29 @@ fn parse_channel_status_text(output: &str, channel_type: &str) -> Option<(bool,
 }
 
 /// 测试渠道连接（检查状态并发送测试消息）
+#[guarded]
 #[command]
 pub async fn test_channel(channel_type: String) -> Result<ChannelTestResult, String> {
     info!("[渠道测试] 测试渠道: {}", channel_type);
     let channel_lower = channel_type.to_lowercase();
-    
+
     // 使用 openclaw channels status 检查渠道状态（不加 --json，因为可能不支持）
     info!("[渠道测试] 步骤1: 检查渠道状态...");
     let status_result = shell::run_openclaw(&["channels", "status"]);
-    
+
     let mut channel_ok = false;
     let mut status_message = String::new();
     let mut debug_info = String::new();
-    
+
     match &status_result {
         Ok(output) => {
             info!("[渠道测试] status 命令执行成功");
-            
+        
             // 尝试从文本输出解析状态
             if let Some((enabled, configured, linked, status_msg)) = parse_channel_status_text(output, &channel_type) {
                 debug_info = format!("enabled={}, configured={}, linked={}", enabled, configured, linked);
                 info!("[渠道测试] {} 状态: {}", channel_type, debug_info);
-                
+            
                 if !configured {
                     info!("[渠道测试] {} 未配置", channel_type);
                     return Ok(ChannelTestResult {
@@ -313,7 +371,7 @@ pub async fn test_channel(channel_type: String) -> Result<ChannelTestResult, Str
                         error: Some(format!("请运行: openclaw channels add --channel {}", channel_lower)),
                     });
                 }
-                
+            
                 // 已配置就认为状态OK（Gateway可能没启动，但配置是有的）
                 channel_ok = configured;
                 status_message = if linked {
@@ -337,7 +395,7 @@ pub async fn test_channel(channel_type: String) -> Result<ChannelTestResult, Str
                         }
                     }
                 }
-                
+            
                 if !channel_ok {
                     debug_info = format!("无法解析 {} 的状态", channel_type);
                     info!("[渠道测试] {}", debug_info);
@@ -349,7 +407,7 @@ pub async fn test_channel(channel_type: String) -> Result<ChannelTestResult, Str
             info!("[渠道测试] {}", debug_info);
         }
     }
-    
+
     // 如果渠道状态不 OK，直接返回失败
     if !channel_ok {
         info!("[渠道测试] {} 状态检查失败，不发送测试消息", channel_type);
@@ -365,9 +423,9 @@ pub async fn test_channel(channel_type: String) -> Result<ChannelTestResult, Str
             error: Some(error_msg),
         });
     }
-    
+
     info!("[渠道测试] {} 状态正常 ({})", channel_type, status_message);
-    
+
     // 对于 WhatsApp 和 iMessage，只返回状态检查结果，不发送测试消息
     if !channel_needs_send_test(&channel_type) {
         info!("[渠道测试] {} 不需要发送测试消息（状态检查即可）", channel_type);
@@ -378,16 +436,16 @@ pub async fn test_channel(channel_type: String) -> Result<ChannelTestResult, Str
             error: None,
         });
     }
-    
+
     // 尝试发送测试消息
     info!("[渠道测试] 步骤2: 获取测试目标...");
     let test_target = get_channel_test_target(&channel_type);
-    
+
     if let Some(target) = test_target {
         info!("[渠道测试] 步骤3: 发送测试消息到 {}...", target);
-        let timestamp = chrono::Local::now().format("%Y-%m-%d %H:%M:%S");
+        let timestamp = time::now().format("%Y-%m-%d %H:%M:%S");
         let message = format!("🤖 OpenClaw 测试消息\n\n✅ 连接成功！\n⏰ {}", timestamp);
-        
+    
         // 使用 openclaw message send 发送测试消息
         info!("[渠道测试] 执行: openclaw message send --channel {} --target {} ...", channel_lower, target);
         let send_result = shell::run_openclaw(&[
@@ -397,11 +455,11 @@ pub async fn test_channel(channel_type: String) -> Result<ChannelTestResult, Str
             "--message", &message,
             "--json"
         ]);
-        
+    
         match send_result {
             Ok(output) => {
                 info!("[渠道测试] 发送命令输出长度: {}", output.len());
-                
+            
                 // 检查发送是否成功
                 let send_ok = if let Some(json_str) = extract_json_from_output(&output) {
                     info!("[渠道测试] 提取到 JSON: {}", json_str);
@@ -416,10 +474,10 @@ pub async fn test_channel(channel_type: String) -> Result<ChannelTestResult, Str
                             .and_then(|p| p.get("result"))
                             .and_then(|r| r.get("messageId"))
                             .is_some();
-                        
+                    
                         info!("[渠道测试] 判断条件: ok={}, success={}, messageId={}, payload.ok={}, payload.messageId={}, payload.result.messageId={}",
                             has_ok, has_success, has_message_id, has_payload_ok, has_payload_message_id, has_payload_result_message_id);
-                        
+                    
                         has_ok || has_success || has_message_id || has_payload_ok || has_payload_message_id || has_payload_result_message_id
                     } else {
                         info!("[渠道测试] JSON 解析失败");
@@ -430,7 +488,7 @@ pub async fn test_channel(channel_type: String) -> Result<ChannelTestResult, Str
                     // 如果没有 JSON，检查是否有错误关键词
                     !output.to_lowercase().contains("error") && !output.to_lowercase().contains("failed")
                 };
-                
+            
                 if send_ok {
                     info!("[渠道测试] ✓ {} 测试消息发送成功", channel_type);
                     Ok(ChannelTestResult {
@@ -468,7 +526,7 @@ pub async fn test_channel(channel_type: String) -> Result<ChannelTestResult, Str
             "feishu" => "请配置 OPENCLAW_FEISHU_TESTCHATID",
             _ => "请配置测试目标",
         };
-        
+    
         info!("[渠道测试] {} 未配置测试目标，跳过发送消息 ({})", channel_type, hint);
         Ok(ChannelTestResult {
             success: true,
@@ -480,11 +538,12 @@ pub async fn test_channel(channel_type: String) -> Result<ChannelTestResult, Str
 }
 
 /// 发送测试消息到渠道
+#[guarded]
 #[command]
 pub async fn send_test_message(channel_type: String, target: String) -> Result<ChannelTestResult, String> {
-    let timestamp = chrono::Local::now().format("%Y-%m-%d %H:%M:%S");
+    let timestamp = time::now().format("%Y-%m-%d %H:%M:%S");
     let message = format!("🤖 OpenClaw 测试消息\n\n✅ 连接成功！\n⏰ {}", timestamp);
-    
+
     // 使用 openclaw message send 命令发送测试消息
     let send_result = shell::run_openclaw(&[
         "message", "send",
@@ -493,7 +552,7 @@ pub async fn send_test_message(channel_type: String, target: String) -> Result<C
         "--message", &message,
         "--json"
     ]);
-    
+
     match send_result {
         Ok(output) => {
             // 尝试从混合输出中提取并解析 JSON 结果
@@ -509,7 +568,7 @@ pub async fn send_test_message(channel_type: String, target: String) -> Result<C
                 // 非 JSON 输出，检查是否包含错误关键词
                 !output.to_lowercase().contains("error") && !output.to_lowercase().contains("failed")
             };
-            
+        
             Ok(ChannelTestResult {
                 success,
                 channel: channel_type,
@@ -527,13 +586,14 @@ pub async fn send_test_message(channel_type: String, target: String) -> Result<C
 }
 
 /// 获取系统信息
+#[guarded]
 #[command]
 pub async fn get_system_info() -> Result<SystemInfo, String> {
     info!("[系统信息] 获取系统信息...");
     let os = platform::get_os();
     let arch = platform::get_arch();
     info!("[系统信息] OS: {}, Arch: {}", os, arch);
-    
+
     // 获取 OS 版本
     let os_version = if platform::is_macos() {
         shell::run_command_output("sw_vers", &["-productVersion"])
@@ -544,16 +604,16 @@ pub async fn get_system_info() -> Result<SystemInfo, String> {
     } else {
         "unknown".to_string()
     };
-    
+
     let openclaw_installed = shell::get_openclaw_path().is_some();
     let openclaw_version = if openclaw_installed {
         shell::run_openclaw(&["--version"]).ok()
     } else {
         None
     };
-    
+
     let node_version = shell::run_command_output("node", &["--version"]).ok();
-    
+
     Ok(SystemInfo {
         os,
         os_version,
@@ -566,17 +626,18 @@ pub async fn get_system_info() -> Result<SystemInfo, String> {
 }
 
 /// 启动渠道登录（如 WhatsApp 扫码）
+#[guarded]
 #[command]
 pub async fn start_channel_login(channel_type: String) -> Result<String, String> {
     info!("[渠道登录] 开始渠道登录流程: {}", channel_type);
-    
+
     match channel_type.as_str() {
         "whatsapp" => {
             info!("[渠道登录] WhatsApp 登录流程...");
             // 先在后台启用插件
             info!("[渠道登录] 启用 whatsapp 插件...");
             let _ = shell::run_openclaw(&["plugins", "enable", "whatsapp"]);
-            
+        
             #[cfg(target_os = "macos")]
             {
                 let env_path = platform::get_env_file_path();
@@ -657,24 +718,17 @@ read -p "按回车键关闭此窗口..."
 "#,
                     env_path
                 );
-                
-                let script_path = "/tmp/openclaw_whatsapp_login.command";
-                std::fs::write(script_path, script_content)
+            
+                let script_path = temp::create_script("whatsapp_login.command", &script_content)
                     .map_err(|e| format!("创建脚本失败: {}", e))?;
-                
-                // 设置可执行权限
-                std::process::Command::new("chmod")
-                    .args(["+x", script_path])
-                    .output()
-                    .map_err(|e| format!("设置权限失败: {}", e))?;
-                
+            
                 // 使用 open 命令打开 .command 文件（会自动在新终端窗口中执行）
                 std::process::Command::new("open")
-                    .arg(script_path)
+                    .arg(&script_path)
                     .spawn()
                     .map_err(|e| format!("启动终端失败: {}", e))?;
             }
-            
+        
             #[cfg(target_os = "linux")]
             {
                 let env_path = platform::get_env_file_path();
@@ -691,41 +745,37 @@ read -p "按回车键关闭..."
 "#,
                     env_path
                 );
-                
-                let script_path = "/tmp/openclaw_whatsapp_login.sh";
-                std::fs::write(script_path, &script_content)
+            
+                let script_path = temp::create_script("whatsapp_login.sh", &script_content)
                     .map_err(|e| format!("创建脚本失败: {}", e))?;
-                
-                std::process::Command::new("chmod")
-                    .args(["+x", script_path])
-                    .output()
-                    .map_err(|e| format!("设置权限失败: {}", e))?;
-                
+            
                 // 尝试不同的终端模拟器
                 let terminals = ["gnome-terminal", "xfce4-terminal", "konsole", "xterm"];
                 let mut launched = false;
-                
+            
                 for term in terminals {
                     let result = std::process::Command::new(term)
-                        .args(["--", script_path])
+                        .arg("--")
+                        .arg(&script_path)
+                        .envs(platform::profile_env())
                         .spawn();
-                    
+                
                     if result.is_ok() {
                         launched = true;
                         break;
                     }
                 }
-                
+            
                 if !launched {
                     return Err("无法启动终端，请手动运行: openclaw channels login --channel whatsapp".to_string());
                 }
             }
-            
+        
             #[cfg(target_os = "windows")]
             {
                 return Err("Windows 暂不支持自动启动终端，请手动运行: openclaw channels login --channel whatsapp".to_string());
             }
-            
+        
             Ok("已在新终端窗口中启动 WhatsApp 登录，请查看弹出的终端窗口并扫描二维码".to_string())
         }
         _ => Err(format!("不支持 {} 的登录向导", channel_type)),
@@ -0,0 +1,279 @@
+//! 通知摘要：开启后，非紧急的通知（技能更新、工作区告警等）不再逐条发送，而是记入当天的摘要，
+//! 由后台监控在每天设定的时间把前一天的摘要汇总为一条通知发送；
+//! 历史摘要可通过 get_daily_digest 按日期查询
+use crate::commands::notifications::{self, NotificationTrigger};
+use crate::utils::{file, platform, time};
+use chrono::{NaiveDate, NaiveTime};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use openclaw_macros::guarded;
+use tauri::command;
+
+/// 保留的摘要天数
+const HISTORY_DAYS: usize = 30;
+/// 摘要通知中最多列出的条目数
+const MAX_LINES: usize = 10;
+
+/// 通知摘要设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DigestSettings {
+    pub enabled: bool,
+    /// 记入摘要的触发器（其余触发器仍即时通知）
+    pub triggers: Vec<NotificationTrigger>,
+    /// 每天发送摘要的时间（HH:MM，设置中的时区）
+    pub send_at: String,
+}
+
+impl Default for DigestSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            triggers: vec![
+                NotificationTrigger::UpdateAvailable,
+                NotificationTrigger::InstallFinished,
+                NotificationTrigger::SkillUpdates,
+                NotificationTrigger::WorkspaceAlert,
+            ],
+            send_at: "09:00".to_string(),
+        }
+    }
+}
+
+/// 摘要中的一条通知（同一触发器、同一对象的通知合并计数）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DigestEntry {
+    pub trigger: NotificationTrigger,
+    pub key: String,
+    pub title: String,
+    pub body: String,
+    pub count: u32,
+    pub first_at: String,
+    pub last_at: String,
+}
+
+/// 某一天的通知摘要
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DailyDigest {
+    /// YYYY-MM-DD（设置中的时区）
+    pub date: String,
+    pub entries: Vec<DigestEntry>,
+    /// 摘要通知的发送时间（尚未发送时为 None）
+    pub sent_at: Option<String>,
+}
+
+/// 摘要文件的读写锁
+static LOCK: Mutex<()> = Mutex::new(());
+
+fn digest_path() -> PathBuf {
+    Path::new(&platform::get_manager_data_dir()).join("notification_digest.json")
+}
+
+fn load() -> BTreeMap<String, DailyDigest> {
+    std::fs::read_to_string(digest_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save(digests: &mut BTreeMap<String, DailyDigest>) {
+    while digests.len() > HISTORY_DAYS {
+        digests.pop_first();
+    }
+    let result = serde_json::to_vec_pretty(digests)
+        .map_err(|e| e.to_string())
+        .and_then(|content| {
+            file::write_atomic(&digest_path(), &content).map_err(|e| e.to_string())
+        });
+    if let Err(e) = result {
+        warn!("[通知摘要] 保存失败: {}", e);
+    }
+}
+
+/// 该触发器的通知是否记入摘要
+pub fn should_batch(settings: &DigestSettings, trigger: NotificationTrigger) -> bool {
+    settings.enabled
+        && trigger != NotificationTrigger::DailyDigest
+        && settings.triggers.contains(&trigger)
+}
+
+/// 加入摘要：同一触发器、同一对象只保留最新的内容并累加次数
+fn add_entry(
+    digest: &mut DailyDigest,
+    trigger: NotificationTrigger,
+    key: &str,
+    title: &str,
+    body: &str,
+    at: &str,
+) {
+    match digest
+        .entries
+        .iter_mut()
+        .find(|e| e.trigger == trigger && e.key == key)
+    {
+        Some(entry) => {
+            entry.title = title.to_string();
+            entry.body = body.to_string();
+            entry.count += 1;
+            entry.last_at = at.to_string();
+        }
+        None => digest.entries.push(DigestEntry {
+            trigger,
+            key: key.to_string(),
+            title: title.to_string(),
+            body: body.to_string(),
+            count: 1,
+            first_at: at.to_string(),
+            last_at: at.to_string(),
+        }),
+    }
+}
+
+/// 记入当天的摘要（由 notifications::notify 调用）
+pub fn record(trigger: NotificationTrigger, key: &str, title: &str, body: &str) {
+    let now = time::now();
+    let date = now.format("%Y-%m-%d").to_string();
+    let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut digests = load();
+    let digest = digests.entry(date.clone()).or_insert_with(|| DailyDigest {
+        date,
+        ..Default::default()
+    });
+    add_entry(digest, trigger, key, title, body, &now.to_rfc3339());
+    save(&mut digests);
+}
+
+/// 汇总为一条通知：(标题, 正文)
+fn summarize(digest: &DailyDigest) -> (String, String) {
+    let total: u32 = digest.entries.iter().map(|e| e.count).sum();
+    let mut lines: Vec<String> = digest
+        .entries
+        .iter()
+        .take(MAX_LINES)
+        .map(|e| {
+            let repeat = if e.count > 1 {
+                format!("（{} 次）", e.count)
+            } else {
+                String::new()
+            };
+            format!("• {}{}：{}", e.title, repeat, e.body)
+        })
+        .collect();
+    if digest.entries.len() > MAX_LINES {
+        lines.push(format!("…另有 {} 项", digest.entries.len() - MAX_LINES));
+    }
+    (
+        format!("OpenClaw 每日摘要（{}，{} 条通知）", digest.date, total),
+        lines.join("\n"),
+    )
+}
+
+/// 后台监控定期调用：到达发送时间后，发送此前各天尚未发送的摘要
+pub fn tick() {
+    let settings = notifications::load_notification_settings().digest;
+    if !settings.enabled {
+        return;
+    }
+    let now = time::now();
+    let send_at =
+        NaiveTime::parse_from_str(settings.send_at.trim(), "%H:%M").unwrap_or(NaiveTime::MIN);
+    if now.time() < send_at {
+        return;
+    }
+    let today = now.format("%Y-%m-%d").to_string();
+    let pending: Vec<DailyDigest> = {
+        let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let mut digests = load();
+        let pending: Vec<DailyDigest> = digests
+            .values_mut()
+            .filter(|d| d.date < today && d.sent_at.is_none())
+            .map(|d| {
+                d.sent_at = Some(now.to_rfc3339());
+                d.clone()
+            })
+            .collect();
+        if !pending.is_empty() {
+            save(&mut digests);
+        }
+        pending
+    };
+    for digest in pending.iter().filter(|d| !d.entries.is_empty()) {
+        let (title, body) = summarize(digest);
+        info!("[通知摘要] 发送 {} 的摘要", digest.date);
+        notifications::notify(
+            NotificationTrigger::DailyDigest,
+            &digest.date,
+            &title,
+            &body,
+        );
+    }
+}
+
+/// 获取某一天（YYYY-MM-DD，默认今天）的通知摘要
+#[guarded]
+#[command]
+pub async fn get_daily_digest(date: Option<String>) -> Result<DailyDigest, String> {
+    let date = match date.as_deref().map(str::trim).filter(|d| !d.is_empty()) {
+        Some(date) => NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .map_err(|_| format!("日期格式不正确: {}（应为 YYYY-MM-DD）", date))?
+            .format("%Y-%m-%d")
+            .to_string(),
+        None => time::now().format("%Y-%m-%d").to_string(),
+    };
+    let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    Ok(load().remove(&date).unwrap_or(DailyDigest {
+        date,
+        ..Default::default()
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn batches_minor_triggers_and_merges_repeats() {
+        let mut settings = DigestSettings::default();
+        assert!(!should_batch(&settings, NotificationTrigger::SkillUpdates));
+        settings.enabled = true;
+        assert!(should_batch(&settings, NotificationTrigger::SkillUpdates));
+        assert!(!should_batch(
+            &settings,
+            NotificationTrigger::GatewayCrashed
+        ));
+        settings.triggers.push(NotificationTrigger::DailyDigest);
+        assert!(!should_batch(&settings, NotificationTrigger::DailyDigest));
+
+        let mut digest = DailyDigest {
+            date: "2026-10-15".to_string(),
+            ..Default::default()
+        };
+        let skill = NotificationTrigger::SkillUpdates;
+        add_entry(&mut digest, skill, "skills", "技能有更新", "2 个技能", "t1");
+        add_entry(&mut digest, skill, "skills", "技能有更新", "3 个技能", "t2");
+        let workspace = NotificationTrigger::WorkspaceAlert;
+        add_entry(
+            &mut digest,
+            workspace,
+            "main",
+            "工作区告警",
+            "超出容量",
+            "t3",
+        );
+        assert_eq!(digest.entries.len(), 2);
+        assert_eq!(digest.entries[0].count, 2);
+        assert_eq!(digest.entries[0].body, "3 个技能");
+        assert_eq!(digest.entries[0].first_at, "t1");
+        assert_eq!(digest.entries[0].last_at, "t2");
+
+        let (title, body) = summarize(&digest);
+        assert_eq!(title, "OpenClaw 每日摘要（2026-10-15，3 条通知）");
+        assert_eq!(
+            body,
+            "• 技能有更新（2 次）：3 个技能\n• 工作区告警：超出容量"
+        );
+    }
+}
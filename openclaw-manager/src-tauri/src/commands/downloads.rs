@@ -0,0 +1,513 @@
+use crate::commands::{events, settings, shutdown};
+use crate::utils::{platform, time};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use openclaw_macros::guarded;
+use tauri::command;
+
+/// 下载进度事件的最小间隔
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// 下载目录：cache/<sha256>/<文件名> 为已校验的内容寻址缓存，partial/ 为未完成的下载
+fn downloads_dir() -> PathBuf {
+    Path::new(&platform::get_manager_data_dir()).join("downloads")
+}
+
+/// 未完成下载的文件名（按 URL 区分，便于中断后续传）
+fn partial_name(url: &str) -> String {
+    format!(
+        "{}.part",
+        &hex::encode(Sha256::digest(url.as_bytes()))[..16]
+    )
+}
+
+/// 解析 Content-Range 中的起始位置（`bytes 100-199/200`）
+fn content_range_start(header: &str) -> Option<u64> {
+    header
+        .strip_prefix("bytes ")?
+        .split_once('-')?
+        .0
+        .trim()
+        .parse()
+        .ok()
+}
+
+/// 对已下载的部分重新计算哈希，续传时接着更新
+fn hash_existing(path: &Path) -> std::io::Result<(Sha256, u64)> {
+    let mut hasher = Sha256::new();
+    let mut file = std::fs::File::open(path)?;
+    let mut buf = vec![0u8; 64 * 1024];
+    let mut len = 0u64;
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+        len += n as u64;
+    }
+    Ok((hasher, len))
+}
+
+/// 进行中的下载（用于进度查询和暂停 / 继续）
+struct ActiveDownload {
+    id: String,
+    url: String,
+    file_name: String,
+    downloaded: AtomicU64,
+    /// 0 表示未知
+    total: AtomicU64,
+    /// 本次请求的平均速度（字节/秒），0 表示未知
+    rate: AtomicU64,
+    paused: AtomicBool,
+}
+
+impl ActiveDownload {
+    fn info(&self) -> DownloadInfo {
+        let downloaded = self.downloaded.load(Ordering::Relaxed);
+        let total = self.total.load(Ordering::Relaxed);
+        let rate = self.rate.load(Ordering::Relaxed);
+        let paused = self.paused.load(Ordering::Relaxed);
+        DownloadInfo {
+            id: self.id.clone(),
+            url: self.url.clone(),
+            file_name: self.file_name.clone(),
+            downloaded,
+            total: (total > 0).then_some(total),
+            bytes_per_sec: (rate > 0 && !paused).then_some(rate),
+            eta_secs: if paused {
+                None
+            } else {
+                eta_secs(downloaded, total, rate)
+            },
+            paused,
+        }
+    }
+}
+
+/// 剩余时间（秒）：按当前速度估算，总大小或速度未知时为空
+fn eta_secs(downloaded: u64, total: u64, rate: u64) -> Option<u64> {
+    (total > 0 && rate > 0).then(|| total.saturating_sub(downloaded).div_ceil(rate))
+}
+
+static ACTIVE: Mutex<Option<HashMap<String, Arc<ActiveDownload>>>> = Mutex::new(None);
+
+/// 进行中的下载信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DownloadInfo {
+    pub id: String,
+    pub url: String,
+    pub file_name: String,
+    pub downloaded: u64,
+    pub total: Option<u64>,
+    /// 下载速度（字节/秒）
+    pub bytes_per_sec: Option<u64>,
+    /// 预计剩余时间（秒）
+    pub eta_secs: Option<u64>,
+    pub paused: bool,
+}
+
+/// 下载注册表项，drop 时移除
+struct Registration(String);
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        if let Ok(mut active) = ACTIVE.lock() {
+            if let Some(map) = active.as_mut() {
+                map.remove(&self.0);
+            }
+        }
+    }
+}
+
+fn register(id: &str, url: &str, file_name: &str) -> (Arc<ActiveDownload>, Registration) {
+    let entry = Arc::new(ActiveDownload {
+        id: id.to_string(),
+        url: url.to_string(),
+        file_name: file_name.to_string(),
+        downloaded: AtomicU64::new(0),
+        total: AtomicU64::new(0),
+        rate: AtomicU64::new(0),
+        paused: AtomicBool::new(false),
+    });
+    if let Ok(mut active) = ACTIVE.lock() {
+        active
+            .get_or_insert_with(HashMap::new)
+            .insert(id.to_string(), entry.clone());
+    }
+    (entry, Registration(id.to_string()))
+}
+
+/// 限速时本次已下载的数据需要等待多久（按从开始到现在的平均速度计算）
+fn throttle_delay(bytes: u64, elapsed: Duration, limit_kbps: u64) -> Duration {
+    if limit_kbps == 0 {
+        return Duration::ZERO;
+    }
+    let expected = Duration::from_secs_f64(bytes as f64 / (limit_kbps as f64 * 1024.0));
+    expected.saturating_sub(elapsed)
+}
+
+/// 单次请求的结果
+enum Attempt {
+    Finished,
+    /// 用户暂停，连接已断开，继续时从断点续传
+    Paused,
+}
+
+/// 下载文件到内容寻址缓存，返回本地路径
+/// 已知校验值且缓存命中时不再下载；中断后再次调用会通过 HTTP Range 续传
+/// 按设置中的 download_limit_kbps 限速，下载中可通过 pause_download / resume_download 暂停和继续
+pub async fn fetch(
+    client: &reqwest::Client,
+    url: &str,
+    file_name: &str,
+    expected_sha256: Option<&str>,
+) -> Result<PathBuf, String> {
+    let dir = downloads_dir();
+    if let Some(expected) = expected_sha256 {
+        let cached = dir.join("cache").join(expected).join(file_name);
+        if cached.exists() {
+            info!("[下载] 使用缓存 {}", cached.display());
+            return Ok(cached);
+        }
+    }
+
+    let partial_dir = dir.join("partial");
+    std::fs::create_dir_all(&partial_dir).map_err(|e| format!("创建下载目录失败: {}", e))?;
+    let name = partial_name(url);
+    let partial = partial_dir.join(&name);
+    let id = name.trim_end_matches(".part").to_string();
+    let (control, _registration) = register(&id, url, file_name);
+    let _operation = shutdown::begin_operation(&format!("下载 {}", file_name));
+    let limit_kbps = settings::load_settings().download_limit_kbps;
+
+    let mut failures = 0;
+    let hasher = loop {
+        while control.paused.load(Ordering::Relaxed) && !shutdown::is_shutting_down() {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+        }
+        if shutdown::is_shutting_down() {
+            return Err(format!(
+                "Manager 正在退出，{} 的下载已中止（再次下载会续传）",
+                file_name
+            ));
+        }
+        let (hasher, offset) = if partial.exists() {
+            hash_existing(&partial).map_err(|e| format!("读取未完成的下载失败: {}", e))?
+        } else {
+            (Sha256::new(), 0)
+        };
+        match attempt(
+            client, url, file_name, &partial, hasher, offset, &control, limit_kbps,
+        )
+        .await
+        {
+            Ok((Attempt::Finished, hasher)) => break hasher,
+            Ok((Attempt::Paused, _)) => info!("[下载] 已暂停 {}", file_name),
+            Err((e, progressed)) => {
+                // 有进展的中断（如限速导致请求超时）自动续传，连续失败则放弃
+                failures = if progressed { 0 } else { failures + 1 };
+                if failures >= 3 {
+                    return Err(e);
+                }
+                warn!("[下载] {}，稍后续传", e);
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+        }
+    };
+
+    let actual = hex::encode(hasher.finalize());
+    if let Some(expected) = expected_sha256 {
+        if actual != expected {
+            let _ = std::fs::remove_file(&partial);
+            return Err(format!(
+                "{} 校验失败（期望 {}，实际 {}）",
+                file_name, expected, actual
+            ));
+        }
+    }
+
+    let target_dir = dir.join("cache").join(&actual);
+    std::fs::create_dir_all(&target_dir).map_err(|e| format!("创建缓存目录失败: {}", e))?;
+    let target = target_dir.join(file_name);
+    std::fs::rename(&partial, &target).map_err(|e| format!("保存下载文件失败: {}", e))?;
+    info!("[下载] ✓ {} ({})", file_name, actual);
+    Ok(target)
+}
+
+/// 发起一次（续传）请求并写入未完成文件；出错时返回是否写入了新数据
+#[allow(clippy::too_many_arguments)]
+async fn attempt(
+    client: &reqwest::Client,
+    url: &str,
+    file_name: &str,
+    partial: &Path,
+    mut hasher: Sha256,
+    mut offset: u64,
+    control: &ActiveDownload,
+    limit_kbps: u64,
+) -> Result<(Attempt, Sha256), (String, bool)> {
+    let mut request = client.get(url);
+    if offset > 0 {
+        info!("[下载] 从 {} 字节处续传 {}", offset, url);
+        request = request.header(reqwest::header::RANGE, format!("bytes={}-", offset));
+    } else {
+        info!("[下载] {}", url);
+    }
+    let failed = |e: reqwest::Error| (format!("下载 {} 失败: {}", file_name, e), false);
+    let mut response = request.send().await.map_err(failed)?;
+    if response.status() == reqwest::StatusCode::RANGE_NOT_SATISFIABLE {
+        // 未完成的文件比服务器上的还大（文件已更新），丢弃后从头下载
+        warn!("[下载] 续传位置无效，重新下载 {}", file_name);
+        response = client.get(url).send().await.map_err(failed)?;
+    }
+    let mut response = response.error_for_status().map_err(failed)?;
+
+    // 服务器不支持 Range（返回 200）或起始位置不一致时从头下载
+    let resumed = response.status() == reqwest::StatusCode::PARTIAL_CONTENT
+        && response
+            .headers()
+            .get(reqwest::header::CONTENT_RANGE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(content_range_start)
+            == Some(offset);
+    if offset > 0 && !resumed {
+        warn!("[下载] 服务器不支持续传，重新下载 {}", file_name);
+        hasher = Sha256::new();
+        offset = 0;
+    }
+    if let Some(len) = response.content_length() {
+        control.total.store(offset + len, Ordering::Relaxed);
+    }
+    control.downloaded.store(offset, Ordering::Relaxed);
+    control.rate.store(0, Ordering::Relaxed);
+
+    let write_failed = |e: std::io::Error| (format!("写入下载文件失败: {}", e), false);
+    let mut out = std::fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(offset > 0)
+        .truncate(offset == 0)
+        .open(partial)
+        .map_err(write_failed)?;
+
+    let start = Instant::now();
+    let mut last_event = start;
+    let mut received = 0u64;
+    loop {
+        if control.paused.load(Ordering::Relaxed) || shutdown::is_shutting_down() {
+            out.sync_all().map_err(write_failed)?;
+            return Ok((Attempt::Paused, hasher));
+        }
+        let chunk = match response.chunk().await {
+            Ok(Some(chunk)) => chunk,
+            Ok(None) => break,
+            Err(e) => {
+                let _ = out.sync_all();
+                return Err((
+                    format!("下载 {} 中断（再次下载会续传）: {}", file_name, e),
+                    received > 0,
+                ));
+            }
+        };
+        hasher.update(&chunk);
+        out.write_all(&chunk).map_err(write_failed)?;
+        received += chunk.len() as u64;
+        control
+            .downloaded
+            .store(offset + received, Ordering::Relaxed);
+        if last_event.elapsed() >= PROGRESS_INTERVAL {
+            last_event = Instant::now();
+            let rate = received as f64 / start.elapsed().as_secs_f64();
+            control.rate.store(rate as u64, Ordering::Relaxed);
+            events::publish(events::DOWNLOAD_PROGRESS_EVENT, control.info());
+        }
+        let delay = throttle_delay(received, start.elapsed(), limit_kbps);
+        if !delay.is_zero() {
+            tokio::time::sleep(delay).await;
+        }
+    }
+    out.sync_all().map_err(write_failed)?;
+    Ok((Attempt::Finished, hasher))
+}
+
+fn set_paused(id: &str, paused: bool) -> Result<(), String> {
+    let active = ACTIVE.lock().map_err(|e| e.to_string())?;
+    let entry = active
+        .as_ref()
+        .and_then(|map| map.get(id))
+        .ok_or_else(|| format!("下载 {} 不存在或已完成", id))?;
+    entry.paused.store(paused, Ordering::Relaxed);
+    info!(
+        "[下载] {} {}",
+        if paused { "暂停" } else { "继续" },
+        entry.file_name
+    );
+    Ok(())
+}
+
+/// 获取进行中的下载
+#[guarded]
+#[command]
+pub async fn list_downloads() -> Result<Vec<DownloadInfo>, String> {
+    let active = ACTIVE.lock().map_err(|e| e.to_string())?;
+    let mut downloads: Vec<DownloadInfo> = active
+        .iter()
+        .flat_map(|map| map.values())
+        .map(|d| d.info())
+        .collect();
+    downloads.sort_by(|a, b| a.file_name.cmp(&b.file_name));
+    Ok(downloads)
+}
+
+/// 暂停下载（断开连接，保留已下载部分）
+#[guarded]
+#[command]
+pub async fn pause_download(id: String) -> Result<(), String> {
+    set_paused(&id, true)
+}
+
+/// 继续已暂停的下载（从断点续传）
+#[guarded]
+#[command]
+pub async fn resume_download(id: String) -> Result<(), String> {
+    set_paused(&id, false)
+}
+
+/// 缓存中的单个文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedDownload {
+    pub sha256: String,
+    pub file_name: String,
+    pub size: u64,
+    pub modified: Option<String>,
+}
+
+/// 下载缓存占用
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DownloadCacheUsage {
+    pub path: String,
+    pub entries: Vec<CachedDownload>,
+    /// 未完成（可续传）的下载占用
+    pub partial_bytes: u64,
+    pub total_bytes: u64,
+}
+
+fn cache_usage(dir: &Path) -> DownloadCacheUsage {
+    let mut entries = Vec::new();
+    for hash_dir in std::fs::read_dir(dir.join("cache"))
+        .into_iter()
+        .flatten()
+        .flatten()
+    {
+        for file in std::fs::read_dir(hash_dir.path())
+            .into_iter()
+            .flatten()
+            .flatten()
+        {
+            let Ok(meta) = file.metadata() else {
+                continue;
+            };
+            entries.push(CachedDownload {
+                sha256: hash_dir.file_name().to_string_lossy().to_string(),
+                file_name: file.file_name().to_string_lossy().to_string(),
+                size: meta.len(),
+                modified: meta
+                    .modified()
+                    .ok()
+                    .map(|t| time::from_system_time(t).to_rfc3339()),
+            });
+        }
+    }
+    entries.sort_by_key(|e| std::cmp::Reverse(e.size));
+    let partial_bytes: u64 = std::fs::read_dir(dir.join("partial"))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|e| e.metadata().ok())
+        .map(|m| m.len())
+        .sum();
+    let total_bytes = partial_bytes + entries.iter().map(|e| e.size).sum::<u64>();
+    DownloadCacheUsage {
+        path: dir.to_string_lossy().to_string(),
+        entries,
+        partial_bytes,
+        total_bytes,
+    }
+}
+
+/// 获取下载缓存占用
+#[guarded]
+#[command]
+pub async fn get_download_cache_usage() -> Result<DownloadCacheUsage, String> {
+    Ok(cache_usage(&downloads_dir()))
+}
+
+/// 清空下载缓存（包括未完成的下载），返回释放的字节数
+#[guarded]
+#[command]
+pub async fn clear_download_cache() -> Result<u64, String> {
+    let dir = downloads_dir();
+    let usage = cache_usage(&dir);
+    for sub in ["cache", "partial"] {
+        let path = dir.join(sub);
+        if path.exists() {
+            std::fs::remove_dir_all(&path).map_err(|e| format!("清空下载缓存失败: {}", e))?;
+        }
+    }
+    info!("[下载] ✓ 已清空下载缓存，释放 {} 字节", usage.total_bytes);
+    Ok(usage.total_bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn content_range_and_cache_usage() {
+        assert_eq!(content_range_start("bytes 100-199/200"), Some(100));
+        assert_eq!(content_range_start("bytes */200"), None);
+
+        // 100 KB/s 限速下 1 秒内收到 200 KB，需要再等 1 秒
+        let delay = throttle_delay(200 * 1024, Duration::from_secs(1), 100);
+        assert_eq!(delay.as_secs(), 1);
+        assert!(throttle_delay(200 * 1024, Duration::from_secs(3), 100).is_zero());
+        assert!(throttle_delay(u64::MAX / 2, Duration::ZERO, 0).is_zero());
+
+        // 还剩 250 KB，每秒 100 KB，约 3 秒
+        assert_eq!(eta_secs(750 * 1024, 1000 * 1024, 100 * 1024), Some(3));
+        assert_eq!(eta_secs(100, 0, 100), None);
+        assert_eq!(eta_secs(100, 200, 0), None);
+
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!("openclaw-downloads-{}", nanos));
+        let entry = dir.join("cache").join("abc123");
+        std::fs::create_dir_all(&entry).unwrap();
+        std::fs::create_dir_all(dir.join("partial")).unwrap();
+        std::fs::write(entry.join("node-v22.pkg"), vec![0u8; 10]).unwrap();
+        std::fs::write(
+            dir.join("partial").join(partial_name("https://x")),
+            [0u8; 5],
+        )
+        .unwrap();
+
+        let usage = cache_usage(&dir);
+        assert_eq!(usage.entries.len(), 1);
+        assert_eq!(usage.entries[0].sha256, "abc123");
+        assert_eq!(usage.entries[0].file_name, "node-v22.pkg");
+        assert_eq!(usage.partial_bytes, 5);
+        assert_eq!(usage.total_bytes, 15);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,405 @@
+//! 向量嵌入（Embedding）Provider 配置：长期记忆 / 知识库检索可以使用与对话模型不同的
+//! Provider，如用 Ollama 本地 bge 生成向量、用 OpenAI 对话，写入
+//! agents.defaults.memorySearch
+use crate::commands::{config, config_lint, privacy, secret_handles};
+use crate::utils::{audit, http};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::time::Instant;
+use openclaw_macros::guarded;
+use tauri::command;
+
+const PROVIDERS: &[&str] = &["openai", "gemini", "local"];
+const GEMINI_BASE_URL: &str = "https://generativelanguage.googleapis.com/v1beta";
+const OPENAI_BASE_URL: &str = "https://api.openai.com/v1";
+/// API Key 在配置中的位置（用于换回密钥句柄）
+const API_KEY_LOCATION: &str = "agents/defaults/memorySearch/remote/apiKey";
+/// 测试用的文本
+const TEST_INPUT: &str = "OpenClaw embedding test";
+
+/// 预设的 Embedding 方案
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmbeddingPreset {
+    pub id: String,
+    pub name: String,
+    pub provider: String,
+    pub model: Option<String>,
+    pub base_url: Option<String>,
+    pub needs_api_key: bool,
+}
+
+fn preset(
+    id: &str,
+    name: &str,
+    provider: &str,
+    model: Option<&str>,
+    base_url: Option<&str>,
+    needs_api_key: bool,
+) -> EmbeddingPreset {
+    EmbeddingPreset {
+        id: id.to_string(),
+        name: name.to_string(),
+        provider: provider.to_string(),
+        model: model.map(str::to_string),
+        base_url: base_url.map(str::to_string),
+        needs_api_key,
+    }
+}
+
+fn presets() -> Vec<EmbeddingPreset> {
+    // Ollama 提供 OpenAI 兼容的 /v1/embeddings 接口
+    let ollama = Some("http://127.0.0.1:11434/v1");
+    vec![
+        preset(
+            "ollama-bge",
+            "Ollama · bge-m3",
+            "openai",
+            Some("bge-m3"),
+            ollama,
+            false,
+        ),
+        preset(
+            "ollama-nomic",
+            "Ollama · nomic-embed-text",
+            "openai",
+            Some("nomic-embed-text"),
+            ollama,
+            false,
+        ),
+        preset(
+            "openai",
+            "OpenAI · text-embedding-3-small",
+            "openai",
+            Some("text-embedding-3-small"),
+            None,
+            true,
+        ),
+        preset(
+            "gemini",
+            "Gemini · gemini-embedding-001",
+            "gemini",
+            Some("gemini-embedding-001"),
+            None,
+            true,
+        ),
+        preset(
+            "local",
+            "内置本地模型（node-llama-cpp）",
+            "local",
+            None,
+            None,
+            false,
+        ),
+    ]
+}
+
+/// Embedding 配置（provider 为空表示由 Gateway 自动选择）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EmbeddingConfig {
+    #[serde(default)]
+    pub provider: Option<String>,
+    #[serde(default)]
+    pub model: Option<String>,
+    /// OpenAI 兼容接口地址（为空时使用官方地址）
+    #[serde(default)]
+    pub base_url: Option<String>,
+    /// 保存时为空表示保留原有的 API Key（新输入的 Key 为 `stage_secret` 返回的句柄）；读取时不返回
+    #[serde(default, skip_serializing)]
+    pub api_key: Option<String>,
+    #[serde(default)]
+    pub has_api_key: bool,
+    /// 主 Provider 不可用时的备用 Provider（none 表示不使用）
+    #[serde(default)]
+    pub fallback: Option<String>,
+}
+
+/// 测试结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmbeddingTestResult {
+    pub success: bool,
+    /// 向量维度
+    pub dimensions: Option<usize>,
+    pub latency_ms: u64,
+    pub error: Option<String>,
+}
+
+fn text(value: Option<&Value>) -> Option<String> {
+    value
+        .and_then(Value::as_str)
+        .filter(|s| !s.trim().is_empty())
+        .map(str::to_string)
+}
+
+/// 从 agents.defaults.memorySearch 读取配置
+fn read_embedding(cfg: &Value) -> EmbeddingConfig {
+    let search = cfg.pointer("/agents/defaults/memorySearch");
+    let field = |pointer: &str| text(search.and_then(|s| s.pointer(pointer)));
+    EmbeddingConfig {
+        provider: field("/provider"),
+        model: field("/model"),
+        base_url: field("/remote/baseUrl"),
+        api_key: None,
+        has_api_key: field("/remote/apiKey").is_some(),
+        fallback: field("/fallback"),
+    }
+}
+
+fn validate(config: &EmbeddingConfig) -> Result<(), String> {
+    let Some(provider) = config.provider.as_deref() else {
+        return Ok(());
+    };
+    if !PROVIDERS.contains(&provider) {
+        return Err(format!("未知的 Embedding Provider: {}", provider));
+    }
+    if provider != "local" && config.model.as_deref().is_none_or(|m| m.trim().is_empty()) {
+        return Err("请填写 Embedding 模型".to_string());
+    }
+    if let Some(url) = config.base_url.as_deref() {
+        if provider != "openai" {
+            return Err("只有 OpenAI 兼容接口可以自定义地址".to_string());
+        }
+        reqwest::Url::parse(url).map_err(|e| format!("接口地址无效: {}", e))?;
+    }
+    if let Some(fallback) = config.fallback.as_deref() {
+        if fallback != "none" && !PROVIDERS.contains(&fallback) {
+            return Err(format!("未知的备用 Provider: {}", fallback));
+        }
+        if fallback == provider {
+            return Err("备用 Provider 不能与主 Provider 相同".to_string());
+        }
+    }
+    Ok(())
+}
+
+/// 写入 agents.defaults.memorySearch，保留其它字段（enabled、extraPaths 等）；
+/// 未传入新 API Key 且接口地址未变时保留原有的 Key
+fn apply_embedding(cfg: &mut Value, config: &EmbeddingConfig) -> Result<(), String> {
+    let current = read_embedding(cfg);
+    let existing_key = text(cfg.pointer("/agents/defaults/memorySearch/remote/apiKey"));
+    let api_key = match config.api_key.as_deref().map(str::trim) {
+        Some(key) if !key.is_empty() => Some(secret_handles::resolve(
+            key,
+            API_KEY_LOCATION,
+            existing_key.as_deref(),
+        )?),
+        _ if current.base_url == config.base_url && current.provider == config.provider => {
+            existing_key
+        }
+        _ => None,
+    };
+    let path: Vec<String> = ["agents", "defaults", "memorySearch"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    if cfg.pointer("/agents/defaults/memorySearch").is_none()
+        && !config_lint::insert(cfg, &path, json!({}))
+    {
+        return Err("agents.defaults 配置格式无效".to_string());
+    }
+    let search = cfg
+        .pointer_mut("/agents/defaults/memorySearch")
+        .and_then(Value::as_object_mut)
+        .ok_or_else(|| "memorySearch 配置格式无效".to_string())?;
+    let mut set = |key: &str, value: Option<Value>| match value {
+        Some(value) => search.insert(key.to_string(), value),
+        None => search.remove(key),
+    };
+    set("provider", config.provider.clone().map(Value::from));
+    set("model", config.model.clone().map(Value::from));
+    set("fallback", config.fallback.clone().map(Value::from));
+    let mut remote = search
+        .get("remote")
+        .and_then(Value::as_object)
+        .cloned()
+        .unwrap_or_else(Map::new);
+    for (key, value) in [("baseUrl", config.base_url.clone()), ("apiKey", api_key)] {
+        match value {
+            Some(value) => remote.insert(key.to_string(), json!(value)),
+            None => remote.remove(key),
+        };
+    }
+    if remote.is_empty() {
+        search.remove("remote");
+    } else {
+        search.insert("remote".to_string(), Value::Object(remote));
+    }
+    Ok(())
+}
+
+/// 从接口返回中读取向量维度（OpenAI: data[0].embedding，Gemini: embedding.values）
+fn parse_dimensions(body: &Value) -> Option<usize> {
+    body.pointer("/data/0/embedding")
+        .or_else(|| body.pointer("/embedding/values"))
+        .and_then(Value::as_array)
+        .map(Vec::len)
+        .filter(|n| *n > 0)
+}
+
+async fn request_embedding(config: &EmbeddingConfig, api_key: &str) -> Result<usize, String> {
+    let provider = config.provider.as_deref().unwrap_or_default();
+    let model = config.model.as_deref().unwrap_or_default();
+    let client = http::client()?;
+    let request = if provider == "gemini" {
+        client
+            .post(format!("{}/models/{}:embedContent", GEMINI_BASE_URL, model))
+            .header("x-goog-api-key", api_key)
+            .json(&json!({ "content": { "parts": [{ "text": TEST_INPUT }] } }))
+    } else {
+        let base_url = config.base_url.as_deref().unwrap_or(OPENAI_BASE_URL);
+        let request = client
+            .post(format!("{}/embeddings", base_url.trim_end_matches('/')))
+            .json(&json!({ "model": model, "input": TEST_INPUT }));
+        if api_key.is_empty() {
+            request
+        } else {
+            request.bearer_auth(api_key)
+        }
+    };
+    let resp = request.send().await.map_err(|e| e.to_string())?;
+    let status = resp.status();
+    let body: Value = resp.json().await.unwrap_or(Value::Null);
+    if !status.is_success() {
+        let message = body
+            .pointer("/error/message")
+            .or_else(|| body.get("error"))
+            .and_then(Value::as_str)
+            .unwrap_or("");
+        return Err(format!("HTTP {} {}", status.as_u16(), message)
+            .trim()
+            .to_string());
+    }
+    parse_dimensions(&body).ok_or_else(|| "返回内容中没有向量".to_string())
+}
+
+/// 预设的 Embedding 方案
+#[guarded]
+#[command]
+pub async fn list_embedding_presets() -> Result<Vec<EmbeddingPreset>, String> {
+    Ok(presets())
+}
+
+/// 读取当前的 Embedding 配置（不返回 API Key）
+#[guarded]
+#[command]
+pub async fn get_embedding_config() -> Result<EmbeddingConfig, String> {
+    Ok(read_embedding(&config::load_openclaw_config()?))
+}
+
+/// 用一段测试文本请求向量，检查地址、Key 和模型是否可用（未填写 Key 时使用已保存的 Key）
+#[guarded]
+#[command]
+pub async fn test_embedding_provider(
+    config: EmbeddingConfig,
+) -> Result<EmbeddingTestResult, String> {
+    validate(&config)?;
+    match config.provider.as_deref() {
+        None => return Err("请先选择 Embedding Provider".to_string()),
+        Some("local") => {
+            return Err("内置本地模型由 Gateway 在首次检索时下载，无需测试".to_string())
+        }
+        Some(_) => {}
+    }
+    let base_url = config.base_url.as_deref().unwrap_or(OPENAI_BASE_URL);
+    if config.provider.as_deref() == Some("gemini") || !privacy::is_local_url(base_url) {
+        privacy::guard("测试远程 Embedding Provider")?;
+    }
+    let api_key = match config.api_key.as_deref().map(str::trim) {
+        Some(key) if !key.is_empty() => secret_handles::resolve(key, API_KEY_LOCATION, None)?,
+        _ => {
+            let mut cfg = config::load_openclaw_config()?;
+            apply_embedding(&mut cfg, &config)?;
+            text(cfg.pointer("/agents/defaults/memorySearch/remote/apiKey")).unwrap_or_default()
+        }
+    };
+
+    let started = Instant::now();
+    let result = request_embedding(&config, &api_key).await;
+    let latency_ms = started.elapsed().as_millis() as u64;
+    match &result {
+        Ok(dimensions) => info!(
+            "[Embedding] ✓ {} 可用（{} 维，{}ms）",
+            config.model.as_deref().unwrap_or_default(),
+            dimensions,
+            latency_ms
+        ),
+        Err(e) => warn!("[Embedding] ✗ 测试失败: {}", e),
+    }
+    Ok(EmbeddingTestResult {
+        success: result.is_ok(),
+        dimensions: result.as_ref().ok().copied(),
+        latency_ms,
+        error: result.err(),
+    })
+}
+
+/// 保存 Embedding 配置到 openclaw.json
+#[guarded]
+#[command]
+pub async fn save_embedding_config(config: EmbeddingConfig) -> Result<String, String> {
+    validate(&config)?;
+    let mut cfg = config::load_openclaw_config()?;
+    apply_embedding(&mut cfg, &config)?;
+    config::save_config(cfg).await?;
+    audit::record(
+        "save_embedding_config",
+        config.provider.as_deref().unwrap_or("auto"),
+        true,
+        json!({
+            "model": config.model,
+            "base_url": config.base_url,
+            "fallback": config.fallback,
+        }),
+    );
+    info!(
+        "[Embedding] ✓ 已保存 Embedding 配置: {} {}",
+        config.provider.as_deref().unwrap_or("auto"),
+        config.model.as_deref().unwrap_or_default()
+    );
+    Ok("Embedding 配置已保存，重建知识库索引后生效".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_embedding_config_and_keeps_api_key() {
+        let mut cfg = json!({ "agents": { "defaults": { "memorySearch": {
+            "enabled": true,
+            "provider": "openai",
+            "model": "text-embedding-3-small",
+            "remote": { "apiKey": "sk-old" }
+        }}}});
+        let mut config = read_embedding(&cfg);
+        assert!(config.has_api_key);
+
+        config.model = Some("text-embedding-3-large".to_string());
+        apply_embedding(&mut cfg, &config).unwrap();
+        let search = &cfg["agents"]["defaults"]["memorySearch"];
+        assert_eq!(search["remote"]["apiKey"], "sk-old");
+        assert_eq!(search["enabled"], true);
+
+        // 切换到 Ollama 时不沿用 OpenAI 的 Key
+        config.base_url = Some("http://127.0.0.1:11434/v1".to_string());
+        config.model = Some("bge-m3".to_string());
+        config.fallback = Some("openai".to_string());
+        assert!(validate(&config).is_err());
+        config.fallback = Some("local".to_string());
+        validate(&config).unwrap();
+        apply_embedding(&mut cfg, &config).unwrap();
+        let search = &cfg["agents"]["defaults"]["memorySearch"];
+        assert_eq!(
+            search["remote"],
+            json!({ "baseUrl": "http://127.0.0.1:11434/v1" })
+        );
+        assert_eq!(search["fallback"], "local");
+
+        let body = json!({ "data": [{ "embedding": [0.1, 0.2, 0.3] }] });
+        assert_eq!(parse_dimensions(&body), Some(3));
+        assert_eq!(
+            parse_dimensions(&json!({ "embedding": { "values": [] } })),
+            None
+        );
+    }
+}
@@ -0,0 +1,148 @@
+//! 事件回放：推送给前端的事件同时按通道保存在环形缓冲区中，
+//! WebView 尚未加载完成（如启动时自动继续的安装）或页面刷新期间发出的事件，
+//! 前端挂载后可通过 `replay_events` 补齐
+use log::debug;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+use openclaw_macros::guarded;
+use tauri::{command, AppHandle, Emitter};
+
+/// 安装步骤进度事件
+pub const INSTALL_PROGRESS_EVENT: &str = "install-progress";
+/// 安装步骤耗时事件
+pub const INSTALL_TIMING_EVENT: &str = "install-timing";
+/// 下载进度事件
+pub const DOWNLOAD_PROGRESS_EVENT: &str = "download-progress";
+
+/// 每个通道保留的事件数
+const CHANNEL_CAPACITY: usize = 200;
+
+/// 已推送的事件
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedEvent {
+    /// 全局递增序号，回放时作为 `since` 参数传回
+    pub seq: u64,
+    pub channel: String,
+    /// 毫秒时间戳
+    pub ts: i64,
+    pub payload: Value,
+}
+
+static APP_HANDLE: OnceLock<AppHandle> = OnceLock::new();
+static BUFFERS: Mutex<BTreeMap<String, VecDeque<RecordedEvent>>> = Mutex::new(BTreeMap::new());
+static NEXT_SEQ: AtomicU64 = AtomicU64::new(1);
+
+/// 注入 AppHandle，供没有 AppHandle 的后台流程推送事件
+pub fn init(app: &AppHandle) {
+    let _ = APP_HANDLE.set(app.clone());
+}
+
+fn push(
+    buffers: &mut BTreeMap<String, VecDeque<RecordedEvent>>,
+    event: RecordedEvent,
+    capacity: usize,
+) {
+    let buffer = buffers.entry(event.channel.clone()).or_default();
+    if buffer.len() >= capacity {
+        buffer.pop_front();
+    }
+    buffer.push_back(event);
+}
+
+/// 序号大于 `since` 的事件（`channel` 为空时包括全部通道），按序号排列
+fn events_since(
+    buffers: &BTreeMap<String, VecDeque<RecordedEvent>>,
+    channel: Option<&str>,
+    since: u64,
+) -> Vec<RecordedEvent> {
+    let mut events: Vec<RecordedEvent> = buffers
+        .iter()
+        .filter(|(name, _)| channel.is_none_or(|c| c == name.as_str()))
+        .flat_map(|(_, buffer)| buffer.iter().filter(|e| e.seq > since).cloned())
+        .collect();
+    events.sort_by_key(|e| e.seq);
+    events
+}
+
+fn record(channel: &str, payload: Value) {
+    let event = RecordedEvent {
+        seq: NEXT_SEQ.fetch_add(1, Ordering::Relaxed),
+        channel: channel.to_string(),
+        ts: chrono::Utc::now().timestamp_millis(),
+        payload,
+    };
+    let mut buffers = BUFFERS.lock().unwrap_or_else(|e| e.into_inner());
+    push(&mut buffers, event, CHANNEL_CAPACITY);
+}
+
+/// 记录事件并推送给前端
+pub fn emit<S: Serialize + Clone>(
+    app: &AppHandle,
+    channel: &str,
+    payload: S,
+) -> Result<(), String> {
+    record(channel, serde_json::to_value(&payload).unwrap_or_default());
+    app.emit(channel, payload).map_err(|e| e.to_string())
+}
+
+/// 记录事件并在 AppHandle 已注入时推送给前端（未注入时只记录，前端挂载后回放）
+pub fn publish<S: Serialize + Clone>(channel: &str, payload: S) {
+    match APP_HANDLE.get() {
+        Some(app) => {
+            if let Err(e) = emit(app, channel, payload) {
+                debug!("[事件] 推送 {} 失败: {}", channel, e);
+            }
+        }
+        None => record(channel, serde_json::to_value(&payload).unwrap_or_default()),
+    }
+}
+
+/// 回放最近的事件：`since` 为上次收到的最大序号（为空时返回缓冲区中的全部事件）
+#[guarded]
+#[command]
+pub async fn replay_events(
+    channel: Option<String>,
+    since: Option<u64>,
+) -> Result<Vec<RecordedEvent>, String> {
+    let buffers = BUFFERS.lock().unwrap_or_else(|e| e.into_inner());
+    Ok(events_since(
+        &buffers,
+        channel.as_deref(),
+        since.unwrap_or_default(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_latest_events_per_channel() {
+        let event = |seq: u64, channel: &str| RecordedEvent {
+            seq,
+            channel: channel.to_string(),
+            ts: 0,
+            payload: Value::from(seq),
+        };
+        let mut buffers = BTreeMap::new();
+        for seq in 1..=5 {
+            push(&mut buffers, event(seq, "install-progress"), 3);
+        }
+        push(&mut buffers, event(6, "config-changed"), 3);
+
+        let seqs = |events: Vec<RecordedEvent>| events.iter().map(|e| e.seq).collect::<Vec<_>>();
+        assert_eq!(
+            seqs(events_since(&buffers, Some("install-progress"), 0)),
+            vec![3, 4, 5]
+        );
+        assert_eq!(
+            seqs(events_since(&buffers, Some("install-progress"), 4)),
+            vec![5]
+        );
+        assert_eq!(seqs(events_since(&buffers, None, 4)), vec![5, 6]);
+        assert!(events_since(&buffers, Some("missing"), 0).is_empty());
+    }
+}
@@ -0,0 +1,289 @@
+use crate::commands::installer::InstallResult;
+use crate::commands::winpkg::{self, WindowsPackage};
+use crate::commands::{downloads, privacy, watcher};
+use crate::utils::{file, http, platform, shell, temp};
+use log::{info, warn};
+use std::path::{Path, PathBuf};
+use openclaw_macros::guarded;
+use tauri::command;
+
+/// 语音 / ASR 功能需要的最低 ffmpeg 主版本
+pub const MIN_FFMPEG_MAJOR: u32 = 4;
+
+/// Linux 上可用于安装 ffmpeg 的包管理器（按优先顺序）
+const LINUX_PACKAGE_MANAGERS: &[(&str, &str)] = &[
+    ("apt-get", "apt-get install -y ffmpeg"),
+    ("dnf", "dnf install -y ffmpeg"),
+    ("pacman", "pacman -S --noconfirm ffmpeg"),
+    ("zypper", "zypper --non-interactive install ffmpeg"),
+    ("apk", "apk add ffmpeg"),
+];
+
+fn tools_bin_dir() -> PathBuf {
+    PathBuf::from(platform::get_tools_bin_dir())
+}
+
+fn exe_name(name: &str) -> String {
+    if platform::is_windows() {
+        format!("{}.exe", name)
+    } else {
+        name.to_string()
+    }
+}
+
+/// 可用的 ffmpeg：PATH 中的优先，其次是 Manager 下载的静态构建
+fn ffmpeg_program() -> String {
+    let managed = tools_bin_dir().join(exe_name("ffmpeg"));
+    if !shell::command_exists("ffmpeg") && managed.is_file() {
+        managed.to_string_lossy().to_string()
+    } else {
+        "ffmpeg".to_string()
+    }
+}
+
+/// 从 `ffmpeg -version` 的第一行取出版本号
+fn parse_version(output: &str) -> Option<String> {
+    output
+        .lines()
+        .next()?
+        .strip_prefix("ffmpeg version ")?
+        .split_whitespace()
+        .next()
+        .map(String::from)
+}
+
+/// 版本是否满足要求：`6.1.1-3ubuntu5`、`n7.0` 按主版本判断，
+/// 开发版（`N-118000-g…`）和按日期命名的构建（`2024-03-11-git-…`）视为满足
+pub fn version_ok(version: &str) -> bool {
+    if version.starts_with('N') {
+        return true;
+    }
+    let digits: String = version
+        .trim_start_matches('n')
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect();
+    digits
+        .parse::<u32>()
+        .is_ok_and(|major| major >= MIN_FFMPEG_MAJOR)
+}
+
+/// 获取 ffmpeg 版本（未安装时返回 None）
+pub fn ffmpeg_version() -> Option<String> {
+    shell::run_command_output(&ffmpeg_program(), &["-version"])
+        .ok()
+        .and_then(|out| parse_version(&out))
+}
+
+/// 选择 Linux 上安装 ffmpeg 的命令
+pub fn linux_install_command(exists: impl Fn(&str) -> bool) -> Option<&'static str> {
+    LINUX_PACKAGE_MANAGERS
+        .iter()
+        .find(|(pm, _)| exists(pm))
+        .map(|(_, cmd)| *cmd)
+}
+
+/// 当前平台的静态构建下载地址和文件名
+pub fn static_build() -> Option<(&'static str, &'static str)> {
+    let arch = platform::get_arch();
+    if platform::is_windows() {
+        Some((
+            "https://www.gyan.dev/ffmpeg/builds/ffmpeg-release-essentials.zip",
+            "ffmpeg-release-essentials.zip",
+        ))
+    } else if platform::is_macos() {
+        // 只有 x86_64 构建，Apple Silicon 上通过 Rosetta 运行
+        Some((
+            "https://evermeet.cx/ffmpeg/getrelease/zip",
+            "ffmpeg-macos.zip",
+        ))
+    } else if arch.contains("x86_64") {
+        Some((
+            "https://johnvansickle.com/ffmpeg/releases/ffmpeg-release-amd64-static.tar.xz",
+            "ffmpeg-release-amd64-static.tar.xz",
+        ))
+    } else if arch.contains("aarch64") {
+        Some((
+            "https://johnvansickle.com/ffmpeg/releases/ffmpeg-release-arm64-static.tar.xz",
+            "ffmpeg-release-arm64-static.tar.xz",
+        ))
+    } else {
+        None
+    }
+}
+
+/// 在解压目录中查找可执行文件
+fn find_binary(dir: &Path, name: &str) -> Option<PathBuf> {
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(current) = pending.pop() {
+        for entry in std::fs::read_dir(&current).into_iter().flatten().flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                pending.push(path);
+            } else if entry.file_name().to_string_lossy() == name {
+                return Some(path);
+            }
+        }
+    }
+    None
+}
+
+/// 下载静态构建到 Manager 工具目录，并在 OpenClaw 环境变量中设置 FFMPEG_PATH
+async fn install_static() -> Result<InstallResult, String> {
+    privacy::guard("下载 ffmpeg")?;
+    let (url, file_name) =
+        static_build().ok_or_else(|| "当前平台没有可用的 ffmpeg 静态构建".to_string())?;
+    info!("[安装ffmpeg] 下载静态构建: {}", url);
+    let archive = downloads::fetch(&http::client()?, url, file_name, None).await?;
+
+    tokio::task::spawn_blocking(move || {
+        let work = temp::run_dir()
+            .map_err(|e| format!("创建临时目录失败: {}", e))?
+            .join(format!("ffmpeg-{}", uuid::Uuid::new_v4().simple()));
+        std::fs::create_dir_all(&work).map_err(|e| format!("创建临时目录失败: {}", e))?;
+        // Windows 10 起自带的 tar（bsdtar）也能解压 zip
+        let extracted = shell::run_command_output(
+            "tar",
+            &[
+                "-xf",
+                &archive.to_string_lossy(),
+                "-C",
+                &work.to_string_lossy(),
+            ],
+        )
+        .map_err(|e| format!("解压失败: {}", e))
+        .and_then(|_| {
+            let bin = tools_bin_dir();
+            std::fs::create_dir_all(&bin).map_err(|e| format!("创建工具目录失败: {}", e))?;
+            for name in ["ffmpeg", "ffprobe"] {
+                let Some(src) = find_binary(&work, &exe_name(name)) else {
+                    if name == "ffmpeg" {
+                        return Err("压缩包中没有 ffmpeg".to_string());
+                    }
+                    continue;
+                };
+                let dst = bin.join(exe_name(name));
+                file::copy_atomic(&src, &dst).map_err(|e| format!("复制 {} 失败: {}", name, e))?;
+                #[cfg(unix)]
+                {
+                    use std::os::unix::fs::PermissionsExt;
+                    let _ = std::fs::set_permissions(&dst, std::fs::Permissions::from_mode(0o755));
+                }
+            }
+            Ok(bin.join(exe_name("ffmpeg")))
+        });
+        let _ = std::fs::remove_dir_all(&work);
+        let ffmpeg = extracted?;
+
+        let version = shell::run_command_output(&ffmpeg.to_string_lossy(), &["-version"])
+            .ok()
+            .and_then(|out| parse_version(&out))
+            .ok_or_else(|| "下载的 ffmpeg 无法运行".to_string())?;
+        // 语音相关技能通过 FFMPEG_PATH 找到 ffmpeg（Windows 上 Gateway 不使用扩展 PATH）
+        let env_path = platform::get_env_file_path();
+        watcher::note_internal_write(&env_path);
+        file::set_env_value(&env_path, "FFMPEG_PATH", &ffmpeg.to_string_lossy())
+            .map_err(|e| format!("写入 FFMPEG_PATH 失败: {}", e))?;
+        Ok(InstallResult {
+            success: true,
+            message: format!("ffmpeg {} 已安装到 {}", version, ffmpeg.display()),
+            error: None,
+            timings: Vec::new(),
+        })
+    })
+    .await
+    .map_err(|e| format!("安装 ffmpeg 失败: {}", e))?
+}
+
+/// 通过系统包管理器安装，返回 None 表示没有可用的包管理器（改用静态构建）
+fn install_with_package_manager() -> Option<Result<String, String>> {
+    if platform::is_windows() {
+        winpkg::install_command(WindowsPackage::Ffmpeg)?;
+        Some(winpkg::install(WindowsPackage::Ffmpeg))
+    } else if platform::is_macos() {
+        if !shell::command_exists("brew") {
+            return None;
+        }
+        info!("[安装ffmpeg] 使用 Homebrew 安装...");
+        Some(shell::run_command_output("brew", &["install", "ffmpeg"]))
+    } else {
+        let cmd = linux_install_command(shell::command_exists)?;
+        if !shell::command_exists("pkexec") {
+            return None;
+        }
+        info!("[安装ffmpeg] 执行: pkexec {}", cmd);
+        Some(shell::run_command_output("pkexec", &["sh", "-c", cmd]))
+    }
+}
+
+/// 安装 ffmpeg（语音渠道和音频相关技能需要）
+/// 优先使用系统包管理器（winget / Homebrew / apt 等），不可用或失败时下载静态构建
+#[guarded]
+#[command]
+pub async fn install_ffmpeg() -> Result<InstallResult, String> {
+    if let Some(version) = ffmpeg_version().filter(|v| version_ok(v)) {
+        return Ok(InstallResult {
+            success: true,
+            message: format!("ffmpeg 已安装: {}", version),
+            error: None,
+            timings: Vec::new(),
+        });
+    }
+    info!("[安装ffmpeg] 开始安装 ffmpeg...");
+    let packaged = tokio::task::spawn_blocking(install_with_package_manager)
+        .await
+        .map_err(|e| format!("安装 ffmpeg 失败: {}", e))?;
+    match packaged {
+        Some(Ok(_)) => {
+            if let Some(version) = ffmpeg_version().filter(|v| version_ok(v)) {
+                info!("[安装ffmpeg] ✓ ffmpeg {} 安装成功", version);
+                return Ok(InstallResult {
+                    success: true,
+                    message: format!("ffmpeg {} 安装成功", version),
+                    error: None,
+                    timings: Vec::new(),
+                });
+            }
+            warn!("[安装ffmpeg] 包管理器安装的 ffmpeg 不可用或版本过低，改用静态构建");
+        }
+        Some(Err(e)) => warn!("[安装ffmpeg] 包管理器安装失败，改用静态构建: {}", e),
+        None => info!("[安装ffmpeg] 没有可用的包管理器，下载静态构建"),
+    }
+
+    match install_static().await {
+        Ok(result) => {
+            info!("[安装ffmpeg] ✓ {}", result.message);
+            Ok(result)
+        }
+        Err(e) => {
+            warn!("[安装ffmpeg] ✗ 安装失败: {}", e);
+            Ok(InstallResult {
+                success: false,
+                message: "ffmpeg 安装失败".to_string(),
+                error: Some(e),
+                timings: Vec::new(),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_and_validates_ffmpeg_versions() {
+        let output = "ffmpeg version 6.1.1-3ubuntu5 Copyright (c) 2000-2023 the FFmpeg developers\nbuilt with gcc 13";
+        assert_eq!(parse_version(output).as_deref(), Some("6.1.1-3ubuntu5"));
+        assert_eq!(parse_version("command not found"), None);
+        assert!(version_ok("6.1.1-3ubuntu5"));
+        assert!(version_ok("n7.0"));
+        assert!(version_ok("7.1-essentials_build-www.gyan.dev"));
+        assert!(version_ok("N-118000-g1234abcd"));
+        assert!(version_ok(
+            "2024-03-11-git-3d1860ec8d-essentials_build-www.gyan.dev"
+        ));
+        assert!(!version_ok("3.4.11"));
+        assert!(!version_ok(""));
+    }
+}
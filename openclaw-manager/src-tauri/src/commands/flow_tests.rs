@@ -0,0 +1,139 @@
+//! 安装 / 更新 / 卸载流程测试：子进程调用由模拟执行后端返回预设输出，
+//! Manager 数据目录指向临时目录
+use crate::commands::{install_state, installer, policy};
+use crate::utils::executor::{self, Matcher, MockExecutor, Scoped};
+use crate::utils::platform;
+use std::future::Future;
+use std::sync::Arc;
+
+// 替换执行后端的测试锁是同步锁，不跨 await 持有：每个流程在独立的运行时中执行
+fn block_on<F: Future>(future: F) -> F::Output {
+    tokio::runtime::Runtime::new().unwrap().block_on(future)
+}
+
+fn setup() -> (Scoped, Arc<MockExecutor>) {
+    let mock = Arc::new(MockExecutor::new());
+    let scoped = executor::scoped(mock.clone());
+    let data_dir = std::env::temp_dir().join(format!("flow-tests-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&data_dir).unwrap();
+    std::env::set_var(platform::DATA_DIR_ENV, &data_dir);
+    // npm 全局目录指向空的临时目录，不检查真实环境中的残留
+    mock.expect_script("npm root -g")
+        .stdout(&data_dir.join("lib/node_modules").to_string_lossy());
+    (scoped, mock)
+}
+
+fn script(text: &str) -> Matcher {
+    Matcher::Script(text.to_string())
+}
+
+fn argv(args: &[&str]) -> Matcher {
+    Matcher::Argv(args.iter().map(|a| a.to_string()).collect())
+}
+
+#[test]
+fn install_runs_npm_and_clears_progress() {
+    let (_scoped, mock) = setup();
+    mock.expect(&["which", "openclaw"]).code(1).once();
+    mock.expect(&["which", "openclaw"])
+        .stdout("/usr/local/bin/openclaw");
+    mock.expect_script("npm install -g openclaw@latest")
+        .stdout("openclaw 2.1.0");
+    mock.expect(&["openclaw", "--version"]).stdout("2.1.0\n");
+
+    let result = block_on(installer::run_install()).unwrap();
+    assert!(result.success, "{:?}", result);
+    assert!(mock.called(&script("npm install -g openclaw@latest")));
+    // 安装后初始化默认技能
+    assert!(mock.called(&argv(&["openclaw", "skill", "install", "browser"])));
+    assert!(block_on(install_state::get_install_state())
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn failed_install_is_resumed_on_next_run() {
+    let (_scoped, mock) = setup();
+    mock.expect_script("npm install -g openclaw@latest")
+        .code(1)
+        .stderr("npm ERR! code EACCES")
+        .once();
+
+    let result = block_on(installer::run_install()).unwrap();
+    assert!(!result.success);
+    assert_eq!(result.error.as_deref(), Some("npm ERR! code EACCES"));
+    let states = block_on(install_state::get_install_state()).unwrap();
+    assert_eq!(states.len(), 1);
+    assert_eq!(states[0].attempts, 1);
+
+    // 第二次运行继续上次的进度，npm 安装成功后清除记录
+    mock.expect_script("npm install -g openclaw@latest");
+    let result = block_on(installer::run_install()).unwrap();
+    assert!(result.success, "{:?}", result);
+    assert!(block_on(install_state::get_install_state())
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn update_skips_when_already_latest() {
+    let (_scoped, mock) = setup();
+    mock.expect(&["which", "openclaw"]);
+    mock.expect(&["openclaw", "--version"]).stdout("2.1.0\n");
+    mock.expect_script("npm view openclaw@latest version dist.tarball --json")
+        .stdout(r#"{"version": "2.1.0", "dist.tarball": "https://registry.example/openclaw-2.1.0.tgz"}"#);
+
+    let result = block_on(installer::run_update(None)).unwrap();
+    assert!(result.success, "{:?}", result);
+    assert_eq!(result.message, "OpenClaw 已是最新版本");
+    assert!(!mock.called(&script("npm install -g")));
+}
+
+#[test]
+fn update_reports_registry_failure() {
+    let (_scoped, mock) = setup();
+    mock.expect(&["which", "openclaw"]);
+    mock.expect(&["openclaw", "--version"]).stdout("2.0.0\n");
+    mock.expect_script("npm view openclaw@latest")
+        .code(1)
+        .stderr("npm ERR! network timeout");
+
+    let result = block_on(installer::run_update(None)).unwrap();
+    assert!(!result.success);
+    assert_eq!(result.message, "下载 OpenClaw 更新失败");
+    assert!(result.error.unwrap().contains("network timeout"));
+}
+
+#[test]
+fn uninstall_stops_gateway_before_npm_uninstall() {
+    let (_scoped, mock) = setup();
+    mock.expect(&["which", "openclaw"]);
+    mock.expect(&["openclaw", "--version"]).stdout("2.1.0\n");
+    mock.expect(&["openclaw", "gateway", "stop"]);
+    mock.expect_script("npm uninstall -g openclaw")
+        .stdout("OpenClaw 已成功卸载");
+
+    let token = policy::confirmed_token("uninstall_openclaw");
+    let result = block_on(installer::uninstall_openclaw(Some(token), None)).unwrap();
+    assert!(result.success, "{:?}", result);
+    let calls = mock.calls();
+    let position = |matcher: Matcher| calls.iter().position(|argv| matcher.matches(argv));
+    assert!(
+        position(argv(&["openclaw", "gateway", "stop"])).unwrap()
+            < position(script("npm uninstall -g openclaw")).unwrap()
+    );
+}
+
+#[test]
+fn uninstall_refuses_while_gateway_running() {
+    let (_scoped, mock) = setup();
+    mock.expect(&["which", "openclaw"]);
+    mock.expect(&["openclaw", "--version"]).stdout("2.1.0\n");
+    mock.expect(&["lsof", "-ti", ":8789"]).stdout("4242\n");
+
+    let token = policy::confirmed_token("uninstall_openclaw");
+    let result = block_on(installer::uninstall_openclaw(Some(token), None)).unwrap();
+    assert!(!result.success);
+    assert!(result.error.unwrap().contains("PID 4242"));
+    assert!(!mock.called(&script("npm uninstall")));
+}
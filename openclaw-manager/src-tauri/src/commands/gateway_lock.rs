@@ -0,0 +1,239 @@
+//! Gateway PID 文件和锁文件：Manager 与外部脚本据此判断 Gateway 是否在运行。
+//! 约定（位于 OpenClaw 配置目录 ~/.openclaw）：
+//! - `gateway.pid`：只有一行 PID，便于脚本直接 `kill $(cat ~/.openclaw/gateway.pid)`
+//! - `gateway.lock`：JSON，含 pid、端口、进程启动时间（Unix 为 `ps -o lstart= -p <pid>` 的输出，
+//!   Windows 为 `(Get-Process -Id <pid>).StartTime.ToUniversalTime().ToString('o')`）和写入方
+//!
+//! 启动成功后写入，停止后删除。PID 对应的进程已退出，或进程启动时间与记录不一致（PID 被复用）时
+//! 视为过期锁，启动前和检测到 Gateway 退出时清除
+use crate::models::GatewayOwner;
+use crate::utils::{file, platform, shell, time};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use openclaw_macros::guarded;
+use tauri::command;
+
+/// 锁文件的写入方（外部脚本写入时可使用其他名称）
+const OWNER: &str = "openclaw-manager";
+
+/// gateway.lock 的内容
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GatewayLock {
+    pub pid: u32,
+    pub port: u16,
+    /// 操作系统报告的进程启动时间，用于识别 PID 复用
+    pub process_started: Option<String>,
+    pub locked_at: String,
+    pub owner: String,
+    /// 写入锁文件的 Manager 进程（外部脚本写入时为空）
+    #[serde(default)]
+    pub manager_pid: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LockState {
+    /// 没有锁文件
+    Missing,
+    /// 锁文件中的进程仍在运行
+    Live,
+    /// 进程已退出或 PID 已被复用
+    Stale,
+}
+
+/// 锁文件状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GatewayLockStatus {
+    pub state: LockState,
+    pub lock: Option<GatewayLock>,
+    pub lock_path: String,
+    pub pid_path: String,
+    /// 过期的原因
+    pub reason: Option<String>,
+}
+
+fn lock_path() -> PathBuf {
+    Path::new(&platform::get_config_dir()).join("gateway.lock")
+}
+
+fn pid_path() -> PathBuf {
+    Path::new(&platform::get_config_dir()).join("gateway.pid")
+}
+
+/// 进程启动时间（进程不存在时为 None）
+fn process_start_time(pid: u32) -> Option<String> {
+    let output = if platform::is_windows() {
+        shell::run_powershell_output(&format!(
+            "(Get-Process -Id {} -ErrorAction Stop).StartTime.ToUniversalTime().ToString('o')",
+            pid
+        ))
+    } else {
+        shell::run_command_output("ps", &["-o", "lstart=", "-p", &pid.to_string()])
+    };
+    output
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+/// 判断锁是否过期：`current_start` 为 PID 对应进程当前的启动时间（进程不存在时为 None）
+fn stale_reason(lock: &GatewayLock, current_start: Option<&str>) -> Option<String> {
+    let Some(current) = current_start else {
+        return Some(format!("PID {} 已退出", lock.pid));
+    };
+    match lock.process_started.as_deref() {
+        Some(recorded) if recorded != current => Some(format!(
+            "PID {} 已被其他进程复用（启动时间 {}，锁文件记录 {}）",
+            lock.pid, current, recorded
+        )),
+        _ => None,
+    }
+}
+
+fn read_lock() -> Option<GatewayLock> {
+    let content = std::fs::read_to_string(lock_path()).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn remove_files() {
+    let _ = std::fs::remove_file(lock_path());
+    let _ = std::fs::remove_file(pid_path());
+}
+
+/// 读取锁文件并检查是否过期（阻塞）
+pub fn status() -> GatewayLockStatus {
+    let lock = read_lock();
+    let (state, reason) = match &lock {
+        None if pid_path().exists() => (LockState::Stale, Some("缺少 gateway.lock".to_string())),
+        None => (LockState::Missing, None),
+        Some(lock) => match stale_reason(lock, process_start_time(lock.pid).as_deref()) {
+            Some(reason) => (LockState::Stale, Some(reason)),
+            None => (LockState::Live, None),
+        },
+    };
+    GatewayLockStatus {
+        state,
+        lock,
+        lock_path: lock_path().to_string_lossy().to_string(),
+        pid_path: pid_path().to_string_lossy().to_string(),
+        reason,
+    }
+}
+
+/// 清除过期的锁文件，返回仍有效的锁
+pub fn clear_stale() -> Option<GatewayLock> {
+    let status = status();
+    match status.state {
+        LockState::Live => status.lock,
+        LockState::Stale => {
+            info!(
+                "[Gateway 锁] 清除过期的锁文件: {}",
+                status.reason.unwrap_or_default()
+            );
+            remove_files();
+            None
+        }
+        LockState::Missing => None,
+    }
+}
+
+/// Gateway 启动成功后写入 PID 文件和锁文件
+pub fn acquire(pid: u32, port: u16) {
+    let lock = GatewayLock {
+        pid,
+        port,
+        process_started: process_start_time(pid),
+        locked_at: time::now_rfc3339(),
+        owner: OWNER.to_string(),
+        manager_pid: Some(std::process::id()),
+    };
+    let result = serde_json::to_vec_pretty(&lock)
+        .map_err(|e| e.to_string())
+        .and_then(|content| file::write_atomic(&lock_path(), &content).map_err(|e| e.to_string()))
+        .and_then(|()| {
+            file::write_atomic(&pid_path(), format!("{}\n", pid).as_bytes())
+                .map_err(|e| e.to_string())
+        });
+    match result {
+        Ok(()) => info!("[Gateway 锁] ✓ 已写入锁文件 (PID {})", pid),
+        Err(e) => warn!("[Gateway 锁] 写入锁文件失败: {}", e),
+    }
+}
+
+/// Gateway 停止后删除 PID 文件和锁文件
+pub fn release() {
+    if lock_path().exists() || pid_path().exists() {
+        info!("[Gateway 锁] 删除锁文件");
+        remove_files();
+    }
+}
+
+/// 指定进程退出后删除锁文件（锁已属于其他进程时保留）
+pub fn release_if(pid: u32) {
+    if read_lock().is_some_and(|lock| lock.pid == pid) {
+        release();
+    }
+}
+
+/// 监听端口的 Gateway 由谁启动：锁文件由 Manager 写入且 PID 一致时为 Manager，否则为外部启动。
+/// 只比较 PID（不检查进程启动时间），状态轮询时不需要执行额外命令
+fn owner_of(pid: u32, lock: Option<&GatewayLock>) -> GatewayOwner {
+    match lock {
+        Some(lock) if lock.pid == pid && lock.owner == OWNER => GatewayOwner::Manager,
+        _ => GatewayOwner::External,
+    }
+}
+
+/// 正在监听端口的 Gateway 的启动方
+pub fn owner(pid: u32) -> GatewayOwner {
+    owner_of(pid, read_lock().as_ref())
+}
+
+/// 获取 Gateway 锁文件状态
+#[guarded]
+#[command]
+pub async fn get_gateway_lock() -> Result<GatewayLockStatus, String> {
+    tokio::task::spawn_blocking(status)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_exited_and_reused_pids() {
+        let lock = GatewayLock {
+            pid: 4242,
+            port: 18789,
+            process_started: Some("Thu Oct 15 10:00:00 2026".to_string()),
+            locked_at: "2026-10-15T10:00:01+08:00".to_string(),
+            owner: OWNER.to_string(),
+            manager_pid: Some(100),
+        };
+        assert_eq!(stale_reason(&lock, Some("Thu Oct 15 10:00:00 2026")), None);
+        assert_eq!(
+            stale_reason(&lock, None).as_deref(),
+            Some("PID 4242 已退出")
+        );
+        assert!(stale_reason(&lock, Some("Fri Oct 16 08:00:00 2026"))
+            .is_some_and(|r| r.contains("已被其他进程复用")));
+
+        // 外部脚本写入的锁可以没有启动时间和 Manager PID
+        let external: GatewayLock = serde_json::from_str(
+            r#"{"pid":4242,"port":18789,"process_started":null,"locked_at":"","owner":"systemd"}"#,
+        )
+        .unwrap();
+        assert_eq!(external.manager_pid, None);
+        assert_eq!(owner_of(4242, Some(&external)), GatewayOwner::External);
+        assert_eq!(owner_of(4242, Some(&lock)), GatewayOwner::Manager);
+        assert_eq!(owner_of(5000, Some(&lock)), GatewayOwner::External);
+        assert_eq!(owner_of(4242, None), GatewayOwner::External);
+        assert_eq!(
+            stale_reason(&external, Some("Fri Oct 16 08:00:00 2026")),
+            None
+        );
+    }
+}
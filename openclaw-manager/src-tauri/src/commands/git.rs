@@ -0,0 +1,160 @@
+use crate::commands::installer::InstallResult;
+use crate::commands::winpkg::{self, WindowsPackage};
+use crate::utils::{platform, shell};
+use log::{info, warn};
+use openclaw_macros::guarded;
+use tauri::command;
+
+/// Linux 上可用于安装 git 的包管理器（按优先顺序）
+const LINUX_PACKAGE_MANAGERS: &[(&str, &str)] = &[
+    ("apt-get", "apt-get install -y git"),
+    ("dnf", "dnf install -y git"),
+    ("yum", "yum install -y git"),
+    ("pacman", "pacman -S --noconfirm git"),
+    ("zypper", "zypper --non-interactive install git"),
+    ("apk", "apk add git"),
+];
+
+/// 获取 git 版本
+/// macOS 上 /usr/bin/git 在未安装命令行工具时只是占位程序，因此以 `git --version` 能否执行为准
+pub fn git_version() -> Option<String> {
+    shell::run_command_output("git", &["--version"])
+        .ok()
+        .map(|out| out.trim().trim_start_matches("git version").trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/// 未安装 git 时的处理建议
+pub fn remediation() -> String {
+    let hint = if platform::is_windows() {
+        "可在设置中一键安装，或从 https://git-scm.com/download/win 下载安装".to_string()
+    } else if platform::is_macos() {
+        "可在设置中一键安装，或在终端执行 xcode-select --install".to_string()
+    } else {
+        match linux_install_command(shell::command_exists) {
+            Some(cmd) => format!("可在设置中一键安装，或在终端执行 sudo {}", cmd),
+            None => "请使用系统包管理器安装 git".to_string(),
+        }
+    };
+    format!("未检测到 git。{}", hint)
+}
+
+/// 需要 git 的操作开始前检查，未安装时返回带处理建议的错误
+pub fn ensure_git() -> Result<String, String> {
+    git_version().ok_or_else(remediation)
+}
+
+/// 选择 Linux 上安装 git 的命令
+pub fn linux_install_command(exists: impl Fn(&str) -> bool) -> Option<&'static str> {
+    LINUX_PACKAGE_MANAGERS
+        .iter()
+        .find(|(pm, _)| exists(pm))
+        .map(|(_, cmd)| *cmd)
+}
+
+fn install_macos() -> Result<InstallResult, String> {
+    if shell::command_exists("brew") {
+        info!("[安装Git] 使用 Homebrew 安装...");
+        shell::run_command_output("brew", &["install", "git"])?;
+        return Ok(InstallResult {
+            success: true,
+            message: "Git 安装成功".to_string(),
+            error: None,
+            timings: Vec::new(),
+        });
+    }
+    // 命令行工具安装器是图形界面，命令会立即返回
+    info!("[安装Git] 打开 Xcode 命令行工具安装窗口...");
+    shell::run_command_output("xcode-select", &["--install"])
+        .map_err(|e| format!("启动命令行工具安装失败: {}", e))?;
+    Ok(InstallResult {
+        success: false,
+        message: "已打开 Xcode 命令行工具安装窗口，完成安装后请重新检测环境".to_string(),
+        error: None,
+        timings: Vec::new(),
+    })
+}
+
+fn install_linux() -> Result<InstallResult, String> {
+    let cmd = linux_install_command(shell::command_exists)
+        .ok_or_else(|| "未找到支持的包管理器，请手动安装 git".to_string())?;
+    if !shell::command_exists("pkexec") {
+        return Ok(InstallResult {
+            success: false,
+            message: format!("需要管理员权限，请在终端执行: sudo {}", cmd),
+            error: None,
+            timings: Vec::new(),
+        });
+    }
+    info!("[安装Git] 执行: pkexec {}", cmd);
+    shell::run_command_output("pkexec", &["sh", "-c", cmd])?;
+    Ok(InstallResult {
+        success: true,
+        message: "Git 安装成功".to_string(),
+        error: None,
+        timings: Vec::new(),
+    })
+}
+
+/// 安装 git（GitHub 同步和源码构建需要）
+#[guarded]
+#[command]
+pub async fn install_git() -> Result<InstallResult, String> {
+    if let Some(version) = git_version() {
+        return Ok(InstallResult {
+            success: true,
+            message: format!("Git 已安装: {}", version),
+            error: None,
+            timings: Vec::new(),
+        });
+    }
+    info!("[安装Git] 开始安装 Git...");
+    let result = tokio::task::spawn_blocking(|| {
+        if platform::is_windows() {
+            winpkg::install(WindowsPackage::Git).map(|_| InstallResult {
+                success: true,
+                message: "Git 安装成功，可能需要重启应用以刷新 PATH".to_string(),
+                error: None,
+                timings: Vec::new(),
+            })
+        } else if platform::is_macos() {
+            install_macos()
+        } else {
+            install_linux()
+        }
+    })
+    .await
+    .map_err(|e| format!("安装 Git 失败: {}", e))?;
+
+    match &result {
+        Ok(r) if r.success => info!("[安装Git] ✓ {}", r.message),
+        Ok(r) => warn!("[安装Git] {}", r.message),
+        Err(e) => warn!("[安装Git] ✗ 安装失败: {}", e),
+    }
+    result.or_else(|e| {
+        Ok(InstallResult {
+            success: false,
+            message: "Git 安装失败".to_string(),
+            error: Some(format!("{}。{}", e, remediation())),
+            timings: Vec::new(),
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn linux_install_command_follows_package_manager_priority() {
+        assert_eq!(
+            linux_install_command(|pm| pm == "dnf" || pm == "yum"),
+            Some("dnf install -y git")
+        );
+        assert_eq!(
+            linux_install_command(|pm| pm == "pacman"),
+            Some("pacman -S --noconfirm git")
+        );
+        assert_eq!(linux_install_command(|_| false), None);
+    }
+}
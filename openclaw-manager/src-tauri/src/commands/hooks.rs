@@ -0,0 +1,545 @@
+use crate::commands::{policy, settings};
+use crate::utils::{audit, encoding, file, platform, shell};
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use openclaw_macros::guarded;
+use tauri::command;
+
+/// 钩子输出保留的最大字符数（写入审计日志）
+const OUTPUT_LIMIT: usize = 4000;
+/// 脚本结束后等待输出读取完成的最长时间（脚本留下的后台进程可能一直占用输出管道）
+const OUTPUT_GRACE: Duration = Duration::from_secs(2);
+
+/// 生命周期事件
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HookEvent {
+    PreInstall,
+    PostInstall,
+    PreUpdate,
+    PostGatewayStart,
+}
+
+impl HookEvent {
+    pub const ALL: [HookEvent; 4] = [
+        HookEvent::PreInstall,
+        HookEvent::PostInstall,
+        HookEvent::PreUpdate,
+        HookEvent::PostGatewayStart,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HookEvent::PreInstall => "pre_install",
+            HookEvent::PostInstall => "post_install",
+            HookEvent::PreUpdate => "pre_update",
+            HookEvent::PostGatewayStart => "post_gateway_start",
+        }
+    }
+
+    fn parse(name: &str) -> Option<HookEvent> {
+        Self::ALL.into_iter().find(|e| e.as_str() == name)
+    }
+
+    /// pre_* 钩子失败会中止对应操作
+    fn is_pre(&self) -> bool {
+        matches!(self, HookEvent::PreInstall | HookEvent::PreUpdate)
+    }
+}
+
+/// 钩子设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HookSettings {
+    pub enabled: bool,
+    /// 单个钩子脚本的超时（秒）
+    pub timeout_secs: u64,
+}
+
+impl Default for HookSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            timeout_secs: 120,
+        }
+    }
+}
+
+/// 已注册的钩子脚本
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HookScript {
+    pub event: HookEvent,
+    pub name: String,
+    pub path: String,
+}
+
+/// 单个钩子的执行结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HookResult {
+    pub event: HookEvent,
+    pub name: String,
+    pub success: bool,
+    pub exit_code: Option<i32>,
+    pub timed_out: bool,
+    pub duration_ms: u64,
+    pub output: String,
+}
+
+/// 钩子目录：Manager 数据目录下 hooks/<事件名>/，目录内脚本按文件名顺序执行
+pub fn hooks_dir() -> PathBuf {
+    Path::new(&platform::get_manager_data_dir()).join("hooks")
+}
+
+fn event_dir(hooks_dir: &Path, event: HookEvent) -> PathBuf {
+    hooks_dir.join(event.as_str())
+}
+
+/// 某事件下的脚本（按文件名排序，忽略隐藏文件）
+fn list_scripts(hooks_dir: &Path, event: HookEvent) -> Vec<PathBuf> {
+    let mut scripts: Vec<PathBuf> = std::fs::read_dir(event_dir(hooks_dir, event))
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| p.is_file())
+                .filter(|p| {
+                    p.file_name()
+                        .map(|n| !n.to_string_lossy().starts_with('.'))
+                        .unwrap_or(false)
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    scripts.sort();
+    scripts
+}
+
+/// 按扩展名选择解释器
+fn interpreter(script: &Path) -> (String, Vec<String>) {
+    let path = script.to_string_lossy().to_string();
+    let ext = script
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match ext.as_str() {
+        "sh" => ("bash".to_string(), vec![path]),
+        "ps1" => (
+            "powershell".to_string(),
+            vec![
+                "-NoProfile".to_string(),
+                "-ExecutionPolicy".to_string(),
+                "Bypass".to_string(),
+                "-File".to_string(),
+                path,
+            ],
+        ),
+        "cmd" | "bat" => ("cmd".to_string(), vec!["/C".to_string(), path]),
+        "js" | "mjs" => ("node".to_string(), vec![path]),
+        _ => (path, Vec::new()),
+    }
+}
+
+/// 钩子脚本可用的环境变量
+fn hook_env(event: HookEvent, extra: &[(&str, String)]) -> Vec<(String, String)> {
+    let mut env = vec![
+        (
+            "OPENCLAW_HOOK_EVENT".to_string(),
+            event.as_str().to_string(),
+        ),
+        (
+            "OPENCLAW_MANAGER_VERSION".to_string(),
+            env!("CARGO_PKG_VERSION").to_string(),
+        ),
+        (
+            "OPENCLAW_MANAGER_DATA_DIR".to_string(),
+            platform::get_manager_data_dir(),
+        ),
+        (
+            "OPENCLAW_CONFIG_DIR".to_string(),
+            platform::get_config_dir(),
+        ),
+        (
+            "OPENCLAW_CONFIG_FILE".to_string(),
+            platform::get_config_file_path(),
+        ),
+        ("OPENCLAW_OS".to_string(), platform::get_os()),
+    ];
+    env.extend(extra.iter().map(|(k, v)| (k.to_string(), v.clone())));
+    env
+}
+
+/// 截取输出末尾（保留最后的报错信息）
+fn tail(output: &str, limit: usize) -> String {
+    let count = output.chars().count();
+    if count <= limit {
+        return output.to_string();
+    }
+    output.chars().skip(count - limit).collect()
+}
+
+/// 执行单个钩子脚本，超时后强制结束脚本及其启动的所有进程
+fn run_script(
+    script: &Path,
+    event: HookEvent,
+    env: &[(String, String)],
+    timeout: Duration,
+) -> HookResult {
+    let name = script
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let start = Instant::now();
+    let (program, args) = interpreter(script);
+    let args: Vec<&str> = args.iter().map(|a| a.as_str()).collect();
+    let cwd = script.parent().unwrap_or(Path::new("."));
+
+    let mut command = shell::piped_command(&program, &args, cwd);
+    command.envs(env.iter().map(|(k, v)| (k.as_str(), v.as_str())));
+    shell::ProcessTree::prepare(&mut command);
+
+    let failed = |output: String| HookResult {
+        event,
+        name: name.clone(),
+        success: false,
+        exit_code: None,
+        timed_out: false,
+        duration_ms: start.elapsed().as_millis() as u64,
+        output,
+    };
+    let mut child = match command.spawn() {
+        Ok(c) => c,
+        Err(e) => return failed(format!("启动钩子失败: {}", e)),
+    };
+    let tree = shell::ProcessTree::attach(&child);
+
+    // 输出在独立线程读取，避免管道写满导致脚本阻塞
+    let readers: Vec<_> = [
+        child
+            .stdout
+            .take()
+            .map(|r| Box::new(r) as Box<dyn Read + Send>),
+        child
+            .stderr
+            .take()
+            .map(|r| Box::new(r) as Box<dyn Read + Send>),
+    ]
+    .into_iter()
+    .flatten()
+    .map(|mut reader| {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let mut buf = Vec::new();
+            let _ = reader.read_to_end(&mut buf);
+            let _ = sender.send(buf);
+        });
+        receiver
+    })
+    .collect();
+
+    let mut timed_out = false;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break Some(status),
+            Ok(None) if start.elapsed() >= timeout => {
+                timed_out = true;
+                tree.kill();
+                let _ = child.kill();
+                let _ = child.wait();
+                break None;
+            }
+            Ok(None) => std::thread::sleep(Duration::from_millis(100)),
+            Err(e) => return failed(format!("等待钩子结束失败: {}", e)),
+        }
+    };
+
+    // 脚本已结束，其后台进程仍占用管道时不再等待
+    let deadline = Instant::now() + OUTPUT_GRACE;
+    let output: Vec<String> = readers
+        .into_iter()
+        .filter_map(|r| {
+            r.recv_timeout(deadline.saturating_duration_since(Instant::now()))
+                .ok()
+        })
+        .map(|buf| encoding::decode_output(&buf).trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+    let mut output = tail(&output.join("\n"), OUTPUT_LIMIT);
+    if timed_out {
+        output = format!(
+            "{}\n钩子超时（{}秒），已强制结束",
+            output,
+            timeout.as_secs()
+        );
+    }
+
+    HookResult {
+        event,
+        name,
+        success: status.map(|s| s.success()).unwrap_or(false),
+        exit_code: status.and_then(|s| s.code()),
+        timed_out,
+        duration_ms: start.elapsed().as_millis() as u64,
+        output,
+    }
+}
+
+/// 将钩子执行结果写入审计日志
+fn record_audit(result: &HookResult) {
+    audit::record(
+        "hook",
+        &format!("{}/{}", result.event.as_str(), result.name),
+        result.success,
+        json!({
+            "exit_code": result.exit_code,
+            "timed_out": result.timed_out,
+            "duration_ms": result.duration_ms,
+            "output": result.output,
+        }),
+    );
+}
+
+fn run_hooks_in(
+    hooks_dir: &Path,
+    event: HookEvent,
+    extra_env: &[(&str, String)],
+    timeout: Duration,
+    record: fn(&HookResult),
+) -> Result<Vec<HookResult>, String> {
+    let scripts = list_scripts(hooks_dir, event);
+    if scripts.is_empty() {
+        return Ok(Vec::new());
+    }
+    info!("[钩子] 执行 {} 钩子 ({} 个)", event.as_str(), scripts.len());
+
+    let env = hook_env(event, extra_env);
+    let mut results = Vec::new();
+    for script in scripts {
+        let result = run_script(&script, event, &env, timeout);
+        record(&result);
+        if result.success {
+            info!(
+                "[钩子] ✓ {}/{} ({}ms)",
+                event.as_str(),
+                result.name,
+                result.duration_ms
+            );
+        } else {
+            error!(
+                "[钩子] ✗ {}/{}: {}",
+                event.as_str(),
+                result.name,
+                result.output
+            );
+            if event.is_pre() {
+                return Err(format!(
+                    "{} 钩子 {} 执行失败，已中止操作: {}",
+                    event.as_str(),
+                    result.name,
+                    result.output
+                ));
+            }
+        }
+        results.push(result);
+    }
+    Ok(results)
+}
+
+/// 执行某个生命周期事件的钩子
+/// pre_* 钩子失败时返回错误，调用方应中止操作；post_* 钩子失败只记录
+pub fn run_hooks(
+    event: HookEvent,
+    extra_env: &[(&str, String)],
+) -> Result<Vec<HookResult>, String> {
+    let hook_settings = settings::load_settings().hooks;
+    if !hook_settings.enabled {
+        return Ok(Vec::new());
+    }
+    let timeout = Duration::from_secs(hook_settings.timeout_secs.max(1));
+    run_hooks_in(&hooks_dir(), event, extra_env, timeout, record_audit)
+}
+
+/// 列出已注册的钩子脚本
+#[guarded]
+#[command]
+pub async fn list_hooks() -> Result<Vec<HookScript>, String> {
+    let dir = hooks_dir();
+    Ok(HookEvent::ALL
+        .into_iter()
+        .flat_map(|event| {
+            list_scripts(&dir, event)
+                .into_iter()
+                .map(move |path| HookScript {
+                    event,
+                    name: path
+                        .file_name()
+                        .map(|n| n.to_string_lossy().to_string())
+                        .unwrap_or_default(),
+                    path: path.to_string_lossy().to_string(),
+                })
+        })
+        .collect())
+}
+
+/// 注册钩子：将脚本复制到钩子目录（钩子会在安装、更新等流程中执行，需要确认令牌）
+#[guarded]
+#[command]
+pub async fn register_hook(
+    event: String,
+    script_path: String,
+    confirm_token: Option<String>,
+) -> Result<HookScript, String> {
+    let event = HookEvent::parse(&event).ok_or_else(|| format!("未知的钩子事件: {}", event))?;
+    policy::require_confirmation("register_hook", confirm_token.as_deref())?;
+    let source = Path::new(&script_path);
+    if !source.is_file() {
+        return Err(format!("脚本不存在: {}", script_path));
+    }
+    let name = source
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .ok_or_else(|| "脚本文件名无效".to_string())?;
+
+    let dir = event_dir(&hooks_dir(), event);
+    std::fs::create_dir_all(&dir).map_err(|e| format!("创建钩子目录失败: {}", e))?;
+    let target = dir.join(&name);
+    file::copy_atomic(source, &target).map_err(|e| format!("复制钩子脚本失败: {}", e))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = std::fs::set_permissions(&target, std::fs::Permissions::from_mode(0o755));
+    }
+
+    info!("[钩子] 已注册 {}/{}", event.as_str(), name);
+    Ok(HookScript {
+        event,
+        name,
+        path: target.to_string_lossy().to_string(),
+    })
+}
+
+/// 删除钩子脚本（需要确认令牌）
+#[guarded]
+#[command]
+pub async fn remove_hook(
+    event: String,
+    name: String,
+    confirm_token: Option<String>,
+) -> Result<(), String> {
+    let event = HookEvent::parse(&event).ok_or_else(|| format!("未知的钩子事件: {}", event))?;
+    if name.contains('/') || name.contains('\\') || name.starts_with('.') {
+        return Err(format!("无效的钩子名称: {}", name));
+    }
+    policy::require_confirmation("remove_hook", confirm_token.as_deref())?;
+    let path = event_dir(&hooks_dir(), event).join(&name);
+    std::fs::remove_file(&path).map_err(|e| format!("删除钩子失败: {}", e))?;
+    warn!("[钩子] 已删除 {}/{}", event.as_str(), name);
+    Ok(())
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn make_temp_dir(prefix: &str) -> PathBuf {
+        let mut base = std::env::temp_dir();
+        let suffix = format!(
+            "{}_{}",
+            prefix,
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_nanos()
+        );
+        base.push(suffix);
+        std::fs::create_dir_all(&base).unwrap();
+        base
+    }
+
+    #[test]
+    fn failing_pre_hook_aborts_and_receives_env() {
+        let dir = make_temp_dir("openclaw_hooks");
+        let pre_dir = event_dir(&dir, HookEvent::PreInstall);
+        std::fs::create_dir_all(&pre_dir).unwrap();
+        std::fs::write(
+            pre_dir.join("10-check.sh"),
+            "echo \"event=$OPENCLAW_HOOK_EVENT\"\nexit 3\n",
+        )
+        .unwrap();
+
+        let err = run_hooks_in(
+            &dir,
+            HookEvent::PreInstall,
+            &[],
+            Duration::from_secs(10),
+            |_| {},
+        )
+        .unwrap_err();
+        assert!(err.contains("event=pre_install"));
+
+        // post 钩子失败不会中止
+        let post_dir = event_dir(&dir, HookEvent::PostInstall);
+        std::fs::create_dir_all(&post_dir).unwrap();
+        std::fs::write(post_dir.join("slow.sh"), "exec sleep 5\n").unwrap();
+        let results = run_hooks_in(
+            &dir,
+            HookEvent::PostInstall,
+            &[],
+            Duration::from_millis(300),
+            |_| {},
+        )
+        .unwrap();
+        assert_eq!(results.len(), 1);
+        assert!(results[0].timed_out && !results[0].success);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn timeout_kills_forked_children() {
+        let dir = make_temp_dir("openclaw_hooks_tree");
+        let post_dir = event_dir(&dir, HookEvent::PostGatewayStart);
+        std::fs::create_dir_all(&post_dir).unwrap();
+        // 后台子进程继承了输出管道，不结束它时读取输出会一直等待
+        std::fs::write(
+            post_dir.join("fork.sh"),
+            "sleep 30 &\necho $! > child.pid\nsleep 30\n",
+        )
+        .unwrap();
+
+        let start = Instant::now();
+        let results = run_hooks_in(
+            &dir,
+            HookEvent::PostGatewayStart,
+            &[],
+            Duration::from_millis(500),
+            |_| {},
+        )
+        .unwrap();
+        assert!(results[0].timed_out);
+        assert!(start.elapsed() < Duration::from_secs(10));
+
+        let pid = std::fs::read_to_string(post_dir.join("child.pid")).unwrap();
+        let alive = || {
+            std::process::Command::new("ps")
+                .args(["-o", "stat=", "-p", pid.trim()])
+                .output()
+                .map(|o| {
+                    let stat = String::from_utf8_lossy(&o.stdout).trim().to_string();
+                    !stat.is_empty() && !stat.starts_with('Z')
+                })
+                .unwrap_or(false)
+        };
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while alive() && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        assert!(!alive(), "钩子启动的子进程没有被结束");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
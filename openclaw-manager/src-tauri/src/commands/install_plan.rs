@@ -0,0 +1,572 @@
+use crate::commands::installer::{self, MacNodeInstallMethod, NodeInstallOptions};
+use crate::commands::winpkg::{self, WindowsPackage, WindowsPackageManager};
+use crate::commands::{ffmpeg, git, node_dist, settings};
+use crate::utils::{platform, shell};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use openclaw_macros::guarded;
+use tauri::command;
+
+/// 可以规划安装步骤的组件
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InstallComponent {
+    Nodejs,
+    Openclaw,
+    Git,
+    Ffmpeg,
+}
+
+/// 安装步骤
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlanStep {
+    pub id: String,
+    pub description: String,
+    /// 使用的包管理器（winget / brew / apt-get 等）
+    pub package_manager: Option<String>,
+    /// 执行的命令
+    pub command: Option<String>,
+    /// 使用的安装包（本地文件或下载地址）
+    pub artifact: Option<String>,
+    /// 是否需要管理员权限（UAC / 管理员密码 / pkexec）
+    pub elevation: bool,
+    pub estimated_seconds: u64,
+    /// 备用步骤：只在前面的步骤失败时执行
+    pub fallback: bool,
+}
+
+/// 安装计划：安装程序在这台机器上会依次执行的步骤（不执行任何操作）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstallPlan {
+    pub component: InstallComponent,
+    pub os: String,
+    /// 已安装的版本（满足要求时安装程序会直接返回）
+    pub installed_version: Option<String>,
+    /// 无法安装的原因
+    pub blocker: Option<String>,
+    /// 主流程（不含备用步骤）是否需要管理员权限
+    pub requires_elevation: bool,
+    /// 主流程预计耗时
+    pub estimated_seconds: u64,
+    pub steps: Vec<PlanStep>,
+}
+
+/// 规划时用到的本机信息
+#[derive(Debug, Clone, Default)]
+struct PlanContext {
+    os: String,
+    /// 可用的命令（brew、pkexec、apt-get 等）
+    commands: Vec<String>,
+    /// tool 目录中的 Node.js 离线安装包
+    local_node_installer: Option<String>,
+    /// tool 目录中的 lnode.js
+    lnode_tool: bool,
+    mac_method: MacNodeInstallMethod,
+    windows_pm: Option<WindowsPackageManager>,
+    windows_pm_for_git: Option<WindowsPackageManager>,
+    windows_pm_for_ffmpeg: Option<WindowsPackageManager>,
+    node_version: Option<String>,
+    node_ok: bool,
+    openclaw_version: Option<String>,
+    git_version: Option<String>,
+    ffmpeg_version: Option<String>,
+    ffmpeg_ok: bool,
+    /// npm 全局目录是否可写（不可写时 npm install -g 需要 sudo）
+    npm_global_writable: bool,
+    npm_package: String,
+    npm_args: String,
+    node_dist_base: String,
+    ffmpeg_static: Option<(String, String)>,
+}
+
+/// 规划时检测的命令
+const PROBED_COMMANDS: &[&str] = &[
+    "brew", "pkexec", "apt-get", "dnf", "yum", "pacman", "zypper", "apk",
+];
+
+impl PlanContext {
+    fn has(&self, name: &str) -> bool {
+        self.commands.iter().any(|c| c == name)
+    }
+}
+
+fn step(id: &str, description: &str, estimated_seconds: u64) -> PlanStep {
+    PlanStep {
+        id: id.to_string(),
+        description: description.to_string(),
+        package_manager: None,
+        command: None,
+        artifact: None,
+        elevation: false,
+        estimated_seconds,
+        fallback: false,
+    }
+}
+
+impl PlanStep {
+    fn command(mut self, command: impl Into<String>) -> Self {
+        self.command = Some(command.into());
+        self
+    }
+
+    fn package_manager(mut self, pm: &str) -> Self {
+        self.package_manager = Some(pm.to_string());
+        self
+    }
+
+    fn artifact(mut self, artifact: impl Into<String>) -> Self {
+        self.artifact = Some(artifact.into());
+        self
+    }
+
+    fn elevated(mut self) -> Self {
+        self.elevation = true;
+        self
+    }
+
+    fn fallback(mut self) -> Self {
+        self.fallback = true;
+        self
+    }
+}
+
+/// 通过 Windows 包管理器安装的步骤（winget / choco 安装 MSI 时会弹出 UAC）
+fn windows_pm_step(pm: WindowsPackageManager, package: WindowsPackage) -> Option<PlanStep> {
+    let command = pm.install_command(package)?;
+    let mut s = step("package_manager", "通过包管理器安装", 120)
+        .package_manager(pm.executable())
+        .command(command);
+    if pm != WindowsPackageManager::Scoop {
+        s = s.elevated();
+    }
+    Some(s)
+}
+
+fn plan_node(ctx: &PlanContext) -> (Vec<PlanStep>, Option<String>) {
+    let major = node_dist::NODE_MAJOR;
+    let mut steps = Vec::new();
+    let mut blocker = None;
+    match ctx.os.as_str() {
+        "windows" => {
+            let mut has_primary = false;
+            if let Some(msi) = &ctx.local_node_installer {
+                steps.push(
+                    step("local_installer", "使用本地 MSI 安装包静默安装", 60)
+                        .artifact(msi.clone())
+                        .command("msiexec /i <msi> /qn /norestart")
+                        .elevated(),
+                );
+                has_primary = true;
+            }
+            if let Some(s) = ctx
+                .windows_pm
+                .and_then(|pm| windows_pm_step(pm, WindowsPackage::Node))
+            {
+                steps.push(if has_primary { s.fallback() } else { s });
+                has_primary = true;
+            }
+            let fnm = step("fnm", "通过 fnm 安装 Node.js", 90).command(format!(
+                "irm https://fnm.vercel.app/install.ps1 | iex; fnm install {}",
+                major
+            ));
+            steps.push(if has_primary { fnm.fallback() } else { fnm });
+        }
+        "macos" => {
+            if let Some(pkg) = &ctx.local_node_installer {
+                steps.push(
+                    step("local_installer", "使用本地 pkg 安装包安装", 60)
+                        .artifact(pkg.clone())
+                        .command("installer -pkg <pkg> -target /")
+                        .elevated(),
+                );
+            }
+            let use_homebrew = match ctx.mac_method {
+                MacNodeInstallMethod::Homebrew => true,
+                MacNodeInstallMethod::OfficialPkg => false,
+                MacNodeInstallMethod::Auto => ctx.has("brew"),
+            };
+            let mut primary = Vec::new();
+            if use_homebrew {
+                if !ctx.has("brew") {
+                    primary.push(
+                        step("install_homebrew", "安装 Homebrew", 300)
+                            .command("/bin/bash -c \"$(curl -fsSL https://raw.githubusercontent.com/Homebrew/install/HEAD/install.sh)\"")
+                            .elevated(),
+                    );
+                }
+                primary.push(
+                    step("homebrew", "通过 Homebrew 安装 Node.js", 180)
+                        .package_manager("brew")
+                        .command(format!(
+                            "brew install node@{0} && brew link --overwrite node@{0}",
+                            major
+                        )),
+                );
+            } else {
+                primary.push(
+                    step("download", "下载官方 pkg 安装包并校验 SHA-256", 60)
+                        .artifact(format!("{}/latest-v{}.x/", ctx.node_dist_base, major)),
+                );
+                primary.push(
+                    step("install_pkg", "安装官方 pkg", 60)
+                        .command("installer -pkg <pkg> -target /")
+                        .elevated(),
+                );
+            }
+            let local = ctx.local_node_installer.is_some();
+            steps.extend(
+                primary
+                    .into_iter()
+                    .map(|s| if local { s.fallback() } else { s }),
+            );
+        }
+        _ => {
+            let repo = |pm: &str, setup: &str, install: &str| {
+                vec![
+                    step("nodesource", "添加 NodeSource 软件源", 30)
+                        .package_manager(pm)
+                        .command(format!(
+                            "curl -fsSL https://{}.nodesource.com/setup_{}.x | sudo -E bash -",
+                            setup, major
+                        ))
+                        .elevated(),
+                    step("package_manager", "通过包管理器安装 Node.js", 90)
+                        .package_manager(pm)
+                        .command(format!("sudo {}", install))
+                        .elevated(),
+                ]
+            };
+            if ctx.has("apt-get") {
+                steps.extend(repo("apt-get", "deb", "apt-get install -y nodejs"));
+            } else if ctx.has("dnf") {
+                steps.extend(repo("dnf", "rpm", "dnf install -y nodejs"));
+            } else if ctx.has("yum") {
+                steps.extend(repo("yum", "rpm", "yum install -y nodejs"));
+            } else if ctx.has("pacman") {
+                steps.push(
+                    step("package_manager", "通过包管理器安装 Node.js", 90)
+                        .package_manager("pacman")
+                        .command("sudo pacman -S nodejs npm --noconfirm")
+                        .elevated(),
+                );
+            } else {
+                blocker = Some("无法检测到支持的包管理器（apt / dnf / yum / pacman）".to_string());
+            }
+        }
+    }
+    if blocker.is_none() && ctx.lnode_tool {
+        steps.push(
+            step("configure", "运行 tool/lnode.js 配置环境", 5).command("node tool/lnode.js"),
+        );
+    }
+    (steps, blocker)
+}
+
+fn plan_openclaw(ctx: &PlanContext) -> (Vec<PlanStep>, Option<String>) {
+    let blocker = (!ctx.node_ok).then(|| {
+        format!(
+            "需要先安装 Node.js {}+（当前: {}）",
+            node_dist::NODE_MAJOR,
+            ctx.node_version.as_deref().unwrap_or("未安装")
+        )
+    });
+    let mut package = step("package", "通过 npm 全局安装 OpenClaw", 120)
+        .package_manager("npm")
+        .artifact(ctx.npm_package.clone());
+    let command = format!(
+        "npm install -g {} --unsafe-perm {}",
+        ctx.npm_package, ctx.npm_args
+    );
+    package = if ctx.npm_global_writable {
+        package.command(command)
+    } else {
+        package.command(format!("sudo {}", command)).elevated()
+    };
+    let steps = vec![
+        step("pre_install_hook", "执行 pre_install 钩子", 1),
+        step("repair", "清理上次未完成安装的残留", 2),
+        package,
+        step("init_skills", "安装默认技能（browser、files、shell）", 30)
+            .command("openclaw skill install <skill>"),
+    ];
+    (steps, blocker)
+}
+
+fn plan_git(ctx: &PlanContext) -> (Vec<PlanStep>, Option<String>) {
+    if ctx.git_version.is_some() {
+        return (Vec::new(), None);
+    }
+    match ctx.os.as_str() {
+        "windows" => match ctx
+            .windows_pm_for_git
+            .and_then(|pm| windows_pm_step(pm, WindowsPackage::Git))
+        {
+            Some(s) => (vec![s], None),
+            None => (
+                Vec::new(),
+                Some("没有可用的包管理器（winget / choco / scoop）".to_string()),
+            ),
+        },
+        "macos" if ctx.has("brew") => (
+            vec![step("homebrew", "通过 Homebrew 安装 Git", 60)
+                .package_manager("brew")
+                .command("brew install git")],
+            None,
+        ),
+        "macos" => (
+            vec![step("xcode_clt", "打开 Xcode 命令行工具安装窗口", 600)
+                .command("xcode-select --install")
+                .elevated()],
+            None,
+        ),
+        _ => match git::linux_install_command(|c| ctx.has(c)) {
+            Some(cmd) if ctx.has("pkexec") => (
+                vec![step("package_manager", "通过包管理器安装 Git", 60)
+                    .package_manager(cmd.split_whitespace().next().unwrap_or_default())
+                    .command(format!("pkexec {}", cmd))
+                    .elevated()],
+                None,
+            ),
+            Some(cmd) => (
+                Vec::new(),
+                Some(format!("需要管理员权限，请在终端执行: sudo {}", cmd)),
+            ),
+            None => (
+                Vec::new(),
+                Some("未找到支持的包管理器，请手动安装 git".to_string()),
+            ),
+        },
+    }
+}
+
+fn plan_ffmpeg(ctx: &PlanContext) -> (Vec<PlanStep>, Option<String>) {
+    if ctx.ffmpeg_ok {
+        return (Vec::new(), None);
+    }
+    let mut steps = Vec::new();
+    match ctx.os.as_str() {
+        "windows" => steps.extend(
+            ctx.windows_pm_for_ffmpeg
+                .and_then(|pm| windows_pm_step(pm, WindowsPackage::Ffmpeg)),
+        ),
+        "macos" if ctx.has("brew") => steps.push(
+            step("homebrew", "通过 Homebrew 安装 ffmpeg", 120)
+                .package_manager("brew")
+                .command("brew install ffmpeg"),
+        ),
+        "macos" => {}
+        _ => {
+            if let Some(cmd) =
+                ffmpeg::linux_install_command(|c| ctx.has(c)).filter(|_| ctx.has("pkexec"))
+            {
+                steps.push(
+                    step("package_manager", "通过包管理器安装 ffmpeg", 90)
+                        .package_manager(cmd.split_whitespace().next().unwrap_or_default())
+                        .command(format!("pkexec {}", cmd))
+                        .elevated(),
+                );
+            }
+        }
+    }
+    let fallback = !steps.is_empty();
+    match &ctx.ffmpeg_static {
+        Some((url, _)) => {
+            let download =
+                step("download_static", "下载 ffmpeg 静态构建", 60).artifact(url.clone());
+            let extract = step(
+                "extract_static",
+                "解压到 Manager 工具目录并设置 FFMPEG_PATH",
+                10,
+            )
+            .command("tar -xf <archive>");
+            if fallback {
+                steps.push(download.fallback());
+                steps.push(extract.fallback());
+            } else {
+                steps.push(download);
+                steps.push(extract);
+            }
+            (steps, None)
+        }
+        None if fallback => (steps, None),
+        None => (
+            steps,
+            Some("当前平台没有可用的 ffmpeg 静态构建".to_string()),
+        ),
+    }
+}
+
+/// 根据本机信息生成安装计划
+fn build_plan(component: InstallComponent, ctx: &PlanContext) -> InstallPlan {
+    let (steps, blocker) = match component {
+        InstallComponent::Nodejs => plan_node(ctx),
+        InstallComponent::Openclaw => plan_openclaw(ctx),
+        InstallComponent::Git => plan_git(ctx),
+        InstallComponent::Ffmpeg => plan_ffmpeg(ctx),
+    };
+    let installed_version = match component {
+        InstallComponent::Nodejs => ctx.node_version.clone(),
+        InstallComponent::Openclaw => ctx.openclaw_version.clone(),
+        InstallComponent::Git => ctx.git_version.clone(),
+        InstallComponent::Ffmpeg => ctx.ffmpeg_version.clone(),
+    };
+    let primary = || steps.iter().filter(|s| !s.fallback);
+    InstallPlan {
+        component,
+        os: ctx.os.clone(),
+        installed_version,
+        requires_elevation: primary().any(|s| s.elevation),
+        estimated_seconds: primary().map(|s| s.estimated_seconds).sum(),
+        blocker,
+        steps,
+    }
+}
+
+/// npm 全局目录是否可写
+fn npm_global_writable() -> bool {
+    if platform::is_windows() {
+        return true;
+    }
+    let settings = settings::load_settings();
+    let Ok(root) = shell::run_script_with_env("npm root -g", &settings.env_overrides.npm) else {
+        // 还没有 npm 时按安装后的默认位置判断（NodeSource / 官方 pkg 安装到系统目录）
+        return false;
+    };
+    let probe = Path::new(root.trim()).join(format!(".manager-write-test-{}", std::process::id()));
+    let writable = std::fs::write(&probe, b"").is_ok();
+    let _ = std::fs::remove_file(&probe);
+    writable
+}
+
+/// 收集本机信息
+fn probe_context(component: InstallComponent, options: NodeInstallOptions) -> PlanContext {
+    let settings = settings::load_settings();
+    let os = platform::get_os();
+    let arch = platform::get_arch();
+    let tool_dir = installer::get_tool_dir().ok();
+    let local_node_installer = tool_dir
+        .as_deref()
+        .and_then(|dir| match os.as_str() {
+            "windows" => installer::find_local_node_msi(dir),
+            "macos" => installer::find_local_node_pkg(dir, &arch),
+            _ => None,
+        })
+        .map(|p| p.to_string_lossy().to_string());
+    let node_version = installer::get_node_version();
+    let ffmpeg_version = ffmpeg::ffmpeg_version();
+    PlanContext {
+        commands: PROBED_COMMANDS
+            .iter()
+            .filter(|c| shell::command_exists(c))
+            .map(|c| c.to_string())
+            .collect(),
+        local_node_installer,
+        lnode_tool: tool_dir.is_some_and(|dir| dir.join("lnode.js").exists()),
+        mac_method: options.macos_method,
+        windows_pm: winpkg::selected(WindowsPackage::Node),
+        windows_pm_for_git: winpkg::selected(WindowsPackage::Git),
+        windows_pm_for_ffmpeg: winpkg::selected(WindowsPackage::Ffmpeg),
+        node_ok: installer::check_node_version_requirement(&node_version),
+        node_version,
+        openclaw_version: (component == InstallComponent::Openclaw)
+            .then(installer::get_openclaw_version)
+            .flatten(),
+        git_version: git::git_version(),
+        ffmpeg_ok: ffmpeg_version.as_deref().is_some_and(ffmpeg::version_ok),
+        ffmpeg_version,
+        npm_global_writable: component == InstallComponent::Openclaw && npm_global_writable(),
+        npm_package: settings.openclaw_package(),
+        npm_args: settings.npm_args(),
+        node_dist_base: node_dist::dist_base(&settings).to_string(),
+        ffmpeg_static: ffmpeg::static_build().map(|(u, f)| (u.to_string(), f.to_string())),
+        os,
+    }
+}
+
+/// 获取安装计划：安装程序在这台机器上会执行的步骤、使用的包管理器和安装包、
+/// 是否需要管理员权限及预计耗时（只检测，不执行任何安装操作）
+#[guarded]
+#[command]
+pub async fn plan_install(
+    component: InstallComponent,
+    options: Option<NodeInstallOptions>,
+) -> Result<InstallPlan, String> {
+    let options = options.unwrap_or_default();
+    tokio::task::spawn_blocking(move || {
+        build_plan(component, &probe_context(component, options))
+    })
+    .await
+    .map_err(|e| format!("生成安装计划失败: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ctx(os: &str, commands: &[&str]) -> PlanContext {
+        PlanContext {
+            os: os.to_string(),
+            commands: commands.iter().map(|c| c.to_string()).collect(),
+            npm_package: "openclaw@latest".to_string(),
+            node_dist_base: "https://nodejs.org/dist".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn plans_follow_installer_branches() {
+        // Windows：本地 MSI 优先，包管理器和 fnm 作为备用
+        let mut win = ctx("windows", &[]);
+        win.local_node_installer = Some("C:\\tool\\node-v22-x64.msi".to_string());
+        win.windows_pm = Some(WindowsPackageManager::Scoop);
+        let plan = build_plan(InstallComponent::Nodejs, &win);
+        let ids: Vec<&str> = plan.steps.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, vec!["local_installer", "package_manager", "fnm"]);
+        assert!(plan.steps[1].fallback && plan.steps[2].fallback);
+        assert!(plan.requires_elevation);
+        assert_eq!(plan.estimated_seconds, 60);
+
+        // macOS 自动模式：没有 Homebrew 时下载官方 pkg
+        let plan = build_plan(InstallComponent::Nodejs, &ctx("macos", &[]));
+        assert_eq!(plan.steps[0].id, "download");
+        assert_eq!(
+            plan.steps[0].artifact.as_deref(),
+            Some("https://nodejs.org/dist/latest-v22.x/")
+        );
+        let plan = build_plan(InstallComponent::Nodejs, &ctx("macos", &["brew"]));
+        assert_eq!(plan.steps[0].package_manager.as_deref(), Some("brew"));
+        assert!(!plan.requires_elevation);
+
+        // Linux 没有支持的包管理器时给出原因
+        let plan = build_plan(InstallComponent::Nodejs, &ctx("linux", &["zypper"]));
+        assert!(plan.blocker.is_some() && plan.steps.is_empty());
+
+        // OpenClaw：npm 全局目录不可写时需要 sudo，缺少 Node.js 时给出原因
+        let plan = build_plan(InstallComponent::Openclaw, &ctx("linux", &[]));
+        let package = plan.steps.iter().find(|s| s.id == "package").unwrap();
+        assert!(package.elevation);
+        assert!(package
+            .command
+            .as_deref()
+            .unwrap()
+            .starts_with("sudo npm install -g openclaw@latest"));
+        assert!(plan.blocker.unwrap().contains("Node.js"));
+
+        // ffmpeg：包管理器优先，静态构建作为备用
+        let mut linux = ctx("linux", &["apt-get", "pkexec"]);
+        linux.ffmpeg_static = Some((
+            "https://example.com/ffmpeg.tar.xz".to_string(),
+            "ffmpeg.tar.xz".to_string(),
+        ));
+        let plan = build_plan(InstallComponent::Ffmpeg, &linux);
+        assert_eq!(
+            plan.steps[0].command.as_deref(),
+            Some("pkexec apt-get install -y ffmpeg")
+        );
+        assert!(plan.steps[1].fallback);
+        linux.ffmpeg_ok = true;
+        assert!(build_plan(InstallComponent::Ffmpeg, &linux)
+            .steps
+            .is_empty());
+    }
+}
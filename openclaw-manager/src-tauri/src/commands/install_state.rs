@@ -0,0 +1,364 @@
+use crate::commands::{events, install_timings, inventory, settings};
+use crate::utils::{file, platform, shell, time};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use openclaw_macros::guarded;
+use tauri::command;
+
+/// 安装流水线
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InstallPipeline {
+    Node,
+    Openclaw,
+}
+
+impl InstallPipeline {
+    /// 对应的耗时记录（见 install_timings）
+    fn timing_operation(&self) -> &'static str {
+        match self {
+            Self::Node => install_timings::NODE,
+            Self::Openclaw => install_timings::OPENCLAW,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepStatus {
+    /// 已开始但未结束（状态文件中出现表示上次安装在这一步被中断）
+    Running,
+    Done,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StepRecord {
+    pub name: String,
+    pub status: StepStatus,
+    pub updated_at: String,
+    pub error: Option<String>,
+}
+
+/// 未完成的安装（保存在 Manager 数据目录的 install_state.json，安装成功后删除）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PipelineState {
+    pub pipeline: InstallPipeline,
+    pub started_at: String,
+    /// 执行次数（每次重新运行安装加一）
+    pub attempts: u32,
+    pub steps: Vec<StepRecord>,
+}
+
+impl PipelineState {
+    fn step(&self, name: &str) -> Option<&StepRecord> {
+        self.steps.iter().find(|s| s.name == name)
+    }
+
+    /// 被中断的步骤
+    pub fn interrupted_step(&self) -> Option<&str> {
+        self.steps
+            .iter()
+            .find(|s| s.status == StepStatus::Running)
+            .map(|s| s.name.as_str())
+    }
+}
+
+fn state_path() -> PathBuf {
+    Path::new(&platform::get_manager_data_dir()).join("install_state.json")
+}
+
+fn load_all() -> BTreeMap<InstallPipeline, PipelineState> {
+    std::fs::read_to_string(state_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_all(states: &BTreeMap<InstallPipeline, PipelineState>) {
+    let result = serde_json::to_string_pretty(states)
+        .map_err(|e| e.to_string())
+        .and_then(|content| {
+            file::write_atomic(&state_path(), content.as_bytes()).map_err(|e| e.to_string())
+        });
+    if let Err(e) = result {
+        warn!("[安装状态] 保存安装进度失败: {}", e);
+    }
+}
+
+/// 安装步骤状态变化（推送给前端的进度事件）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstallProgress {
+    pub pipeline: InstallPipeline,
+    pub step: StepRecord,
+    /// 根据历史耗时和本次进度估算的剩余时间（毫秒），没有历史记录时为空
+    pub eta_ms: Option<u64>,
+}
+
+/// 步骤级安装进度：每一步开始和结束时写入状态文件，
+/// 中断后重新运行安装时据此跳过已完成的步骤、清理被中断步骤留下的残留
+pub struct InstallTracker {
+    state: PipelineState,
+    resumed: bool,
+}
+
+/// 开始（或继续上次未完成的）安装
+pub fn begin(pipeline: InstallPipeline) -> InstallTracker {
+    let now = time::now_rfc3339();
+    let (state, resumed) = match load_all().remove(&pipeline) {
+        Some(mut state) => {
+            state.attempts += 1;
+            info!(
+                "[安装状态] 检测到未完成的 {:?} 安装（第 {} 次运行，中断于: {}）",
+                pipeline,
+                state.attempts,
+                state.interrupted_step().unwrap_or("-")
+            );
+            (state, true)
+        }
+        None => (
+            PipelineState {
+                pipeline,
+                started_at: now,
+                attempts: 1,
+                steps: Vec::new(),
+            },
+            false,
+        ),
+    };
+    let tracker = InstallTracker { state, resumed };
+    tracker.persist();
+    tracker
+}
+
+impl InstallTracker {
+    /// 是否在继续上次未完成的安装
+    pub fn resumed(&self) -> bool {
+        self.resumed
+    }
+
+    pub fn interrupted_step(&self) -> Option<&str> {
+        self.state.interrupted_step()
+    }
+
+    /// 步骤是否已在之前的运行中完成
+    pub fn is_done(&self, step: &str) -> bool {
+        self.state
+            .step(step)
+            .is_some_and(|s| s.status == StepStatus::Done)
+    }
+
+    fn set(&mut self, step: &str, status: StepStatus, error: Option<String>) {
+        let record = StepRecord {
+            name: step.to_string(),
+            status,
+            updated_at: time::now_rfc3339(),
+            error,
+        };
+        events::publish(
+            events::INSTALL_PROGRESS_EVENT,
+            InstallProgress {
+                pipeline: self.state.pipeline,
+                step: record.clone(),
+                eta_ms: install_timings::remaining_ms(self.state.pipeline.timing_operation()),
+            },
+        );
+        match self.state.steps.iter_mut().find(|s| s.name == step) {
+            Some(existing) => *existing = record,
+            None => self.state.steps.push(record),
+        }
+        self.persist();
+    }
+
+    pub fn start(&mut self, step: &str) {
+        self.set(step, StepStatus::Running, None);
+    }
+
+    pub fn done(&mut self, step: &str) {
+        self.set(step, StepStatus::Done, None);
+    }
+
+    pub fn fail(&mut self, step: &str, error: &str) {
+        self.set(step, StepStatus::Failed, Some(error.to_string()));
+    }
+
+    /// 安装完成，删除进度记录
+    pub fn finish(self) {
+        let mut states = load_all();
+        states.remove(&self.state.pipeline);
+        save_all(&states);
+    }
+
+    fn persist(&self) {
+        let mut states = load_all();
+        states.insert(self.state.pipeline, self.state.clone());
+        save_all(&states);
+    }
+}
+
+/// 被中断的 OpenClaw 安装留下的残留
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PartialOpenclaw {
+    /// npm 安装过程中的临时目录（`node_modules/.openclaw-XXXX`）
+    pub staging_dirs: Vec<PathBuf>,
+    /// 解压了一半的包目录（没有 package.json）
+    pub broken_package: Option<PathBuf>,
+    /// 指向不存在的包的命令链接 / shim
+    pub dangling_bins: Vec<PathBuf>,
+    /// 守护进程已注册但 openclaw 不可用
+    pub orphaned_daemon: bool,
+}
+
+impl PartialOpenclaw {
+    pub fn is_clean(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+/// 检查 npm 全局目录中 openclaw 的残留
+fn scan_npm_global(root: &Path, bin_dir: &Path) -> PartialOpenclaw {
+    let mut partial = PartialOpenclaw::default();
+    for entry in std::fs::read_dir(root).into_iter().flatten().flatten() {
+        if entry
+            .file_name()
+            .to_string_lossy()
+            .starts_with(".openclaw-")
+        {
+            partial.staging_dirs.push(entry.path());
+        }
+    }
+    partial.staging_dirs.sort();
+    let package = root.join("openclaw");
+    let package_ok = package.join("package.json").is_file();
+    if package.exists() && !package_ok {
+        partial.broken_package = Some(package);
+    }
+    for name in ["openclaw", "openclaw.cmd", "openclaw.ps1"] {
+        let bin = bin_dir.join(name);
+        let Ok(meta) = bin.symlink_metadata() else {
+            continue;
+        };
+        // 符号链接目标不存在，或包已损坏时指向它的 npm shim 脚本（Windows）
+        let dangling = if meta.file_type().is_symlink() {
+            !bin.exists()
+        } else {
+            !package_ok
+                && std::fs::read_to_string(&bin)
+                    .is_ok_and(|s| s.replace('\\', "/").contains("node_modules/openclaw/"))
+        };
+        if dangling {
+            partial.dangling_bins.push(bin);
+        }
+    }
+    partial
+}
+
+/// npm 全局 node_modules 目录和命令目录
+fn npm_global_dirs() -> Option<(PathBuf, PathBuf)> {
+    let settings = settings::load_settings();
+    let root = shell::run_script_with_env("npm root -g", &settings.env_overrides.npm).ok()?;
+    let root = PathBuf::from(root.trim());
+    // Unix: <prefix>/lib/node_modules + <prefix>/bin；Windows: <prefix>\node_modules + <prefix>
+    let bin_dir = if platform::is_windows() {
+        root.parent()?.to_path_buf()
+    } else {
+        root.parent()?.parent()?.join("bin")
+    };
+    Some((root, bin_dir))
+}
+
+/// 检查被中断的 OpenClaw 安装留下的残留
+pub fn detect_partial_openclaw(openclaw_available: bool) -> PartialOpenclaw {
+    let mut partial = npm_global_dirs()
+        .map(|(root, bin_dir)| scan_npm_global(&root, &bin_dir))
+        .unwrap_or_default();
+    partial.orphaned_daemon = !openclaw_available && inventory::daemon_registration().registered;
+    partial
+}
+
+fn remove_path(path: &Path) -> Result<(), String> {
+    let result = if path.is_dir() && !path.is_symlink() {
+        std::fs::remove_dir_all(path)
+    } else {
+        std::fs::remove_file(path)
+    };
+    result.map_err(|e| format!("删除 {} 失败: {}", path.display(), e))
+}
+
+/// 清理残留，让 npm 可以重新安装（守护进程在安装完成后重新注册）
+pub fn repair_openclaw(partial: &PartialOpenclaw) -> Result<(), String> {
+    for path in partial
+        .staging_dirs
+        .iter()
+        .chain(partial.broken_package.iter())
+        .chain(partial.dangling_bins.iter())
+    {
+        info!("[安装状态] 清理未完成安装的残留: {}", path.display());
+        remove_path(path)?;
+    }
+    Ok(())
+}
+
+/// 被中断的 Node.js 安装：Linux 上 dpkg 可能停在未配置状态，导致之后的 apt 安装全部失败
+pub fn repair_node() {
+    if platform::is_windows()
+        || platform::is_macos()
+        || !shell::command_exists("dpkg")
+        || !shell::command_exists("pkexec")
+    {
+        return;
+    }
+    info!("[安装状态] 继续配置被中断的软件包安装 (dpkg --configure -a)...");
+    if let Err(e) = shell::run_command_output("pkexec", &["dpkg", "--configure", "-a"]) {
+        warn!("[安装状态] dpkg --configure -a 失败: {}", e);
+    }
+}
+
+/// 获取未完成的安装（上次安装被中断时，界面提示重新运行安装以继续）
+#[guarded]
+#[command]
+pub async fn get_install_state() -> Result<Vec<PipelineState>, String> {
+    Ok(load_all().into_values().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_leftovers_of_interrupted_npm_install() {
+        let dir = std::env::temp_dir().join(format!("install-state-{}", uuid::Uuid::new_v4()));
+        let root = dir.join("lib").join("node_modules");
+        let bin = dir.join("bin");
+        std::fs::create_dir_all(root.join(".openclaw-a1b2c3").join("dist")).unwrap();
+        std::fs::create_dir_all(root.join("openclaw").join("dist")).unwrap();
+        std::fs::create_dir_all(root.join("npm")).unwrap();
+        std::fs::create_dir_all(&bin).unwrap();
+        std::fs::write(
+            bin.join("openclaw.cmd"),
+            "@node \"%dp0%\\node_modules\\openclaw\\openclaw.mjs\" %*",
+        )
+        .unwrap();
+
+        let partial = scan_npm_global(&root, &bin);
+        assert_eq!(partial.staging_dirs, vec![root.join(".openclaw-a1b2c3")]);
+        assert_eq!(partial.broken_package, Some(root.join("openclaw")));
+        assert_eq!(partial.dangling_bins, vec![bin.join("openclaw.cmd")]);
+        repair_openclaw(&partial).unwrap();
+        assert!(scan_npm_global(&root, &bin).is_clean());
+        assert!(root.join("npm").exists());
+
+        // 完整安装的包不算残留
+        std::fs::create_dir_all(root.join("openclaw")).unwrap();
+        std::fs::write(root.join("openclaw").join("package.json"), "{}").unwrap();
+        std::fs::write(
+            bin.join("openclaw.cmd"),
+            "@node \"%dp0%\\node_modules\\openclaw\\openclaw.mjs\" %*",
+        )
+        .unwrap();
+        assert!(scan_npm_global(&root, &bin).is_clean());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
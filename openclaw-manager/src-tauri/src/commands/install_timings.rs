@@ -0,0 +1,481 @@
+//! 安装耗时：记录安装 / 更新每一步（下载、等待提权、msiexec、npm、校验等）的耗时，
+//! 随安装结果返回并通过 install-timing 事件推送；成功安装的耗时保存在 Manager 数据目录，
+//! 用于估算下次安装的剩余时间
+use crate::commands::events;
+use crate::commands::installer::InstallResult;
+use crate::utils::{file, platform, time};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use openclaw_macros::guarded;
+use tauri::command;
+
+/// 安装 Node.js（与统计事件名一致）
+pub const NODE: &str = "install_nodejs";
+/// 安装 OpenClaw
+pub const OPENCLAW: &str = "install_openclaw";
+/// 更新 OpenClaw
+pub const UPDATE: &str = "update_openclaw";
+
+/// 每种操作保留的历史记录数
+const HISTORY_SIZE: usize = 5;
+/// 提权命令输出中的开始时间标记（毫秒时间戳），用于区分等待提权和实际安装的耗时
+pub const ELEVATED_AT_MARKER: &str = "ELEVATED_AT=";
+
+/// 单个步骤的耗时
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StepTiming {
+    pub step: String,
+    pub label: String,
+    pub started_at: String,
+    pub duration_ms: u64,
+    pub success: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimingPhase {
+    Started,
+    Finished,
+}
+
+/// 步骤开始 / 结束事件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstallTimingEvent {
+    pub operation: String,
+    pub step: String,
+    pub label: String,
+    pub phase: TimingPhase,
+    /// 本步骤耗时（结束时）
+    pub duration_ms: Option<u64>,
+    pub success: Option<bool>,
+    /// 本操作已用时间
+    pub elapsed_ms: u64,
+    /// 根据历史记录估算的本步骤耗时（开始时）
+    pub step_estimate_ms: Option<u64>,
+    /// 根据历史记录估算的剩余时间
+    pub remaining_ms: Option<u64>,
+}
+
+/// 一次成功的安装
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct TimingRecord {
+    recorded_at: String,
+    total_ms: u64,
+    steps: Vec<StepTiming>,
+}
+
+/// 根据最近几次成功安装估算的耗时（取中位数）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct InstallEstimate {
+    pub operation: String,
+    /// 参与估算的记录数（为 0 时没有估算）
+    pub samples: usize,
+    pub total_ms: Option<u64>,
+    pub steps: BTreeMap<String, u64>,
+}
+
+/// 进行中的操作
+struct Run {
+    started: Instant,
+    steps: Vec<StepTiming>,
+    estimate: InstallEstimate,
+    /// 正在执行的步骤
+    current: Option<(String, Instant)>,
+}
+
+impl Run {
+    fn remaining(&self) -> Option<u64> {
+        let current = self
+            .current
+            .as_ref()
+            .map(|(step, started)| (step.as_str(), started.elapsed()));
+        remaining(&self.estimate, &self.steps, current)
+    }
+}
+
+static RUNS: Mutex<BTreeMap<String, Run>> = Mutex::new(BTreeMap::new());
+
+fn history_path() -> PathBuf {
+    Path::new(&platform::get_manager_data_dir()).join("install_timings.json")
+}
+
+fn load_history() -> BTreeMap<String, Vec<TimingRecord>> {
+    std::fs::read_to_string(history_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_history(history: &BTreeMap<String, Vec<TimingRecord>>) {
+    let result = serde_json::to_string_pretty(history)
+        .map_err(|e| e.to_string())
+        .and_then(|content| {
+            file::write_atomic(&history_path(), content.as_bytes()).map_err(|e| e.to_string())
+        });
+    if let Err(e) = result {
+        warn!("[安装耗时] 保存耗时记录失败: {}", e);
+    }
+}
+
+/// 步骤名称
+fn label(step: &str) -> &str {
+    match step {
+        "download" => "下载",
+        "elevation" => "等待管理员授权",
+        "msiexec" => "msiexec 安装",
+        "pkg_install" => "pkg 安装",
+        "package_manager" => "包管理器安装",
+        "homebrew" => "Homebrew 安装",
+        "fnm" => "fnm 安装",
+        "npm" => "npm 安装",
+        "verification" => "校验安装",
+        "configure" => "环境配置",
+        "repair" => "清理残留",
+        "daemon" => "注册守护进程",
+        "init_skills" => "初始化技能",
+        "stop_gateway" => "停止 Gateway",
+        "health_check" => "健康检查",
+        "rollback" => "回滚",
+        other => other,
+    }
+}
+
+fn median(mut values: Vec<u64>) -> Option<u64> {
+    values.sort_unstable();
+    values.get(values.len() / 2).copied()
+}
+
+fn estimate_from(operation: &str, records: &[TimingRecord]) -> InstallEstimate {
+    let mut per_step: BTreeMap<String, Vec<u64>> = BTreeMap::new();
+    for step in records.iter().flat_map(|r| &r.steps) {
+        per_step
+            .entry(step.step.clone())
+            .or_default()
+            .push(step.duration_ms);
+    }
+    InstallEstimate {
+        operation: operation.to_string(),
+        samples: records.len(),
+        total_ms: median(records.iter().map(|r| r.total_ms).collect()),
+        steps: per_step
+            .into_iter()
+            .filter_map(|(step, values)| median(values).map(|v| (step, v)))
+            .collect(),
+    }
+}
+
+/// 剩余时间：未完成的步骤按历史耗时估算，并按本次已完成步骤相对历史的快慢缩放
+/// （反映本次的网络和磁盘速度）；正在执行的步骤扣除已用时间。没有历史记录时为空
+fn remaining(
+    estimate: &InstallEstimate,
+    done: &[StepTiming],
+    current: Option<(&str, Duration)>,
+) -> Option<u64> {
+    if estimate.steps.is_empty() {
+        return None;
+    }
+    let (actual, expected) = done
+        .iter()
+        .filter_map(|t| estimate.steps.get(&t.step).map(|e| (t.duration_ms, *e)))
+        .fold((0, 0), |(a, e), (x, y)| (a + x, e + y));
+    let pace = if expected > 0 {
+        (actual as f64 / expected as f64).clamp(0.25, 4.0)
+    } else {
+        1.0
+    };
+    let pending = estimate
+        .steps
+        .iter()
+        .filter(|(step, _)| !done.iter().any(|t| &t.step == *step))
+        .map(|(step, ms)| {
+            let scaled = (*ms as f64 * pace) as u64;
+            match current {
+                Some((name, elapsed)) if name == step => {
+                    scaled.saturating_sub(elapsed.as_millis() as u64)
+                }
+                _ => scaled,
+            }
+        })
+        .sum();
+    Some(pending)
+}
+
+/// 进行中的操作的预计剩余时间（毫秒）
+pub fn remaining_ms(operation: &str) -> Option<u64> {
+    RUNS.lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(operation)
+        .and_then(Run::remaining)
+}
+
+/// 开始记录一次操作（清除同一操作未结束的记录）
+pub fn begin(operation: &str) {
+    let estimate = estimate_from(
+        operation,
+        load_history()
+            .get(operation)
+            .map(Vec::as_slice)
+            .unwrap_or(&[]),
+    );
+    if let Some(total) = estimate.total_ms {
+        info!(
+            "[安装耗时] {} 预计需要 {} 秒（{} 次记录）",
+            operation,
+            total / 1000,
+            estimate.samples
+        );
+    }
+    RUNS.lock().unwrap_or_else(|e| e.into_inner()).insert(
+        operation.to_string(),
+        Run {
+            started: Instant::now(),
+            steps: Vec::new(),
+            estimate,
+            current: None,
+        },
+    );
+}
+
+fn publish(
+    operation: &str,
+    step: &str,
+    phase: TimingPhase,
+    timing: Option<&StepTiming>,
+    run: &Run,
+) {
+    events::publish(
+        events::INSTALL_TIMING_EVENT,
+        InstallTimingEvent {
+            operation: operation.to_string(),
+            step: step.to_string(),
+            label: label(step).to_string(),
+            phase,
+            duration_ms: timing.map(|t| t.duration_ms),
+            success: timing.map(|t| t.success),
+            elapsed_ms: run.started.elapsed().as_millis() as u64,
+            step_estimate_ms: run.estimate.steps.get(step).copied(),
+            remaining_ms: run.remaining(),
+        },
+    );
+}
+
+fn record(operation: &str, step: &str, started_at: SystemTime, duration: Duration, success: bool) {
+    let timing = StepTiming {
+        step: step.to_string(),
+        label: label(step).to_string(),
+        started_at: time::from_system_time(started_at).to_rfc3339(),
+        duration_ms: duration.as_millis() as u64,
+        success,
+    };
+    info!(
+        "[安装耗时] {} / {}: {} ms{}",
+        operation,
+        step,
+        timing.duration_ms,
+        if success { "" } else { "（失败）" }
+    );
+    let mut runs = RUNS.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(run) = runs.get_mut(operation) {
+        if run.current.as_ref().is_some_and(|(name, _)| name == step) {
+            run.current = None;
+        }
+        run.steps.push(timing);
+        publish(
+            operation,
+            step,
+            TimingPhase::Finished,
+            run.steps.last(),
+            run,
+        );
+    }
+}
+
+/// 进行中的步骤，调用 `finish` 记录结果；未调用就被丢弃时记为失败
+pub struct StepTimer {
+    operation: &'static str,
+    step: &'static str,
+    started: Instant,
+    started_at: SystemTime,
+    finished: bool,
+}
+
+/// 开始一个步骤（操作未通过 `begin` 开始记录时不记录，如单独下载更新）
+pub fn step(operation: &'static str, step: &'static str) -> StepTimer {
+    if let Some(run) = RUNS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get_mut(operation)
+    {
+        run.current = Some((step.to_string(), Instant::now()));
+        publish(operation, step, TimingPhase::Started, None, run);
+    }
+    StepTimer {
+        operation,
+        step,
+        started: Instant::now(),
+        started_at: SystemTime::now(),
+        finished: false,
+    }
+}
+
+/// 从提权命令的输出中读取提权后命令开始执行的时间
+fn elevated_at(output: &str) -> Option<SystemTime> {
+    output
+        .lines()
+        .find_map(|line| line.trim().strip_prefix(ELEVATED_AT_MARKER))
+        .and_then(|ms| ms.trim().parse::<u64>().ok())
+        .map(|ms| UNIX_EPOCH + Duration::from_millis(ms))
+}
+
+impl StepTimer {
+    pub fn finish(mut self, success: bool) {
+        self.finished = true;
+        record(
+            self.operation,
+            self.step,
+            self.started_at,
+            self.started.elapsed(),
+            success,
+        );
+    }
+
+    /// 结束提权执行的步骤：输出中带有开始时间标记时，把等待授权的时间单独记为 elevation 步骤
+    pub fn finish_elevated(mut self, output: &str, success: bool) {
+        let total = self.started.elapsed();
+        let waited = elevated_at(output)
+            .and_then(|at| at.duration_since(self.started_at).ok())
+            .filter(|waited| *waited <= total);
+        let Some(waited) = waited else {
+            return self.finish(success);
+        };
+        self.finished = true;
+        record(self.operation, "elevation", self.started_at, waited, true);
+        record(
+            self.operation,
+            self.step,
+            self.started_at + waited,
+            total - waited,
+            success,
+        );
+    }
+}
+
+impl Drop for StepTimer {
+    fn drop(&mut self) {
+        if !self.finished {
+            record(
+                self.operation,
+                self.step,
+                self.started_at,
+                self.started.elapsed(),
+                false,
+            );
+        }
+    }
+}
+
+/// 结束一次操作，返回各步骤耗时；成功时保存到历史记录用于估算
+pub fn finish(operation: &str, success: bool) -> Vec<StepTiming> {
+    let Some(run) = RUNS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(operation)
+    else {
+        return Vec::new();
+    };
+    if success && !run.steps.is_empty() {
+        let mut history = load_history();
+        let records = history.entry(operation.to_string()).or_default();
+        records.push(TimingRecord {
+            recorded_at: time::now_rfc3339(),
+            total_ms: run.started.elapsed().as_millis() as u64,
+            steps: run.steps.clone(),
+        });
+        let excess = records.len().saturating_sub(HISTORY_SIZE);
+        records.drain(..excess);
+        save_history(&history);
+    }
+    run.steps
+}
+
+/// 结束一次操作，把各步骤耗时附加到安装结果
+pub fn attach(
+    operation: &str,
+    result: Result<InstallResult, String>,
+) -> Result<InstallResult, String> {
+    let timings = finish(operation, matches!(&result, Ok(r) if r.success));
+    result.map(|mut r| {
+        r.timings = timings;
+        r
+    })
+}
+
+/// 估算安装 / 更新的耗时（install_nodejs / install_openclaw / update_openclaw）
+#[guarded]
+#[command]
+pub async fn get_install_estimate(operation: String) -> Result<InstallEstimate, String> {
+    if ![NODE, OPENCLAW, UPDATE].contains(&operation.as_str()) {
+        return Err(format!("未知的操作: {}", operation));
+    }
+    let history = load_history();
+    Ok(estimate_from(
+        &operation,
+        history.get(&operation).map(Vec::as_slice).unwrap_or(&[]),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timing(step: &str, duration_ms: u64) -> StepTiming {
+        StepTiming {
+            step: step.to_string(),
+            label: label(step).to_string(),
+            started_at: String::new(),
+            duration_ms,
+            success: true,
+        }
+    }
+
+    #[test]
+    fn estimates_from_median_and_splits_elevation_wait() {
+        let records: Vec<TimingRecord> = [(30_000, 20_000), (90_000, 80_000), (40_000, 25_000)]
+            .into_iter()
+            .map(|(total_ms, npm)| TimingRecord {
+                recorded_at: String::new(),
+                total_ms,
+                steps: vec![timing("download", total_ms - npm), timing("npm", npm)],
+            })
+            .collect();
+        let estimate = estimate_from(UPDATE, &records);
+        assert_eq!(estimate.samples, 3);
+        assert_eq!(estimate.total_ms, Some(40_000));
+        assert_eq!(estimate.steps.get("npm"), Some(&25_000));
+        assert_eq!(estimate.steps.get("download"), Some(&10_000));
+        // 尚未开始：各步骤估算之和
+        assert_eq!(remaining(&estimate, &[], None), Some(35_000));
+        // 下载比平时慢一倍，npm 也按两倍估算，并扣除已用时间
+        let done = [timing("download", 20_000)];
+        assert_eq!(
+            remaining(&estimate, &done, Some(("npm", Duration::from_secs(10)))),
+            Some(40_000)
+        );
+        assert_eq!(
+            remaining(&estimate, &done, Some(("npm", Duration::from_secs(90)))),
+            Some(0)
+        );
+        assert_eq!(remaining(&estimate_from(NODE, &[]), &[], None), None);
+
+        let output = "Installing...\nELEVATED_AT=1700000012345\r\ndone";
+        assert_eq!(
+            elevated_at(output),
+            Some(UNIX_EPOCH + Duration::from_millis(1_700_000_012_345))
+        );
+        assert_eq!(elevated_at("no marker"), None);
+    }
+}
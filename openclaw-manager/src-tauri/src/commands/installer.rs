@@ -1,6 +1,24 @@
-use crate::utils::{platform, shell};
+use crate::commands::compat::{self, CompatibilityCheck};
+use crate::commands::dependencies::{self, UninstallTarget};
+use crate::commands::ffmpeg;
+use crate::commands::git;
+use crate::commands::hooks::{self, HookEvent};
+use crate::commands::install_state::{self, InstallPipeline};
+use crate::commands::install_timings::{self, StepTiming};
+use crate::commands::jobs::{self, JobKind};
+use crate::commands::monitor;
+use crate::commands::notifications::{self, NotificationTrigger};
+use crate::commands::vulnerabilities::{self, SeverityCounts};
+use crate::commands::winpkg::{self, WindowsPackage};
+use crate::commands::{
+    node_dist, policy, privacy, probe_cache, settings, source_build, telemetry, updates,
+};
+use crate::utils::{
+    file, login_env, node_paths, platform, shell, temp, time,
+};
 use serde::{Deserialize, Serialize};
-use tauri::command;
+use openclaw_macros::guarded;
+use tauri::{command, AppHandle};
 use log::{info, warn, error, debug};
 
 /// 环境检查结果
@@ -16,6 +34,16 @@ pub struct EnvironmentStatus {
     pub openclaw_installed: bool,
     /// OpenClaw 版本
     pub openclaw_version: Option<String>,
+    /// Git 是否安装（GitHub 同步和源码构建需要）
+    pub git_installed: bool,
+    /// Git 版本
+    pub git_version: Option<String>,
+    /// ffmpeg 是否安装（语音渠道和音频相关技能需要）
+    pub ffmpeg_installed: bool,
+    /// ffmpeg 版本
+    pub ffmpeg_version: Option<String>,
+    /// ffmpeg 版本是否满足要求 (>=4)
+    pub ffmpeg_version_ok: bool,
     /// 配置目录是否存在
     pub config_dir_exists: bool,
     /// 是否全部就绪
@@ -39,45 +67,68 @@ pub struct InstallResult {
     pub success: bool,
     pub message: String,
     pub error: Option<String>,
+    /// 各步骤耗时（安装 Node.js / OpenClaw 和更新 OpenClaw 时记录）
+    #[serde(default)]
+    pub timings: Vec<StepTiming>,
 }
 
 /// 检查环境状态
+#[guarded]
 #[command]
 pub async fn check_environment() -> Result<EnvironmentStatus, String> {
     info!("[环境检查] 开始检查系统环境...");
-    
+
     let os = platform::get_os();
     info!("[环境检查] 操作系统: {}", os);
-    
+
     // 检查 Node.js
     info!("[环境检查] 检查 Node.js...");
-    let node_version = get_node_version();
+    let node_version = cached_node_version();
     let node_installed = node_version.is_some();
     let node_version_ok = check_node_version_requirement(&node_version);
     info!("[环境检查] Node.js: installed={}, version={:?}, version_ok={}", 
         node_installed, node_version, node_version_ok);
-    
+
     // 检查 OpenClaw
     info!("[环境检查] 检查 OpenClaw...");
-    let openclaw_version = get_openclaw_version();
+    let openclaw_version = cached_openclaw_version();
     let openclaw_installed = openclaw_version.is_some();
     info!("[环境检查] OpenClaw: installed={}, version={:?}", 
         openclaw_installed, openclaw_version);
-    
+
+    // 检查 Git（非必需，仅 GitHub 同步和源码构建使用）
+    let git_version = git::git_version();
+    let git_installed = git_version.is_some();
+    info!("[环境检查] Git: installed={}, version={:?}", git_installed, git_version);
+
+    // 检查 ffmpeg（非必需，仅语音 / 音频功能使用）
+    let ffmpeg_version = ffmpeg::ffmpeg_version();
+    let ffmpeg_installed = ffmpeg_version.is_some();
+    let ffmpeg_version_ok = ffmpeg_version.as_deref().is_some_and(ffmpeg::version_ok);
+    info!(
+        "[环境检查] ffmpeg: installed={}, version={:?}, version_ok={}",
+        ffmpeg_installed, ffmpeg_version, ffmpeg_version_ok
+    );
+
     // 检查配置目录
     let config_dir = platform::get_config_dir();
     let config_dir_exists = std::path::Path::new(&config_dir).exists();
     info!("[环境检查] 配置目录: {}, exists={}", config_dir, config_dir_exists);
-    
+
     let ready = node_installed && node_version_ok && openclaw_installed;
     info!("[环境检查] 环境就绪状态: ready={}", ready);
-    
+
     Ok(EnvironmentStatus {
         node_installed,
         node_version,
         node_version_ok,
         openclaw_installed,
         openclaw_version,
+        git_installed,
+        git_version,
+        ffmpeg_installed,
+        ffmpeg_version,
+        ffmpeg_version_ok,
         config_dir_exists,
         ready,
         os,
@@ -86,7 +137,7 @@ pub async fn check_environment() -> Result<EnvironmentStatus, String> {
 
 /// 获取 Node.js 版本
 /// 检测多个可能的安装路径，因为 GUI 应用不继承用户 shell 的 PATH
-fn get_node_version() -> Option<String> {
+pub fn get_node_version() -> Option<String> {
     if platform::is_windows() {
         // Windows: 先尝试直接调用（如果 PATH 已更新）
         if let Ok(v) = shell::run_cmd_output("node --version") {
@@ -98,12 +149,11 @@ fn get_node_version() -> Option<String> {
         }
         
         // Windows: 检查常见的安装路径
-        let possible_paths = get_windows_node_paths();
+        let possible_paths = node_paths::system().node_candidates();
         for path in possible_paths {
             if std::path::Path::new(&path).exists() {
-                // 使用完整路径执行
-                let cmd = format!("\"{}\" --version", path);
-                if let Ok(output) = shell::run_cmd_output(&cmd) {
+                // 使用完整路径直接执行（参数数组，路径含空格/中文也安全）
+                if let Ok(output) = shell::run_command_output(&path, &["--version"]) {
                     let version = output.trim().to_string();
                     if !version.is_empty() && version.starts_with('v') {
                         info!("[环境检查] 在 {} 找到 Node.js: {}", path, version);
@@ -121,7 +171,7 @@ fn get_node_version() -> Option<String> {
         }
         
         // 检测常见的 Node.js 安装路径（macOS/Linux）
-        let possible_paths = get_unix_node_paths();
+        let possible_paths = node_paths::system().node_candidates();
         for path in possible_paths {
             if std::path::Path::new(&path).exists() {
                 if let Ok(output) = shell::run_command_output(&path, &["--version"]) {
@@ -131,11 +181,13 @@ fn get_node_version() -> Option<String> {
             }
         }
         
-        // 尝试通过 shell 加载用户环境来检测
-        if let Ok(output) = shell::run_bash_output("source ~/.zshrc 2>/dev/null || source ~/.bashrc 2>/dev/null; node --version 2>/dev/null") {
-            if !output.is_empty() && output.starts_with('v') {
-                info!("[环境检查] 通过用户 shell 找到 Node.js: {}", output.trim());
-                return Some(output.trim().to_string());
+        // 在登录 shell 的 PATH 中查找
+        if let Some(path) = login_env::find_executable("node") {
+            if let Ok(output) = shell::run_command_output(&path.display().to_string(), &["--version"]) {
+                if output.starts_with('v') {
+                    info!("[环境检查] 通过登录 shell 环境找到 Node.js: {}", output.trim());
+                    return Some(output.trim().to_string());
+                }
             }
         }
         
@@ -145,133 +197,34 @@ fn get_node_version() -> Option<String> {
 
 
 
-/// 获取 Unix 系统上可能的 Node.js 路径
-fn get_unix_node_paths() -> Vec<String> {
-    let mut paths = Vec::new();
-    
-    // Homebrew (macOS)
-    paths.push("/opt/homebrew/bin/node".to_string()); // Apple Silicon
-    paths.push("/usr/local/bin/node".to_string());     // Intel Mac
-    
-    // 系统安装
-    paths.push("/usr/bin/node".to_string());
-    
-    // nvm (检查常见版本)
-    if let Some(home) = dirs::home_dir() {
-        let home_str = home.display().to_string();
-        
-        // nvm 默认版本
-        paths.push(format!("{}/.nvm/versions/node/v22.0.0/bin/node", home_str));
-        paths.push(format!("{}/.nvm/versions/node/v22.1.0/bin/node", home_str));
-        paths.push(format!("{}/.nvm/versions/node/v22.2.0/bin/node", home_str));
-        paths.push(format!("{}/.nvm/versions/node/v22.11.0/bin/node", home_str));
-        paths.push(format!("{}/.nvm/versions/node/v22.12.0/bin/node", home_str));
-        paths.push(format!("{}/.nvm/versions/node/v23.0.0/bin/node", home_str));
-        
-        // 尝试 nvm alias default（读取 nvm 的 default alias）
-        let nvm_default = format!("{}/.nvm/alias/default", home_str);
-        if let Ok(version) = std::fs::read_to_string(&nvm_default) {
-            let version = version.trim();
-            if !version.is_empty() {
-                paths.insert(0, format!("{}/.nvm/versions/node/v{}/bin/node", home_str, version));
-            }
-        }
-        
-        // fnm
-        paths.push(format!("{}/.fnm/aliases/default/bin/node", home_str));
-        
-        // volta
-        paths.push(format!("{}/.volta/bin/node", home_str));
-        
-        // asdf
-        paths.push(format!("{}/.asdf/shims/node", home_str));
-        
-        // mise (formerly rtx)
-        paths.push(format!("{}/.local/share/mise/shims/node", home_str));
-    }
-    
-    paths
-}
-
-/// 获取 Windows 系统上可能的 Node.js 路径
-fn get_windows_node_paths() -> Vec<String> {
-    let mut paths = Vec::new();
-    
-    // 1. 标准安装路径 (Program Files)
-    paths.push("C:\\Program Files\\nodejs\\node.exe".to_string());
-    paths.push("C:\\Program Files (x86)\\nodejs\\node.exe".to_string());
-    
-    // 2. nvm for Windows (nvm4w) - 常见安装位置
-    paths.push("C:\\nvm4w\\nodejs\\node.exe".to_string());
-    
-    // 3. 用户目录下的各种安装
-    if let Some(home) = dirs::home_dir() {
-        let home_str = home.display().to_string();
-        
-        // nvm for Windows 用户安装
-        paths.push(format!("{}\\AppData\\Roaming\\nvm\\current\\node.exe", home_str));
-        
-        // fnm (Fast Node Manager) for Windows
-        paths.push(format!("{}\\AppData\\Roaming\\fnm\\aliases\\default\\node.exe", home_str));
-        paths.push(format!("{}\\AppData\\Local\\fnm\\aliases\\default\\node.exe", home_str));
-        paths.push(format!("{}\\.fnm\\aliases\\default\\node.exe", home_str));
-        
-        // volta
-        paths.push(format!("{}\\AppData\\Local\\Volta\\bin\\node.exe", home_str));
-        // volta 通过 shim 调用，检查 bin 目录即可
-        
-        // scoop 安装
-        paths.push(format!("{}\\scoop\\apps\\nodejs\\current\\node.exe", home_str));
-        paths.push(format!("{}\\scoop\\apps\\nodejs-lts\\current\\node.exe", home_str));
-        
-        // chocolatey 安装
-        paths.push("C:\\ProgramData\\chocolatey\\lib\\nodejs\\tools\\node.exe".to_string());
-    }
-    
-    // 4. 从注册表读取的安装路径（通过环境变量间接获取）
-    if let Ok(program_files) = std::env::var("ProgramFiles") {
-        paths.push(format!("{}\\nodejs\\node.exe", program_files));
-    }
-    if let Ok(program_files_x86) = std::env::var("ProgramFiles(x86)") {
-        paths.push(format!("{}\\nodejs\\node.exe", program_files_x86));
-    }
-    
-    // 5. nvm-windows 的符号链接路径（NVM_SYMLINK 环境变量）
-    if let Ok(nvm_symlink) = std::env::var("NVM_SYMLINK") {
-        paths.insert(0, format!("{}\\node.exe", nvm_symlink));
-    }
-    
-    // 6. nvm-windows 的 NVM_HOME 路径下的当前版本
-    if let Ok(nvm_home) = std::env::var("NVM_HOME") {
-        // 尝试读取当前激活的版本
-        let settings_path = format!("{}\\settings.txt", nvm_home);
-        if let Ok(content) = std::fs::read_to_string(&settings_path) {
-            for line in content.lines() {
-                if line.starts_with("current:") {
-                    if let Some(version) = line.strip_prefix("current:") {
-                        let version = version.trim();
-                        if !version.is_empty() {
-                            paths.insert(0, format!("{}\\v{}\\node.exe", nvm_home, version));
-                        }
-                    }
-                }
-            }
-        }
-    }
-    
-    paths
-}
-
 /// 获取 OpenClaw 版本
-fn get_openclaw_version() -> Option<String> {
+pub fn get_openclaw_version() -> Option<String> {
     // 使用 run_openclaw 统一处理各平台
     shell::run_openclaw(&["--version"])
         .ok()
         .map(|v| v.trim().to_string())
 }
 
+/// 获取 Node.js 版本（使用探测缓存，供界面展示；安装后的校验请直接调用 get_node_version）
+pub fn cached_node_version() -> Option<String> {
+    probe_cache::get_or_probe(probe_cache::NODE_VERSION, probe_cache::LOCAL_VERSION_TTL, || {
+        get_node_version().ok_or_else(|| "未安装 Node.js".to_string())
+    })
+    .ok()
+}
+
+/// 获取 OpenClaw 版本（使用探测缓存，供界面展示；安装后的校验请直接调用 get_openclaw_version）
+pub fn cached_openclaw_version() -> Option<String> {
+    probe_cache::get_or_probe(
+        probe_cache::OPENCLAW_VERSION,
+        probe_cache::LOCAL_VERSION_TTL,
+        || get_openclaw_version().ok_or_else(|| "未安装 OpenClaw".to_string()),
+    )
+    .ok()
+}
+
 /// 检查 Node.js 版本是否 >= 22
-fn check_node_version_requirement(version: &Option<String>) -> bool {
+pub fn check_node_version_requirement(version: &Option<String>) -> bool {
     if let Some(v) = version {
         // 解析版本号 "v22.1.0" -> 22
         let major = v.trim_start_matches('v')
@@ -285,51 +238,115 @@ fn check_node_version_requirement(version: &Option<String>) -> bool {
     }
 }
 
+/// macOS 上的 Node.js 安装方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MacNodeInstallMethod {
+    /// 已安装 Homebrew 时使用 Homebrew，否则下载官方 pkg（不会自动安装 Homebrew）
+    #[default]
+    Auto,
+    /// 使用 Homebrew（未安装时先安装 Homebrew）
+    Homebrew,
+    /// 下载官方 pkg 安装包
+    OfficialPkg,
+}
+
+/// Node.js 安装选项
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NodeInstallOptions {
+    pub macos_method: MacNodeInstallMethod,
+}
+
 /// 安装 Node.js
+#[guarded]
 #[command]
-pub async fn install_nodejs() -> Result<InstallResult, String> {
+pub async fn install_nodejs(
+    options: Option<NodeInstallOptions>,
+) -> Result<InstallResult, String> {
+    let options = options.unwrap_or_default();
+    let _fresh = probe_cache::InvalidateVersionsOnDrop;
     info!("[安装Node.js] 开始安装 Node.js...");
+    let started = std::time::Instant::now();
+    install_timings::begin(install_timings::NODE);
     let os = platform::get_os();
     info!("[安装Node.js] 检测到操作系统: {}", os);
-    
-    let result = match os.as_str() {
-        "windows" => {
-            info!("[安装Node.js] 使用 Windows 安装方式...");
-            install_nodejs_windows().await
-        },
-        "macos" => {
-            info!("[安装Node.js] 使用 macOS 安装方式 (Homebrew)...");
-            install_nodejs_macos().await
-        },
-        "linux" => {
-            info!("[安装Node.js] 使用 Linux 安装方式...");
-            install_nodejs_linux().await
-        },
-        _ => {
-            error!("[安装Node.js] 不支持的操作系统: {}", os);
-            Ok(InstallResult {
-                success: false,
-                message: "不支持的操作系统".to_string(),
-                error: Some(format!("不支持的操作系统: {}", os)),
-            })
-        },
+    let mut tracker = install_state::begin(InstallPipeline::Node);
+    if tracker.interrupted_step() == Some("package") {
+        let _ = tokio::task::spawn_blocking(install_state::repair_node).await;
+    }
+    // 继续上次未完成的安装时，Node.js 已可用则跳过安装步骤
+    let skip_package = (tracker.resumed() || tracker.is_done("package"))
+        && check_node_version_requirement(&get_node_version());
+
+    let result = if skip_package {
+        info!("[安装Node.js] Node.js 已安装，跳过安装步骤");
+        Ok(InstallResult {
+            success: true,
+            message: "Node.js 安装成功！".to_string(),
+            error: None,
+            timings: Vec::new(),
+        })
+    } else {
+        tracker.start("package");
+        match os.as_str() {
+            "windows" => {
+                info!("[安装Node.js] 使用 Windows 安装方式...");
+                install_nodejs_windows().await
+            },
+            "macos" => {
+                info!("[安装Node.js] 使用 macOS 安装方式 ({:?})...", options.macos_method);
+                install_nodejs_macos(options.macos_method).await
+            },
+            "linux" => {
+                info!("[安装Node.js] 使用 Linux 安装方式...");
+                install_nodejs_linux().await
+            },
+            _ => {
+                error!("[安装Node.js] 不支持的操作系统: {}", os);
+                Ok(InstallResult {
+                    success: false,
+                    message: "不支持的操作系统".to_string(),
+                    error: Some(format!("不支持的操作系统: {}", os)),
+                    timings: Vec::new(),
+                })
+            },
+        }
     };
-    
+
     match &result {
         Ok(r) if r.success => {
+            tracker.done("package");
             info!("[安装Node.js] ✓ 安装成功");
+            notifications::notify(
+                NotificationTrigger::InstallFinished,
+                "nodejs",
+                "Node.js 安装完成",
+                &r.message,
+            );
             // 安装成功后，尝试运行 tool/lnode.js 进行进一步配置
-            let _ = run_lnode_tool().await;
+            tracker.start("configure");
+            let timer = install_timings::step(install_timings::NODE, "configure");
+            timer.finish(run_lnode_tool().await.is_ok());
+            tracker.finish();
+        },
+        Ok(r) => {
+            tracker.fail("package", r.error.as_deref().unwrap_or(&r.message));
+            warn!("[安装Node.js] ✗ 安装失败: {}", r.message);
+        },
+        Err(e) => {
+            tracker.fail("package", e);
+            error!("[安装Node.js] ✗ 安装错误: {}", e);
         },
-        Ok(r) => warn!("[安装Node.js] ✗ 安装失败: {}", r.message),
-        Err(e) => error!("[安装Node.js] ✗ 安装错误: {}", e),
     }
-    
+    let result = install_timings::attach(install_timings::NODE, result);
+    telemetry::record_install("install_nodejs", started, &result);
+
     result
 }
 
 /// 获取 tool 目录路径
-fn get_tool_dir() -> Result<std::path::PathBuf, String> {
+pub fn get_tool_dir() -> Result<std::path::PathBuf, String> {
     // 1. 尝试当前执行文件目录（生产环境）
     if let Ok(exe_path) = std::env::current_exe() {
         if let Some(exe_dir) = exe_path.parent() {
@@ -387,7 +404,7 @@ fn score_node_msi_filename(name: &str) -> i32 {
     score
 }
 
-fn find_local_node_msi(tool_dir: &std::path::Path) -> Option<std::path::PathBuf> {
+pub fn find_local_node_msi(tool_dir: &std::path::Path) -> Option<std::path::PathBuf> {
     let entries = std::fs::read_dir(tool_dir).ok()?;
     let mut candidates: Vec<(i32, String, std::path::PathBuf)> = Vec::new();
 
@@ -436,7 +453,7 @@ fn score_node_pkg_filename(name: &str, arch: &str) -> i32 {
     score
 }
 
-fn find_local_node_pkg(tool_dir: &std::path::Path, arch: &str) -> Option<std::path::PathBuf> {
+pub fn find_local_node_pkg(tool_dir: &std::path::Path, arch: &str) -> Option<std::path::PathBuf> {
     let entries = std::fs::read_dir(tool_dir).ok()?;
     let mut candidates: Vec<(i32, String, std::path::PathBuf)> = Vec::new();
 
@@ -458,30 +475,30 @@ fn escape_applescript_string(s: &str) -> String {
 
 fn install_macos_pkg_with_admin(pkg_path: &std::path::Path) -> Result<String, String> {
     let pkg = pkg_path.to_string_lossy().to_string();
-    let cmd = format!("installer -pkg \\\"{}\\\" -target /", escape_applescript_string(&pkg));
+    // 先输出提权后的开始时间，用于区分等待授权和实际安装的耗时
+    let cmd = format!(
+        "echo {}$(date +%s)000; installer -pkg \\\"{}\\\" -target /",
+        install_timings::ELEVATED_AT_MARKER,
+        escape_applescript_string(&pkg)
+    );
     let applescript = format!("do shell script \"{}\" with administrator privileges", cmd);
     shell::run_command_output("osascript", &["-e", &applescript])
 }
 
-fn resolve_node_executable() -> Option<String> {
-    if platform::is_windows() {
-        for path in get_windows_node_paths() {
-            if std::path::Path::new(&path).exists() {
-                return Some(path);
-            }
-        }
-        None
-    } else {
-        if shell::run_command_output("node", &["--version"]).is_ok() {
-            return Some("node".to_string());
-        }
-        for path in get_unix_node_paths() {
-            if std::path::Path::new(&path).exists() {
-                return Some(path);
-            }
-        }
-        None
+pub fn resolve_node_executable() -> Option<String> {
+    if !platform::is_windows() && shell::run_command_output("node", &["--version"]).is_ok() {
+        return Some("node".to_string());
     }
+    node_paths::system().find_node()
+}
+
+/// 安装后等待环境生效并检查 Node.js 是否可用
+fn verify_node_installed() -> bool {
+    let timer = install_timings::step(install_timings::NODE, "verification");
+    std::thread::sleep(std::time::Duration::from_secs(2));
+    let installed = get_node_version().is_some();
+    timer.finish(installed);
+    installed
 }
 
 /// 运行 tool/lnode.js 进行环境配置
@@ -518,6 +535,18 @@ async fn run_lnode_tool() -> Result<(), String> {
     }
 }
 
+/// 生成以管理员权限静默安装 MSI 的 PowerShell 命令
+/// 参数以数组传给 Start-Process，MSI 路径单独加引号，支持空格、中文和长路径；
+/// 结束后输出 msiexec 的启动时间，用于区分等待 UAC 授权和实际安装的耗时
+fn msiexec_install_script(msi_path: &std::path::Path) -> String {
+    let path = platform::strip_verbatim_prefix(&msi_path.to_string_lossy());
+    format!(
+        "$p = Start-Process -FilePath 'msiexec.exe' -ArgumentList @('/i', {}, '/qn', '/norestart') -Wait -PassThru -Verb RunAs; try {{ Write-Output ('{}' + ([DateTimeOffset]$p.StartTime).ToUnixTimeMilliseconds()) }} catch {{}}; exit $p.ExitCode",
+        shell::powershell_quote(&format!("\"{}\"", path)),
+        install_timings::ELEVATED_AT_MARKER
+    )
+}
+
 /// Windows 安装 Node.js
 async fn install_nodejs_windows() -> Result<InstallResult, String> {
     // 0. 尝试本地离线安装
@@ -525,53 +554,59 @@ async fn install_nodejs_windows() -> Result<InstallResult, String> {
         info!("[安装Node.js] 检查本地安装包: {:?}", tool_dir);
         if let Some(path) = find_local_node_msi(&tool_dir) {
             info!("[安装Node.js] 发现本地安装包: {:?}", path);
-            let path_str = path.to_string_lossy().to_string();
-            let script = format!(
-                "Start-Process msiexec.exe -ArgumentList '/i \"{}\" /qn /norestart' -Wait -Verb RunAs",
-                path_str
-            );
+            let script = msiexec_install_script(&path);
 
+            let timer = install_timings::step(install_timings::NODE, "msiexec");
             match shell::run_powershell_output(&script) {
-                Ok(_) => {
+                Ok(output) => {
+                    timer.finish_elevated(&output, true);
                     info!("[安装Node.js] 本地安装执行完成");
-                    std::thread::sleep(std::time::Duration::from_secs(2));
-                    if get_node_version().is_some() {
+                    if verify_node_installed() {
                         return Ok(InstallResult {
                             success: true,
                             message: "Node.js 本地安装成功！".to_string(),
                             error: None,
+                            timings: Vec::new(),
                         });
                     }
                     warn!("[安装Node.js] 已执行安装但未检测到 Node.js（可能需要重启应用）");
                 }
-                Err(e) => warn!("[安装Node.js] 本地安装失败: {}", e),
+                Err(e) => {
+                    timer.finish(false);
+                    warn!("[安装Node.js] 本地安装失败: {}", e)
+                }
             }
         }
     }
 
-    // 使用 winget 安装 Node.js（Windows 10/11 自带）
+    // 通过包管理器安装（winget / choco / scoop，可在设置中选择首选项）
+    let timer = install_timings::step(install_timings::NODE, "package_manager");
+    let installed = winpkg::install(WindowsPackage::Node);
+    timer.finish(installed.is_ok());
+    match installed {
+        Ok(_) if get_node_version().is_some() => {
+            return Ok(InstallResult {
+                success: true,
+                message: "Node.js 安装成功！".to_string(),
+                error: None,
+                timings: Vec::new(),
+            });
+        }
+        Ok(output) => {
+            return Ok(InstallResult {
+                success: false,
+                message: "安装后需要重启应用".to_string(),
+                error: Some(output),
+                timings: Vec::new(),
+            });
+        }
+        Err(e) => warn!("[安装Node.js] 包管理器安装失败: {}", e),
+    }
+
+    // 备用方案：使用 fnm (Fast Node Manager)
     let script = r#"
 $ErrorActionPreference = 'Stop'
 
-# 检查是否已安装
-$nodeVersion = node --version 2>$null
-if ($nodeVersion) {
-    Write-Host "Node.js 已安装: $nodeVersion"
-    exit 0
-}
-
-# 优先使用 winget
-$hasWinget = Get-Command winget -ErrorAction SilentlyContinue
-if ($hasWinget) {
-    Write-Host "使用 winget 安装 Node.js..."
-    winget install --id OpenJS.NodeJS.LTS --accept-source-agreements --accept-package-agreements
-    if ($LASTEXITCODE -eq 0) {
-        Write-Host "Node.js 安装成功！"
-        exit 0
-    }
-}
-
-# 备用方案：使用 fnm (Fast Node Manager)
 Write-Host "尝试使用 fnm 安装 Node.js..."
 $fnmInstallScript = "irm https://fnm.vercel.app/install.ps1 | iex"
 Invoke-Expression $fnmInstallScript
@@ -596,7 +631,10 @@ if ($nodeVersion) {
 }
 "#;
     
-    match shell::run_powershell_output(script) {
+    let timer = install_timings::step(install_timings::NODE, "fnm");
+    let installed = shell::run_powershell_output(script);
+    timer.finish(installed.is_ok());
+    match installed {
         Ok(output) => {
             // 验证安装
             if get_node_version().is_some() {
@@ -604,12 +642,14 @@ if ($nodeVersion) {
                     success: true,
                     message: "Node.js 安装成功！请重启应用以使环境变量生效。".to_string(),
                     error: None,
+                    timings: Vec::new(),
                 })
             } else {
                 Ok(InstallResult {
                     success: false,
                     message: "安装后需要重启应用".to_string(),
                     error: Some(output),
+                    timings: Vec::new(),
                 })
             }
         }
@@ -617,37 +657,53 @@ if ($nodeVersion) {
             success: false,
             message: "Node.js 安装失败".to_string(),
             error: Some(e),
+            timings: Vec::new(),
         }),
     }
 }
 
 /// macOS 安装 Node.js
-async fn install_nodejs_macos() -> Result<InstallResult, String> {
+async fn install_nodejs_macos(method: MacNodeInstallMethod) -> Result<InstallResult, String> {
     if let Ok(tool_dir) = get_tool_dir() {
         let arch = platform::get_arch();
         if let Some(pkg_path) = find_local_node_pkg(&tool_dir, &arch) {
             info!("[安装Node.js] 发现本地 macOS 安装包: {:?}", pkg_path);
+            let timer = install_timings::step(install_timings::NODE, "pkg_install");
             match install_macos_pkg_with_admin(&pkg_path) {
                 Ok(output) => {
-                    std::thread::sleep(std::time::Duration::from_secs(2));
-                    if get_node_version().is_some() {
+                    timer.finish_elevated(&output, true);
+                    if verify_node_installed() {
                         return Ok(InstallResult {
                             success: true,
                             message: "Node.js 本地安装成功！".to_string(),
                             error: None,
+                            timings: Vec::new(),
                         });
                     }
                     return Ok(InstallResult {
                         success: false,
                         message: "Node.js 安装完成但未检测到版本，可能需要重启应用".to_string(),
                         error: Some(output),
+                        timings: Vec::new(),
                     });
                 }
-                Err(e) => warn!("[安装Node.js] 本地 pkg 安装失败: {}", e),
+                Err(e) => {
+                    timer.finish(false);
+                    warn!("[安装Node.js] 本地 pkg 安装失败: {}", e)
+                }
             }
         }
     }
 
+    let use_homebrew = match method {
+        MacNodeInstallMethod::Homebrew => true,
+        MacNodeInstallMethod::OfficialPkg => false,
+        MacNodeInstallMethod::Auto => shell::command_exists("brew"),
+    };
+    if !use_homebrew {
+        return install_nodejs_macos_pkg().await;
+    }
+
     // 使用 Homebrew 安装
     let script = r#"
 # 检查 Homebrew
@@ -671,16 +727,68 @@ brew link --overwrite node@22
 node --version
 "#;
     
-    match shell::run_bash_output(script) {
+    let timer = install_timings::step(install_timings::NODE, "homebrew");
+    let installed = shell::run_bash_output(script);
+    timer.finish(installed.is_ok());
+    match installed {
         Ok(output) => Ok(InstallResult {
             success: true,
             message: format!("Node.js 安装成功！{}", output),
             error: None,
+            timings: Vec::new(),
+        }),
+        Err(e) => Ok(InstallResult {
+            success: false,
+            message: "Node.js 安装失败".to_string(),
+            error: Some(e),
+            timings: Vec::new(),
         }),
+    }
+}
+
+/// macOS 下载官方 pkg 安装 Node.js（不依赖 Homebrew）
+async fn install_nodejs_macos_pkg() -> Result<InstallResult, String> {
+    info!("[安装Node.js] 下载官方 pkg 安装包...");
+    let settings = settings::load_settings();
+    let timer = install_timings::step(install_timings::NODE, "download");
+    let downloaded = node_dist::download_macos_pkg(&settings).await;
+    timer.finish(downloaded.is_ok());
+    let pkg_path = match downloaded {
+        Ok(path) => path,
+        Err(e) => {
+            return Ok(InstallResult {
+                success: false,
+                message: "下载 Node.js 安装包失败".to_string(),
+                error: Some(e),
+                timings: Vec::new(),
+            })
+        }
+    };
+    let timer = install_timings::step(install_timings::NODE, "pkg_install");
+    match install_macos_pkg_with_admin(&pkg_path) {
+        Ok(output) => {
+            timer.finish_elevated(&output, true);
+            if verify_node_installed() {
+                Ok(InstallResult {
+                    success: true,
+                    message: "Node.js 安装成功！".to_string(),
+                    error: None,
+                    timings: Vec::new(),
+                })
+            } else {
+                Ok(InstallResult {
+                    success: false,
+                    message: "Node.js 安装完成但未检测到版本，可能需要重启应用".to_string(),
+                    error: Some(output),
+                    timings: Vec::new(),
+                })
+            }
+        }
         Err(e) => Ok(InstallResult {
             success: false,
             message: "Node.js 安装失败".to_string(),
             error: Some(e),
+            timings: Vec::new(),
         }),
     }
 }
@@ -714,51 +822,170 @@ fi
 node --version
 "#;
     
-    match shell::run_bash_output(script) {
+    let timer = install_timings::step(install_timings::NODE, "package_manager");
+    let installed = shell::run_bash_output(script);
+    timer.finish(installed.is_ok());
+    match installed {
         Ok(output) => Ok(InstallResult {
             success: true,
             message: format!("Node.js 安装成功！{}", output),
             error: None,
+            timings: Vec::new(),
         }),
         Err(e) => Ok(InstallResult {
             success: false,
             message: "Node.js 安装失败".to_string(),
             error: Some(e),
+            timings: Vec::new(),
         }),
     }
 }
 
-/// 安装 OpenClaw
+/// 安装 OpenClaw（更新等冲突操作进行中时加入任务队列）
+#[guarded]
 #[command]
 pub async fn install_openclaw() -> Result<InstallResult, String> {
+    let Some(_exclusive) = jobs::try_exclusive("安装 OpenClaw") else {
+        return jobs::defer(JobKind::InstallOpenclaw);
+    };
+    run_install().await
+}
+
+/// 执行 OpenClaw 安装（任务队列也会调用）
+/// 按步骤记录进度，上次安装被中断时先清理残留，跳过已完成的步骤
+pub async fn run_install() -> Result<InstallResult, String> {
     info!("[安装OpenClaw] 开始安装 OpenClaw...");
-    let os = platform::get_os();
-    info!("[安装OpenClaw] 检测到操作系统: {}", os);
-    
-    let result = match os.as_str() {
-        "windows" => {
-            info!("[安装OpenClaw] 使用 Windows 安装方式...");
-            install_openclaw_windows().await
-        },
-        _ => {
-            info!("[安装OpenClaw] 使用 Unix 安装方式 (npm)...");
-            install_openclaw_unix().await
-        },
+    let started = std::time::Instant::now();
+    if let Err(e) = hooks::run_hooks(HookEvent::PreInstall, &[]) {
+        return Ok(InstallResult {
+            success: false,
+            message: "pre_install 钩子执行失败，已取消安装".to_string(),
+            error: Some(e),
+            timings: Vec::new(),
+        });
+    }
+    let mut tracker = install_state::begin(InstallPipeline::Openclaw);
+    install_timings::begin(install_timings::OPENCLAW);
+
+    // 1. 清理被中断的安装留下的残留（临时目录、解压一半的包、失效的命令链接）
+    tracker.start("repair");
+    let timer = install_timings::step(install_timings::OPENCLAW, "repair");
+    let installed = get_openclaw_version().is_some();
+    let partial =
+        tokio::task::spawn_blocking(move || install_state::detect_partial_openclaw(installed))
+            .await
+            .map_err(|e| format!("检查安装残留失败: {}", e))?;
+    if !partial.is_clean() {
+        info!("[安装OpenClaw] 检测到未完成的安装: {:?}", partial);
+    }
+    if let Err(e) = install_state::repair_openclaw(&partial) {
+        tracker.fail("repair", &e);
+        timer.finish(false);
+        return Ok(InstallResult {
+            success: false,
+            message: "清理上次未完成的安装失败".to_string(),
+            error: Some(e),
+            timings: install_timings::finish(install_timings::OPENCLAW, false),
+        });
+    }
+    tracker.done("repair");
+    timer.finish(true);
+
+    // 2. npm 安装（上次已完成且 openclaw 可用时跳过）
+    let result = if tracker.is_done("package") && installed {
+        info!("[安装OpenClaw] 上次已完成 npm 安装，跳过");
+        Ok(InstallResult {
+            success: true,
+            message: "OpenClaw 安装成功！".to_string(),
+            error: None,
+            timings: Vec::new(),
+        })
+    } else {
+        tracker.start("package");
+        let os = platform::get_os();
+        info!("[安装OpenClaw] 检测到操作系统: {}", os);
+        match os.as_str() {
+            "windows" => {
+                info!("[安装OpenClaw] 使用 Windows 安装方式...");
+                install_openclaw_windows().await
+            },
+            _ => {
+                info!("[安装OpenClaw] 使用 Unix 安装方式 (npm)...");
+                install_openclaw_unix().await
+            },
+        }
     };
-    
+
     match &result {
         Ok(r) if r.success => {
+            tracker.done("package");
+            // 3. 守护进程指向已删除的 openclaw 时重新注册
+            if partial.orphaned_daemon && !tracker.is_done("daemon") {
+                tracker.start("daemon");
+                info!("[安装OpenClaw] 重新注册网关守护进程...");
+                let timer = install_timings::step(install_timings::OPENCLAW, "daemon");
+                let registered = shell::run_openclaw(&["gateway", "install"]);
+                timer.finish(registered.is_ok());
+                match registered {
+                    Ok(_) => tracker.done("daemon"),
+                    Err(e) => {
+                        warn!("[安装OpenClaw] 重新注册网关守护进程失败: {}", e);
+                        tracker.fail("daemon", &e);
+                    }
+                }
+            }
+            tracker.finish();
             info!("[安装OpenClaw] ✓ 安装成功");
+            source_build::clear_record();
+            let version = get_openclaw_version().unwrap_or_default();
+            let _ = hooks::run_hooks(HookEvent::PostInstall, &[("OPENCLAW_VERSION", version)]);
+            notifications::notify(
+                NotificationTrigger::InstallFinished,
+                "openclaw",
+                "OpenClaw 安装完成",
+                &r.message,
+            );
             // 安装成功后，自动初始化技能和 Agent
-            let _ = init_skills_agents().await;
+            let timer = install_timings::step(install_timings::OPENCLAW, "init_skills");
+            timer.finish(init_skills_agents().await.is_ok());
+        },
+        Ok(r) => {
+            tracker.fail("package", r.error.as_deref().unwrap_or(&r.message));
+            warn!("[安装OpenClaw] ✗ 安装失败: {}", r.message);
+        },
+        Err(e) => {
+            tracker.fail("package", e);
+            error!("[安装OpenClaw] ✗ 安装错误: {}", e);
         },
-        Ok(r) => warn!("[安装OpenClaw] ✗ 安装失败: {}", r.message),
-        Err(e) => error!("[安装OpenClaw] ✗ 安装错误: {}", e),
     }
-    
+    let result = install_timings::attach(install_timings::OPENCLAW, result);
+    telemetry::record_install("install_openclaw", started, &result);
+
     result
 }
 
+/// 安装指定版本的 OpenClaw（用于批量部署锁定版本）
+pub async fn install_openclaw_version(version: &str) -> Result<InstallResult, String> {
+    let settings = settings::load_settings();
+    let cmd = format!("npm install -g openclaw@{} {}", version, settings.npm_args());
+    info!("[安装OpenClaw] 执行 {}...", cmd);
+
+    match shell::run_script_with_env(&cmd, &settings.env_overrides.npm) {
+        Ok(_) => Ok(InstallResult {
+            success: true,
+            message: format!("OpenClaw {} 安装成功", version),
+            error: None,
+            timings: Vec::new(),
+        }),
+        Err(e) => Ok(InstallResult {
+            success: false,
+            message: format!("OpenClaw {} 安装失败", version),
+            error: Some(e),
+            timings: Vec::new(),
+        }),
+    }
+}
+
 /// 初始化 Skills 和 Agents
 async fn init_skills_agents() -> Result<(), String> {
     info!("[初始化Skills] 开始初始化默认技能和 Agent...");
@@ -781,43 +1008,52 @@ async fn init_skills_agents() -> Result<(), String> {
 
 /// Windows 安装 OpenClaw
 async fn install_openclaw_windows() -> Result<InstallResult, String> {
-    let script = r#"
+    let settings = settings::load_settings();
+    let script = format!(r#"
 $ErrorActionPreference = 'Stop'
 
 # 检查 Node.js
 $nodeVersion = node --version 2>$null
-if (-not $nodeVersion) {
+if (-not $nodeVersion) {{
     Write-Host "错误：请先安装 Node.js"
     exit 1
-}
+}}
 
 Write-Host "使用 npm 安装 OpenClaw..."
-npm install -g openclaw@latest --unsafe-perm --registry=https://registry.npmmirror.com
+npm install -g {} --unsafe-perm {}
 
 # 验证安装
 $openclawVersion = openclaw --version 2>$null
-if ($openclawVersion) {
+if ($openclawVersion) {{
     Write-Host "OpenClaw 安装成功: $openclawVersion"
     exit 0
-} else {
+}} else {{
     Write-Host "OpenClaw 安装失败"
     exit 1
-}
-"#;
+}}
+"#, settings.openclaw_package(), settings.npm_args());
     
-    match shell::run_powershell_output(script) {
+    let timer = install_timings::step(install_timings::OPENCLAW, "npm");
+    let installed = shell::run_powershell_output(&script);
+    timer.finish(installed.is_ok());
+    match installed {
         Ok(output) => {
-            if get_openclaw_version().is_some() {
+            let timer = install_timings::step(install_timings::OPENCLAW, "verification");
+            let version = get_openclaw_version();
+            timer.finish(version.is_some());
+            if version.is_some() {
                 Ok(InstallResult {
                     success: true,
                     message: "OpenClaw 安装成功！".to_string(),
                     error: None,
+                    timings: Vec::new(),
                 })
             } else {
                 Ok(InstallResult {
                     success: false,
                     message: "安装后需要重启应用".to_string(),
                     error: Some(output),
+                    timings: Vec::new(),
                 })
             }
         }
@@ -825,13 +1061,15 @@ if ($openclawVersion) {
             success: false,
             message: "OpenClaw 安装失败".to_string(),
             error: Some(e),
+            timings: Vec::new(),
         }),
     }
 }
 
 /// Unix 系统安装 OpenClaw
 async fn install_openclaw_unix() -> Result<InstallResult, String> {
-    let script = r#"
+    let settings = settings::load_settings();
+    let script = format!(r#"
 # 检查 Node.js
 if ! command -v node &> /dev/null; then
     echo "错误：请先安装 Node.js"
@@ -839,34 +1077,40 @@ if ! command -v node &> /dev/null; then
 fi
 
 echo "使用 npm 安装 OpenClaw..."
-npm install -g openclaw@latest --unsafe-perm --registry=https://registry.npmmirror.com
+npm install -g {} --unsafe-perm {}
 
 # 验证安装
 openclaw --version
-"#;
+"#, settings.openclaw_package(), settings.npm_args());
     
-    match shell::run_bash_output(script) {
+    let timer = install_timings::step(install_timings::OPENCLAW, "npm");
+    let installed = shell::run_bash_output(&script);
+    timer.finish(installed.is_ok());
+    match installed {
         Ok(output) => Ok(InstallResult {
             success: true,
             message: format!("OpenClaw 安装成功！{}", output),
             error: None,
+            timings: Vec::new(),
         }),
         Err(e) => Ok(InstallResult {
             success: false,
             message: "OpenClaw 安装失败".to_string(),
             error: Some(e),
+            timings: Vec::new(),
         }),
     }
 }
 
 /// 初始化 OpenClaw 配置
+#[guarded]
 #[command]
 pub async fn init_openclaw_config() -> Result<InstallResult, String> {
     info!("[初始化配置] 开始初始化 OpenClaw 配置...");
-    
+
     let config_dir = platform::get_config_dir();
     info!("[初始化配置] 配置目录: {}", config_dir);
-    
+
     // 创建配置目录
     info!("[初始化配置] 创建配置目录...");
     if let Err(e) = std::fs::create_dir_all(&config_dir) {
@@ -875,9 +1119,10 @@ pub async fn init_openclaw_config() -> Result<InstallResult, String> {
             success: false,
             message: "创建配置目录失败".to_string(),
             error: Some(e.to_string()),
+            timings: Vec::new(),
         });
     }
-    
+
     // 创建子目录
     let subdirs = ["agents/main/sessions", "agents/main/agent", "credentials"];
     for subdir in subdirs {
@@ -889,10 +1134,11 @@ pub async fn init_openclaw_config() -> Result<InstallResult, String> {
                 success: false,
                 message: format!("创建目录失败: {}", subdir),
                 error: Some(e.to_string()),
+                timings: Vec::new(),
             });
         }
     }
-    
+
     // 设置配置目录权限为 700（与 shell 脚本 chmod 700 一致）
     // 仅在 Unix 系统上执行
     #[cfg(unix)]
@@ -909,11 +1155,11 @@ pub async fn init_openclaw_config() -> Result<InstallResult, String> {
             }
         }
     }
-    
+
     // 设置 gateway mode 为 local
     info!("[初始化配置] 执行: openclaw config set gateway.mode local");
     let result = shell::run_openclaw(&["config", "set", "gateway.mode", "local"]);
-    
+
     match result {
         Ok(output) => {
             info!("[初始化配置] ✓ 配置初始化成功");
@@ -922,6 +1168,7 @@ pub async fn init_openclaw_config() -> Result<InstallResult, String> {
                 success: true,
                 message: "配置初始化成功！".to_string(),
                 error: None,
+                timings: Vec::new(),
             })
         },
         Err(e) => {
@@ -930,12 +1177,14 @@ pub async fn init_openclaw_config() -> Result<InstallResult, String> {
                 success: false,
                 message: "配置初始化失败".to_string(),
                 error: Some(e),
+                timings: Vec::new(),
             })
         },
     }
 }
 
 /// 打开终端执行安装脚本（用于需要管理员权限的场景）
+#[guarded]
 #[command]
 pub async fn open_install_terminal(install_type: String) -> Result<String, String> {
     match install_type.as_str() {
@@ -948,33 +1197,37 @@ pub async fn open_install_terminal(install_type: String) -> Result<String, Strin
 /// 打开终端安装 Node.js
 async fn open_nodejs_install_terminal() -> Result<String, String> {
     if platform::is_windows() {
-        // Windows: 打开 PowerShell 执行安装
-        let script = r#"
+        // Windows: 打开 PowerShell 执行安装（使用首选包管理器）
+        let install = match winpkg::install_command(WindowsPackage::Node) {
+            Some(cmd) => format!(
+                "Write-Host \"正在安装 Node.js 22...\" -ForegroundColor Yellow\n{}",
+                cmd
+            ),
+            None => r#"Write-Host "请从以下地址下载安装 Node.js:" -ForegroundColor Yellow
+Write-Host "https://nodejs.org/en/download" -ForegroundColor Green
+Write-Host ""
+Start-Process "https://nodejs.org/en/download""#
+                .to_string(),
+        };
+        let script = format!(
+            r#"
 Start-Process powershell -ArgumentList '-NoExit', '-Command', '
 Write-Host "========================================" -ForegroundColor Cyan
 Write-Host "    Node.js 安装向导" -ForegroundColor White
 Write-Host "========================================" -ForegroundColor Cyan
 Write-Host ""
 
-# 检查 winget
-$hasWinget = Get-Command winget -ErrorAction SilentlyContinue
-if ($hasWinget) {
-    Write-Host "正在使用 winget 安装 Node.js 22..." -ForegroundColor Yellow
-    winget install --id OpenJS.NodeJS.LTS --accept-source-agreements --accept-package-agreements
-} else {
-    Write-Host "请从以下地址下载安装 Node.js:" -ForegroundColor Yellow
-    Write-Host "https://nodejs.org/en/download" -ForegroundColor Green
-    Write-Host ""
-    Start-Process "https://nodejs.org/en/download"
-}
+{}
 
 Write-Host ""
 Write-Host "安装完成后请重启 OpenClaw Manager" -ForegroundColor Green
 Write-Host ""
 Read-Host "按回车键关闭此窗口"
 ' -Verb RunAs
-"#;
-        shell::run_powershell_output(script)?;
+"#,
+            install
+        );
+        shell::run_powershell_output(&script)?;
         Ok("已打开安装终端".to_string())
     } else if platform::is_macos() {
         // macOS: 打开 Terminal.app
@@ -1008,17 +1261,11 @@ echo ""
 read -p "按回车键关闭此窗口..."
 "#;
         
-        let script_path = "/tmp/openclaw_install_nodejs.command";
-        std::fs::write(script_path, script_content)
+        let script_path = temp::create_script("install_nodejs.command", script_content)
             .map_err(|e| format!("创建脚本失败: {}", e))?;
         
-        std::process::Command::new("chmod")
-            .args(["+x", script_path])
-            .output()
-            .map_err(|e| format!("设置权限失败: {}", e))?;
-        
         std::process::Command::new("open")
-            .arg(script_path)
+            .arg(&script_path)
             .spawn()
             .map_err(|e| format!("启动终端失败: {}", e))?;
         
@@ -1080,17 +1327,11 @@ echo ""
 read -p "按回车键关闭此窗口..."
 "#;
         
-        let script_path = "/tmp/openclaw_install_openclaw.command";
-        std::fs::write(script_path, script_content)
+        let script_path = temp::create_script("install_openclaw.command", script_content)
             .map_err(|e| format!("创建脚本失败: {}", e))?;
         
-        std::process::Command::new("chmod")
-            .args(["+x", script_path])
-            .output()
-            .map_err(|e| format!("设置权限失败: {}", e))?;
-        
         std::process::Command::new("open")
-            .arg(script_path)
+            .arg(&script_path)
             .spawn()
             .map_err(|e| format!("启动终端失败: {}", e))?;
         
@@ -1122,20 +1363,16 @@ echo ""
 read -p "按回车键关闭..."
 "#;
         
-        let script_path = "/tmp/openclaw_install_openclaw.sh";
-        std::fs::write(script_path, script_content)
+        let script_path = temp::create_script("install_openclaw.sh", script_content)
             .map_err(|e| format!("创建脚本失败: {}", e))?;
         
-        std::process::Command::new("chmod")
-            .args(["+x", script_path])
-            .output()
-            .map_err(|e| format!("设置权限失败: {}", e))?;
-        
         // 尝试不同的终端
         let terminals = ["gnome-terminal", "xfce4-terminal", "konsole", "xterm"];
         for term in terminals {
             if std::process::Command::new(term)
-                .args(["--", script_path])
+                .arg("--")
+                .arg(&script_path)
+                .envs(platform::profile_env())
                 .spawn()
                 .is_ok()
             {
@@ -1148,17 +1385,53 @@ read -p "按回车键关闭..."
 }
 
 /// 卸载 OpenClaw
+/// 存在依赖组件时拒绝卸载，`cascade` 为 true 时先清理依赖再卸载
+#[guarded]
 #[command]
-pub async fn uninstall_openclaw() -> Result<InstallResult, String> {
+pub async fn uninstall_openclaw(
+    confirm_token: Option<String>,
+    cascade: Option<bool>,
+) -> Result<InstallResult, String> {
+    policy::require_confirmation("uninstall_openclaw", confirm_token.as_deref())?;
+    let _fresh = probe_cache::InvalidateVersionsOnDrop;
     info!("[卸载OpenClaw] 开始卸载 OpenClaw...");
     let os = platform::get_os();
     info!("[卸载OpenClaw] 检测到操作系统: {}", os);
-    
+
+    // 检查依赖：运行中的网关、守护进程、定时任务、开发链接的技能
+    let check = dependencies::check(UninstallTarget::Openclaw);
+    if !check.blocking.is_empty() {
+        let reasons: Vec<String> = check.blocking.iter().map(|d| d.detail.clone()).collect();
+        if !cascade.unwrap_or(false) {
+            warn!("[卸载OpenClaw] 存在依赖，拒绝卸载: {:?}", reasons);
+            return Ok(InstallResult {
+                success: false,
+                message: "存在依赖 OpenClaw 的组件，请确认后级联卸载".to_string(),
+                error: Some(reasons.join("\n")),
+                timings: Vec::new(),
+            });
+        }
+        info!("[卸载OpenClaw] 级联清理依赖: {:?}", reasons);
+        // 先停止网关，避免守护进程在清理期间重新拉起
+        monitor::expect_gateway_stop();
+        let _ = shell::run_openclaw(&["gateway", "stop"]);
+        if let Err(e) = dependencies::cascade(&check) {
+            error!("[卸载OpenClaw] ✗ 级联清理失败: {}", e);
+            return Ok(InstallResult {
+                success: false,
+                message: "清理依赖失败，已取消卸载".to_string(),
+                error: Some(e),
+                timings: Vec::new(),
+            });
+        }
+    }
+
     // 先停止服务
     info!("[卸载OpenClaw] 尝试停止服务...");
+    monitor::expect_gateway_stop();
     let _ = shell::run_openclaw(&["gateway", "stop"]);
     std::thread::sleep(std::time::Duration::from_millis(500));
-    
+
     let result = match os.as_str() {
         "windows" => {
             info!("[卸载OpenClaw] 使用 Windows 卸载方式...");
@@ -1169,13 +1442,16 @@ pub async fn uninstall_openclaw() -> Result<InstallResult, String> {
             uninstall_openclaw_unix().await
         },
     };
-    
+
     match &result {
-        Ok(r) if r.success => info!("[卸载OpenClaw] ✓ 卸载成功"),
+        Ok(r) if r.success => {
+            info!("[卸载OpenClaw] ✓ 卸载成功");
+            source_build::clear_record();
+        }
         Ok(r) => warn!("[卸载OpenClaw] ✗ 卸载失败: {}", r.message),
         Err(e) => error!("[卸载OpenClaw] ✗ 卸载错误: {}", e),
     }
-    
+
     result
 }
 
@@ -1195,12 +1471,14 @@ async fn uninstall_openclaw_windows() -> Result<InstallResult, String> {
                     success: true,
                     message: "OpenClaw 已成功卸载！".to_string(),
                     error: None,
+                    timings: Vec::new(),
                 })
             } else {
                 Ok(InstallResult {
                     success: false,
                     message: "卸载命令已执行，但 OpenClaw 仍然存在，请尝试手动卸载".to_string(),
                     error: Some(output),
+                    timings: Vec::new(),
                 })
             }
         }
@@ -1210,6 +1488,7 @@ async fn uninstall_openclaw_windows() -> Result<InstallResult, String> {
                 success: false,
                 message: "OpenClaw 卸载失败".to_string(),
                 error: Some(e),
+                timings: Vec::new(),
             })
         }
     }
@@ -1236,11 +1515,13 @@ fi
             success: true,
             message: format!("OpenClaw 已成功卸载！{}", output),
             error: None,
+            timings: Vec::new(),
         }),
         Err(e) => Ok(InstallResult {
             success: false,
             message: "OpenClaw 卸载失败".to_string(),
             error: Some(e),
+            timings: Vec::new(),
         }),
     }
 }
@@ -1254,65 +1535,100 @@ pub struct UpdateInfo {
     pub current_version: Option<String>,
     /// 最新版本
     pub latest_version: Option<String>,
+    /// 当前版本最近一次漏洞扫描的结果（未扫描时为 None）
+    pub vulnerabilities: Option<SeverityCounts>,
+    /// 最新版本与当前 Manager 的兼容性（没有更新时为 None）
+    #[serde(default)]
+    pub compatibility: Option<CompatibilityCheck>,
     /// 错误信息
     pub error: Option<String>,
 }
 
 /// 检查 OpenClaw 更新
+#[guarded]
 #[command]
 pub async fn check_openclaw_update() -> Result<UpdateInfo, String> {
     info!("[版本检查] 开始检查 OpenClaw 更新...");
-    
+    privacy::guard("检查更新")?;
+
     // 获取当前版本
-    let current_version = get_openclaw_version();
+    let current_version = cached_openclaw_version();
     info!("[版本检查] 当前版本: {:?}", current_version);
-    
+
     if current_version.is_none() {
         info!("[版本检查] OpenClaw 未安装");
         return Ok(UpdateInfo {
             update_available: false,
             current_version: None,
             latest_version: None,
+            vulnerabilities: None,
+            compatibility: None,
             error: Some("OpenClaw 未安装".to_string()),
         });
     }
-    
+
+    let known_vulnerabilities = vulnerabilities::last_scan()
+        .filter(|scan| scan.openclaw_version == current_version)
+        .map(|scan| scan.counts);
+
     // 获取最新版本
     let latest_version = get_latest_openclaw_version();
     info!("[版本检查] 最新版本: {:?}", latest_version);
-    
+
     if latest_version.is_none() {
         return Ok(UpdateInfo {
             update_available: false,
             current_version,
             latest_version: None,
+            vulnerabilities: known_vulnerabilities,
+            compatibility: None,
             error: Some("无法获取最新版本信息".to_string()),
         });
     }
-    
+
     // 比较版本
     let current = current_version.clone().unwrap();
     let latest = latest_version.clone().unwrap();
     let update_available = compare_versions(&current, &latest);
-    
+
     info!("[版本检查] 是否有更新: {}", update_available);
-    
+    let compatibility = if update_available {
+        Some(compat::check(latest.trim_start_matches('v')).await)
+    } else {
+        None
+    };
+
     Ok(UpdateInfo {
         update_available,
         current_version,
         latest_version,
+        vulnerabilities: known_vulnerabilities,
+        compatibility,
         error: None,
     })
 }
 
-/// 获取 npm registry 上的最新版本
+/// 获取 npm registry 上的最新版本（按包名和更新通道缓存）
 fn get_latest_openclaw_version() -> Option<String> {
-    // 使用 npm view 获取最新版本
-    let result = if platform::is_windows() {
-        shell::run_cmd_output("npm view openclaw version")
+    let package = settings::load_settings().openclaw_package();
+    probe_cache::get_or_probe(
+        &format!("{}:{}", probe_cache::LATEST_OPENCLAW_VERSION, package),
+        probe_cache::LATEST_VERSION_TTL,
+        || fetch_latest_openclaw_version().ok_or_else(|| "无法获取最新版本".to_string()),
+    )
+    .ok()
+}
+
+fn fetch_latest_openclaw_version() -> Option<String> {
+    // 使用 npm view 获取最新版本（按设置中的更新通道和镜像）
+    let settings = settings::load_settings();
+    let cmd = format!("npm view {} version {}", settings.openclaw_package(), settings.npm_args());
+    let script = if platform::is_windows() {
+        cmd
     } else {
-        shell::run_bash_output("npm view openclaw version 2>/dev/null")
+        format!("{} 2>/dev/null", cmd)
     };
+    let result = shell::run_script_with_env(&script, &settings.env_overrides.npm);
     
     match result {
         Ok(version) => {
@@ -1333,7 +1649,7 @@ fn get_latest_openclaw_version() -> Option<String> {
 /// 比较版本号，返回是否有更新可用
 /// current: 当前版本 (如 "1.0.0" 或 "v1.0.0")
 /// latest: 最新版本 (如 "1.0.1")
-fn compare_versions(current: &str, latest: &str) -> bool {
+pub fn compare_versions(current: &str, latest: &str) -> bool {
     // 移除可能的 'v' 前缀和空白
     let current = current.trim().trim_start_matches('v');
     let latest = latest.trim().trim_start_matches('v');
@@ -1363,169 +1679,346 @@ fn compare_versions(current: &str, latest: &str) -> bool {
 }
 
 /// 更新 OpenClaw
+#[guarded]
 #[command]
-pub async fn update_openclaw() -> Result<InstallResult, String> {
-    info!("[更新OpenClaw] 开始更新 OpenClaw...");
-    let os = platform::get_os();
-    
-    // 先停止服务
-    info!("[更新OpenClaw] 尝试停止服务...");
-    let _ = shell::run_openclaw(&["gateway", "stop"]);
-    std::thread::sleep(std::time::Duration::from_millis(500));
-    
-    let result = match os.as_str() {
-        "windows" => {
-            info!("[更新OpenClaw] 使用 Windows 更新方式...");
-            update_openclaw_windows().await
-        },
-        _ => {
-            info!("[更新OpenClaw] 使用 Unix 更新方式 (npm)...");
-            update_openclaw_unix().await
-        },
+pub async fn update_openclaw(app: AppHandle) -> Result<InstallResult, String> {
+    let Some(_exclusive) = jobs::try_exclusive("更新 OpenClaw") else {
+        return jobs::defer(JobKind::UpdateOpenclaw);
     };
-    
-    match &result {
-        Ok(r) if r.success => info!("[更新OpenClaw] ✓ 更新成功"),
-        Ok(r) => warn!("[更新OpenClaw] ✗ 更新失败: {}", r.message),
-        Err(e) => error!("[更新OpenClaw] ✗ 更新错误: {}", e),
-    }
-    
+    let started = std::time::Instant::now();
+    let result = run_update(Some(app)).await;
+    telemetry::record_install("update_openclaw", started, &result);
     result
 }
 
-/// Windows 更新 OpenClaw
-async fn update_openclaw_windows() -> Result<InstallResult, String> {
-    info!("[更新OpenClaw] 执行 npm install -g openclaw@latest...");
+/// 更新 OpenClaw（批量部署也会调用，此时没有 AppHandle，回滚事件不发送到界面）
+pub async fn run_update(app: Option<AppHandle>) -> Result<InstallResult, String> {
+    info!("[更新OpenClaw] 开始更新 OpenClaw...");
+    let current_version = get_openclaw_version().unwrap_or_default();
+    let pre_update =
+        hooks::run_hooks(HookEvent::PreUpdate, &[("OPENCLAW_VERSION", current_version)]);
+    if let Err(e) = pre_update {
+        return Ok(InstallResult {
+            success: false,
+            message: "pre_update 钩子执行失败，已取消更新".to_string(),
+            error: Some(e),
+            timings: Vec::new(),
+        });
+    }
+
+    // 源码构建的安装：重新拉取记录的 ref 并构建
+    if let Some(record) = source_build::load_record() {
+        info!(
+            "[更新OpenClaw] 当前为源码构建，重新构建 {} @ {}",
+            record.repo, record.git_ref
+        );
+        return source_build::build_and_register(None, &record.repo, &record.git_ref).await;
+    }
     
-    match shell::run_cmd_output("npm install -g openclaw@latest --registry=https://registry.npmmirror.com") {
-        Ok(output) => {
-            info!("[更新OpenClaw] npm 输出: {}", output);
-            
-            // 获取新版本
-            let new_version = get_openclaw_version();
-            
-            Ok(InstallResult {
+    // 先下载到缓存（Gateway 继续运行），再停止服务安装，缩短停机时间
+    install_timings::begin(install_timings::UPDATE);
+    let staged = match updates::stage().await {
+        Ok(Some(staged)) => staged,
+        Ok(None) => {
+            install_timings::finish(install_timings::UPDATE, false);
+            return Ok(InstallResult {
                 success: true,
-                message: format!("OpenClaw 已更新到 {}", new_version.unwrap_or("最新版本".to_string())),
+                message: "OpenClaw 已是最新版本".to_string(),
                 error: None,
+                timings: Vec::new(),
             })
         }
         Err(e) => {
-            warn!("[更新OpenClaw] npm install 失败: {}", e);
-            Ok(InstallResult {
+            warn!("[更新OpenClaw] ✗ 下载更新失败: {}", e);
+            return Ok(InstallResult {
                 success: false,
-                message: "OpenClaw 更新失败".to_string(),
+                message: "下载 OpenClaw 更新失败".to_string(),
                 error: Some(e),
-            })
+                timings: install_timings::finish(install_timings::UPDATE, false),
+            });
         }
-    }
+    };
+    let result = updates::install_staged(app, &staged).await;
+    install_timings::attach(install_timings::UPDATE, result)
 }
 
-/// Unix 系统更新 OpenClaw
-async fn update_openclaw_unix() -> Result<InstallResult, String> {
-    let script = r#"
-echo "更新 OpenClaw..."
-npm install -g openclaw@latest --registry=https://registry.npmmirror.com
+/// OpenClaw GitHub 仓库地址
+pub const OPENCLAW_GITHUB_REPO: &str = "https://github.com/openclaw/openclaw.git";
 
-# 验证更新
-openclaw --version
-"#;
-    
-    match shell::run_bash_output(script) {
-        Ok(output) => Ok(InstallResult {
-            success: true,
-            message: format!("OpenClaw 已更新！{}", output),
-            error: None,
-        }),
-        Err(e) => Ok(InstallResult {
-            success: false,
-            message: "OpenClaw 更新失败".to_string(),
-            error: Some(e),
-        }),
+/// 通过 GitHub 安装的 OpenClaw 版本
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitInstallRef {
+    /// 请求的 ref（tag / 分支 / commit SHA，默认分支为 HEAD）
+    pub git_ref: String,
+    /// 实际安装的 commit SHA
+    pub commit: String,
+    pub installed_at: String,
+}
+
+/// GitHub 同步状态（保存在 Manager 数据目录的 github_sync.json）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GitHubSyncState {
+    /// 当前安装的 ref
+    pub current: Option<GitInstallRef>,
+    /// 上一次安装的 ref
+    pub previous: Option<GitInstallRef>,
+}
+
+fn github_sync_state_path() -> std::path::PathBuf {
+    std::path::Path::new(&platform::get_manager_data_dir()).join("github_sync.json")
+}
+
+fn load_github_sync_state() -> GitHubSyncState {
+    std::fs::read_to_string(github_sync_state_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_github_sync_state(state: &GitHubSyncState) {
+    let path = github_sync_state_path();
+    let result = serde_json::to_string_pretty(state)
+        .map_err(|e| e.to_string())
+        .and_then(|content| {
+            file::write_atomic(&path, content.as_bytes()).map_err(|e| e.to_string())
+        });
+    if let Err(e) = result {
+        warn!("[同步GitHub] 记录安装版本失败: {}", e);
     }
 }
 
-/// 同步 GitHub 上的 OpenClaw 更新
-#[command]
-pub async fn sync_openclaw_github() -> Result<InstallResult, String> {
-    info!("[同步GitHub] 开始同步 OpenClaw GitHub 更新...");
-    
-    // 停止服务
-    let _ = shell::run_openclaw(&["gateway", "stop"]);
-    std::thread::sleep(std::time::Duration::from_millis(500));
+/// ref 名称是否合法（会拼接进 git / npm 命令行，只允许常见字符）
+pub fn is_valid_git_ref(git_ref: &str) -> bool {
+    !git_ref.is_empty()
+        && git_ref.len() <= 200
+        && !git_ref.starts_with('-')
+        && !git_ref.contains("..")
+        && git_ref
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-' | '/'))
+}
 
-    let os = platform::get_os();
-    let result = match os.as_str() {
-        "windows" => sync_github_windows().await,
-        _ => sync_github_unix().await,
-    };
+/// 是否为完整的 commit SHA
+fn is_full_commit_sha(git_ref: &str) -> bool {
+    git_ref.len() == 40 && git_ref.chars().all(|c| c.is_ascii_hexdigit())
+}
 
-    match &result {
-        Ok(r) if r.success => info!("[同步GitHub] ✓ 同步成功"),
-        Ok(r) => warn!("[同步GitHub] ✗ 同步失败: {}", r.message),
-        Err(e) => error!("[同步GitHub] ✗ 同步错误: {}", e),
+/// 从 `git ls-remote` 输出中找出 ref 对应的 commit
+/// 附注标签优先取 `^{}` 解引用后的提交，其次是标签、分支
+fn parse_ls_remote(output: &str, git_ref: &str) -> Option<String> {
+    let refs: Vec<(&str, &str)> = output
+        .lines()
+        .filter_map(|line| line.trim().split_once('\t'))
+        .collect();
+    let candidates = [
+        format!("refs/tags/{}^{{}}", git_ref),
+        format!("refs/tags/{}", git_ref),
+        format!("refs/heads/{}", git_ref),
+        git_ref.to_string(),
+    ];
+    candidates.iter().find_map(|name| {
+        refs.iter()
+            .find(|(_, r)| r == name)
+            .map(|(sha, _)| sha.to_lowercase())
+    })
+}
+
+/// 将 ref 解析为完整 commit SHA（优先通过 GitHub 加速代理）
+fn resolve_git_ref(git_ref: &str) -> Result<String, String> {
+    if is_full_commit_sha(git_ref) {
+        return Ok(git_ref.to_lowercase());
+    }
+    if (7..40).contains(&git_ref.len()) && git_ref.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err("请使用完整的 40 位 commit SHA".to_string());
     }
 
-    result
+    let settings = settings::load_settings();
+    let mut urls: Vec<String> = settings
+        .github_proxied_url(OPENCLAW_GITHUB_REPO)
+        .into_iter()
+        .collect();
+    urls.push(OPENCLAW_GITHUB_REPO.to_string());
+
+    let mut last_error = String::new();
+    for url in &urls {
+        info!("[同步GitHub] 执行: git ls-remote {} {}", url, git_ref);
+        match shell::run_command_output("git", &["ls-remote", url, git_ref]) {
+            Ok(output) => match parse_ls_remote(&output, git_ref) {
+                Some(commit) => return Ok(commit),
+                None => return Err(format!("仓库中找不到 ref: {}", git_ref)),
+            },
+            Err(e) => last_error = e,
+        }
+    }
+    Err(format!("查询 ref 失败（需要已安装 git）: {}", last_error))
 }
 
-async fn sync_github_windows() -> Result<InstallResult, String> {
-    // 使用 ghproxy 加速
-    let cmd = "npm install -g git+https://ghproxy.com/https://github.com/openclaw/openclaw.git";
+/// 安装指定 commit（优先通过 GitHub 加速代理，失败后直连）
+fn install_git_commit(commit: &str) -> Result<(), String> {
+    let settings = settings::load_settings();
+    let mirror_error = match settings.github_proxied_url(OPENCLAW_GITHUB_REPO) {
+        Some(mirror) => {
+            let cmd = format!("npm install -g git+{}#{} {}", mirror, commit, settings.npm_args());
+            info!("[同步GitHub] 执行: {}", cmd);
+            match shell::run_script_with_env(&cmd, &settings.env_overrides.npm) {
+                Ok(_) => return Ok(()),
+                Err(e) => {
+                    info!("[同步GitHub] 镜像失败，尝试直连...");
+                    Some(e)
+                }
+            }
+        }
+        None => None,
+    };
+
+    let cmd = format!(
+        "npm install -g git+{}#{} {}",
+        OPENCLAW_GITHUB_REPO,
+        commit,
+        settings.npm_args()
+    );
     info!("[同步GitHub] 执行: {}", cmd);
-    
-    match shell::run_cmd_output(cmd) {
-        Ok(output) => {
-             Ok(InstallResult {
+    shell::run_script_with_env(&cmd, &settings.env_overrides.npm)
+        .map(|_| ())
+        .map_err(|e| match mirror_error {
+            Some(m) => format!("镜像错误: {}; 直连错误: {}", m, e),
+            None => e,
+        })
+}
+
+/// 从 `npm ls -g openclaw --json --long` 输出中读取安装来源的 commit
+pub fn parse_installed_commit(output: &str) -> Option<String> {
+    let value: serde_json::Value = serde_json::from_str(output).ok()?;
+    let resolved = value.pointer("/dependencies/openclaw/resolved")?.as_str()?;
+    resolved
+        .rsplit_once('#')
+        .map(|(_, commit)| commit.to_lowercase())
+}
+
+/// 安装后校验：全局 openclaw 的来源 commit 与请求一致，且能正常运行
+fn verify_git_install(commit: &str) -> Result<String, String> {
+    let output = shell::run_script_output("npm ls -g openclaw --json --long")?;
+    let installed = parse_installed_commit(&output)
+        .ok_or_else(|| "无法读取已安装 OpenClaw 的来源 commit".to_string())?;
+    if installed != commit {
+        return Err(format!(
+            "已安装的 commit {} 与请求的 {} 不一致",
+            installed, commit
+        ));
+    }
+    get_openclaw_version().ok_or_else(|| "安装后无法运行 openclaw --version".to_string())
+}
+
+/// 同步失败后回退到之前的版本，返回回退结果说明
+async fn revert_github_sync(state: &GitHubSyncState, previous_version: Option<String>) -> String {
+    if let Some(current) = &state.current {
+        warn!("[同步GitHub] 回退到之前的 ref: {} ({})", current.git_ref, current.commit);
+        let result =
+            install_git_commit(&current.commit).and_then(|_| verify_git_install(&current.commit));
+        return match result {
+            Ok(_) => format!("已回退到 {}", current.git_ref),
+            Err(e) => format!("回退到 {} 也失败了: {}", current.git_ref, e),
+        };
+    }
+    // 之前是 npm 发布版，按版本号重新安装
+    let version = previous_version
+        .as_deref()
+        .and_then(|v| v.split_whitespace().last())
+        .map(|v| v.trim_start_matches('v').to_string());
+    match version {
+        Some(version) => {
+            warn!("[同步GitHub] 回退到之前的 npm 版本: {}", version);
+            match install_openclaw_version(&version).await {
+                Ok(r) if r.success => format!("已回退到 {}", version),
+                Ok(r) => format!(
+                    "回退到 {} 也失败了: {}",
+                    version,
+                    r.error.unwrap_or(r.message)
+                ),
+                Err(e) => format!("回退到 {} 也失败了: {}", version, e),
+            }
+        }
+        None => "之前未安装 OpenClaw，无需回退".to_string(),
+    }
+}
+
+/// 同步 GitHub 上的 OpenClaw（可指定 tag / 分支 / commit，默认为默认分支最新提交）
+/// 安装固定到解析出的 commit，安装后校验来源 commit，失败时自动回退到之前的版本
+#[guarded]
+#[command]
+pub async fn sync_openclaw_github(git_ref: Option<String>) -> Result<InstallResult, String> {
+    let git_ref = git_ref
+        .map(|r| r.trim().to_string())
+        .filter(|r| !r.is_empty())
+        .unwrap_or_else(|| "HEAD".to_string());
+    if !is_valid_git_ref(&git_ref) {
+        return Err(format!("无效的 Git ref: {}", git_ref));
+    }
+    let _fresh = probe_cache::InvalidateVersionsOnDrop;
+    info!("[同步GitHub] 开始同步 OpenClaw GitHub 代码: {}", git_ref);
+    if let Err(e) = git::ensure_git() {
+        warn!("[同步GitHub] ✗ {}", e);
+        return Ok(InstallResult {
+            success: false,
+            message: "同步失败：未安装 git".to_string(),
+            error: Some(e),
+            timings: Vec::new(),
+        });
+    }
+
+    let commit = resolve_git_ref(&git_ref)?;
+    info!("[同步GitHub] {} -> {}", git_ref, commit);
+
+    let state = load_github_sync_state();
+    let previous_version = get_openclaw_version();
+
+    // 停止服务
+    monitor::expect_gateway_stop();
+    let _ = shell::run_openclaw(&["gateway", "stop"]);
+    std::thread::sleep(std::time::Duration::from_millis(500));
+
+    match install_git_commit(&commit).and_then(|_| verify_git_install(&commit)) {
+        Ok(version) => {
+            info!("[同步GitHub] ✓ 同步成功: {} ({})", git_ref, version);
+            source_build::clear_record();
+            save_github_sync_state(&GitHubSyncState {
+                previous: state.current.clone(),
+                current: Some(GitInstallRef {
+                    git_ref: git_ref.clone(),
+                    commit: commit.clone(),
+                    installed_at: time::now_rfc3339(),
+                }),
+            });
+            Ok(InstallResult {
                 success: true,
-                message: "已从 GitHub 同步最新代码".to_string(),
+                message: format!(
+                    "已从 GitHub 安装 {} ({}): {}",
+                    git_ref,
+                    &commit[..12],
+                    version
+                ),
                 error: None,
+                timings: Vec::new(),
             })
         }
         Err(e) => {
-            // 如果 ghproxy 失败，尝试直连
-            info!("[同步GitHub] 镜像失败，尝试直连...");
-             match shell::run_cmd_output("npm install -g git+https://github.com/openclaw/openclaw.git") {
-                Ok(_) => Ok(InstallResult {
-                    success: true,
-                    message: "已从 GitHub 同步最新代码".to_string(),
-                    error: None,
-                }),
-                Err(e2) => Ok(InstallResult {
-                    success: false,
-                    message: "同步失败".to_string(),
-                    error: Some(format!("镜像错误: {}; 直连错误: {}", e, e2)),
-                })
-             }
+            error!("[同步GitHub] ✗ 同步失败: {}", e);
+            let reverted = revert_github_sync(&state, previous_version).await;
+            Ok(InstallResult {
+                success: false,
+                message: format!("同步 {} 失败，{}", git_ref, reverted),
+                error: Some(e),
+                timings: Vec::new(),
+            })
         }
     }
 }
 
-async fn sync_github_unix() -> Result<InstallResult, String> {
-    let script = r#"
-echo "从 GitHub 同步 OpenClaw..."
-# 尝试使用 ghproxy
-if npm install -g git+https://ghproxy.com/https://github.com/openclaw/openclaw.git; then
-    echo "同步成功"
-else
-    echo "镜像源失败，尝试直连..."
-    npm install -g git+https://github.com/openclaw/openclaw.git
-fi
-openclaw --version
-"#;
-    
-    match shell::run_bash_output(script) {
-        Ok(output) => Ok(InstallResult {
-            success: true,
-            message: format!("GitHub 同步完成: {}", output),
-            error: None,
-        }),
-        Err(e) => Ok(InstallResult {
-            success: false,
-            message: "同步失败".to_string(),
-            error: Some(e),
-        }),
-    }
+/// 获取 GitHub 同步记录（当前和上一次安装的 ref）
+#[guarded]
+#[command]
+pub async fn get_github_sync_state() -> Result<GitHubSyncState, String> {
+    Ok(load_github_sync_state())
 }
 
 #[cfg(test)]
@@ -1548,6 +2041,26 @@ mod tests {
         base
     }
 
+    #[test]
+    fn git_ref_is_resolved_and_validated() {
+        assert!(is_valid_git_ref("v2026.1.5"));
+        assert!(is_valid_git_ref("release/1.x"));
+        assert!(!is_valid_git_ref("main; rm -rf /"));
+        assert!(!is_valid_git_ref("--upload-pack=evil"));
+
+        let tag = "1111111111111111111111111111111111111111";
+        let peeled = "2222222222222222222222222222222222222222";
+        let output = format!(
+            "{}\trefs/tags/v1.0\n{}\trefs/tags/v1.0^{{}}\n",
+            tag, peeled
+        );
+        assert_eq!(parse_ls_remote(&output, "v1.0").as_deref(), Some(peeled));
+        assert_eq!(parse_ls_remote(&output, "v2.0"), None);
+
+        let ls = r#"{"dependencies":{"openclaw":{"resolved":"git+ssh://git@github.com/openclaw/openclaw.git#2222222222222222222222222222222222222222"}}}"#;
+        assert_eq!(parse_installed_commit(ls).as_deref(), Some(peeled));
+    }
+
     #[test]
     fn picks_x64_msi_over_others() {
         let tool_dir = make_temp_dir("openclaw_tool");
@@ -1564,6 +2077,54 @@ mod tests {
         let _ = std::fs::remove_dir_all(&tool_dir);
     }
 
+    #[test]
+    fn finds_msi_in_dir_with_spaces_and_cjk() {
+        let tool_dir = make_temp_dir("openclaw tool 名字 with spaces");
+        std::fs::write(tool_dir.join("node-v22.11.0-x64.msi"), "x").unwrap();
+
+        let picked = find_local_node_msi(&tool_dir).unwrap();
+        assert!(picked.exists());
+        assert_eq!(picked.parent().unwrap(), tool_dir.as_path());
+
+        let _ = std::fs::remove_dir_all(&tool_dir);
+    }
+
+    #[test]
+    fn msiexec_script_quotes_path_as_single_argument() {
+        let script = msiexec_install_script(std::path::Path::new(
+            r"\\?\C:\Users\名字 with spaces\O'Brien\tool\node-v22.11.0-x64.msi",
+        ));
+        assert!(script.contains(
+            r#"@('/i', '"C:\Users\名字 with spaces\O''Brien\tool\node-v22.11.0-x64.msi"', '/qn', '/norestart')"#
+        ));
+    }
+
+    #[test]
+    fn runs_executable_from_path_with_spaces_and_cjk() {
+        // 通过参数数组调用位于含空格、中文目录中的程序，路径不会被拆开
+        let dir = make_temp_dir("openclaw exec 名字 with spaces");
+        #[cfg(windows)]
+        let exe = {
+            let exe = dir.join("echo args.cmd");
+            std::fs::write(&exe, "@echo %~1").unwrap();
+            exe
+        };
+        #[cfg(unix)]
+        let exe = {
+            use std::os::unix::fs::PermissionsExt;
+            let exe = dir.join("echo args.sh");
+            std::fs::write(&exe, "#!/bin/sh\necho \"$1\"\n").unwrap();
+            std::fs::set_permissions(&exe, std::fs::Permissions::from_mode(0o755)).unwrap();
+            exe
+        };
+
+        let _system = crate::utils::executor::scoped_system();
+        let output = shell::run_command_output(&exe.to_string_lossy(), &["hello 世界"]).unwrap();
+        assert_eq!(output, "hello 世界");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn picks_pkg_by_arch() {
         let tool_dir = make_temp_dir("openclaw_tool_pkg");
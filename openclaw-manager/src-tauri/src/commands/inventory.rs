@@ -0,0 +1,358 @@
+use crate::commands::{installer, source_build};
+use crate::utils::{platform, shell, time};
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use openclaw_macros::guarded;
+use tauri::command;
+
+/// Node.js 安装信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeComponent {
+    pub path: Option<String>,
+    pub version: String,
+    /// 安装来源：homebrew / nvm / fnm / volta / official / system / unknown
+    pub source: String,
+}
+
+/// OpenClaw 安装方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InstallMethod {
+    Npm,
+    Git,
+    Source,
+}
+
+/// OpenClaw 安装信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenClawComponent {
+    pub path: Option<String>,
+    pub version: String,
+    pub install_method: InstallMethod,
+    /// git / 源码安装时的 commit
+    pub commit: Option<String>,
+}
+
+/// 已安装的技能（~/.openclaw/skills/<name>）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkillComponent {
+    pub name: String,
+    pub version: Option<String>,
+    pub path: String,
+    /// 开发链接模式：技能目录是指向本地仓库的符号链接
+    pub linked: bool,
+}
+
+/// 网关守护进程注册状态（`openclaw gateway install`）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DaemonRegistration {
+    /// launchd / systemd / schtasks
+    pub kind: String,
+    pub registered: bool,
+    /// plist / unit 文件路径或计划任务名
+    pub location: Option<String>,
+}
+
+/// 内网穿透客户端
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TunnelClient {
+    pub name: String,
+    pub path: Option<String>,
+    pub version: Option<String>,
+}
+
+/// Manager 自己管理的运行时（如源码构建的检出目录）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManagedRuntime {
+    pub name: String,
+    pub path: String,
+    pub version: Option<String>,
+}
+
+/// Manager 管理的全部组件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Inventory {
+    pub generated_at: String,
+    pub node: Option<NodeComponent>,
+    pub openclaw: Option<OpenClawComponent>,
+    pub skills: Vec<SkillComponent>,
+    pub daemon: DaemonRegistration,
+    pub tunnels: Vec<TunnelClient>,
+    pub managed_runtimes: Vec<ManagedRuntime>,
+}
+
+/// 常见的内网穿透客户端
+const TUNNEL_CLIENTS: &[&str] = &["cloudflared", "tailscale", "ngrok", "frpc"];
+
+/// 查找命令的完整路径
+pub fn which(cmd: &str) -> Option<String> {
+    let finder = if platform::is_windows() {
+        "where"
+    } else {
+        "which"
+    };
+    shell::run_command_output(finder, &[cmd])
+        .ok()
+        .and_then(|out| out.lines().next().map(|l| l.trim().to_string()))
+        .filter(|p| !p.is_empty())
+}
+
+/// 根据路径判断 Node.js 安装来源
+fn node_source(path: &str) -> &'static str {
+    let p = path.replace('\\', "/").to_lowercase();
+    if p.contains("/.nvm/") || p.contains("/nvm/") {
+        "nvm"
+    } else if p.contains("fnm") {
+        "fnm"
+    } else if p.contains("/.volta/") {
+        "volta"
+    } else if p.starts_with("/opt/homebrew/") || p.contains("/cellar/") {
+        "homebrew"
+    } else if p.contains("program files/nodejs") || p == "/usr/local/bin/node" {
+        "official"
+    } else if p.starts_with("/usr/bin/") {
+        "system"
+    } else {
+        "unknown"
+    }
+}
+
+fn node_component() -> Option<NodeComponent> {
+    let version = installer::get_node_version()?;
+    let path = which("node").or_else(installer::resolve_node_executable);
+    let source = path.as_deref().map(node_source).unwrap_or("unknown");
+    Some(NodeComponent {
+        path,
+        version,
+        source: source.to_string(),
+    })
+}
+
+pub fn openclaw_component() -> Option<OpenClawComponent> {
+    let version = installer::get_openclaw_version()?;
+    let path = shell::get_openclaw_path();
+
+    if let Some(record) = source_build::load_record() {
+        return Some(OpenClawComponent {
+            path,
+            version,
+            install_method: InstallMethod::Source,
+            commit: Some(record.commit),
+        });
+    }
+
+    let listing = shell::run_script_output("npm ls -g openclaw --json --long").unwrap_or_default();
+    let from_git = serde_json::from_str::<serde_json::Value>(&listing)
+        .ok()
+        .and_then(|v| {
+            v.pointer("/dependencies/openclaw/resolved")
+                .and_then(|r| r.as_str())
+                .map(|r| r.starts_with("git+") || r.starts_with("github:"))
+        })
+        .unwrap_or(false);
+    Some(OpenClawComponent {
+        path,
+        version,
+        install_method: if from_git {
+            InstallMethod::Git
+        } else {
+            InstallMethod::Npm
+        },
+        commit: if from_git {
+            installer::parse_installed_commit(&listing)
+        } else {
+            None
+        },
+    })
+}
+
+/// 读取技能版本：SKILL.md frontmatter 的 version，其次 package.json 的 version
+fn skill_version(dir: &Path) -> Option<String> {
+    if let Ok(content) = std::fs::read_to_string(dir.join("SKILL.md")) {
+        let frontmatter = content
+            .strip_prefix("---")
+            .and_then(|rest| rest.split_once("\n---").map(|(fm, _)| fm));
+        if let Some(version) = frontmatter
+            .and_then(|fm| serde_yaml::from_str::<serde_yaml::Value>(fm).ok())
+            .and_then(|fm| {
+                fm.get("version").and_then(|v| match v {
+                    serde_yaml::Value::String(s) => Some(s.clone()),
+                    serde_yaml::Value::Number(n) => Some(n.to_string()),
+                    _ => None,
+                })
+            })
+        {
+            return Some(version);
+        }
+    }
+    std::fs::read_to_string(dir.join("package.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+        .and_then(|pkg| pkg.get("version")?.as_str().map(|s| s.to_string()))
+}
+
+/// 列出技能目录下的技能
+pub fn list_skills(skills_dir: &Path) -> Vec<SkillComponent> {
+    let Ok(entries) = std::fs::read_dir(skills_dir) else {
+        return Vec::new();
+    };
+    let mut skills: Vec<SkillComponent> = entries
+        .flatten()
+        .filter(|e| e.path().is_dir())
+        .map(|e| {
+            let path = e.path();
+            let linked = std::fs::symlink_metadata(&path)
+                .map(|m| m.file_type().is_symlink())
+                .unwrap_or(false);
+            SkillComponent {
+                name: e.file_name().to_string_lossy().to_string(),
+                version: skill_version(&path),
+                path: path.to_string_lossy().to_string(),
+                linked,
+            }
+        })
+        .collect();
+    skills.sort_by(|a, b| a.name.cmp(&b.name));
+    skills
+}
+
+/// 检查网关守护进程是否已注册
+pub fn daemon_registration() -> DaemonRegistration {
+    let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+    if platform::is_windows() {
+        let task = "OpenClaw Gateway";
+        let registered = shell::run_command_output("schtasks", &["/Query", "/TN", task]).is_ok();
+        DaemonRegistration {
+            kind: "schtasks".to_string(),
+            registered,
+            location: registered.then(|| task.to_string()),
+        }
+    } else if platform::is_macos() {
+        let plist = home
+            .join("Library")
+            .join("LaunchAgents")
+            .join("ai.openclaw.gateway.plist");
+        DaemonRegistration {
+            kind: "launchd".to_string(),
+            registered: plist.exists(),
+            location: plist.exists().then(|| plist.to_string_lossy().to_string()),
+        }
+    } else {
+        let unit = home
+            .join(".config")
+            .join("systemd")
+            .join("user")
+            .join("openclaw-gateway.service");
+        DaemonRegistration {
+            kind: "systemd".to_string(),
+            registered: unit.exists(),
+            location: unit.exists().then(|| unit.to_string_lossy().to_string()),
+        }
+    }
+}
+
+fn tunnel_clients() -> Vec<TunnelClient> {
+    TUNNEL_CLIENTS
+        .iter()
+        .filter_map(|name| {
+            let path = which(name)?;
+            let version = shell::run_command_output(&path, &["--version"])
+                .ok()
+                .and_then(|out| out.lines().next().map(|l| l.trim().to_string()));
+            Some(TunnelClient {
+                name: name.to_string(),
+                path: Some(path),
+                version,
+            })
+        })
+        .collect()
+}
+
+fn managed_runtimes() -> Vec<ManagedRuntime> {
+    source_build::load_record()
+        .map(|record| ManagedRuntime {
+            name: "openclaw-source".to_string(),
+            path: record.path,
+            version: Some(format!("{} ({})", record.git_ref, record.commit)),
+        })
+        .into_iter()
+        .collect()
+}
+
+/// 收集组件清单（仪表盘和卸载流程共用）
+pub fn collect_inventory() -> Inventory {
+    let skills_dir = Path::new(&platform::get_config_dir()).join("skills");
+    Inventory {
+        generated_at: time::now_rfc3339(),
+        node: node_component(),
+        openclaw: openclaw_component(),
+        skills: list_skills(&skills_dir),
+        daemon: daemon_registration(),
+        tunnels: tunnel_clients(),
+        managed_runtimes: managed_runtimes(),
+    }
+}
+
+/// 获取 Manager 管理的全部组件清单
+#[guarded]
+#[command]
+pub async fn get_inventory() -> Result<Inventory, String> {
+    info!("[组件清单] 收集已安装组件...");
+    let inventory = tokio::task::spawn_blocking(collect_inventory)
+        .await
+        .map_err(|e| format!("收集组件清单失败: {}", e))?;
+    info!(
+        "[组件清单] ✓ Node: {}, OpenClaw: {}, 技能 {} 个",
+        inventory.node.is_some(),
+        inventory.openclaw.is_some(),
+        inventory.skills.len()
+    );
+    Ok(inventory)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn node_source_is_inferred_from_path() {
+        assert_eq!(
+            node_source("/Users/a/.nvm/versions/node/v22.1.0/bin/node"),
+            "nvm"
+        );
+        assert_eq!(node_source("/opt/homebrew/bin/node"), "homebrew");
+        assert_eq!(node_source(r"C:\Program Files\nodejs\node.exe"), "official");
+        assert_eq!(node_source("/usr/bin/node"), "system");
+        assert_eq!(node_source("/home/a/.volta/bin/node"), "volta");
+    }
+
+    #[test]
+    fn skill_versions_come_from_frontmatter_or_package_json() {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!("openclaw-inventory-{}", nanos));
+        let weather = dir.join("weather");
+        let notes = dir.join("notes");
+        std::fs::create_dir_all(&weather).unwrap();
+        std::fs::create_dir_all(&notes).unwrap();
+        std::fs::write(
+            weather.join("SKILL.md"),
+            "---\nname: weather\nversion: 1.2.0\n---\n# Weather\n",
+        )
+        .unwrap();
+        std::fs::write(notes.join("SKILL.md"), "# Notes\n").unwrap();
+        std::fs::write(notes.join("package.json"), r#"{"version":"0.3.1"}"#).unwrap();
+
+        let skills = list_skills(&dir);
+        assert_eq!(skills.len(), 2);
+        assert_eq!(skills[0].name, "notes");
+        assert_eq!(skills[0].version.as_deref(), Some("0.3.1"));
+        assert_eq!(skills[1].version.as_deref(), Some("1.2.0"));
+        assert!(!skills[1].linked);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
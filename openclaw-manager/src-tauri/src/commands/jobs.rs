@@ -0,0 +1,575 @@
+use crate::commands::installer::{self, InstallResult};
+use crate::commands::{maintenance, probe_cache, service, updates};
+use crate::utils::{platform, time};
+use log::{info, warn};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use openclaw_macros::guarded;
+use tauri::command;
+
+/// 任务被中断（Manager 退出、断电）超过该次数后不再重试
+const MAX_ATTEMPTS: u32 = 3;
+/// 保留的已结束任务数量
+const HISTORY_LIMIT: i64 = 200;
+/// 没有被唤醒时重新检查队列的间隔（维护窗口打开后据此开始执行）
+const POLL_INTERVAL: Duration = Duration::from_secs(15);
+
+/// 排队的操作
+///
+/// 这些操作都会修改 OpenClaw 安装或重启 Gateway，同一时间只能执行一个
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum JobKind {
+    /// 安装 OpenClaw
+    InstallOpenclaw,
+    /// 下载并安装 OpenClaw 最新版本
+    UpdateOpenclaw,
+    /// 安装已下载的 OpenClaw 更新
+    ApplyUpdate { version: String },
+    /// 重启 Gateway
+    RestartGateway { reason: String },
+}
+
+impl JobKind {
+    /// 类型名（同类任务排队时只保留最新的一个）
+    fn type_name(&self) -> &'static str {
+        match self {
+            JobKind::InstallOpenclaw => "install_openclaw",
+            JobKind::UpdateOpenclaw => "update_openclaw",
+            JobKind::ApplyUpdate { .. } => "apply_update",
+            JobKind::RestartGateway { .. } => "restart_gateway",
+        }
+    }
+
+    /// 默认优先级，数值大的先执行
+    fn priority(&self) -> i64 {
+        match self {
+            JobKind::InstallOpenclaw => 30,
+            JobKind::UpdateOpenclaw | JobKind::ApplyUpdate { .. } => 20,
+            JobKind::RestartGateway { .. } => 10,
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            JobKind::InstallOpenclaw => "安装 OpenClaw".to_string(),
+            JobKind::UpdateOpenclaw => "更新 OpenClaw".to_string(),
+            JobKind::ApplyUpdate { version } => format!("安装 OpenClaw {}", version),
+            JobKind::RestartGateway { .. } => "重启 Gateway".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Succeeded,
+    Failed,
+    Cancelled,
+}
+
+impl JobStatus {
+    fn as_str(&self) -> &'static str {
+        match self {
+            JobStatus::Queued => "queued",
+            JobStatus::Running => "running",
+            JobStatus::Succeeded => "succeeded",
+            JobStatus::Failed => "failed",
+            JobStatus::Cancelled => "cancelled",
+        }
+    }
+
+    fn parse(value: &str) -> Self {
+        match value {
+            "queued" => JobStatus::Queued,
+            "running" => JobStatus::Running,
+            "succeeded" => JobStatus::Succeeded,
+            "cancelled" => JobStatus::Cancelled,
+            _ => JobStatus::Failed,
+        }
+    }
+}
+
+/// 任务队列中的任务（保存在 Manager 数据目录的 jobs.db）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Job {
+    pub id: i64,
+    #[serde(flatten)]
+    pub kind: JobKind,
+    pub label: String,
+    pub priority: i64,
+    pub status: JobStatus,
+    /// 只在维护窗口内执行
+    pub maintenance_window: bool,
+    /// 已开始执行的次数（被中断后重新排队会再次计数）
+    pub attempts: u32,
+    pub created_at: String,
+    pub started_at: Option<String>,
+    pub finished_at: Option<String>,
+    pub message: Option<String>,
+    pub error: Option<String>,
+}
+
+/// 正在执行的互斥操作名称
+static EXCLUSIVE: Mutex<Option<String>> = Mutex::new(None);
+/// 新任务入队或互斥操作结束时唤醒任务执行循环
+static WAKE: tokio::sync::Notify = tokio::sync::Notify::const_new();
+
+/// 互斥操作的持有凭证，drop 时释放
+pub struct Exclusive;
+
+impl Drop for Exclusive {
+    fn drop(&mut self) {
+        let mut holder = EXCLUSIVE.lock().unwrap_or_else(|e| e.into_inner());
+        *holder = None;
+        // 安装、更新后已安装的版本可能变化
+        probe_cache::invalidate_versions();
+        // 因冲突排队的任务可以执行了
+        WAKE.notify_one();
+    }
+}
+
+/// 尝试开始一个互斥操作（安装、更新、重启 Gateway），已有操作进行中时返回 None
+pub fn try_exclusive(name: &str) -> Option<Exclusive> {
+    let mut holder = EXCLUSIVE.lock().unwrap_or_else(|e| e.into_inner());
+    if holder.is_some() {
+        return None;
+    }
+    *holder = Some(name.to_string());
+    Some(Exclusive)
+}
+
+fn exclusive_holder() -> Option<String> {
+    EXCLUSIVE.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+fn db_path() -> PathBuf {
+    Path::new(&platform::get_manager_data_dir()).join("jobs.db")
+}
+
+fn open_db(path: &Path) -> Result<Connection, String> {
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    let conn = Connection::open(path).map_err(|e| format!("打开任务数据库失败: {}", e))?;
+    init_schema(&conn)?;
+    Ok(conn)
+}
+
+fn init_schema(conn: &Connection) -> Result<(), String> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS jobs (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            type TEXT NOT NULL,
+            payload TEXT NOT NULL,
+            priority INTEGER NOT NULL,
+            status TEXT NOT NULL,
+            maintenance_window INTEGER NOT NULL DEFAULT 0,
+            attempts INTEGER NOT NULL DEFAULT 0,
+            created_at INTEGER NOT NULL,
+            started_at INTEGER,
+            finished_at INTEGER,
+            message TEXT,
+            error TEXT
+        );
+        CREATE INDEX IF NOT EXISTS idx_jobs_status ON jobs(status);",
+    )
+    .map_err(|e| format!("初始化任务数据库失败: {}", e))
+}
+
+fn format_ts(ts: Option<i64>) -> Option<String> {
+    ts.and_then(|t| time::from_millis(t.saturating_mul(1000)))
+        .map(|t| t.to_rfc3339())
+}
+
+const COLUMNS: &str = "id, payload, priority, status, maintenance_window, attempts,
+    created_at, started_at, finished_at, message, error";
+
+fn row_to_job(row: &Row) -> rusqlite::Result<Option<Job>> {
+    let payload: String = row.get(1)?;
+    // 无法解析的任务（新版本写入后降级）直接跳过
+    let Ok(kind) = serde_json::from_str::<JobKind>(&payload) else {
+        return Ok(None);
+    };
+    let status: String = row.get(3)?;
+    Ok(Some(Job {
+        id: row.get(0)?,
+        label: kind.label(),
+        kind,
+        priority: row.get(2)?,
+        status: JobStatus::parse(&status),
+        maintenance_window: row.get(4)?,
+        attempts: row.get(5)?,
+        created_at: format_ts(row.get(6)?).unwrap_or_default(),
+        started_at: format_ts(row.get(7)?),
+        finished_at: format_ts(row.get(8)?),
+        message: row.get(9)?,
+        error: row.get(10)?,
+    }))
+}
+
+/// 加入队列，同类的排队任务被新任务替换
+fn insert(
+    conn: &Connection,
+    kind: &JobKind,
+    maintenance_window: bool,
+    now: i64,
+) -> Result<i64, String> {
+    let payload = serde_json::to_string(kind).map_err(|e| e.to_string())?;
+    conn.execute(
+        "UPDATE jobs SET status = 'cancelled', finished_at = ?1, message = '被新的同类任务替换'
+         WHERE status = 'queued' AND type = ?2",
+        params![now, kind.type_name()],
+    )
+    .map_err(|e| format!("更新任务失败: {}", e))?;
+    conn.execute(
+        "INSERT INTO jobs (type, payload, priority, status, maintenance_window, created_at)
+         VALUES (?1, ?2, ?3, 'queued', ?4, ?5)",
+        params![
+            kind.type_name(),
+            payload,
+            kind.priority(),
+            maintenance_window,
+            now
+        ],
+    )
+    .map_err(|e| format!("加入任务队列失败: {}", e))?;
+    Ok(conn.last_insert_rowid())
+}
+
+fn query(conn: &Connection, filter: &str) -> Result<Vec<Job>, String> {
+    let sql = format!("SELECT {} FROM jobs {}", COLUMNS, filter);
+    let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([], row_to_job)
+        .map_err(|e| format!("读取任务失败: {}", e))?;
+    Ok(rows.flatten().flatten().collect())
+}
+
+/// 下一个可以执行的任务：优先级高的先执行，同优先级按加入顺序
+fn next_runnable(conn: &Connection, window_open: bool) -> Result<Option<Job>, String> {
+    let filter = if window_open {
+        "WHERE status = 'queued'"
+    } else {
+        "WHERE status = 'queued' AND maintenance_window = 0"
+    };
+    Ok(query(
+        conn,
+        &format!("{} ORDER BY priority DESC, id ASC LIMIT 1", filter),
+    )?
+    .into_iter()
+    .next())
+}
+
+/// 标记为执行中，任务已被取消时返回 false
+fn mark_running(conn: &Connection, id: i64, now: i64) -> Result<bool, String> {
+    conn.execute(
+        "UPDATE jobs SET status = 'running', started_at = ?1, attempts = attempts + 1
+         WHERE id = ?2 AND status = 'queued'",
+        params![now, id],
+    )
+    .map(|n| n == 1)
+    .map_err(|e| format!("更新任务失败: {}", e))
+}
+
+fn mark_finished(
+    conn: &Connection,
+    id: i64,
+    result: &Result<InstallResult, String>,
+    now: i64,
+) -> Result<(), String> {
+    let (status, message, error) = match result {
+        Ok(r) if r.success => (JobStatus::Succeeded, Some(r.message.clone()), None),
+        Ok(r) => (JobStatus::Failed, Some(r.message.clone()), r.error.clone()),
+        Err(e) => (JobStatus::Failed, None, Some(e.clone())),
+    };
+    conn.execute(
+        "UPDATE jobs SET status = ?1, finished_at = ?2, message = ?3, error = ?4 WHERE id = ?5",
+        params![status.as_str(), now, message, error, id],
+    )
+    .map_err(|e| format!("更新任务失败: {}", e))?;
+    conn.execute(
+        "DELETE FROM jobs WHERE status NOT IN ('queued', 'running') AND id NOT IN (
+            SELECT id FROM jobs WHERE status NOT IN ('queued', 'running')
+            ORDER BY id DESC LIMIT ?1)",
+        params![HISTORY_LIMIT],
+    )
+    .map_err(|e| format!("清理任务记录失败: {}", e))?;
+    Ok(())
+}
+
+/// 取消排队的任务，返回取消的数量
+fn cancel_where(conn: &Connection, filter: &str, now: i64) -> Result<usize, String> {
+    conn.execute(
+        &format!(
+            "UPDATE jobs SET status = 'cancelled', finished_at = ?1, message = '已取消'
+             WHERE status = 'queued' AND {}",
+            filter
+        ),
+        params![now],
+    )
+    .map_err(|e| format!("取消任务失败: {}", e))
+}
+
+/// 上次退出时仍在执行的任务重新排队，多次中断的标记为失败
+fn requeue_interrupted(conn: &Connection, now: i64) -> Result<usize, String> {
+    conn.execute(
+        "UPDATE jobs SET status = 'failed', finished_at = ?1, error = '任务多次被中断'
+         WHERE status = 'running' AND attempts >= ?2",
+        params![now, MAX_ATTEMPTS],
+    )
+    .map_err(|e| format!("更新任务失败: {}", e))?;
+    conn.execute(
+        "UPDATE jobs SET status = 'queued', started_at = NULL WHERE status = 'running'",
+        [],
+    )
+    .map_err(|e| format!("更新任务失败: {}", e))
+}
+
+fn now() -> i64 {
+    chrono::Utc::now().timestamp()
+}
+
+/// 加入任务队列，返回任务编号
+pub fn enqueue(kind: JobKind, maintenance_window: bool) -> Result<i64, String> {
+    let conn = open_db(&db_path())?;
+    let id = insert(&conn, &kind, maintenance_window, now())?;
+    info!("[任务队列] 加入任务 #{}: {}", id, kind.label());
+    WAKE.notify_one();
+    Ok(id)
+}
+
+/// 有冲突的操作正在进行时把请求排入队列，返回给界面的结果
+pub fn defer(kind: JobKind) -> Result<InstallResult, String> {
+    let holder = exclusive_holder().unwrap_or_else(|| "其它操作".to_string());
+    let label = kind.label();
+    let id = enqueue(kind, false)?;
+    Ok(InstallResult {
+        success: false,
+        message: format!(
+            "{}正在进行，{}已加入任务队列（#{}），完成后自动执行",
+            holder, label, id
+        ),
+        error: None,
+        timings: Vec::new(),
+    })
+}
+
+/// 排队中的维护窗口任务
+pub fn queued_maintenance_jobs() -> Vec<Job> {
+    open_db(&db_path())
+        .and_then(|conn| {
+            query(
+                &conn,
+                "WHERE status = 'queued' AND maintenance_window = 1 ORDER BY id",
+            )
+        })
+        .unwrap_or_default()
+}
+
+/// 排队中和执行中的任务
+pub fn active_jobs() -> Result<Vec<Job>, String> {
+    let conn = open_db(&db_path())?;
+    query(
+        &conn,
+        "WHERE status IN ('queued', 'running') ORDER BY priority DESC, id ASC",
+    )
+}
+
+/// 取消所有排队的维护窗口任务
+pub fn cancel_maintenance_jobs() -> Result<usize, String> {
+    let conn = open_db(&db_path())?;
+    cancel_where(&conn, "maintenance_window = 1", now())
+}
+
+/// Manager 启动时调用：把上次退出时中断的任务重新排队
+pub fn resume_interrupted() {
+    match open_db(&db_path()).and_then(|conn| requeue_interrupted(&conn, now())) {
+        Ok(0) => {}
+        Ok(n) => info!("[任务队列] {} 个被中断的任务已重新排队", n),
+        Err(e) => warn!("[任务队列] 恢复任务失败: {}", e),
+    }
+}
+
+async fn run(kind: &JobKind) -> Result<InstallResult, String> {
+    match kind {
+        JobKind::InstallOpenclaw => installer::run_install().await,
+        JobKind::UpdateOpenclaw => installer::run_update(None).await,
+        JobKind::ApplyUpdate { version } => {
+            match updates::load_staged().filter(|s| &s.version == version) {
+                Some(staged) => updates::apply(None, &staged).await,
+                None => Err(format!("更新 {} 的安装包已不存在", version)),
+            }
+        }
+        JobKind::RestartGateway { reason } => {
+            info!("[任务队列] 重启 Gateway: {}", reason);
+            service::restart_service(None)
+                .await
+                .map_err(String::from)
+                .map(|message| InstallResult {
+                    success: true,
+                    message,
+                    error: None,
+                    timings: Vec::new(),
+                })
+        }
+    }
+}
+
+/// 任务执行循环：Manager 启动时在独立的异步任务中运行，安装、更新等长时间的任务不会阻塞后台监控
+pub async fn run_worker() {
+    loop {
+        tick().await;
+        let _ = tokio::time::timeout(POLL_INTERVAL, WAKE.notified()).await;
+    }
+}
+
+/// 依次执行可以执行的任务（维护窗口任务只在窗口内执行）
+async fn tick() {
+    loop {
+        let conn = match open_db(&db_path()) {
+            Ok(conn) => conn,
+            Err(e) => {
+                warn!("[任务队列] {}", e);
+                return;
+            }
+        };
+        let job = match next_runnable(&conn, maintenance::window_open()) {
+            Ok(Some(job)) => job,
+            Ok(None) => return,
+            Err(e) => {
+                warn!("[任务队列] {}", e);
+                return;
+            }
+        };
+        let label = job.kind.label();
+        let Some(_exclusive) = try_exclusive(&label) else {
+            return;
+        };
+        if !mark_running(&conn, job.id, now()).unwrap_or(false) {
+            continue;
+        }
+        drop(conn);
+        info!("[任务队列] 执行任务 #{}: {}", job.id, label);
+        let result = run(&job.kind).await;
+        match &result {
+            Ok(r) if r.success => info!("[任务队列] ✓ #{} {}", job.id, r.message),
+            Ok(r) => warn!("[任务队列] ✗ #{} {}", job.id, r.message),
+            Err(e) => warn!("[任务队列] ✗ #{} {}", job.id, e),
+        }
+        let finished = open_db(&db_path()).and_then(|conn| {
+            mark_finished(&conn, job.id, &result, now())?;
+            // 更新过程会重启 Gateway，排队的重启不再需要
+            let updated = matches!(
+                job.kind,
+                JobKind::UpdateOpenclaw | JobKind::ApplyUpdate { .. }
+            );
+            if updated && result.as_ref().is_ok_and(|r| r.success) {
+                cancel_where(&conn, "type = 'restart_gateway'", now())?;
+            }
+            Ok(())
+        });
+        if let Err(e) = finished {
+            warn!("[任务队列] {}", e);
+            return;
+        }
+    }
+}
+
+/// 获取任务队列（默认只返回排队中和执行中的任务）
+#[guarded]
+#[command]
+pub async fn list_jobs(include_finished: Option<bool>) -> Result<Vec<Job>, String> {
+    if !include_finished.unwrap_or(false) {
+        return active_jobs();
+    }
+    query(&open_db(&db_path())?, "ORDER BY id DESC")
+}
+
+/// 取消排队中的任务（执行中的任务无法取消）
+#[guarded]
+#[command]
+pub async fn cancel_job(id: i64) -> Result<(), String> {
+    let conn = open_db(&db_path())?;
+    if cancel_where(&conn, &format!("id = {}", id), now())? == 0 {
+        let status: Option<String> = conn
+            .query_row("SELECT status FROM jobs WHERE id = ?1", [id], |row| {
+                row.get(0)
+            })
+            .optional()
+            .map_err(|e| e.to_string())?;
+        return Err(match status.as_deref() {
+            None => format!("任务 #{} 不存在", id),
+            Some("running") => format!("任务 #{} 正在执行，无法取消", id),
+            Some(_) => format!("任务 #{} 已结束", id),
+        });
+    }
+    info!("[任务队列] 已取消任务 #{}", id);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn queue_orders_replaces_and_resumes_jobs() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_schema(&conn).unwrap();
+        let restart = JobKind::RestartGateway {
+            reason: "配置修改".to_string(),
+        };
+        let old = insert(
+            &conn,
+            &JobKind::ApplyUpdate {
+                version: "1.0.0".to_string(),
+            },
+            true,
+            1,
+        )
+        .unwrap();
+        insert(&conn, &restart, false, 2).unwrap();
+        let update = insert(
+            &conn,
+            &JobKind::ApplyUpdate {
+                version: "1.1.0".to_string(),
+            },
+            true,
+            3,
+        )
+        .unwrap();
+
+        // 同类任务只保留最新的一个，窗口外只执行普通任务
+        assert_eq!(query(&conn, "WHERE status = 'queued'").unwrap().len(), 2);
+        assert_eq!(
+            next_runnable(&conn, false).unwrap().map(|j| j.kind),
+            Some(restart)
+        );
+        let next = next_runnable(&conn, true).unwrap().unwrap();
+        assert_eq!(next.id, update);
+        assert_ne!(next.id, old);
+
+        // 执行中退出后重新排队，超过次数后标记为失败
+        for attempt in 1..=MAX_ATTEMPTS {
+            assert!(mark_running(&conn, update, 10).unwrap());
+            requeue_interrupted(&conn, 11).unwrap();
+            let job = &query(&conn, &format!("WHERE id = {}", update)).unwrap()[0];
+            let expected = if attempt < MAX_ATTEMPTS {
+                JobStatus::Queued
+            } else {
+                JobStatus::Failed
+            };
+            assert_eq!(job.status, expected);
+        }
+        assert_eq!(
+            cancel_where(&conn, "maintenance_window = 0", 12).unwrap(),
+            1
+        );
+        assert!(next_runnable(&conn, true).unwrap().is_none());
+    }
+}
@@ -0,0 +1,560 @@
+//! 知识库（RAG）文档管理：把本地文档或网页加入 Agent 的长期记忆检索范围
+//! （memorySearch.extraPaths），并调用 `openclaw memory index` 重建索引
+use crate::commands::capabilities::{self, Feature};
+use crate::commands::{config, events, memory, privacy, settings, workspace};
+use crate::utils::{audit, http, platform, shell};
+use log::{info, warn};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use openclaw_macros::guarded;
+use tauri::{command, AppHandle};
+
+/// 知识库重建索引进度事件
+pub const KNOWLEDGE_INDEX_PROGRESS_EVENT: &str = "knowledge-index-progress";
+
+/// 参与索引的文档扩展名
+const DOCUMENT_EXTENSIONS: &[&str] = &["md", "markdown", "txt"];
+
+/// 正在重建索引（同一时间只允许一个）
+static INDEXING: AtomicBool = AtomicBool::new(false);
+
+/// 知识来源类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KnowledgeSourceKind {
+    /// 本地文件或目录（直接加入检索范围）
+    Path,
+    /// 网页（下载转换为文本后保存在 ~/.openclaw/knowledge/<agent>/）
+    Url,
+}
+
+/// 知识来源
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KnowledgeSource {
+    pub id: String,
+    pub agent: String,
+    pub kind: KnowledgeSourceKind,
+    /// 本地路径或网页地址
+    pub location: String,
+    /// 网页的标题或本地文件名
+    pub title: String,
+    pub added_at: i64,
+    #[serde(default)]
+    pub indexed_at: Option<i64>,
+    /// 参与索引的文档数和大小
+    #[serde(default)]
+    pub files: u64,
+    #[serde(default)]
+    pub bytes: u64,
+    #[serde(default)]
+    pub last_error: Option<String>,
+}
+
+/// 重建索引的阶段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KnowledgeIndexStage {
+    /// 重新下载网页来源
+    Fetch,
+    /// 调用 openclaw memory index
+    Index,
+}
+
+/// 重建索引进度（每一步开始和结束时各推送一次）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KnowledgeIndexProgress {
+    pub agent: String,
+    pub stage: KnowledgeIndexStage,
+    /// 从 1 开始
+    pub step: usize,
+    pub total: usize,
+    pub source: Option<String>,
+    pub done: bool,
+    pub error: Option<String>,
+}
+
+/// 网页来源保存目录
+fn knowledge_dir(agent: &str) -> PathBuf {
+    PathBuf::from(platform::get_config_dir())
+        .join("knowledge")
+        .join(agent)
+}
+
+fn is_url(location: &str) -> bool {
+    location.starts_with("http://") || location.starts_with("https://")
+}
+
+fn is_document(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| DOCUMENT_EXTENSIONS.contains(&e.to_ascii_lowercase().as_str()))
+}
+
+/// 统计路径下参与索引的文档（跳过隐藏目录）
+fn document_stats(path: &Path) -> (u64, u64) {
+    let Ok(meta) = std::fs::metadata(path) else {
+        return (0, 0);
+    };
+    if meta.is_file() {
+        return if is_document(path) {
+            (1, meta.len())
+        } else {
+            (0, 0)
+        };
+    }
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return (0, 0);
+    };
+    entries
+        .flatten()
+        .filter(|e| !e.file_name().to_string_lossy().starts_with('.'))
+        .map(|e| document_stats(&e.path()))
+        .fold((0, 0), |(f, b), (ef, eb)| (f + ef, b + eb))
+}
+
+/// 把 HTML 转换为纯文本，返回（标题，正文）
+fn html_to_text(html: &str) -> (Option<String>, String) {
+    static PATTERNS: OnceLock<[Regex; 4]> = OnceLock::new();
+    let [hidden, block, tag, title] = PATTERNS.get_or_init(|| {
+        [
+            r"(?is)<(script|style|noscript|title)\b.*?</(script|style|noscript|title)>",
+            r"(?i)<br\s*/?>|</(p|div|li|h[1-6]|tr|section|article)>",
+            r"(?s)<[^>]*>",
+            r"(?is)<title[^>]*>(.*?)</title>",
+        ]
+        .map(|p| Regex::new(p).unwrap())
+    });
+    let decode = |s: &str| {
+        s.replace("&nbsp;", " ")
+            .replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&quot;", "\"")
+            .replace("&#39;", "'")
+            .replace("&amp;", "&")
+    };
+    let title = title
+        .captures(html)
+        .map(|c| decode(c[1].trim()))
+        .filter(|t| !t.is_empty());
+    let text = hidden.replace_all(html, "");
+    let text = block.replace_all(&text, "\n");
+    let text = decode(&tag.replace_all(&text, ""));
+    let mut lines: Vec<&str> = Vec::new();
+    for line in text.lines().map(str::trim) {
+        if !line.is_empty() || lines.last().is_some_and(|l| !l.is_empty()) {
+            lines.push(line);
+        }
+    }
+    (title, lines.join("\n").trim().to_string())
+}
+
+/// 下载网页并转换为 Markdown 文本，返回（标题，内容）
+async fn fetch_document(url: &str) -> Result<(String, String), String> {
+    if !privacy::is_local_url(url) {
+        privacy::guard("下载网页知识来源")?;
+    }
+    let response = http::client()?
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("下载 {} 失败: {}", url, e))?;
+    if !response.status().is_success() {
+        return Err(format!("下载 {} 失败: HTTP {}", url, response.status()));
+    }
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let body = response
+        .text()
+        .await
+        .map_err(|e| format!("读取 {} 失败: {}", url, e))?;
+    let (title, text) = if content_type.contains("html") {
+        html_to_text(&body)
+    } else if content_type.starts_with("text/") || content_type.contains("markdown") {
+        (None, body.trim().to_string())
+    } else {
+        return Err(format!("不支持的内容类型: {}", content_type));
+    };
+    if text.is_empty() {
+        return Err(format!("{} 没有可索引的文本内容", url));
+    }
+    let title = title.unwrap_or_else(|| url.to_string());
+    let document = format!("# {}\n\n> 来源: {}\n\n{}\n", title, url, text);
+    Ok((title, document))
+}
+
+/// 下载网页来源到知识库目录，更新来源的统计信息
+async fn store_url_source(source: &mut KnowledgeSource) -> Result<(), String> {
+    let (title, document) = fetch_document(&source.location).await?;
+    let dir = knowledge_dir(&source.agent);
+    std::fs::create_dir_all(&dir).map_err(|e| format!("创建知识库目录失败: {}", e))?;
+    let path = dir.join(format!("{}.md", source.id));
+    std::fs::write(&path, &document)
+        .map_err(|e| format!("保存 {} 失败: {}", source.location, e))?;
+    source.title = title;
+    source.files = 1;
+    source.bytes = document.len() as u64;
+    Ok(())
+}
+
+/// 来源加入检索范围的路径（网页来源共用知识库目录）
+fn indexed_path(source: &KnowledgeSource) -> String {
+    match source.kind {
+        KnowledgeSourceKind::Path => source.location.clone(),
+        KnowledgeSourceKind::Url => knowledge_dir(&source.agent).to_string_lossy().to_string(),
+    }
+}
+
+/// 在 Agent（或 agents.defaults）的 memorySearch.extraPaths 中添加或移除路径，返回是否有修改
+fn update_extra_paths(cfg: &mut Value, agent: &str, path: &str, add: bool) -> Result<bool, String> {
+    let section = match memory::agent_index(cfg, agent) {
+        Some(i) => cfg.pointer_mut(&format!("/agents/list/{}", i)),
+        None if agent == "main" => {
+            if !cfg.get("agents").is_some_and(Value::is_object) {
+                cfg["agents"] = json!({});
+            }
+            let agents = &mut cfg["agents"];
+            if !agents.get("defaults").is_some_and(Value::is_object) {
+                agents["defaults"] = json!({});
+            }
+            agents.get_mut("defaults")
+        }
+        None => return Err(format!("Agent {} 不在 agents.list 中", agent)),
+    }
+    .ok_or_else(|| format!("Agent {} 的配置格式无效", agent))?;
+    let mut paths: Vec<Value> = section
+        .pointer("/memorySearch/extraPaths")
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default();
+    let exists = paths.iter().any(|p| p.as_str() == Some(path));
+    match (add, exists) {
+        (true, false) => paths.push(json!(path)),
+        (false, true) => paths.retain(|p| p.as_str() != Some(path)),
+        _ => return Ok(false),
+    }
+    let Some(section) = section.as_object_mut() else {
+        return Err(format!("Agent {} 的配置格式无效", agent));
+    };
+    let search = section.entry("memorySearch").or_insert_with(|| json!({}));
+    if !search.is_object() {
+        return Err("memorySearch 配置格式无效".to_string());
+    }
+    if paths.is_empty() {
+        search.as_object_mut().map(|s| s.remove("extraPaths"));
+    } else {
+        search["extraPaths"] = Value::Array(paths);
+    }
+    Ok(true)
+}
+
+/// 生成来源 ID：类型前缀加随机后缀
+fn new_source_id(kind: KnowledgeSourceKind) -> String {
+    let prefix = match kind {
+        KnowledgeSourceKind::Path => "doc",
+        KnowledgeSourceKind::Url => "web",
+    };
+    let id = uuid::Uuid::new_v4().simple().to_string();
+    format!("{}-{}", prefix, &id[..8])
+}
+
+fn run_index(agent: &str) -> Result<String, String> {
+    capabilities::require(Feature::MemoryIndex)?;
+    shell::run_openclaw(&["memory", "index", "--agent", agent, "--force"])
+}
+
+/// 列出知识来源（`agent` 为空时列出所有 Agent 的来源），本地来源重新统计文档数
+#[guarded]
+#[command]
+pub async fn list_knowledge_sources(agent: Option<String>) -> Result<Vec<KnowledgeSource>, String> {
+    let mut sources = settings::load_settings().knowledge;
+    if let Some(agent) = agent.as_deref() {
+        let agent = workspace::validate_agent(agent)?;
+        sources.retain(|s| s.agent == agent);
+    }
+    for source in sources.iter_mut() {
+        if source.kind == KnowledgeSourceKind::Path {
+            (source.files, source.bytes) = document_stats(Path::new(&source.location));
+        }
+    }
+    Ok(sources)
+}
+
+/// 添加知识来源：本地文件 / 目录或网页地址（添加后需要重建索引才能被检索）
+#[guarded]
+#[command]
+pub async fn add_knowledge_source(
+    agent: Option<String>,
+    location: String,
+) -> Result<KnowledgeSource, String> {
+    let agent = workspace::validate_agent(agent.as_deref().unwrap_or_default())?.to_string();
+    let location = location.trim();
+    let kind = if is_url(location) {
+        KnowledgeSourceKind::Url
+    } else {
+        KnowledgeSourceKind::Path
+    };
+    let location = match kind {
+        KnowledgeSourceKind::Url => location.to_string(),
+        KnowledgeSourceKind::Path => workspace::expand_home(location)
+            .canonicalize()
+            .map_err(|e| format!("无法访问 {}: {}", location, e))?
+            .to_string_lossy()
+            .to_string(),
+    };
+    if settings::load_settings()
+        .knowledge
+        .iter()
+        .any(|s| s.agent == agent && s.location == location)
+    {
+        return Err(format!("{} 已在 {} 的知识库中", location, agent));
+    }
+
+    let mut source = KnowledgeSource {
+        id: new_source_id(kind),
+        agent: agent.clone(),
+        kind,
+        title: Path::new(&location)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| location.clone()),
+        location,
+        added_at: chrono::Utc::now().timestamp(),
+        indexed_at: None,
+        files: 0,
+        bytes: 0,
+        last_error: None,
+    };
+    match kind {
+        KnowledgeSourceKind::Url => store_url_source(&mut source).await?,
+        KnowledgeSourceKind::Path => {
+            (source.files, source.bytes) = document_stats(Path::new(&source.location));
+            if source.files == 0 {
+                return Err(format!(
+                    "{} 中没有可索引的文档（支持 {}）",
+                    source.location,
+                    DOCUMENT_EXTENSIONS.join(" / ")
+                ));
+            }
+        }
+    }
+
+    let mut cfg = config::load_openclaw_config()?;
+    if update_extra_paths(&mut cfg, &agent, &indexed_path(&source), true)? {
+        config::save_config(cfg).await?;
+    }
+    settings::update(|settings| {
+        // 下载网页期间可能已有相同来源被添加
+        if !settings
+            .knowledge
+            .iter()
+            .any(|s| s.agent == source.agent && s.location == source.location)
+        {
+            settings.knowledge.push(source.clone());
+        }
+        Ok(())
+    })?;
+    audit::record(
+        "add_knowledge_source",
+        &source.location,
+        true,
+        json!({ "agent": agent, "kind": source.kind, "files": source.files }),
+    );
+    info!(
+        "[知识库] ✓ 已添加 {} 到 {}（{} 个文档）",
+        source.location, agent, source.files
+    );
+    Ok(source)
+}
+
+/// 移除知识来源：从检索范围中移除，并删除下载的网页副本（不会删除本地文档）
+#[guarded]
+#[command]
+pub async fn remove_knowledge_source(id: String) -> Result<String, String> {
+    let mut settings = settings::load_settings();
+    let index = settings
+        .knowledge
+        .iter()
+        .position(|s| s.id == id)
+        .ok_or_else(|| format!("知识来源 {} 不存在", id))?;
+    let source = settings.knowledge.remove(index);
+    // 网页来源共用目录，最后一个网页来源移除时才从检索范围中移除
+    let shared = source.kind == KnowledgeSourceKind::Url
+        && settings
+            .knowledge
+            .iter()
+            .any(|s| s.agent == source.agent && s.kind == KnowledgeSourceKind::Url);
+    if !shared {
+        let mut cfg = config::load_openclaw_config()?;
+        if update_extra_paths(&mut cfg, &source.agent, &indexed_path(&source), false)? {
+            config::save_config(cfg).await?;
+        }
+    }
+    if source.kind == KnowledgeSourceKind::Url {
+        let path = knowledge_dir(&source.agent).join(format!("{}.md", source.id));
+        if let Err(e) = std::fs::remove_file(&path) {
+            warn!("[知识库] 删除 {} 失败: {}", path.display(), e);
+        }
+    }
+    settings::update(|settings| {
+        settings.knowledge.retain(|s| s.id != id);
+        Ok(())
+    })?;
+    audit::record(
+        "remove_knowledge_source",
+        &source.location,
+        true,
+        json!({ "agent": source.agent }),
+    );
+    info!("[知识库] ✓ 已移除 {}", source.location);
+    Ok(format!(
+        "已从 {} 的知识库移除 {}",
+        source.agent, source.title
+    ))
+}
+
+/// 重建 Agent 的知识库索引：先重新下载网页来源，再调用 openclaw memory index，
+/// 每一步通过 knowledge-index-progress 事件推送进度
+#[guarded]
+#[command]
+pub async fn reindex_knowledge(
+    app: AppHandle,
+    agent: Option<String>,
+) -> Result<Vec<KnowledgeSource>, String> {
+    let agent = workspace::validate_agent(agent.as_deref().unwrap_or_default())?.to_string();
+    if INDEXING.swap(true, Ordering::SeqCst) {
+        return Err("知识库正在重建索引，请稍后再试".to_string());
+    }
+    let result = reindex(&app, &agent).await;
+    INDEXING.store(false, Ordering::SeqCst);
+    result
+}
+
+async fn reindex(app: &AppHandle, agent: &str) -> Result<Vec<KnowledgeSource>, String> {
+    let mut sources: Vec<KnowledgeSource> = settings::load_settings()
+        .knowledge
+        .into_iter()
+        .filter(|s| s.agent == agent)
+        .collect();
+    let fetches = sources
+        .iter()
+        .filter(|s| s.kind == KnowledgeSourceKind::Url)
+        .count();
+    let total = fetches + 1;
+    info!(
+        "[知识库] 开始重建 {} 的索引（{} 个来源）",
+        agent,
+        sources.len()
+    );
+
+    for (i, source) in sources
+        .iter_mut()
+        .filter(|s| s.kind == KnowledgeSourceKind::Url)
+        .enumerate()
+    {
+        let mut progress = KnowledgeIndexProgress {
+            agent: agent.to_string(),
+            stage: KnowledgeIndexStage::Fetch,
+            step: i + 1,
+            total,
+            source: Some(source.id.clone()),
+            done: false,
+            error: None,
+        };
+        let _ = events::emit(app, KNOWLEDGE_INDEX_PROGRESS_EVENT, &progress);
+        // 下载失败时保留上次的副本继续索引
+        source.last_error = store_url_source(source).await.err();
+        if let Some(e) = &source.last_error {
+            warn!("[知识库] ✗ 更新 {} 失败: {}", source.location, e);
+        }
+        progress.done = true;
+        progress.error = source.last_error.clone();
+        let _ = events::emit(app, KNOWLEDGE_INDEX_PROGRESS_EVENT, &progress);
+    }
+
+    let mut progress = KnowledgeIndexProgress {
+        agent: agent.to_string(),
+        stage: KnowledgeIndexStage::Index,
+        step: total,
+        total,
+        source: None,
+        done: false,
+        error: None,
+    };
+    let _ = events::emit(app, KNOWLEDGE_INDEX_PROGRESS_EVENT, &progress);
+    let owned = agent.to_string();
+    let result = tokio::task::spawn_blocking(move || run_index(&owned))
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|r| r);
+    progress.done = true;
+    progress.error = result.as_ref().err().cloned();
+    let _ = events::emit(app, KNOWLEDGE_INDEX_PROGRESS_EVENT, &progress);
+
+    let now = chrono::Utc::now().timestamp();
+    for source in sources.iter_mut() {
+        if result.is_ok() {
+            source.indexed_at = Some(now);
+        }
+        if source.kind == KnowledgeSourceKind::Path {
+            (source.files, source.bytes) = document_stats(Path::new(&source.location));
+        }
+    }
+    // 重建期间可能有来源被添加或移除，只更新仍然存在的来源
+    settings::update(|settings| {
+        for existing in settings.knowledge.iter_mut() {
+            if let Some(updated) = sources.iter().find(|s| s.id == existing.id) {
+                *existing = updated.clone();
+            }
+        }
+        Ok(())
+    })?;
+    audit::record(
+        "reindex_knowledge",
+        agent,
+        result.is_ok(),
+        json!({ "sources": sources.len(), "error": progress.error }),
+    );
+    result.map_err(|e| format!("重建索引失败: {}", e))?;
+    info!("[知识库] ✓ {} 的索引已重建", agent);
+    Ok(sources)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_html_and_tracks_extra_paths() {
+        let html = "<html><head><title>部署 &amp; 运维</title><style>p{}</style></head>\
+                    <body><h1>指南</h1><p>第一段<br>第二行</p>\n\n\n<script>x()</script><p>结束</p></body></html>";
+        let (title, text) = html_to_text(html);
+        assert_eq!(title.as_deref(), Some("部署 & 运维"));
+        assert_eq!(text, "指南\n第一段\n第二行\n\n结束");
+
+        let mut cfg = json!({ "agents": { "list": [{ "id": "ops" }] } });
+        assert!(update_extra_paths(&mut cfg, "ops", "/docs", true).unwrap());
+        assert!(!update_extra_paths(&mut cfg, "ops", "/docs", true).unwrap());
+        assert_eq!(
+            cfg["agents"]["list"][0]["memorySearch"]["extraPaths"],
+            json!(["/docs"])
+        );
+        assert!(update_extra_paths(&mut cfg, "main", "/notes", true).unwrap());
+        assert_eq!(
+            cfg["agents"]["defaults"]["memorySearch"]["extraPaths"],
+            json!(["/notes"])
+        );
+        assert!(update_extra_paths(&mut cfg, "ops", "/docs", false).unwrap());
+        assert!(cfg["agents"]["list"][0]["memorySearch"]
+            .get("extraPaths")
+            .is_none());
+        assert!(update_extra_paths(&mut cfg, "ghost", "/docs", true).is_err());
+    }
+}
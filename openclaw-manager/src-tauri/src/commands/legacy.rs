@@ -0,0 +1,448 @@
+use crate::commands::policy;
+use crate::utils::{audit, file, platform, shell, time};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::path::{Path, PathBuf};
+use openclaw_macros::guarded;
+use tauri::command;
+
+/// 旧版本（改名前）的命令和配置目录名
+const LEGACY_NAMES: &[&str] = &["clawdbot", "moltbot"];
+/// install.sh 可能修改的 shell 配置文件
+const PROFILE_FILES: &[&str] = &[
+    ".bashrc",
+    ".bash_profile",
+    ".zshrc",
+    ".profile",
+    ".config/fish/config.fish",
+];
+/// 注释掉的行使用的前缀，便于用户识别和恢复
+const COMMENT_MARKER: &str = "# [openclaw-manager] ";
+
+/// 脚本安装遗留物类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LegacyKind {
+    /// shell 配置文件中的 PATH / 补全 / alias 片段
+    ProfileSnippet,
+    /// 旧的配置目录（~/.clawdbot、~/.moltbot）
+    ConfigDir,
+    /// 旧命令或重复安装的 openclaw 链接
+    StaleBinary,
+}
+
+/// 处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LegacyAction {
+    /// 接管：保留并由 Manager 使用（如迁移旧配置目录）
+    Adopt,
+    /// 清理：注释掉片段、归档目录或删除链接
+    Remove,
+    /// 无法自动处理，需要用户手动确认
+    Manual,
+}
+
+/// 脚本安装遗留物
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LegacyArtifact {
+    /// 稳定 ID，迁移时用于选择要处理的项
+    pub id: String,
+    pub kind: LegacyKind,
+    pub path: String,
+    /// 配置文件片段所在行（从 1 开始）
+    pub line: Option<usize>,
+    pub detail: String,
+    pub action: LegacyAction,
+}
+
+/// 迁移结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LegacyMigrationReport {
+    pub applied: Vec<String>,
+    pub errors: Vec<String>,
+    /// 迁移后重新检测的结果
+    pub remaining: Vec<LegacyArtifact>,
+}
+
+fn mentions_legacy(line: &str) -> bool {
+    let lower = line.to_lowercase();
+    LEGACY_NAMES.iter().any(|n| lower.contains(n))
+}
+
+/// 扫描 shell 配置文件内容，返回 (行号, 处理方式, 原始行)
+/// 旧命令名相关的行注释掉；openclaw / .npm-global 的 PATH 由 Manager 接管（保留）
+fn scan_profile(content: &str) -> Vec<(usize, LegacyAction, String)> {
+    content
+        .lines()
+        .enumerate()
+        .filter_map(|(i, line)| {
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                return None;
+            }
+            let lower = trimmed.to_lowercase();
+            let action = if mentions_legacy(&lower) {
+                LegacyAction::Remove
+            } else if lower.contains("openclaw") || lower.contains(".npm-global") {
+                LegacyAction::Adopt
+            } else {
+                return None;
+            };
+            Some((i + 1, action, trimmed.to_string()))
+        })
+        .collect()
+}
+
+/// 注释掉指定行（行号从 1 开始），保留原内容便于恢复
+fn comment_out(content: &str, lines: &[usize]) -> String {
+    let mut result: Vec<String> = content
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            if lines.contains(&(i + 1)) {
+                format!("{}{}", COMMENT_MARKER, line)
+            } else {
+                line.to_string()
+            }
+        })
+        .collect();
+    if content.ends_with('\n') {
+        result.push(String::new());
+    }
+    result.join("\n")
+}
+
+fn artifact_id(kind: LegacyKind, path: &str, line: Option<usize>) -> String {
+    match line {
+        Some(line) => format!("{:?}:{}:{}", kind, path, line),
+        None => format!("{:?}:{}", kind, path),
+    }
+}
+
+/// 命令在 PATH 中的所有位置
+fn which_all(cmd: &str) -> Vec<String> {
+    let output = if platform::is_windows() {
+        shell::run_command_output("where", &[cmd])
+    } else {
+        shell::run_command_output("which", &["-a", cmd])
+    };
+    let mut paths: Vec<String> = output
+        .unwrap_or_default()
+        .lines()
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty())
+        .collect();
+    paths.dedup();
+    paths
+}
+
+/// 检测脚本安装的遗留物
+fn detect(home: &Path) -> Vec<LegacyArtifact> {
+    let mut artifacts = Vec::new();
+
+    // 1. shell 配置文件片段
+    if !platform::is_windows() {
+        for name in PROFILE_FILES {
+            let path = home.join(name);
+            let Ok(content) = std::fs::read_to_string(&path) else {
+                continue;
+            };
+            let path_str = path.to_string_lossy().to_string();
+            for (line, action, text) in scan_profile(&content) {
+                artifacts.push(LegacyArtifact {
+                    id: artifact_id(LegacyKind::ProfileSnippet, &path_str, Some(line)),
+                    kind: LegacyKind::ProfileSnippet,
+                    path: path_str.clone(),
+                    line: Some(line),
+                    detail: text,
+                    action,
+                });
+            }
+        }
+    }
+
+    // 2. 旧配置目录
+    let config_dir = PathBuf::from(platform::get_config_dir());
+    let has_config = config_dir.join("openclaw.json").exists();
+    for name in LEGACY_NAMES {
+        let dir = home.join(format!(".{}", name));
+        let is_real_dir = std::fs::symlink_metadata(&dir)
+            .map(|m| m.is_dir())
+            .unwrap_or(false);
+        if !is_real_dir {
+            continue;
+        }
+        let dir_str = dir.to_string_lossy().to_string();
+        let (action, detail) = if has_config {
+            (
+                LegacyAction::Remove,
+                "已有 ~/.openclaw 配置，旧目录将归档到 Manager 数据目录".to_string(),
+            )
+        } else {
+            (
+                LegacyAction::Adopt,
+                "将迁移为 ~/.openclaw 配置目录".to_string(),
+            )
+        };
+        artifacts.push(LegacyArtifact {
+            id: artifact_id(LegacyKind::ConfigDir, &dir_str, None),
+            kind: LegacyKind::ConfigDir,
+            path: dir_str,
+            line: None,
+            detail,
+            action,
+        });
+    }
+
+    // 3. 旧命令和重复的 openclaw
+    for name in LEGACY_NAMES {
+        for path in which_all(name) {
+            artifacts.push(LegacyArtifact {
+                id: artifact_id(LegacyKind::StaleBinary, &path, None),
+                kind: LegacyKind::StaleBinary,
+                path,
+                line: None,
+                detail: format!("旧版命令 {}", name),
+                action: LegacyAction::Remove,
+            });
+        }
+    }
+    // 只有确定当前使用的是哪个 openclaw 时才判断重复
+    let active = shell::get_openclaw_path().filter(|p| Path::new(p).is_absolute());
+    let copies = which_all("openclaw");
+    if active.is_some() && copies.len() > 1 {
+        for path in copies.into_iter().filter(|p| Some(p) != active.as_ref()) {
+            let is_link = std::fs::symlink_metadata(&path)
+                .map(|m| m.file_type().is_symlink())
+                .unwrap_or(false);
+            artifacts.push(LegacyArtifact {
+                id: artifact_id(LegacyKind::StaleBinary, &path, None),
+                kind: LegacyKind::StaleBinary,
+                detail: format!(
+                    "重复的 openclaw（当前使用 {}）",
+                    active.clone().unwrap_or_default()
+                ),
+                path,
+                line: None,
+                action: if is_link {
+                    LegacyAction::Remove
+                } else {
+                    LegacyAction::Manual
+                },
+            });
+        }
+    }
+
+    artifacts
+}
+
+fn home_dir() -> Result<PathBuf, String> {
+    dirs::home_dir().ok_or_else(|| "无法获取用户主目录".to_string())
+}
+
+/// 注释掉某个配置文件中的旧片段（先备份原文件）
+fn clean_profile(path: &Path, lines: &[usize]) -> Result<(), String> {
+    let content = std::fs::read_to_string(path).map_err(|e| format!("读取失败: {}", e))?;
+    let backup = PathBuf::from(format!("{}.openclaw-manager.bak", path.display()));
+    file::copy_atomic(path, &backup).map_err(|e| format!("备份失败: {}", e))?;
+    file::write_atomic(path, comment_out(&content, lines).as_bytes())
+        .map_err(|e| format!("写入失败: {}", e))
+}
+
+/// 迁移或归档旧配置目录
+fn migrate_config_dir(dir: &Path, action: LegacyAction) -> Result<(), String> {
+    let target = PathBuf::from(platform::get_config_dir());
+    if action == LegacyAction::Adopt && !target.join("openclaw.json").exists() {
+        if target.exists() {
+            // 目标目录已存在但没有配置（如只初始化了子目录），逐项合并
+            let entries = std::fs::read_dir(dir).map_err(|e| format!("读取失败: {}", e))?;
+            for entry in entries.flatten() {
+                let to = target.join(entry.file_name());
+                if !to.exists() {
+                    std::fs::rename(entry.path(), &to).map_err(|e| format!("移动失败: {}", e))?;
+                }
+            }
+        } else {
+            std::fs::rename(dir, &target).map_err(|e| format!("移动失败: {}", e))?;
+        }
+        // 旧版本的配置文件名
+        let name = dir
+            .file_name()
+            .map(|n| n.to_string_lossy().trim_start_matches('.').to_string())
+            .unwrap_or_default();
+        let old_config = target.join(format!("{}.json", name));
+        if old_config.exists() && !target.join("openclaw.json").exists() {
+            std::fs::rename(&old_config, target.join("openclaw.json"))
+                .map_err(|e| format!("重命名配置文件失败: {}", e))?;
+        }
+        if dir.exists() {
+            archive(dir)?;
+        }
+        return Ok(());
+    }
+    archive(dir)
+}
+
+/// 归档到 Manager 数据目录（不直接删除用户数据）
+fn archive(dir: &Path) -> Result<(), String> {
+    let name = dir
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let archive_dir = Path::new(&platform::get_manager_data_dir())
+        .join("backups")
+        .join("legacy");
+    std::fs::create_dir_all(&archive_dir).map_err(|e| format!("创建归档目录失败: {}", e))?;
+    let target = archive_dir.join(format!(
+        "{}-{}",
+        name.trim_start_matches('.'),
+        time::now().format("%Y%m%d%H%M%S")
+    ));
+    std::fs::rename(dir, &target).map_err(|e| format!("归档失败: {}", e))
+}
+
+/// 删除旧命令：符号链接直接删除，npm 全局包用 npm uninstall
+fn remove_binary(path: &Path) -> Result<(), String> {
+    let is_link = std::fs::symlink_metadata(path)
+        .map(|m| m.file_type().is_symlink())
+        .unwrap_or(false);
+    if is_link {
+        return std::fs::remove_file(path).map_err(|e| format!("删除链接失败: {}", e));
+    }
+    let name = path
+        .file_stem()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    if !LEGACY_NAMES.contains(&name.as_str()) {
+        return Err("不是符号链接，请手动删除".to_string());
+    }
+    shell::run_script_output(&format!("npm uninstall -g {}", name)).map(|_| ())
+}
+
+/// 检测 install.sh 等脚本安装留下的配置片段、旧配置目录和旧命令
+#[guarded]
+#[command]
+pub async fn detect_legacy_install() -> Result<Vec<LegacyArtifact>, String> {
+    info!("[旧版迁移] 检测脚本安装遗留物...");
+    let home = home_dir()?;
+    let artifacts = tokio::task::spawn_blocking(move || detect(&home))
+        .await
+        .map_err(|e| format!("检测失败: {}", e))?;
+    info!("[旧版迁移] 发现 {} 项", artifacts.len());
+    Ok(artifacts)
+}
+
+/// 接管或清理脚本安装遗留物，`ids` 为空时处理所有可自动处理的项
+#[guarded]
+#[command]
+pub async fn migrate_legacy_install(
+    ids: Option<Vec<String>>,
+    confirm_token: Option<String>,
+) -> Result<LegacyMigrationReport, String> {
+    policy::require_confirmation("migrate_legacy_install", confirm_token.as_deref())?;
+    let home = home_dir()?;
+    let selected: Vec<LegacyArtifact> = detect(&home)
+        .into_iter()
+        .filter(|a| a.action != LegacyAction::Manual)
+        .filter(|a| match &ids {
+            Some(ids) if !ids.is_empty() => ids.contains(&a.id),
+            _ => true,
+        })
+        .collect();
+    info!("[旧版迁移] 处理 {} 项", selected.len());
+
+    let mut applied = Vec::new();
+    let mut errors = Vec::new();
+
+    // 同一配置文件的多行一次处理，避免行号错位
+    let mut profiles: Vec<(String, Vec<usize>, Vec<String>)> = Vec::new();
+    for artifact in &selected {
+        if artifact.kind != LegacyKind::ProfileSnippet {
+            continue;
+        }
+        if artifact.action == LegacyAction::Adopt {
+            applied.push(artifact.id.clone());
+            continue;
+        }
+        let line = artifact.line.unwrap_or_default();
+        match profiles.iter_mut().find(|(p, _, _)| p == &artifact.path) {
+            Some((_, lines, ids)) => {
+                lines.push(line);
+                ids.push(artifact.id.clone());
+            }
+            None => profiles.push((artifact.path.clone(), vec![line], vec![artifact.id.clone()])),
+        }
+    }
+    for (path, lines, ids) in profiles {
+        match clean_profile(Path::new(&path), &lines) {
+            Ok(_) => applied.extend(ids),
+            Err(e) => errors.push(format!("{}: {}", path, e)),
+        }
+    }
+
+    for artifact in selected
+        .iter()
+        .filter(|a| a.kind != LegacyKind::ProfileSnippet)
+    {
+        let path = Path::new(&artifact.path);
+        let result = match artifact.kind {
+            LegacyKind::ConfigDir => migrate_config_dir(path, artifact.action),
+            _ => remove_binary(path),
+        };
+        match result {
+            Ok(_) => applied.push(artifact.id.clone()),
+            Err(e) => errors.push(format!("{}: {}", artifact.path, e)),
+        }
+    }
+
+    for e in &errors {
+        warn!("[旧版迁移] ✗ {}", e);
+    }
+    info!("[旧版迁移] ✓ 完成 {} 项", applied.len());
+    audit::record(
+        "migrate_legacy_install",
+        &home.to_string_lossy(),
+        errors.is_empty(),
+        json!({ "applied": applied, "errors": errors }),
+    );
+
+    Ok(LegacyMigrationReport {
+        applied,
+        errors,
+        remaining: detect(&home),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profile_snippets_are_classified_and_commented_out() {
+        let content = "export EDITOR=vim\n\
+            export PATH=\"$HOME/.npm-global/bin:$PATH\"\n\
+            # clawdbot completion (already commented)\n\
+            source <(clawdbot completion zsh)\n\
+            alias oc=openclaw\n";
+
+        let found = scan_profile(content);
+        let summary: Vec<_> = found.iter().map(|(l, a, _)| (*l, *a)).collect();
+        assert_eq!(
+            summary,
+            vec![
+                (2, LegacyAction::Adopt),
+                (4, LegacyAction::Remove),
+                (5, LegacyAction::Adopt),
+            ]
+        );
+
+        let cleaned = comment_out(content, &[4]);
+        assert!(cleaned.contains("# [openclaw-manager] source <(clawdbot completion zsh)"));
+        assert!(cleaned.ends_with('\n'));
+        assert!(scan_profile(&cleaned)
+            .iter()
+            .all(|(_, a, _)| *a == LegacyAction::Adopt));
+    }
+}
@@ -0,0 +1,626 @@
+use crate::commands::{analytics, consoles, events};
+use crate::utils::platform;
+use log::{debug, info};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+use openclaw_macros::guarded;
+use tauri::{command, AppHandle};
+
+/// Gateway 错误事件名
+pub const GATEWAY_ERROR_EVENT: &str = "gateway://error";
+/// 汇总时返回的问题数量上限
+const TOP_ISSUES: usize = 10;
+/// 日志跟随的轮询间隔
+const FOLLOW_INTERVAL: Duration = Duration::from_secs(2);
+/// 同一类错误在该时间内只提醒一次
+const ERROR_COOLDOWN: Duration = Duration::from_secs(5 * 60);
+
+/// 日志级别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+    Fatal,
+}
+
+impl LogLevel {
+    fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "trace" | "silly" => Some(Self::Trace),
+            "debug" => Some(Self::Debug),
+            "info" | "log" => Some(Self::Info),
+            "warn" | "warning" => Some(Self::Warn),
+            "error" | "err" => Some(Self::Error),
+            "fatal" | "critical" => Some(Self::Fatal),
+            _ => None,
+        }
+    }
+}
+
+/// 解析后的 Gateway 日志行
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogEntry {
+    pub timestamp: Option<String>,
+    pub level: LogLevel,
+    /// 子系统 / 模块（如 gateway、telegram、agent）
+    pub module: Option<String>,
+    pub request_id: Option<String>,
+    pub message: String,
+}
+
+/// 常见错误类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueCategory {
+    /// AI Provider 鉴权失败（API Key 无效或过期）
+    ProviderAuth,
+    /// AI Provider 限流或额度不足
+    ProviderQuota,
+    /// 渠道 Webhook 返回 4xx
+    ChannelWebhook,
+    /// 技能执行崩溃
+    SkillCrash,
+    /// 网络连接失败
+    Network,
+    Other,
+}
+
+/// 一类重复出现的问题
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogIssue {
+    pub category: IssueCategory,
+    /// 去掉数字、ID 后的错误信息，用于合并同类错误
+    pub signature: String,
+    pub module: Option<String>,
+    pub count: usize,
+    pub first_seen: Option<String>,
+    pub last_seen: Option<String>,
+    pub sample: String,
+}
+
+/// 日志分析结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogInsights {
+    pub range: String,
+    pub files: Vec<String>,
+    pub total_entries: usize,
+    pub errors: usize,
+    pub warnings: usize,
+    /// 按出现次数排序的主要问题
+    pub issues: Vec<LogIssue>,
+}
+
+fn text_line_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(
+            r"(?i)^(?P<ts>\d{4}-\d{2}-\d{2}[T ]\d{2}:\d{2}:\d{2}(?:\.\d+)?(?:Z|[+-]\d{2}:?\d{2})?)\s+(?:(?P<level1>trace|debug|info|warn|warning|error|fatal)\s+)?(?:\[(?P<module>[^\]]+)\]\s*)?(?:(?P<level2>trace|debug|info|warn|warning|error|fatal)[:\s]\s*)?(?P<msg>.*)$",
+        )
+        .unwrap()
+    })
+}
+
+fn request_id_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r#"(?i)\b(?:req(?:uest)?[_-]?id|run[_-]?id)["']?\s*[=:]\s*["']?([\w.-]+)"#)
+            .unwrap()
+    })
+}
+
+/// 从 JSON 对象中按顺序取第一个存在的字符串字段
+fn first_str(value: &Value, pointers: &[&str]) -> Option<String> {
+    pointers
+        .iter()
+        .find_map(|p| value.pointer(p).and_then(|v| v.as_str()))
+        .map(|s| s.to_string())
+}
+
+/// 解析 JSON 格式的日志行（tslog / pino 风格）
+fn parse_json_line(value: &Value) -> Option<LogEntry> {
+    let message = first_str(value, &["/msg", "/message", "/0", "/1"])?;
+    let level = first_str(value, &["/level", "/_meta/logLevelName"])
+        .and_then(|l| LogLevel::parse(&l))
+        .or_else(|| {
+            // pino 用数字表示级别
+            value
+                .get("level")
+                .and_then(|l| l.as_u64())
+                .map(|n| match n {
+                    0..=10 => LogLevel::Trace,
+                    11..=20 => LogLevel::Debug,
+                    21..=30 => LogLevel::Info,
+                    31..=40 => LogLevel::Warn,
+                    41..=50 => LogLevel::Error,
+                    _ => LogLevel::Fatal,
+                })
+        })
+        .unwrap_or(LogLevel::Info);
+    // tslog 的 _meta.name 可能是 {"subsystem":"gateway"} 形式的 JSON 字符串
+    let module = first_str(value, &["/module", "/subsystem", "/_meta/name", "/name"]).map(|m| {
+        serde_json::from_str::<Value>(&m)
+            .ok()
+            .and_then(|v| first_str(&v, &["/subsystem", "/module"]))
+            .unwrap_or(m)
+    });
+    let request_id = first_str(value, &["/requestId", "/reqId", "/runId", "/request_id"])
+        .or_else(|| request_id_from(&message));
+    Some(LogEntry {
+        timestamp: first_str(value, &["/time", "/timestamp", "/ts", "/_meta/date"]),
+        level,
+        module,
+        request_id,
+        message,
+    })
+}
+
+fn request_id_from(message: &str) -> Option<String> {
+    request_id_regex()
+        .captures(message)
+        .map(|c| c[1].to_string())
+}
+
+/// 解析一行 Gateway 日志（JSON 或 `时间 [模块] 级别 消息` 文本格式）
+pub fn parse_line(line: &str) -> Option<LogEntry> {
+    let line = line.trim();
+    if line.is_empty() {
+        return None;
+    }
+    if line.starts_with('{') {
+        if let Ok(value) = serde_json::from_str::<Value>(line) {
+            return parse_json_line(&value);
+        }
+    }
+    match text_line_regex().captures(line) {
+        Some(caps) => {
+            let message = caps["msg"].trim().to_string();
+            let level = caps
+                .name("level1")
+                .or_else(|| caps.name("level2"))
+                .and_then(|l| LogLevel::parse(l.as_str()))
+                .unwrap_or(LogLevel::Info);
+            Some(LogEntry {
+                timestamp: Some(caps["ts"].to_string()),
+                level,
+                module: caps.name("module").map(|m| m.as_str().to_string()),
+                request_id: request_id_from(&message),
+                message,
+            })
+        }
+        // 无时间戳的行（如堆栈）按消息内容推断级别
+        None => Some(LogEntry {
+            timestamp: None,
+            level: if line.to_lowercase().contains("error") {
+                LogLevel::Error
+            } else {
+                LogLevel::Info
+            },
+            module: None,
+            request_id: request_id_from(line),
+            message: line.to_string(),
+        }),
+    }
+}
+
+/// 渠道名（模块名或消息中出现即视为渠道相关）
+const CHANNEL_NAMES: &[&str] = &[
+    "telegram", "discord", "slack", "feishu", "lark", "whatsapp", "wechat", "dingtalk", "signal",
+    "imessage", "webhook",
+];
+
+/// 对警告 / 错误日志进行分类，非问题日志返回 None
+pub fn classify(entry: &LogEntry) -> Option<IssueCategory> {
+    if entry.level < LogLevel::Warn {
+        return None;
+    }
+    let msg = entry.message.to_lowercase();
+    let module = entry.module.as_deref().unwrap_or("").to_lowercase();
+    let has_status = |codes: &[&str]| codes.iter().any(|c| msg.contains(c));
+    let is_channel = CHANNEL_NAMES
+        .iter()
+        .any(|c| module.contains(c) || msg.contains(c));
+
+    let category = if msg.contains("invalid api key")
+        || msg.contains("invalid x-api-key")
+        || msg.contains("incorrect api key")
+        || msg.contains("authentication_error")
+        || msg.contains("unauthorized") && !is_channel
+        || has_status(&["401", "403"]) && (msg.contains("provider") || msg.contains("model"))
+    {
+        IssueCategory::ProviderAuth
+    } else if msg.contains("rate limit")
+        || msg.contains("rate_limit")
+        || msg.contains("insufficient_quota")
+        || msg.contains("429")
+    {
+        IssueCategory::ProviderQuota
+    } else if is_channel && has_status(&["400", "401", "403", "404", "409", "410", "413"]) {
+        IssueCategory::ChannelWebhook
+    } else if (module.contains("skill") || msg.contains("skill"))
+        && (msg.contains("crash")
+            || msg.contains("exited")
+            || msg.contains("threw")
+            || msg.contains("exception")
+            || msg.contains("error"))
+    {
+        IssueCategory::SkillCrash
+    } else if msg.contains("econnrefused")
+        || msg.contains("etimedout")
+        || msg.contains("enotfound")
+        || msg.contains("econnreset")
+        || msg.contains("fetch failed")
+        || msg.contains("socket hang up")
+    {
+        IssueCategory::Network
+    } else {
+        IssueCategory::Other
+    };
+    Some(category)
+}
+
+/// 去掉数字和 ID，合并同类错误
+fn signature(message: &str) -> String {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    let re = RE.get_or_init(|| Regex::new(r"[0-9a-fA-F]{8,}(?:-[0-9a-fA-F]{4,})*|\d+").unwrap());
+    let normalized = re.replace_all(message, "#");
+    normalized.chars().take(160).collect()
+}
+
+/// 汇总问题，按出现次数排序
+pub fn summarize(entries: &[LogEntry]) -> Vec<LogIssue> {
+    let mut issues: HashMap<(IssueCategory, String), LogIssue> = HashMap::new();
+    for entry in entries {
+        let Some(category) = classify(entry) else {
+            continue;
+        };
+        let sig = signature(&entry.message);
+        let issue = issues
+            .entry((category, sig.clone()))
+            .or_insert_with(|| LogIssue {
+                category,
+                signature: sig,
+                module: entry.module.clone(),
+                count: 0,
+                first_seen: entry.timestamp.clone(),
+                last_seen: None,
+                sample: entry.message.clone(),
+            });
+        issue.count += 1;
+        issue.last_seen = entry.timestamp.clone().or(issue.last_seen.take());
+    }
+    let mut issues: Vec<LogIssue> = issues.into_values().collect();
+    issues.sort_by(|a, b| b.count.cmp(&a.count).then(a.signature.cmp(&b.signature)));
+    issues
+}
+
+/// Gateway 日志文件：Manager 启动时的日志，以及 OpenClaw 按天滚动的日志
+pub fn log_files() -> Vec<PathBuf> {
+    let mut files = vec![PathBuf::from(platform::get_log_file_path())];
+    let daily_dir = std::env::temp_dir().join("openclaw");
+    let mut daily: Vec<PathBuf> = std::fs::read_dir(&daily_dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().map(|e| e == "log").unwrap_or(false))
+        .collect();
+    daily.sort();
+    files.extend(daily);
+    files.retain(|p| p.exists());
+    files
+}
+
+/// 读取并解析所有日志文件
+pub fn read_entries() -> (Vec<String>, Vec<LogEntry>) {
+    let files = log_files();
+    let entries = files
+        .iter()
+        .filter_map(|f| std::fs::read(f).ok())
+        .flat_map(|bytes| {
+            String::from_utf8_lossy(&bytes)
+                .lines()
+                .filter_map(parse_line)
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    let names = files
+        .iter()
+        .map(|f| f.to_string_lossy().to_string())
+        .collect();
+    (names, entries)
+}
+
+/// 日志时间（毫秒），无时间戳或无法解析时返回 None
+pub fn entry_millis(entry: &LogEntry) -> Option<i64> {
+    let ts = entry.timestamp.as_deref()?;
+    chrono::DateTime::parse_from_rfc3339(ts)
+        .map(|t| t.timestamp_millis())
+        .or_else(|_| {
+            chrono::NaiveDateTime::parse_from_str(ts, "%Y-%m-%d %H:%M:%S%.f")
+                .map(|t| t.and_utc().timestamp_millis())
+        })
+        .ok()
+}
+
+/// 日志时间是否在统计范围内（无法解析的时间保留）
+pub fn in_range(entry: &LogEntry, since_ms: i64) -> bool {
+    since_ms == 0 || entry_millis(entry).map(|ms| ms >= since_ms).unwrap_or(true)
+}
+
+/// 分析 Gateway 日志，汇总主要问题（range: 24h / 7d / 30d / all）
+#[guarded]
+#[command]
+pub async fn get_log_insights(range: Option<String>) -> Result<LogInsights, String> {
+    let range = range.unwrap_or_else(|| "24h".to_string());
+    let since = analytics::range_start(&range, chrono::Utc::now().timestamp_millis())?;
+    info!("[日志分析] 分析 Gateway 日志 ({})...", range);
+    let (files, entries) = tokio::task::spawn_blocking(read_entries)
+        .await
+        .map_err(|e| format!("读取日志失败: {}", e))?;
+    let entries: Vec<LogEntry> = entries.into_iter().filter(|e| in_range(e, since)).collect();
+    let errors = entries
+        .iter()
+        .filter(|e| e.level >= LogLevel::Error)
+        .count();
+    let warnings = entries.iter().filter(|e| e.level == LogLevel::Warn).count();
+    let mut issues = summarize(&entries);
+    issues.truncate(TOP_ISSUES);
+    info!(
+        "[日志分析] ✓ {} 条日志，{} 个错误，{} 类问题",
+        entries.len(),
+        errors,
+        issues.len()
+    );
+    Ok(LogInsights {
+        range,
+        files,
+        total_entries: entries.len(),
+        errors,
+        warnings,
+        issues,
+    })
+}
+
+/// 建议用户采取的操作（前端据此跳转到对应页面）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SuggestedAction {
+    OpenProviderSettings,
+    OpenChannelSettings,
+    OpenSkills,
+    RunNetworkDiagnostics,
+    ViewLogs,
+}
+
+/// 实时 Gateway 错误（gateway://error 事件）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GatewayErrorEvent {
+    pub category: IssueCategory,
+    pub module: Option<String>,
+    pub timestamp: Option<String>,
+    pub message: String,
+    /// 面向用户的原因说明
+    pub cause: String,
+    pub action: SuggestedAction,
+    /// 操作按钮文字
+    pub action_label: String,
+}
+
+/// 错误类型对应的原因说明和建议操作
+fn suggestion(category: IssueCategory) -> (&'static str, SuggestedAction, &'static str) {
+    match category {
+        IssueCategory::ProviderAuth => (
+            "AI Provider 的 API Key 无效或已过期",
+            SuggestedAction::OpenProviderSettings,
+            "打开 AI 配置",
+        ),
+        IssueCategory::ProviderQuota => (
+            "AI Provider 限流或额度不足",
+            SuggestedAction::OpenProviderSettings,
+            "检查 Provider 额度",
+        ),
+        IssueCategory::ChannelWebhook => (
+            "渠道接口拒绝了请求，Token 或 Webhook 配置可能有误",
+            SuggestedAction::OpenChannelSettings,
+            "打开渠道配置",
+        ),
+        IssueCategory::SkillCrash => ("技能执行时崩溃", SuggestedAction::OpenSkills, "查看技能"),
+        IssueCategory::Network => (
+            "Gateway 无法连接外部服务",
+            SuggestedAction::RunNetworkDiagnostics,
+            "运行网络诊断",
+        ),
+        IssueCategory::Other => ("Gateway 报告了错误", SuggestedAction::ViewLogs, "查看日志"),
+    }
+}
+
+/// 是否需要立即提醒：已识别原因的错误，或未识别的致命错误
+fn is_alert(entry: &LogEntry) -> Option<IssueCategory> {
+    let category = classify(entry)?;
+    let severe = match category {
+        IssueCategory::Other => entry.level >= LogLevel::Fatal,
+        _ => entry.level >= LogLevel::Error,
+    };
+    severe.then_some(category)
+}
+
+/// 跟随日志文件，记录每个文件已读取的位置
+struct LogFollower {
+    offsets: HashMap<PathBuf, u64>,
+    /// 已提醒过的错误（类别 + 签名）及时间
+    alerted: HashMap<(IssueCategory, String), Instant>,
+}
+
+impl LogFollower {
+    /// 启动时从现有文件末尾开始，只关注新写入的日志
+    fn new() -> Self {
+        let offsets = log_files()
+            .into_iter()
+            .filter_map(|p| std::fs::metadata(&p).ok().map(|m| (p, m.len())))
+            .collect();
+        Self {
+            offsets,
+            alerted: HashMap::new(),
+        }
+    }
+
+    /// 读取各文件新增的完整行（新出现的文件从头读取，被截断的文件重新开始）
+    fn poll(&mut self) -> Vec<String> {
+        let mut lines = Vec::new();
+        for path in log_files() {
+            let Ok(mut f) = std::fs::File::open(&path) else {
+                continue;
+            };
+            let len = f.metadata().map(|m| m.len()).unwrap_or(0);
+            let offset = self.offsets.entry(path).or_insert(0);
+            if len < *offset {
+                *offset = 0;
+            }
+            if len == *offset || f.seek(SeekFrom::Start(*offset)).is_err() {
+                continue;
+            }
+            let mut buf = Vec::new();
+            if f.read_to_end(&mut buf).is_err() {
+                continue;
+            }
+            // 未写完的最后一行留到下次读取
+            let Some(end) = buf.iter().rposition(|b| *b == b'\n') else {
+                continue;
+            };
+            *offset += end as u64 + 1;
+            lines.extend(
+                String::from_utf8_lossy(&buf[..end])
+                    .lines()
+                    .map(|l| l.to_string()),
+            );
+        }
+        lines
+    }
+
+    /// 从新日志中挑出需要提醒的错误（同类错误冷却期内不重复提醒）
+    fn alerts(&mut self, lines: &[String], now: Instant) -> Vec<GatewayErrorEvent> {
+        self.alerted
+            .retain(|_, t| now.duration_since(*t) < ERROR_COOLDOWN);
+        let mut events = Vec::new();
+        for entry in lines.iter().filter_map(|l| parse_line(l)) {
+            let Some(category) = is_alert(&entry) else {
+                continue;
+            };
+            let key = (category, signature(&entry.message));
+            if self.alerted.contains_key(&key) {
+                continue;
+            }
+            self.alerted.insert(key, now);
+            let (cause, action, label) = suggestion(category);
+            events.push(GatewayErrorEvent {
+                category,
+                module: entry.module,
+                timestamp: entry.timestamp,
+                message: entry.message,
+                cause: cause.to_string(),
+                action,
+                action_label: label.to_string(),
+            });
+        }
+        events
+    }
+}
+
+/// 启动日志跟随：Gateway 出现严重错误时发送 gateway://error 事件
+pub fn start(app: AppHandle) {
+    std::thread::spawn(move || {
+        let mut follower = LogFollower::new();
+        info!("[日志跟随] 开始跟随 Gateway 日志");
+        loop {
+            std::thread::sleep(FOLLOW_INTERVAL);
+            let lines = follower.poll();
+            if lines.is_empty() {
+                continue;
+            }
+            consoles::forward_log_lines(&app, &lines);
+            for event in follower.alerts(&lines, Instant::now()) {
+                info!("[日志跟随] ✗ {}: {}", event.cause, event.message);
+                if let Err(e) = events::emit(&app, GATEWAY_ERROR_EVENT, event) {
+                    debug!("[日志跟随] 发送事件失败: {}", e);
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_and_json_lines_are_parsed() {
+        let text = parse_line(
+            "2026-03-01T10:00:00.123Z [telegram] error: sendMessage failed: 403 Forbidden reqId=abc-123",
+        )
+        .unwrap();
+        assert_eq!(text.level, LogLevel::Error);
+        assert_eq!(text.module.as_deref(), Some("telegram"));
+        assert_eq!(text.request_id.as_deref(), Some("abc-123"));
+        assert_eq!(classify(&text), Some(IssueCategory::ChannelWebhook));
+
+        let json = parse_line(
+            r#"{"0":"Anthropic request failed: 401 invalid x-api-key","_meta":{"logLevelName":"ERROR","name":"{\"subsystem\":\"agent\"}"},"time":"2026-03-01T10:00:01Z","runId":"run-9"}"#,
+        )
+        .unwrap();
+        assert_eq!(json.module.as_deref(), Some("agent"));
+        assert_eq!(json.request_id.as_deref(), Some("run-9"));
+        assert_eq!(classify(&json), Some(IssueCategory::ProviderAuth));
+
+        let info = parse_line("2026-03-01 10:00:02 INFO [gateway] listening on 18789").unwrap();
+        assert_eq!(info.level, LogLevel::Info);
+        assert_eq!(classify(&info), None);
+    }
+
+    #[test]
+    fn recurring_errors_are_grouped() {
+        let lines = [
+            "2026-03-01T10:00:00Z [skills] error: skill weather crashed: exit code 1",
+            "2026-03-01T10:05:00Z [skills] error: skill weather crashed: exit code 137",
+            "2026-03-01T10:06:00Z [gateway] warn: fetch failed: ECONNREFUSED 127.0.0.1:11434",
+        ];
+        let entries: Vec<LogEntry> = lines.iter().filter_map(|l| parse_line(l)).collect();
+        let issues = summarize(&entries);
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].category, IssueCategory::SkillCrash);
+        assert_eq!(issues[0].count, 2);
+        assert_eq!(issues[0].last_seen.as_deref(), Some("2026-03-01T10:05:00Z"));
+        assert_eq!(issues[1].category, IssueCategory::Network);
+    }
+
+    #[test]
+    fn alerts_carry_suggestion_and_respect_cooldown() {
+        let mut follower = LogFollower {
+            offsets: HashMap::new(),
+            alerted: HashMap::new(),
+        };
+        let lines = vec![
+            "2026-03-01T10:00:00Z [agent] error: OpenAI request failed: 401 Incorrect API key provided".to_string(),
+            "2026-03-01T10:00:01Z [agent] error: OpenAI request failed: 401 Incorrect API key provided".to_string(),
+            "2026-03-01T10:00:02Z [gateway] warn: fetch failed: ECONNRESET".to_string(),
+            "2026-03-01T10:00:03Z [gateway] error: something odd happened".to_string(),
+        ];
+        let now = Instant::now();
+        let events = follower.alerts(&lines, now);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].category, IssueCategory::ProviderAuth);
+        assert_eq!(events[0].action, SuggestedAction::OpenProviderSettings);
+
+        assert!(follower.alerts(&lines[..1], now).is_empty());
+        assert_eq!(follower.alerts(&lines[..1], now + ERROR_COOLDOWN).len(), 1);
+    }
+}
@@ -0,0 +1,236 @@
+use crate::commands::jobs::{self, JobKind};
+use crate::commands::{settings, updates};
+use crate::utils::time;
+use chrono::{Duration, NaiveDateTime, NaiveTime};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use openclaw_macros::guarded;
+use tauri::command;
+
+/// 维护时间窗口（设置中时区的当地时间，结束早于开始时表示跨越午夜，如 23:00–02:00）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MaintenanceWindow {
+    /// HH:MM
+    pub start: String,
+    /// HH:MM
+    pub end: String,
+}
+
+/// 维护设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MaintenanceSettings {
+    /// 发现新版本后自动下载，并在维护窗口内安装
+    pub auto_update: bool,
+    /// 允许自动更新和重启 Gateway 的时间窗口（为空表示任何时间）
+    pub windows: Vec<MaintenanceWindow>,
+}
+
+impl Default for MaintenanceSettings {
+    fn default() -> Self {
+        Self {
+            auto_update: false,
+            windows: vec![MaintenanceWindow {
+                start: "03:00".to_string(),
+                end: "05:00".to_string(),
+            }],
+        }
+    }
+}
+
+/// 等待维护窗口执行的操作
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum MaintenanceActionKind {
+    /// 安装已下载的 OpenClaw 更新
+    Update { version: String },
+    /// 重启 Gateway
+    RestartGateway { reason: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaintenanceAction {
+    #[serde(flatten)]
+    pub kind: MaintenanceActionKind,
+    pub queued_at: String,
+}
+
+/// 待执行的维护操作
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingMaintenance {
+    pub actions: Vec<MaintenanceAction>,
+    /// 当前是否处于维护窗口
+    pub in_window: bool,
+    /// 下一个维护窗口开始时间（当前处于窗口内时为 None）
+    pub next_window: Option<String>,
+}
+
+fn parse_time(value: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M").ok()
+}
+
+/// 某个时刻是否处于窗口内
+fn window_contains(window: &MaintenanceWindow, time: NaiveTime) -> bool {
+    let (Some(start), Some(end)) = (parse_time(&window.start), parse_time(&window.end)) else {
+        return false;
+    };
+    if start <= end {
+        time >= start && time < end
+    } else {
+        time >= start || time < end
+    }
+}
+
+/// 是否允许执行维护操作
+fn in_window(windows: &[MaintenanceWindow], now: NaiveDateTime) -> bool {
+    windows.is_empty() || windows.iter().any(|w| window_contains(w, now.time()))
+}
+
+/// 下一个维护窗口的开始时间
+fn next_window_start(windows: &[MaintenanceWindow], now: NaiveDateTime) -> Option<NaiveDateTime> {
+    windows
+        .iter()
+        .filter_map(|w| parse_time(&w.start))
+        .map(|start| {
+            let today = now.date().and_time(start);
+            if today > now {
+                today
+            } else {
+                today + Duration::days(1)
+            }
+        })
+        .min()
+}
+
+/// 加入任务队列，在维护窗口内执行（同类操作只保留最新的一个）
+fn enqueue(kind: MaintenanceActionKind) -> Result<(), String> {
+    let job = match kind {
+        MaintenanceActionKind::Update { version } => JobKind::ApplyUpdate { version },
+        MaintenanceActionKind::RestartGateway { reason } => JobKind::RestartGateway { reason },
+    };
+    jobs::enqueue(job, true).map(|_| ())
+}
+
+/// 当前是否允许执行维护操作
+pub fn window_open() -> bool {
+    in_window(
+        &settings::load_settings().maintenance.windows,
+        time::now().naive_local(),
+    )
+}
+
+/// 发现新版本时调用（后台监控）：开启自动更新时先下载，安装排入维护窗口
+pub async fn on_update_available(version: &str) {
+    if !settings::load_settings().maintenance.auto_update {
+        return;
+    }
+    match updates::stage().await {
+        Ok(Some(staged)) => {
+            let queued = enqueue(MaintenanceActionKind::Update {
+                version: staged.version,
+            });
+            if let Err(e) = queued {
+                warn!("[维护窗口] {}", e);
+            }
+        }
+        Ok(None) => {}
+        Err(e) => warn!("[维护窗口] 下载更新 {} 失败: {}", version, e),
+    }
+}
+
+/// 获取等待维护窗口执行的操作
+#[guarded]
+#[command]
+pub async fn get_pending_maintenance_actions() -> Result<PendingMaintenance, String> {
+    let windows = settings::load_settings().maintenance.windows;
+    let tz = time::user_timezone();
+    let now = time::in_zone(tz, chrono::Utc::now()).naive_local();
+    let in_window = in_window(&windows, now);
+    let next_window = if in_window {
+        None
+    } else {
+        next_window_start(&windows, now)
+            .and_then(|t| time::localize(tz, t))
+            .map(|t| t.to_rfc3339())
+    };
+    let actions = jobs::queued_maintenance_jobs()
+        .into_iter()
+        .filter_map(|job| {
+            let kind = match job.kind {
+                JobKind::ApplyUpdate { version } => MaintenanceActionKind::Update { version },
+                JobKind::RestartGateway { reason } => {
+                    MaintenanceActionKind::RestartGateway { reason }
+                }
+                _ => return None,
+            };
+            Some(MaintenanceAction {
+                kind,
+                queued_at: job.created_at,
+            })
+        })
+        .collect();
+    Ok(PendingMaintenance {
+        actions,
+        in_window,
+        next_window,
+    })
+}
+
+/// 在下一个维护窗口重启 Gateway（如修改了需要重启才生效的配置）
+#[guarded]
+#[command]
+pub async fn schedule_gateway_restart(reason: Option<String>) -> Result<(), String> {
+    enqueue(MaintenanceActionKind::RestartGateway {
+        reason: reason.unwrap_or_else(|| "手动安排".to_string()),
+    })
+}
+
+/// 取消所有排队的维护操作
+#[guarded]
+#[command]
+pub async fn clear_maintenance_actions() -> Result<(), String> {
+    let cancelled = jobs::cancel_maintenance_jobs()?;
+    info!("[维护窗口] 已取消 {} 个排队的操作", cancelled);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn window(start: &str, end: &str) -> MaintenanceWindow {
+        MaintenanceWindow {
+            start: start.to_string(),
+            end: end.to_string(),
+        }
+    }
+
+    fn at(h: u32, m: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 3, 1)
+            .unwrap()
+            .and_hms_opt(h, m, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn windows_wrap_midnight_and_next_start_is_found() {
+        let night = [window("03:00", "05:00")];
+        assert!(in_window(&night, at(3, 30)));
+        assert!(!in_window(&night, at(5, 0)));
+        assert!(in_window(&[], at(12, 0)));
+
+        let overnight = [window("23:00", "02:00")];
+        assert!(in_window(&overnight, at(23, 30)));
+        assert!(in_window(&overnight, at(1, 59)));
+        assert!(!in_window(&overnight, at(12, 0)));
+
+        let both = [window("03:00", "05:00"), window("13:00", "14:00")];
+        assert_eq!(next_window_start(&both, at(12, 0)), Some(at(13, 0)));
+        assert_eq!(
+            next_window_start(&both, at(15, 0)),
+            Some(at(3, 0) + Duration::days(1))
+        );
+        assert_eq!(next_window_start(&[window("bad", "05:00")], at(1, 0)), None);
+    }
+}
@@ -0,0 +1,365 @@
+//! Agent 对话记忆管理：上下文裁剪、压缩（摘要）阈值、长期记忆开关，
+//! 以及按范围清除会话历史 / 长期记忆文件
+use crate::commands::{config, policy, service, workspace};
+use crate::utils::{audit, platform};
+use log::info;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::path::{Path, PathBuf};
+use openclaw_macros::guarded;
+use tauri::command;
+
+const COMPACTION_MODES: &[&str] = &["default", "safeguard"];
+const PRUNING_MODES: &[&str] = &["off", "cache-ttl"];
+
+/// 记忆设置（字段为空表示使用 OpenClaw 默认值）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MemorySettings {
+    /// 上下文窗口上限（tokens）
+    #[serde(default)]
+    pub context_tokens: Option<u64>,
+    /// 上下文裁剪策略（off / cache-ttl）
+    #[serde(default)]
+    pub context_pruning: Option<String>,
+    /// 压缩（摘要）模式（default / safeguard）
+    #[serde(default)]
+    pub compaction_mode: Option<String>,
+    /// 压缩后至少保留给新对话的 tokens
+    #[serde(default)]
+    pub reserve_tokens_floor: Option<u64>,
+    /// 压缩前是否先把要点写入长期记忆
+    #[serde(default)]
+    pub memory_flush: Option<bool>,
+    /// 距离压缩还剩多少 tokens 时写入长期记忆
+    #[serde(default)]
+    pub memory_flush_threshold: Option<u64>,
+    /// 是否启用长期记忆检索
+    #[serde(default)]
+    pub persistent_memory: Option<bool>,
+}
+
+/// 设置字段与配置路径（相对 agents.defaults 或 agents.list[] 中的 Agent）
+const FIELDS: &[(&str, &str)] = &[
+    ("context_tokens", "contextTokens"),
+    ("context_pruning", "contextPruning/mode"),
+    ("compaction_mode", "compaction/mode"),
+    ("reserve_tokens_floor", "compaction/reserveTokensFloor"),
+    ("memory_flush", "compaction/memoryFlush/enabled"),
+    (
+        "memory_flush_threshold",
+        "compaction/memoryFlush/softThresholdTokens",
+    ),
+    ("persistent_memory", "memorySearch/enabled"),
+];
+
+/// 文件统计
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MemoryFiles {
+    pub files: u64,
+    pub bytes: u64,
+}
+
+/// Agent 记忆概况
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentMemory {
+    pub agent: String,
+    /// 生效的设置（Agent 覆盖值优先于默认值）
+    pub settings: MemorySettings,
+    /// Agent 单独覆盖的字段
+    pub overridden: Vec<String>,
+    /// 会话历史（agents/<agent>/sessions）
+    pub sessions: MemoryFiles,
+    /// 长期记忆（工作区中的 MEMORY.md 和 memory/）
+    pub persistent: MemoryFiles,
+}
+
+/// 清除范围
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MemoryScope {
+    Sessions,
+    Persistent,
+    All,
+}
+
+/// Agent 在 agents.list 中的位置
+pub fn agent_index(cfg: &Value, agent: &str) -> Option<usize> {
+    cfg.pointer("/agents/list")?
+        .as_array()?
+        .iter()
+        .position(|a| a.get("id").and_then(Value::as_str) == Some(agent))
+}
+
+/// 读取生效的设置和 Agent 单独覆盖的字段
+fn read_settings(cfg: &Value, agent: &str) -> Result<(MemorySettings, Vec<String>), String> {
+    let scoped = agent_index(cfg, agent).and_then(|i| cfg.pointer(&format!("/agents/list/{}", i)));
+    let defaults = cfg.pointer("/agents/defaults");
+    let mut merged = Map::new();
+    let mut overridden = Vec::new();
+    for (field, path) in FIELDS {
+        let pointer = format!("/{}", path);
+        if let Some(value) = scoped.and_then(|s| s.pointer(&pointer)) {
+            merged.insert(field.to_string(), value.clone());
+            overridden.push(field.to_string());
+        } else if let Some(value) = defaults.and_then(|d| d.pointer(&pointer)) {
+            merged.insert(field.to_string(), value.clone());
+        }
+    }
+    let settings = serde_json::from_value(Value::Object(merged))
+        .map_err(|e| format!("openclaw.json 中的记忆设置格式无效: {}", e))?;
+    Ok((settings, overridden))
+}
+
+fn validate(settings: &MemorySettings) -> Result<(), String> {
+    if let Some(mode) = settings.context_pruning.as_deref() {
+        if !PRUNING_MODES.contains(&mode) {
+            return Err(format!("未知的上下文裁剪策略: {}", mode));
+        }
+    }
+    if let Some(mode) = settings.compaction_mode.as_deref() {
+        if !COMPACTION_MODES.contains(&mode) {
+            return Err(format!("未知的压缩模式: {}", mode));
+        }
+    }
+    if settings.context_tokens.is_some_and(|t| t < 1000) {
+        return Err("上下文窗口至少为 1000 tokens".to_string());
+    }
+    if let (Some(context), Some(floor)) = (settings.context_tokens, settings.reserve_tokens_floor) {
+        if floor >= context {
+            return Err("压缩保留的 tokens 必须小于上下文窗口".to_string());
+        }
+    }
+    if settings.memory_flush_threshold.is_some() && settings.memory_flush == Some(false) {
+        return Err("关闭压缩前写入长期记忆时不需要设置阈值".to_string());
+    }
+    Ok(())
+}
+
+/// 写入路径上的值，值为空时删除并清理变空的父对象
+fn write_path(target: &mut Value, path: &str, value: Option<Value>) {
+    if !target.is_object() {
+        return;
+    }
+    let Some((key, rest)) = path.split_once('/') else {
+        if let Some(map) = target.as_object_mut() {
+            match value {
+                Some(value) => map.insert(path.to_string(), value),
+                None => map.remove(path),
+            };
+        }
+        return;
+    };
+    if value.is_some() && !target.get(key).is_some_and(Value::is_object) {
+        target[key] = json!({});
+    }
+    let Some(child) = target.get_mut(key) else {
+        return;
+    };
+    write_path(child, rest, value);
+    if child.as_object().is_some_and(Map::is_empty) {
+        if let Some(map) = target.as_object_mut() {
+            map.remove(key);
+        }
+    }
+}
+
+/// 写入设置：指定了 agents.list 中的 Agent 时写入该 Agent，否则写入 agents.defaults
+fn apply_settings(cfg: &mut Value, agent: &str, settings: &MemorySettings) -> Result<(), String> {
+    let (target, prefix) = match agent_index(cfg, agent) {
+        Some(i) => (cfg.pointer_mut(&format!("/agents/list/{}", i)), ""),
+        None if agent == "main" => (Some(cfg), "agents/defaults/"),
+        None => return Err(format!("Agent {} 不在 agents.list 中", agent)),
+    };
+    let target = target.ok_or_else(|| format!("Agent {} 的配置格式无效", agent))?;
+    let values = serde_json::to_value(settings).map_err(|e| e.to_string())?;
+    for (field, path) in FIELDS {
+        let value = values.get(*field).filter(|v| !v.is_null()).cloned();
+        write_path(target, &format!("{}{}", prefix, path), value);
+    }
+    Ok(())
+}
+
+/// 统计目录中符合条件的文件
+fn collect_files(dir: &Path, filter: &dyn Fn(&Path) -> bool, out: &mut Vec<(PathBuf, u64)>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Ok(meta) = entry.metadata() else {
+            continue;
+        };
+        if meta.is_dir() {
+            collect_files(&path, filter, out);
+        } else if filter(&path) {
+            out.push((path, meta.len()));
+        }
+    }
+}
+
+/// 会话历史文件：~/.openclaw/agents/<agent>/sessions 下的 *.jsonl 和 sessions.json
+fn session_files(config_dir: &Path, agent: &str) -> Vec<(PathBuf, u64)> {
+    let mut files = Vec::new();
+    let is_session = |p: &Path| {
+        p.extension().is_some_and(|e| e == "jsonl")
+            || p.file_name().is_some_and(|n| n == "sessions.json")
+    };
+    collect_files(
+        &config_dir.join("agents").join(agent).join("sessions"),
+        &is_session,
+        &mut files,
+    );
+    files
+}
+
+/// 长期记忆文件：工作区根目录的 MEMORY.md / memory.md 和 memory/ 目录
+fn persistent_files(workspace: &Path) -> Vec<(PathBuf, u64)> {
+    let mut files = Vec::new();
+    for name in ["MEMORY.md", "memory.md"] {
+        if let Ok(meta) = std::fs::metadata(workspace.join(name)) {
+            if meta.is_file() {
+                files.push((workspace.join(name), meta.len()));
+            }
+        }
+    }
+    collect_files(&workspace.join("memory"), &|_| true, &mut files);
+    files
+}
+
+fn summarize(files: &[(PathBuf, u64)]) -> MemoryFiles {
+    MemoryFiles {
+        files: files.len() as u64,
+        bytes: files.iter().map(|(_, size)| size).sum(),
+    }
+}
+
+fn agent_workspace(cfg: &Value, config_dir: &Path, agent: &str) -> PathBuf {
+    workspace::resolve_workspace(cfg, config_dir, agent)
+}
+
+/// 查看 Agent 的记忆设置和占用
+#[guarded]
+#[command]
+pub async fn get_agent_memory(agent: Option<String>) -> Result<AgentMemory, String> {
+    let agent = workspace::validate_agent(agent.as_deref().unwrap_or_default())?.to_string();
+    let cfg = config::load_openclaw_config()?;
+    let (settings, overridden) = read_settings(&cfg, &agent)?;
+    let config_dir = PathBuf::from(platform::get_config_dir());
+    Ok(AgentMemory {
+        sessions: summarize(&session_files(&config_dir, &agent)),
+        persistent: summarize(&persistent_files(&agent_workspace(
+            &cfg,
+            &config_dir,
+            &agent,
+        ))),
+        agent,
+        settings,
+        overridden,
+    })
+}
+
+/// 保存记忆设置（`agent` 为空时修改所有 Agent 的默认值）
+#[guarded]
+#[command]
+pub async fn save_memory_settings(
+    agent: Option<String>,
+    settings: MemorySettings,
+) -> Result<String, String> {
+    validate(&settings)?;
+    let agent = workspace::validate_agent(agent.as_deref().unwrap_or_default())?.to_string();
+    let mut cfg = config::load_openclaw_config()?;
+    apply_settings(&mut cfg, &agent, &settings)?;
+    config::save_config(cfg).await?;
+    audit::record(
+        "save_memory_settings",
+        &agent,
+        true,
+        serde_json::to_value(&settings).unwrap_or_default(),
+    );
+    info!("[记忆管理] ✓ 已保存 {} 的记忆设置", agent);
+    Ok("记忆设置已保存".to_string())
+}
+
+/// 清除 Agent 的会话历史和/或长期记忆（危险操作，需要确认令牌）
+/// 清除会话历史前需要停止 Gateway，避免正在写入的会话文件被删除
+#[guarded]
+#[command]
+pub async fn clear_agent_memory(
+    agent: Option<String>,
+    scope: MemoryScope,
+    confirm_token: Option<String>,
+) -> Result<MemoryFiles, String> {
+    policy::require_confirmation("clear_agent_memory", confirm_token.as_deref())?;
+    let agent = workspace::validate_agent(agent.as_deref().unwrap_or_default())?.to_string();
+    let cfg = config::load_openclaw_config()?;
+    let config_dir = PathBuf::from(platform::get_config_dir());
+
+    let mut files = Vec::new();
+    if matches!(scope, MemoryScope::Sessions | MemoryScope::All) {
+        if service::gateway_pid().is_some() {
+            return Err("清除会话历史前请先停止 Gateway".to_string());
+        }
+        files.extend(session_files(&config_dir, &agent));
+    }
+    if matches!(scope, MemoryScope::Persistent | MemoryScope::All) {
+        files.extend(persistent_files(&agent_workspace(&cfg, &config_dir, &agent)));
+    }
+
+    let mut removed = Vec::new();
+    let mut failed = Vec::new();
+    for (path, size) in files {
+        match std::fs::remove_file(&path) {
+            Ok(()) => removed.push((path, size)),
+            Err(e) => failed.push(format!("{}: {}", path.display(), e)),
+        }
+    }
+    let result = summarize(&removed);
+    audit::record(
+        "clear_agent_memory",
+        &agent,
+        failed.is_empty(),
+        json!({ "scope": scope, "files": result.files, "bytes": result.bytes, "failed": failed }),
+    );
+    if !failed.is_empty() {
+        return Err(format!("部分文件删除失败: {}", failed.join("；")));
+    }
+    info!(
+        "[记忆管理] ✓ 已清除 {} 的记忆 ({:?})：{} 个文件",
+        agent, scope, result.files
+    );
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_overrides_and_writes_memory_settings() {
+        let mut cfg = json!({ "agents": {
+            "defaults": {
+                "workspace": "~/work",
+                "compaction": { "mode": "safeguard", "memoryFlush": { "enabled": true } }
+            },
+            "list": [{ "id": "ops", "memorySearch": { "enabled": false } }]
+        }});
+        let (settings, overridden) = read_settings(&cfg, "ops").unwrap();
+        assert_eq!(settings.compaction_mode.as_deref(), Some("safeguard"));
+        assert_eq!(settings.persistent_memory, Some(false));
+        assert_eq!(overridden, vec!["persistent_memory"]);
+
+        let mut defaults = read_settings(&cfg, "main").unwrap().0;
+        defaults.memory_flush = None;
+        defaults.context_tokens = Some(64_000);
+        apply_settings(&mut cfg, "main", &defaults).unwrap();
+        let written = &cfg["agents"]["defaults"];
+        assert_eq!(written["contextTokens"], 64_000);
+        assert_eq!(written["workspace"], "~/work");
+        // 清空的字段连同变空的父对象一起删除
+        assert!(written["compaction"].get("memoryFlush").is_none());
+        assert!(apply_settings(&mut cfg, "ghost", &defaults).is_err());
+
+        defaults.reserve_tokens_floor = Some(64_000);
+        assert!(validate(&defaults).is_err());
+    }
+}
@@ -0,0 +1,313 @@
+use crate::commands::settings::{self, SETTINGS_VERSION};
+use crate::utils::{file, platform, time};
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+use openclaw_macros::guarded;
+use tauri::command;
+
+/// 单个迁移步骤：把设置从 `version - 1` 升级到 `version`
+struct Migration {
+    version: u32,
+    description: &'static str,
+    apply: fn(&mut Value, &mut MigrationContext) -> Result<(), String>,
+}
+
+/// 按版本顺序排列的迁移列表，新增迁移时追加到末尾并同步提升 SETTINGS_VERSION
+/// 首个发布版本的设置即为 v1，迁移从 v2 开始
+const MIGRATIONS: &[Migration] = &[];
+
+/// 迁移执行上下文
+/// 文件移动在设置写入成功后才执行，避免迁移中途失败导致旧文件丢失
+struct MigrationContext {
+    data_dir: PathBuf,
+    pending_moves: Vec<(PathBuf, PathBuf)>,
+}
+
+impl MigrationContext {
+    fn new(data_dir: &Path) -> Self {
+        Self {
+            data_dir: data_dir.to_path_buf(),
+            pending_moves: Vec::new(),
+        }
+    }
+
+    /// 登记一次文件移动（相对 Manager 数据目录）
+    #[allow(dead_code)]
+    fn move_file(&mut self, from: &str, to: &str) {
+        self.pending_moves
+            .push((self.data_dir.join(from), self.data_dir.join(to)));
+    }
+
+    /// 执行登记的文件移动（失败只记录警告，设置已经迁移完成）
+    fn finish(self) {
+        for (from, to) in self.pending_moves {
+            if !from.exists() {
+                continue;
+            }
+            if let Some(parent) = to.parent() {
+                let _ = std::fs::create_dir_all(parent);
+            }
+            match std::fs::rename(&from, &to) {
+                Ok(_) => info!("[迁移] 已移动 {:?} -> {:?}", from, to),
+                Err(e) => warn!("[迁移] 移动 {:?} 失败: {}", from, e),
+            }
+        }
+    }
+}
+
+/// 迁移历史记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MigrationRecord {
+    pub from_version: u32,
+    pub to_version: u32,
+    pub description: String,
+    pub manager_version: String,
+    pub applied_at: String,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+/// 迁移状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MigrationStatus {
+    /// settings.json 中记录的版本
+    pub current_version: u32,
+    /// 当前 Manager 支持的版本
+    pub target_version: u32,
+    /// 迁移历史（按时间顺序）
+    pub history: Vec<MigrationRecord>,
+}
+
+fn history_path(data_dir: &Path) -> PathBuf {
+    data_dir.join("migration_history.json")
+}
+
+fn load_history(data_dir: &Path) -> Vec<MigrationRecord> {
+    file::read_file(&history_path(data_dir).to_string_lossy())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn append_history(data_dir: &Path, record: MigrationRecord) {
+    let mut history = load_history(data_dir);
+    history.push(record);
+    let result = serde_json::to_string_pretty(&history)
+        .map_err(|e| e.to_string())
+        .and_then(|content| {
+            file::write_file(&history_path(data_dir).to_string_lossy(), &content)
+                .map_err(|e| e.to_string())
+        });
+    if let Err(e) = result {
+        warn!("[迁移] 写入迁移历史失败: {}", e);
+    }
+}
+
+fn stored_version(raw: &Value) -> u32 {
+    raw.get("version").and_then(|v| v.as_u64()).unwrap_or(0) as u32
+}
+
+/// 启动时执行设置迁移
+pub fn run_startup_migrations() {
+    let data_dir = platform::get_manager_data_dir();
+    match run_migrations(Path::new(&data_dir), MIGRATIONS, SETTINGS_VERSION) {
+        Ok(version) => info!("[迁移] 设置版本: {}", version),
+        Err(e) => error!("[迁移] ✗ 设置迁移失败，已保留原设置文件: {}", e),
+    }
+}
+
+/// 将数据目录中的设置迁移到 `target` 版本，返回迁移后的版本号
+/// 还没有 settings.json 时无需迁移（首次写入时即为当前版本）
+fn run_migrations(data_dir: &Path, migrations: &[Migration], target: u32) -> Result<u32, String> {
+    let path = settings::settings_path(data_dir);
+    let Ok(content) = file::read_file(&path.to_string_lossy()) else {
+        return Ok(target);
+    };
+    let mut raw = serde_json::from_str::<Value>(&content)
+        .map_err(|e| format!("settings.json 不是有效的 JSON: {}", e))?;
+
+    let mut version = stored_version(&raw);
+    if version > target {
+        warn!(
+            "[迁移] settings.json 版本 {} 高于当前支持的 {}（可能使用过更新的 Manager），跳过迁移",
+            version, target
+        );
+        return Ok(version);
+    }
+    if version == target {
+        return Ok(version);
+    }
+
+    // 迁移前备份原设置
+    let backup = data_dir.join("backups").join(format!("settings.v{}.json", version));
+    if let Some(parent) = backup.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    file::copy_atomic(&path, &backup).map_err(|e| format!("备份设置失败: {}", e))?;
+    info!("[迁移] 已备份设置到 {:?}", backup);
+
+    let start_version = version;
+    for migration in migrations
+        .iter()
+        .filter(|m| m.version > start_version && m.version <= target)
+    {
+        info!("[迁移] v{} -> v{}: {}", version, migration.version, migration.description);
+        let mut ctx = MigrationContext::new(data_dir);
+        let mut next = raw.clone();
+        let result = (migration.apply)(&mut next, &mut ctx).and_then(|_| {
+            next["version"] = json!(migration.version);
+            let content = serde_json::to_string_pretty(&next)
+                .map_err(|e| format!("序列化设置失败: {}", e))?;
+            file::write_file(&path.to_string_lossy(), &content)
+                .map_err(|e| format!("写入设置失败: {}", e))
+        });
+
+        append_history(
+            data_dir,
+            MigrationRecord {
+                from_version: version,
+                to_version: migration.version,
+                description: migration.description.to_string(),
+                manager_version: env!("CARGO_PKG_VERSION").to_string(),
+                applied_at: time::now_rfc3339(),
+                success: result.is_ok(),
+                error: result.as_ref().err().cloned(),
+            },
+        );
+
+        if let Err(e) = result {
+            return Err(format!("v{} -> v{}: {}", version, migration.version, e));
+        }
+        ctx.finish();
+        info!("[迁移] ✓ 已迁移到 v{}", migration.version);
+        raw = next;
+        version = migration.version;
+    }
+
+    Ok(version)
+}
+
+/// 获取设置迁移状态和历史
+#[guarded]
+#[command]
+pub async fn get_migration_status() -> Result<MigrationStatus, String> {
+    let data_dir = platform::get_manager_data_dir();
+    let data_dir = Path::new(&data_dir);
+    let current_version = file::read_file(&settings::settings_path(data_dir).to_string_lossy())
+        .ok()
+        .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+        .map(|raw| stored_version(&raw))
+        .unwrap_or(SETTINGS_VERSION);
+    Ok(MigrationStatus {
+        current_version,
+        target_version: SETTINGS_VERSION,
+        history: load_history(data_dir),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_temp_dir(prefix: &str) -> PathBuf {
+        let mut base = std::env::temp_dir();
+        let suffix = format!(
+            "{}_{}",
+            prefix,
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_nanos()
+        );
+        base.push(suffix);
+        std::fs::create_dir_all(&base).unwrap();
+        base
+    }
+
+    #[test]
+    fn migrations_are_ordered_and_reach_current_version() {
+        // v1 为首个发布版本的设置，迁移从 v2 开始依次递增
+        let mut version = 1;
+        for migration in MIGRATIONS {
+            version += 1;
+            assert_eq!(migration.version, version);
+        }
+        assert_eq!(version, SETTINGS_VERSION);
+    }
+
+    /// 测试用迁移：把 lang 改名为 locale，并把旧文件移到 legacy 目录
+    fn rename_lang(raw: &mut Value, ctx: &mut MigrationContext) -> Result<(), String> {
+        if let Some(lang) = raw.as_object_mut().and_then(|obj| obj.remove("lang")) {
+            raw["locale"] = lang;
+        }
+        ctx.move_file("old.json", "legacy/old.json");
+        Ok(())
+    }
+
+    fn broken(_raw: &mut Value, ctx: &mut MigrationContext) -> Result<(), String> {
+        ctx.move_file("old.json", "legacy/old.json");
+        Err("boom".to_string())
+    }
+
+    #[test]
+    fn migrations_are_applied_backed_up_and_recorded() {
+        let migrations = [Migration {
+            version: 2,
+            description: "lang -> locale",
+            apply: rename_lang,
+        }];
+        let dir = make_temp_dir("openclaw_settings_migrate");
+        std::fs::write(dir.join("settings.json"), r#"{"version": 1, "lang": "en"}"#).unwrap();
+        std::fs::write(dir.join("old.json"), "{}").unwrap();
+
+        assert_eq!(run_migrations(&dir, &migrations, 2).unwrap(), 2);
+        let raw: Value =
+            serde_json::from_str(&std::fs::read_to_string(dir.join("settings.json")).unwrap())
+                .unwrap();
+        assert_eq!(raw, json!({"version": 2, "locale": "en"}));
+        assert!(dir.join("backups").join("settings.v1.json").exists());
+        assert!(!dir.join("old.json").exists());
+        assert!(dir.join("legacy").join("old.json").exists());
+        let history = load_history(&dir);
+        assert_eq!(history.len(), 1);
+        assert!(history[0].success);
+
+        // 再次运行不会重复迁移
+        assert_eq!(run_migrations(&dir, &migrations, 2).unwrap(), 2);
+        assert_eq!(load_history(&dir).len(), 1);
+
+        // 失败时保留原设置和文件，并记录失败
+        let migrations = [Migration {
+            version: 3,
+            description: "broken",
+            apply: broken,
+        }];
+        std::fs::write(dir.join("old.json"), "{}").unwrap();
+        assert!(run_migrations(&dir, &migrations, 3).is_err());
+        let content = std::fs::read_to_string(dir.join("settings.json")).unwrap();
+        assert_eq!(stored_version(&serde_json::from_str(&content).unwrap()), 2);
+        assert!(dir.join("old.json").exists());
+        let history = load_history(&dir);
+        assert_eq!(history.len(), 2);
+        assert!(!history[1].success);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn newer_settings_version_is_left_untouched() {
+        let dir = make_temp_dir("openclaw_settings_newer");
+        let content = format!(r#"{{"version": {}, "locale": "en"}}"#, SETTINGS_VERSION + 1);
+        std::fs::write(dir.join("settings.json"), &content).unwrap();
+
+        assert_eq!(
+            run_migrations(&dir, MIGRATIONS, SETTINGS_VERSION).unwrap(),
+            SETTINGS_VERSION + 1
+        );
+        assert_eq!(std::fs::read_to_string(dir.join("settings.json")).unwrap(), content);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
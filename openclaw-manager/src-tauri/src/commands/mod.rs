@@ -1,5 +1,85 @@
+pub mod allowlist;
+pub mod analytics;
+pub mod backups;
+pub mod benchmark;
+pub mod browser;
+pub mod budgets;
+pub mod cache_proxy;
+pub mod capabilities;
+pub mod certs;
+pub mod clipboard;
+pub mod clock;
+pub mod compat;
 pub mod config;
+pub mod config_conflict;
+pub mod config_lint;
+pub mod consoles;
+pub mod crash;
+pub mod dashboard;
+pub mod deep_link;
+pub mod dependencies;
 pub mod diagnostics;
+pub mod digest;
+pub mod downloads;
+pub mod embeddings;
+pub mod events;
+pub mod ffmpeg;
+#[cfg(all(test, unix))]
+mod flow_tests;
+pub mod gateway_lock;
+pub mod git;
+pub mod hooks;
+pub mod install_plan;
+pub mod install_state;
+pub mod install_timings;
 pub mod installer;
+pub mod inventory;
+pub mod jobs;
+pub mod knowledge;
+pub mod legacy;
+pub mod logs;
+pub mod maintenance;
+pub mod memory;
+pub mod migrations;
+pub mod monitor;
+pub mod network;
+pub mod node_dist;
+pub mod notifications;
+pub mod oauth;
+pub mod orphans;
+pub mod pairing;
+pub mod permissions;
+pub mod policy;
+pub mod presets;
+pub mod privacy;
+pub mod probe_cache;
 pub mod process;
+pub mod profiles;
+pub mod python;
+pub mod provisioning;
+pub mod rate_limit;
+pub mod recovery;
+pub mod reports;
+pub mod requests;
+pub mod resources;
+pub mod secret_handles;
 pub mod service;
+pub mod sessions;
+pub mod settings;
+pub mod shell_policy;
+pub mod shutdown;
+pub mod sidecar;
+pub mod skills;
+pub mod source_build;
+pub mod startup;
+pub mod sync;
+pub mod telemetry;
+pub mod templates;
+pub mod trace;
+pub mod ui_preferences;
+pub mod updates;
+pub mod vulnerabilities;
+pub mod watcher;
+pub mod window_state;
+pub mod winpkg;
+pub mod workspace;
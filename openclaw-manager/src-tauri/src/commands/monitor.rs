@@ -0,0 +1,325 @@
+use crate::commands::installer::UpdateInfo;
+use crate::commands::notifications::{self, NotificationTrigger};
+use crate::commands::{
+    analytics, backups, budgets, digest, events, gateway_lock, installer, jobs, maintenance,
+    network, oauth, recovery, resources, service, sidecar, skills, sync, telemetry, workspace,
+};
+use crate::models::GatewayOwner;
+use crate::utils::shell;
+use log::{debug, info, warn};
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+
+/// 服务状态轮询间隔
+const STATUS_INTERVAL: Duration = Duration::from_secs(15);
+/// 渠道状态检查间隔
+const CHANNEL_INTERVAL: Duration = Duration::from_secs(5 * 60);
+/// 版本更新检查间隔
+const UPDATE_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+/// 镜像测速检查间隔（实际是否测速由设置中的 recheck_hours 决定）
+const MIRROR_INTERVAL: Duration = Duration::from_secs(60 * 60);
+/// 费用预算检查间隔
+const BUDGET_INTERVAL: Duration = Duration::from_secs(10 * 60);
+/// 匿名使用统计上报间隔（仅在用户开启时发送）
+const TELEMETRY_INTERVAL: Duration = Duration::from_secs(60 * 60);
+/// 技能更新检查间隔
+const SKILL_UPDATE_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+/// Agent 工作区检查间隔
+const WORKSPACE_INTERVAL: Duration = Duration::from_secs(10 * 60);
+/// OAuth 令牌过期检查间隔
+const TOKEN_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+/// 磁盘空间和可用内存检查间隔
+const RESOURCE_INTERVAL: Duration = Duration::from_secs(60);
+/// 每日通知摘要发送检查间隔
+const DIGEST_INTERVAL: Duration = Duration::from_secs(5 * 60);
+/// 设置同步检查间隔（实际同步间隔见同步设置）
+const SYNC_INTERVAL: Duration = Duration::from_secs(5 * 60);
+/// 远程备份检查间隔（实际备份间隔见备份设置）
+const BACKUP_INTERVAL: Duration = Duration::from_secs(10 * 60);
+/// Gateway 状态未变化时的采样记录间隔（用于统计可用率）
+pub const STATUS_RECORD_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// 检测到在 Manager 之外启动的 Gateway 时发送的事件（载荷为 ServiceStatus）
+pub const EXTERNAL_GATEWAY_EVENT: &str = "gateway://external";
+
+/// 标记下一次 Gateway 停止是用户/Manager 主动发起的，不应视为崩溃
+static EXPECTED_STOP: AtomicBool = AtomicBool::new(false);
+
+/// 最近一次轮询时 Gateway 是否在运行（只在 Gateway 运行时执行的周期检查据此判断）
+static GATEWAY_RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// 最近一次后台更新检查的结果：(毫秒时间戳, 结果)
+static LAST_UPDATE_CHECK: Mutex<Option<(i64, UpdateInfo)>> = Mutex::new(None);
+
+/// 最近一次后台更新检查的结果（首页等直接展示，避免重复执行 npm view）
+pub fn last_update_check() -> Option<(i64, UpdateInfo)> {
+    LAST_UPDATE_CHECK
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+}
+
+/// 在主动停止 Gateway 前调用（停止、重启、更新、卸载等）
+pub fn expect_gateway_stop() {
+    EXPECTED_STOP.store(true, Ordering::SeqCst);
+}
+
+/// 启动后台监控任务
+/// 与窗口是否聚焦无关，Gateway 崩溃、更新可用、渠道异常都会通过系统通知提醒
+///
+/// 崩溃检测单独轮询；其他周期检查（可能调用 openclaw 命令或访问网络）各自在独立的任务中执行，
+/// 一个检查卡住时不会拖延崩溃检测和其他检查
+pub fn start(app: AppHandle) {
+    notifications::init(&app);
+    let monitor_app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        info!("[后台监控] 启动后台监控任务");
+        run_loop(monitor_app).await;
+    });
+    // 任务队列（安装、更新）可能持续很久
+    tauri::async_runtime::spawn(jobs::run_worker());
+
+    // 渠道状态检查（仅在 Gateway 运行时；openclaw 命令是同步调用，放到阻塞线程池）
+    spawn_periodic(CHANNEL_INTERVAL, true, || async {
+        if let Err(e) = tokio::task::spawn_blocking(check_channels).await {
+            debug!("[后台监控] 渠道状态检查任务失败: {}", e);
+        }
+    });
+    // 版本更新检查
+    tauri::async_runtime::spawn(async {
+        let mut notified_version = None;
+        loop {
+            check_update(&mut notified_version).await;
+            tokio::time::sleep(UPDATE_INTERVAL).await;
+        }
+    });
+    // 镜像自动选择（首次运行及定期重新测速）
+    spawn_periodic(MIRROR_INTERVAL, false, network::auto_select_mirrors);
+    // 费用预算检查
+    let budget_app = app.clone();
+    spawn_periodic(BUDGET_INTERVAL, false, move || {
+        let app = budget_app.clone();
+        async move { budgets::check(&app).await }
+    });
+    // 匿名使用统计上报
+    spawn_periodic(TELEMETRY_INTERVAL, false, telemetry::flush);
+    // 技能更新检查
+    tauri::async_runtime::spawn(async {
+        let mut notified_skills = None;
+        loop {
+            skills::check_and_notify(&mut notified_skills).await;
+            tokio::time::sleep(SKILL_UPDATE_INTERVAL).await;
+        }
+    });
+    // Agent 工作区容量和可疑文件检查
+    let workspace_app = app.clone();
+    spawn_periodic(WORKSPACE_INTERVAL, false, move || {
+        let app = workspace_app.clone();
+        async move { workspace::check(&app).await }
+    });
+    // 提前刷新即将过期的 OAuth 令牌并同步到 Gateway 配置
+    spawn_periodic(TOKEN_REFRESH_INTERVAL, false, oauth::refresh_expiring);
+    // 磁盘空间和可用内存检查（仅在 Gateway 运行时）
+    spawn_periodic(RESOURCE_INTERVAL, true, move || {
+        let app = app.clone();
+        async move { resources::check(&app).await }
+    });
+    // 到达设定时间后发送前一天的通知摘要
+    spawn_periodic(DIGEST_INTERVAL, false, || async {
+        if let Err(e) = tokio::task::spawn_blocking(digest::tick).await {
+            debug!("[后台监控] 通知摘要任务失败: {}", e);
+        }
+    });
+    // 开启设置同步时按间隔与其他电脑同步
+    spawn_periodic(SYNC_INTERVAL, false, sync::tick);
+    // 远程备份（上传可能持续很久）
+    spawn_periodic(BACKUP_INTERVAL, false, backups::tick);
+}
+
+/// 在独立的任务中按间隔执行周期检查
+/// `when_running` 为 true 时只在 Gateway 运行时执行，未运行时按状态轮询间隔等待
+fn spawn_periodic<F, Fut>(interval: Duration, when_running: bool, mut check: F)
+where
+    F: FnMut() -> Fut + Send + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    tauri::async_runtime::spawn(async move {
+        loop {
+            if when_running && !GATEWAY_RUNNING.load(Ordering::SeqCst) {
+                tokio::time::sleep(STATUS_INTERVAL).await;
+                continue;
+            }
+            check().await;
+            tokio::time::sleep(interval).await;
+        }
+    });
+}
+
+/// 崩溃检测：轮询 Gateway 状态，意外退出时记录并通知
+async fn run_loop(app: AppHandle) {
+    let mut was_running: Option<bool> = None;
+    let mut gateway_owner: Option<GatewayOwner> = None;
+    let mut running_since: Option<Instant> = None;
+    let mut failures_cleared = false;
+    let mut last_status_record: Option<Instant> = None;
+
+    loop {
+        let status = match service::get_service_status().await {
+            Ok(status) => Some(status),
+            Err(e) => {
+                debug!("[后台监控] 获取服务状态失败: {}", e);
+                None
+            }
+        };
+        let running = status.as_ref().is_some_and(|s| s.running);
+        let owner = status.as_ref().and_then(|s| s.managed_by);
+
+        // 外部启动的 Gateway 只纳入监控，退出时不按崩溃处理（用户可通过 adopt_gateway 接管）
+        if owner == Some(GatewayOwner::External) && gateway_owner != owner {
+            info!("[后台监控] 检测到在 Manager 之外启动的 Gateway，仅监控其状态");
+            let _ = events::emit(&app, EXTERNAL_GATEWAY_EVENT, &status);
+        }
+
+        let mut crashed = false;
+        if was_running == Some(true) && !running {
+            if EXPECTED_STOP.swap(false, Ordering::SeqCst) {
+                info!("[后台监控] Gateway 已按预期停止");
+            } else if gateway_owner == Some(GatewayOwner::External) {
+                info!("[后台监控] 外部启动的 Gateway 已停止");
+            } else {
+                warn!("[后台监控] ✗ 检测到 Gateway 意外退出");
+                crashed = true;
+                recovery::record_failure("Gateway 进程意外退出");
+                gateway_lock::clear_stale();
+                notifications::notify(
+                    NotificationTrigger::GatewayCrashed,
+                    "gateway",
+                    "OpenClaw Gateway 已停止运行",
+                    "Gateway 进程意外退出，请打开 Manager 查看日志并重新启动",
+                );
+            }
+        } else if running {
+            EXPECTED_STOP.store(false, Ordering::SeqCst);
+        }
+        // 连续运行一段时间后视为稳定，清空崩溃记录（更新后恢复据此判断崩溃循环）
+        if !running {
+            running_since = None;
+            failures_cleared = false;
+        } else {
+            let since = *running_since.get_or_insert_with(Instant::now);
+            if !failures_cleared && since.elapsed() >= recovery::STABLE_AFTER {
+                recovery::clear_failures();
+                failures_cleared = true;
+            }
+        }
+        if was_running != Some(running) || is_due(last_status_record, STATUS_RECORD_INTERVAL) {
+            last_status_record = Some(Instant::now());
+            let state = match (running, crashed) {
+                (true, _) => "running",
+                (false, true) => "crashed",
+                (false, false) => "stopped",
+            };
+            analytics::record_gateway_state(state);
+        }
+        was_running = Some(running);
+        gateway_owner = owner;
+        GATEWAY_RUNNING.store(running, Ordering::SeqCst);
+
+        // 按重启策略重启意外退出的辅助进程
+        sidecar::supervise();
+
+        tokio::time::sleep(STATUS_INTERVAL).await;
+    }
+}
+
+/// 距离上次执行是否已超过间隔
+fn is_due(last: Option<Instant>, interval: Duration) -> bool {
+    match last {
+        Some(t) => t.elapsed() >= interval,
+        None => true,
+    }
+}
+
+/// 解析 `openclaw channels status` 的输出，返回异常的渠道：(渠道, 状态说明)
+fn parse_channel_problems(output: &str) -> Vec<(String, String)> {
+    output
+        .lines()
+        .map(str::trim)
+        .filter(|line| line.starts_with("- "))
+        .filter(|line| {
+            let lower = line.to_lowercase();
+            lower.contains("error") || lower.contains("failed") || lower.contains("unauthorized")
+        })
+        .map(|line| {
+            let channel = line
+                .trim_start_matches("- ")
+                .split(|c: char| c == ':' || c.is_whitespace())
+                .next()
+                .unwrap_or("unknown")
+                .to_string();
+            (channel, line.trim_start_matches("- ").to_string())
+        })
+        .collect()
+}
+
+/// 获取状态异常的渠道：(渠道, 状态说明)
+pub fn channel_problems() -> Result<Vec<(String, String)>, String> {
+    let output = shell::run_openclaw(&["channels", "status"])?;
+    Ok(parse_channel_problems(&output))
+}
+
+/// 检查渠道状态，发现错误时通知
+fn check_channels() {
+    let problems = match channel_problems() {
+        Ok(p) => p,
+        Err(e) => {
+            debug!("[后台监控] 获取渠道状态失败: {}", e);
+            return;
+        }
+    };
+
+    for (channel, detail) in problems {
+        warn!("[后台监控] 渠道 {} 状态异常: - {}", channel, detail);
+        notifications::notify(
+            NotificationTrigger::ChannelFailing,
+            &channel.to_lowercase(),
+            &format!("{} 渠道异常", channel),
+            &detail,
+        );
+    }
+}
+
+/// 检查 OpenClaw 更新，有新版本时通知（同一版本只通知一次）
+async fn check_update(notified_version: &mut Option<String>) {
+    let result = installer::check_openclaw_update().await;
+    if let Ok(info) = &result {
+        *LAST_UPDATE_CHECK.lock().unwrap_or_else(|e| e.into_inner()) =
+            Some((chrono::Utc::now().timestamp_millis(), info.clone()));
+    }
+    match result {
+        Ok(info) if info.update_available => {
+            let latest = info.latest_version.unwrap_or_default();
+            if notified_version.as_deref() == Some(latest.as_str()) {
+                return;
+            }
+            info!("[后台监控] 发现新版本: {}", latest);
+            *notified_version = Some(latest.clone());
+            notifications::notify(
+                NotificationTrigger::UpdateAvailable,
+                &latest,
+                "OpenClaw 有新版本可用",
+                &format!(
+                    "当前版本 {}，最新版本 {}",
+                    info.current_version.unwrap_or_default(),
+                    latest
+                ),
+            );
+            maintenance::on_update_available(&latest).await;
+        }
+        Ok(_) => debug!("[后台监控] 暂无更新"),
+        Err(e) => debug!("[后台监控] 检查更新失败: {}", e),
+    }
+}
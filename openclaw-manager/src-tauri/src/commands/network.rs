@@ -0,0 +1,899 @@
+use crate::commands::{config, privacy, settings};
+use crate::utils::{http, time};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+use openclaw_macros::guarded;
+use tauri::command;
+
+/// 单次探测超时（诊断时不使用设置里的 HTTP 超时，避免整体等待过久）
+const PROBE_TIMEOUT: Duration = Duration::from_secs(8);
+
+/// 强制门户检测地址（正常网络返回 204，门户会重定向或返回登录页）
+const CAPTIVE_CHECK_URLS: &[&str] = &[
+    "http://connect.rom.miui.com/generate_204",
+    "http://www.gstatic.com/generate_204",
+];
+
+/// 自动选择时参与测速的 npm 源
+const REGISTRY_CANDIDATES: &[&str] = &[
+    "https://registry.npmjs.org",
+    "https://registry.npmmirror.com",
+    "https://mirrors.cloud.tencent.com/npm",
+    "https://repo.huaweicloud.com/repository/npm",
+];
+
+/// 自动选择时参与测速的 GitHub 加速代理（另外还会测试直连）
+const GITHUB_PROXY_CANDIDATES: &[&str] = &[
+    "https://ghproxy.com/",
+    "https://mirror.ghproxy.com/",
+    "https://gh-proxy.com/",
+];
+
+/// 通过 GitHub 加速代理测速时访问的地址
+const GITHUB_BENCHMARK_URL: &str = "https://github.com/openclaw/openclaw";
+
+/// 下载测速时 GitHub 上下载的文件
+const GITHUB_SPEED_TEST_URL: &str =
+    "https://github.com/openclaw/openclaw/archive/refs/heads/main.tar.gz";
+/// 下载测速每个地址最多下载的字节数
+const SPEED_TEST_MAX_BYTES: u64 = 2 * 1024 * 1024;
+/// 下载测速每个地址最长下载时间
+const SPEED_TEST_DURATION: Duration = Duration::from_secs(15);
+/// 低于该速度视为网络较慢（KB/s）
+const SLOW_THROUGHPUT_KBPS: f64 = 100.0;
+
+/// 依赖的外部端点
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkEndpoint {
+    pub name: String,
+    /// 分类：npm / github / ai / channel
+    pub category: String,
+    pub url: String,
+}
+
+impl NetworkEndpoint {
+    fn new(name: &str, category: &str, url: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            category: category.to_string(),
+            url: url.to_string(),
+        }
+    }
+}
+
+/// 探测失败类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProbeFailure {
+    /// 域名解析失败
+    Dns,
+    /// 请求超时
+    Timeout,
+    /// TCP 连接失败
+    Connect,
+    /// TLS 握手或证书错误
+    Tls,
+    /// 证书不受信任（自签名 / 未知颁发者，通常是公司的 HTTPS 解密代理）
+    UntrustedCertificate,
+    /// 被强制门户（酒店/机场 Wi-Fi 登录页）拦截
+    CaptivePortal,
+    Other,
+}
+
+/// 单次探测结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProbeResult {
+    /// 收到任意 HTTP 响应即视为可达（401/404 也说明网络是通的）
+    pub reachable: bool,
+    pub status: Option<u16>,
+    pub latency_ms: Option<u64>,
+    pub failure: Option<ProbeFailure>,
+    pub error: Option<String>,
+}
+
+impl ProbeResult {
+    fn failed(failure: ProbeFailure, error: String) -> Self {
+        Self {
+            reachable: false,
+            status: None,
+            latency_ms: None,
+            failure: Some(failure),
+            error: Some(error),
+        }
+    }
+}
+
+/// 矩阵中的一行：同一端点直连和经代理的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EndpointDiagnostic {
+    pub endpoint: NetworkEndpoint,
+    pub direct: ProbeResult,
+    /// 未配置代理时为 None
+    pub via_proxy: Option<ProbeResult>,
+}
+
+/// 网络诊断结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkDiagnostics {
+    /// 设置中配置的代理
+    pub proxy: Option<String>,
+    /// 是否检测到强制门户
+    pub captive_portal: bool,
+    pub endpoints: Vec<EndpointDiagnostic>,
+    /// 针对整体网络问题的处理建议
+    pub remediation: Option<String>,
+}
+
+/// 镜像自动选择设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MirrorSettings {
+    /// 自动选择最快的 npm 源和 GitHub 加速代理
+    /// 手动修改 npm_registry / github_proxy 后自动关闭，以用户设置为准
+    pub auto: bool,
+    /// 重新测速间隔（小时）
+    pub recheck_hours: u64,
+    /// 最近一次自动选择的结果
+    pub last_selection: Option<MirrorSelection>,
+}
+
+impl Default for MirrorSettings {
+    fn default() -> Self {
+        Self {
+            auto: true,
+            recheck_hours: 24 * 7,
+            last_selection: None,
+        }
+    }
+}
+
+/// 单个候选镜像的测速结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MirrorBenchmark {
+    /// 候选地址（GitHub 直连记为 "direct"）
+    pub candidate: String,
+    /// 不可用时为 None
+    pub latency_ms: Option<u64>,
+}
+
+/// 一次镜像自动选择的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MirrorSelection {
+    pub registry: String,
+    /// None 表示 GitHub 直连最快
+    pub github_proxy: Option<String>,
+    pub selected_at: String,
+    pub registry_results: Vec<MirrorBenchmark>,
+    pub github_results: Vec<MirrorBenchmark>,
+}
+
+/// 单个地址的下载测速结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpeedTestResult {
+    pub name: String,
+    /// npm / github
+    pub category: String,
+    pub url: String,
+    /// 收到响应头的耗时
+    pub latency_ms: Option<u64>,
+    pub bytes: u64,
+    pub duration_ms: u64,
+    /// 下载速度（KB/s）
+    pub throughput_kbps: Option<f64>,
+    pub error: Option<String>,
+}
+
+/// 下载测速结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpeedTest {
+    pub tested_at: String,
+    pub results: Vec<SpeedTestResult>,
+    /// 结论：网络问题还是 npm 本身的问题
+    pub summary: String,
+}
+
+/// 收集需要探测的端点：npm 源、GitHub 及加速代理、已配置的 AI Provider、渠道 API
+fn collect_endpoints(settings: &settings::ManagerSettings) -> Vec<NetworkEndpoint> {
+    let mut endpoints = vec![
+        NetworkEndpoint::new("npmjs", "npm", "https://registry.npmjs.org/"),
+        NetworkEndpoint::new("npmmirror", "npm", "https://registry.npmmirror.com/"),
+    ];
+
+    let registry = settings.npm_registry.trim();
+    if !registry.is_empty()
+        && !endpoints
+            .iter()
+            .any(|e| e.url.trim_end_matches('/') == registry.trim_end_matches('/'))
+    {
+        endpoints.push(NetworkEndpoint::new("npm (设置)", "npm", registry));
+    }
+
+    endpoints.push(NetworkEndpoint::new(
+        "github.com",
+        "github",
+        "https://github.com/",
+    ));
+    let github_proxy = settings
+        .github_proxy
+        .as_deref()
+        .map(|p| p.trim())
+        .filter(|p| !p.is_empty())
+        .unwrap_or(settings::DEFAULT_GITHUB_PROXY);
+    endpoints.push(NetworkEndpoint::new(
+        "GitHub 加速代理",
+        "github",
+        github_proxy,
+    ));
+
+    if let Ok(config) = config::load_openclaw_config() {
+        if let Some(providers) = config
+            .pointer("/models/providers")
+            .and_then(|v| v.as_object())
+        {
+            for (name, provider) in providers {
+                if let Some(base_url) = provider
+                    .get("baseUrl")
+                    .and_then(|v| v.as_str())
+                    .filter(|u| !u.is_empty())
+                {
+                    endpoints.push(NetworkEndpoint::new(name, "ai", base_url));
+                }
+            }
+        }
+    }
+
+    endpoints.push(NetworkEndpoint::new(
+        "Telegram",
+        "channel",
+        "https://api.telegram.org/",
+    ));
+    endpoints.push(NetworkEndpoint::new(
+        "Discord",
+        "channel",
+        "https://discord.com/api/v10/gateway",
+    ));
+
+    endpoints
+}
+
+/// 错误链的完整描述（reqwest 的顶层错误信息通常不包含根因）
+fn error_chain(e: &reqwest::Error) -> String {
+    let mut message = e.to_string();
+    let mut source = std::error::Error::source(e);
+    while let Some(inner) = source {
+        message.push_str(": ");
+        message.push_str(&inner.to_string());
+        source = inner.source();
+    }
+    message
+}
+
+/// 错误信息是否为证书不受信任（rustls 为 UnknownIssuer，OpenSSL / Node.js 为 self signed 等）
+fn is_untrusted_certificate(lower: &str) -> bool {
+    [
+        "unknownissuer",
+        "unknown issuer",
+        "self signed",
+        "self-signed",
+        "unable to get local issuer certificate",
+        "unable to verify the first certificate",
+    ]
+    .iter()
+    .any(|signature| lower.contains(signature))
+}
+
+/// 对请求错误分类（超时 / TLS / 连接失败 / 其它）
+fn classify_error(e: &reqwest::Error) -> (ProbeFailure, String) {
+    let chain = error_chain(e);
+    let lower = chain.to_lowercase();
+    if e.is_timeout() {
+        (
+            ProbeFailure::Timeout,
+            format!("请求超时（{}秒）", PROBE_TIMEOUT.as_secs()),
+        )
+    } else if is_untrusted_certificate(&lower) {
+        (
+            ProbeFailure::UntrustedCertificate,
+            format!("证书不受信任: {}", chain),
+        )
+    } else if lower.contains("certificate") || lower.contains("tls") || lower.contains("handshake")
+    {
+        (ProbeFailure::Tls, format!("TLS 握手失败: {}", chain))
+    } else if lower.contains("dns") || lower.contains("resolve") {
+        (ProbeFailure::Dns, format!("域名解析失败: {}", chain))
+    } else if e.is_connect() {
+        (ProbeFailure::Connect, format!("连接失败: {}", chain))
+    } else {
+        (ProbeFailure::Other, format!("请求失败: {}", chain))
+    }
+}
+
+/// 解析端点域名（仅直连时检查，经代理时由代理负责解析）
+async fn resolve_host(url: &str) -> Result<(), String> {
+    let parsed = reqwest::Url::parse(url).map_err(|e| format!("地址无效: {}", e))?;
+    let host = parsed
+        .host_str()
+        .ok_or_else(|| "地址缺少主机名".to_string())?;
+    let port = parsed.port_or_known_default().unwrap_or(443);
+    let resolved = tokio::time::timeout(PROBE_TIMEOUT, tokio::net::lookup_host((host, port)))
+        .await
+        .map_err(|_| format!("解析 {} 超时", host))?
+        .map_err(|e| format!("无法解析 {}: {}", host, e))?;
+    if resolved.count() == 0 {
+        return Err(format!("{} 没有解析结果", host));
+    }
+    Ok(())
+}
+
+/// 用指定客户端探测一次端点
+async fn probe(client: &reqwest::Client, url: &str, check_dns: bool) -> ProbeResult {
+    if check_dns {
+        if let Err(e) = resolve_host(url).await {
+            return ProbeResult::failed(ProbeFailure::Dns, e);
+        }
+    }
+    let start = Instant::now();
+    match client.get(url).send().await {
+        Ok(resp) => ProbeResult {
+            reachable: true,
+            status: Some(resp.status().as_u16()),
+            latency_ms: Some(start.elapsed().as_millis() as u64),
+            failure: None,
+            error: None,
+        },
+        Err(e) => {
+            let (failure, error) = classify_error(&e);
+            ProbeResult::failed(failure, error)
+        }
+    }
+}
+
+/// 204 检测结果是否表明处于强制门户之后
+/// 任一检测地址返回了非 204 的响应（重定向到登录页或直接返回 HTML）即判定为门户
+fn is_captive_portal(statuses: &[Option<u16>]) -> bool {
+    statuses.iter().flatten().any(|status| *status != 204)
+}
+
+/// 通过 HTTP 204 探测判断是否处于强制门户之后（不跟随重定向，直连）
+async fn detect_captive_portal() -> bool {
+    let client = match reqwest::Client::builder()
+        .user_agent(concat!("openclaw-manager/", env!("CARGO_PKG_VERSION")))
+        .timeout(PROBE_TIMEOUT)
+        .redirect(reqwest::redirect::Policy::none())
+        .no_proxy()
+        .build()
+    {
+        Ok(c) => c,
+        Err(_) => return false,
+    };
+    let mut statuses = Vec::new();
+    for url in CAPTIVE_CHECK_URLS {
+        let status = client
+            .get(*url)
+            .send()
+            .await
+            .ok()
+            .map(|r| r.status().as_u16());
+        if status == Some(204) {
+            // 有一个地址返回 204 说明网络未被拦截
+            return false;
+        }
+        statuses.push(status);
+    }
+    is_captive_portal(&statuses)
+}
+
+/// 根据诊断结果给出整体处理建议
+fn remediation(captive_portal: bool, rows: &[EndpointDiagnostic]) -> Option<String> {
+    if captive_portal {
+        return Some(
+            "你似乎处于需要网页登录的网络（酒店、机场或公司 Wi-Fi）。请在浏览器中打开任意网页完成登录或认证后重新诊断"
+                .to_string(),
+        );
+    }
+    if rows.is_empty() {
+        return None;
+    }
+    let direct_failed: Vec<&EndpointDiagnostic> =
+        rows.iter().filter(|r| !r.direct.reachable).collect();
+    if direct_failed.is_empty() {
+        return None;
+    }
+    if direct_failed
+        .iter()
+        .any(|r| r.direct.failure == Some(ProbeFailure::UntrustedCertificate))
+    {
+        return Some("HTTPS 证书不受信任：公司网络可能使用了 HTTPS 解密代理，请在设置中导入公司根证书（可向 IT 部门索取）".to_string());
+    }
+    let dns_failed = direct_failed
+        .iter()
+        .filter(|r| r.direct.failure == Some(ProbeFailure::Dns))
+        .count();
+    let proxy_ok = rows
+        .iter()
+        .any(|r| r.via_proxy.as_ref().map(|p| p.reachable).unwrap_or(false));
+
+    if dns_failed == rows.len() {
+        Some("所有域名都无法解析：请检查网络是否已连接，或将 DNS 服务器改为 223.5.5.5 / 119.29.29.29 后重试".to_string())
+    } else if dns_failed > 0 {
+        Some("部分域名无法解析：可能是 DNS 污染或公司网络限制，可尝试更换 DNS 服务器或在设置中配置代理".to_string())
+    } else if direct_failed.len() == rows.len() && !proxy_ok {
+        Some("所有端点都无法访问：请检查网络连接、防火墙或安全软件设置".to_string())
+    } else if proxy_ok {
+        Some("部分端点直连失败但经代理可访问：安装和渠道会使用设置中的代理".to_string())
+    } else {
+        Some(
+            "部分端点无法直连：可在设置中切换 npm 镜像、GitHub 加速代理或配置 HTTP 代理"
+                .to_string(),
+        )
+    }
+}
+
+/// 运行网络连通性诊断：每个端点分别直连和经设置中的代理探测
+#[guarded]
+#[command]
+pub async fn run_network_diagnostics() -> Result<NetworkDiagnostics, String> {
+    info!("[网络诊断] 开始网络连通性诊断...");
+    let settings = settings::load_settings();
+    let proxy = settings.proxy.clone().filter(|p| !p.trim().is_empty());
+
+    let direct_client = http::build_client(None, PROBE_TIMEOUT)?;
+    let proxy_client = match proxy.as_deref() {
+        Some(p) => Some(http::build_client(Some(p), PROBE_TIMEOUT)?),
+        None => None,
+    };
+
+    let captive_check = tokio::spawn(detect_captive_portal());
+
+    // 所有端点并发探测，按原顺序汇总
+    let handles: Vec<_> = collect_endpoints(&settings)
+        .into_iter()
+        .map(|endpoint| {
+            let direct_client = direct_client.clone();
+            let proxy_client = proxy_client.clone();
+            tokio::spawn(async move {
+                let direct = probe(&direct_client, &endpoint.url, true).await;
+                let via_proxy = match &proxy_client {
+                    Some(client) => Some(probe(client, &endpoint.url, false).await),
+                    None => None,
+                };
+                EndpointDiagnostic {
+                    endpoint,
+                    direct,
+                    via_proxy,
+                }
+            })
+        })
+        .collect();
+
+    let mut endpoints = Vec::with_capacity(handles.len());
+    for handle in handles {
+        let row = handle
+            .await
+            .map_err(|e| format!("网络探测任务失败: {}", e))?;
+        let reachable =
+            row.direct.reachable || row.via_proxy.as_ref().map(|p| p.reachable).unwrap_or(false);
+        if reachable {
+            info!("[网络诊断] ✓ {} ({})", row.endpoint.name, row.endpoint.url);
+        } else {
+            warn!(
+                "[网络诊断] ✗ {} ({}): {}",
+                row.endpoint.name,
+                row.endpoint.url,
+                row.direct.error.as_deref().unwrap_or("")
+            );
+        }
+        endpoints.push(row);
+    }
+
+    let captive_portal = captive_check.await.unwrap_or(false);
+    if captive_portal {
+        warn!("[网络诊断] ✗ 检测到强制门户（需要网页登录的网络）");
+        // 门户拦截导致的直连失败单独归类，便于界面提示
+        for row in endpoints.iter_mut() {
+            if !row.direct.reachable && row.direct.failure != Some(ProbeFailure::Dns) {
+                row.direct.failure = Some(ProbeFailure::CaptivePortal);
+            }
+        }
+    }
+
+    let remediation = remediation(captive_portal, &endpoints);
+    info!("[网络诊断] 完成，共探测 {} 个端点", endpoints.len());
+    Ok(NetworkDiagnostics {
+        proxy,
+        captive_portal,
+        endpoints,
+        remediation,
+    })
+}
+
+/// 测速一个地址：取两次中较快的一次，非 2xx/3xx 视为不可用
+async fn benchmark(client: &reqwest::Client, candidate: &str, url: &str) -> MirrorBenchmark {
+    let mut best: Option<u64> = None;
+    for _ in 0..2 {
+        let start = Instant::now();
+        match client.get(url).send().await {
+            Ok(resp) if resp.status().as_u16() < 400 => {
+                let latency = start.elapsed().as_millis() as u64;
+                best = Some(best.map_or(latency, |b| b.min(latency)));
+            }
+            _ => break,
+        }
+    }
+    MirrorBenchmark {
+        candidate: candidate.to_string(),
+        latency_ms: best,
+    }
+}
+
+/// 并发测速一组候选
+async fn benchmark_all(
+    client: &reqwest::Client,
+    targets: Vec<(String, String)>,
+) -> Vec<MirrorBenchmark> {
+    let handles: Vec<_> = targets
+        .into_iter()
+        .map(|(candidate, url)| {
+            let client = client.clone();
+            tokio::spawn(async move { benchmark(&client, &candidate, &url).await })
+        })
+        .collect();
+    let mut results = Vec::with_capacity(handles.len());
+    for handle in handles {
+        if let Ok(result) = handle.await {
+            results.push(result);
+        }
+    }
+    results
+}
+
+/// 选出延迟最低的可用候选
+fn pick_fastest(results: &[MirrorBenchmark]) -> Option<&MirrorBenchmark> {
+    results
+        .iter()
+        .filter(|r| r.latency_ms.is_some())
+        .min_by_key(|r| r.latency_ms)
+}
+
+/// 是否需要重新测速：开启自动选择，且从未测速或距上次测速超过间隔
+fn mirror_selection_due(mirror: &MirrorSettings, now: chrono::DateTime<chrono::Utc>) -> bool {
+    if !mirror.auto {
+        return false;
+    }
+    let last = mirror
+        .last_selection
+        .as_ref()
+        .and_then(|s| chrono::DateTime::parse_from_rfc3339(&s.selected_at).ok());
+    match last {
+        Some(t) => {
+            now.signed_duration_since(t) >= chrono::Duration::hours(mirror.recheck_hours as i64)
+        }
+        None => true,
+    }
+}
+
+/// 测速所有候选 npm 源和 GitHub 加速代理，得出最快的组合
+async fn benchmark_mirrors(
+    settings: &settings::ManagerSettings,
+) -> Result<MirrorSelection, String> {
+    let proxy = settings.proxy.as_deref().filter(|p| !p.trim().is_empty());
+    let client = http::build_client(proxy, PROBE_TIMEOUT)?;
+
+    let registry_targets = REGISTRY_CANDIDATES
+        .iter()
+        .map(|r| (r.to_string(), format!("{}/", r)))
+        .collect();
+    let mut github_targets = vec![("direct".to_string(), GITHUB_BENCHMARK_URL.to_string())];
+    github_targets.extend(GITHUB_PROXY_CANDIDATES.iter().map(|p| {
+        (
+            p.to_string(),
+            format!("{}/{}", p.trim_end_matches('/'), GITHUB_BENCHMARK_URL),
+        )
+    }));
+
+    let (registry_results, github_results) = tokio::join!(
+        benchmark_all(&client, registry_targets),
+        benchmark_all(&client, github_targets)
+    );
+
+    let registry = pick_fastest(&registry_results)
+        .map(|r| r.candidate.clone())
+        .ok_or_else(|| "所有 npm 源都无法访问，请先运行网络诊断".to_string())?;
+    // GitHub 全部不可用时保留原设置
+    let github_proxy = match pick_fastest(&github_results) {
+        Some(r) if r.candidate == "direct" => None,
+        Some(r) => Some(r.candidate.clone()),
+        None => settings.github_proxy.clone(),
+    };
+
+    Ok(MirrorSelection {
+        registry,
+        github_proxy,
+        selected_at: time::now_rfc3339(),
+        registry_results,
+        github_results,
+    })
+}
+
+/// 测速并写入设置
+async fn select_and_save_mirrors() -> Result<MirrorSelection, String> {
+    let selection = benchmark_mirrors(&settings::load_settings()).await?;
+    // 测速耗时较长，在锁内重新读取后写入，避免覆盖期间的其它修改
+    settings::update(|current| {
+        current.npm_registry = selection.registry.clone();
+        current.github_proxy = selection.github_proxy.clone();
+        current.mirror.last_selection = Some(selection.clone());
+        Ok(())
+    })?;
+    info!(
+        "[镜像选择] ✓ npm 源: {}，GitHub: {}",
+        selection.registry,
+        selection.github_proxy.as_deref().unwrap_or("直连")
+    );
+    Ok(selection)
+}
+
+/// 按需自动选择镜像（首次运行及超过测速间隔时执行，后台监控定期调用）
+pub async fn auto_select_mirrors() {
+    if privacy::is_enabled() || !mirror_selection_due(&settings::load_settings().mirror, chrono::Utc::now()) {
+        return;
+    }
+    info!("[镜像选择] 开始测速 npm 源和 GitHub 加速代理...");
+    if let Err(e) = select_and_save_mirrors().await {
+        warn!("[镜像选择] ✗ 自动选择失败，保留当前设置: {}", e);
+    }
+}
+
+/// 立即测速并选择最快的镜像（同时恢复自动选择）
+#[guarded]
+#[command]
+pub async fn select_fastest_mirrors() -> Result<MirrorSelection, String> {
+    info!("[镜像选择] 手动触发镜像测速...");
+    if !settings::load_settings().mirror.auto {
+        settings::update(|current| {
+            current.mirror.auto = true;
+            Ok(())
+        })?;
+    }
+    select_and_save_mirrors().await
+}
+
+/// 从 npm 源获取 openclaw 最新版本的 tarball 地址（即安装时实际下载的文件）
+async fn registry_tarball(client: &reqwest::Client, registry: &str) -> Result<String, String> {
+    let url = format!("{}/openclaw/latest", registry.trim_end_matches('/'));
+    let meta: serde_json::Value = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| error_chain(&e))?
+        .error_for_status()
+        .map_err(|e| e.to_string())?
+        .json()
+        .await
+        .map_err(|e| format!("解析包信息失败: {}", e))?;
+    meta.pointer("/dist/tarball")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string())
+        .ok_or_else(|| "包信息中没有 tarball 地址".to_string())
+}
+
+/// 下载一个文件测速：最多下载 SPEED_TEST_MAX_BYTES 或 SPEED_TEST_DURATION
+async fn measure_download(
+    client: &reqwest::Client,
+    name: &str,
+    category: &str,
+    url: Result<String, String>,
+) -> SpeedTestResult {
+    let mut result = SpeedTestResult {
+        name: name.to_string(),
+        category: category.to_string(),
+        url: url.clone().unwrap_or_default(),
+        latency_ms: None,
+        bytes: 0,
+        duration_ms: 0,
+        throughput_kbps: None,
+        error: None,
+    };
+    let url = match url {
+        Ok(url) => url,
+        Err(e) => {
+            result.error = Some(e);
+            return result;
+        }
+    };
+    let start = Instant::now();
+    let mut resp = match client.get(&url).send().await {
+        Ok(resp) if resp.status().is_success() => resp,
+        Ok(resp) => {
+            result.error = Some(format!("HTTP {}", resp.status().as_u16()));
+            return result;
+        }
+        Err(e) => {
+            result.error = Some(error_chain(&e));
+            return result;
+        }
+    };
+    result.latency_ms = Some(start.elapsed().as_millis() as u64);
+    let download_start = Instant::now();
+    while result.bytes < SPEED_TEST_MAX_BYTES && download_start.elapsed() < SPEED_TEST_DURATION {
+        match resp.chunk().await {
+            Ok(Some(chunk)) => result.bytes += chunk.len() as u64,
+            Ok(None) => break,
+            Err(e) => {
+                result.error = Some(error_chain(&e));
+                break;
+            }
+        }
+    }
+    let elapsed = download_start.elapsed();
+    result.duration_ms = elapsed.as_millis() as u64;
+    if result.bytes > 0 && !elapsed.is_zero() {
+        result.throughput_kbps = Some(result.bytes as f64 / 1024.0 / elapsed.as_secs_f64());
+    }
+    result
+}
+
+/// 根据测速结果判断安装缓慢是网络问题还是 npm 本身的问题
+fn speed_test_summary(results: &[SpeedTestResult]) -> String {
+    let ok: Vec<&SpeedTestResult> = results.iter().filter(|r| r.bytes > 0).collect();
+    if ok.is_empty() {
+        return "所有下载都失败，请先运行网络诊断检查代理、DNS 和证书".to_string();
+    }
+    let Some(npm) = results.iter().find(|r| r.category == "npm") else {
+        return "未测试 npm 源".to_string();
+    };
+    match npm.throughput_kbps {
+        None => format!(
+            "npm 源 {} 无法下载，但其它地址正常，建议切换 npm 源或运行镜像测速",
+            npm.name
+        ),
+        Some(kbps) if kbps < SLOW_THROUGHPUT_KBPS => format!(
+            "npm 源下载速度较慢（{:.0} KB/s），安装卡住多半是网络原因，建议切换镜像或检查代理",
+            kbps
+        ),
+        Some(kbps) => format!(
+            "网络下载正常（npm 源 {:.0} KB/s），安装卡住可能是 npm 本身的问题（缓存、锁文件或权限），请查看安装日志",
+            kbps
+        ),
+    }
+}
+
+/// 从设置中的 npm 源和 GitHub 加速代理各下载一个文件，测量延迟和下载速度
+#[guarded]
+#[command]
+pub async fn run_speed_test() -> Result<SpeedTest, String> {
+    let settings = settings::load_settings();
+    info!("[下载测速] 开始测速...");
+    let proxy = settings.proxy.as_deref().filter(|p| !p.trim().is_empty());
+    let client = http::build_client(proxy, SPEED_TEST_DURATION + PROBE_TIMEOUT)?;
+
+    let registry = settings.npm_registry.trim().to_string();
+    let tarball = registry_tarball(&client, &registry).await;
+    let mut results = vec![measure_download(&client, &registry, "npm", tarball).await];
+    if let Some(url) = settings.github_proxied_url(GITHUB_SPEED_TEST_URL) {
+        results.push(measure_download(&client, "GitHub 加速代理", "github", Ok(url)).await);
+    }
+    results.push(
+        measure_download(
+            &client,
+            "GitHub 直连",
+            "github",
+            Ok(GITHUB_SPEED_TEST_URL.to_string()),
+        )
+        .await,
+    );
+
+    let summary = speed_test_summary(&results);
+    info!("[下载测速] ✓ {}", summary);
+    Ok(SpeedTest {
+        tested_at: time::now_rfc3339(),
+        results,
+        summary,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(direct: ProbeResult) -> EndpointDiagnostic {
+        EndpointDiagnostic {
+            endpoint: NetworkEndpoint::new("npmjs", "npm", "https://registry.npmjs.org/"),
+            direct,
+            via_proxy: None,
+        }
+    }
+
+    #[test]
+    fn fastest_reachable_mirror_wins_and_override_disables_recheck() {
+        let results = vec![
+            MirrorBenchmark {
+                candidate: "a".to_string(),
+                latency_ms: None,
+            },
+            MirrorBenchmark {
+                candidate: "b".to_string(),
+                latency_ms: Some(300),
+            },
+            MirrorBenchmark {
+                candidate: "c".to_string(),
+                latency_ms: Some(120),
+            },
+        ];
+        assert_eq!(pick_fastest(&results).unwrap().candidate, "c");
+        assert!(pick_fastest(&results[..1]).is_none());
+
+        let now = chrono::Utc::now();
+        let mut mirror = MirrorSettings::default();
+        assert!(mirror_selection_due(&mirror, now));
+        mirror.last_selection = Some(MirrorSelection {
+            registry: "c".to_string(),
+            github_proxy: None,
+            selected_at: (now - chrono::Duration::hours(1)).to_rfc3339(),
+            registry_results: results,
+            github_results: Vec::new(),
+        });
+        assert!(!mirror_selection_due(&mirror, now));
+        assert!(mirror_selection_due(
+            &mirror,
+            now + chrono::Duration::hours(24 * 7)
+        ));
+        mirror.auto = false;
+        assert!(!mirror_selection_due(
+            &mirror,
+            now + chrono::Duration::hours(24 * 7)
+        ));
+    }
+
+    #[test]
+    fn speed_test_separates_network_from_npm_problems() {
+        let result = |category: &str, kbps: Option<f64>| SpeedTestResult {
+            name: category.to_string(),
+            category: category.to_string(),
+            url: String::new(),
+            latency_ms: kbps.map(|_| 80),
+            bytes: if kbps.is_some() { 1024 } else { 0 },
+            duration_ms: 1000,
+            throughput_kbps: kbps,
+            error: kbps.is_none().then(|| "timeout".to_string()),
+        };
+        assert!(speed_test_summary(&[result("npm", None), result("github", None)])
+            .contains("网络诊断"));
+        assert!(speed_test_summary(&[result("npm", None), result("github", Some(900.0))])
+            .contains("切换 npm 源"));
+        assert!(speed_test_summary(&[result("npm", Some(20.0))]).contains("网络原因"));
+        assert!(speed_test_summary(&[result("npm", Some(2048.0))]).contains("npm 本身"));
+    }
+
+    #[test]
+    fn captive_portal_is_detected_from_non_204_response() {
+        assert!(!is_captive_portal(&[None, None]));
+        assert!(is_captive_portal(&[Some(302), None]));
+        assert!(is_captive_portal(&[Some(200)]));
+    }
+
+    #[test]
+    fn dns_failures_and_captive_portal_get_distinct_remediation() {
+        let dns = vec![row(ProbeResult::failed(
+            ProbeFailure::Dns,
+            "无法解析".to_string(),
+        ))];
+        let dns_hint = remediation(false, &dns).unwrap();
+        assert!(dns_hint.contains("DNS"));
+
+        let portal_hint = remediation(true, &dns).unwrap();
+        assert!(portal_hint.contains("网页登录"));
+        assert_ne!(dns_hint, portal_hint);
+
+        let untrusted = vec![row(ProbeResult::failed(
+            ProbeFailure::UntrustedCertificate,
+            "证书不受信任".to_string(),
+        ))];
+        assert!(remediation(false, &untrusted).unwrap().contains("根证书"));
+        assert!(is_untrusted_certificate(
+            "invalid peer certificate: unknownissuer"
+        ));
+        assert!(!is_untrusted_certificate("certificate expired"));
+    }
+}
@@ -0,0 +1,118 @@
+use crate::commands::downloads;
+use crate::commands::settings::ManagerSettings;
+use crate::utils::{http, platform};
+use log::info;
+use serde_json::Value;
+use std::path::PathBuf;
+
+/// Node.js 官方下载地址
+const NODE_DIST_OFFICIAL: &str = "https://nodejs.org/dist";
+/// Node.js 国内镜像
+const NODE_DIST_MIRROR: &str = "https://npmmirror.com/mirrors/node";
+/// 安装的 Node.js 主版本
+pub const NODE_MAJOR: u32 = 22;
+
+/// 下载源：npm 使用国内镜像时 Node.js 也走镜像
+pub fn dist_base(settings: &ManagerSettings) -> &'static str {
+    if settings.npm_registry.contains("npmmirror") {
+        NODE_DIST_MIRROR
+    } else {
+        NODE_DIST_OFFICIAL
+    }
+}
+
+/// 从 index.json 中选择指定主版本的最新 macOS 版本
+/// 官方 pkg 是通用包（文件列表中标记为 osx-x64-pkg），Apple Silicon 需额外确认发布了 arm64 构建
+fn pick_macos_release(index: &Value, major: u32, arch: &str) -> Option<String> {
+    let prefix = format!("v{}.", major);
+    let needs_arm = arch.contains("aarch64") || arch.contains("arm64");
+    index.as_array()?.iter().find_map(|release| {
+        let version = release.get("version")?.as_str()?;
+        if !version.starts_with(&prefix) {
+            return None;
+        }
+        let files: Vec<&str> = release
+            .get("files")?
+            .as_array()?
+            .iter()
+            .filter_map(|f| f.as_str())
+            .collect();
+        let has_pkg = files.contains(&"osx-x64-pkg");
+        let has_arch = !needs_arm || files.iter().any(|f| f.starts_with("osx-arm64"));
+        (has_pkg && has_arch).then(|| version.to_string())
+    })
+}
+
+/// 从 SHASUMS256.txt 中查找文件的校验值
+fn find_checksum(shasums: &str, file_name: &str) -> Option<String> {
+    shasums.lines().find_map(|line| {
+        let (hash, name) = line.split_once(char::is_whitespace)?;
+        (name.trim() == file_name).then(|| hash.trim().to_lowercase())
+    })
+}
+
+/// 下载官方 macOS pkg 并校验 SHA-256，返回本地路径
+pub async fn download_macos_pkg(settings: &ManagerSettings) -> Result<PathBuf, String> {
+    let base = dist_base(settings);
+    let client = http::download_client()?;
+    let arch = platform::get_arch();
+
+    let index: Value = client
+        .get(format!("{}/index.json", base))
+        .send()
+        .await
+        .map_err(|e| format!("获取 Node.js 版本列表失败: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("解析 Node.js 版本列表失败: {}", e))?;
+    let version = pick_macos_release(&index, NODE_MAJOR, &arch)
+        .ok_or_else(|| format!("找不到适用于 {} 的 Node.js {} 安装包", arch, NODE_MAJOR))?;
+
+    let file_name = format!("node-{}.pkg", version);
+    let shasums = client
+        .get(format!("{}/{}/SHASUMS256.txt", base, version))
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("获取校验文件失败: {}", e))?
+        .text()
+        .await
+        .map_err(|e| format!("读取校验文件失败: {}", e))?;
+    let expected = find_checksum(&shasums, &file_name)
+        .ok_or_else(|| format!("校验文件中没有 {}", file_name))?;
+
+    let url = format!("{}/{}/{}", base, version, file_name);
+    let target = downloads::fetch(&client, &url, &file_name, Some(&expected)).await?;
+    info!("[安装Node.js] ✓ 已下载并校验 {}", file_name);
+    Ok(target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn macos_release_and_checksum_are_selected() {
+        let index = json!([
+            {"version": "v23.1.0", "files": ["osx-arm64-tar", "osx-x64-pkg"]},
+            {"version": "v22.12.0", "files": ["osx-x64-tar", "osx-x64-pkg"]},
+            {"version": "v22.11.0", "files": ["osx-arm64-tar", "osx-x64-pkg", "osx-x64-tar"]},
+        ]);
+        assert_eq!(
+            pick_macos_release(&index, 22, "x86_64").as_deref(),
+            Some("v22.12.0")
+        );
+        assert_eq!(
+            pick_macos_release(&index, 22, "aarch64").as_deref(),
+            Some("v22.11.0")
+        );
+        assert_eq!(pick_macos_release(&index, 20, "aarch64"), None);
+
+        let shasums = "abc123  node-v22.11.0-darwin-arm64.tar.gz\nDEF456  node-v22.11.0.pkg\n";
+        assert_eq!(
+            find_checksum(shasums, "node-v22.11.0.pkg").as_deref(),
+            Some("def456")
+        );
+    }
+}
@@ -0,0 +1,583 @@
+use crate::commands::digest::{self, DigestSettings};
+use crate::commands::{deep_link, settings};
+use crate::utils::{http, secrets};
+use hmac::{Hmac, Mac};
+use log::{debug, error, info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use openclaw_macros::guarded;
+use tauri::{command, AppHandle};
+use tauri_plugin_notification::NotificationExt;
+
+/// 同一事件的最短通知间隔，避免后台监控反复弹窗
+const NOTIFY_COOLDOWN: Duration = Duration::from_secs(10 * 60);
+
+/// 后台任务使用的 AppHandle（在 setup 阶段注入）
+static APP_HANDLE: OnceLock<AppHandle> = OnceLock::new();
+
+/// 最近一次发送通知的时间（按 触发器+key 去重）
+static LAST_SENT: OnceLock<Mutex<HashMap<String, Instant>>> = OnceLock::new();
+
+/// 通知触发器
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationTrigger {
+    /// Gateway 异常退出
+    GatewayCrashed,
+    /// 有新版本可用
+    UpdateAvailable,
+    /// 安装/更新完成
+    InstallFinished,
+    /// 渠道 Webhook 异常
+    ChannelFailing,
+    /// 已应用 OpenClaw 更新
+    UpdateApplied,
+    /// AI Provider 费用达到预算
+    BudgetAlert,
+    /// 已安装的技能有新版本
+    SkillUpdates,
+    /// Agent 工作区超出容量、增长过快或出现可疑文件
+    WorkspaceAlert,
+    /// OAuth 等短期令牌刷新失败
+    CredentialRefreshFailed,
+    /// 磁盘空间或可用内存低于阈值
+    LowResources,
+    /// 每日通知摘要
+    DailyDigest,
+}
+
+impl NotificationTrigger {
+    fn as_str(&self) -> &'static str {
+        match self {
+            NotificationTrigger::GatewayCrashed => "gateway_crashed",
+            NotificationTrigger::UpdateAvailable => "update_available",
+            NotificationTrigger::InstallFinished => "install_finished",
+            NotificationTrigger::ChannelFailing => "channel_failing",
+            NotificationTrigger::UpdateApplied => "update_applied",
+            NotificationTrigger::BudgetAlert => "budget_alert",
+            NotificationTrigger::SkillUpdates => "skill_updates",
+            NotificationTrigger::WorkspaceAlert => "workspace_alert",
+            NotificationTrigger::CredentialRefreshFailed => "credential_refresh_failed",
+            NotificationTrigger::LowResources => "low_resources",
+            NotificationTrigger::DailyDigest => "daily_digest",
+        }
+    }
+
+    /// 点击通知后应打开的 Manager 页面
+    fn deep_link(&self) -> String {
+        match self {
+            NotificationTrigger::GatewayCrashed => deep_link::link("diagnostics", Some("run")),
+            NotificationTrigger::UpdateAvailable | NotificationTrigger::UpdateApplied => {
+                deep_link::link("update", None)
+            }
+            NotificationTrigger::ChannelFailing => deep_link::link("channels", None),
+            NotificationTrigger::InstallFinished
+            | NotificationTrigger::LowResources
+            | NotificationTrigger::DailyDigest => deep_link::link("dashboard", None),
+            NotificationTrigger::BudgetAlert
+            | NotificationTrigger::SkillUpdates
+            | NotificationTrigger::WorkspaceAlert
+            | NotificationTrigger::CredentialRefreshFailed => deep_link::link("settings", None),
+        }
+    }
+}
+
+/// 系统通知设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationSettings {
+    /// 总开关
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Gateway 异常退出时通知
+    #[serde(default = "default_true")]
+    pub gateway_crashed: bool,
+    /// 有新版本时通知
+    #[serde(default = "default_true")]
+    pub update_available: bool,
+    /// 安装完成时通知
+    #[serde(default = "default_true")]
+    pub install_finished: bool,
+    /// 渠道异常时通知
+    #[serde(default = "default_true")]
+    pub channel_failing: bool,
+    /// 应用更新后通知
+    #[serde(default = "default_true")]
+    pub update_applied: bool,
+    /// 费用达到预算时通知
+    #[serde(default = "default_true")]
+    pub budget_alert: bool,
+    /// 技能有新版本时通知
+    #[serde(default = "default_true")]
+    pub skill_updates: bool,
+    /// 工作区告警时通知
+    #[serde(default = "default_true")]
+    pub workspace_alert: bool,
+    /// 令牌刷新失败时通知
+    #[serde(default = "default_true")]
+    pub credential_refresh_failed: bool,
+    /// 磁盘空间或内存不足时通知
+    #[serde(default = "default_true")]
+    pub low_resources: bool,
+    /// 发送每日摘要通知
+    #[serde(default = "default_true")]
+    pub daily_digest: bool,
+    /// 把非紧急的通知汇总为每日摘要
+    #[serde(default)]
+    pub digest: DigestSettings,
+    /// 远程通知目标（Webhook / Bark / Server酱 / Telegram）
+    #[serde(default)]
+    pub targets: Vec<NotificationTarget>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// 远程通知目标
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationTarget {
+    /// 目标名称
+    pub name: String,
+    /// 是否启用
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// 目标类型及参数
+    #[serde(flatten)]
+    pub kind: NotificationTargetKind,
+    /// 触发该目标的事件（默认：Gateway 崩溃、已应用更新）
+    #[serde(default = "default_remote_triggers")]
+    pub triggers: Vec<NotificationTrigger>,
+}
+
+fn default_remote_triggers() -> Vec<NotificationTrigger> {
+    vec![NotificationTrigger::GatewayCrashed, NotificationTrigger::UpdateApplied]
+}
+
+/// 远程通知目标类型
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum NotificationTargetKind {
+    /// 自定义 Webhook，配置 secret 时使用 HMAC-SHA256 签名
+    Webhook {
+        url: String,
+        #[serde(default)]
+        secret: Option<String>,
+    },
+    /// Bark (iOS 推送)
+    Bark {
+        #[serde(default)]
+        server: Option<String>,
+        device_key: String,
+    },
+    /// Server酱
+    ServerChan { send_key: String },
+    /// Telegram Bot 私信
+    Telegram { bot_token: String, chat_id: String },
+}
+
+impl NotificationTargetKind {
+    /// 需要保存在钥匙串中的敏感字段
+    fn secret_fields_mut(&mut self) -> Vec<(&'static str, &mut String)> {
+        match self {
+            NotificationTargetKind::Webhook { secret, .. } => match secret {
+                Some(secret) => vec![("secret", secret)],
+                None => Vec::new(),
+            },
+            NotificationTargetKind::Bark { device_key, .. } => vec![("device_key", device_key)],
+            NotificationTargetKind::ServerChan { send_key } => vec![("send_key", send_key)],
+            NotificationTargetKind::Telegram { bot_token, .. } => vec![("bot_token", bot_token)],
+        }
+    }
+}
+
+impl NotificationTarget {
+    /// 钥匙串中的账户名：`notify:<目标名>:<字段>`
+    fn secret_account(name: &str, field: &str) -> String {
+        format!("notify:{}:{}", name, field)
+    }
+
+    /// 将钥匙串引用替换为真实密钥（仅用于发送）
+    /// 只解析本目标自己的钥匙串条目，目标来自前端时也不会读出其他密钥
+    fn with_resolved_secrets(&self) -> Result<NotificationTarget, String> {
+        let mut target = self.clone();
+        let name = target.name.clone();
+        for (field, value) in target.kind.secret_fields_mut() {
+            secrets::check_ref(value, &Self::secret_account(&name, field))?;
+            *value = secrets::resolve_secret(value)?;
+        }
+        Ok(target)
+    }
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            gateway_crashed: true,
+            update_available: true,
+            install_finished: true,
+            channel_failing: true,
+            update_applied: true,
+            budget_alert: true,
+            skill_updates: true,
+            workspace_alert: true,
+            credential_refresh_failed: true,
+            low_resources: true,
+            daily_digest: true,
+            digest: DigestSettings::default(),
+            targets: Vec::new(),
+        }
+    }
+}
+
+impl NotificationSettings {
+    /// 指定触发器是否启用
+    pub fn allows(&self, trigger: NotificationTrigger) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        match trigger {
+            NotificationTrigger::GatewayCrashed => self.gateway_crashed,
+            NotificationTrigger::UpdateAvailable => self.update_available,
+            NotificationTrigger::InstallFinished => self.install_finished,
+            NotificationTrigger::ChannelFailing => self.channel_failing,
+            NotificationTrigger::UpdateApplied => self.update_applied,
+            NotificationTrigger::BudgetAlert => self.budget_alert,
+            NotificationTrigger::SkillUpdates => self.skill_updates,
+            NotificationTrigger::WorkspaceAlert => self.workspace_alert,
+            NotificationTrigger::CredentialRefreshFailed => self.credential_refresh_failed,
+            NotificationTrigger::LowResources => self.low_resources,
+            NotificationTrigger::DailyDigest => self.daily_digest,
+        }
+    }
+
+    /// 将远程目标中的明文密钥移入系统钥匙串，设置中只保留引用
+    /// 钥匙串不可用时保留明文并记录警告，返回成功迁移的字段数；
+    /// 引用了其他钥匙串条目或密钥句柄时返回错误
+    pub fn secure_secrets(&mut self) -> Result<usize, String> {
+        let mut moved = 0;
+        for target in &mut self.targets {
+            let name = target.name.clone();
+            for (field, value) in target.kind.secret_fields_mut() {
+                let account = NotificationTarget::secret_account(&name, field);
+                secrets::check_ref(value, &account)?;
+                if value.is_empty() || secrets::is_keychain_ref(value) {
+                    continue;
+                }
+                match secrets::store_secret(&account, value) {
+                    Ok(reference) => {
+                        *value = reference;
+                        moved += 1;
+                    }
+                    Err(e) => warn!("[通知] 无法将 {} 的 {} 存入钥匙串: {}", name, field, e),
+                }
+            }
+        }
+        Ok(moved)
+    }
+}
+
+/// 读取通知设置
+pub fn load_notification_settings() -> NotificationSettings {
+    settings::load_settings().notifications
+}
+
+/// 注入 AppHandle，供后台监控和调度任务发送通知
+pub fn init(app: &AppHandle) {
+    let _ = APP_HANDLE.set(app.clone());
+}
+
+/// 检查冷却时间，返回是否允许发送
+fn check_cooldown(dedupe_key: &str) -> bool {
+    let map = LAST_SENT.get_or_init(|| Mutex::new(HashMap::new()));
+    let mut map = match map.lock() {
+        Ok(m) => m,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    let now = Instant::now();
+    if let Some(last) = map.get(dedupe_key) {
+        if now.duration_since(*last) < NOTIFY_COOLDOWN {
+            return false;
+        }
+    }
+    map.insert(dedupe_key.to_string(), now);
+    true
+}
+
+/// 发送系统通知，并推送到订阅了该事件的远程目标；开启通知摘要时，摘要中的触发器只记入当天的摘要
+/// `key` 用于区分同一触发器下的不同对象（如不同渠道），相同 key 在冷却时间内只通知一次
+pub fn notify(trigger: NotificationTrigger, key: &str, title: &str, body: &str) {
+    let settings = load_notification_settings();
+    let desktop = settings.allows(trigger);
+    let targets: Vec<NotificationTarget> = settings
+        .targets
+        .into_iter()
+        .filter(|t| t.enabled && t.triggers.contains(&trigger))
+        .collect();
+
+    if !desktop && targets.is_empty() {
+        debug!("[通知] 触发器 {} 已关闭，跳过: {}", trigger.as_str(), title);
+        return;
+    }
+
+    if digest::should_batch(&settings.digest, trigger) {
+        debug!("[通知] 记入每日摘要 ({}): {}", trigger.as_str(), title);
+        digest::record(trigger, key, title, body);
+        return;
+    }
+
+    let dedupe_key = format!("{}:{}", trigger.as_str(), key);
+    if !check_cooldown(&dedupe_key) {
+        debug!("[通知] {} 处于冷却时间内，跳过", dedupe_key);
+        return;
+    }
+
+    if desktop {
+        match APP_HANDLE.get() {
+            Some(app) => {
+                info!("[通知] 发送通知 ({}): {}", trigger.as_str(), title);
+                if let Err(e) = app.notification().builder().title(title).body(body).show() {
+                    warn!("[通知] 发送通知失败: {}", e);
+                }
+            }
+            None => warn!("[通知] AppHandle 未初始化，无法发送通知: {}", title),
+        }
+    }
+
+    if !targets.is_empty() {
+        let payload = NotificationPayload::new(trigger, title, body);
+        tauri::async_runtime::spawn(async move {
+            for target in targets {
+                match send_to_target(&target, &payload).await {
+                    Ok(_) => info!("[通知] ✓ 已推送到 {}", target.name),
+                    Err(e) => error!("[通知] ✗ 推送到 {} 失败: {}", target.name, e),
+                }
+            }
+        });
+    }
+}
+
+/// 远程通知内容
+#[derive(Debug, Clone, Serialize)]
+struct NotificationPayload {
+    event: NotificationTrigger,
+    title: String,
+    body: String,
+    host: String,
+    timestamp: i64,
+    /// 在本机打开 Manager 对应页面的深度链接
+    link: String,
+}
+
+impl NotificationPayload {
+    fn new(event: NotificationTrigger, title: &str, body: &str) -> Self {
+        Self {
+            event,
+            title: title.to_string(),
+            body: body.to_string(),
+            host: hostname(),
+            timestamp: chrono::Utc::now().timestamp(),
+            link: event.deep_link(),
+        }
+    }
+}
+
+/// 获取主机名，用于区分多台机器的通知
+fn hostname() -> String {
+    std::env::var("COMPUTERNAME")
+        .or_else(|_| std::env::var("HOSTNAME"))
+        .ok()
+        .filter(|h| !h.is_empty())
+        .or_else(|| {
+            crate::utils::shell::run_command_output("hostname", &[])
+                .ok()
+                .filter(|h| !h.is_empty())
+        })
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// 计算 HMAC-SHA256 并输出十六进制
+fn hmac_sha256_hex(secret: &str, message: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .expect("HMAC 支持任意长度的密钥");
+    mac.update(message.as_bytes());
+    hex::encode(mac.finalize().into_bytes())
+}
+
+/// 推送到单个远程目标
+async fn send_to_target(target: &NotificationTarget, payload: &NotificationPayload) -> Result<(), String> {
+    let target = target.with_resolved_secrets()?;
+    let client = http::client()?;
+    let text = format!("{}\n{}\n({})", payload.title, payload.body, payload.host);
+
+    let request = match &target.kind {
+        NotificationTargetKind::Webhook { url, secret } => {
+            let body = serde_json::to_string(payload)
+                .map_err(|e| format!("序列化通知内容失败: {}", e))?;
+            let mut req = client
+                .post(url)
+                .header("Content-Type", "application/json")
+                .header("X-OpenClaw-Event", payload.event.as_str())
+                .header("X-OpenClaw-Timestamp", payload.timestamp.to_string());
+            if let Some(secret) = secret.as_deref().filter(|s| !s.is_empty()) {
+                // 签名内容为 "{timestamp}.{body}"，接收方可据此校验来源并防重放
+                let signature = hmac_sha256_hex(secret, &format!("{}.{}", payload.timestamp, body));
+                req = req.header("X-OpenClaw-Signature", format!("sha256={}", signature));
+            }
+            req.body(body)
+        }
+        NotificationTargetKind::Bark { server, device_key } => {
+            let server = server
+                .as_deref()
+                .filter(|s| !s.is_empty())
+                .unwrap_or("https://api.day.app")
+                .trim_end_matches('/');
+            client.post(format!("{}/push", server)).json(&json!({
+                "device_key": device_key,
+                "title": payload.title,
+                "body": format!("{}\n({})", payload.body, payload.host),
+                "group": "OpenClaw",
+            }))
+        }
+        NotificationTargetKind::ServerChan { send_key } => client
+            .post(format!("https://sctapi.ftqq.com/{}.send", send_key))
+            .form(&[
+                ("title", payload.title.as_str()),
+                ("desp", &format!("{}\n\n主机: {}", payload.body, payload.host)),
+            ]),
+        NotificationTargetKind::Telegram { bot_token, chat_id } => client
+            .post(format!("https://api.telegram.org/bot{}/sendMessage", bot_token))
+            .json(&json!({
+                "chat_id": chat_id,
+                "text": text,
+            })),
+    };
+
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("请求失败: {}", e))?;
+    let status = response.status();
+    if status.is_success() {
+        Ok(())
+    } else {
+        let body = response.text().await.unwrap_or_default();
+        Err(format!("HTTP {}: {}", status.as_u16(), body.chars().take(200).collect::<String>()))
+    }
+}
+
+/// 获取通知设置
+#[guarded]
+#[command]
+pub async fn get_notification_settings() -> Result<NotificationSettings, String> {
+    info!("[通知] 读取通知设置...");
+    Ok(load_notification_settings())
+}
+
+/// 保存通知设置
+#[guarded]
+#[command]
+pub async fn save_notification_settings(mut settings: NotificationSettings) -> Result<String, String> {
+    info!("[通知] 保存通知设置...");
+    settings.secure_secrets()?;
+    settings::update(|manager_settings| {
+        manager_settings.notifications = settings;
+        Ok(())
+    })?;
+    info!("[通知] ✓ 通知设置已保存");
+    Ok("通知设置已保存".to_string())
+}
+
+/// 发送测试通知
+#[guarded]
+#[command]
+pub async fn send_test_notification(app: AppHandle) -> Result<String, String> {
+    info!("[通知] 发送测试通知...");
+    app.notification()
+        .builder()
+        .title("OpenClaw Manager")
+        .body("🦞 这是一条测试通知")
+        .show()
+        .map_err(|e| format!("发送通知失败: {}", e))?;
+    Ok("测试通知已发送".to_string())
+}
+
+/// 测试远程通知目标
+#[guarded]
+#[command]
+pub async fn test_notification_target(target: NotificationTarget) -> Result<String, String> {
+    info!("[通知] 测试远程通知目标: {}", target.name);
+    let payload = NotificationPayload::new(
+        NotificationTrigger::InstallFinished,
+        "OpenClaw Manager 测试通知",
+        "🦞 如果你收到这条消息，说明通知目标配置正确",
+    );
+    match send_to_target(&target, &payload).await {
+        Ok(_) => {
+            info!("[通知] ✓ 测试通知已发送到 {}", target.name);
+            Ok(format!("测试通知已发送到 {}", target.name))
+        }
+        Err(e) => {
+            error!("[通知] ✗ 测试通知发送失败: {}", e);
+            Err(format!("发送失败: {}", e))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hmac_matches_rfc4231_vector() {
+        assert_eq!(
+            hmac_sha256_hex("Jefe", "what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn target_parses_with_default_triggers() {
+        let target: NotificationTarget = serde_json::from_str(
+            r#"{"name":"ops","type":"webhook","url":"https://example.com/hook","secret":"s"}"#,
+        )
+        .unwrap();
+        assert!(target.enabled);
+        assert!(matches!(target.kind, NotificationTargetKind::Webhook { .. }));
+        assert_eq!(target.triggers, default_remote_triggers());
+    }
+
+    #[test]
+    fn refuses_secret_refs_outside_own_namespace() {
+        let target = |name: &str, key: &str| NotificationTarget {
+            name: name.to_string(),
+            enabled: true,
+            kind: NotificationTargetKind::Bark {
+                server: Some("https://attacker.example".to_string()),
+                device_key: key.to_string(),
+            },
+            triggers: default_remote_triggers(),
+        };
+        for key in [
+            "keychain:backup:passphrase",
+            "keychain:notify:other:device_key",
+            "secret-handle:0123",
+        ] {
+            assert!(target("ops", key).with_resolved_secrets().is_err());
+            let mut settings = NotificationSettings {
+                targets: vec![target("ops", key)],
+                ..Default::default()
+            };
+            assert!(settings.secure_secrets().is_err());
+        }
+        let mut settings = NotificationSettings {
+            targets: vec![target("ops", "keychain:notify:ops:device_key")],
+            ..Default::default()
+        };
+        assert_eq!(settings.secure_secrets(), Ok(0));
+    }
+}
@@ -0,0 +1,912 @@
+//! 需要浏览器授权的 Provider / 渠道（Google、Slack OAuth 应用等）的 OAuth 流程：
+//! 打开系统浏览器，在本机临时监听回调地址接收授权码，换取令牌后保存到系统钥匙串
+use crate::commands::notifications::{self, NotificationTrigger};
+use crate::commands::{config, config_lint, policy, settings, watcher};
+use crate::utils::{audit, file, http, platform, secrets};
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::time::Duration;
+use openclaw_macros::guarded;
+use tauri::command;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// 本地回调路径
+const CALLBACK_PATH: &str = "/oauth/callback";
+/// 等待用户在浏览器中完成授权的最长时间
+const CALLBACK_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+/// 单个回调连接发送请求行的最长时间
+const REQUEST_READ_TIMEOUT: Duration = Duration::from_secs(10);
+/// 令牌在过期前多久自动刷新（后台刷新和按需获取令牌共用）
+const REFRESH_MARGIN_SECS: i64 = 10 * 60;
+
+/// 串行化令牌刷新：部分 Provider 的 refresh_token 只能使用一次
+static REFRESH_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+fn default_true() -> bool {
+    true
+}
+
+/// OAuth 应用配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OAuthProviderConfig {
+    /// 连接 ID（同一 Provider 可以授权多个账号，如 google-work）
+    pub id: String,
+    pub name: String,
+    pub authorize_url: String,
+    pub token_url: String,
+    pub client_id: String,
+    /// 客户端密钥（保存后替换为钥匙串引用；公开客户端可为空）
+    #[serde(default)]
+    pub client_secret: Option<String>,
+    #[serde(default)]
+    pub scopes: Vec<String>,
+    /// 附加到授权地址的参数（如 Google 的 access_type=offline）
+    #[serde(default)]
+    pub extra_params: BTreeMap<String, String>,
+    /// 固定回调端口（应用只登记了固定回调地址时使用，为空时随机分配）
+    #[serde(default)]
+    pub redirect_port: Option<u16>,
+    /// 是否使用 PKCE
+    #[serde(default = "default_true")]
+    pub pkce: bool,
+    /// 授权 / 刷新后写入 access_token 的位置：openclaw.json 路径（如 channels.slack.userToken）
+    /// 或 `env:KEY`（~/.openclaw/.env）
+    #[serde(default)]
+    pub sync_to: Vec<String>,
+}
+
+/// 已授权的 OAuth 连接（令牌本身保存在钥匙串中）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OAuthConnection {
+    #[serde(flatten)]
+    pub provider: OAuthProviderConfig,
+    /// 令牌在钥匙串中的引用
+    pub tokens: String,
+    /// access_token 过期时间（Unix 秒，Provider 未返回时为空）
+    #[serde(default)]
+    pub expires_at: Option<i64>,
+    #[serde(default)]
+    pub has_refresh_token: bool,
+    /// Provider 实际授予的权限
+    #[serde(default)]
+    pub scope: Option<String>,
+    pub connected_at: i64,
+    #[serde(default)]
+    pub refreshed_at: Option<i64>,
+    /// 最近一次刷新或同步失败的原因（成功后清空）
+    #[serde(default)]
+    pub last_error: Option<String>,
+}
+
+/// 钥匙串中保存的令牌
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct StoredTokens {
+    access_token: String,
+    #[serde(default)]
+    refresh_token: Option<String>,
+    #[serde(default)]
+    token_type: Option<String>,
+}
+
+/// 令牌接口返回的结果
+#[derive(Debug, Clone, PartialEq)]
+struct TokenGrant {
+    tokens: StoredTokens,
+    expires_in: Option<i64>,
+    scope: Option<String>,
+}
+
+/// 回调请求的解析结果
+#[derive(Debug, PartialEq)]
+enum Callback {
+    Code(String),
+    Denied(String),
+    /// 与授权无关的请求（如 /favicon.ico）
+    Ignored,
+}
+
+/// 内置的 OAuth 应用模板（client_id 需由用户填写自己的应用）
+fn presets() -> Vec<OAuthProviderConfig> {
+    vec![
+        OAuthProviderConfig {
+            id: "google".to_string(),
+            name: "Google".to_string(),
+            authorize_url: "https://accounts.google.com/o/oauth2/v2/auth".to_string(),
+            token_url: "https://oauth2.googleapis.com/token".to_string(),
+            client_id: String::new(),
+            client_secret: None,
+            scopes: vec!["openid".to_string(), "email".to_string()],
+            // 不加 prompt=consent 时，重复授权不会再返回 refresh_token
+            extra_params: BTreeMap::from([
+                ("access_type".to_string(), "offline".to_string()),
+                ("prompt".to_string(), "consent".to_string()),
+            ]),
+            redirect_port: None,
+            pkce: true,
+            sync_to: Vec::new(),
+        },
+        OAuthProviderConfig {
+            id: "slack".to_string(),
+            name: "Slack".to_string(),
+            authorize_url: "https://slack.com/oauth/v2/authorize".to_string(),
+            token_url: "https://slack.com/api/oauth.v2.access".to_string(),
+            client_id: String::new(),
+            client_secret: None,
+            scopes: vec!["chat:write".to_string(), "channels:history".to_string()],
+            extra_params: BTreeMap::new(),
+            redirect_port: None,
+            pkce: false,
+            sync_to: Vec::new(),
+        },
+    ]
+}
+
+/// base64url 编码（无填充），用于 PKCE
+fn base64_url(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, b)| n | (*b as u32) << (16 - 8 * i));
+        for i in 0..=chunk.len() {
+            out.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+        }
+    }
+    out
+}
+
+/// PKCE code_challenge（S256）
+fn pkce_challenge(verifier: &str) -> String {
+    base64_url(&Sha256::digest(verifier.as_bytes()))
+}
+
+/// 拼接授权地址
+fn authorize_url(
+    provider: &OAuthProviderConfig,
+    redirect_uri: &str,
+    state: &str,
+    challenge: Option<&str>,
+) -> Result<reqwest::Url, String> {
+    let scope = provider.scopes.join(" ");
+    let mut params: Vec<(&str, &str)> = vec![
+        ("response_type", "code"),
+        ("client_id", &provider.client_id),
+        ("redirect_uri", redirect_uri),
+        ("state", state),
+    ];
+    if !scope.is_empty() {
+        params.push(("scope", &scope));
+    }
+    if let Some(challenge) = challenge {
+        params.push(("code_challenge", challenge));
+        params.push(("code_challenge_method", "S256"));
+    }
+    params.extend(
+        provider
+            .extra_params
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_str())),
+    );
+    reqwest::Url::parse_with_params(&provider.authorize_url, &params)
+        .map_err(|e| format!("授权地址无效: {}", e))
+}
+
+/// 解析回调请求行（"GET /oauth/callback?code=...&state=... HTTP/1.1"）并校验 state
+fn parse_callback(request_line: &str, expected_state: &str) -> Result<Callback, String> {
+    let target = request_line.split_whitespace().nth(1).unwrap_or_default();
+    let url = reqwest::Url::parse(&format!("http://127.0.0.1{}", target))
+        .map_err(|e| format!("回调请求无效: {}", e))?;
+    if url.path() != CALLBACK_PATH {
+        return Ok(Callback::Ignored);
+    }
+    let query: BTreeMap<String, String> = url.query_pairs().into_owned().collect();
+    if query.get("state").map(String::as_str) != Some(expected_state) {
+        return Err("回调 state 不匹配，已拒绝（可能是过期或伪造的授权请求）".to_string());
+    }
+    if let Some(error) = query.get("error") {
+        let detail = query.get("error_description").unwrap_or(error);
+        return Ok(Callback::Denied(detail.clone()));
+    }
+    match query.get("code").filter(|c| !c.is_empty()) {
+        Some(code) => Ok(Callback::Code(code.clone())),
+        None => Err("回调中没有授权码".to_string()),
+    }
+}
+
+/// 解析令牌接口的响应（兼容 Slack 的 ok/authed_user 格式）
+fn parse_token_response(body: &Value) -> Result<TokenGrant, String> {
+    if body.get("ok") == Some(&Value::Bool(false)) || body.get("error").is_some() {
+        let error = body
+            .get("error_description")
+            .or_else(|| body.get("error"))
+            .and_then(Value::as_str)
+            .unwrap_or("未知错误");
+        return Err(format!("Provider 拒绝了令牌请求: {}", error));
+    }
+    // Slack 只申请用户权限时，令牌在 authed_user 中
+    let source = match body.get("access_token") {
+        Some(_) => body,
+        None => body.get("authed_user").unwrap_or(body),
+    };
+    let field = |name: &str| source.get(name).and_then(Value::as_str).map(str::to_string);
+    let access_token = field("access_token").ok_or("响应中没有 access_token")?;
+    let expires_in = source.get("expires_in").and_then(|v| {
+        v.as_i64()
+            .or_else(|| v.as_str().and_then(|s| s.parse().ok()))
+    });
+    Ok(TokenGrant {
+        tokens: StoredTokens {
+            access_token,
+            refresh_token: field("refresh_token"),
+            token_type: field("token_type"),
+        },
+        expires_in,
+        scope: field("scope"),
+    })
+}
+
+/// 请求令牌接口
+async fn request_token(
+    provider: &OAuthProviderConfig,
+    params: &[(&str, &str)],
+) -> Result<TokenGrant, String> {
+    let secret = match provider.client_secret.as_deref().filter(|s| !s.is_empty()) {
+        Some(secret) => {
+            secrets::check_ref(secret, &client_secret_account(&provider.id))?;
+            Some(secrets::resolve_secret(secret)?)
+        }
+        None => None,
+    };
+    let mut form: Vec<(&str, &str)> = params.to_vec();
+    form.push(("client_id", &provider.client_id));
+    if let Some(secret) = secret.as_deref() {
+        form.push(("client_secret", secret));
+    }
+    let response = http::client()?
+        .post(&provider.token_url)
+        .header("Accept", "application/json")
+        .form(&form)
+        .send()
+        .await
+        .map_err(|e| format!("请求令牌失败: {}", e))?;
+    let status = response.status();
+    let text = response
+        .text()
+        .await
+        .map_err(|e| format!("读取令牌响应失败: {}", e))?;
+    match serde_json::from_str::<Value>(&text) {
+        Ok(body) => parse_token_response(&body),
+        Err(_) if !status.is_success() => Err(format!("令牌接口返回 HTTP {}", status.as_u16())),
+        Err(e) => Err(format!("令牌响应格式无效: {}", e)),
+    }
+}
+
+/// 回复浏览器一个简单页面
+async fn respond(stream: &mut TcpStream, status: &str, message: &str) {
+    let body = format!(
+        "<!doctype html><meta charset=\"utf-8\"><title>OpenClaw Manager</title><p>{}</p>",
+        message
+    );
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+    let _ = stream.shutdown().await;
+}
+
+/// 读取请求行；客户端迟迟不发送数据时在超时后放弃
+async fn read_request_line(stream: &mut TcpStream) -> String {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 1024];
+    let read = async {
+        while buf.len() < 8192 && !buf.windows(2).any(|w| w == b"\r\n") {
+            match stream.read(&mut chunk).await {
+                Ok(0) | Err(_) => break,
+                Ok(n) => buf.extend_from_slice(&chunk[..n]),
+            }
+        }
+    };
+    let _ = tokio::time::timeout(REQUEST_READ_TIMEOUT, read).await;
+    let request = String::from_utf8_lossy(&buf);
+    request.lines().next().unwrap_or_default().to_string()
+}
+
+/// 处理单个回调连接；与本次授权无关或无效的请求返回 None，继续等待
+async fn handle_callback(mut stream: TcpStream, state: String) -> Option<Result<String, String>> {
+    let line = read_request_line(&mut stream).await;
+    match parse_callback(&line, &state) {
+        Ok(Callback::Code(code)) => {
+            respond(
+                &mut stream,
+                "200 OK",
+                "授权成功，可以关闭此页面并返回 OpenClaw Manager。",
+            )
+            .await;
+            Some(Ok(code))
+        }
+        Ok(Callback::Denied(reason)) => {
+            respond(&mut stream, "200 OK", "授权已取消，可以关闭此页面。").await;
+            Some(Err(format!("授权被拒绝: {}", reason)))
+        }
+        Ok(Callback::Ignored) => {
+            respond(&mut stream, "404 Not Found", "Not Found").await;
+            None
+        }
+        Err(e) => {
+            warn!("[OAuth] 忽略无效的回调请求: {}", e);
+            respond(&mut stream, "400 Bad Request", &e).await;
+            None
+        }
+    }
+}
+
+/// 接收浏览器回调，返回授权码
+///
+/// 每个连接单独处理，空闲连接或 state 不匹配的请求不会阻塞、中断正在进行的授权
+async fn wait_for_code(listener: &TcpListener, state: &str) -> Result<String, String> {
+    let mut connections = tokio::task::JoinSet::new();
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => {
+                let (stream, _) = accepted.map_err(|e| format!("接收回调失败: {}", e))?;
+                connections.spawn(handle_callback(stream, state.to_string()));
+            }
+            Some(handled) = connections.join_next(), if !connections.is_empty() => {
+                if let Ok(Some(result)) = handled {
+                    return result;
+                }
+            }
+        }
+    }
+}
+
+/// 完整的授权流程：监听回调 → 打开浏览器 → 用授权码换取令牌
+async fn authorize(provider: &OAuthProviderConfig) -> Result<TokenGrant, String> {
+    let listener = TcpListener::bind(("127.0.0.1", provider.redirect_port.unwrap_or(0)))
+        .await
+        .map_err(|e| format!("监听本地回调端口失败: {}", e))?;
+    let port = listener
+        .local_addr()
+        .map_err(|e| format!("获取回调端口失败: {}", e))?
+        .port();
+    let redirect_uri = format!("http://127.0.0.1:{}{}", port, CALLBACK_PATH);
+    let state = uuid::Uuid::new_v4().simple().to_string();
+    // 64 个字符，满足 PKCE 43~128 字符的要求
+    let verifier = format!(
+        "{}{}",
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    );
+    let challenge = provider.pkce.then(|| pkce_challenge(&verifier));
+    let url = authorize_url(provider, &redirect_uri, &state, challenge.as_deref())?;
+
+    info!(
+        "[OAuth] 打开浏览器授权 {}，回调地址 {}",
+        provider.name, redirect_uri
+    );
+    if let Err(e) = open::that(url.as_str()) {
+        warn!("[OAuth] 无法打开浏览器 ({})，请手动访问: {}", e, url);
+    }
+
+    let code = tokio::time::timeout(CALLBACK_TIMEOUT, wait_for_code(&listener, &state))
+        .await
+        .map_err(|_| "等待浏览器授权超时，请重试".to_string())??;
+
+    let mut params = vec![
+        ("grant_type", "authorization_code"),
+        ("code", code.as_str()),
+        ("redirect_uri", redirect_uri.as_str()),
+    ];
+    if provider.pkce {
+        params.push(("code_verifier", &verifier));
+    }
+    request_token(provider, &params).await
+}
+
+/// 令牌在钥匙串中的账号名
+fn tokens_account(id: &str) -> String {
+    format!("oauth:{}:tokens", id)
+}
+
+/// 客户端密钥在钥匙串中的账号名
+fn client_secret_account(id: &str) -> String {
+    format!("oauth:{}:client_secret", id)
+}
+
+/// 检查前端提交的应用配置：客户端密钥为钥匙串引用时（重新授权），
+/// 只能是该连接已保存的密钥，且令牌接口与保存时一致，避免密钥被发送到调用方指定的地址
+fn check_client_secret(provider: &OAuthProviderConfig) -> Result<(), String> {
+    let Some(secret) = provider.client_secret.as_deref() else {
+        return Ok(());
+    };
+    secrets::check_ref(secret, &client_secret_account(&provider.id))?;
+    if !secrets::is_keychain_ref(secret) {
+        return Ok(());
+    }
+    let saved = connection(&provider.id).is_some_and(|c| {
+        c.provider.client_secret.as_deref() == Some(secret)
+            && c.provider.token_url == provider.token_url
+    });
+    if saved {
+        Ok(())
+    } else {
+        Err("已保存的 client_secret 只能用于原来的令牌接口，请重新输入 client_secret".to_string())
+    }
+}
+
+fn read_tokens(connection: &OAuthConnection) -> Result<StoredTokens, String> {
+    let raw = secrets::resolve_secret(&connection.tokens)?;
+    serde_json::from_str(&raw).map_err(|e| format!("钥匙串中的令牌已损坏: {}", e))
+}
+
+/// 保存令牌到钥匙串并更新连接信息
+fn store_grant(connection: &mut OAuthConnection, grant: TokenGrant) -> Result<(), String> {
+    let raw = serde_json::to_string(&grant.tokens).map_err(|e| e.to_string())?;
+    connection.tokens = secrets::store_secret(&tokens_account(&connection.provider.id), &raw)?;
+    connection.expires_at = grant
+        .expires_in
+        .map(|secs| chrono::Utc::now().timestamp() + secs);
+    connection.has_refresh_token = grant.tokens.refresh_token.is_some();
+    if grant.scope.is_some() {
+        connection.scope = grant.scope;
+    }
+    Ok(())
+}
+
+/// 检查同步目标的格式
+fn validate_target(target: &str) -> Result<(), String> {
+    let valid = match target.strip_prefix("env:") {
+        Some(key) => {
+            !key.is_empty()
+                && !key.starts_with(|c: char| c.is_ascii_digit())
+                && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        }
+        None => !target.is_empty() && target.split('.').all(|k| !k.trim().is_empty()),
+    };
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "同步目标无效: {}（应为配置路径或 env:KEY）",
+            target
+        ))
+    }
+}
+
+/// 将 access_token 写入 openclaw.json 中的目标路径
+/// 返回配置是否有变化，以及需要写入 .env 的键
+fn apply_targets(
+    config: &mut Value,
+    targets: &[String],
+    token: &str,
+) -> Result<(bool, Vec<String>), String> {
+    let mut changed = false;
+    let mut env_keys = Vec::new();
+    for target in targets {
+        validate_target(target)?;
+        if let Some(key) = target.strip_prefix("env:") {
+            env_keys.push(key.to_string());
+            continue;
+        }
+        let path: Vec<String> = target.split('.').map(str::to_string).collect();
+        if config
+            .pointer(&format!("/{}", path.join("/")))
+            .and_then(Value::as_str)
+            == Some(token)
+        {
+            continue;
+        }
+        if !config_lint::insert(config, &path, json!(token)) {
+            return Err(format!("无法写入 {}：路径上存在非对象的值", target));
+        }
+        changed = true;
+    }
+    Ok((changed, env_keys))
+}
+
+/// 将新的 access_token 同步到 Gateway 配置和 .env（配置文件原子写入，Gateway 自动重新加载）
+async fn sync_credentials(provider: &OAuthProviderConfig, token: &str) -> Result<(), String> {
+    if provider.sync_to.is_empty() {
+        return Ok(());
+    }
+    let mut cfg = config::load_openclaw_config()?;
+    let (changed, env_keys) = apply_targets(&mut cfg, &provider.sync_to, token)?;
+    if changed {
+        config::save_config(cfg).await?;
+    }
+    let env_path = platform::get_env_file_path();
+    for key in env_keys {
+        if file::read_env_value(&env_path, &key).as_deref() == Some(token) {
+            continue;
+        }
+        watcher::note_internal_write(&env_path);
+        file::set_env_value(&env_path, &key, token)
+            .map_err(|e| format!("写入环境变量 {} 失败: {}", key, e))?;
+    }
+    debug!(
+        "[OAuth] 已同步 {} 的令牌到 {:?}",
+        provider.id, provider.sync_to
+    );
+    Ok(())
+}
+
+/// 保存或替换连接
+fn save_connection(connection: &OAuthConnection) -> Result<(), String> {
+    settings::update(|current| {
+        current
+            .oauth
+            .retain(|c| c.provider.id != connection.provider.id);
+        current.oauth.push(connection.clone());
+        Ok(())
+    })
+}
+
+/// 按 ID 查找已授权的连接
+pub fn connection(id: &str) -> Option<OAuthConnection> {
+    settings::load_settings()
+        .oauth
+        .into_iter()
+        .find(|c| c.provider.id == id)
+}
+
+/// 使用 refresh_token 刷新令牌
+async fn refresh(id: &str) -> Result<OAuthConnection, String> {
+    let mut connection = connection(id).ok_or_else(|| format!("未找到 OAuth 连接: {}", id))?;
+    let tokens = read_tokens(&connection)?;
+    let refresh_token = tokens.refresh_token.clone().ok_or_else(|| {
+        format!(
+            "{} 没有 refresh_token，请重新授权",
+            connection.provider.name
+        )
+    })?;
+    let mut grant = request_token(
+        &connection.provider,
+        &[
+            ("grant_type", "refresh_token"),
+            ("refresh_token", refresh_token.as_str()),
+        ],
+    )
+    .await?;
+    // 多数 Provider 刷新时不返回新的 refresh_token，沿用原来的
+    if grant.tokens.refresh_token.is_none() {
+        grant.tokens.refresh_token = Some(refresh_token);
+    }
+    let access_token = grant.tokens.access_token.clone();
+    store_grant(&mut connection, grant)?;
+    connection.refreshed_at = Some(chrono::Utc::now().timestamp());
+    // 同步失败时仍保存新令牌（refresh_token 可能已轮换），由后台刷新重试同步
+    let synced = sync_credentials(&connection.provider, &access_token).await;
+    connection.last_error = synced.as_ref().err().cloned();
+    save_connection(&connection)?;
+    synced?;
+    info!("[OAuth] ✓ 已刷新 {} 的令牌", connection.provider.id);
+    Ok(connection)
+}
+
+/// 记录刷新失败并通知，避免渠道在令牌过期后悄悄停止工作
+fn report_failure(connection: &OAuthConnection, error: &str) {
+    warn!(
+        "[OAuth] ✗ {} 的令牌刷新失败: {}",
+        connection.provider.id, error
+    );
+    // 重新读取：刷新可能已保存了新令牌，只是同步失败
+    if let Some(mut current) = self::connection(&connection.provider.id) {
+        current.last_error = Some(error.to_string());
+        if let Err(e) = save_connection(&current) {
+            warn!("[OAuth] 保存连接状态失败: {}", e);
+        }
+    }
+    notifications::notify(
+        NotificationTrigger::CredentialRefreshFailed,
+        &connection.provider.id,
+        &format!("{} 令牌刷新失败", connection.provider.name),
+        &format!(
+            "{}。令牌过期后相关渠道将无法工作，请在 Manager 中重新授权",
+            error
+        ),
+    );
+}
+
+/// 后台刷新：令牌即将过期或上次同步失败时刷新并同步到 Gateway，失败时通知
+pub async fn refresh_expiring() {
+    let now = chrono::Utc::now().timestamp();
+    for connection in settings::load_settings().oauth {
+        let expiring = connection
+            .expires_at
+            .is_some_and(|at| at - now < REFRESH_MARGIN_SECS);
+        if !expiring && connection.last_error.is_none() {
+            continue;
+        }
+        if !connection.has_refresh_token {
+            if connection.expires_at.is_some_and(|at| at <= now) {
+                report_failure(&connection, "令牌已过期且没有 refresh_token");
+            }
+            continue;
+        }
+        let result = {
+            let _lock = REFRESH_LOCK.lock().await;
+            refresh(&connection.provider.id).await
+        };
+        if let Err(e) = result {
+            report_failure(&connection, &e);
+        }
+    }
+}
+
+/// 获取可用的 access_token，即将过期时自动刷新
+pub async fn access_token(id: &str) -> Result<String, String> {
+    let _lock = REFRESH_LOCK.lock().await;
+    let mut connection = connection(id).ok_or_else(|| format!("未找到 OAuth 连接: {}", id))?;
+    let expiring = connection
+        .expires_at
+        .is_some_and(|at| at - chrono::Utc::now().timestamp() < REFRESH_MARGIN_SECS);
+    if expiring && connection.has_refresh_token {
+        connection = refresh(id).await?;
+    }
+    Ok(read_tokens(&connection)?.access_token)
+}
+
+/// 内置的 OAuth 应用模板
+#[guarded]
+#[command]
+pub async fn list_oauth_presets() -> Result<Vec<OAuthProviderConfig>, String> {
+    Ok(presets())
+}
+
+/// 已授权的 OAuth 连接
+#[guarded]
+#[command]
+pub async fn list_oauth_connections() -> Result<Vec<OAuthConnection>, String> {
+    Ok(settings::load_settings().oauth)
+}
+
+/// 打开浏览器进行授权，完成后保存令牌（同一 ID 的连接会被替换）
+#[guarded]
+#[command]
+pub async fn start_oauth_flow(provider: OAuthProviderConfig) -> Result<OAuthConnection, String> {
+    let mut provider = provider;
+    if provider.id.trim().is_empty() || provider.client_id.trim().is_empty() {
+        return Err("连接 ID 和 client_id 不能为空".to_string());
+    }
+    provider
+        .sync_to
+        .iter()
+        .try_for_each(|t| validate_target(t))?;
+    check_client_secret(&provider)?;
+    if !secrets::keychain_available() {
+        return Err("系统钥匙串不可用，无法安全保存令牌".to_string());
+    }
+    let grant = match authorize(&provider).await {
+        Ok(grant) => grant,
+        Err(e) => {
+            audit::record(
+                "start_oauth_flow",
+                &provider.id,
+                false,
+                json!({ "error": e }),
+            );
+            return Err(e);
+        }
+    };
+    // 客户端密钥也移入钥匙串
+    if let Some(secret) = provider
+        .client_secret
+        .as_mut()
+        .filter(|s| !s.is_empty() && !secrets::is_keychain_ref(s))
+    {
+        *secret = secrets::store_secret(&client_secret_account(&provider.id), secret)?;
+    }
+    let mut connection = OAuthConnection {
+        provider,
+        tokens: String::new(),
+        expires_at: None,
+        has_refresh_token: false,
+        scope: None,
+        connected_at: chrono::Utc::now().timestamp(),
+        refreshed_at: None,
+        last_error: None,
+    };
+    let access_token = grant.tokens.access_token.clone();
+    store_grant(&mut connection, grant)?;
+    save_connection(&connection)?;
+    sync_credentials(&connection.provider, &access_token).await?;
+    audit::record(
+        "start_oauth_flow",
+        &connection.provider.id,
+        true,
+        json!({ "scope": connection.scope, "refresh_token": connection.has_refresh_token }),
+    );
+    info!("[OAuth] ✓ {} 授权完成", connection.provider.name);
+    Ok(connection)
+}
+
+/// 立即刷新令牌
+#[guarded]
+#[command]
+pub async fn refresh_oauth_token(id: String) -> Result<OAuthConnection, String> {
+    let _lock = REFRESH_LOCK.lock().await;
+    refresh(&id).await
+}
+
+/// 检查连接的令牌是否可用（即将过期时自动刷新）
+#[guarded]
+#[command]
+pub async fn check_oauth_connection(id: String) -> Result<OAuthConnection, String> {
+    access_token(&id).await?;
+    connection(&id).ok_or_else(|| format!("未找到 OAuth 连接: {}", id))
+}
+
+/// 修改令牌的同步目标，并立即写入当前令牌
+#[guarded]
+#[command]
+pub async fn set_oauth_sync_targets(
+    id: String,
+    targets: Vec<String>,
+) -> Result<OAuthConnection, String> {
+    targets.iter().try_for_each(|t| validate_target(t))?;
+    let token = access_token(&id).await?;
+    let mut connection = connection(&id).ok_or_else(|| format!("未找到 OAuth 连接: {}", id))?;
+    connection.provider.sync_to = targets;
+    sync_credentials(&connection.provider, &token).await?;
+    connection.last_error = None;
+    save_connection(&connection)?;
+    audit::record(
+        "set_oauth_sync_targets",
+        &id,
+        true,
+        json!({ "targets": connection.provider.sync_to }),
+    );
+    Ok(connection)
+}
+
+/// 断开连接并删除钥匙串中的令牌（需要确认令牌）
+#[guarded]
+#[command]
+pub async fn disconnect_oauth(id: String, confirm_token: Option<String>) -> Result<String, String> {
+    policy::require_confirmation("disconnect_oauth", confirm_token.as_deref())?;
+    let removed = settings::update(|current| {
+        let position = current
+            .oauth
+            .iter()
+            .position(|c| c.provider.id == id)
+            .ok_or_else(|| format!("未找到 OAuth 连接: {}", id))?;
+        Ok(current.oauth.remove(position))
+    })?;
+    for reference in
+        std::iter::once(&removed.tokens).chain(removed.provider.client_secret.as_ref())
+    {
+        if let Err(e) = secrets::delete_secret(reference) {
+            warn!("[OAuth] 删除钥匙串条目失败: {}", e);
+        }
+    }
+    audit::record("disconnect_oauth", &id, true, json!({}));
+    info!("[OAuth] 已断开 {}", id);
+    Ok(format!("已断开 {}", removed.provider.name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pkce_challenge_is_unpadded_base64url_sha256() {
+        assert_eq!(base64_url(b"ab"), "YWI");
+        assert_eq!(
+            pkce_challenge("dBjftJeZ4CVP-mJ92IFQcwAy2BoGtk6vRaa3d4GJmPk"),
+            "TijkLg09gGbM0L7cqbt8CmS97_R3cAwCV_mWxfWKCoQ"
+        );
+    }
+
+    #[test]
+    fn parses_callback_and_token_responses() {
+        let line = "GET /oauth/callback?code=4%2F0Ab&state=abc HTTP/1.1";
+        assert_eq!(
+            parse_callback(line, "abc"),
+            Ok(Callback::Code("4/0Ab".to_string()))
+        );
+        assert!(parse_callback(line, "other").is_err());
+        assert_eq!(
+            parse_callback("GET /favicon.ico HTTP/1.1", "abc"),
+            Ok(Callback::Ignored)
+        );
+        assert_eq!(
+            parse_callback(
+                "GET /oauth/callback?error=access_denied&state=abc HTTP/1.1",
+                "abc"
+            ),
+            Ok(Callback::Denied("access_denied".to_string()))
+        );
+
+        let grant = parse_token_response(&json!({
+            "access_token": "ya29", "refresh_token": "1//r", "expires_in": 3599, "token_type": "Bearer"
+        }))
+        .unwrap();
+        assert_eq!(grant.expires_in, Some(3599));
+        assert_eq!(grant.tokens.refresh_token.as_deref(), Some("1//r"));
+
+        let slack = parse_token_response(&json!({
+            "ok": true, "authed_user": { "access_token": "xoxp-1", "scope": "chat:write" }
+        }))
+        .unwrap();
+        assert_eq!(slack.tokens.access_token, "xoxp-1");
+        assert_eq!(slack.scope.as_deref(), Some("chat:write"));
+        assert!(parse_token_response(&json!({ "ok": false, "error": "invalid_code" })).is_err());
+    }
+
+    #[test]
+    fn refuses_foreign_client_secret_refs() {
+        let mut provider = presets().remove(0);
+        provider.token_url = "https://attacker.example/token".to_string();
+        for secret in [
+            "keychain:backup:passphrase",
+            "keychain:oauth:google:tokens",
+            "keychain:oauth:slack:client_secret",
+            "secret-handle:0123",
+        ] {
+            provider.client_secret = Some(secret.to_string());
+            assert!(check_client_secret(&provider).is_err());
+        }
+        provider.client_secret = Some("plain-secret".to_string());
+        assert!(check_client_secret(&provider).is_ok());
+    }
+
+    #[test]
+    fn applies_tokens_to_config_and_env_targets() {
+        let mut cfg = json!({ "channels": { "slack": { "enabled": true } } });
+        let targets = vec![
+            "channels.slack.userToken".to_string(),
+            "env:GOOGLE_ACCESS_TOKEN".to_string(),
+        ];
+        let (changed, env) = apply_targets(&mut cfg, &targets, "xoxp-2").unwrap();
+        assert!(changed);
+        assert_eq!(env, vec!["GOOGLE_ACCESS_TOKEN".to_string()]);
+        assert_eq!(cfg["channels"]["slack"]["userToken"], "xoxp-2");
+        assert_eq!(cfg["channels"]["slack"]["enabled"], true);
+        // 令牌未变化时不重写配置
+        assert!(!apply_targets(&mut cfg, &targets, "xoxp-2").unwrap().0);
+
+        assert!(validate_target("env:1BAD").is_err());
+        assert!(validate_target("channels..token").is_err());
+        let mut scalar = json!({ "channels": "x" });
+        assert!(apply_targets(&mut scalar, &targets[..1], "t").is_err());
+    }
+
+    #[test]
+    fn keeps_waiting_past_idle_and_mismatched_callbacks() {
+        tokio::runtime::Runtime::new().unwrap().block_on(async {
+            let listener = TcpListener::bind(("127.0.0.1", 0)).await.unwrap();
+            let addr = listener.local_addr().unwrap();
+            let client = tokio::spawn(async move {
+                // 只连接不发送请求的客户端不应阻塞后续回调
+                let idle = TcpStream::connect(addr).await.unwrap();
+                for state in ["forged", "expected"] {
+                    let mut stream = TcpStream::connect(addr).await.unwrap();
+                    let request = format!(
+                        "GET {}?code=c-{}&state={} HTTP/1.1\r\nHost: localhost\r\n\r\n",
+                        CALLBACK_PATH, state, state
+                    );
+                    stream.write_all(request.as_bytes()).await.unwrap();
+                    let mut response = String::new();
+                    stream.read_to_string(&mut response).await.unwrap();
+                }
+                idle
+            });
+            let code =
+                tokio::time::timeout(Duration::from_secs(5), wait_for_code(&listener, "expected"))
+                    .await
+                    .expect("空闲连接阻塞了回调")
+                    .unwrap();
+            assert_eq!(code, "c-expected");
+            drop(client.await.unwrap());
+        });
+    }
+}
@@ -0,0 +1,479 @@
+//! 残留进程清理：Manager 崩溃或被强制结束后，之前启动的 Gateway / Node 进程可能仍在运行并占用端口，
+//! 再次启动时报“端口已被占用”。辅助进程启动时在 Manager 数据目录记录 PID 文件（Gateway 使用
+//! 配置目录中的锁文件，见 gateway_lock），检查时找出以下进程，用户确认后通过 cleanup_orphans 结束：
+//! - PID 文件来自之前的 Manager 会话且进程仍在运行
+//! - 占用 Gateway 端口、父进程已退出且不再响应的进程
+//! - 父进程未回收的僵尸进程（结束其父进程后由系统回收）
+use crate::commands::{gateway_lock, policy, service};
+use crate::models::DiagnosticResult;
+use crate::utils::{audit, file, platform, shell, time};
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use openclaw_macros::guarded;
+use tauri::command;
+
+/// 清理残留进程的修复项 ID
+pub const CLEANUP_ORPHANS_FIX: &str = "cleanup_orphans";
+/// OpenClaw Gateway 的默认端口
+const DEFAULT_GATEWAY_PORT: u16 = 18789;
+/// 结束进程时等待其退出的时间，超时后强制结束
+const TERMINATE_GRACE: Duration = Duration::from_secs(3);
+
+/// Manager 启动的进程（保存在数据目录的 pids/<name>.json）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PidFile {
+    pub name: String,
+    pub pid: u32,
+    /// 启动该进程的 Manager 进程 PID
+    pub manager_pid: u32,
+    pub started_at: String,
+}
+
+/// 进程表中的一项
+#[derive(Debug, Clone, PartialEq)]
+struct ProcessInfo {
+    pid: u32,
+    ppid: u32,
+    /// 进程状态（ps 的 STAT 列，Z 开头为僵尸进程；Windows 上为 -）
+    state: String,
+    command: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OrphanKind {
+    /// 之前的 Manager 会话启动、仍在运行的进程
+    StalePidfile,
+    /// 占用 Gateway 端口、父进程已退出且不响应的进程
+    PortHolder,
+    /// 未被父进程回收的僵尸进程
+    Zombie,
+}
+
+/// 残留进程
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrphanProcess {
+    pub pid: u32,
+    pub ppid: u32,
+    pub command: String,
+    pub kind: OrphanKind,
+    pub reason: String,
+    /// 清理时结束的进程（僵尸进程为其父进程，无法清理时为 None）
+    pub terminate_pid: Option<u32>,
+}
+
+/// 清理结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OrphanCleanup {
+    pub terminated: Vec<u32>,
+    pub errors: Vec<String>,
+}
+
+fn pid_dir() -> PathBuf {
+    Path::new(&platform::get_manager_data_dir()).join("pids")
+}
+
+fn pid_path(name: &str) -> PathBuf {
+    pid_dir().join(format!("{}.json", name))
+}
+
+/// 记录 Manager 启动的进程
+pub fn record_pid(name: &str, pid: u32) {
+    let entry = PidFile {
+        name: name.to_string(),
+        pid,
+        manager_pid: std::process::id(),
+        started_at: time::now_rfc3339(),
+    };
+    let result = serde_json::to_vec_pretty(&entry)
+        .map_err(|e| e.to_string())
+        .and_then(|content| {
+            file::write_atomic(&pid_path(name), &content).map_err(|e| e.to_string())
+        });
+    if let Err(e) = result {
+        warn!("[残留进程] 记录 {} 的 PID 文件失败: {}", name, e);
+    }
+}
+
+/// 进程退出后删除 PID 文件（PID 已被新进程覆盖时保留）
+pub fn remove_pid(name: &str, pid: u32) {
+    let path = pid_path(name);
+    let current = std::fs::read_to_string(&path)
+        .ok()
+        .and_then(|content| serde_json::from_str::<PidFile>(&content).ok());
+    if current.is_some_and(|entry| entry.pid == pid) {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+fn load_pidfiles() -> Vec<PidFile> {
+    let mut pidfiles: Vec<PidFile> = std::fs::read_dir(pid_dir())
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| std::fs::read_to_string(entry.path()).ok())
+        .filter_map(|content| serde_json::from_str(&content).ok())
+        .collect();
+    // Manager 启动的 Gateway 记录在锁文件中（外部脚本启动的不处理）
+    if let Some(lock) = gateway_lock::clear_stale() {
+        if let Some(manager_pid) = lock.manager_pid {
+            pidfiles.push(PidFile {
+                name: "gateway".to_string(),
+                pid: lock.pid,
+                manager_pid,
+                started_at: lock.locked_at,
+            });
+        }
+    }
+    pidfiles
+}
+
+/// 解析进程表（每行：PID PPID 状态 命令行）
+fn parse_process_table(output: &str) -> Vec<ProcessInfo> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let pid = parts.next()?.parse().ok()?;
+            let ppid = parts.next()?.parse().ok()?;
+            let state = parts.next()?.to_string();
+            Some(ProcessInfo {
+                pid,
+                ppid,
+                state,
+                command: parts.collect::<Vec<_>>().join(" "),
+            })
+        })
+        .collect()
+}
+
+fn process_table() -> Vec<ProcessInfo> {
+    let output = if platform::is_windows() {
+        shell::run_powershell_output(
+            "Get-CimInstance Win32_Process | ForEach-Object { $c = if ($_.CommandLine) { $_.CommandLine } else { $_.Name }; \"$($_.ProcessId) $($_.ParentProcessId) - $c\" }",
+        )
+    } else {
+        shell::run_command_output("ps", &["-axo", "pid=,ppid=,stat=,command="])
+    };
+    match output {
+        Ok(output) => parse_process_table(&output),
+        Err(e) => {
+            debug!("[残留进程] 读取进程列表失败: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// 是否为 OpenClaw Gateway 或 Node 进程（只清理这类进程）
+fn is_openclaw_process(command: &str) -> bool {
+    let lower = command.to_lowercase();
+    let program = lower.split_whitespace().next().unwrap_or_default();
+    let name = program.rsplit(['/', '\\']).next().unwrap_or_default();
+    lower.contains("openclaw") || matches!(name, "node" | "node.exe")
+}
+
+/// 父进程是否已退出（被 init / launchd 收养，或父进程已不存在）
+fn parent_gone(process: &ProcessInfo, table: &[ProcessInfo]) -> bool {
+    process.ppid <= 1 || !table.iter().any(|p| p.pid == process.ppid)
+}
+
+/// 按进程表、PID 文件和端口占用找出残留进程。
+/// `gateway_responding` 为 true 时端口占用进程视为仍在使用（上次会话留下的 Gateway 会被直接接管）
+fn classify(
+    table: &[ProcessInfo],
+    pidfiles: &[PidFile],
+    port_holders: &[(u16, u32)],
+    gateway_responding: bool,
+    manager_pid: u32,
+) -> Vec<OrphanProcess> {
+    let in_use = |pid: u32| gateway_responding && port_holders.iter().any(|(_, p)| *p == pid);
+    let find = |pid: u32| table.iter().find(|p| p.pid == pid);
+    let mut orphans: Vec<OrphanProcess> = Vec::new();
+    let mut push = |orphan: OrphanProcess| {
+        if !orphans.iter().any(|o| o.pid == orphan.pid) {
+            orphans.push(orphan);
+        }
+    };
+
+    for entry in pidfiles {
+        if entry.manager_pid == manager_pid || in_use(entry.pid) {
+            continue;
+        }
+        // PID 可能已被其他进程复用，命令行不匹配时不处理
+        let Some(process) = find(entry.pid).filter(|p| is_openclaw_process(&p.command)) else {
+            continue;
+        };
+        push(OrphanProcess {
+            pid: process.pid,
+            ppid: process.ppid,
+            command: process.command.clone(),
+            kind: OrphanKind::StalePidfile,
+            reason: format!(
+                "{} 由之前的 Manager 会话（PID {}）于 {} 启动，仍在运行",
+                entry.name, entry.manager_pid, entry.started_at
+            ),
+            terminate_pid: Some(process.pid),
+        });
+    }
+
+    for (port, pid) in port_holders.iter().filter(|(_, pid)| !in_use(*pid)) {
+        let Some(process) = find(*pid) else {
+            continue;
+        };
+        // 端口占用进程只处理命令行中明确是 OpenClaw 的
+        if process.ppid == manager_pid
+            || !process.command.to_lowercase().contains("openclaw")
+            || !parent_gone(process, table)
+        {
+            continue;
+        }
+        push(OrphanProcess {
+            pid: process.pid,
+            ppid: process.ppid,
+            command: process.command.clone(),
+            kind: OrphanKind::PortHolder,
+            reason: format!("占用端口 {}，父进程已退出且 Gateway 无响应", port),
+            terminate_pid: Some(process.pid),
+        });
+    }
+
+    for process in table.iter().filter(|p| p.state.starts_with('Z')) {
+        let Some(parent) = find(process.ppid).filter(|p| is_openclaw_process(&p.command)) else {
+            continue;
+        };
+        if !is_openclaw_process(&process.command) && !process.command.contains("defunct") {
+            continue;
+        }
+        push(OrphanProcess {
+            pid: process.pid,
+            ppid: process.ppid,
+            command: process.command.clone(),
+            kind: OrphanKind::Zombie,
+            reason: format!("僵尸进程，父进程 {} 未回收", parent.pid),
+            // 父进程是 Manager 自身时无法通过结束父进程回收
+            terminate_pid: (parent.pid != manager_pid).then_some(parent.pid),
+        });
+    }
+    orphans
+}
+
+/// 查找残留进程（阻塞，会读取进程列表并检查 Gateway 是否响应）
+pub fn scan() -> Vec<OrphanProcess> {
+    let table = process_table();
+    let pidfiles = load_pidfiles();
+    // 清理进程已退出的 PID 文件
+    for entry in &pidfiles {
+        if entry.manager_pid != std::process::id() && !table.iter().any(|p| p.pid == entry.pid) {
+            remove_pid(&entry.name, entry.pid);
+        }
+    }
+
+    let mut ports = vec![DEFAULT_GATEWAY_PORT, service::SERVICE_PORT];
+    ports.dedup();
+    let port_holders: Vec<(u16, u32)> = ports
+        .into_iter()
+        .filter_map(|port| service::check_port_listening(port).map(|pid| (port, pid)))
+        .collect();
+    let gateway_responding =
+        !port_holders.is_empty() && shell::run_openclaw(&["health", "--timeout", "2000"]).is_ok();
+    classify(
+        &table,
+        &pidfiles,
+        &port_holders,
+        gateway_responding,
+        std::process::id(),
+    )
+}
+
+fn wait_for_exit(pids: &HashSet<u32>, timeout: Duration) -> HashSet<u32> {
+    let started = Instant::now();
+    loop {
+        let remaining: HashSet<u32> = process_table()
+            .into_iter()
+            .filter(|p| pids.contains(&p.pid) && !p.state.starts_with('Z'))
+            .map(|p| p.pid)
+            .collect();
+        if remaining.is_empty() || started.elapsed() >= timeout {
+            return remaining;
+        }
+        std::thread::sleep(Duration::from_millis(300));
+    }
+}
+
+/// 结束残留进程：只处理本次检查仍判定为残留的进程（`pids` 为空时处理全部），先正常结束，超时后强制结束
+pub fn cleanup(pids: &[u32]) -> OrphanCleanup {
+    let orphans = scan();
+    let mut result = OrphanCleanup::default();
+    for pid in pids {
+        if !orphans.iter().any(|o| o.pid == *pid) {
+            result
+                .errors
+                .push(format!("PID {} 不是残留进程或已退出", pid));
+        }
+    }
+    let selected: Vec<&OrphanProcess> = orphans
+        .iter()
+        .filter(|o| pids.is_empty() || pids.contains(&o.pid))
+        .collect();
+    let targets: HashSet<u32> = selected.iter().filter_map(|o| o.terminate_pid).collect();
+    for orphan in selected.iter().filter(|o| o.terminate_pid.is_none()) {
+        result.errors.push(format!(
+            "PID {} 的父进程是 Manager 自身，重启 Manager 后会被回收",
+            orphan.pid
+        ));
+    }
+    if targets.is_empty() {
+        return result;
+    }
+
+    for pid in &targets {
+        info!("[残留进程] 结束 PID {}", pid);
+        shell::terminate_process(*pid, false);
+    }
+    let mut remaining = wait_for_exit(&targets, TERMINATE_GRACE);
+    if !remaining.is_empty() {
+        for pid in &remaining {
+            warn!("[残留进程] PID {} 未退出，强制结束", pid);
+            shell::terminate_process(*pid, true);
+        }
+        remaining = wait_for_exit(&remaining, TERMINATE_GRACE);
+    }
+    for pid in &targets {
+        if remaining.contains(pid) {
+            result.errors.push(format!("无法结束 PID {}", pid));
+        } else {
+            result.terminated.push(*pid);
+        }
+    }
+    result.terminated.sort_unstable();
+    audit::record(
+        "cleanup_orphans",
+        "processes",
+        result.errors.is_empty(),
+        json!({ "orphans": selected, "terminated": result.terminated, "errors": result.errors }),
+    );
+    result
+}
+
+/// 诊断项：残留进程（没有时不显示）
+pub fn diagnostic() -> Option<DiagnosticResult> {
+    let orphans = scan();
+    if orphans.is_empty() {
+        return None;
+    }
+    let summary: Vec<String> = orphans
+        .iter()
+        .map(|o| format!("PID {}：{}", o.pid, o.reason))
+        .collect();
+    Some(DiagnosticResult {
+        name: "残留进程".to_string(),
+        passed: false,
+        message: format!(
+            "发现 {} 个残留进程（{}）",
+            orphans.len(),
+            summary.join("；")
+        ),
+        suggestion: Some(
+            "之前的 Manager 会话留下的进程会占用 Gateway 端口，确认后可一键结束".to_string(),
+        ),
+        fix: Some(CLEANUP_ORPHANS_FIX.to_string()),
+    })
+}
+
+/// 修复项：结束全部残留进程
+pub async fn cleanup_all() -> Result<String, String> {
+    let result = tokio::task::spawn_blocking(|| cleanup(&[]))
+        .await
+        .map_err(|e| e.to_string())?;
+    if !result.errors.is_empty() {
+        return Err(result.errors.join("；"));
+    }
+    Ok(format!("已结束 {} 个残留进程", result.terminated.len()))
+}
+
+/// 查找残留的 Gateway / Node 进程
+#[guarded]
+#[command]
+pub async fn get_orphan_processes() -> Result<Vec<OrphanProcess>, String> {
+    tokio::task::spawn_blocking(scan)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 结束用户确认的残留进程（`pids` 为空时结束全部）
+#[guarded]
+#[command]
+pub async fn cleanup_orphans(
+    pids: Vec<u32>,
+    confirm_token: Option<String>,
+) -> Result<OrphanCleanup, String> {
+    policy::require_confirmation("cleanup_orphans", confirm_token.as_deref())?;
+    tokio::task::spawn_blocking(move || cleanup(&pids))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_stale_and_unresponsive_processes() {
+        let table = parse_process_table(
+            "    1     0 Ss   /sbin/init\n\
+             \x20 500     1 Sl   node /usr/lib/node_modules/openclaw/openclaw.mjs gateway --port 18789\n\
+             \x20 600     1 Sl   /usr/bin/node /opt/other/server.js\n\
+             \x20 700   900 Sl   node /usr/lib/node_modules/openclaw/openclaw.mjs gateway\n\
+             \x20 900     1 Sl   /opt/OpenClaw Manager/openclaw-manager\n\
+             \x20 901   700 Z    [node] <defunct>\n\
+             \x20 800     1 Ss   nginx: master process\n",
+        );
+        assert_eq!(table.len(), 7);
+        assert_eq!(
+            table[1].command,
+            "node /usr/lib/node_modules/openclaw/openclaw.mjs gateway --port 18789"
+        );
+
+        let pidfile = |name: &str, pid: u32, manager_pid: u32| PidFile {
+            name: name.to_string(),
+            pid,
+            manager_pid,
+            started_at: "2026-10-15T10:00:00+08:00".to_string(),
+        };
+        let pidfiles = vec![
+            pidfile("gateway", 500, 400),
+            // PID 被非 OpenClaw 进程复用
+            pidfile("cache-proxy", 800, 400),
+            // 当前会话
+            pidfile("gateway", 700, 900),
+        ];
+        let orphans = classify(&table, &pidfiles, &[(18789, 500)], false, 900);
+        let kinds: Vec<(u32, OrphanKind)> = orphans.iter().map(|o| (o.pid, o.kind)).collect();
+        assert_eq!(
+            kinds,
+            vec![(500, OrphanKind::StalePidfile), (901, OrphanKind::Zombie)]
+        );
+        assert_eq!(orphans[1].terminate_pid, Some(700));
+
+        // 正常响应的 Gateway 会被接管，不算残留
+        let orphans = classify(&table, &pidfiles, &[(18789, 500)], true, 900);
+        assert_eq!(orphans.len(), 1);
+
+        // 没有 PID 文件时按端口占用判断
+        let orphans = classify(&table, &[], &[(18789, 500), (8789, 600)], false, 900);
+        assert_eq!(orphans.len(), 2);
+        assert_eq!(orphans[0].kind, OrphanKind::PortHolder);
+        assert_eq!(
+            orphans[0].reason,
+            "占用端口 18789，父进程已退出且 Gateway 无响应"
+        );
+        assert!(is_openclaw_process(
+            "C:\\Program Files\\nodejs\\node.exe openclaw.mjs"
+        ));
+        assert!(!is_openclaw_process("nginx: master process"));
+    }
+}
@@ -0,0 +1,370 @@
+//! 扫码配对的渠道（WhatsApp、个人微信桥接等）：通过 Gateway 获取登录二维码，
+//! 后台等待扫码结果，前端轮询配对状态并显示登录的账号
+use crate::commands::service;
+use crate::utils::audit;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use openclaw_macros::guarded;
+use tauri::command;
+
+/// 二维码有效期，超过后需要重新获取
+const PAIRING_TTL_SECS: i64 = 3 * 60;
+/// 每次等待扫码结果的时长（期间无法取消，取较短的时间）
+const WAIT_CHUNK: Duration = Duration::from_secs(20);
+/// 获取二维码的超时时间
+const START_TIMEOUT: Duration = Duration::from_secs(30);
+/// 连续失败多少次后放弃等待
+const MAX_WAIT_FAILURES: u32 = 3;
+/// 已结束的会话保留多久（供前端读取最终状态）
+const FINISHED_RETENTION_SECS: i64 = 10 * 60;
+
+/// 扫码登录的渠道及其 Gateway 方法
+struct QrChannel {
+    channel: &'static str,
+    start: &'static str,
+    wait: &'static str,
+}
+
+const QR_CHANNELS: &[QrChannel] = &[
+    QrChannel {
+        channel: "whatsapp",
+        start: "web.login.start",
+        wait: "web.login.wait",
+    },
+    // 个人微信桥接插件约定使用 <渠道>.login.start / <渠道>.login.wait
+    QrChannel {
+        channel: "wechat",
+        start: "wechat.login.start",
+        wait: "wechat.login.wait",
+    },
+];
+
+/// 配对状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PairingStatus {
+    WaitingForScan,
+    Connected,
+    Expired,
+    Failed,
+    Cancelled,
+}
+
+/// 配对成功后登录的账号
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PairedAccount {
+    pub account_id: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    /// 手机号、微信号等可识别的身份
+    #[serde(default)]
+    pub identity: Option<String>,
+}
+
+/// 配对会话
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PairingSession {
+    pub id: String,
+    pub channel: String,
+    #[serde(default)]
+    pub account_id: Option<String>,
+    pub status: PairingStatus,
+    /// 二维码图片（data URL）
+    #[serde(default)]
+    pub qr_data_url: Option<String>,
+    /// 二维码原始内容（Gateway 不返回图片时由前端生成二维码）
+    #[serde(default)]
+    pub qr_text: Option<String>,
+    #[serde(default)]
+    pub message: Option<String>,
+    #[serde(default)]
+    pub account: Option<PairedAccount>,
+    pub started_at: i64,
+    pub expires_at: i64,
+}
+
+static SESSIONS: Mutex<Option<HashMap<String, PairingSession>>> = Mutex::new(None);
+
+/// 二维码内容
+#[derive(Debug, Default, PartialEq)]
+struct QrPayload {
+    data_url: Option<String>,
+    text: Option<String>,
+}
+
+fn str_field(value: &Value, names: &[&str]) -> Option<String> {
+    names
+        .iter()
+        .find_map(|name| value.get(*name).and_then(Value::as_str))
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+}
+
+/// 从 Gateway 返回结果中读取二维码（兼容 qrDataUrl / qr / qrCode 等字段）
+fn parse_qr(value: &Value) -> QrPayload {
+    let data_url = str_field(value, &["qrDataUrl", "qr_data_url"]);
+    let text = str_field(value, &["qr", "qrCode", "qrText", "qr_text"]);
+    // 部分插件把图片放在 qr 字段中
+    match text {
+        Some(text) if text.starts_with("data:image/") && data_url.is_none() => QrPayload {
+            data_url: Some(text),
+            text: None,
+        },
+        text => QrPayload { data_url, text },
+    }
+}
+
+/// 从 channels.status 的结果中找到登录的账号
+fn parse_account(status: &Value, channel: &str, account_id: Option<&str>) -> Option<PairedAccount> {
+    let accounts = status
+        .pointer(&format!("/channelAccounts/{}", channel))
+        .or_else(|| status.pointer(&format!("/channels/{}/accounts", channel)))?;
+    let list: Vec<&Value> = match accounts {
+        Value::Array(items) => items.iter().collect(),
+        Value::Object(map) => map.values().collect(),
+        _ => return None,
+    };
+    let id_of =
+        |a: &Value| str_field(a, &["accountId", "id"]).unwrap_or_else(|| "default".to_string());
+    let account = match account_id {
+        Some(wanted) => list.into_iter().find(|a| id_of(a) == wanted)?,
+        None => list
+            .iter()
+            .find(|a| a.get("linked").and_then(Value::as_bool) == Some(true))
+            .or(list.first())
+            .copied()?,
+    };
+    let identity = account
+        .get("self")
+        .and_then(|s| str_field(s, &["e164", "jid", "id", "username"]))
+        .or_else(|| str_field(account, &["e164", "phone", "username", "wxid"]));
+    Some(PairedAccount {
+        account_id: id_of(account),
+        name: str_field(account, &["name", "displayName", "nickname"]),
+        identity,
+    })
+}
+
+fn qr_channel(channel: &str) -> Result<&'static QrChannel, String> {
+    QR_CHANNELS
+        .iter()
+        .find(|c| c.channel == channel)
+        .ok_or_else(|| format!("渠道 {} 不支持扫码配对", channel))
+}
+
+fn with_sessions<R>(f: impl FnOnce(&mut HashMap<String, PairingSession>) -> R) -> R {
+    let mut sessions = match SESSIONS.lock() {
+        Ok(s) => s,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    f(sessions.get_or_insert_with(HashMap::new))
+}
+
+/// 更新仍在等待扫码的会话，返回会话是否仍在等待
+fn update_waiting(id: &str, f: impl FnOnce(&mut PairingSession)) -> bool {
+    with_sessions(|sessions| match sessions.get_mut(id) {
+        Some(session) if session.status == PairingStatus::WaitingForScan => {
+            f(session);
+            session.status == PairingStatus::WaitingForScan
+        }
+        _ => false,
+    })
+}
+
+/// 在阻塞线程中调用 Gateway 方法
+async fn call(method: &'static str, params: Value, timeout: Duration) -> Result<Value, String> {
+    tokio::task::spawn_blocking(move || service::gateway_call(method, &params, timeout))
+        .await
+        .map_err(|e| format!("调用 Gateway 失败: {}", e))?
+}
+
+/// 后台等待扫码结果，直到登录成功、二维码过期或被取消
+async fn watch(id: String, channel: &'static QrChannel, account_id: Option<String>) {
+    let mut failures = 0;
+    loop {
+        let expires_at = with_sessions(|s| s.get(&id).map(|s| s.expires_at));
+        if expires_at.is_some_and(|at| chrono::Utc::now().timestamp() >= at) {
+            update_waiting(&id, |s| {
+                s.status = PairingStatus::Expired;
+                s.message = Some("二维码已过期，请重新获取".to_string());
+            });
+        }
+        if !update_waiting(&id, |_| {}) {
+            return;
+        }
+        let params = json!({ "timeoutMs": WAIT_CHUNK.as_millis() as u64, "accountId": account_id });
+        match call(channel.wait, params, WAIT_CHUNK + Duration::from_secs(5)).await {
+            Ok(result) => {
+                failures = 0;
+                let qr = parse_qr(&result);
+                let message = str_field(&result, &["message"]);
+                if result.get("connected").and_then(Value::as_bool) == Some(true) {
+                    let account = call("channels.status", json!({}), START_TIMEOUT)
+                        .await
+                        .ok()
+                        .and_then(|status| {
+                            parse_account(&status, channel.channel, account_id.as_deref())
+                        });
+                    info!(
+                        "[扫码配对] ✓ {} 配对成功: {:?}",
+                        channel.channel,
+                        account.as_ref().and_then(|a| a.identity.as_deref())
+                    );
+                    audit::record(
+                        "channel_pairing",
+                        channel.channel,
+                        true,
+                        json!({ "account": account }),
+                    );
+                    update_waiting(&id, |s| {
+                        s.status = PairingStatus::Connected;
+                        s.message = message;
+                        s.account = account;
+                        s.qr_data_url = None;
+                        s.qr_text = None;
+                    });
+                    return;
+                }
+                // 二维码定期刷新时 Gateway 返回新的二维码
+                update_waiting(&id, |s| {
+                    if qr != QrPayload::default() {
+                        s.qr_data_url = qr.data_url;
+                        s.qr_text = qr.text;
+                    }
+                    if message.is_some() {
+                        s.message = message;
+                    }
+                });
+            }
+            Err(e) => {
+                failures += 1;
+                warn!("[扫码配对] 等待 {} 扫码结果失败: {}", channel.channel, e);
+                if failures >= MAX_WAIT_FAILURES {
+                    audit::record(
+                        "channel_pairing",
+                        channel.channel,
+                        false,
+                        json!({ "error": e }),
+                    );
+                    update_waiting(&id, |s| {
+                        s.status = PairingStatus::Failed;
+                        s.message = Some(e);
+                    });
+                    return;
+                }
+            }
+        }
+    }
+}
+
+/// 支持扫码配对的渠道
+#[guarded]
+#[command]
+pub async fn list_qr_channels() -> Result<Vec<String>, String> {
+    Ok(QR_CHANNELS.iter().map(|c| c.channel.to_string()).collect())
+}
+
+/// 获取登录二维码并开始等待扫码（同一渠道账号未完成的配对会被取消）
+/// `force` 为 true 时即使已登录也重新配对
+#[guarded]
+#[command]
+pub async fn start_channel_pairing(
+    channel: String,
+    account_id: Option<String>,
+    force: Option<bool>,
+) -> Result<PairingSession, String> {
+    let qr_channel = qr_channel(&channel)?;
+    info!("[扫码配对] 获取 {} 登录二维码...", channel);
+    let params = json!({ "force": force.unwrap_or(false), "accountId": account_id });
+    let result = call(qr_channel.start, params, START_TIMEOUT).await?;
+    let qr = parse_qr(&result);
+    let message = str_field(&result, &["message"]);
+    if qr == QrPayload::default() {
+        return Err(message.unwrap_or_else(|| "Gateway 没有返回二维码".to_string()));
+    }
+
+    let now = chrono::Utc::now().timestamp();
+    let session = PairingSession {
+        id: uuid::Uuid::new_v4().simple().to_string(),
+        channel: channel.clone(),
+        account_id: account_id.clone(),
+        status: PairingStatus::WaitingForScan,
+        qr_data_url: qr.data_url,
+        qr_text: qr.text,
+        message,
+        account: None,
+        started_at: now,
+        expires_at: now + PAIRING_TTL_SECS,
+    };
+    with_sessions(|sessions| {
+        sessions.retain(|_, s| {
+            s.status == PairingStatus::WaitingForScan
+                || now - s.started_at < FINISHED_RETENTION_SECS
+        });
+        for s in sessions.values_mut() {
+            if s.channel == channel
+                && s.account_id == account_id
+                && s.status == PairingStatus::WaitingForScan
+            {
+                s.status = PairingStatus::Cancelled;
+            }
+        }
+        sessions.insert(session.id.clone(), session.clone());
+    });
+    tauri::async_runtime::spawn(watch(session.id.clone(), qr_channel, account_id));
+    Ok(session)
+}
+
+/// 查询配对状态（前端轮询）
+#[guarded]
+#[command]
+pub async fn get_channel_pairing(id: String) -> Result<PairingSession, String> {
+    with_sessions(|sessions| sessions.get(&id).cloned())
+        .ok_or_else(|| format!("配对会话不存在或已过期: {}", id))
+}
+
+/// 取消配对
+#[guarded]
+#[command]
+pub async fn cancel_channel_pairing(id: String) -> Result<(), String> {
+    update_waiting(&id, |s| s.status = PairingStatus::Cancelled);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_qr_and_linked_account() {
+        let qr = parse_qr(&json!({ "qrDataUrl": "data:image/png;base64,AAA", "message": "scan" }));
+        assert_eq!(qr.data_url.as_deref(), Some("data:image/png;base64,AAA"));
+        let raw = parse_qr(&json!({ "qr": "2@abc,def" }));
+        assert_eq!(raw.text.as_deref(), Some("2@abc,def"));
+        assert_eq!(
+            parse_qr(&json!({ "message": "already linked" })),
+            QrPayload::default()
+        );
+
+        let status = json!({
+            "channelAccounts": { "whatsapp": [
+                { "accountId": "default", "linked": false },
+                { "accountId": "work", "linked": true, "name": "Work", "self": { "e164": "+8613800000000" } }
+            ]}
+        });
+        let account = parse_account(&status, "whatsapp", None).unwrap();
+        assert_eq!(account.account_id, "work");
+        assert_eq!(account.identity.as_deref(), Some("+8613800000000"));
+        assert_eq!(
+            parse_account(&status, "whatsapp", Some("default"))
+                .unwrap()
+                .identity,
+            None
+        );
+        assert!(parse_account(&status, "wechat", None).is_none());
+    }
+}
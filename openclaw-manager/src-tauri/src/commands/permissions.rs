@@ -0,0 +1,425 @@
+use crate::commands::policy;
+use crate::utils::{audit, platform};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::path::Path;
+use openclaw_macros::guarded;
+use tauri::command;
+
+/// 权限问题类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PermissionIssueKind {
+    /// 配置根目录允许其他用户访问（应为 700）
+    LooseDirectory,
+    /// 凭据文件（credentials/、.env、openclaw.json 等）对其他用户可读
+    ExposedCredential,
+    /// 任何用户都可写
+    WorldWritable,
+    /// 文件属主不是当前用户（如 sudo 安装后属于 root）
+    WrongOwner,
+    /// Windows ACL 授予了 Everyone / Users 等宽泛用户组访问权限
+    BroadAcl,
+}
+
+/// 权限问题
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PermissionIssue {
+    pub path: String,
+    pub kind: PermissionIssueKind,
+    pub detail: String,
+}
+
+/// 权限审计结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PermissionAudit {
+    pub root: String,
+    pub scanned: usize,
+    pub issues: Vec<PermissionIssue>,
+}
+
+/// 权限修复结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PermissionFixReport {
+    pub fixed: usize,
+    pub errors: Vec<String>,
+    /// 修复后重新审计的结果
+    pub audit: PermissionAudit,
+}
+
+/// 是否是包含密钥的文件（相对配置目录的路径）
+fn is_sensitive(relative: &Path) -> bool {
+    if relative
+        .components()
+        .next()
+        .map(|c| c.as_os_str() == "credentials")
+        .unwrap_or(false)
+    {
+        return true;
+    }
+    let name = relative
+        .file_name()
+        .map(|n| n.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    matches!(
+        name.as_str(),
+        "openclaw.json" | ".env" | "auth-profiles.json" | "auth.json"
+    ) || name.ends_with(".key")
+        || name.ends_with(".pem")
+}
+
+#[cfg(unix)]
+mod unix {
+    use super::*;
+    use std::os::unix::fs::{MetadataExt, PermissionsExt};
+    use std::path::PathBuf;
+
+    /// 期望的属主：用户主目录的属主（uid, gid）
+    pub fn expected_owner() -> Option<(u32, u32)> {
+        let home = dirs::home_dir()?;
+        let meta = std::fs::metadata(home).ok()?;
+        Some((meta.uid(), meta.gid()))
+    }
+
+    /// 遍历目录（不跟随符号链接），返回 (路径, 元数据)
+    fn walk(root: &Path) -> Vec<(PathBuf, std::fs::Metadata)> {
+        let mut entries = Vec::new();
+        let mut stack = vec![root.to_path_buf()];
+        while let Some(path) = stack.pop() {
+            let Ok(meta) = std::fs::symlink_metadata(&path) else {
+                continue;
+            };
+            if meta.file_type().is_symlink() {
+                continue;
+            }
+            if meta.is_dir() {
+                if let Ok(children) = std::fs::read_dir(&path) {
+                    stack.extend(children.flatten().map(|c| c.path()));
+                }
+            }
+            entries.push((path, meta));
+        }
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+        entries
+    }
+
+    pub fn audit_dir(root: &Path, owner_uid: Option<u32>) -> PermissionAudit {
+        let entries = walk(root);
+        let mut issues = Vec::new();
+        // 属主错误只报告最上层的路径，避免 sudo 安装后刷出成千上万条
+        let mut wrong_owner_roots: Vec<PathBuf> = Vec::new();
+
+        for (path, meta) in &entries {
+            let mode = meta.permissions().mode() & 0o777;
+            let relative = path.strip_prefix(root).unwrap_or(path);
+            let display = path.to_string_lossy().to_string();
+
+            if let Some(uid) = owner_uid {
+                if meta.uid() != uid && !wrong_owner_roots.iter().any(|r| path.starts_with(r)) {
+                    wrong_owner_roots.push(path.clone());
+                    issues.push(PermissionIssue {
+                        path: display.clone(),
+                        kind: PermissionIssueKind::WrongOwner,
+                        detail: format!("属主 uid {}，应为 {}", meta.uid(), uid),
+                    });
+                }
+            }
+            if path == root && mode & 0o077 != 0 {
+                issues.push(PermissionIssue {
+                    path: display,
+                    kind: PermissionIssueKind::LooseDirectory,
+                    detail: format!("权限 {:o}，应为 700", mode),
+                });
+            } else if meta.is_file() && is_sensitive(relative) && mode & 0o077 != 0 {
+                issues.push(PermissionIssue {
+                    path: display,
+                    kind: PermissionIssueKind::ExposedCredential,
+                    detail: format!("权限 {:o}，应为 600", mode),
+                });
+            } else if mode & 0o002 != 0 {
+                issues.push(PermissionIssue {
+                    path: display,
+                    kind: PermissionIssueKind::WorldWritable,
+                    detail: format!("权限 {:o}", mode),
+                });
+            }
+        }
+
+        PermissionAudit {
+            root: root.to_string_lossy().to_string(),
+            scanned: entries.len(),
+            issues,
+        }
+    }
+
+    /// 修复权限位：根目录 700，凭据文件 600，其余去掉其他用户写权限
+    pub fn fix_modes(root: &Path, errors: &mut Vec<String>) -> usize {
+        let mut fixed = 0;
+        for (path, meta) in walk(root) {
+            let mode = meta.permissions().mode() & 0o777;
+            let relative = path.strip_prefix(root).unwrap_or(&path);
+            let target = if path == root {
+                0o700
+            } else if meta.is_file() && is_sensitive(relative) {
+                mode & 0o700
+            } else {
+                mode & !0o002
+            };
+            if target == mode {
+                continue;
+            }
+            match std::fs::set_permissions(&path, std::fs::Permissions::from_mode(target)) {
+                Ok(_) => fixed += 1,
+                Err(e) => errors.push(format!("{}: {}", path.display(), e)),
+            }
+        }
+        fixed
+    }
+
+    /// 把属主改回当前用户（需要管理员权限）
+    pub fn fix_owner(paths: &[String], uid: u32, gid: u32) -> Result<(), String> {
+        let quoted: Vec<String> = paths
+            .iter()
+            .map(|p| format!("'{}'", p.replace('\'', "'\\''")))
+            .collect();
+        let cmd = format!("chown -R {}:{} {}", uid, gid, quoted.join(" "));
+
+        if platform::is_macos() {
+            let script = format!(
+                "do shell script \"{}\" with administrator privileges",
+                cmd.replace('\\', "\\\\").replace('"', "\\\"")
+            );
+            crate::utils::shell::run_command_output("osascript", &["-e", &script]).map(|_| ())
+        } else if crate::utils::shell::command_exists("pkexec") {
+            crate::utils::shell::run_command_output("pkexec", &["sh", "-c", &cmd]).map(|_| ())
+        } else {
+            Err(format!("需要管理员权限，请在终端执行: sudo {}", cmd))
+        }
+    }
+}
+
+#[cfg(windows)]
+mod windows {
+    use super::*;
+    use crate::utils::shell;
+
+    /// Everyone / Anonymous / Authenticated Users / BUILTIN\Users
+    /// 使用 SID 判断，避免中文系统下用户组名称本地化
+    const BROAD_SIDS: &[&str] = &["S-1-1-0", "S-1-5-7", "S-1-5-11", "S-1-5-32-545"];
+
+    /// 审计根目录和凭据文件的 ACL（逐个文件查询 ACL 太慢，只检查关键路径）
+    pub fn audit_dir(root: &Path) -> PermissionAudit {
+        let mut targets = vec![root.to_path_buf()];
+        for name in ["openclaw.json", ".env", "credentials"] {
+            let path = root.join(name);
+            if path.exists() {
+                targets.push(path);
+            }
+        }
+        if let Ok(children) = std::fs::read_dir(root.join("credentials")) {
+            targets.extend(children.flatten().map(|c| c.path()));
+        }
+
+        let list = targets
+            .iter()
+            .map(|p| shell::powershell_quote(&p.to_string_lossy()))
+            .collect::<Vec<_>>()
+            .join(",");
+        let script = format!(
+            r#"foreach ($p in @({})) {{
+  foreach ($a in (Get-Acl -LiteralPath $p).Access) {{
+    if ($a.AccessControlType -ne 'Allow') {{ continue }}
+    try {{ $sid = $a.IdentityReference.Translate([System.Security.Principal.SecurityIdentifier]).Value }} catch {{ continue }}
+    Write-Output ($p + "`t" + $sid + "`t" + $a.IdentityReference.Value + "`t" + $a.FileSystemRights)
+  }}
+}}"#,
+            list
+        );
+
+        let mut issues = Vec::new();
+        match shell::run_powershell_output(&script) {
+            Ok(output) => {
+                for line in output.lines() {
+                    let parts: Vec<&str> = line.split('\t').collect();
+                    if parts.len() == 4 && BROAD_SIDS.contains(&parts[1]) {
+                        issues.push(PermissionIssue {
+                            path: parts[0].to_string(),
+                            kind: PermissionIssueKind::BroadAcl,
+                            detail: format!("{} 拥有 {} 权限", parts[2], parts[3]),
+                        });
+                    }
+                }
+            }
+            Err(e) => warn!("[权限审计] 读取 ACL 失败: {}", e),
+        }
+
+        PermissionAudit {
+            root: root.to_string_lossy().to_string(),
+            scanned: targets.len(),
+            issues,
+        }
+    }
+
+    /// 停止继承并只授予当前用户和 SYSTEM 完全控制，移除宽泛用户组
+    pub fn fix_acl(root: &Path) -> Result<(), String> {
+        let script = format!(
+            r#"$root = {}
+$sid = [System.Security.Principal.WindowsIdentity]::GetCurrent().User.Value
+icacls $root /inheritance:r /grant:r "*${{sid}}:(OI)(CI)F" "*S-1-5-18:(OI)(CI)F" /C /Q | Out-Null
+icacls $root /remove:g *S-1-1-0 *S-1-5-7 *S-1-5-11 *S-1-5-32-545 /T /C /Q | Out-Null
+if ($LASTEXITCODE -ne 0) {{ exit $LASTEXITCODE }}"#,
+            shell::powershell_quote(&root.to_string_lossy())
+        );
+        shell::run_powershell_output(&script).map(|_| ())
+    }
+}
+
+fn audit_config_dir(root: &Path) -> PermissionAudit {
+    #[cfg(unix)]
+    {
+        unix::audit_dir(root, unix::expected_owner().map(|(uid, _)| uid))
+    }
+    #[cfg(windows)]
+    {
+        windows::audit_dir(root)
+    }
+}
+
+/// 审计 ~/.openclaw 的权限：对其他用户可读的凭据、属主错误、Windows 宽泛 ACL
+#[guarded]
+#[command]
+pub async fn audit_permissions() -> Result<PermissionAudit, String> {
+    let root = platform::get_config_dir();
+    if !Path::new(&root).exists() {
+        return Err(format!("配置目录不存在: {}", root));
+    }
+    info!("[权限审计] 检查 {}", root);
+    let audit = audit_config_dir(Path::new(&root));
+    info!(
+        "[权限审计] 扫描 {} 项，发现 {} 个问题",
+        audit.scanned,
+        audit.issues.len()
+    );
+    Ok(audit)
+}
+
+/// 递归修复 ~/.openclaw 的权限（属主错误时会请求管理员权限）
+#[guarded]
+#[command]
+pub async fn fix_permissions(confirm_token: Option<String>) -> Result<PermissionFixReport, String> {
+    policy::require_confirmation("fix_permissions", confirm_token.as_deref())?;
+    let root_str = platform::get_config_dir();
+    let root = Path::new(&root_str);
+    if !root.exists() {
+        return Err(format!("配置目录不存在: {}", root_str));
+    }
+    info!("[权限修复] 修复 {}", root_str);
+    let before = audit_config_dir(root);
+    let mut errors = Vec::new();
+    let mut fixed = 0;
+
+    #[cfg(unix)]
+    {
+        let wrong_owner: Vec<String> = before
+            .issues
+            .iter()
+            .filter(|i| i.kind == PermissionIssueKind::WrongOwner)
+            .map(|i| i.path.clone())
+            .collect();
+        if !wrong_owner.is_empty() {
+            match unix::expected_owner() {
+                Some((uid, gid)) => match unix::fix_owner(&wrong_owner, uid, gid) {
+                    Ok(_) => fixed += wrong_owner.len(),
+                    Err(e) => errors.push(format!("修复属主失败: {}", e)),
+                },
+                None => errors.push("无法确定当前用户".to_string()),
+            }
+        }
+        fixed += unix::fix_modes(root, &mut errors);
+    }
+    #[cfg(windows)]
+    {
+        if !before.issues.is_empty() {
+            match windows::fix_acl(root) {
+                Ok(_) => fixed += before.issues.len(),
+                Err(e) => errors.push(format!("修复 ACL 失败: {}", e)),
+            }
+        }
+    }
+
+    let audit = audit_config_dir(root);
+    for e in &errors {
+        warn!("[权限修复] ✗ {}", e);
+    }
+    info!(
+        "[权限修复] 修复 {} 项，剩余 {} 个问题",
+        fixed,
+        audit.issues.len()
+    );
+    audit::record(
+        "fix_permissions",
+        &root_str,
+        errors.is_empty(),
+        json!({ "fixed": fixed, "remaining": audit.issues.len(), "errors": errors }),
+    );
+
+    Ok(PermissionFixReport {
+        fixed,
+        errors,
+        audit,
+    })
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::{MetadataExt, PermissionsExt};
+    use std::path::PathBuf;
+
+    fn make_temp_dir(prefix: &str) -> PathBuf {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!("{}-{}", prefix, nanos));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn exposed_credentials_are_flagged_and_fixed() {
+        let root = make_temp_dir("openclaw-perm-audit");
+        let creds = root.join("credentials");
+        std::fs::create_dir_all(&creds).unwrap();
+        std::fs::write(creds.join("telegram.json"), "{}").unwrap();
+        std::fs::write(root.join("README.md"), "x").unwrap();
+        let set = |p: &Path, mode| {
+            std::fs::set_permissions(p, std::fs::Permissions::from_mode(mode)).unwrap()
+        };
+        set(&root, 0o755);
+        set(&creds.join("telegram.json"), 0o644);
+        set(&root.join("README.md"), 0o644);
+
+        let uid = std::fs::metadata(&root).unwrap().uid();
+        let audit = unix::audit_dir(&root, Some(uid));
+        let kinds: Vec<_> = audit.issues.iter().map(|i| i.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                PermissionIssueKind::LooseDirectory,
+                PermissionIssueKind::ExposedCredential
+            ]
+        );
+
+        let mut errors = Vec::new();
+        assert_eq!(unix::fix_modes(&root, &mut errors), 2);
+        assert!(errors.is_empty());
+        assert!(unix::audit_dir(&root, Some(uid)).issues.is_empty());
+        // 普通文件保持原权限
+        let readme = std::fs::metadata(root.join("README.md")).unwrap();
+        assert_eq!(readme.permissions().mode() & 0o777, 0o644);
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
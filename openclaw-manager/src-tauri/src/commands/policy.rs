@@ -0,0 +1,422 @@
+use crate::commands::settings;
+use crate::utils::panic_guard::{CommandError, ErrorKind};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use openclaw_macros::guarded;
+use tauri::ipc::Invoke;
+use tauri::{command, AppHandle};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+/// 需要二次确认的危险操作（操作名即命令名）
+pub const DANGEROUS_OPERATIONS: &[&str] = &[
+    "uninstall_openclaw",
+    "delete_provider",
+    "clear_channel_config",
+    "update_policy",
+    "update_settings",
+    "clear_agent_memory",
+    "delete_profile",
+    "restore_remote_backup",
+    "cleanup_orphans",
+    "resolve_config_conflict",
+    "apply_fix",
+    "migrate_legacy_install",
+    "fix_permissions",
+    "delete_workspace_file",
+    "execute_recovery",
+    "register_hook",
+    "remove_hook",
+    "disconnect_oauth",
+];
+
+/// 确认对话框中显示的操作说明
+fn describe(operation: &str) -> &'static str {
+    match operation {
+        "uninstall_openclaw" => "卸载 OpenClaw",
+        "delete_provider" => "删除模型 Provider 及其配置",
+        "clear_channel_config" => "清空渠道配置",
+        "update_policy" => "修改安全策略",
+        "update_settings" => "修改代理、证书、镜像、钩子等安全相关设置",
+        "clear_agent_memory" => "清除 Agent 的记忆和会话历史",
+        "delete_profile" => "删除配置档案及其目录",
+        "restore_remote_backup" => "用远程备份替换当前配置目录",
+        "cleanup_orphans" => "结束残留的 OpenClaw 进程（包括占用 Gateway 端口的进程）",
+        "resolve_config_conflict" => "处理配置冲突（会覆盖其中一方的修改）",
+        "apply_fix" => "执行诊断修复（可能结束进程或修改系统设置）",
+        "migrate_legacy_install" => "接管或清理旧版安装（会移动或删除旧文件）",
+        "fix_permissions" => "修复配置目录的属主和权限",
+        "delete_workspace_file" => "删除 Agent 工作区中的文件",
+        "register_hook" => "注册生命周期钩子（脚本会在安装、更新等流程中执行）",
+        "remove_hook" => "删除生命周期钩子脚本",
+        "disconnect_oauth" => "断开 OAuth 连接并删除钥匙串中的令牌",
+        "execute_recovery" => "执行更新恢复（会覆盖 openclaw.json 或卸载后重新安装 OpenClaw）",
+        _ => "危险操作",
+    }
+}
+
+/// 只读模式下仍允许调用的命令（状态、诊断、日志、指标等不修改本机状态的命令）
+/// 采用白名单：新增命令默认在只读模式下被拒绝。会消耗状态或用已保存的凭据向外发送请求的命令
+/// （如取出待处理的深度链接、发送测试消息）不属于只读命令
+pub const READ_ONLY_COMMANDS: &[&str] = &[
+    "get_service_status",
+    "get_logs",
+    "get_log_insights",
+    "trace_request",
+    "get_startup_report",
+    "check_openclaw_installed",
+    "get_openclaw_version",
+    "check_port_in_use",
+    "get_config",
+    "get_config_conflict",
+    "lint_config",
+    "get_env_value",
+    "get_ai_providers",
+    "get_channels_config",
+    "open_dashboard",
+    "get_official_providers",
+    "list_config_presets",
+    "get_ai_config",
+    "check_feishu_plugin",
+    "run_doctor",
+    "run_network_diagnostics",
+    "run_speed_test",
+    "scan_openclaw_vulnerabilities",
+    "get_vulnerability_scan",
+    "check_skill_updates",
+    "list_workspace_files",
+    "read_workspace_file",
+    "get_workspace_usage",
+    "get_shell_policy",
+    "test_policy",
+    "detect_browser_runtimes",
+    "detect_python",
+    "get_skill_python_env",
+    "get_ca_certificate",
+    "get_download_cache_usage",
+    "list_downloads",
+    "get_staged_update",
+    "get_last_update",
+    "get_pending_maintenance_actions",
+    "list_jobs",
+    "get_install_state",
+    "plan_install",
+    "get_conversation_stats",
+    "get_budget_status",
+    "get_cache_proxy_status",
+    "list_sidecars",
+    "get_sidecar_logs",
+    "get_system_info",
+    "check_environment",
+    "get_inventory",
+    "check_uninstall",
+    "detect_legacy_install",
+    "get_package_managers",
+    "check_openclaw_update",
+    "get_github_sync_state",
+    "get_source_build_info",
+    "get_settings",
+    "list_timezones",
+    "get_migration_status",
+    "get_policy_status",
+    "audit_permissions",
+    "get_privacy_status",
+    "get_telemetry_preview",
+    "list_crash_reports",
+    "get_crash_report",
+    "get_request_trace",
+    "list_benchmark_results",
+    "list_hooks",
+    "get_notification_settings",
+    "list_message_templates",
+    "validate_message_template",
+    "render_template_preview",
+    "list_oauth_presets",
+    "list_oauth_connections",
+    "list_qr_channels",
+    "get_channel_pairing",
+    "list_channel_contacts",
+    "get_channel_access",
+    "validate_channel_access",
+    "get_rate_limits",
+    "simulate_rate_limit",
+    "get_agent_memory",
+    "list_knowledge_sources",
+    "list_embedding_presets",
+    "get_embedding_config",
+    "get_dashboard_snapshot",
+    "list_probe_cache",
+    "replay_events",
+    "get_window_state",
+    "open_console",
+    "list_consoles",
+    "close_console",
+    "get_ui_preferences",
+    "list_secrets",
+    "export_sanitized_config",
+    "get_capabilities",
+    "get_compatibility_matrix",
+    "check_openclaw_compatibility",
+    "get_install_estimate",
+    "get_resource_status",
+    "get_daily_digest",
+    "get_recovery_options",
+    "get_orphan_processes",
+    "get_gateway_lock",
+    "list_profiles",
+    "get_sync_status",
+    "get_backup_status",
+    "list_remote_backups",
+];
+
+/// 强制开启只读模式的环境变量（远程协助时由支持人员设置）
+const READ_ONLY_ENV: &str = "OPENCLAW_MANAGER_READ_ONLY";
+
+/// 已签发、尚未使用的确认令牌：token -> (操作, 过期时间)
+static PENDING_TOKENS: OnceLock<Mutex<HashMap<String, (String, Instant)>>> = OnceLock::new();
+
+/// 安全策略设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PolicySettings {
+    /// 危险操作是否需要后端签发的确认令牌
+    pub confirm_dangerous: bool,
+    /// 确认令牌有效期（秒）
+    pub confirm_ttl_secs: u64,
+    /// 只读（kiosk）模式：拒绝所有修改类命令
+    /// 开启后界面无法关闭，需要编辑 settings.json 或去掉环境变量后重启
+    pub read_only: bool,
+}
+
+impl Default for PolicySettings {
+    fn default() -> Self {
+        Self {
+            confirm_dangerous: true,
+            confirm_ttl_secs: 60,
+            read_only: false,
+        }
+    }
+}
+
+/// 确认令牌
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfirmationToken {
+    pub token: String,
+    pub operation: String,
+    /// 有效期（秒）
+    pub expires_in_secs: u64,
+}
+
+fn pending_tokens() -> std::sync::MutexGuard<'static, HashMap<String, (String, Instant)>> {
+    let map = PENDING_TOKENS.get_or_init(|| Mutex::new(HashMap::new()));
+    match map.lock() {
+        Ok(m) => m,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+fn issue_token(operation: &str, ttl: Duration) -> String {
+    let token = uuid::Uuid::new_v4().to_string();
+    let mut tokens = pending_tokens();
+    let now = Instant::now();
+    tokens.retain(|_, (_, expires)| *expires > now);
+    tokens.insert(token.clone(), (operation.to_string(), now + ttl));
+    token
+}
+
+/// 测试中跳过确认对话框直接签发令牌
+#[cfg(test)]
+pub fn confirmed_token(operation: &str) -> String {
+    issue_token(operation, Duration::from_secs(60))
+}
+
+/// 校验并消耗令牌（一次性，且必须与操作匹配、未过期）
+fn consume_token(operation: &str, token: &str) -> Result<(), String> {
+    let (op, expires) = pending_tokens()
+        .remove(token)
+        .ok_or_else(|| "确认令牌无效或已使用".to_string())?;
+    if op != operation {
+        return Err(format!("确认令牌不适用于此操作（签发给 {}）", op));
+    }
+    if Instant::now() > expires {
+        return Err("确认令牌已过期，请重新确认".to_string());
+    }
+    Ok(())
+}
+
+/// 危险操作执行前调用：策略开启时必须提供有效的确认令牌
+pub fn require_confirmation(operation: &str, token: Option<&str>) -> Result<(), String> {
+    if !settings::load_settings().policy.confirm_dangerous {
+        return Ok(());
+    }
+    let result = match token.filter(|t| !t.is_empty()) {
+        Some(token) => consume_token(operation, token),
+        None => Err("该操作需要确认，请先调用 request_confirmation 获取确认令牌".to_string()),
+    };
+    if let Err(e) = &result {
+        warn!("[安全策略] ✗ 拒绝执行 {}: {}", operation, e);
+    }
+    result
+}
+
+/// 当前是否处于只读模式
+pub fn is_read_only() -> bool {
+    let forced = std::env::var(READ_ONLY_ENV)
+        .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+        .unwrap_or(false);
+    if forced {
+        return true;
+    }
+    // settings.json 无法解析时无法确认策略，按只读处理
+    match settings::try_load_settings() {
+        Ok(settings) => settings.policy.read_only,
+        Err(e) => {
+            warn!("[安全策略] {}，按只读模式处理", e);
+            true
+        }
+    }
+}
+
+/// 检查命令在当前模式下是否允许执行
+pub fn check_command_allowed(command: &str) -> Result<(), String> {
+    if READ_ONLY_COMMANDS.contains(&command) || !is_read_only() {
+        return Ok(());
+    }
+    warn!("[安全策略] ✗ 只读模式拒绝命令: {}", command);
+    Err(format!("当前处于只读模式，不允许执行 {}", command))
+}
+
+/// 包装 invoke handler，在分发前统一执行只读模式检查
+/// 拒绝时返回与其他命令错误相同结构的 CommandError（kind 为 policy_denied）
+pub fn guard_invoke_handler<F>(handler: F) -> impl Fn(Invoke) -> bool + Send + Sync + 'static
+where
+    F: Fn(Invoke) -> bool + Send + Sync + 'static,
+{
+    move |invoke: Invoke| {
+        let command = invoke.message.command();
+        if let Err(message) = check_command_allowed(command) {
+            invoke.resolver.reject(CommandError {
+                kind: ErrorKind::PolicyDenied,
+                command: command.to_string(),
+                message,
+                request_id: uuid::Uuid::new_v4().to_string(),
+            });
+            return true;
+        }
+        handler(invoke)
+    }
+}
+
+/// 策略状态（供界面显示只读提示）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicyStatus {
+    pub read_only: bool,
+    pub confirm_dangerous: bool,
+}
+
+/// 获取当前策略状态
+#[guarded]
+#[command]
+pub async fn get_policy_status() -> Result<PolicyStatus, String> {
+    let policy = settings::load_settings().policy;
+    Ok(PolicyStatus {
+        read_only: is_read_only(),
+        confirm_dangerous: policy.confirm_dangerous,
+    })
+}
+
+/// 弹出系统确认对话框，用户点击确认时返回 true（关闭对话框视为取消）
+async fn confirm_with_user(app: &AppHandle, operation: &str) -> bool {
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    app.dialog()
+        .message(format!("即将{}，确定继续吗？", describe(operation)))
+        .title("确认危险操作")
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            "继续".to_string(),
+            "取消".to_string(),
+        ))
+        .show(move |confirmed| {
+            let _ = tx.send(confirmed);
+        });
+    rx.await.unwrap_or(false)
+}
+
+/// 为危险操作签发确认令牌：由后端弹出系统确认对话框，用户确认后才签发
+/// （前端无法绕过对话框自行获取令牌）
+#[guarded]
+#[command]
+pub async fn request_confirmation(
+    app: AppHandle,
+    operation: String,
+) -> Result<ConfirmationToken, String> {
+    if !DANGEROUS_OPERATIONS.contains(&operation.as_str()) {
+        return Err(format!("未知的危险操作: {}", operation));
+    }
+    let policy = settings::load_settings().policy;
+    if policy.confirm_dangerous && !confirm_with_user(&app, &operation).await {
+        info!("[安全策略] 用户取消了 {}", operation);
+        return Err(format!("Cancelled: 已取消{}", describe(&operation)));
+    }
+    let ttl = policy.confirm_ttl_secs.max(1);
+    let token = issue_token(&operation, Duration::from_secs(ttl));
+    info!("[安全策略] 签发确认令牌: {} ({}秒内有效)", operation, ttl);
+    Ok(ConfirmationToken {
+        token,
+        operation,
+        expires_in_secs: ttl,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_is_single_use_and_bound_to_operation() {
+        let token = issue_token("delete_provider", Duration::from_secs(60));
+        assert!(consume_token("uninstall_openclaw", &token).is_err());
+
+        let token = issue_token("delete_provider", Duration::from_secs(60));
+        assert!(consume_token("delete_provider", &token).is_ok());
+        assert!(consume_token("delete_provider", &token).is_err());
+    }
+
+    #[test]
+    fn read_only_allowlist_contains_only_queries() {
+        for cmd in READ_ONLY_COMMANDS {
+            assert!(!DANGEROUS_OPERATIONS.contains(cmd), "{} 不能在只读白名单中", cmd);
+        }
+        // 会写入状态或向外发送数据的命令
+        for cmd in [
+            "copy_secret_to_clipboard",
+            "stage_secret",
+            "set_ui_preferences",
+            "send_test_notification",
+            "update_console_options",
+            "take_pending_deep_link",
+            "test_channel",
+            "test_ai_connection",
+            "test_embedding_provider",
+        ] {
+            assert!(!READ_ONLY_COMMANDS.contains(&cmd), "{} 不能在只读白名单中", cmd);
+        }
+        assert!(check_command_allowed("get_service_status").is_ok());
+    }
+
+    #[test]
+    fn every_dangerous_operation_has_a_description() {
+        for op in DANGEROUS_OPERATIONS {
+            assert_ne!(describe(op), "危险操作", "{} 缺少确认说明", op);
+        }
+    }
+
+    #[test]
+    fn expired_token_is_rejected() {
+        let token = issue_token("clear_channel_config", Duration::from_millis(0));
+        std::thread::sleep(Duration::from_millis(5));
+        assert!(consume_token("clear_channel_config", &token).is_err());
+    }
+}
@@ -0,0 +1,441 @@
+use crate::commands::{config, config_conflict};
+use crate::utils::audit;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use openclaw_macros::guarded;
+use tauri::command;
+
+/// 需要用户填写的配置项（如 Bot Token、API Key）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PresetField {
+    /// 填写时使用的键，如 `telegram.botToken`
+    pub key: String,
+    pub label: String,
+    /// openclaw.json 中的 JSON Pointer
+    pub path: String,
+    pub secret: bool,
+    /// 当前配置中是否已有值（已有值时可不填，保留原值）
+    pub filled: bool,
+}
+
+/// 配置预设
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigPreset {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    /// 启用的渠道
+    pub channels: Vec<String>,
+    pub fields: Vec<PresetField>,
+}
+
+/// 应用预设产生的一项变更（JSON Pointer 路径，null 表示不存在）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PresetChange {
+    pub path: String,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+}
+
+/// 预设的预览 / 应用结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PresetPlan {
+    pub preset: ConfigPreset,
+    pub changes: Vec<PresetChange>,
+    /// 仍需填写的配置项
+    pub missing: Vec<String>,
+    pub applied: bool,
+}
+
+/// 预设定义
+struct PresetDef {
+    id: &'static str,
+    name: &'static str,
+    description: &'static str,
+    /// 启用的渠道（同时加入插件白名单）
+    channels: &'static [&'static str],
+    /// 覆盖写入的配置
+    set: Vec<(&'static str, Value)>,
+    /// 仅在当前配置中不存在时写入（如 Provider 占位）
+    defaults: Vec<(&'static str, Value)>,
+    /// 需要用户填写的配置项：(键, 名称, 路径, 是否密钥)
+    fields: &'static [(&'static str, &'static str, &'static str, bool)],
+    /// 停用当前配置中其他所有渠道
+    disable_other_channels: bool,
+}
+
+fn presets() -> Vec<PresetDef> {
+    vec![
+        PresetDef {
+            id: "personal-telegram",
+            name: "个人助理（Telegram）",
+            description: "本地 Gateway + Telegram 私聊，使用 Anthropic Claude 模型",
+            channels: &["telegram"],
+            set: vec![
+                ("/gateway/mode", json!("local")),
+                ("/channels/telegram/dmPolicy", json!("pairing")),
+            ],
+            defaults: vec![
+                (
+                    "/models/providers/anthropic",
+                    json!({
+                        "baseUrl": "https://api.anthropic.com",
+                        "api": "anthropic-messages",
+                        "models": [{"id": "claude-sonnet-4", "name": "Claude Sonnet 4", "input": ["text"]}],
+                    }),
+                ),
+                (
+                    "/agents/defaults/model/primary",
+                    json!("anthropic/claude-sonnet-4"),
+                ),
+            ],
+            fields: &[
+                (
+                    "telegram.botToken",
+                    "Telegram Bot Token",
+                    "/channels/telegram/botToken",
+                    true,
+                ),
+                (
+                    "anthropic.apiKey",
+                    "Anthropic API Key",
+                    "/models/providers/anthropic/apiKey",
+                    true,
+                ),
+            ],
+            disable_other_channels: false,
+        },
+        PresetDef {
+            id: "team-slack-wecom",
+            name: "团队机器人（Slack + 企业微信）",
+            description: "本地 Gateway，同时接入 Slack 和企业微信群聊，使用 OpenAI 模型",
+            channels: &["slack", "wecom"],
+            set: vec![
+                ("/gateway/mode", json!("local")),
+                ("/channels/slack/groupPolicy", json!("open")),
+                ("/channels/wecom/groupPolicy", json!("open")),
+            ],
+            defaults: vec![
+                (
+                    "/models/providers/openai",
+                    json!({
+                        "baseUrl": "https://api.openai.com/v1",
+                        "api": "openai-completions",
+                        "models": [{"id": "gpt-4o", "name": "GPT-4o", "input": ["text"]}],
+                    }),
+                ),
+                ("/agents/defaults/model/primary", json!("openai/gpt-4o")),
+            ],
+            fields: &[
+                (
+                    "slack.botToken",
+                    "Slack Bot Token",
+                    "/channels/slack/botToken",
+                    true,
+                ),
+                (
+                    "slack.appToken",
+                    "Slack App Token",
+                    "/channels/slack/appToken",
+                    true,
+                ),
+                (
+                    "wecom.corpId",
+                    "企业微信 CorpID",
+                    "/channels/wecom/corpId",
+                    false,
+                ),
+                (
+                    "wecom.agentId",
+                    "企业微信 AgentID",
+                    "/channels/wecom/agentId",
+                    false,
+                ),
+                (
+                    "wecom.secret",
+                    "企业微信 Secret",
+                    "/channels/wecom/secret",
+                    true,
+                ),
+                (
+                    "openai.apiKey",
+                    "OpenAI API Key",
+                    "/models/providers/openai/apiKey",
+                    true,
+                ),
+            ],
+            disable_other_channels: false,
+        },
+        PresetDef {
+            id: "local-privacy",
+            name: "纯本地隐私模式",
+            description: "只使用本机 Ollama 模型，停用所有外部渠道，数据不离开本机",
+            channels: &[],
+            set: vec![
+                ("/gateway/mode", json!("local")),
+                (
+                    "/models/providers/ollama",
+                    json!({
+                        "baseUrl": "http://127.0.0.1:11434/v1",
+                        "api": "openai-completions",
+                        "apiKey": "ollama",
+                        "models": [{"id": "llama3.1", "name": "Llama 3.1", "input": ["text"]}],
+                    }),
+                ),
+                ("/agents/defaults/model/primary", json!("ollama/llama3.1")),
+            ],
+            defaults: vec![],
+            fields: &[],
+            disable_other_channels: true,
+        },
+    ]
+}
+
+/// JSON Pointer 的一段（处理 ~0 / ~1 转义）
+fn unescape(segment: &str) -> String {
+    segment.replace("~1", "/").replace("~0", "~")
+}
+
+/// 按 JSON Pointer 写入值，中间路径不存在或不是对象时创建对象
+fn set_pointer(root: &mut Value, pointer: &str, value: Value) {
+    let mut current = root;
+    for segment in pointer.split('/').skip(1) {
+        if !current.is_object() {
+            *current = json!({});
+        }
+        current = current
+            .as_object_mut()
+            .expect("刚确保为对象")
+            .entry(unescape(segment))
+            .or_insert(Value::Null);
+    }
+    *current = value;
+}
+
+fn is_set(config: &Value, pointer: &str) -> bool {
+    match config.pointer(pointer) {
+        None | Some(Value::Null) => false,
+        Some(Value::String(s)) => !s.trim().is_empty(),
+        Some(_) => true,
+    }
+}
+
+fn describe(def: &PresetDef, current: &Value) -> ConfigPreset {
+    ConfigPreset {
+        id: def.id.to_string(),
+        name: def.name.to_string(),
+        description: def.description.to_string(),
+        channels: def.channels.iter().map(|c| c.to_string()).collect(),
+        fields: def
+            .fields
+            .iter()
+            .map(|(key, label, path, secret)| PresetField {
+                key: key.to_string(),
+                label: label.to_string(),
+                path: path.to_string(),
+                secret: *secret,
+                filled: is_set(current, path),
+            })
+            .collect(),
+    }
+}
+
+/// 在当前配置上应用预设，返回新配置和仍需填写的配置项
+fn build(
+    def: &PresetDef,
+    current: &Value,
+    values: &HashMap<String, String>,
+) -> (Value, Vec<String>) {
+    let mut next = current.clone();
+    if !next.is_object() {
+        next = json!({});
+    }
+
+    if def.disable_other_channels {
+        if let Some(channels) = next.get_mut("channels").and_then(|c| c.as_object_mut()) {
+            for (id, channel) in channels.iter_mut() {
+                if !def.channels.contains(&id.as_str()) && channel.is_object() {
+                    channel["enabled"] = json!(false);
+                }
+            }
+        }
+        if let Some(entries) = next
+            .pointer_mut("/plugins/entries")
+            .and_then(|e| e.as_object_mut())
+        {
+            for (id, entry) in entries.iter_mut() {
+                if !def.channels.contains(&id.as_str()) && entry.is_object() {
+                    entry["enabled"] = json!(false);
+                }
+            }
+        }
+    }
+
+    for (pointer, value) in &def.defaults {
+        if next.pointer(pointer).is_none() {
+            set_pointer(&mut next, pointer, value.clone());
+        }
+    }
+    for (pointer, value) in &def.set {
+        set_pointer(&mut next, pointer, value.clone());
+    }
+
+    // 与 save_channel_config 一致：启用渠道并加入插件白名单
+    for channel in def.channels {
+        set_pointer(
+            &mut next,
+            &format!("/channels/{}/enabled", channel),
+            json!(true),
+        );
+        set_pointer(
+            &mut next,
+            &format!("/plugins/entries/{}/enabled", channel),
+            json!(true),
+        );
+        let allow = next.pointer_mut("/plugins/allow");
+        match allow.and_then(|a| a.as_array_mut()) {
+            Some(list) => {
+                if !list.contains(&json!(channel)) {
+                    list.push(json!(channel));
+                }
+            }
+            None => set_pointer(&mut next, "/plugins/allow", json!([channel])),
+        }
+    }
+
+    let mut missing = Vec::new();
+    for (key, _, pointer, _) in def.fields {
+        match values.get(*key).map(|v| v.trim()).filter(|v| !v.is_empty()) {
+            Some(value) => set_pointer(&mut next, pointer, json!(value)),
+            None if !is_set(&next, pointer) => missing.push(key.to_string()),
+            None => {}
+        }
+    }
+    (next, missing)
+}
+
+/// 新旧配置的差异（密钥字段只显示是否设置）
+fn diff(def: &PresetDef, before: &Value, after: &Value) -> Vec<PresetChange> {
+    let secret_paths: Vec<&str> = def
+        .fields
+        .iter()
+        .filter(|(_, _, _, secret)| *secret)
+        .map(|(_, _, path, _)| *path)
+        .collect();
+    let mask = |v: Option<Value>, path: &str| match v {
+        Some(Value::String(s)) if secret_paths.contains(&path) && !s.is_empty() => {
+            Some(json!("••••••"))
+        }
+        other => other,
+    };
+    config_conflict::three_way_diff(before, after, before)
+        .into_iter()
+        .map(|entry| PresetChange {
+            before: mask(entry.base, &entry.path),
+            after: mask(entry.ours, &entry.path),
+            path: entry.path,
+        })
+        .collect()
+}
+
+fn find(name: &str) -> Result<PresetDef, String> {
+    presets()
+        .into_iter()
+        .find(|p| p.id == name)
+        .ok_or_else(|| format!("未知的配置预设: {}", name))
+}
+
+/// 列出内置的配置预设
+#[guarded]
+#[command]
+pub async fn list_config_presets() -> Result<Vec<ConfigPreset>, String> {
+    let current = config::load_openclaw_config().unwrap_or_else(|_| json!({}));
+    Ok(presets().iter().map(|p| describe(p, &current)).collect())
+}
+
+/// 应用配置预设：一次性写入 Gateway 模式、渠道和 Provider 占位
+/// `values` 填写预设需要的配置项（键见 [`PresetField::key`]）；
+/// `dry_run` 为 true 时只返回差异预览，不修改配置
+#[guarded]
+#[command]
+pub async fn apply_config_preset(
+    name: String,
+    values: Option<HashMap<String, String>>,
+    dry_run: Option<bool>,
+) -> Result<PresetPlan, String> {
+    let dry_run = dry_run.unwrap_or(false);
+    info!(
+        "[配置预设] {} 预设: {}",
+        if dry_run { "预览" } else { "应用" },
+        name
+    );
+    let def = find(&name)?;
+    let current = config::load_openclaw_config()?;
+    let (next, missing) = build(&def, &current, &values.unwrap_or_default());
+    let mut plan = PresetPlan {
+        preset: describe(&def, &current),
+        changes: diff(&def, &current, &next),
+        missing,
+        applied: false,
+    };
+    if dry_run {
+        return Ok(plan);
+    }
+    if !plan.missing.is_empty() {
+        warn!("[配置预设] ✗ 缺少配置项: {:?}", plan.missing);
+        return Err(format!("请先填写: {}", plan.missing.join(", ")));
+    }
+
+    // 整份配置一次写入（带外部修改冲突检测），不会只应用一部分
+    let result = config::save_config(next).await;
+    audit::record(
+        "apply_config_preset",
+        &name,
+        result.is_ok(),
+        json!({ "changes": plan.changes.len() }),
+    );
+    result?;
+    plan.applied = true;
+    info!(
+        "[配置预设] ✓ 已应用 {}，{} 项变更",
+        name,
+        plan.changes.len()
+    );
+    Ok(plan)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn preset_keeps_existing_values_and_reports_missing_fields() {
+        let def = find("personal-telegram").unwrap();
+        let current = json!({
+            "gateway": {"mode": "remote"},
+            "models": {"providers": {"anthropic": {"baseUrl": "https://proxy.example", "apiKey": "sk-old"}}},
+            "plugins": {"allow": ["feishu"], "entries": {}},
+        });
+
+        let (next, missing) = build(&def, &current, &HashMap::new());
+        assert_eq!(missing, vec!["telegram.botToken".to_string()]);
+        assert_eq!(next["gateway"]["mode"], "local");
+        assert_eq!(
+            next["models"]["providers"]["anthropic"]["baseUrl"],
+            "https://proxy.example"
+        );
+        assert_eq!(next["plugins"]["allow"], json!(["feishu", "telegram"]));
+
+        let values = HashMap::from([("telegram.botToken".to_string(), "123:abc".to_string())]);
+        let (next, missing) = build(&def, &current, &values);
+        assert!(missing.is_empty());
+        let changes = diff(&def, &current, &next);
+        let token = changes
+            .iter()
+            .find(|c| c.path == "/channels/telegram/botToken")
+            .unwrap();
+        assert_eq!(token.after, Some(json!("••••••")));
+    }
+}
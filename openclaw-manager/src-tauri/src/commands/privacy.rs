@@ -0,0 +1,396 @@
+use crate::commands::{config, service, settings, sidecar};
+use crate::models::DiagnosticResult;
+use crate::utils::{audit, encoding, file, platform, shell};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use openclaw_macros::guarded;
+use tauri::command;
+
+/// 隐私模式下为子进程设置的环境变量：(目标, 变量, 值)
+/// 目标为 gateway 或 npm，对应设置中的 env_overrides
+const PRIVACY_ENV: &[(&str, &str, &str)] = &[
+    ("gateway", "DO_NOT_TRACK", "1"),
+    ("gateway", "OPENCLAW_TELEMETRY_DISABLED", "1"),
+    ("npm", "npm_config_update_notifier", "false"),
+    ("npm", "npm_config_fund", "false"),
+    ("npm", "npm_config_audit", "false"),
+];
+
+/// 开启隐私模式前的模型设置（关闭时恢复）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct PrivacyState {
+    primary: Option<String>,
+    fallbacks: Option<Value>,
+}
+
+/// 仍在向外连接的组件
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OutboundConnection {
+    /// gateway / manager / 辅助进程名
+    pub component: String,
+    pub pid: u32,
+    /// 远端地址（host:port）
+    pub remote: String,
+}
+
+/// 隐私模式状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrivacyStatus {
+    pub enabled: bool,
+    /// 未指向本机的 Provider
+    pub remote_providers: Vec<String>,
+    /// 当前仍在向外连接的组件（只检查 Manager 启动的进程）
+    pub outbound: Vec<OutboundConnection>,
+}
+
+fn state_path() -> PathBuf {
+    PathBuf::from(platform::get_manager_data_dir()).join("privacy_state.json")
+}
+
+fn load_state() -> PrivacyState {
+    std::fs::read_to_string(state_path())
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+fn save_state(state: &PrivacyState) -> Result<(), String> {
+    let content = serde_json::to_vec_pretty(state).map_err(|e| e.to_string())?;
+    file::write_atomic(&state_path(), &content).map_err(|e| format!("保存隐私模式状态失败: {}", e))
+}
+
+/// 隐私模式是否开启
+pub fn is_enabled() -> bool {
+    settings::load_settings().privacy_mode
+}
+
+/// 隐私模式下拒绝非必要的联网操作（更新检查、镜像测速等）
+pub fn guard(operation: &str) -> Result<(), String> {
+    if is_enabled() {
+        info!("[隐私模式] 已阻止: {}", operation);
+        return Err(format!("隐私模式已开启，{}已禁用", operation));
+    }
+    Ok(())
+}
+
+/// 主机是否为本机回环地址
+fn is_loopback_host(host: &str) -> bool {
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    host.eq_ignore_ascii_case("localhost")
+        || host.starts_with("127.")
+        || host == "::1"
+        || host == "0:0:0:0:0:0:0:1"
+        || host.starts_with("::ffff:127.")
+}
+
+/// Provider 地址是否指向本机
+pub fn is_local_url(url: &str) -> bool {
+    reqwest::Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(is_loopback_host))
+        .unwrap_or(false)
+}
+
+/// 配置中未指向本机的 Provider
+fn remote_providers(cfg: &Value) -> Vec<String> {
+    let mut names: Vec<String> = cfg
+        .pointer("/models/providers")
+        .and_then(|p| p.as_object())
+        .map(|providers| {
+            providers
+                .iter()
+                .filter(|(_, p)| {
+                    !p.get("baseUrl")
+                        .and_then(|u| u.as_str())
+                        .is_some_and(is_local_url)
+                })
+                .map(|(name, _)| name.clone())
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+    names
+}
+
+/// 把主模型和备用模型限定为本地 Provider，返回修改前的设置
+fn pin_local_models(cfg: &mut Value) -> Result<PrivacyState, String> {
+    let remote = remote_providers(cfg);
+    let is_local_model = |id: &str| {
+        id.split_once('/')
+            .is_some_and(|(provider, _)| !remote.iter().any(|r| r == provider))
+    };
+    let local_model = cfg
+        .pointer("/models/providers")
+        .and_then(|p| p.as_object())
+        .and_then(|providers| {
+            providers
+                .iter()
+                .filter(|(name, _)| !remote.contains(name))
+                .find_map(|(name, p)| {
+                    let model = p.pointer("/models/0/id")?.as_str()?;
+                    Some(format!("{}/{}", name, model))
+                })
+        })
+        .ok_or("没有本地模型 Provider，请先添加 Ollama 等本机 Provider（或应用「纯本地隐私模式」预设）")?;
+
+    let previous = PrivacyState {
+        primary: cfg
+            .pointer("/agents/defaults/model/primary")
+            .and_then(|v| v.as_str())
+            .map(String::from),
+        fallbacks: cfg.pointer("/agents/defaults/model/fallbacks").cloned(),
+    };
+    if !previous.primary.as_deref().is_some_and(is_local_model) {
+        cfg["agents"]["defaults"]["model"]["primary"] = json!(local_model);
+    }
+    if let Some(list) = cfg
+        .pointer_mut("/agents/defaults/model/fallbacks")
+        .and_then(|f| f.as_array_mut())
+    {
+        list.retain(|m| m.as_str().is_some_and(is_local_model));
+    }
+    Ok(previous)
+}
+
+/// 开启 / 关闭子进程环境变量中的遥测开关
+fn apply_env(settings: &mut settings::ManagerSettings, enabled: bool) {
+    for (target, key, value) in PRIVACY_ENV {
+        let map = match *target {
+            "gateway" => &mut settings.env_overrides.gateway,
+            _ => &mut settings.env_overrides.npm,
+        };
+        if enabled {
+            map.insert(key.to_string(), value.to_string());
+        } else if map.get(*key).map(String::as_str) == Some(*value) {
+            map.remove(*key);
+        }
+    }
+}
+
+/// 解析 `lsof -nP -iTCP -sTCP:ESTABLISHED` 或 Windows `netstat -ano` 的输出，返回 (PID, 远端地址)
+fn parse_connections(output: &str, windows: bool) -> Vec<(u32, String)> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let cols: Vec<&str> = line.split_whitespace().collect();
+            if windows {
+                // TCP  本地地址  远端地址  ESTABLISHED  PID
+                if cols.len() < 5 || cols[0] != "TCP" || cols[3] != "ESTABLISHED" {
+                    return None;
+                }
+                Some((cols[4].parse().ok()?, cols[2].to_string()))
+            } else {
+                // COMMAND PID USER FD TYPE DEVICE SIZE/OFF NODE NAME (ESTABLISHED)
+                let pid = cols.get(1)?.parse().ok()?;
+                let (_, remote) = cols.iter().find_map(|c| c.split_once("->"))?;
+                Some((pid, remote.to_string()))
+            }
+        })
+        .collect()
+}
+
+fn remote_host(remote: &str) -> &str {
+    remote
+        .rsplit_once(':')
+        .map(|(host, _)| host)
+        .unwrap_or(remote)
+}
+
+/// Manager、Gateway 和辅助进程中连向非本机地址的 TCP 连接
+pub async fn outbound_connections() -> Vec<OutboundConnection> {
+    let mut components = vec![("manager".to_string(), std::process::id())];
+    if let Some(pid) = service::gateway_pid() {
+        components.push(("gateway".to_string(), pid));
+    }
+    for s in sidecar::list_sidecars().await.unwrap_or_default() {
+        if let Some(pid) = s.pid {
+            components.push((s.name, pid));
+        }
+    }
+
+    let windows = platform::is_windows();
+    // lsof 没有匹配的连接时退出码为 1，只看 stdout
+    let output = if windows {
+        shell::run_command("netstat", &["-ano", "-p", "TCP"])
+    } else {
+        let pids: Vec<String> = components.iter().map(|(_, p)| p.to_string()).collect();
+        shell::run_command(
+            "lsof",
+            &[
+                "-nP",
+                "-iTCP",
+                "-sTCP:ESTABLISHED",
+                "-a",
+                "-p",
+                &pids.join(","),
+            ],
+        )
+    };
+    let output = match output {
+        Ok(o) => encoding::decode_output(&o.stdout),
+        Err(e) => {
+            warn!("[隐私模式] 查询网络连接失败: {}", e);
+            return Vec::new();
+        }
+    };
+
+    let mut connections: Vec<OutboundConnection> = parse_connections(&output, windows)
+        .into_iter()
+        .filter(|(_, remote)| !is_loopback_host(remote_host(remote)))
+        .filter_map(|(pid, remote)| {
+            let (component, _) = components.iter().find(|(_, p)| *p == pid)?;
+            Some(OutboundConnection {
+                component: component.clone(),
+                pid,
+                remote,
+            })
+        })
+        .collect();
+    connections.dedup();
+    connections
+}
+
+/// 诊断项：隐私模式开启时报告仍在向外连接的组件
+pub async fn diagnostic() -> Option<DiagnosticResult> {
+    if !is_enabled() {
+        return None;
+    }
+    let outbound = outbound_connections().await;
+    let passed = outbound.is_empty();
+    Some(DiagnosticResult {
+        name: "隐私模式".to_string(),
+        passed,
+        message: if passed {
+            "未发现向外部的网络连接".to_string()
+        } else {
+            outbound
+                .iter()
+                .map(|c| format!("{} (PID {}) → {}", c.component, c.pid, c.remote))
+                .collect::<Vec<_>>()
+                .join("\n")
+        },
+        suggestion: if passed {
+            None
+        } else {
+            Some("检查上述组件使用的 Provider、渠道或技能是否访问外网".to_string())
+        },
+        fix: None,
+    })
+}
+
+/// 获取隐私模式状态
+#[guarded]
+#[command]
+pub async fn get_privacy_status() -> Result<PrivacyStatus, String> {
+    let cfg = config::load_openclaw_config().unwrap_or_else(|_| json!({}));
+    Ok(PrivacyStatus {
+        enabled: is_enabled(),
+        remote_providers: remote_providers(&cfg),
+        outbound: outbound_connections().await,
+    })
+}
+
+/// 开启 / 关闭隐私模式：
+/// 关闭 Gateway 和 npm 的遥测、模型只使用本地 Provider、停止更新检查和镜像测速，
+/// 关闭时恢复原来的主模型和备用模型
+#[guarded]
+#[command]
+pub async fn set_privacy_mode(enabled: bool) -> Result<PrivacyStatus, String> {
+    info!(
+        "[隐私模式] {}隐私模式...",
+        if enabled { "开启" } else { "关闭" }
+    );
+    let settings = settings::load_settings();
+    let mut cfg = config::load_openclaw_config()?;
+
+    if enabled {
+        let previous = pin_local_models(&mut cfg)?;
+        if !settings.privacy_mode {
+            save_state(&previous)?;
+        }
+    } else if settings.privacy_mode {
+        let previous = load_state();
+        if let Some(primary) = previous.primary {
+            cfg["agents"]["defaults"]["model"]["primary"] = json!(primary);
+        }
+        if let Some(fallbacks) = previous.fallbacks {
+            cfg["agents"]["defaults"]["model"]["fallbacks"] = fallbacks;
+        }
+        let _ = std::fs::remove_file(state_path());
+    }
+
+    config::save_config(cfg).await?;
+    settings::update(|current| {
+        current.privacy_mode = enabled;
+        apply_env(current, enabled);
+        Ok(())
+    })?;
+    audit::record(
+        "set_privacy_mode",
+        "privacy",
+        true,
+        json!({ "enabled": enabled }),
+    );
+
+    // 环境变量和模型设置在 Gateway 重启后生效
+    service::restart_if_running().await;
+    info!(
+        "[隐私模式] ✓ 隐私模式已{}",
+        if enabled { "开启" } else { "关闭" }
+    );
+    Ok(get_privacy_status().await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pins_models_to_local_providers() {
+        let mut cfg = json!({
+            "models": {"providers": {
+                "anthropic": {"baseUrl": "https://api.anthropic.com", "models": [{"id": "claude"}]},
+                "ollama": {"baseUrl": "http://127.0.0.1:11434/v1", "models": [{"id": "llama3.1"}]},
+            }},
+            "agents": {"defaults": {"model": {
+                "primary": "anthropic/claude",
+                "fallbacks": ["ollama/llama3.1", "anthropic/claude"],
+            }}},
+        });
+        assert_eq!(remote_providers(&cfg), vec!["anthropic".to_string()]);
+
+        let previous = pin_local_models(&mut cfg).unwrap();
+        assert_eq!(previous.primary.as_deref(), Some("anthropic/claude"));
+        assert_eq!(
+            cfg["agents"]["defaults"]["model"]["primary"],
+            "ollama/llama3.1"
+        );
+        assert_eq!(
+            cfg["agents"]["defaults"]["model"]["fallbacks"],
+            json!(["ollama/llama3.1"])
+        );
+    }
+
+    #[test]
+    fn parses_lsof_and_netstat_connections() {
+        let lsof = "COMMAND PID USER FD TYPE DEVICE SIZE/OFF NODE NAME\n\
+            node 4242 me 23u IPv4 0x1 0t0 TCP 10.0.0.2:51234->142.250.1.1:443 (ESTABLISHED)\n\
+            node 4242 me 24u IPv6 0x2 0t0 TCP [::1]:51000->[::1]:18789 (ESTABLISHED)\n";
+        let parsed = parse_connections(lsof, false);
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[0], (4242, "142.250.1.1:443".to_string()));
+        assert!(is_loopback_host(remote_host(&parsed[1].1)));
+
+        let netstat =
+            "  Proto  Local Address          Foreign Address        State           PID\n\
+            \x20 TCP    10.0.0.2:51234         142.250.1.1:443        ESTABLISHED     4242\n\
+            \x20 TCP    0.0.0.0:18789          0.0.0.0:0              LISTENING       4242\n";
+        assert_eq!(
+            parse_connections(netstat, true),
+            vec![(4242, "142.250.1.1:443".to_string())]
+        );
+    }
+}
@@ -0,0 +1,207 @@
+//! 探测结果缓存：版本检查、npm view 等耗时探测在 TTL 内直接返回上次结果，
+//! 同时写入 Manager 数据目录的 probe-cache.json，重启 Manager 后仍然有效
+use crate::utils::{file, platform};
+use log::{debug, info, warn};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use openclaw_macros::guarded;
+use tauri::command;
+
+/// 已安装的 Node.js 版本
+pub const NODE_VERSION: &str = "node_version";
+/// 已安装的 OpenClaw 版本
+pub const OPENCLAW_VERSION: &str = "openclaw_version";
+/// npm registry 上的最新 OpenClaw 版本（键后附加包名和更新通道）
+pub const LATEST_OPENCLAW_VERSION: &str = "latest_openclaw_version";
+
+/// 本机版本的缓存时间（安装、更新、卸载后会主动失效）
+pub const LOCAL_VERSION_TTL: Duration = Duration::from_secs(10 * 60);
+/// 远程最新版本的缓存时间
+pub const LATEST_VERSION_TTL: Duration = Duration::from_secs(30 * 60);
+
+/// 缓存条目
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct CacheEntry {
+    value: Value,
+    /// 毫秒时间戳
+    stored_at: i64,
+    expires_at: i64,
+}
+
+/// 缓存条目概况（不含缓存内容）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheEntryInfo {
+    pub key: String,
+    pub stored_at: i64,
+    pub expires_at: i64,
+    pub expired: bool,
+}
+
+/// 内存中的缓存，首次访问时从磁盘加载
+static CACHE: Mutex<Option<HashMap<String, CacheEntry>>> = Mutex::new(None);
+
+fn cache_path() -> PathBuf {
+    Path::new(&platform::get_manager_data_dir()).join("probe-cache.json")
+}
+
+fn now_ms() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
+
+fn load_disk(path: &Path) -> HashMap<String, CacheEntry> {
+    std::fs::read(path)
+        .ok()
+        .and_then(|content| serde_json::from_slice(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_disk(path: &Path, entries: &HashMap<String, CacheEntry>) {
+    let result = serde_json::to_vec_pretty(entries)
+        .map_err(|e| e.to_string())
+        .and_then(|content| file::write_atomic(path, &content).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+        warn!("[探测缓存] 保存缓存失败: {}", e);
+    }
+}
+
+/// 在缓存上执行操作，`persist` 为 true 时操作后写回磁盘
+fn with_cache<R>(persist: bool, f: impl FnOnce(&mut HashMap<String, CacheEntry>) -> R) -> R {
+    let mut guard = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    let entries = guard.get_or_insert_with(|| load_disk(&cache_path()));
+    let result = f(entries);
+    if persist {
+        entries.retain(|_, entry| entry.expires_at > now_ms());
+        save_disk(&cache_path(), entries);
+    }
+    result
+}
+
+fn lookup(entries: &HashMap<String, CacheEntry>, key: &str, now: i64) -> Option<Value> {
+    entries
+        .get(key)
+        .filter(|entry| entry.expires_at > now)
+        .map(|entry| entry.value.clone())
+}
+
+/// 读取缓存，未命中或已过期时执行 `probe` 并缓存成功的结果（失败不缓存）
+/// 探测过程不持有锁，同一键并发未命中时可能重复探测
+pub fn get_or_probe<T, F>(key: &str, ttl: Duration, probe: F) -> Result<T, String>
+where
+    T: Serialize + DeserializeOwned,
+    F: FnOnce() -> Result<T, String>,
+{
+    let cached = with_cache(false, |entries| lookup(entries, key, now_ms()));
+    if let Some(value) = cached.and_then(|v| serde_json::from_value(v).ok()) {
+        debug!("[探测缓存] 命中: {}", key);
+        return Ok(value);
+    }
+    let value = probe()?;
+    if let Ok(json) = serde_json::to_value(&value) {
+        let now = now_ms();
+        let entry = CacheEntry {
+            value: json,
+            stored_at: now,
+            expires_at: now + ttl.as_millis() as i64,
+        };
+        with_cache(true, |entries| entries.insert(key.to_string(), entry));
+    }
+    Ok(value)
+}
+
+/// 使缓存失效：指定键（也会清除以 `键:` 开头的子键）或全部，返回清除的条目数
+pub fn invalidate(key: Option<&str>) -> usize {
+    with_cache(true, |entries| {
+        let before = entries.len();
+        match key {
+            Some(key) => {
+                let prefix = format!("{}:", key);
+                entries.retain(|k, _| k != key && !k.starts_with(&prefix));
+            }
+            None => entries.clear(),
+        }
+        before - entries.len()
+    })
+}
+
+/// 本机安装的组件可能已变化（安装、更新、卸载后调用）
+pub fn invalidate_versions() {
+    for key in [NODE_VERSION, OPENCLAW_VERSION, LATEST_OPENCLAW_VERSION] {
+        invalidate(Some(key));
+    }
+}
+
+/// 离开作用域时使本机版本缓存失效（用于有多个返回路径的安装 / 卸载流程）
+pub struct InvalidateVersionsOnDrop;
+
+impl Drop for InvalidateVersionsOnDrop {
+    fn drop(&mut self) {
+        invalidate_versions();
+    }
+}
+
+/// 查看缓存条目
+#[guarded]
+#[command]
+pub async fn list_probe_cache() -> Result<Vec<CacheEntryInfo>, String> {
+    let now = now_ms();
+    let mut entries: Vec<CacheEntryInfo> = with_cache(false, |entries| {
+        entries
+            .iter()
+            .map(|(key, entry)| CacheEntryInfo {
+                key: key.clone(),
+                stored_at: entry.stored_at,
+                expires_at: entry.expires_at,
+                expired: entry.expires_at <= now,
+            })
+            .collect()
+    });
+    entries.sort_by(|a, b| a.key.cmp(&b.key));
+    Ok(entries)
+}
+
+/// 使缓存失效（`key` 为空时清除全部），下次访问时重新探测
+#[guarded]
+#[command]
+pub async fn invalidate_cache(key: Option<String>) -> Result<usize, String> {
+    let removed = invalidate(key.as_deref());
+    info!(
+        "[探测缓存] ✓ 已清除 {} 个缓存条目 ({})",
+        removed,
+        key.as_deref().unwrap_or("全部")
+    );
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn looks_up_unexpired_entries_and_round_trips_to_disk() {
+        let entry = |value: &str, expires_at: i64| CacheEntry {
+            value: Value::from(value),
+            stored_at: 0,
+            expires_at,
+        };
+        let mut entries = HashMap::new();
+        entries.insert(NODE_VERSION.to_string(), entry("v22.1.0", 2_000));
+        entries.insert(OPENCLAW_VERSION.to_string(), entry("2026.1.5", 500));
+        assert_eq!(
+            lookup(&entries, NODE_VERSION, 1_000),
+            Some(Value::from("v22.1.0"))
+        );
+        assert_eq!(lookup(&entries, OPENCLAW_VERSION, 1_000), None);
+        assert_eq!(lookup(&entries, "missing", 1_000), None);
+
+        let path = std::env::temp_dir().join(format!("probe-cache-{}.json", uuid::Uuid::new_v4()));
+        save_disk(&path, &entries);
+        assert_eq!(load_disk(&path), entries);
+        let _ = std::fs::remove_file(&path);
+        assert!(load_disk(&path).is_empty());
+    }
+}
@@ -1,8 +1,10 @@
 use crate::utils::shell;
+use openclaw_macros::guarded;
 use tauri::command;
 use log::{info, debug};
 
 /// 检查 OpenClaw 是否已安装
+#[guarded]
 #[command]
 pub async fn check_openclaw_installed() -> Result<bool, String> {
     info!("[进程检查] 检查 OpenClaw 是否已安装...");
@@ -13,6 +15,7 @@ pub async fn check_openclaw_installed() -> Result<bool, String> {
 }
 
 /// 获取 OpenClaw 版本
+#[guarded]
 #[command]
 pub async fn get_openclaw_version() -> Result<Option<String>, String> {
     info!("[进程检查] 获取 OpenClaw 版本...");
@@ -31,10 +34,11 @@ pub async fn get_openclaw_version() -> Result<Option<String>, String> {
 }
 
 /// 检查端口是否被占用（通过尝试连接 openclaw gateway）
+#[guarded]
 #[command]
 pub async fn check_port_in_use(port: u16) -> Result<bool, String> {
     info!("[进程检查] 检查端口 {} 是否被占用...", port);
-    
+
     // 使用 openclaw health 检查 gateway 是否在运行
     // 如果 port 是默认的 18789，直接使用 openclaw health
     if port == 18789 {
@@ -45,12 +49,12 @@ pub async fn check_port_in_use(port: u16) -> Result<bool, String> {
         info!("[进程检查] 端口 18789 状态: {}", if in_use { "被占用" } else { "空闲" });
         return Ok(in_use);
     }
-    
+
     // 对于非默认端口，尝试使用 TCP 连接检查
     debug!("[进程检查] 使用 TCP 连接检查端口 {}...", port);
     use std::net::TcpStream;
     use std::time::Duration;
-    
+
     let addr = format!("127.0.0.1:{}", port);
     match TcpStream::connect_timeout(&addr.parse().unwrap(), Duration::from_millis(500)) {
         Ok(_) => {
@@ -65,6 +69,7 @@ pub async fn check_port_in_use(port: u16) -> Result<bool, String> {
 }
 
 /// 获取 Node.js 版本
+#[guarded]
 #[command]
 pub async fn get_node_version() -> Result<Option<String>, String> {
     info!("[进程检查] 获取 Node.js 版本...");
@@ -72,7 +77,7 @@ pub async fn get_node_version() -> Result<Option<String>, String> {
         info!("[进程检查] Node.js 未安装");
         return Ok(None);
     }
-    
+
     match shell::run_command_output("node", &["--version"]) {
         Ok(version) => {
             info!("[进程检查] Node.js 版本: {}", version);
@@ -0,0 +1,373 @@
+//! 配置档案：为不同使用场景（如 work、personal）各用一套 OpenClaw 配置目录，分别保存 Provider、
+//! 渠道和凭据。默认档案使用 ~/.openclaw，其他档案使用 ~/.openclaw-<name>（与 `openclaw --profile`
+//! 的目录约定一致）。切换时停止 Gateway，切换 Manager 读写的配置目录，openclaw 子进程通过
+//! OPENCLAW_STATE_DIR / OPENCLAW_CONFIG_PATH 使用同一目录，切换前 Gateway 在运行时再启动
+use crate::commands::{events, policy, probe_cache, service, watcher};
+use crate::utils::{audit, file, platform, time};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use openclaw_macros::guarded;
+use tauri::{command, AppHandle};
+
+/// 默认档案名
+pub const DEFAULT_PROFILE: &str = "default";
+/// 切换档案后发送的事件（载荷为新的档案名）
+pub const PROFILE_SWITCHED_EVENT: &str = "profile://switched";
+/// 新建档案时从来源档案复制的文件（Provider、渠道配置和环境变量）
+const COPIED_FILES: &[&str] = &["openclaw.json", "env", ".env"];
+
+/// 配置档案
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Profile {
+    pub name: String,
+    pub description: Option<String>,
+    pub config_dir: String,
+    pub created_at: String,
+}
+
+/// 档案列表（保存在 Manager 数据目录的 profiles.json）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct ProfilesFile {
+    /// 当前档案（None 为默认档案）
+    active: Option<String>,
+    profiles: Vec<Profile>,
+}
+
+/// 档案及其配置概况
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileStatus {
+    #[serde(flatten)]
+    pub profile: Profile,
+    pub active: bool,
+    /// 已有 openclaw.json
+    pub initialized: bool,
+    pub providers: usize,
+    pub channels: usize,
+    /// 当前档案的 Gateway 是否在运行（非当前档案为 None）
+    pub gateway_running: Option<bool>,
+}
+
+/// 切换档案的互斥锁（避免并发切换时 Gateway 启停交错）
+static SWITCH_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+/// profiles.json 的读写锁
+static LOCK: Mutex<()> = Mutex::new(());
+
+fn profiles_path() -> PathBuf {
+    Path::new(&platform::get_manager_data_dir()).join("profiles.json")
+}
+
+fn load() -> ProfilesFile {
+    std::fs::read_to_string(profiles_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save(profiles: &ProfilesFile) -> Result<(), String> {
+    let content = serde_json::to_vec_pretty(profiles).map_err(|e| e.to_string())?;
+    file::write_atomic(&profiles_path(), &content).map_err(|e| format!("保存配置档案失败: {}", e))
+}
+
+/// 默认档案的配置目录（Manager 启动时已设置 OPENCLAW_STATE_DIR 则沿用）
+fn default_profile() -> Profile {
+    let config_dir = match std::env::var_os(platform::STATE_DIR_ENV).filter(|d| !d.is_empty()) {
+        Some(dir) => PathBuf::from(dir).display().to_string(),
+        None => platform::get_default_config_dir(),
+    };
+    Profile {
+        name: DEFAULT_PROFILE.to_string(),
+        description: Some("默认配置".to_string()),
+        config_dir,
+        created_at: String::new(),
+    }
+}
+
+/// 校验档案名：小写字母、数字、- 和 _，以字母或数字开头，最长 32 个字符
+fn validate_name(name: &str) -> Result<String, String> {
+    let name = name.trim().to_lowercase();
+    let valid = !name.is_empty()
+        && name.len() <= 32
+        && name.starts_with(|c: char| c.is_ascii_alphanumeric())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        return Err(format!(
+            "档案名无效: {}（只能包含小写字母、数字、- 和 _，最长 32 个字符）",
+            name
+        ));
+    }
+    Ok(name)
+}
+
+/// 档案的配置目录：与默认配置目录同级的 .openclaw-<name>
+fn profile_dir(default_dir: &Path, name: &str) -> PathBuf {
+    default_dir.with_file_name(format!(".openclaw-{}", name))
+}
+
+fn find(profiles: &ProfilesFile, name: &str) -> Option<Profile> {
+    if name == DEFAULT_PROFILE {
+        return Some(default_profile());
+    }
+    profiles.profiles.iter().find(|p| p.name == name).cloned()
+}
+
+fn active_name(profiles: &ProfilesFile) -> String {
+    profiles
+        .active
+        .clone()
+        .filter(|name| find(profiles, name).is_some())
+        .unwrap_or_else(|| DEFAULT_PROFILE.to_string())
+}
+
+/// 当前档案名
+pub fn active_profile() -> String {
+    let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    active_name(&load())
+}
+
+/// 切换 Manager 和 openclaw 子进程使用的配置目录（默认档案沿用启动时的环境变量）
+fn activate(profile: &Profile) {
+    platform::set_profile_dir(
+        (profile.name != DEFAULT_PROFILE).then(|| PathBuf::from(&profile.config_dir)),
+    );
+}
+
+/// Manager 启动时调用：切换到上次使用的档案
+pub fn init() {
+    let profiles = load();
+    let name = active_name(&profiles);
+    if let Some(profile) = find(&profiles, &name).filter(|p| p.name != DEFAULT_PROFILE) {
+        info!(
+            "[配置档案] 使用档案 {} ({})",
+            profile.name, profile.config_dir
+        );
+        activate(&profile);
+    }
+}
+
+/// openclaw.json 中的 Provider 和渠道数量
+fn count_config(cfg: &Value) -> (usize, usize) {
+    let count = |v: Option<&Value>| v.and_then(Value::as_object).map_or(0, |o| o.len());
+    (
+        count(cfg.pointer("/models/providers")),
+        count(cfg.get("channels")),
+    )
+}
+
+async fn status_of(profile: Profile, active: bool) -> ProfileStatus {
+    let config_path = Path::new(&profile.config_dir).join("openclaw.json");
+    let cfg = std::fs::read_to_string(&config_path)
+        .ok()
+        .and_then(|content| serde_json::from_str::<Value>(&content).ok());
+    let (providers, channels) = cfg.as_ref().map(count_config).unwrap_or_default();
+    let gateway_running = if active {
+        Some(matches!(service::get_service_status().await, Ok(s) if s.running))
+    } else {
+        None
+    };
+    ProfileStatus {
+        initialized: config_path.exists(),
+        providers,
+        channels,
+        active,
+        gateway_running,
+        profile,
+    }
+}
+
+/// 列出配置档案（默认档案在最前）
+#[guarded]
+#[command]
+pub async fn list_profiles() -> Result<Vec<ProfileStatus>, String> {
+    let (profiles, active) = {
+        let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let profiles = load();
+        let active = active_name(&profiles);
+        (profiles, active)
+    };
+    let mut result = Vec::new();
+    for profile in std::iter::once(default_profile()).chain(profiles.profiles) {
+        let is_active = profile.name == active;
+        result.push(status_of(profile, is_active).await);
+    }
+    Ok(result)
+}
+
+/// 新建配置档案，`copy_from` 为来源档案时复制其 openclaw.json 和环境变量文件
+#[guarded]
+#[command]
+pub async fn create_profile(
+    name: String,
+    description: Option<String>,
+    copy_from: Option<String>,
+) -> Result<Profile, String> {
+    let name = validate_name(&name)?;
+    let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut profiles = load();
+    if find(&profiles, &name).is_some() {
+        return Err(format!("档案 {} 已存在", name));
+    }
+    let default_dir = PathBuf::from(default_profile().config_dir);
+    let dir = profile_dir(&default_dir, &name);
+    std::fs::create_dir_all(&dir).map_err(|e| format!("创建配置目录失败: {}", e))?;
+
+    if let Some(source) = copy_from
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+    {
+        let source =
+            find(&profiles, source).ok_or_else(|| format!("档案 {} 不存在", source))?;
+        for file_name in COPIED_FILES {
+            let src = Path::new(&source.config_dir).join(file_name);
+            let dst = dir.join(file_name);
+            if src.exists() && !dst.exists() {
+                file::copy_atomic(&src, &dst)
+                    .map_err(|e| format!("复制 {} 失败: {}", file_name, e))?;
+            }
+        }
+    }
+
+    let profile = Profile {
+        name: name.clone(),
+        description: description
+            .map(|d| d.trim().to_string())
+            .filter(|d| !d.is_empty()),
+        config_dir: dir.display().to_string(),
+        created_at: time::now_rfc3339(),
+    };
+    profiles.profiles.push(profile.clone());
+    save(&profiles)?;
+    audit::record(
+        "create_profile",
+        &name,
+        true,
+        json!({ "config_dir": profile.config_dir, "copy_from": copy_from }),
+    );
+    info!("[配置档案] ✓ 已创建档案 {} ({})", name, profile.config_dir);
+    Ok(profile)
+}
+
+/// 删除配置档案（不能删除默认档案和当前档案）；`remove_files` 为 true 时同时删除其配置目录（需要确认令牌）
+#[guarded]
+#[command]
+pub async fn delete_profile(
+    name: String,
+    remove_files: bool,
+    confirm_token: Option<String>,
+) -> Result<String, String> {
+    if remove_files {
+        policy::require_confirmation("delete_profile", confirm_token.as_deref())?;
+    }
+    let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut profiles = load();
+    if name == DEFAULT_PROFILE {
+        return Err("不能删除默认档案".to_string());
+    }
+    if active_name(&profiles) == name {
+        return Err("不能删除当前使用的档案，请先切换到其他档案".to_string());
+    }
+    let index = profiles
+        .profiles
+        .iter()
+        .position(|p| p.name == name)
+        .ok_or_else(|| format!("档案 {} 不存在", name))?;
+    let profile = profiles.profiles.remove(index);
+    save(&profiles)?;
+    if remove_files {
+        std::fs::remove_dir_all(&profile.config_dir)
+            .map_err(|e| format!("档案已删除，但删除配置目录失败: {}", e))?;
+    }
+    audit::record(
+        "delete_profile",
+        &name,
+        true,
+        json!({ "config_dir": profile.config_dir, "remove_files": remove_files }),
+    );
+    info!("[配置档案] 已删除档案 {}", name);
+    Ok(format!("已删除档案 {}", name))
+}
+
+/// 切换配置档案：停止 Gateway，切换配置目录，切换前 Gateway 在运行时使用新档案重新启动
+#[guarded]
+#[command]
+pub async fn switch_profile(app: AppHandle, name: String) -> Result<String, String> {
+    let _switching = SWITCH_LOCK.lock().await;
+    let (mut profiles, current) = {
+        let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let profiles = load();
+        let current = active_name(&profiles);
+        (profiles, current)
+    };
+    let target = find(&profiles, &name).ok_or_else(|| format!("档案 {} 不存在", name))?;
+    if target.name == current {
+        return Ok(format!("已在使用档案 {}", name));
+    }
+    info!("[配置档案] 从 {} 切换到 {}...", current, target.name);
+
+    let was_running = matches!(service::get_service_status().await, Ok(s) if s.running);
+    if was_running {
+        service::stop_service().await?;
+    }
+
+    activate(&target);
+    profiles.active = (target.name != DEFAULT_PROFILE).then(|| target.name.clone());
+    {
+        let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        save(&profiles)?;
+    }
+    probe_cache::invalidate(None);
+    watcher::start(app.clone());
+    let _ = events::emit(&app, PROFILE_SWITCHED_EVENT, &target.name);
+
+    let mut message = format!("已切换到档案 {}", target.name);
+    if was_running {
+        match service::start_service(None).await {
+            Ok(_) => message.push_str("，Gateway 已重新启动"),
+            Err(e) => {
+                warn!("[配置档案] 切换后启动 Gateway 失败: {}", e);
+                message.push_str(&format!("，但 Gateway 启动失败: {}", e));
+            }
+        }
+    }
+    audit::record(
+        "switch_profile",
+        &target.name,
+        true,
+        json!({ "from": current, "config_dir": target.config_dir, "restarted": was_running }),
+    );
+    info!("[配置档案] ✓ {}", message);
+    Ok(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validates_names_and_resolves_dirs() {
+        assert_eq!(validate_name(" Work ").unwrap(), "work");
+        assert_eq!(validate_name("client_a-2").unwrap(), "client_a-2");
+        assert!(validate_name("").is_err());
+        assert!(validate_name("-work").is_err());
+        assert!(validate_name("../etc").is_err());
+        assert!(validate_name("个人").is_err());
+
+        assert_eq!(
+            profile_dir(Path::new("/home/u/.openclaw"), "work"),
+            PathBuf::from("/home/u/.openclaw-work")
+        );
+
+        let cfg = json!({
+            "models": { "providers": { "anthropic": {}, "openai": {} } },
+            "channels": { "telegram": {} }
+        });
+        assert_eq!(count_config(&cfg), (2, 1));
+        assert_eq!(count_config(&json!({})), (0, 0));
+    }
+}
@@ -0,0 +1,459 @@
+use crate::commands::{compat, config, installer};
+use crate::models::{ChannelConfig, ModelConfig};
+use crate::utils::{file, secrets, shell};
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use openclaw_macros::guarded;
+use tauri::command;
+
+/// 批量部署用的声明式配置文件（YAML / JSON）
+///
+/// ```yaml
+/// node: { version: "22" }
+/// openclaw: { version: latest }
+/// gateway: { mode: local }
+/// skills: [browser, files]
+/// providers:
+///   - name: anthropic
+///     base_url: https://api.anthropic.com
+///     api_type: anthropic-messages
+///     api_key: { env: ANTHROPIC_API_KEY }
+///     models: [{ id: claude-sonnet-4, name: Claude Sonnet 4 }]
+/// primary_model: anthropic/claude-sonnet-4
+/// channels:
+///   telegram: { botToken: { keychain: telegram-bot } }
+/// ```
+///
+/// 密钥可以写成明文，也可以引用 `{ env: NAME }`、`{ keychain: account }`、`{ file: path }`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProvisioningFile {
+    pub node: Option<NodeSpec>,
+    pub openclaw: Option<OpenClawSpec>,
+    pub gateway: Option<GatewaySpec>,
+    pub skills: Vec<String>,
+    pub providers: Vec<ProviderSpec>,
+    pub primary_model: Option<String>,
+    pub channels: BTreeMap<String, HashMap<String, Value>>,
+}
+
+/// Node.js 要求（主版本号下限）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeSpec {
+    pub version: String,
+}
+
+/// OpenClaw 版本（latest 或具体版本号）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenClawSpec {
+    pub version: String,
+}
+
+/// Gateway 设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GatewaySpec {
+    pub mode: String,
+}
+
+/// Provider 声明
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderSpec {
+    pub name: String,
+    pub base_url: String,
+    #[serde(default = "default_api_type")]
+    pub api_type: String,
+    #[serde(default)]
+    pub api_key: Option<Value>,
+    #[serde(default)]
+    pub models: Vec<ModelConfig>,
+}
+
+fn default_api_type() -> String {
+    "openai-completions".to_string()
+}
+
+/// 单项计划/执行结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProvisioningItem {
+    /// 类别：node / openclaw / gateway / skill / provider / model / channel
+    pub category: String,
+    /// 对象名称
+    pub name: String,
+    /// 计划动作：unchanged / install / update / create
+    pub action: String,
+    /// 状态：planned / applied / unchanged / failed
+    pub status: String,
+    /// 详细信息
+    pub message: String,
+}
+
+/// 整体结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProvisioningReport {
+    pub path: String,
+    pub dry_run: bool,
+    pub success: bool,
+    pub items: Vec<ProvisioningItem>,
+}
+
+/// 解析配置文件（.json 按 JSON 解析，其余按 YAML 解析）
+fn parse_provisioning(path: &str, content: &str) -> Result<ProvisioningFile, String> {
+    if path.to_lowercase().ends_with(".json") {
+        serde_json::from_str(content).map_err(|e| format!("解析 JSON 失败: {}", e))
+    } else {
+        serde_yaml::from_str(content).map_err(|e| format!("解析 YAML 失败: {}", e))
+    }
+}
+
+/// 解析密钥引用：{ env } / { keychain } / { file }，其他值原样返回
+fn resolve_value(value: &Value) -> Result<Value, String> {
+    let obj = match value.as_object() {
+        Some(obj) if obj.len() == 1 => obj,
+        _ => return Ok(value.clone()),
+    };
+    let (kind, target) = match obj.iter().next() {
+        Some((k, Value::String(t))) => (k.as_str(), t.as_str()),
+        _ => return Ok(value.clone()),
+    };
+    let resolved = match kind {
+        "env" => std::env::var(target).map_err(|_| format!("环境变量 {} 未设置", target))?,
+        "keychain" => secrets::resolve_secret(&format!("{}{}", secrets::KEYCHAIN_PREFIX, target))?,
+        "file" => file::read_file(target)
+            .map(|s| s.trim().to_string())
+            .map_err(|e| format!("读取密钥文件 {} 失败: {}", target, e))?,
+        _ => return Ok(value.clone()),
+    };
+    Ok(json!(resolved))
+}
+
+/// 主版本号
+fn major_version(version: &str) -> Option<u32> {
+    version
+        .trim()
+        .trim_start_matches('v')
+        .split('.')
+        .next()
+        .and_then(|s| s.parse().ok())
+}
+
+/// 对比 Provider 与当前配置，返回需要执行的动作
+fn plan_provider(spec: &ProviderSpec, api_key: Option<&str>, current: &Value) -> &'static str {
+    let existing = match current.pointer(&format!("/models/providers/{}", spec.name)) {
+        Some(p) => p,
+        None => return "create",
+    };
+    let same_url = existing.get("baseUrl").and_then(|v| v.as_str()) == Some(spec.base_url.as_str());
+    let same_key = match api_key {
+        Some(key) => existing.get("apiKey").and_then(|v| v.as_str()) == Some(key),
+        None => true,
+    };
+    let existing_models: Vec<&str> = existing
+        .get("models")
+        .and_then(|v| v.as_array())
+        .map(|arr| arr.iter().filter_map(|m| m.get("id").and_then(|v| v.as_str())).collect())
+        .unwrap_or_default();
+    let same_models = spec.models.len() == existing_models.len()
+        && spec.models.iter().all(|m| existing_models.contains(&m.id.as_str()));
+
+    if same_url && same_key && same_models {
+        "unchanged"
+    } else {
+        "update"
+    }
+}
+
+/// 对比渠道配置与当前配置
+fn plan_channel(id: &str, desired: &HashMap<String, Value>, current: &Value) -> &'static str {
+    let existing = match current.pointer(&format!("/channels/{}", id)).and_then(|v| v.as_object()) {
+        Some(obj) => obj,
+        None => return "create",
+    };
+    let enabled = existing.get("enabled").and_then(|v| v.as_bool()).unwrap_or(false);
+    if enabled && desired.iter().all(|(k, v)| existing.get(k) == Some(v)) {
+        "unchanged"
+    } else {
+        "update"
+    }
+}
+
+struct Reconciler {
+    dry_run: bool,
+    items: Vec<ProvisioningItem>,
+}
+
+impl Reconciler {
+    /// 记录一项：unchanged 直接记录，dry_run 只记录计划，否则执行 apply
+    async fn item<F, Fut, E>(&mut self, category: &str, name: &str, action: &str, message: String, apply: F)
+    where
+        F: FnOnce() -> Fut,
+        Fut: std::future::Future<Output = Result<String, E>>,
+        E: Into<String>,
+    {
+        let (status, message) = if action == "unchanged" {
+            ("unchanged", message)
+        } else if self.dry_run {
+            ("planned", message)
+        } else {
+            match apply().await {
+                Ok(msg) => {
+                    info!("[批量部署] ✓ {} {}: {}", category, name, msg);
+                    ("applied", msg)
+                }
+                Err(e) => {
+                    let e = e.into();
+                    error!("[批量部署] ✗ {} {}: {}", category, name, e);
+                    ("failed", e)
+                }
+            }
+        };
+        self.items.push(ProvisioningItem {
+            category: category.to_string(),
+            name: name.to_string(),
+            action: action.to_string(),
+            status: status.to_string(),
+            message,
+        });
+    }
+
+    fn failed(&mut self, category: &str, name: &str, message: String) {
+        error!("[批量部署] ✗ {} {}: {}", category, name, message);
+        self.items.push(ProvisioningItem {
+            category: category.to_string(),
+            name: name.to_string(),
+            action: "skip".to_string(),
+            status: "failed".to_string(),
+            message,
+        });
+    }
+}
+
+fn install_result(r: installer::InstallResult) -> Result<String, String> {
+    if r.success {
+        Ok(r.message)
+    } else {
+        Err(r.error.unwrap_or(r.message))
+    }
+}
+
+/// 按声明文件对本机进行对账
+async fn reconcile(spec: &ProvisioningFile, dry_run: bool) -> Vec<ProvisioningItem> {
+    let mut r = Reconciler { dry_run, items: Vec::new() };
+    let env = installer::check_environment().await.ok();
+
+    // 1. Node.js
+    if let Some(node) = &spec.node {
+        let current = env.as_ref().and_then(|e| e.node_version.clone());
+        let required = major_version(&node.version).unwrap_or(22);
+        let ok = current.as_deref().and_then(major_version).is_some_and(|v| v >= required);
+        let action = if ok { "unchanged" } else { "install" };
+        let message = format!("当前 {}，要求 >= {}", current.as_deref().unwrap_or("未安装"), required);
+        r.item("node", "nodejs", action, message, || async {
+            installer::install_nodejs(None).await.map_err(String::from).and_then(install_result)
+        })
+        .await;
+    }
+
+    // 2. OpenClaw
+    if let Some(openclaw) = &spec.openclaw {
+        let current = env.as_ref().and_then(|e| e.openclaw_version.clone());
+        let wanted = openclaw.version.trim().to_string();
+        let action = match &current {
+            None => "install",
+            Some(_) if wanted == "latest" => match installer::check_openclaw_update().await {
+                Ok(info) if info.update_available => "update",
+                _ => "unchanged",
+            },
+            Some(v) if v.contains(wanted.trim_start_matches('v')) => "unchanged",
+            Some(_) => "update",
+        };
+        let message = format!("当前 {}，目标 {}", current.as_deref().unwrap_or("未安装"), wanted);
+        r.item("openclaw", "openclaw", action, message, || async {
+            if wanted == "latest" {
+                if action == "install" {
+                    installer::install_openclaw().await.map_err(String::from).and_then(install_result)
+                } else {
+                    installer::run_update(None).await.and_then(install_result)
+                }
+            } else {
+                compat::ensure_supported(wanted.trim_start_matches('v')).await?;
+                installer::install_openclaw_version(&wanted).await.and_then(install_result)
+            }
+        })
+        .await;
+    }
+
+    // 3. Gateway 模式
+    if let Some(gateway) = &spec.gateway {
+        let current = config::load_openclaw_config().unwrap_or_else(|_| json!({}));
+        let current_mode = current.pointer("/gateway/mode").and_then(|v| v.as_str()).map(String::from);
+        let action = if current_mode.as_deref() == Some(gateway.mode.as_str()) { "unchanged" } else { "update" };
+        let message = format!("当前 {}，目标 {}", current_mode.as_deref().unwrap_or("未设置"), gateway.mode);
+        r.item("gateway", "mode", action, message, || async {
+            shell::run_openclaw(&["config", "set", "gateway.mode", &gateway.mode])
+                .map(|_| format!("gateway.mode = {}", gateway.mode))
+        })
+        .await;
+    }
+
+    // 4. Skills
+    if !spec.skills.is_empty() {
+        let installed = shell::run_openclaw(&["skill", "list"]).unwrap_or_default();
+        for skill in &spec.skills {
+            let present = installed
+                .lines()
+                .any(|l| l.split(|c: char| c.is_whitespace() || c == ':').any(|w| w == skill));
+            let action = if present { "unchanged" } else { "install" };
+            r.item("skill", skill, action, String::new(), || async {
+                shell::run_openclaw(&["skill", "install", skill]).map(|_| "已安装".to_string())
+            })
+            .await;
+        }
+    }
+
+    // 5. Providers
+    for provider in &spec.providers {
+        let api_key = match provider.api_key.as_ref().map(resolve_value).transpose() {
+            Ok(v) => v.and_then(|v| v.as_str().map(String::from)),
+            Err(e) => {
+                r.failed("provider", &provider.name, e);
+                continue;
+            }
+        };
+        let current = config::load_openclaw_config().unwrap_or_else(|_| json!({}));
+        let action = plan_provider(provider, api_key.as_deref(), &current);
+        let message = format!("{} ({} 个模型)", provider.base_url, provider.models.len());
+        r.item("provider", &provider.name, action, message, || async {
+            config::save_provider(
+                provider.name.clone(),
+                provider.base_url.clone(),
+                api_key.clone(),
+                provider.api_type.clone(),
+                provider.models.clone(),
+            )
+            .await
+        })
+        .await;
+    }
+
+    // 6. 主模型
+    if let Some(model) = &spec.primary_model {
+        let current = config::load_openclaw_config().unwrap_or_else(|_| json!({}));
+        let current_primary = current.pointer("/agents/defaults/model/primary").and_then(|v| v.as_str());
+        let action = if current_primary == Some(model.as_str()) { "unchanged" } else { "update" };
+        r.item("model", model, action, "主模型".to_string(), || async {
+            config::set_primary_model(model.clone()).await
+        })
+        .await;
+    }
+
+    // 7. 渠道
+    for (id, values) in &spec.channels {
+        let resolved: Result<HashMap<String, Value>, String> = values
+            .iter()
+            .map(|(k, v)| resolve_value(v).map(|v| (k.clone(), v)))
+            .collect();
+        let resolved = match resolved {
+            Ok(v) => v,
+            Err(e) => {
+                r.failed("channel", id, e);
+                continue;
+            }
+        };
+        let current = config::load_openclaw_config().unwrap_or_else(|_| json!({}));
+        let action = plan_channel(id, &resolved, &current);
+        r.item("channel", id, action, format!("{} 个配置项", resolved.len()), || async {
+            config::save_channel_config(ChannelConfig {
+                id: id.clone(),
+                channel_type: id.clone(),
+                enabled: true,
+                config: resolved.clone(),
+            })
+            .await
+        })
+        .await;
+    }
+
+    r.items
+}
+
+/// 应用批量部署配置文件
+/// `dry_run` 为 true 时只输出计划，不做任何修改
+#[guarded]
+#[command]
+pub async fn apply_provisioning(path: String, dry_run: Option<bool>) -> Result<ProvisioningReport, String> {
+    let dry_run = dry_run.unwrap_or(false);
+    info!("[批量部署] {} 配置文件: {}", if dry_run { "预览" } else { "应用" }, path);
+
+    let content = file::read_file(&path).map_err(|e| format!("读取配置文件失败: {}", e))?;
+    let spec = parse_provisioning(&path, &content)?;
+    let items = reconcile(&spec, dry_run).await;
+    let success = items.iter().all(|i| i.status != "failed");
+
+    if success {
+        info!("[批量部署] ✓ 完成，共 {} 项", items.len());
+    } else {
+        warn!("[批量部署] 部分项目失败");
+    }
+    Ok(ProvisioningReport {
+        path,
+        dry_run,
+        success,
+        items,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_yaml_with_key_references() {
+        let spec = parse_provisioning(
+            "fleet.yaml",
+            r#"
+node: { version: "22" }
+openclaw: { version: latest }
+skills: [browser, files]
+providers:
+  - name: anthropic
+    base_url: https://api.anthropic.com
+    api_type: anthropic-messages
+    api_key: { env: OPENCLAW_PROVISION_TEST_KEY }
+    models:
+      - { id: claude-sonnet-4, name: Claude Sonnet 4 }
+channels:
+  telegram: { botToken: { env: OPENCLAW_PROVISION_TEST_KEY }, dmPolicy: pairing }
+"#,
+        )
+        .unwrap();
+        assert_eq!(spec.skills, vec!["browser", "files"]);
+        assert_eq!(spec.providers[0].models[0].id, "claude-sonnet-4");
+
+        std::env::set_var("OPENCLAW_PROVISION_TEST_KEY", "sk-test");
+        let key = resolve_value(spec.providers[0].api_key.as_ref().unwrap()).unwrap();
+        assert_eq!(key, json!("sk-test"));
+        let policy = resolve_value(&spec.channels["telegram"]["dmPolicy"]).unwrap();
+        assert_eq!(policy, json!("pairing"));
+    }
+
+    #[test]
+    fn provider_plan_detects_changes() {
+        let current = json!({"models": {"providers": {"anthropic": {
+            "baseUrl": "https://api.anthropic.com",
+            "apiKey": "sk-old",
+            "models": [{"id": "claude-sonnet-4"}]
+        }}}});
+        let spec: ProviderSpec = serde_json::from_value(json!({
+            "name": "anthropic",
+            "base_url": "https://api.anthropic.com",
+            "models": [{"id": "claude-sonnet-4", "name": "Claude Sonnet 4"}]
+        }))
+        .unwrap();
+
+        assert_eq!(plan_provider(&spec, Some("sk-old"), &current), "unchanged");
+        assert_eq!(plan_provider(&spec, Some("sk-new"), &current), "update");
+        assert_eq!(plan_provider(&spec, None, &json!({})), "create");
+    }
+}
@@ -0,0 +1,440 @@
+use crate::commands::{config, privacy, settings, skills};
+use crate::utils::{audit, login_env, platform, shell};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+use openclaw_macros::guarded;
+use tauri::command;
+
+/// Python 技能需要的最低版本
+const MIN_PYTHON: (u32, u32) = (3, 9);
+/// 国内 PyPI 镜像（npm 使用国内镜像时 pip 也走镜像）
+const PYPI_MIRROR: &str = "https://mirrors.aliyun.com/pypi/simple/";
+
+/// 解释器来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PythonSource {
+    /// PATH / 登录 shell 中的 python3
+    Path,
+    /// 系统或 Homebrew / python.org 安装
+    System,
+    Pyenv,
+    Conda,
+    /// Manager 为技能创建的虚拟环境
+    Venv,
+}
+
+/// 检测到的 Python 解释器
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PythonInterpreter {
+    pub path: String,
+    pub version: String,
+    pub source: PythonSource,
+    /// 版本是否满足要求 (>=3.9)
+    pub version_ok: bool,
+}
+
+/// 技能的 Python 环境
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkillPythonEnv {
+    pub skill: String,
+    /// 配置中固定的解释器（`skills.entries.<skill>.env.PYTHON`）
+    pub pinned: Option<String>,
+    /// Manager 创建的虚拟环境目录（已创建时）
+    pub venv: Option<String>,
+    /// 技能目录中是否有 requirements.txt
+    pub has_requirements: bool,
+}
+
+/// `Python 3.12.1` -> `3.12.1`
+fn parse_version(output: &str) -> Option<String> {
+    output
+        .trim()
+        .strip_prefix("Python ")
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+fn version_ok(version: &str) -> bool {
+    let mut parts = version.split('.').map(|p| p.parse::<u32>().unwrap_or(0));
+    let major = parts.next().unwrap_or(0);
+    let minor = parts.next().unwrap_or(0);
+    (major, minor) >= MIN_PYTHON
+}
+
+fn probe(path: &str, source: PythonSource) -> Option<PythonInterpreter> {
+    // Python 3.4 之前版本信息输出到 stderr，这里只关心 3.x 新版本
+    let version = shell::run_command_output(path, &["--version"])
+        .ok()
+        .and_then(|out| parse_version(&out))?;
+    Some(PythonInterpreter {
+        path: path.to_string(),
+        version_ok: version_ok(&version),
+        version,
+        source,
+    })
+}
+
+/// 目录下的子目录（按名称倒序，版本号高的在前）
+fn subdirs_desc(dir: &Path) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.is_dir())
+        .collect();
+    dirs.sort();
+    dirs.reverse();
+    dirs
+}
+
+/// 可能的 Python 安装路径（GUI 应用不继承用户 shell 的 PATH，与 Node.js 检测相同的思路）
+fn candidate_paths() -> Vec<(String, PythonSource)> {
+    let mut paths: Vec<(String, PythonSource)> = Vec::new();
+    let home = dirs::home_dir().unwrap_or_default();
+    let conda_roots = ["miniconda3", "anaconda3", "miniforge3", "mambaforge"];
+    if platform::is_windows() {
+        if let Some(local) = dirs::data_local_dir() {
+            for dir in subdirs_desc(&local.join("Programs").join("Python")) {
+                paths.push((
+                    dir.join("python.exe").display().to_string(),
+                    PythonSource::System,
+                ));
+            }
+        }
+        for root in conda_roots {
+            paths.push((
+                home.join(root).join("python.exe").display().to_string(),
+                PythonSource::Conda,
+            ));
+        }
+        for dir in subdirs_desc(&home.join(".pyenv").join("pyenv-win").join("versions")) {
+            paths.push((
+                dir.join("python.exe").display().to_string(),
+                PythonSource::Pyenv,
+            ));
+        }
+    } else {
+        for path in [
+            "/opt/homebrew/bin/python3",
+            "/usr/local/bin/python3",
+            "/usr/bin/python3",
+        ] {
+            paths.push((path.to_string(), PythonSource::System));
+        }
+        paths.push((
+            home.join(".pyenv/shims/python3").display().to_string(),
+            PythonSource::Pyenv,
+        ));
+        for dir in subdirs_desc(&home.join(".pyenv").join("versions")) {
+            paths.push((
+                dir.join("bin/python3").display().to_string(),
+                PythonSource::Pyenv,
+            ));
+        }
+        for root in conda_roots {
+            for base in [home.join(root), Path::new("/opt").join(root)] {
+                paths.push((
+                    base.join("bin/python3").display().to_string(),
+                    PythonSource::Conda,
+                ));
+            }
+        }
+    }
+    paths
+}
+
+/// 检测所有可用的 Python 3 解释器（按路径去重，PATH 中的在前）
+pub fn detect_interpreters() -> Vec<PythonInterpreter> {
+    let mut found: Vec<PythonInterpreter> = Vec::new();
+    let mut push = |interpreter: Option<PythonInterpreter>| {
+        if let Some(i) = interpreter {
+            let canonical = std::fs::canonicalize(&i.path).ok();
+            let duplicate = found.iter().any(|f| {
+                f.path == i.path
+                    || (canonical.is_some() && std::fs::canonicalize(&f.path).ok() == canonical)
+            });
+            if !duplicate {
+                found.push(i);
+            }
+        }
+    };
+    let path_names: &[&str] = if platform::is_windows() {
+        &["python", "python3"]
+    } else {
+        &["python3"]
+    };
+    for name in path_names {
+        let resolved = login_env::find_executable(name).map(|p| p.display().to_string());
+        push(
+            resolved
+                .and_then(|p| probe(&p, PythonSource::Path))
+                .or_else(|| probe(name, PythonSource::Path)),
+        );
+    }
+    for (path, source) in candidate_paths() {
+        if Path::new(&path).is_file() {
+            push(probe(&path, source));
+        }
+    }
+    found
+}
+
+/// 技能名只允许单级目录名
+fn validate_skill(skill: &str) -> Result<&str, String> {
+    let skill = skill.trim();
+    if skill.is_empty() || skill.contains(['/', '\\']) || skill.contains("..") {
+        return Err(format!("无效的技能名: {}", skill));
+    }
+    Ok(skill)
+}
+
+/// 技能的虚拟环境目录
+fn venv_dir(skill: &str) -> PathBuf {
+    Path::new(&platform::get_manager_data_dir())
+        .join("python")
+        .join("venvs")
+        .join(skill)
+}
+
+/// 虚拟环境中的解释器
+fn venv_python(venv: &Path) -> PathBuf {
+    if platform::is_windows() {
+        venv.join("Scripts").join("python.exe")
+    } else {
+        venv.join("bin").join("python3")
+    }
+}
+
+fn pinned_python(cfg: &Value, skill: &str) -> Option<String> {
+    cfg.pointer(&format!("/skills/entries/{}/env/PYTHON", skill))
+        .and_then(|v| v.as_str())
+        .filter(|p| !p.is_empty())
+        .map(String::from)
+}
+
+/// 在配置中为技能固定解释器（`skills.entries.<skill>.env.PYTHON`），`None` 时取消固定
+fn write_pin(cfg: &mut Value, skill: &str, python: Option<&str>) {
+    if !cfg.is_object() {
+        *cfg = json!({});
+    }
+    let entry = &mut cfg["skills"]["entries"][skill];
+    match python {
+        Some(path) => {
+            if !entry["env"].is_object() {
+                entry["env"] = json!({});
+            }
+            entry["env"]["PYTHON"] = json!(path);
+        }
+        None => {
+            if let Some(env) = entry.get_mut("env").and_then(|e| e.as_object_mut()) {
+                env.remove("PYTHON");
+            }
+        }
+    }
+}
+
+async fn save_pin(skill: &str, python: Option<&str>) -> Result<(), String> {
+    let mut cfg = config::load_openclaw_config()?;
+    write_pin(&mut cfg, skill, python);
+    config::save_config(cfg).await?;
+    Ok(())
+}
+
+/// 创建虚拟环境（已存在时直接返回其解释器）
+fn ensure_venv(skill: &str, base: Option<&str>) -> Result<PathBuf, String> {
+    let venv = venv_dir(skill);
+    let python = venv_python(&venv);
+    if python.is_file() {
+        return Ok(python);
+    }
+    let base = match base {
+        Some(path) => probe(path, PythonSource::System)
+            .ok_or_else(|| format!("无法运行 Python 解释器: {}", path))?,
+        None => detect_interpreters()
+            .into_iter()
+            .find(|i| i.version_ok)
+            .ok_or_else(|| {
+                format!(
+                    "未找到 Python {}.{}+，请先安装 Python",
+                    MIN_PYTHON.0, MIN_PYTHON.1
+                )
+            })?,
+    };
+    if !base.version_ok {
+        return Err(format!(
+            "Python {} 版本过低，需要 {}.{}+",
+            base.version, MIN_PYTHON.0, MIN_PYTHON.1
+        ));
+    }
+    std::fs::create_dir_all(venv.parent().unwrap_or(&venv))
+        .map_err(|e| format!("创建虚拟环境目录失败: {}", e))?;
+    info!(
+        "[Python] 使用 {} ({}) 为 {} 创建虚拟环境",
+        base.path, base.version, skill
+    );
+    shell::run_command_output(&base.path, &["-m", "venv", &venv.to_string_lossy()])
+        .map_err(|e| format!("创建虚拟环境失败: {}", e))?;
+    if !python.is_file() {
+        return Err(
+            "创建虚拟环境失败：未生成解释器（Debian/Ubuntu 需要安装 python3-venv）".to_string(),
+        );
+    }
+    Ok(python)
+}
+
+/// 检测本机的 Python 3 解释器（PATH、系统安装、pyenv、conda）
+#[guarded]
+#[command]
+pub async fn detect_python() -> Result<Vec<PythonInterpreter>, String> {
+    tokio::task::spawn_blocking(detect_interpreters)
+        .await
+        .map_err(|e| format!("检测 Python 失败: {}", e))
+}
+
+/// 技能的 Python 环境状态
+#[guarded]
+#[command]
+pub async fn get_skill_python_env(skill: String) -> Result<SkillPythonEnv, String> {
+    let skill = validate_skill(&skill)?.to_string();
+    let cfg = config::load_openclaw_config().unwrap_or(Value::Null);
+    let venv = venv_dir(&skill);
+    Ok(SkillPythonEnv {
+        pinned: pinned_python(&cfg, &skill),
+        venv: venv_python(&venv)
+            .is_file()
+            .then(|| venv.to_string_lossy().to_string()),
+        has_requirements: skills::skills_dir()
+            .join(&skill)
+            .join("requirements.txt")
+            .is_file(),
+        skill,
+    })
+}
+
+/// 为技能创建虚拟环境（可指定基础解释器），并固定为技能使用的解释器
+#[guarded]
+#[command]
+pub async fn create_skill_venv(skill: String, python: Option<String>) -> Result<String, String> {
+    let skill = validate_skill(&skill)?.to_string();
+    let name = skill.clone();
+    let venv_python =
+        tokio::task::spawn_blocking(move || ensure_venv(&name, python.as_deref()))
+            .await
+            .map_err(|e| e.to_string())??;
+    let path = venv_python.to_string_lossy().to_string();
+    save_pin(&skill, Some(&path)).await?;
+    info!("[Python] ✓ {} 使用虚拟环境 {}", skill, path);
+    Ok(path)
+}
+
+/// 在技能的虚拟环境中安装 requirements.txt，完成后固定解释器
+#[guarded]
+#[command]
+pub async fn install_python_requirements(skill: String) -> Result<String, String> {
+    privacy::guard("安装 Python 依赖")?;
+    let skill = validate_skill(&skill)?.to_string();
+    let requirements = skills::skills_dir().join(&skill).join("requirements.txt");
+    if !requirements.is_file() {
+        return Err(format!("技能 {} 没有 requirements.txt", skill));
+    }
+    let cfg = config::load_openclaw_config().unwrap_or(Value::Null);
+    // 已固定的解释器不是 Manager 创建的虚拟环境时，作为创建虚拟环境的基础解释器
+    let base =
+        pinned_python(&cfg, &skill).filter(|p| !Path::new(p).starts_with(venv_dir(&skill)));
+    let name = skill.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        let python = ensure_venv(&name, base.as_deref())?;
+        let settings = settings::load_settings();
+        let python_str = python.to_string_lossy().to_string();
+        let requirements_str = requirements.to_string_lossy().to_string();
+        let mut args = vec!["-m", "pip", "install", "-r", requirements_str.as_str()];
+        if settings.npm_registry.contains("npmmirror") {
+            args.extend(["-i", PYPI_MIRROR]);
+        }
+        let proxy = settings.proxy.clone().filter(|p| !p.trim().is_empty());
+        if let Some(proxy) = proxy.as_deref() {
+            args.extend(["--proxy", proxy.trim()]);
+        }
+        info!("[Python] 安装 {} 的依赖...", name);
+        shell::run_command_output(&python_str, &args)?;
+        Ok::<_, String>(python_str)
+    })
+    .await
+    .map_err(|e| e.to_string())
+    .and_then(|r| r);
+    audit::record(
+        "install_python_requirements",
+        &skill,
+        result.is_ok(),
+        json!({ "error": result.as_ref().err() }),
+    );
+    let python = match result {
+        Ok(python) => python,
+        Err(e) => {
+            warn!("[Python] ✗ 安装 {} 的依赖失败: {}", skill, e);
+            return Err(e);
+        }
+    };
+    save_pin(&skill, Some(&python)).await?;
+    info!("[Python] ✓ {} 的依赖已安装", skill);
+    Ok(format!("已在 {} 中安装依赖", python))
+}
+
+/// 固定技能使用的 Python 解释器（传入空值时取消固定）
+#[guarded]
+#[command]
+pub async fn set_skill_python(skill: String, python: Option<String>) -> Result<(), String> {
+    let skill = validate_skill(&skill)?.to_string();
+    let python = python
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty());
+    if let Some(path) = &python {
+        let interpreter = probe(path, PythonSource::System)
+            .ok_or_else(|| format!("无法运行 Python 解释器: {}", path))?;
+        if !interpreter.version_ok {
+            return Err(format!(
+                "Python {} 版本过低，需要 {}.{}+",
+                interpreter.version, MIN_PYTHON.0, MIN_PYTHON.1
+            ));
+        }
+    }
+    save_pin(&skill, python.as_deref()).await?;
+    audit::record(
+        "set_skill_python",
+        &skill,
+        true,
+        json!({ "python": python }),
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_versions_and_pins_interpreter() {
+        assert_eq!(parse_version("Python 3.12.1\n").as_deref(), Some("3.12.1"));
+        assert_eq!(parse_version("command not found"), None);
+        assert!(version_ok("3.9.18"));
+        assert!(version_ok("3.13.0rc1"));
+        assert!(!version_ok("3.8.10"));
+        assert!(validate_skill("../x").is_err());
+
+        let mut cfg =
+            json!({"skills": {"entries": {"pdf": {"enabled": true, "env": {"TOKEN": "x"}}}}});
+        write_pin(&mut cfg, "pdf", Some("/venvs/pdf/bin/python3"));
+        assert_eq!(
+            pinned_python(&cfg, "pdf").as_deref(),
+            Some("/venvs/pdf/bin/python3")
+        );
+        assert_eq!(cfg["skills"]["entries"]["pdf"]["env"]["TOKEN"], "x");
+        write_pin(&mut cfg, "pdf", None);
+        assert_eq!(pinned_python(&cfg, "pdf"), None);
+        assert_eq!(cfg["skills"]["entries"]["pdf"]["enabled"], true);
+    }
+}
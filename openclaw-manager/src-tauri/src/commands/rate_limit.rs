@@ -0,0 +1,378 @@
+//! 渠道限流和防刷屏策略：每个用户每分钟消息数、触发限流后的冷却时间、单条消息长度上限、
+//! 重复消息拦截，保存到 openclaw.json 的 channels.<渠道>.rateLimit；
+//! 模拟器按策略评估一段假设的消息流量，保存前可以预览效果
+use crate::commands::config;
+use crate::utils::audit;
+use log::info;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use openclaw_macros::guarded;
+use tauri::command;
+
+/// 限流窗口
+const WINDOW_MS: u64 = 60_000;
+/// 模拟器最多处理的消息数
+const MAX_SIMULATED_EVENTS: usize = 10_000;
+
+/// 渠道限流策略（字段为空表示不限制）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RateLimitPolicy {
+    pub channel: String,
+    /// 每个用户每分钟最多处理的消息数
+    #[serde(default)]
+    pub messages_per_minute: Option<u32>,
+    /// 触发限流后该用户的冷却时间（秒）
+    #[serde(default)]
+    pub cooldown_secs: Option<u32>,
+    /// 单条消息最大长度（字符）
+    #[serde(default)]
+    pub max_message_length: Option<u32>,
+    /// 同一用户在多少秒内重复发送相同内容时忽略
+    #[serde(default)]
+    pub duplicate_window_secs: Option<u32>,
+}
+
+/// 模拟的单条消息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrafficEvent {
+    pub user: String,
+    /// 相对开始时间（毫秒）
+    pub at_ms: u64,
+    #[serde(default)]
+    pub length: u32,
+    /// 消息内容（用于重复消息检测，为空时不检测）
+    #[serde(default)]
+    pub text: Option<String>,
+}
+
+/// 某个用户以固定间隔连续发送消息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrafficBurst {
+    pub user: String,
+    pub count: u32,
+    pub interval_ms: u64,
+    #[serde(default)]
+    pub start_ms: u64,
+    #[serde(default)]
+    pub length: u32,
+    /// 每条消息内容相同（模拟刷屏）
+    #[serde(default)]
+    pub repeat_text: Option<String>,
+}
+
+/// 假设的流量：逐条消息和连发可以混合使用
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TrafficPattern {
+    #[serde(default)]
+    pub events: Vec<TrafficEvent>,
+    #[serde(default)]
+    pub bursts: Vec<TrafficBurst>,
+}
+
+/// 被拦截的消息
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RejectedMessage {
+    pub user: String,
+    pub at_ms: u64,
+    /// rate_limited / cooldown / too_long / duplicate
+    pub reason: String,
+}
+
+/// 单个用户的模拟结果
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UserSimulation {
+    pub accepted: u32,
+    pub rejected: u32,
+}
+
+/// 模拟结果
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RateLimitSimulation {
+    pub total: u32,
+    pub accepted: u32,
+    pub rejected: Vec<RejectedMessage>,
+    pub users: BTreeMap<String, UserSimulation>,
+}
+
+fn read_u32(section: &Value, key: &str) -> Option<u32> {
+    section
+        .get(key)
+        .and_then(Value::as_u64)
+        .and_then(|v| u32::try_from(v).ok())
+}
+
+/// 从配置读取渠道限流策略
+fn read_policy(channel: &str, section: &Value) -> RateLimitPolicy {
+    let limit = section.get("rateLimit").cloned().unwrap_or_default();
+    RateLimitPolicy {
+        channel: channel.to_string(),
+        messages_per_minute: read_u32(&limit, "messagesPerMinute"),
+        cooldown_secs: read_u32(&limit, "cooldownSecs"),
+        max_message_length: read_u32(&limit, "maxMessageLength"),
+        duplicate_window_secs: read_u32(&limit, "duplicateWindowSecs"),
+    }
+}
+
+fn validate(policy: &RateLimitPolicy) -> Result<(), String> {
+    let fields = [
+        ("每分钟消息数", policy.messages_per_minute),
+        ("冷却时间", policy.cooldown_secs),
+        ("消息长度上限", policy.max_message_length),
+        ("重复消息窗口", policy.duplicate_window_secs),
+    ];
+    if let Some((name, _)) = fields.iter().find(|(_, v)| *v == Some(0)) {
+        return Err(format!("{}必须大于 0（不限制请留空）", name));
+    }
+    if policy.cooldown_secs.is_some() && policy.messages_per_minute.is_none() {
+        return Err("冷却时间需要同时设置每分钟消息数".to_string());
+    }
+    if policy.cooldown_secs.is_some_and(|c| c > 24 * 60 * 60) {
+        return Err("冷却时间不能超过 24 小时".to_string());
+    }
+    Ok(())
+}
+
+/// 写入渠道配置（所有字段为空时删除 rateLimit）
+fn apply_policy(cfg: &mut Value, policy: &RateLimitPolicy) -> Result<(), String> {
+    let section = cfg
+        .pointer_mut(&format!("/channels/{}", policy.channel))
+        .and_then(Value::as_object_mut)
+        .ok_or_else(|| format!("渠道 {} 尚未配置", policy.channel))?;
+    let limit: Map<String, Value> = [
+        ("messagesPerMinute", policy.messages_per_minute),
+        ("cooldownSecs", policy.cooldown_secs),
+        ("maxMessageLength", policy.max_message_length),
+        ("duplicateWindowSecs", policy.duplicate_window_secs),
+    ]
+    .into_iter()
+    .filter_map(|(k, v)| v.map(|v| (k.to_string(), json!(v))))
+    .collect();
+    if limit.is_empty() {
+        section.remove("rateLimit");
+    } else {
+        section.insert("rateLimit".to_string(), Value::Object(limit));
+    }
+    Ok(())
+}
+
+/// 展开连发并按时间排序
+fn expand(pattern: &TrafficPattern) -> Result<Vec<TrafficEvent>, String> {
+    let total = pattern.events.len()
+        + pattern
+            .bursts
+            .iter()
+            .map(|b| b.count as usize)
+            .sum::<usize>();
+    if total > MAX_SIMULATED_EVENTS {
+        return Err(format!("模拟流量最多 {} 条消息", MAX_SIMULATED_EVENTS));
+    }
+    let mut events = pattern.events.clone();
+    for burst in &pattern.bursts {
+        events.extend((0..burst.count as u64).map(|i| TrafficEvent {
+            user: burst.user.clone(),
+            at_ms: burst.start_ms + i * burst.interval_ms,
+            length: burst.length,
+            text: burst.repeat_text.clone(),
+        }));
+    }
+    // 稳定排序：同一时刻的消息保持输入顺序
+    events.sort_by_key(|e| e.at_ms);
+    Ok(events)
+}
+
+/// 用户状态
+#[derive(Default)]
+struct UserState {
+    /// 最近一分钟内被接受的消息时间
+    window: VecDeque<u64>,
+    cooldown_until: Option<u64>,
+    last_texts: HashMap<String, u64>,
+}
+
+/// 按策略评估流量：检查顺序为冷却、长度、重复内容、频率
+fn simulate(policy: &RateLimitPolicy, events: &[TrafficEvent]) -> RateLimitSimulation {
+    let mut states: HashMap<&str, UserState> = HashMap::new();
+    let mut result = RateLimitSimulation {
+        total: events.len() as u32,
+        ..Default::default()
+    };
+    for event in events {
+        let state = states.entry(&event.user).or_default();
+        while state
+            .window
+            .front()
+            .is_some_and(|t| event.at_ms.saturating_sub(*t) >= WINDOW_MS)
+        {
+            state.window.pop_front();
+        }
+
+        let duplicate = match (&event.text, policy.duplicate_window_secs) {
+            (Some(text), Some(secs)) => state
+                .last_texts
+                .get(text)
+                .is_some_and(|t| event.at_ms.saturating_sub(*t) < secs as u64 * 1000),
+            _ => false,
+        };
+        let reason = if state
+            .cooldown_until
+            .is_some_and(|until| event.at_ms < until)
+        {
+            Some("cooldown")
+        } else if policy
+            .max_message_length
+            .is_some_and(|max| event.length > max)
+        {
+            Some("too_long")
+        } else if duplicate {
+            Some("duplicate")
+        } else if policy
+            .messages_per_minute
+            .is_some_and(|limit| state.window.len() >= limit as usize)
+        {
+            state.cooldown_until = policy
+                .cooldown_secs
+                .map(|secs| event.at_ms + secs as u64 * 1000);
+            Some("rate_limited")
+        } else {
+            None
+        };
+
+        if let Some(text) = &event.text {
+            state.last_texts.insert(text.clone(), event.at_ms);
+        }
+        let user = result.users.entry(event.user.clone()).or_default();
+        match reason {
+            Some(reason) => {
+                user.rejected += 1;
+                result.rejected.push(RejectedMessage {
+                    user: event.user.clone(),
+                    at_ms: event.at_ms,
+                    reason: reason.to_string(),
+                });
+            }
+            None => {
+                user.accepted += 1;
+                result.accepted += 1;
+                state.window.push_back(event.at_ms);
+            }
+        }
+    }
+    result
+}
+
+/// 读取所有已配置渠道的限流策略
+#[guarded]
+#[command]
+pub async fn get_rate_limits() -> Result<Vec<RateLimitPolicy>, String> {
+    let cfg = config::load_openclaw_config()?;
+    Ok(cfg
+        .get("channels")
+        .and_then(Value::as_object)
+        .map(|channels| {
+            channels
+                .iter()
+                .map(|(channel, section)| read_policy(channel, section))
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+/// 保存渠道限流策略到 openclaw.json
+#[guarded]
+#[command]
+pub async fn save_rate_limit(policy: RateLimitPolicy) -> Result<String, String> {
+    validate(&policy)?;
+    let mut cfg = config::load_openclaw_config()?;
+    apply_policy(&mut cfg, &policy)?;
+    config::save_config(cfg).await?;
+    audit::record(
+        "save_rate_limit",
+        &policy.channel,
+        true,
+        serde_json::to_value(&policy).unwrap_or_default(),
+    );
+    info!("[限流策略] ✓ 已保存 {} 的限流策略", policy.channel);
+    Ok(format!("{} 的限流策略已保存", policy.channel))
+}
+
+/// 按策略模拟一段流量，返回被拦截的消息和每个用户的统计
+#[guarded]
+#[command]
+pub async fn simulate_rate_limit(
+    policy: RateLimitPolicy,
+    pattern: TrafficPattern,
+) -> Result<RateLimitSimulation, String> {
+    validate(&policy)?;
+    Ok(simulate(&policy, &expand(&pattern)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn simulates_rate_limit_cooldown_and_spam() {
+        let policy = RateLimitPolicy {
+            channel: "telegram".to_string(),
+            messages_per_minute: Some(3),
+            cooldown_secs: Some(120),
+            max_message_length: Some(100),
+            duplicate_window_secs: Some(30),
+        };
+        let pattern = TrafficPattern {
+            events: vec![TrafficEvent {
+                user: "bob".to_string(),
+                at_ms: 500,
+                length: 500,
+                text: None,
+            }],
+            bursts: vec![
+                // 每 10 秒一条：第 4 条触发限流，之后 2 分钟内全部拦截
+                TrafficBurst {
+                    user: "alice".to_string(),
+                    count: 8,
+                    interval_ms: 10_000,
+                    start_ms: 0,
+                    length: 10,
+                    repeat_text: None,
+                },
+                TrafficBurst {
+                    user: "spam".to_string(),
+                    count: 3,
+                    interval_ms: 5_000,
+                    start_ms: 0,
+                    length: 10,
+                    repeat_text: Some("buy now".to_string()),
+                },
+            ],
+        };
+        let result = simulate(&policy, &expand(&pattern).unwrap());
+        assert_eq!(result.total, 12);
+        assert_eq!(result.users["alice"].accepted, 3);
+        let reasons = |user: &str| {
+            result
+                .rejected
+                .iter()
+                .filter(|r| r.user == user)
+                .map(|r| r.reason.as_str())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(reasons("alice")[0], "rate_limited");
+        assert!(reasons("alice")[1..].iter().all(|r| *r == "cooldown"));
+        assert_eq!(reasons("spam"), vec!["duplicate", "duplicate"]);
+        assert_eq!(reasons("bob"), vec!["too_long"]);
+
+        let mut cfg = json!({ "channels": { "telegram": { "botToken": "1" } } });
+        apply_policy(&mut cfg, &policy).unwrap();
+        assert_eq!(
+            read_policy("telegram", &cfg["channels"]["telegram"]),
+            policy
+        );
+        assert!(validate(&RateLimitPolicy {
+            cooldown_secs: Some(10),
+            ..Default::default()
+        })
+        .is_err());
+    }
+}
@@ -0,0 +1,399 @@
+//! 更新后恢复：应用 OpenClaw 更新后 Gateway 反复崩溃或无法启动时（版本已变化 + 崩溃循环），
+//! Manager 启动时给出恢复方案（回滚到更新前的版本、恢复更新前的配置、清理后重新安装），
+//! 而不是只显示红色的状态。崩溃和启动失败由后台监控和启动流程记录，Gateway 稳定运行后清空
+use crate::commands::notifications::{self, NotificationTrigger};
+use crate::commands::updates::{self, UpdateRecord};
+use crate::commands::{events, installer, policy, probe_cache, settings};
+use crate::utils::{audit, file, platform, time};
+use chrono::DateTime;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use openclaw_macros::guarded;
+use tauri::command;
+
+/// 检测到需要恢复时发送的事件
+pub const RECOVERY_NEEDED_EVENT: &str = "recovery://needed";
+/// 更新后失败达到该次数视为崩溃循环
+const CRASH_LOOP_THRESHOLD: usize = 3;
+/// 只保留最近的失败记录
+const MAX_FAILURES: usize = 20;
+/// Gateway 连续运行超过该时间视为恢复正常，清空失败记录
+pub const STABLE_AFTER: Duration = Duration::from_secs(5 * 60);
+/// 恢复后观察 Gateway 健康状态的最短时间
+const MIN_HEALTH_WINDOW: Duration = Duration::from_secs(15);
+
+/// Gateway 的一次崩溃或启动失败
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GatewayFailure {
+    pub at: String,
+    pub error: String,
+}
+
+/// 恢复方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecoveryAction {
+    /// 重新安装更新前的版本
+    Rollback,
+    /// 恢复更新前的 openclaw.json
+    RevertConfig,
+    /// 卸载后重新安装当前版本
+    Reinstall,
+}
+
+/// 一个恢复方案
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecoveryOption {
+    pub action: RecoveryAction,
+    pub label: String,
+    pub description: String,
+    pub available: bool,
+    /// 不可用的原因
+    pub reason: Option<String>,
+}
+
+/// 更新后的恢复状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecoveryState {
+    /// 更新后 Gateway 陷入崩溃循环，需要恢复
+    pub needed: bool,
+    pub update: Option<UpdateRecord>,
+    pub installed_version: Option<String>,
+    /// 更新后的崩溃 / 启动失败
+    pub failures: Vec<GatewayFailure>,
+    pub options: Vec<RecoveryOption>,
+}
+
+/// 恢复结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecoveryResult {
+    pub action: RecoveryAction,
+    pub success: bool,
+    pub message: String,
+    /// 恢复后 Gateway 是否正常运行
+    pub gateway_healthy: bool,
+}
+
+/// 失败记录文件的读写锁
+static LOCK: Mutex<()> = Mutex::new(());
+
+fn failures_path() -> PathBuf {
+    Path::new(&platform::get_manager_data_dir()).join("gateway_failures.json")
+}
+
+fn load_failures() -> Vec<GatewayFailure> {
+    std::fs::read_to_string(failures_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// 记录 Gateway 崩溃或启动失败
+pub fn record_failure(error: &str) {
+    let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut failures = load_failures();
+    failures.push(GatewayFailure {
+        at: time::now_rfc3339(),
+        error: error.to_string(),
+    });
+    let excess = failures.len().saturating_sub(MAX_FAILURES);
+    failures.drain(..excess);
+    let result = serde_json::to_vec_pretty(&failures)
+        .map_err(|e| e.to_string())
+        .and_then(|content| {
+            file::write_atomic(&failures_path(), &content).map_err(|e| e.to_string())
+        });
+    if let Err(e) = result {
+        warn!("[更新恢复] 记录 Gateway 失败次数失败: {}", e);
+    }
+}
+
+/// Gateway 稳定运行后清空失败记录
+pub fn clear_failures() {
+    let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    if std::fs::remove_file(failures_path()).is_ok() {
+        info!("[更新恢复] Gateway 已稳定运行，清空失败记录");
+    }
+}
+
+/// 更新之后发生的失败
+fn failures_since_update(
+    record: &UpdateRecord,
+    failures: &[GatewayFailure],
+) -> Vec<GatewayFailure> {
+    let Ok(applied_at) = DateTime::parse_from_rfc3339(&record.applied_at) else {
+        return Vec::new();
+    };
+    failures
+        .iter()
+        .filter(|f| DateTime::parse_from_rfc3339(&f.at).is_ok_and(|at| at >= applied_at))
+        .cloned()
+        .collect()
+}
+
+/// 是否处于“更新已应用但 Gateway 无法正常运行”的状态：
+/// 已安装的就是更新后的版本（未回滚），且更新后失败次数达到阈值
+fn needs_recovery(
+    record: &UpdateRecord,
+    installed: Option<&str>,
+    failures_since_update: usize,
+) -> bool {
+    !record.rolled_back
+        && record.from_version.as_deref() != Some(record.to_version.as_str())
+        && updates::is_installed_version(installed, &record.to_version)
+        && failures_since_update >= CRASH_LOOP_THRESHOLD
+}
+
+fn options(record: Option<&UpdateRecord>) -> Vec<RecoveryOption> {
+    let from_version = record.and_then(|r| r.from_version.clone());
+    let config_backup = record
+        .and_then(|r| r.config_backup.clone())
+        .filter(|p| Path::new(p).exists());
+    let to_version = record.map(|r| r.to_version.clone());
+    let option = |action, label: &str, description: String, reason: Option<&str>| RecoveryOption {
+        action,
+        label: label.to_string(),
+        description,
+        available: reason.is_none(),
+        reason: reason.map(String::from),
+    };
+    vec![
+        option(
+            RecoveryAction::Rollback,
+            "回滚版本",
+            format!(
+                "重新安装更新前的 OpenClaw {}，配置保持不变",
+                from_version.as_deref().unwrap_or("")
+            ),
+            from_version.is_none().then_some("未记录更新前的版本"),
+        ),
+        option(
+            RecoveryAction::RevertConfig,
+            "恢复配置",
+            "把 openclaw.json 恢复为更新前的副本（新版本迁移配置后无法启动时使用），保留当前版本"
+                .to_string(),
+            config_backup.is_none().then_some("没有更新前的配置副本"),
+        ),
+        option(
+            RecoveryAction::Reinstall,
+            "重新安装",
+            format!(
+                "卸载后重新安装 OpenClaw {}（安装文件损坏时使用），配置保持不变",
+                to_version.as_deref().unwrap_or("")
+            ),
+            to_version.is_none().then_some("没有更新记录"),
+        ),
+    ]
+}
+
+/// 读取当前的恢复状态（阻塞，会执行 openclaw --version）
+pub fn state() -> RecoveryState {
+    let update = updates::load_record();
+    let installed_version = installer::cached_openclaw_version();
+    let failures = match &update {
+        Some(record) => {
+            let _guard = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+            failures_since_update(record, &load_failures())
+        }
+        None => Vec::new(),
+    };
+    let needed = update
+        .as_ref()
+        .is_some_and(|record| needs_recovery(record, installed_version.as_deref(), failures.len()));
+    RecoveryState {
+        needed,
+        options: options(update.as_ref()),
+        update,
+        installed_version,
+        failures,
+    }
+}
+
+/// Manager 启动时检查：更新后 Gateway 陷入崩溃循环时提示恢复
+pub fn check_on_launch() {
+    let state = state();
+    if !state.needed {
+        return;
+    }
+    let version = state
+        .update
+        .as_ref()
+        .map(|r| r.to_version.clone())
+        .unwrap_or_default();
+    warn!(
+        "[更新恢复] 更新到 OpenClaw {} 后 Gateway 已失败 {} 次",
+        version,
+        state.failures.len()
+    );
+    events::publish(RECOVERY_NEEDED_EVENT, &state);
+    notifications::notify(
+        NotificationTrigger::GatewayCrashed,
+        "update-recovery",
+        "OpenClaw 更新后无法正常运行",
+        &format!(
+            "更新到 {} 后 Gateway 已失败 {} 次，打开 Manager 选择回滚版本、恢复配置或重新安装",
+            version,
+            state.failures.len()
+        ),
+    );
+}
+
+async fn revert_config(record: &UpdateRecord) -> Result<String, String> {
+    let backup = record
+        .config_backup
+        .as_deref()
+        .ok_or("没有更新前的配置副本")?;
+    let content = std::fs::read(backup).map_err(|e| format!("读取配置副本失败: {}", e))?;
+    updates::stop_gateway();
+    let target = PathBuf::from(platform::get_config_file_path());
+    file::write_atomic(&target, &content).map_err(|e| format!("恢复配置失败: {}", e))?;
+    Ok("已恢复更新前的 openclaw.json".to_string())
+}
+
+async fn reinstall(version: &str) -> Result<String, String> {
+    updates::stop_gateway();
+    if let Err(e) = updates::npm_uninstall_global("openclaw".to_string()).await {
+        warn!("[更新恢复] 卸载 OpenClaw 失败，继续重新安装: {}", e);
+    }
+    updates::npm_install_global(format!("openclaw@{}", version)).await?;
+    probe_cache::invalidate_versions();
+    let installed = installer::get_openclaw_version();
+    if !updates::is_installed_version(installed.as_deref(), version) {
+        return Err(format!(
+            "重新安装后版本为 {}，期望 {}",
+            installed.unwrap_or_else(|| "未知".to_string()),
+            version
+        ));
+    }
+    Ok(format!("已重新安装 OpenClaw {}", version))
+}
+
+async fn execute(action: RecoveryAction, record: &UpdateRecord) -> Result<String, String> {
+    match action {
+        RecoveryAction::Rollback => {
+            let from = record.from_version.as_deref().ok_or("未记录更新前的版本")?;
+            updates::rollback(from).await?;
+            probe_cache::invalidate_versions();
+            let mut record = record.clone();
+            record.rolled_back = true;
+            record.reason = Some("更新后 Gateway 无法正常运行，手动回滚".to_string());
+            updates::save_record(&record);
+            Ok(format!("已回滚到 OpenClaw {}", from))
+        }
+        RecoveryAction::RevertConfig => revert_config(record).await,
+        RecoveryAction::Reinstall => reinstall(&record.to_version).await,
+    }
+}
+
+/// 获取更新后的恢复状态和可用的恢复方案
+#[guarded]
+#[command]
+pub async fn get_recovery_options() -> Result<RecoveryState, String> {
+    tokio::task::spawn_blocking(state)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 执行恢复方案，完成后启动 Gateway 并观察健康状态
+/// 恢复配置（覆盖 openclaw.json）和重新安装（全局卸载后重装）属于危险操作，需要确认令牌
+#[guarded]
+#[command]
+pub async fn execute_recovery(
+    option: RecoveryAction,
+    confirm_token: Option<String>,
+) -> Result<RecoveryResult, String> {
+    if matches!(option, RecoveryAction::RevertConfig | RecoveryAction::Reinstall) {
+        policy::require_confirmation("execute_recovery", confirm_token.as_deref())?;
+    }
+    let record = updates::load_record().ok_or("没有更新记录，无需恢复")?;
+    info!("[更新恢复] 执行 {:?}...", option);
+    let outcome = execute(option, &record).await;
+    let (success, message, gateway_healthy) = match outcome {
+        Ok(message) => {
+            let window =
+                Duration::from_secs(settings::load_settings().timeouts.update_health_secs)
+                    .max(MIN_HEALTH_WINDOW);
+            match updates::watch_health(window).await {
+                Ok(()) => {
+                    clear_failures();
+                    info!("[更新恢复] ✓ {}，Gateway 运行正常", message);
+                    (true, format!("{}，Gateway 运行正常", message), true)
+                }
+                Err(e) => {
+                    warn!("[更新恢复] {}，但 Gateway 仍无法正常运行: {}", message, e);
+                    record_failure(&e);
+                    (
+                        true,
+                        format!("{}，但 Gateway 仍无法正常运行: {}", message, e),
+                        false,
+                    )
+                }
+            }
+        }
+        Err(e) => {
+            warn!("[更新恢复] ✗ {:?} 失败: {}", option, e);
+            (false, e, false)
+        }
+    };
+    audit::record(
+        "execute_recovery",
+        &record.to_version,
+        success,
+        json!({ "action": option, "message": message, "gateway_healthy": gateway_healthy }),
+    );
+    Ok(RecoveryResult {
+        action: option,
+        success,
+        message,
+        gateway_healthy,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_crash_loop_after_update() {
+        let record = UpdateRecord {
+            from_version: Some("2026.3.1".to_string()),
+            to_version: "2026.4.0".to_string(),
+            applied_at: "2026-10-15T10:00:00+08:00".to_string(),
+            rolled_back: false,
+            reason: None,
+            config_backup: None,
+        };
+        let failure = |at: &str| GatewayFailure {
+            at: at.to_string(),
+            error: "Gateway 进程意外退出".to_string(),
+        };
+        let failures = vec![
+            failure("2026-10-15T09:00:00+08:00"),
+            failure("2026-10-15T02:05:00Z"),
+            failure("2026-10-15T10:10:00+08:00"),
+            failure("2026-10-15T10:20:00+08:00"),
+        ];
+        let since = failures_since_update(&record, &failures);
+        assert_eq!(since.len(), 3);
+        assert_eq!(since[0].at, "2026-10-15T02:05:00Z");
+
+        let installed = Some("openclaw 2026.4.0");
+        assert!(needs_recovery(&record, installed, 3));
+        assert!(!needs_recovery(&record, installed, 2));
+        assert!(!needs_recovery(&record, Some("openclaw 2026.3.1"), 3));
+        let rolled_back = UpdateRecord {
+            rolled_back: true,
+            ..record.clone()
+        };
+        assert!(!needs_recovery(&rolled_back, installed, 3));
+
+        let options = options(Some(&record));
+        assert!(options[0].available);
+        assert_eq!(options[1].reason.as_deref(), Some("没有更新前的配置副本"));
+        assert!(options.iter().all(|o| !o.available || o.reason.is_none()));
+    }
+}
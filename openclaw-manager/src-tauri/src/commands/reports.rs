@@ -0,0 +1,368 @@
+//! 报表导出：把用量、费用、可用率和错误汇总导出为 CSV / JSON 文件，
+//! 用于报销或团队复盘（数据来自 analytics.db 和 Gateway 日志）
+use crate::commands::{analytics, logs, monitor};
+use crate::utils::{audit, file, time};
+use log::info;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use openclaw_macros::guarded;
+use tauri::command;
+
+/// 报表类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportKind {
+    /// 每日各 Agent 的会话和消息数
+    Usage,
+    /// 每日各 Provider 的费用
+    Costs,
+    /// 每日 Gateway 可用率和崩溃次数
+    Uptime,
+    /// Gateway 日志中的主要错误
+    Errors,
+}
+
+/// 导出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportFormat {
+    Csv,
+    Json,
+}
+
+impl ReportFormat {
+    fn extension(&self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Json => "json",
+        }
+    }
+}
+
+/// 导出结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportExport {
+    pub path: String,
+    pub rows: usize,
+}
+
+/// 报表内容：列名和按列排列的行
+#[derive(Debug, Clone, Default, PartialEq)]
+struct ReportTable {
+    columns: Vec<&'static str>,
+    rows: Vec<Vec<Value>>,
+}
+
+fn query_table(
+    conn: &Connection,
+    sql: &str,
+    since: i64,
+    columns: Vec<&'static str>,
+) -> Result<ReportTable, String> {
+    let mut stmt = conn.prepare(sql).map_err(|e| e.to_string())?;
+    let count = columns.len();
+    let rows = stmt
+        .query_map(params![since, time::sqlite_offset()], |row| {
+            (0..count)
+                .map(|i| {
+                    Ok(match row.get_ref(i)? {
+                        rusqlite::types::ValueRef::Integer(n) => json!(n),
+                        rusqlite::types::ValueRef::Real(f) => {
+                            json!((f * 10000.0).round() / 10000.0)
+                        }
+                        rusqlite::types::ValueRef::Text(t) => json!(String::from_utf8_lossy(t)),
+                        _ => Value::Null,
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(ReportTable { columns, rows })
+}
+
+fn usage_report(conn: &Connection, since: i64) -> Result<ReportTable, String> {
+    query_table(
+        conn,
+        "SELECT date(ts / 1000, 'unixepoch', ?2) AS d, agent,
+                COUNT(DISTINCT session),
+                SUM(role = 'user'),
+                SUM(role = 'assistant'),
+                SUM(is_error),
+                ROUND(AVG(latency_ms))
+         FROM messages WHERE ts >= ?1
+         GROUP BY d, agent ORDER BY d, agent",
+        since,
+        vec![
+            "date",
+            "agent",
+            "sessions",
+            "user_messages",
+            "assistant_messages",
+            "errors",
+            "avg_latency_ms",
+        ],
+    )
+}
+
+fn costs_report(conn: &Connection, since: i64) -> Result<ReportTable, String> {
+    query_table(
+        conn,
+        "SELECT date(ts / 1000, 'unixepoch', ?2) AS d, provider, COUNT(*), SUM(cost)
+         FROM usage WHERE ts >= ?1
+         GROUP BY d, provider ORDER BY d, provider",
+        since,
+        vec!["date", "provider", "responses", "cost_usd"],
+    )
+}
+
+/// 按状态采样计算每日可用率：相邻采样间隔超过两倍采样周期（Manager 未运行）的时段不计入
+fn uptime_table(samples: &[(i64, String)], now_ms: i64) -> ReportTable {
+    let max_gap = 2 * monitor::STATUS_RECORD_INTERVAL.as_millis() as i64;
+    let date = |ms: i64| {
+        time::from_millis(ms)
+            .map(|t| t.format("%Y-%m-%d").to_string())
+            .unwrap_or_default()
+    };
+    // 日期 -> (观测毫秒, 运行毫秒, 崩溃次数)
+    let mut days: BTreeMap<String, (i64, i64, u64)> = BTreeMap::new();
+    for (i, (ts, state)) in samples.iter().enumerate() {
+        let day = days.entry(date(*ts)).or_default();
+        if state == "crashed" {
+            day.2 += 1;
+        }
+        let next = samples.get(i + 1).map(|(t, _)| *t).unwrap_or(now_ms);
+        let gap = next - ts;
+        if gap > 0 && gap <= max_gap {
+            day.0 += gap;
+            if state == "running" {
+                day.1 += gap;
+            }
+        }
+    }
+    let rows = days
+        .into_iter()
+        .map(|(date, (observed, running, crashes))| {
+            let uptime = (observed > 0)
+                .then(|| (running as f64 * 10000.0 / observed as f64).round() / 100.0);
+            vec![
+                json!(date),
+                json!(observed / 60_000),
+                json!(running / 60_000),
+                json!(uptime),
+                json!(crashes),
+            ]
+        })
+        .collect();
+    ReportTable {
+        columns: vec![
+            "date",
+            "observed_minutes",
+            "running_minutes",
+            "uptime_percent",
+            "crashes",
+        ],
+        rows,
+    }
+}
+
+fn uptime_report(conn: &Connection, since: i64, now_ms: i64) -> Result<ReportTable, String> {
+    let mut stmt = conn
+        .prepare("SELECT ts, state FROM gateway_status WHERE ts >= ?1 ORDER BY ts")
+        .map_err(|e| e.to_string())?;
+    let samples = stmt
+        .query_map(params![since], |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(uptime_table(&samples, now_ms))
+}
+
+fn errors_report(since: i64) -> ReportTable {
+    let (_, entries) = logs::read_entries();
+    let entries: Vec<logs::LogEntry> = entries
+        .into_iter()
+        .filter(|e| logs::in_range(e, since))
+        .collect();
+    let rows = logs::summarize(&entries)
+        .into_iter()
+        .map(|issue| {
+            vec![
+                serde_json::to_value(issue.category).unwrap_or_default(),
+                json!(issue.signature),
+                json!(issue.module),
+                json!(issue.count),
+                json!(issue.first_seen),
+                json!(issue.last_seen),
+                json!(issue.sample),
+            ]
+        })
+        .collect();
+    ReportTable {
+        columns: vec![
+            "category",
+            "signature",
+            "module",
+            "count",
+            "first_seen",
+            "last_seen",
+            "sample",
+        ],
+        rows,
+    }
+}
+
+fn csv_field(value: &Value) -> String {
+    let text = match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    // 以公式符号开头的文本加前缀，避免在表格软件中被当作公式执行
+    let text = if text.starts_with(['=', '+', '-', '@']) && !value.is_number() {
+        format!("'{}", text)
+    } else {
+        text
+    };
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text
+    }
+}
+
+fn render(table: &ReportTable, format: ReportFormat, kind: ReportKind, range: &str) -> String {
+    match format {
+        ReportFormat::Csv => {
+            // 带 BOM，Excel 才能正确识别 UTF-8 中文
+            let mut out = format!("\u{feff}{}\r\n", table.columns.join(","));
+            for row in &table.rows {
+                let fields: Vec<String> = row.iter().map(csv_field).collect();
+                out.push_str(&fields.join(","));
+                out.push_str("\r\n");
+            }
+            out
+        }
+        ReportFormat::Json => {
+            let rows: Vec<Value> = table
+                .rows
+                .iter()
+                .map(|row| {
+                    let object: Map<String, Value> = table
+                        .columns
+                        .iter()
+                        .map(|c| c.to_string())
+                        .zip(row.iter().cloned())
+                        .collect();
+                    Value::Object(object)
+                })
+                .collect();
+            let report = json!({
+                "kind": kind,
+                "range": range,
+                "generated_at": time::now_rfc3339(),
+                "rows": rows,
+            });
+            serde_json::to_string_pretty(&report).unwrap_or_default()
+        }
+    }
+}
+
+/// 导出报表到用户选择的文件（range: 24h / 7d / 30d / all），文件没有扩展名时按格式补全
+#[guarded]
+#[command]
+pub async fn export_report(
+    kind: ReportKind,
+    range: String,
+    format: ReportFormat,
+    path: String,
+) -> Result<ReportExport, String> {
+    let now = chrono::Utc::now().timestamp_millis();
+    let since = analytics::range_start(&range, now)?;
+    let mut path = PathBuf::from(path.trim());
+    if !path.is_absolute() {
+        return Err(format!("请选择完整的保存路径: {}", path.display()));
+    }
+    if path.extension().is_none() {
+        path.set_extension(format.extension());
+    }
+
+    let table = tokio::task::spawn_blocking(move || match kind {
+        ReportKind::Usage => usage_report(&analytics::open_store()?, since),
+        ReportKind::Costs => costs_report(&analytics::open_store()?, since),
+        ReportKind::Uptime => uptime_report(&analytics::open_store()?, since, now),
+        ReportKind::Errors => Ok(errors_report(since)),
+    })
+    .await
+    .map_err(|e| format!("生成报表失败: {}", e))??;
+
+    let content = render(&table, format, kind, &range);
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent).map_err(|e| format!("创建目录失败: {}", e))?;
+    }
+    file::write_atomic(Path::new(&path), content.as_bytes())
+        .map_err(|e| format!("保存报表失败: {}", e))?;
+    let path = path.to_string_lossy().to_string();
+    audit::record(
+        "export_report",
+        &path,
+        true,
+        json!({ "kind": kind, "range": range, "format": format, "rows": table.rows.len() }),
+    );
+    info!("[报表导出] ✓ 已导出 {} 行到 {}", table.rows.len(), path);
+    Ok(ReportExport {
+        path,
+        rows: table.rows.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn computes_uptime_and_renders_csv() {
+        let start = chrono::Local::now()
+            .date_naive()
+            .and_hms_opt(1, 0, 0)
+            .unwrap()
+            .and_local_timezone(chrono::Local)
+            .unwrap()
+            .timestamp_millis();
+        let minute = 60_000;
+        let samples: Vec<(i64, String)> = [
+            (0, "running"),
+            (5, "running"),
+            (10, "crashed"),
+            (15, "stopped"),
+            // 之后 Manager 未运行一小时，不计入观测时间
+            (80, "running"),
+        ]
+        .iter()
+        .map(|(m, s)| (start + m * minute, s.to_string()))
+        .collect();
+        let table = uptime_table(&samples, start + 85 * minute);
+        assert_eq!(table.rows.len(), 1);
+        assert_eq!(
+            &table.rows[0][1..],
+            &[json!(20), json!(15), json!(75.0), json!(1)]
+        );
+
+        let table = ReportTable {
+            columns: vec!["name", "note"],
+            rows: vec![
+                vec![json!("a,b"), json!("=SUM(1)")],
+                vec![json!("say \"hi\""), Value::Null],
+            ],
+        };
+        let csv = render(&table, ReportFormat::Csv, ReportKind::Errors, "7d");
+        assert_eq!(
+            csv,
+            "\u{feff}name,note\r\n\"a,b\",'=SUM(1)\r\n\"say \"\"hi\"\"\",\r\n"
+        );
+    }
+}
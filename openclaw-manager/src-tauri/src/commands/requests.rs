@@ -0,0 +1,101 @@
+use crate::commands::crash;
+use crate::utils::audit::{self, AuditEntry};
+use crate::utils::panic_guard;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use openclaw_macros::guarded;
+use tauri::command;
+
+/// 一次前端调用的完整记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RequestTrace {
+    pub request_id: String,
+    /// 执行的命令（从日志中识别）
+    pub command: Option<String>,
+    /// Manager 日志中带该请求编号的行（包括启动的子进程）
+    pub logs: Vec<String>,
+    pub audit: Vec<AuditEntry>,
+    /// 该请求中 panic 生成的崩溃报告
+    pub crash_report: Option<String>,
+}
+
+/// Manager 日志格式：在命令执行期间的日志行中加上请求编号
+pub fn format_record(
+    buf: &mut env_logger::fmt::Formatter,
+    record: &log::Record,
+) -> std::io::Result<()> {
+    let request = panic_guard::current()
+        .map(|(command, id)| format!(" [req:{} {}]", id, command))
+        .unwrap_or_default();
+    writeln!(
+        buf,
+        "[{} {:<5} {}]{} {}",
+        buf.timestamp(),
+        record.level(),
+        record.target(),
+        request,
+        record.args()
+    )
+}
+
+/// 从日志行中识别命令名（格式见 [`format_record`]）
+fn command_from_line(line: &str, request_id: &str) -> Option<String> {
+    let marker = format!("[req:{} ", request_id);
+    let rest = &line[line.find(&marker)? + marker.len()..];
+    rest.split(']').next().map(|s| s.to_string())
+}
+
+fn is_request_id(id: &str) -> bool {
+    uuid::Uuid::parse_str(id).is_ok()
+}
+
+/// 按请求编号汇总一次调用相关的日志、审计记录和崩溃报告
+#[guarded]
+#[command]
+pub async fn get_request_trace(id: String) -> Result<RequestTrace, String> {
+    let id = id.trim().to_string();
+    if !is_request_id(&id) {
+        return Err(format!("无效的请求编号: {}", id));
+    }
+    let logs: Vec<String> = std::fs::read_to_string(crash::log_path())
+        .unwrap_or_default()
+        .lines()
+        .filter(|l| l.contains(&id))
+        .map(|l| l.to_string())
+        .collect();
+    let audit: Vec<AuditEntry> = audit::read_entries()
+        .into_iter()
+        .filter(|e| e.request_id.as_deref() == Some(id.as_str()))
+        .collect();
+    let crash_report = crash::find_by_correlation_id(&id);
+    if logs.is_empty() && audit.is_empty() && crash_report.is_none() {
+        return Err(format!("没有找到请求 {} 的记录（日志可能已轮转）", id));
+    }
+    Ok(RequestTrace {
+        command: logs.iter().find_map(|l| command_from_line(l, &id)),
+        request_id: id,
+        logs,
+        audit,
+        crash_report,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_command_name_in_tagged_log_line() {
+        let id = "0f8fad5b-d9cb-469f-a165-70867728950e";
+        let line = format!(
+            "[2026-10-16T08:00:00Z WARN  openclaw_manager::utils::panic_guard] [req:{} save_provider] [命令] ✗ save_provider 失败: 写入失败",
+            id
+        );
+        assert_eq!(
+            command_from_line(&line, id).as_deref(),
+            Some("save_provider")
+        );
+        assert!(is_request_id(id));
+        assert!(!is_request_id(""));
+    }
+}
@@ -0,0 +1,305 @@
+//! 磁盘 / 内存防护：配置目录所在磁盘空间或可用内存过低时，Gateway 写入会话文件可能被截断损坏。
+//! 启动、重启 Gateway 前检查，低于阈值时拒绝启动（可强制启动）；
+//! 运行中由后台监控定期检查，降到阈值以下时推送 resources://alert 事件并发送通知
+use crate::commands::events;
+use crate::commands::notifications::{self, NotificationTrigger};
+use crate::commands::settings;
+use crate::utils::{audit, platform, shell};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use openclaw_macros::guarded;
+use tauri::{command, AppHandle};
+
+/// 资源不足告警事件
+pub const RESOURCE_ALERT_EVENT: &str = "resources://alert";
+/// 资源不足时拒绝启动的错误前缀（前端据此询问是否强制启动）
+pub const LOW_RESOURCES_PREFIX: &str = "LowResources";
+
+const MB: u64 = 1024 * 1024;
+
+/// 资源阈值（0 表示不检查）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ResourceSettings {
+    /// 配置目录所在磁盘的最少剩余空间（MB）
+    pub min_free_disk_mb: u64,
+    /// 最少可用内存（MB）
+    pub min_available_memory_mb: u64,
+}
+
+impl Default for ResourceSettings {
+    fn default() -> Self {
+        Self {
+            min_free_disk_mb: 512,
+            min_available_memory_mb: 256,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResourceKind {
+    Disk,
+    Memory,
+}
+
+/// 低于阈值的资源
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResourceProblem {
+    pub kind: ResourceKind,
+    pub available_bytes: u64,
+    pub threshold_bytes: u64,
+    pub message: String,
+}
+
+/// 当前资源状况（读取失败的项为空，不限制启动）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceStatus {
+    /// 检查磁盘空间的目录（OpenClaw 配置目录）
+    pub disk_path: String,
+    pub free_disk_bytes: Option<u64>,
+    pub available_memory_bytes: Option<u64>,
+    pub problems: Vec<ResourceProblem>,
+}
+
+/// 已告警的资源（恢复后清除，再次不足时重新告警）
+static ALERTED: Mutex<Option<HashSet<ResourceKind>>> = Mutex::new(None);
+
+/// 路径所在磁盘的可用空间（路径不存在时取最近的已存在上级目录）
+pub fn available_space(path: &Path) -> Option<u64> {
+    let existing = path.ancestors().find(|p| p.exists())?;
+    if platform::is_windows() {
+        let script = format!(
+            "(Get-Item -LiteralPath {}).PSDrive.Free",
+            shell::powershell_quote(&existing.to_string_lossy())
+        );
+        shell::run_powershell_output(&script)
+            .ok()?
+            .trim()
+            .parse()
+            .ok()
+    } else {
+        let output = shell::run_command_output("df", &["-Pk", &existing.to_string_lossy()]).ok()?;
+        let kb: u64 = output
+            .lines()
+            .nth(1)?
+            .split_whitespace()
+            .nth(3)?
+            .parse()
+            .ok()?;
+        Some(kb * 1024)
+    }
+}
+
+/// /proc/meminfo 中的 MemAvailable（字节）
+fn parse_meminfo(content: &str) -> Option<u64> {
+    let line = content.lines().find(|l| l.starts_with("MemAvailable:"))?;
+    let kb: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kb * 1024)
+}
+
+/// vm_stat 输出中空闲、非活跃和可回收的页面（字节）
+fn parse_vm_stat(output: &str) -> Option<u64> {
+    let page_size: u64 = output
+        .lines()
+        .next()?
+        .split("page size of ")
+        .nth(1)?
+        .split_whitespace()
+        .next()?
+        .parse()
+        .ok()?;
+    let pages = |name: &str| -> u64 {
+        output
+            .lines()
+            .find_map(|l| l.strip_prefix(name))
+            .and_then(|v| v.trim().trim_end_matches('.').parse().ok())
+            .unwrap_or(0)
+    };
+    let total = pages("Pages free:") + pages("Pages inactive:") + pages("Pages speculative:");
+    (total > 0).then_some(total * page_size)
+}
+
+/// 系统可用内存
+pub fn available_memory() -> Option<u64> {
+    if platform::is_windows() {
+        let kb: u64 = shell::run_powershell_output(
+            "(Get-CimInstance Win32_OperatingSystem).FreePhysicalMemory",
+        )
+        .ok()?
+        .trim()
+        .parse()
+        .ok()?;
+        Some(kb * 1024)
+    } else if platform::is_macos() {
+        parse_vm_stat(&shell::run_command_output("vm_stat", &[]).ok()?)
+    } else {
+        parse_meminfo(&std::fs::read_to_string("/proc/meminfo").ok()?)
+    }
+}
+
+/// 按阈值判断哪些资源不足
+fn evaluate(
+    free_disk: Option<u64>,
+    available_memory: Option<u64>,
+    settings: &ResourceSettings,
+) -> Vec<ResourceProblem> {
+    let mut problems = Vec::new();
+    let disk_threshold = settings.min_free_disk_mb * MB;
+    if let Some(free) = free_disk.filter(|free| *free < disk_threshold) {
+        problems.push(ResourceProblem {
+            kind: ResourceKind::Disk,
+            available_bytes: free,
+            threshold_bytes: disk_threshold,
+            message: format!(
+                "磁盘空间不足：配置目录所在磁盘剩余 {}MB，低于 {}MB",
+                free / MB,
+                settings.min_free_disk_mb
+            ),
+        });
+    }
+    let memory_threshold = settings.min_available_memory_mb * MB;
+    if let Some(available) = available_memory.filter(|a| *a < memory_threshold) {
+        problems.push(ResourceProblem {
+            kind: ResourceKind::Memory,
+            available_bytes: available,
+            threshold_bytes: memory_threshold,
+            message: format!(
+                "可用内存不足：剩余 {}MB，低于 {}MB",
+                available / MB,
+                settings.min_available_memory_mb
+            ),
+        });
+    }
+    problems
+}
+
+/// 读取当前资源状况（阻塞，异步代码中应放到 spawn_blocking 中调用）
+pub fn status() -> ResourceStatus {
+    let settings = settings::load_settings().resources;
+    let disk_path = PathBuf::from(platform::get_config_dir());
+    let free_disk_bytes = if settings.min_free_disk_mb > 0 {
+        available_space(&disk_path)
+    } else {
+        None
+    };
+    let available_memory_bytes = if settings.min_available_memory_mb > 0 {
+        available_memory()
+    } else {
+        None
+    };
+    ResourceStatus {
+        disk_path: disk_path.to_string_lossy().to_string(),
+        problems: evaluate(free_disk_bytes, available_memory_bytes, &settings),
+        free_disk_bytes,
+        available_memory_bytes,
+    }
+}
+
+/// 启动 / 重启 Gateway 前检查：资源不足时返回 LowResources 错误，`force` 为 true 时只记录
+pub fn ensure_can_start(force: bool) -> Result<(), String> {
+    let status = status();
+    if status.problems.is_empty() {
+        return Ok(());
+    }
+    let messages: Vec<&str> = status.problems.iter().map(|p| p.message.as_str()).collect();
+    if force {
+        warn!(
+            "[资源检查] 资源不足，按用户要求强制启动: {}",
+            messages.join("；")
+        );
+        audit::record(
+            "force_start_low_resources",
+            "gateway",
+            true,
+            json!({ "problems": status.problems }),
+        );
+        return Ok(());
+    }
+    warn!(
+        "[资源检查] ✗ 资源不足，拒绝启动 Gateway: {}",
+        messages.join("；")
+    );
+    Err(format!(
+        "{}: {}。释放空间或内存后重试，也可以强制启动",
+        LOW_RESOURCES_PREFIX,
+        messages.join("；")
+    ))
+}
+
+/// 后台监控定期调用（Gateway 运行时）：资源降到阈值以下时告警，恢复后重新计入
+pub async fn check(app: &AppHandle) {
+    let Ok(status) = tokio::task::spawn_blocking(status).await else {
+        return;
+    };
+    let current: HashSet<ResourceKind> = status.problems.iter().map(|p| p.kind).collect();
+    let new_problems: Vec<&ResourceProblem> = {
+        let mut alerted = ALERTED.lock().unwrap_or_else(|e| e.into_inner());
+        let alerted = alerted.get_or_insert_with(HashSet::new);
+        let new = status
+            .problems
+            .iter()
+            .filter(|p| !alerted.contains(&p.kind))
+            .collect();
+        for kind in alerted.difference(&current) {
+            info!("[资源检查] {:?} 已恢复到阈值以上", kind);
+        }
+        *alerted = current;
+        new
+    };
+    for problem in new_problems {
+        warn!("[资源检查] {}", problem.message);
+        let _ = events::emit(app, RESOURCE_ALERT_EVENT, problem);
+        notifications::notify(
+            NotificationTrigger::LowResources,
+            &format!("resources-{:?}", problem.kind),
+            "系统资源不足",
+            &format!("{}，Gateway 写入会话可能失败", problem.message),
+        );
+    }
+}
+
+/// 获取当前磁盘空间和可用内存
+#[guarded]
+#[command]
+pub async fn get_resource_status() -> Result<ResourceStatus, String> {
+    tokio::task::spawn_blocking(status)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_memory_and_applies_thresholds() {
+        let meminfo = "MemTotal:       16318504 kB\nMemFree:          512000 kB\nMemAvailable:    8159252 kB\n";
+        assert_eq!(parse_meminfo(meminfo), Some(8_159_252 * 1024));
+        let vm_stat = "Mach Virtual Memory Statistics: (page size of 16384 bytes)\nPages free:                               10000.\nPages active:                            500000.\nPages inactive:                           5000.\nPages speculative:                        1000.\n";
+        assert_eq!(parse_vm_stat(vm_stat), Some(16_000 * 16384));
+
+        let settings = ResourceSettings::default();
+        assert!(evaluate(Some(10 * 1024 * MB), Some(4096 * MB), &settings).is_empty());
+        assert!(evaluate(None, None, &settings).is_empty());
+        let problems = evaluate(Some(100 * MB), Some(128 * MB), &settings);
+        assert_eq!(
+            problems.iter().map(|p| p.kind).collect::<Vec<_>>(),
+            vec![ResourceKind::Disk, ResourceKind::Memory]
+        );
+        assert_eq!(
+            problems[0].message,
+            "磁盘空间不足：配置目录所在磁盘剩余 100MB，低于 512MB"
+        );
+
+        let disabled = ResourceSettings {
+            min_free_disk_mb: 0,
+            min_available_memory_mb: 0,
+        };
+        assert!(evaluate(Some(0), Some(0), &disabled).is_empty());
+    }
+}
@@ -0,0 +1,233 @@
+//! 密钥句柄：前端读取配置时密钥字段被替换为不透明的句柄，保存时再由后端换回真实值，
+//! 明文密钥不经过 WebView（页面脚本被注入时也无法读出已保存的密钥）。
+//! 新输入的密钥先通过 `stage_secret` 换成短期句柄再提交
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use openclaw_macros::guarded;
+use tauri::command;
+
+/// 句柄前缀
+pub const HANDLE_PREFIX: &str = "secret-handle:";
+
+/// 新输入密钥的句柄有效期（测试连接后还要保存，有效期内可重复使用）
+const STAGED_TTL: Duration = Duration::from_secs(10 * 60);
+/// 已保存密钥的句柄有效期（再次读取配置时刷新）
+const STORED_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// 视为密钥的字段名后缀（忽略大小写、下划线和连字符，如 apiKey、botToken、OPENAI_API_KEY）
+const SECRET_SUFFIXES: &[&str] = &[
+    "apikey",
+    "token",
+    "secret",
+    "password",
+    "privatekey",
+    "accesskey",
+    "encryptkey",
+    "signingkey",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+enum HandleSource {
+    /// 新输入的密钥
+    Staged(String),
+    /// 已保存的密钥所在位置（如 channels/telegram/botToken、env:OPENAI_API_KEY）
+    Stored(String),
+}
+
+#[derive(Debug, Clone)]
+struct HandleEntry {
+    source: HandleSource,
+    expires_at: Instant,
+}
+
+static HANDLES: Mutex<Option<HashMap<String, HandleEntry>>> = Mutex::new(None);
+
+fn with_handles<R>(f: impl FnOnce(&mut HashMap<String, HandleEntry>) -> R) -> R {
+    let mut guard = HANDLES.lock().unwrap_or_else(|e| e.into_inner());
+    let handles = guard.get_or_insert_with(HashMap::new);
+    let now = Instant::now();
+    handles.retain(|_, entry| entry.expires_at > now);
+    f(handles)
+}
+
+fn new_handle() -> String {
+    format!("{}{}", HANDLE_PREFIX, uuid::Uuid::new_v4().simple())
+}
+
+/// 字段名是否表示密钥
+pub fn is_secret_key(key: &str) -> bool {
+    let normalized: String = key
+        .chars()
+        .filter(|c| *c != '_' && *c != '-')
+        .collect::<String>()
+        .to_ascii_lowercase();
+    SECRET_SUFFIXES.iter().any(|s| normalized.ends_with(s))
+}
+
+pub fn is_handle(value: &str) -> bool {
+    value.starts_with(HANDLE_PREFIX)
+}
+
+/// 已保存密钥的句柄（同一位置复用未过期的句柄）
+pub fn handle_for(location: &str) -> String {
+    with_handles(|handles| {
+        let expires_at = Instant::now() + STORED_TTL;
+        let existing = handles
+            .iter_mut()
+            .find(|(_, e)| e.source == HandleSource::Stored(location.to_string()));
+        if let Some((handle, entry)) = existing {
+            entry.expires_at = expires_at;
+            return handle.clone();
+        }
+        let handle = new_handle();
+        handles.insert(
+            handle.clone(),
+            HandleEntry {
+                source: HandleSource::Stored(location.to_string()),
+                expires_at,
+            },
+        );
+        handle
+    })
+}
+
+/// 把前端提交的值换回真实密钥：不是句柄时原样返回；
+/// 已保存密钥的句柄只能用于原来的位置（表示保持不变），`current` 为该位置当前的值
+pub fn resolve(input: &str, location: &str, current: Option<&str>) -> Result<String, String> {
+    if !is_handle(input) {
+        return Ok(input.to_string());
+    }
+    let source = with_handles(|handles| handles.get(input).map(|e| e.source.clone()));
+    match source {
+        Some(HandleSource::Staged(value)) => Ok(value),
+        Some(HandleSource::Stored(stored)) if stored == location => current
+            .map(|v| v.to_string())
+            .ok_or_else(|| format!("{} 原有的密钥已不存在，请重新输入", location)),
+        Some(HandleSource::Stored(_)) => Err(format!("密钥句柄不能用于 {}", location)),
+        None => Err("密钥句柄已失效，请重新输入密钥".to_string()),
+    }
+}
+
+/// 把 JSON 中的密钥字段替换为句柄（`base` 为该 JSON 在配置中的路径）
+pub fn mask_value(value: &mut Value, base: &str) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map.iter_mut() {
+                let path = join(base, key);
+                match child {
+                    Value::String(s) if is_secret_key(key) && !s.is_empty() => {
+                        *child = Value::String(handle_for(&path));
+                    }
+                    _ => mask_value(child, &path),
+                }
+            }
+        }
+        Value::Array(items) => {
+            for (i, item) in items.iter_mut().enumerate() {
+                mask_value(item, &join(base, &i.to_string()));
+            }
+        }
+        _ => {}
+    }
+}
+
+/// 把 JSON 中的句柄换回真实密钥，`current` 为当前保存的配置（与 `value` 位于同一路径）
+pub fn unmask_value(value: &mut Value, base: &str, current: Option<&Value>) -> Result<(), String> {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map.iter_mut() {
+                unmask_value(child, &join(base, key), current.and_then(|c| c.get(key)))?;
+            }
+        }
+        Value::Array(items) => {
+            for (i, item) in items.iter_mut().enumerate() {
+                unmask_value(
+                    item,
+                    &join(base, &i.to_string()),
+                    current.and_then(|c| c.get(i)),
+                )?;
+            }
+        }
+        Value::String(s) if is_handle(s) => {
+            *s = resolve(s, base, current.and_then(Value::as_str))?;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// 暂存新输入的密钥，返回句柄
+pub fn stage(value: String) -> String {
+    let handle = new_handle();
+    with_handles(|handles| {
+        handles.insert(
+            handle.clone(),
+            HandleEntry {
+                source: HandleSource::Staged(value),
+                expires_at: Instant::now() + STAGED_TTL,
+            },
+        )
+    });
+    handle
+}
+
+fn join(base: &str, key: &str) -> String {
+    if base.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}/{}", base, key)
+    }
+}
+
+/// 暂存新输入的密钥，返回句柄（10 分钟内有效）
+#[guarded]
+#[command]
+pub async fn stage_secret(value: String) -> Result<String, String> {
+    if value.is_empty() {
+        return Err("密钥不能为空".to_string());
+    }
+    if is_handle(&value) {
+        return Ok(value);
+    }
+    Ok(stage(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn masks_and_restores_secrets_by_location() {
+        let saved =
+            json!({ "channels": { "telegram": { "botToken": "123:abc", "userId": "42" } } });
+        let mut view = saved.clone();
+        mask_value(&mut view, "");
+        let handle = view["channels"]["telegram"]["botToken"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        assert!(is_handle(&handle));
+        assert_eq!(view["channels"]["telegram"]["userId"], "42");
+
+        // 原样提交：换回已保存的值
+        let mut submitted = view.clone();
+        unmask_value(&mut submitted, "", Some(&saved)).unwrap();
+        assert_eq!(submitted, saved);
+
+        // 句柄不能挪到其它字段
+        let mut moved = json!({ "channels": { "slack": { "botToken": handle } } });
+        assert!(unmask_value(&mut moved, "", Some(&saved)).is_err());
+
+        // 新输入的密钥可用于任意字段
+        let staged = stage("xoxb-new".to_string());
+        assert_eq!(
+            resolve(&staged, "channels/slack/botToken", None).unwrap(),
+            "xoxb-new"
+        );
+        assert!(resolve("secret-handle:unknown", "channels/slack/botToken", None).is_err());
+        assert_eq!(resolve("plain", "any", None).unwrap(), "plain");
+    }
+}
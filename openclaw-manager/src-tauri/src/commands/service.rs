@@ -1,8 +1,15 @@
-use crate::models::ServiceStatus;
-use crate::utils::shell;
+use crate::commands::hooks::{self, HookEvent};
+use crate::commands::capabilities::{self, Feature};
+use crate::commands::{
+    config_lint, diagnostics, gateway_lock, monitor, recovery, resources, settings,
+};
+use crate::models::{GatewayOwner, ServiceStatus};
+use crate::utils::{audit, encoding, executor, shell};
+use openclaw_macros::guarded;
 use tauri::command;
 use std::process::Command;
 use log::{info, debug, error};
+use serde_json::{json, Value};
 
 #[cfg(windows)]
 use std::os::windows::process::CommandExt;
@@ -11,20 +18,19 @@ use std::os::windows::process::CommandExt;
 #[cfg(windows)]
 const CREATE_NO_WINDOW: u32 = 0x08000000;
 
-const SERVICE_PORT: u16 = 8789;
+pub const SERVICE_PORT: u16 = 8789;
 
 /// 检测端口是否有服务在监听，返回 PID
 /// 简单直接：端口被占用 = 服务运行中
-fn check_port_listening(port: u16) -> Option<u32> {
+pub fn check_port_listening(port: u16) -> Option<u32> {
     #[cfg(unix)]
     {
-        let output = Command::new("lsof")
-            .args(["-ti", &format!(":{}", port)])
-            .output()
+        let output = executor::current()
+            .output(Command::new("lsof").args(["-ti", &format!(":{}", port)]))
             .ok()?;
         
         if output.status.success() {
-            String::from_utf8_lossy(&output.stdout)
+            encoding::decode_output(&output.stdout)
                 .lines()
                 .next()
                 .and_then(|line| line.trim().parse::<u32>().ok())
@@ -39,10 +45,10 @@ fn check_port_listening(port: u16) -> Option<u32> {
         cmd.args(["-ano"]);
         cmd.creation_flags(CREATE_NO_WINDOW);
         
-        let output = cmd.output().ok()?;
+        let output = executor::current().output(&mut cmd).ok()?;
         
         if output.status.success() {
-            let stdout = String::from_utf8_lossy(&output.stdout);
+            let stdout = encoding::decode_output(&output.stdout);
             for line in stdout.lines() {
                 if line.contains(&format!(":{}", port)) && line.contains("LISTENING") {
                     if let Some(pid_str) = line.split_whitespace().last() {
@@ -57,13 +63,54 @@ fn check_port_listening(port: u16) -> Option<u32> {
     }
 }
 
+/// 网关进程 PID（未运行时返回 None）
+pub fn gateway_pid() -> Option<u32> {
+    check_port_listening(SERVICE_PORT)
+}
+
+/// 调用 Gateway RPC 方法（openclaw gateway call），返回 JSON 结果
+/// 阻塞直到命令返回或超时，异步代码中应放到 spawn_blocking 中调用
+pub fn gateway_call(
+    method: &str,
+    params: &Value,
+    timeout: std::time::Duration,
+) -> Result<Value, String> {
+    if gateway_pid().is_none() {
+        return Err("Gateway 未运行，请先启动服务".to_string());
+    }
+    let params = params.to_string();
+    let timeout_ms = timeout.as_millis().to_string();
+    let output = shell::run_openclaw(&[
+        "gateway", "call", method, "--params", &params, "--timeout", &timeout_ms, "--json",
+    ])?;
+    let json = diagnostics::extract_json_from_output(&output)
+        .ok_or_else(|| format!("Gateway 返回了无法解析的结果: {}", output.trim()))?;
+    serde_json::from_str(&json).map_err(|e| format!("解析 Gateway 返回结果失败 ({}): {}", method, e))
+}
+
+/// 后台启动网关并等待端口开始监听（不打开浏览器，供更新等内部流程使用）
+pub fn spawn_gateway_and_wait() -> Result<u32, String> {
+    shell::spawn_openclaw_gateway_with_args(&["gateway", "--port", &SERVICE_PORT.to_string()])
+        .map_err(|e| format!("启动服务失败: {}", e))?;
+    let timeout_secs = settings::load_settings().timeouts.gateway_start_secs.max(1);
+    for _ in 0..timeout_secs {
+        std::thread::sleep(std::time::Duration::from_secs(1));
+        if let Some(pid) = gateway_pid() {
+            gateway_lock::acquire(pid, SERVICE_PORT);
+            return Ok(pid);
+        }
+    }
+    Err(format!("服务启动超时（{}秒）", timeout_secs))
+}
+
 /// 获取服务状态（简单版：直接检查端口占用）
+#[guarded]
 #[command]
 pub async fn get_service_status() -> Result<ServiceStatus, String> {
     // 简单直接：检查端口是否被占用
     let pid = check_port_listening(SERVICE_PORT);
     let running = pid.is_some();
-    
+
     Ok(ServiceStatus {
         running,
         pid,
@@ -71,18 +118,20 @@ pub async fn get_service_status() -> Result<ServiceStatus, String> {
         uptime_seconds: None,
         memory_mb: None,
         cpu_percent: None,
+        managed_by: pid.map(gateway_lock::owner),
     })
 }
 
 /// 发送消息给 OpenClaw Agent
+#[guarded]
 #[command]
 pub async fn send_agent_message(message: String) -> Result<String, String> {
     info!("[Agent] 发送消息: {}", message);
-    
+
     // 调用 openclaw agent --message "..." --thinking high
     // --thinking high 是为了获得更好的推理效果，虽然可能更慢
     let args = ["agent", "--message", &message, "--thinking", "high"];
-    
+
     match shell::run_openclaw(&args) {
         Ok(output) => {
             info!("[Agent] 收到回复: {}", output);
@@ -95,18 +144,27 @@ pub async fn send_agent_message(message: String) -> Result<String, String> {
     }
 }
 
-/// 启动服务
+/// 启动服务（磁盘空间或内存不足时拒绝启动，`force` 为 true 时强制启动）
+#[guarded]
 #[command]
-pub async fn start_service() -> Result<String, String> {
+pub async fn start_service(force: Option<bool>) -> Result<String, String> {
     info!("[服务] 启动服务...");
-    
+
     // 检查是否已经运行
     let status = get_service_status().await?;
     if status.running {
         info!("[服务] 服务已在运行中");
         return Err("服务已在运行中".to_string());
     }
-    
+    // 锁文件中的 Gateway 仍在运行（如外部脚本在其他端口启动），不重复启动
+    if let Some(lock) = gateway_lock::clear_stale() {
+        info!("[服务] 锁文件中的 Gateway 仍在运行 (PID {})", lock.pid);
+        return Err(format!(
+            "Gateway 已在运行（PID {}，端口 {}，由 {} 启动）",
+            lock.pid, lock.port, lock.owner
+        ));
+    }
+
     // 检查 openclaw 命令是否存在
     let openclaw_path = shell::get_openclaw_path();
     if openclaw_path.is_none() {
@@ -114,87 +172,149 @@ pub async fn start_service() -> Result<String, String> {
         return Err("找不到 openclaw 命令，请先通过 npm install -g openclaw 安装".to_string());
     }
     info!("[服务] openclaw 路径: {:?}", openclaw_path);
-    
+    // 配置中的废弃键只提示，不阻止启动
+    config_lint::log_warnings();
+    resources::ensure_can_start(force.unwrap_or(false))?;
+
     // 直接后台启动 gateway（不等待 doctor，避免阻塞）
     info!("[服务] 后台启动 gateway...");
     shell::spawn_openclaw_gateway_with_args(&["gateway", "--port", &SERVICE_PORT.to_string()])
         .map_err(|e| format!("启动服务失败: {}", e))?;
-    
-    // 轮询等待端口开始监听（最多 15 秒）
+
+    // 轮询等待端口开始监听（超时时间取自 Manager 设置，默认 15 秒）
+    let timeout_secs = settings::load_settings().timeouts.gateway_start_secs.max(1);
     info!("[服务] 等待端口 {} 开始监听...", SERVICE_PORT);
-    for i in 1..=15 {
+    for i in 1..=timeout_secs {
         std::thread::sleep(std::time::Duration::from_secs(1));
         if let Some(pid) = check_port_listening(SERVICE_PORT) {
             info!("[服务] ✓ 启动成功 ({}秒), PID: {}", i, pid);
-            
+            gateway_lock::acquire(pid, SERVICE_PORT);
+
+            // post_gateway_start 钩子在后台执行，不阻塞启动
+            std::thread::spawn(move || {
+                let _ = hooks::run_hooks(
+                    HookEvent::PostGatewayStart,
+                    &[
+                        ("OPENCLAW_GATEWAY_PORT", SERVICE_PORT.to_string()),
+                        ("OPENCLAW_GATEWAY_PID", pid.to_string()),
+                    ],
+                );
+            });
+        
             // 自动打开浏览器
             let url = format!("http://127.0.0.1:{}", SERVICE_PORT);
             info!("[服务] 自动打开浏览器: {}", url);
             let _ = open::that(url);
-            
+        
             return Ok(format!("服务已启动，PID: {}", pid));
         }
         if i % 3 == 0 {
             debug!("[服务] 等待中... ({}秒)", i);
         }
     }
-    
+
     info!("[服务] 等待超时，端口仍未监听");
-    Err("服务启动超时（15秒），请检查 openclaw 日志".to_string())
+    recovery::record_failure("Gateway 启动超时，端口未监听");
+    Err(format!("服务启动超时（{}秒），请检查 openclaw 日志", timeout_secs))
 }
 
 /// 停止服务
+#[guarded]
 #[command]
 pub async fn stop_service() -> Result<String, String> {
     info!("[服务] 停止服务...");
-    
+
+    monitor::expect_gateway_stop();
     let _ = shell::run_openclaw(&["gateway", "stop"]);
     std::thread::sleep(std::time::Duration::from_millis(500));
-    
+
     let status = get_service_status().await?;
     if !status.running {
+        release_gateway_lock();
         info!("[服务] ✓ 已停止");
         return Ok("服务已停止".to_string());
     }
-    
+
     // 尝试强制停止
     let _ = shell::run_openclaw(&["gateway", "stop", "--force"]);
     std::thread::sleep(std::time::Duration::from_millis(500));
-    
+
     let status = get_service_status().await?;
     if status.running {
         Err(format!("无法停止服务，PID: {:?}", status.pid))
     } else {
+        release_gateway_lock();
         info!("[服务] ✓ 已停止");
         Ok("服务已停止".to_string())
     }
 }
 
-/// 重启服务
+/// 接管在 Manager 之外启动的 Gateway：写入锁文件，之后退出时按崩溃处理（通知、计入更新后失败次数）
+#[guarded]
 #[command]
-pub async fn restart_service() -> Result<String, String> {
+pub async fn adopt_gateway() -> Result<String, String> {
+    let status = get_service_status().await?;
+    let pid = status.pid.ok_or("Gateway 未运行")?;
+    if status.managed_by == Some(GatewayOwner::Manager) {
+        return Ok(format!("Gateway (PID {}) 已由 Manager 管理", pid));
+    }
+    gateway_lock::acquire(pid, SERVICE_PORT);
+    audit::record("adopt_gateway", "gateway", true, json!({ "pid": pid }));
+    info!("[服务] ✓ 已接管外部启动的 Gateway (PID {})", pid);
+    Ok(format!("已接管 Gateway (PID {})", pid))
+}
+
+/// 停止后删除锁文件：端口已释放但锁文件中的进程仍在运行时一并结束
+fn release_gateway_lock() {
+    if let Some(lock) = gateway_lock::clear_stale() {
+        info!("[服务] 结束锁文件中仍在运行的 Gateway (PID {})", lock.pid);
+        shell::terminate_process(lock.pid, false);
+    }
+    gateway_lock::release();
+}
+
+/// 重启服务（磁盘空间或内存不足时拒绝重启，`force` 为 true 时强制重启）
+#[guarded]
+#[command]
+pub async fn restart_service(force: Option<bool>) -> Result<String, String> {
     info!("[服务] 重启服务...");
-    
+    resources::ensure_can_start(force.unwrap_or(false))?;
+
+    monitor::expect_gateway_stop();
     let _ = shell::run_openclaw(&["gateway", "restart"]);
     std::thread::sleep(std::time::Duration::from_secs(2));
-    
+
     let status = get_service_status().await?;
     if status.running {
         info!("[服务] ✓ 重启成功, PID: {:?}", status.pid);
+        if let Some(pid) = status.pid {
+            gateway_lock::acquire(pid, SERVICE_PORT);
+        }
         Ok(format!("服务已重启，PID: {:?}", status.pid))
     } else {
-        // 手动停止再启动
+        // 手动停止再启动（资源已在重启前检查过）
         let _ = stop_service().await;
         std::thread::sleep(std::time::Duration::from_secs(1));
-        start_service().await
+        Ok(start_service(Some(true)).await?)
+    }
+}
+
+/// Gateway 正在运行时重启（修改配置或环境变量后使其生效）
+pub async fn restart_if_running() {
+    if matches!(get_service_status().await, Ok(s) if s.running) {
+        if let Err(e) = restart_service(None).await {
+            error!("[服务] ✗ 重启 Gateway 失败: {}", e);
+        }
     }
 }
 
 /// 获取日志
+#[guarded]
 #[command]
 pub async fn get_logs(lines: Option<u32>) -> Result<Vec<String>, String> {
     let n = lines.unwrap_or(100);
-    
+    capabilities::require(Feature::Logs)?;
+
     match shell::run_openclaw(&["logs", "--lines", &n.to_string()]) {
         Ok(output) => {
             Ok(output.lines().map(|s| s.to_string()).collect())
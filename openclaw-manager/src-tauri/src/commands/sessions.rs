@@ -0,0 +1,259 @@
+use crate::utils::platform;
+use log::info;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use openclaw_macros::guarded;
+use tauri::command;
+
+/// 脱敏规则
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RedactionRule {
+    pub name: String,
+    /// 正则表达式
+    pub pattern: String,
+    /// 替换文本，如 "[EMAIL]"
+    pub replacement: String,
+}
+
+impl RedactionRule {
+    fn new(name: &str, pattern: &str, replacement: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            pattern: pattern.to_string(),
+            replacement: replacement.to_string(),
+        }
+    }
+}
+
+/// 默认脱敏规则：邮箱、常见 API Key / Token、用户目录路径
+pub fn default_redaction_rules() -> Vec<RedactionRule> {
+    vec![
+        RedactionRule::new(
+            "email",
+            r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}",
+            "[EMAIL]",
+        ),
+        RedactionRule::new("api_key", r"\b(sk|pk|rk)-[A-Za-z0-9_-]{16,}", "[API_KEY]"),
+        RedactionRule::new(
+            "bearer",
+            r"(?i)bearer\s+[A-Za-z0-9._~+/=-]{16,}",
+            "Bearer [TOKEN]",
+        ),
+        RedactionRule::new(
+            "telegram_token",
+            r"\b\d{8,10}:[A-Za-z0-9_-]{35}\b",
+            "[BOT_TOKEN]",
+        ),
+        RedactionRule::new(
+            "secret_assignment",
+            r#"(?i)((?:api[_-]?key|token|secret|password)["']?\s*[:=]\s*["']?)[^\s"',]{6,}"#,
+            "${1}[SECRET]",
+        ),
+        RedactionRule::new(
+            "user_path",
+            r"(?i)(/Users/|/home/|[A-Z]:\\Users\\)[^/\\\s]+",
+            "${1}[USER]",
+        ),
+    ]
+}
+
+/// 脱敏结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RedactedSession {
+    pub id: String,
+    /// 脱敏后的会话副本（JSONL）
+    pub path: String,
+    /// 适合粘贴到问题反馈中的 Markdown 文本
+    pub markdown: String,
+    /// 每条规则的替换次数
+    pub replacements: BTreeMap<String, usize>,
+}
+
+/// 编译后的规则集
+struct Redactor {
+    rules: Vec<(RedactionRule, Regex)>,
+    counts: BTreeMap<String, usize>,
+}
+
+impl Redactor {
+    fn new(rules: Vec<RedactionRule>) -> Result<Self, String> {
+        let rules = rules
+            .into_iter()
+            .map(|rule| {
+                Regex::new(&rule.pattern)
+                    .map(|re| (rule.clone(), re))
+                    .map_err(|e| format!("脱敏规则 {} 无效: {}", rule.name, e))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok(Self {
+            rules,
+            counts: BTreeMap::new(),
+        })
+    }
+
+    fn redact_str(&mut self, text: &str) -> String {
+        let mut text = text.to_string();
+        for (rule, re) in &self.rules {
+            let count = re.find_iter(&text).count();
+            if count > 0 {
+                *self.counts.entry(rule.name.clone()).or_default() += count;
+                text = re
+                    .replace_all(&text, rule.replacement.as_str())
+                    .into_owned();
+            }
+        }
+        text
+    }
+
+    /// 递归处理 JSON 中的所有字符串值，保持结构不变
+    fn redact_value(&mut self, value: &mut Value) {
+        match value {
+            Value::String(s) => *s = self.redact_str(s),
+            Value::Array(items) => items.iter_mut().for_each(|v| self.redact_value(v)),
+            Value::Object(map) => map.values_mut().for_each(|v| self.redact_value(v)),
+            _ => {}
+        }
+    }
+}
+
+/// 按默认规则脱敏一段文本（日志、错误信息等）
+pub fn redact_text(text: &str) -> String {
+    match Redactor::new(default_redaction_rules()) {
+        Ok(mut redactor) => redactor.redact_str(text),
+        Err(_) => text.to_string(),
+    }
+}
+
+/// 会话文件：~/.openclaw/agents/<agent>/sessions/<id>.jsonl
+pub fn find_session_file(config_dir: &Path, id: &str) -> Option<PathBuf> {
+    let agents = std::fs::read_dir(config_dir.join("agents")).ok()?;
+    agents
+        .flatten()
+        .map(|agent| agent.path().join("sessions").join(format!("{}.jsonl", id)))
+        .find(|path| path.is_file())
+}
+
+/// 提取消息的角色和文本（非消息事件返回 None）
+fn message_text(event: &Value) -> Option<(String, String)> {
+    let message = event.get("message").unwrap_or(event);
+    let role = message.get("role")?.as_str()?.to_string();
+    let text = match message.get("content")? {
+        Value::String(s) => s.clone(),
+        Value::Array(parts) => parts
+            .iter()
+            .filter_map(|p| p.get("text").and_then(|t| t.as_str()))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => return None,
+    };
+    if text.trim().is_empty() {
+        return None;
+    }
+    Some((role, text))
+}
+
+/// 脱敏会话内容，返回 (JSONL 副本, Markdown, 替换次数)
+fn redact_transcript(
+    content: &str,
+    rules: Vec<RedactionRule>,
+) -> Result<(String, String, BTreeMap<String, usize>), String> {
+    let mut redactor = Redactor::new(rules)?;
+    let mut lines = Vec::new();
+    let mut markdown = Vec::new();
+
+    for line in content.lines().filter(|l| !l.trim().is_empty()) {
+        match serde_json::from_str::<Value>(line) {
+            Ok(mut event) => {
+                redactor.redact_value(&mut event);
+                if let Some((role, text)) = message_text(&event) {
+                    markdown.push(format!("**{}**:\n\n{}\n", role, text));
+                }
+                lines.push(event.to_string());
+            }
+            // 无法解析的行按纯文本脱敏
+            Err(_) => lines.push(redactor.redact_str(line)),
+        }
+    }
+
+    Ok((lines.join("\n"), markdown.join("\n"), redactor.counts))
+}
+
+/// 生成脱敏后的会话副本和可粘贴到问题反馈的 Markdown
+/// `rules` 为空时使用默认规则（邮箱、密钥、用户目录路径）
+#[guarded]
+#[command]
+pub async fn redact_session(
+    id: String,
+    rules: Option<Vec<RedactionRule>>,
+) -> Result<RedactedSession, String> {
+    if id.is_empty() || id.contains(['/', '\\']) || id.contains("..") {
+        return Err(format!("无效的会话 ID: {}", id));
+    }
+    info!("[会话脱敏] 脱敏会话: {}", id);
+
+    let config_dir = platform::get_config_dir();
+    let path = find_session_file(Path::new(&config_dir), &id)
+        .ok_or_else(|| format!("找不到会话: {}", id))?;
+    let content = std::fs::read_to_string(&path).map_err(|e| format!("读取会话失败: {}", e))?;
+
+    let rules = rules
+        .filter(|r| !r.is_empty())
+        .unwrap_or_else(default_redaction_rules);
+    let (jsonl, body, replacements) = redact_transcript(&content, rules)?;
+
+    let export_dir = Path::new(&platform::get_manager_data_dir()).join("exports");
+    std::fs::create_dir_all(&export_dir).map_err(|e| format!("创建导出目录失败: {}", e))?;
+    let export_path = export_dir.join(format!("session-{}-redacted.jsonl", id));
+    std::fs::write(&export_path, &jsonl).map_err(|e| format!("写入脱敏副本失败: {}", e))?;
+
+    let markdown = format!(
+        "### OpenClaw 会话 {}（已脱敏）\n\n{}",
+        id,
+        if body.is_empty() {
+            "_（无消息内容）_"
+        } else {
+            &body
+        }
+    );
+    let total: usize = replacements.values().sum();
+    info!("[会话脱敏] ✓ 完成，共替换 {} 处", total);
+
+    Ok(RedactedSession {
+        id,
+        path: export_path.to_string_lossy().to_string(),
+        markdown,
+        replacements,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_rules_strip_emails_keys_and_user_paths() {
+        let content = concat!(
+            r#"{"type":"message","message":{"role":"user","content":[{"type":"text","text":"我的邮箱 alice@example.com，key 是 sk-abcdefghijklmnopqrstuvwx"}]}}"#,
+            "\n",
+            r#"{"type":"tool","output":"读取 /Users/alice/project/.env: api_key=supersecret123"}"#,
+        );
+        let (jsonl, markdown, counts) =
+            redact_transcript(content, default_redaction_rules()).unwrap();
+
+        assert!(!jsonl.contains("alice@example.com"));
+        assert!(!jsonl.contains("sk-abcdefghijklmnopqrstuvwx"));
+        assert!(!jsonl.contains("supersecret123"));
+        assert!(jsonl.contains("/Users/[USER]/project"));
+        assert!(markdown.contains("**user**"));
+        assert!(markdown.contains("[EMAIL]"));
+        assert_eq!(counts.get("email"), Some(&1));
+
+        // 每行仍然是合法 JSON
+        for line in jsonl.lines() {
+            assert!(serde_json::from_str::<Value>(line).is_ok());
+        }
+    }
+}
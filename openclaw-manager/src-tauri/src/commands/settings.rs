@@ -0,0 +1,456 @@
+use crate::commands::backups::BackupSettings;
+use crate::commands::budgets::ProviderBudget;
+use crate::commands::cache_proxy::CacheProxySettings;
+use crate::commands::hooks::HookSettings;
+use crate::commands::knowledge::KnowledgeSource;
+use crate::commands::maintenance::MaintenanceSettings;
+use crate::commands::network::MirrorSettings;
+use crate::commands::notifications::NotificationSettings;
+use crate::commands::oauth::OAuthConnection;
+use crate::commands::policy::{self, PolicySettings};
+use crate::commands::resources::ResourceSettings;
+use crate::commands::skills::SkillSettings;
+use crate::commands::sync::SyncSettings;
+use crate::commands::telemetry::TelemetrySettings;
+use crate::commands::templates::MessageTemplate;
+use crate::commands::ui_preferences::UiPreferences;
+use crate::commands::winpkg::WindowsPackageManager;
+use crate::commands::workspace::WorkspaceSettings;
+use crate::utils::{file, platform, time};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use openclaw_macros::guarded;
+use tauri::command;
+
+/// 当前 settings.json 结构版本（迁移见 migrations.rs）
+pub const SETTINGS_VERSION: u32 = 1;
+
+/// 默认 npm 镜像
+pub const DEFAULT_NPM_REGISTRY: &str = "https://registry.npmmirror.com";
+/// 默认 GitHub 加速代理
+pub const DEFAULT_GITHUB_PROXY: &str = "https://ghproxy.com/";
+
+/// Manager 自身设置（保存在 Manager 数据目录的 settings.json）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ManagerSettings {
+    /// 结构版本号，用于迁移
+    pub version: u32,
+    /// 界面语言
+    pub locale: String,
+    /// 时区（IANA 名称，如 Asia/Shanghai；为空时使用系统时区），用于维护窗口、预算周期和按天统计
+    pub timezone: Option<String>,
+    /// npm 镜像地址
+    pub npm_registry: String,
+    /// GitHub 加速代理前缀（为空则直连）
+    pub github_proxy: Option<String>,
+    /// npm 源 / GitHub 加速代理自动选择
+    pub mirror: MirrorSettings,
+    /// HTTP/HTTPS 代理
+    pub proxy: Option<String>,
+    /// 导入的企业根证书（HTTPS 解密代理环境下使用）
+    pub ca_certificate: Option<String>,
+    /// 更新通道（npm dist-tag，如 latest / beta）
+    pub update_channel: String,
+    /// 超时设置
+    pub timeouts: TimeoutSettings,
+    /// 下载限速（KB/s，0 表示不限速），避免后台下载占满带宽
+    pub download_limit_kbps: u64,
+    /// 开机自启（Manager 启动后等待网络、DNS、钥匙串就绪再启动 Gateway）
+    pub autostart: bool,
+    /// 通知设置
+    pub notifications: NotificationSettings,
+    /// 安全策略
+    pub policy: PolicySettings,
+    /// 生命周期钩子
+    pub hooks: HookSettings,
+    /// 自动更新和维护时间窗口
+    pub maintenance: MaintenanceSettings,
+    /// 各 AI Provider 的费用预算
+    pub budgets: Vec<ProviderBudget>,
+    /// 本地缓存代理（测试时缓存相同的 Provider 请求）
+    pub cache_proxy: CacheProxySettings,
+    /// 只作用于特定子进程的环境变量
+    pub env_overrides: EnvOverrides,
+    /// 隐私模式（关闭遥测、只用本地模型、不检查更新）
+    pub privacy_mode: bool,
+    /// 匿名使用统计（默认关闭）
+    pub telemetry: TelemetrySettings,
+    /// 崩溃报告提交地址（为空时只保存在本地）
+    pub crash_report_endpoint: String,
+    /// 技能仓库
+    pub skills: SkillSettings,
+    /// Agent 工作区容量上限和可疑文件告警
+    pub workspace: WorkspaceSettings,
+    /// Windows 首选包管理器（winget / choco / scoop，为空时自动选择）
+    pub windows_package_manager: Option<WindowsPackageManager>,
+    /// 各渠道的通知 / 自动回复消息模板（多语言）
+    pub templates: Vec<MessageTemplate>,
+    /// 已授权的 OAuth 连接（令牌保存在系统钥匙串中）
+    pub oauth: Vec<OAuthConnection>,
+    /// 各 Agent 的知识库来源
+    pub knowledge: Vec<KnowledgeSource>,
+    /// 界面偏好（主题、字体缩放、减少动画）
+    pub ui: UiPreferences,
+    /// 启动 Gateway 所需的最少磁盘空间和可用内存
+    pub resources: ResourceSettings,
+    /// 多台电脑之间的设置同步（默认关闭）
+    pub sync: SyncSettings,
+    /// 远程备份（WebDAV / S3）
+    pub backup: BackupSettings,
+}
+
+impl Default for ManagerSettings {
+    fn default() -> Self {
+        Self {
+            version: SETTINGS_VERSION,
+            locale: "zh-CN".to_string(),
+            timezone: None,
+            npm_registry: DEFAULT_NPM_REGISTRY.to_string(),
+            github_proxy: Some(DEFAULT_GITHUB_PROXY.to_string()),
+            mirror: MirrorSettings::default(),
+            proxy: None,
+            ca_certificate: None,
+            update_channel: "latest".to_string(),
+            timeouts: TimeoutSettings::default(),
+            download_limit_kbps: 0,
+            autostart: false,
+            notifications: NotificationSettings::default(),
+            policy: PolicySettings::default(),
+            hooks: HookSettings::default(),
+            maintenance: MaintenanceSettings::default(),
+            budgets: Vec::new(),
+            cache_proxy: CacheProxySettings::default(),
+            env_overrides: EnvOverrides::default(),
+            privacy_mode: false,
+            telemetry: TelemetrySettings::default(),
+            crash_report_endpoint: String::new(),
+            skills: SkillSettings::default(),
+            workspace: WorkspaceSettings::default(),
+            windows_package_manager: None,
+            templates: Vec::new(),
+            oauth: Vec::new(),
+            knowledge: Vec::new(),
+            ui: UiPreferences::default(),
+            resources: ResourceSettings::default(),
+            sync: SyncSettings::default(),
+            backup: BackupSettings::default(),
+        }
+    }
+}
+
+impl ManagerSettings {
+    /// npm 安装的 OpenClaw 包（openclaw@更新通道）
+    pub fn openclaw_package(&self) -> String {
+        let channel = self.update_channel.trim();
+        if channel.is_empty() {
+            "openclaw@latest".to_string()
+        } else {
+            format!("openclaw@{}", channel)
+        }
+    }
+
+    /// npm 命令通用参数（镜像、代理）
+    pub fn npm_args(&self) -> String {
+        let registry = if self.npm_registry.trim().is_empty() {
+            DEFAULT_NPM_REGISTRY
+        } else {
+            self.npm_registry.trim()
+        };
+        let mut args = format!("--registry={}", registry);
+        if let Some(proxy) = self.proxy.as_deref().filter(|p| !p.is_empty()) {
+            args.push_str(&format!(" --proxy={} --https-proxy={}", proxy, proxy));
+        }
+        // npm 不支持按速率限速，限速时减少并发连接
+        if self.download_limit_kbps > 0 {
+            args.push_str(" --maxsockets=1");
+        }
+        args
+    }
+
+    /// 通过 GitHub 加速代理访问的地址（未配置代理时返回 None）
+    pub fn github_proxied_url(&self, url: &str) -> Option<String> {
+        self.github_proxy
+            .as_deref()
+            .map(|p| p.trim())
+            .filter(|p| !p.is_empty())
+            .map(|p| format!("{}/{}", p.trim_end_matches('/'), url))
+    }
+}
+
+/// 超时设置（秒）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TimeoutSettings {
+    /// HTTP 请求超时
+    pub http_secs: u64,
+    /// 等待 Gateway 启动超时
+    pub gateway_start_secs: u64,
+    /// 自动启动时每个就绪条件（网络、DNS、钥匙串）的最长等待时间
+    pub startup_gate_secs: u64,
+    /// 下载安装包（如 Node.js pkg）超时
+    pub download_secs: u64,
+    /// 更新后观察 Gateway 健康状态的时间，期间异常则自动回滚（0 表示不检查）
+    pub update_health_secs: u64,
+    /// Manager 退出时等待辅助进程和进行中操作结束的宽限期，超时后强制结束
+    pub shutdown_grace_secs: u64,
+}
+
+impl Default for TimeoutSettings {
+    fn default() -> Self {
+        Self {
+            http_secs: 15,
+            gateway_start_secs: 15,
+            startup_gate_secs: 60,
+            download_secs: 600,
+            update_health_secs: 60,
+            shutdown_grace_secs: 5,
+        }
+    }
+}
+
+/// 按操作区分的环境变量覆盖（值为空表示对该子进程移除此变量），不修改 Manager 自身的环境
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EnvOverrides {
+    /// npm 安装 / 查询（如 HTTPS_PROXY、npm_config_strict_ssl）
+    pub npm: BTreeMap<String, String>,
+    /// Gateway 进程（如 NODE_OPTIONS=--max-old-space-size=4096）
+    pub gateway: BTreeMap<String, String>,
+}
+
+/// settings.json 路径
+pub fn settings_path(data_dir: &Path) -> PathBuf {
+    data_dir.join("settings.json")
+}
+
+/// 串行化 settings.json 的读-改-写（后台任务和命令可能同时修改设置）
+static SETTINGS_LOCK: Mutex<()> = Mutex::new(());
+
+/// 读取设置（从 Manager 数据目录）
+pub fn load_settings() -> ManagerSettings {
+    load_settings_from(Path::new(&platform::get_manager_data_dir()))
+}
+
+/// 读取设置，settings.json 无法读取或解析时返回错误（不回退为默认设置）
+pub fn try_load_settings() -> Result<ManagerSettings, String> {
+    read_settings_from(Path::new(&platform::get_manager_data_dir()))
+}
+
+/// 在锁内读取、修改并保存设置，返回 `apply` 的结果；`apply` 返回错误时不保存
+/// settings.json 无法解析时拒绝写入，避免用默认设置覆盖用户的文件
+pub fn update<R>(
+    apply: impl FnOnce(&mut ManagerSettings) -> Result<R, String>,
+) -> Result<R, String> {
+    update_in(Path::new(&platform::get_manager_data_dir()), apply)
+}
+
+fn update_in<R>(
+    data_dir: &Path,
+    apply: impl FnOnce(&mut ManagerSettings) -> Result<R, String>,
+) -> Result<R, String> {
+    let _guard = SETTINGS_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut settings = read_settings_from(data_dir)?;
+    let result = apply(&mut settings)?;
+    save_settings_to(data_dir, &settings)?;
+    time::set_user_timezone(settings.timezone.as_deref());
+    Ok(result)
+}
+
+/// 读取设置，settings.json 不存在时为默认设置，无法读取或解析时返回错误
+fn read_settings_from(data_dir: &Path) -> Result<ManagerSettings, String> {
+    let path = settings_path(data_dir);
+    let content = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(ManagerSettings::default()),
+        Err(e) => return Err(format!("读取 {} 失败: {}", path.display(), e)),
+    };
+    serde_json::from_str(&content).map_err(|e| {
+        format!(
+            "{} 格式不正确（{}），为避免覆盖已停止保存设置，请修复或删除该文件",
+            path.display(),
+            e
+        )
+    })
+}
+
+pub fn load_settings_from(data_dir: &Path) -> ManagerSettings {
+    read_settings_from(data_dir).unwrap_or_else(|e| {
+        warn!("[设置] {}，使用默认设置", e);
+        ManagerSettings::default()
+    })
+}
+
+fn save_settings_to(data_dir: &Path, settings: &ManagerSettings) -> Result<(), String> {
+    let content =
+        serde_json::to_string_pretty(settings).map_err(|e| format!("序列化设置失败: {}", e))?;
+    file::write_file(&settings_path(data_dir).to_string_lossy(), &content)
+        .map_err(|e| format!("写入设置失败: {}", e))
+}
+
+/// JSON Merge Patch (RFC 7386)：null 表示删除（恢复默认值）
+fn merge_patch(target: &mut Value, patch: &Value) {
+    match patch {
+        Value::Object(patch_obj) => {
+            if !target.is_object() {
+                *target = json!({});
+            }
+            if let Some(target_obj) = target.as_object_mut() {
+                for (key, value) in patch_obj {
+                    if value.is_null() {
+                        target_obj.remove(key);
+                    } else {
+                        merge_patch(target_obj.entry(key.clone()).or_insert(Value::Null), value);
+                    }
+                }
+            }
+        }
+        _ => *target = patch.clone(),
+    }
+}
+
+/// 获取 Manager 设置
+#[guarded]
+#[command]
+pub async fn get_settings() -> Result<ManagerSettings, String> {
+    info!("[设置] 读取 Manager 设置...");
+    Ok(load_settings())
+}
+
+/// 通过 update_settings 修改时需要确认令牌的设置（JSON Pointer）：
+/// 会改变网络流向（代理、证书、镜像、上报地址、远程存储、通知目标）
+/// 或在子进程中执行代码、注入环境变量（钩子、Gateway 环境变量）
+const SENSITIVE_SETTINGS: &[&str] = &[
+    "/hooks",
+    "/env_overrides",
+    "/proxy",
+    "/ca_certificate",
+    "/npm_registry",
+    "/github_proxy",
+    "/mirror",
+    "/crash_report_endpoint",
+    "/telemetry/endpoint",
+    "/skills/registry_url",
+    "/notifications/targets",
+    "/oauth",
+    "/sync",
+    "/backup",
+];
+
+/// 补丁中第一个需要确认的设置
+fn sensitive_setting(patch: &Value) -> Option<&'static str> {
+    SENSITIVE_SETTINGS
+        .iter()
+        .copied()
+        .find(|pointer| patch.pointer(pointer).is_some())
+}
+
+/// 更新 Manager 设置（部分更新，JSON Merge Patch 语义）
+/// 修改安全策略本身，或代理、证书、钩子等安全相关的设置属于危险操作，需要确认令牌
+#[guarded]
+#[command]
+pub async fn update_settings(
+    patch: Value,
+    confirm_token: Option<String>,
+) -> Result<ManagerSettings, String> {
+    info!("[设置] 更新 Manager 设置...");
+    if !patch.is_object() {
+        return Err("设置补丁必须是 JSON 对象".to_string());
+    }
+    if patch.get("version").is_some() {
+        return Err("不能修改设置版本号".to_string());
+    }
+    if patch.get("policy").is_some() {
+        policy::require_confirmation("update_policy", confirm_token.as_deref())?;
+    } else if let Some(pointer) = sensitive_setting(&patch) {
+        info!("[设置] 修改 {} 需要确认", pointer);
+        policy::require_confirmation("update_settings", confirm_token.as_deref())?;
+    }
+
+    let updated = update(|current| {
+        let mut value =
+            serde_json::to_value(&*current).map_err(|e| format!("序列化设置失败: {}", e))?;
+        merge_patch(&mut value, &patch);
+
+        let mut updated: ManagerSettings =
+            serde_json::from_value(value).map_err(|e| format!("设置格式不正确: {}", e))?;
+        // 手动指定镜像视为覆盖自动选择
+        let mirror_overridden =
+            patch.get("npm_registry").is_some() || patch.get("github_proxy").is_some();
+        if mirror_overridden && patch.pointer("/mirror/auto").is_none() && updated.mirror.auto {
+            info!("[设置] 已手动指定镜像，关闭镜像自动选择");
+            updated.mirror.auto = false;
+        }
+        if let Some(timezone) = updated.timezone.take() {
+            updated.timezone = time::parse_timezone(&timezone)?.map(|tz| tz.name().to_string());
+        }
+        updated.notifications.secure_secrets()?;
+        *current = updated.clone();
+        Ok(updated)
+    })?;
+    info!("[设置] ✓ 设置已保存");
+    Ok(updated)
+}
+
+/// 可选的时区（IANA 名称）
+#[guarded]
+#[command]
+pub async fn list_timezones() -> Result<Vec<String>, String> {
+    Ok(chrono_tz::TZ_VARIANTS
+        .iter()
+        .map(|tz| tz.name().to_string())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merge_patch_replaces_and_removes_keys() {
+        let mut target = json!({"a": 1, "b": {"c": 2, "d": 3}});
+        merge_patch(&mut target, &json!({"a": 5, "b": {"d": null}}));
+        assert_eq!(target, json!({"a": 5, "b": {"c": 2}}));
+    }
+
+    #[test]
+    fn security_relevant_patches_need_confirmation() {
+        assert_eq!(
+            sensitive_setting(&json!({"env_overrides": {"gateway": {"NODE_OPTIONS": "-r x"}}})),
+            Some("/env_overrides")
+        );
+        assert_eq!(
+            sensitive_setting(&json!({"telemetry": {"endpoint": "https://x"}})),
+            Some("/telemetry/endpoint")
+        );
+        assert_eq!(sensitive_setting(&json!({"proxy": null})), Some("/proxy"));
+        assert_eq!(
+            sensitive_setting(&json!({"locale": "en", "telemetry": {"enabled": false}})),
+            None
+        );
+    }
+
+    #[test]
+    fn update_refuses_to_overwrite_unparseable_file() {
+        let dir = std::env::temp_dir().join(format!("openclaw-settings-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = settings_path(&dir);
+
+        update_in(&dir, |s| {
+            s.privacy_mode = true;
+            Ok(())
+        })
+        .unwrap();
+        assert!(load_settings_from(&dir).privacy_mode);
+
+        std::fs::write(&path, "{ not json").unwrap();
+        assert!(update_in(&dir, |_| Ok(())).is_err());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "{ not json");
+        assert!(!load_settings_from(&dir).privacy_mode);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
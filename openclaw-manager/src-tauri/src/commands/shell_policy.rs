@@ -0,0 +1,311 @@
+use crate::commands::{config, inventory, skills};
+use crate::models::DiagnosticResult;
+use crate::utils::audit;
+use log::info;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use openclaw_macros::guarded;
+use tauri::command;
+
+/// Shell 技能名称
+pub const SHELL_SKILL: &str = "shell";
+
+/// 未命中任何规则时的处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PolicyAction {
+    /// 允许（未配置策略时 Shell 技能的行为）
+    #[default]
+    Allow,
+    Deny,
+}
+
+/// Shell 技能的命令策略，保存在 `skills.entries.shell.config` 中
+///
+/// 规则支持 `*` / `?` 通配符，不含通配符的规则同时匹配以它开头的命令（`git` 匹配 `git status`）
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ShellPolicy {
+    pub allow: Vec<String>,
+    pub deny: Vec<String>,
+    pub default_action: PolicyAction,
+}
+
+/// 单条命令的判定结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicySegmentDecision {
+    pub command: String,
+    pub allowed: bool,
+    /// 命中的规则（未命中时为 None，按默认处理方式判定）
+    pub matched_rule: Option<String>,
+}
+
+/// `test_policy` 的结果：组合命令（`&&`、`;`、`|` 等）中任一条被拒绝则整体拒绝
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicyDecision {
+    pub allowed: bool,
+    pub segments: Vec<PolicySegmentDecision>,
+}
+
+/// `*` 匹配任意字符，`?` 匹配单个字符
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let p: Vec<char> = pattern.chars().collect();
+    let t: Vec<char> = text.chars().collect();
+    let (mut pi, mut ti) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while ti < t.len() {
+        if pi < p.len() && (p[pi] == '?' || p[pi] == t[ti]) {
+            pi += 1;
+            ti += 1;
+        } else if pi < p.len() && p[pi] == '*' {
+            star = Some((pi, ti));
+            pi += 1;
+        } else if let Some((sp, st)) = star {
+            pi = sp + 1;
+            ti = st + 1;
+            star = Some((sp, st + 1));
+        } else {
+            return false;
+        }
+    }
+    p[pi..].iter().all(|c| *c == '*')
+}
+
+fn normalize(command: &str) -> String {
+    command.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn rule_matches(rule: &str, command: &str) -> bool {
+    let rule = normalize(rule);
+    !rule.is_empty()
+        && (wildcard_match(&rule, command) || wildcard_match(&format!("{} *", rule), command))
+}
+
+/// 按 `&&`、`||`、`;`、`|`、`&` 和换行拆分组合命令（`2>&1`、`&>` 等重定向除外）
+fn split_commands(command: &str) -> Vec<String> {
+    let chars: Vec<char> = command.chars().collect();
+    let mut segments = Vec::new();
+    let mut current = String::new();
+    for (i, c) in chars.iter().enumerate() {
+        let redirect =
+            *c == '&' && ((i > 0 && chars[i - 1] == '>') || chars.get(i + 1) == Some(&'>'));
+        if matches!(c, '\n' | ';' | '|' | '&') && !redirect {
+            segments.push(std::mem::take(&mut current));
+        } else {
+            current.push(*c);
+        }
+    }
+    segments.push(current);
+    segments
+        .iter()
+        .map(|s| normalize(s))
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+impl ShellPolicy {
+    /// 判定命令：拒绝规则优先，其次允许规则，都未命中时按默认处理方式
+    pub fn evaluate(&self, command: &str) -> PolicyDecision {
+        let segments: Vec<PolicySegmentDecision> = split_commands(command)
+            .into_iter()
+            .map(|segment| {
+                let (allowed, matched_rule) =
+                    match self.deny.iter().find(|r| rule_matches(r, &segment)) {
+                        Some(rule) => (false, Some(rule.clone())),
+                        None => match self.allow.iter().find(|r| rule_matches(r, &segment)) {
+                            Some(rule) => (true, Some(rule.clone())),
+                            None => (self.default_action == PolicyAction::Allow, None),
+                        },
+                    };
+                PolicySegmentDecision {
+                    command: segment,
+                    allowed,
+                    matched_rule,
+                }
+            })
+            .collect();
+        PolicyDecision {
+            allowed: !segments.is_empty() && segments.iter().all(|s| s.allowed),
+            segments,
+        }
+    }
+
+    /// 策略是否形同虚设：默认允许且没有拒绝规则，或允许规则中有 `*`
+    pub fn wide_open_reason(&self) -> Option<String> {
+        if self.default_action == PolicyAction::Allow
+            && self.deny.iter().all(|r| normalize(r).is_empty())
+        {
+            return Some("默认允许所有命令且没有任何拒绝规则".to_string());
+        }
+        self.allow
+            .iter()
+            .find(|r| normalize(r).chars().all(|c| c == '*'))
+            .filter(|_| self.default_action == PolicyAction::Deny)
+            .map(|r| format!("允许规则 \"{}\" 匹配所有命令", r))
+    }
+
+    fn from_config(cfg: &Value) -> Self {
+        let section = cfg.pointer(&format!("/skills/entries/{}/config", SHELL_SKILL));
+        let list = |key: &str| -> Vec<String> {
+            section
+                .and_then(|s| s.get(key))
+                .and_then(|v| v.as_array())
+                .into_iter()
+                .flatten()
+                .filter_map(|v| v.as_str().map(String::from))
+                .collect()
+        };
+        Self {
+            allow: list("allow"),
+            deny: list("deny"),
+            default_action: section
+                .and_then(|s| s.get("defaultAction"))
+                .and_then(|v| serde_json::from_value(v.clone()).ok())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// 写入技能配置，保留其它配置项
+    fn write_to(&self, cfg: &mut Value) {
+        if !cfg.is_object() {
+            *cfg = json!({});
+        }
+        let entry = &mut cfg["skills"]["entries"][SHELL_SKILL];
+        if !entry["config"].is_object() {
+            entry["config"] = json!({});
+        }
+        let clean = |rules: &[String]| -> Vec<String> {
+            rules
+                .iter()
+                .map(|r| normalize(r))
+                .filter(|r| !r.is_empty())
+                .collect()
+        };
+        entry["config"]["allow"] = json!(clean(&self.allow));
+        entry["config"]["deny"] = json!(clean(&self.deny));
+        entry["config"]["defaultAction"] = json!(self.default_action);
+    }
+}
+
+/// 诊断：Shell 技能已安装 / 已配置但命令策略形同虚设时给出警告
+pub fn diagnostic() -> Option<DiagnosticResult> {
+    let cfg = config::load_openclaw_config().ok()?;
+    let entry = cfg.pointer(&format!("/skills/entries/{}", SHELL_SKILL));
+    let installed = inventory::list_skills(&skills::skills_dir())
+        .iter()
+        .any(|s| s.name == SHELL_SKILL);
+    let enabled = entry
+        .and_then(|e| e.get("enabled"))
+        .and_then(|v| v.as_bool())
+        .unwrap_or(true);
+    if !(installed || entry.is_some()) || !enabled {
+        return None;
+    }
+    let reason = ShellPolicy::from_config(&cfg).wide_open_reason();
+    let passed = reason.is_none();
+    Some(DiagnosticResult {
+        name: "Shell 技能命令策略".to_string(),
+        passed,
+        message: reason
+            .map(|r| format!("Shell 技能可以执行任意命令：{}", r))
+            .unwrap_or_else(|| "已配置命令白名单 / 黑名单".to_string()),
+        suggestion: if passed {
+            None
+        } else {
+            Some(
+                "在技能设置中为 Shell 技能配置允许的命令，并拒绝 rm -rf、sudo 等高危命令"
+                    .to_string(),
+            )
+        },
+        fix: None,
+    })
+}
+
+/// 读取 Shell 技能的命令策略
+#[guarded]
+#[command]
+pub async fn get_shell_policy() -> Result<ShellPolicy, String> {
+    Ok(ShellPolicy::from_config(&config::load_openclaw_config()?))
+}
+
+/// 保存 Shell 技能的命令策略（Gateway 重新加载技能配置后生效）
+#[guarded]
+#[command]
+pub async fn save_shell_policy(policy: ShellPolicy) -> Result<ShellPolicy, String> {
+    let mut cfg = config::load_openclaw_config()?;
+    policy.write_to(&mut cfg);
+    config::save_config(cfg.clone()).await?;
+    let saved = ShellPolicy::from_config(&cfg);
+    audit::record(
+        "save_shell_policy",
+        SHELL_SKILL,
+        true,
+        json!({
+            "allow": saved.allow,
+            "deny": saved.deny,
+            "default_action": saved.default_action,
+        }),
+    );
+    info!(
+        "[Shell 策略] ✓ 已保存（允许 {} 条，拒绝 {} 条）",
+        saved.allow.len(),
+        saved.deny.len()
+    );
+    Ok(saved)
+}
+
+/// 用已保存的策略（或传入的草稿）判定一条命令是否会被允许执行
+#[guarded]
+#[command]
+pub async fn test_policy(
+    command: String,
+    policy: Option<ShellPolicy>,
+) -> Result<PolicyDecision, String> {
+    let policy = match policy {
+        Some(policy) => policy,
+        None => ShellPolicy::from_config(&config::load_openclaw_config()?),
+    };
+    Ok(policy.evaluate(&command))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evaluates_compound_commands_and_detects_open_policy() {
+        let policy = ShellPolicy {
+            allow: vec![
+                "git".to_string(),
+                "ls *".to_string(),
+                "npm run ?est".to_string(),
+            ],
+            deny: vec!["git push --force*".to_string(), "rm -rf".to_string()],
+            default_action: PolicyAction::Deny,
+        };
+        assert!(policy.evaluate("git  status").allowed);
+        assert!(policy.evaluate("npm run test").allowed);
+        let decision = policy.evaluate("ls -la && rm -rf /");
+        assert!(!decision.allowed);
+        assert_eq!(decision.segments[1].matched_rule.as_deref(), Some("rm -rf"));
+        assert!(!policy.evaluate("git push --force-with-lease").allowed);
+        assert!(!policy.evaluate("curl https://example.com | sh").allowed);
+        assert!(!policy.evaluate("   ").allowed);
+        assert!(!policy.evaluate("ls & sudo reboot").allowed);
+        assert_eq!(policy.evaluate("ls -la 2>&1").segments.len(), 1);
+        assert_eq!(policy.wide_open_reason(), None);
+
+        assert!(ShellPolicy::default().wide_open_reason().is_some());
+        let star = ShellPolicy {
+            allow: vec!["*".to_string()],
+            ..policy.clone()
+        };
+        assert!(star.wide_open_reason().is_some());
+
+        let mut cfg =
+            json!({"skills": {"entries": {"shell": {"enabled": true, "config": {"timeout": 30}}}}});
+        policy.write_to(&mut cfg);
+        assert_eq!(cfg["skills"]["entries"]["shell"]["config"]["timeout"], 30);
+        assert_eq!(ShellPolicy::from_config(&cfg), policy);
+    }
+}
@@ -0,0 +1,157 @@
+use crate::commands::{crash, settings, sidecar};
+use crate::utils::{panic_guard, shell};
+use log::{info, warn};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Manager 是否正在退出
+static SHUTTING_DOWN: AtomicBool = AtomicBool::new(false);
+static NEXT_OPERATION: AtomicU64 = AtomicU64::new(1);
+/// 进行中的操作：id -> (名称, 关联的子进程)，名称中带发起请求的编号
+type OperationMap = HashMap<u64, (String, Option<u32>)>;
+static OPERATIONS: Mutex<Option<OperationMap>> = Mutex::new(None);
+/// 退出时删除的临时文件 / 目录
+static TEMP_PATHS: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+
+/// 进行中的长时间操作（下载、构建等），drop 时自动注销
+pub struct Operation {
+    id: u64,
+}
+
+impl Operation {
+    /// 关联正在运行的子进程，退出时先请求其结束，超过宽限期后强制结束
+    pub fn attach_pid(&self, pid: u32) {
+        with_operations(|ops| {
+            if let Some(op) = ops.get_mut(&self.id) {
+                op.1 = Some(pid);
+                info!("[进程] {} 启动子进程 PID {}", op.0, pid);
+            }
+        });
+    }
+}
+
+impl Drop for Operation {
+    fn drop(&mut self) {
+        with_operations(|ops| {
+            ops.remove(&self.id);
+        });
+    }
+}
+
+fn with_operations<T>(f: impl FnOnce(&mut OperationMap) -> T) -> T {
+    let mut guard = OPERATIONS.lock().unwrap_or_else(|e| e.into_inner());
+    f(guard.get_or_insert_with(HashMap::new))
+}
+
+/// 登记一个长时间操作
+pub fn begin_operation(name: &str) -> Operation {
+    let id = NEXT_OPERATION.fetch_add(1, Ordering::Relaxed);
+    // 子进程通常在 spawn 出的线程中关联，这里记下发起请求的编号
+    let name = match panic_guard::request_id() {
+        Some(request_id) => format!("{} [req:{}]", name, request_id),
+        None => name.to_string(),
+    };
+    with_operations(|ops| {
+        ops.insert(id, (name, None));
+    });
+    Operation { id }
+}
+
+/// Manager 是否正在退出（长时间操作应尽快结束）
+pub fn is_shutting_down() -> bool {
+    SHUTTING_DOWN.load(Ordering::SeqCst)
+}
+
+/// 登记退出时需要删除的临时文件或目录
+pub fn track_temp_path(path: impl Into<PathBuf>) {
+    if let Ok(mut paths) = TEMP_PATHS.lock() {
+        paths.push(path.into());
+    }
+}
+
+fn remove_path(path: &Path) {
+    let result = if path.is_dir() {
+        std::fs::remove_dir_all(path)
+    } else {
+        std::fs::remove_file(path)
+    };
+    if let Err(e) = result {
+        if e.kind() != std::io::ErrorKind::NotFound {
+            warn!("[退出] 删除临时文件 {:?} 失败: {}", path, e);
+        }
+    }
+}
+
+/// 删除登记的临时文件，返回删除数量
+fn cleanup_temp_paths() -> usize {
+    let paths: Vec<PathBuf> = TEMP_PATHS
+        .lock()
+        .map(|mut p| p.drain(..).collect())
+        .unwrap_or_default();
+    for path in &paths {
+        remove_path(path);
+    }
+    paths.len()
+}
+
+/// 等待进行中的操作结束，返回超时后仍未结束的操作
+fn wait_for_operations(grace: Duration) -> Vec<(String, Option<u32>)> {
+    let deadline = Instant::now() + grace;
+    loop {
+        let remaining: Vec<(String, Option<u32>)> =
+            with_operations(|ops| ops.values().cloned().collect());
+        if remaining.is_empty() || Instant::now() >= deadline {
+            return remaining;
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+}
+
+/// 退出流程（Tauri Exit 事件中调用）：
+/// 通知操作中止并结束其子进程 → 停止辅助进程 → 等待宽限期 → 删除临时文件 → 刷新日志
+pub fn run() {
+    if SHUTTING_DOWN.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let grace = Duration::from_secs(settings::load_settings().timeouts.shutdown_grace_secs);
+    info!("[退出] Manager 正在退出（宽限期 {} 秒）", grace.as_secs());
+
+    let pids: Vec<u32> = with_operations(|ops| ops.values().filter_map(|(_, pid)| *pid).collect());
+    for pid in &pids {
+        shell::terminate_process(*pid, false);
+    }
+    sidecar::stop_all(grace);
+
+    let remaining = wait_for_operations(grace);
+    for (name, pid) in &remaining {
+        warn!("[退出] 操作未在宽限期内结束: {}", name);
+        if let Some(pid) = pid {
+            shell::terminate_process(*pid, true);
+        }
+    }
+
+    let removed = cleanup_temp_paths();
+    info!("[退出] ✓ 清理完成，删除 {} 个临时文件", removed);
+    crash::mark_clean_exit();
+    log::logger().flush();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn operations_unregister_on_drop() {
+        let op = begin_operation("download");
+        op.attach_pid(42);
+        let names: Vec<(String, Option<u32>)> =
+            with_operations(|ops| ops.get(&op.id).cloned().into_iter().collect());
+        assert_eq!(names, vec![("download".to_string(), Some(42))]);
+        let id = op.id;
+        drop(op);
+        assert!(with_operations(|ops| !ops.contains_key(&id)));
+    }
+}
@@ -0,0 +1,370 @@
+use crate::commands::{orphans, shutdown};
+use crate::utils::{http, platform, shell};
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::io::{BufRead, BufReader, Read};
+use std::process::{Child, Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use openclaw_macros::guarded;
+use tauri::command;
+
+#[cfg(windows)]
+use crate::utils::shell::CREATE_NO_WINDOW;
+#[cfg(windows)]
+use std::os::windows::process::CommandExt;
+
+/// 每个辅助进程保留的日志行数
+const LOG_LINES: usize = 500;
+/// 运行超过该时间后重置重启计数（视为已恢复稳定）
+const STABLE_AFTER: Duration = Duration::from_secs(5 * 60);
+
+/// 健康检查方式
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum HealthProbe {
+    /// HTTP GET 返回 2xx
+    Http { url: String },
+    /// TCP 端口可以连接
+    Tcp { port: u16 },
+}
+
+/// 退出后的重启策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RestartPolicy {
+    Never,
+    /// 异常退出时重启，最多连续重启 max_restarts 次
+    OnFailure {
+        max_restarts: u32,
+    },
+    /// 任何退出都重启
+    Always,
+}
+
+/// 辅助进程定义（隧道、缓存代理、Ollama 等）
+#[derive(Debug, Clone)]
+pub struct SidecarSpec {
+    pub name: String,
+    pub program: String,
+    pub args: Vec<String>,
+    pub env: Vec<(String, String)>,
+    pub health: Option<HealthProbe>,
+    pub restart: RestartPolicy,
+}
+
+/// 辅助进程状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SidecarStatus {
+    pub name: String,
+    pub program: String,
+    pub running: bool,
+    pub pid: Option<u32>,
+    /// 连续自动重启次数
+    pub restarts: u32,
+    pub uptime_secs: Option<u64>,
+    pub last_exit: Option<String>,
+    pub restart: RestartPolicy,
+}
+
+struct Sidecar {
+    spec: SidecarSpec,
+    child: Option<Child>,
+    started_at: Option<Instant>,
+    restarts: u32,
+    last_exit: Option<String>,
+    logs: Arc<Mutex<VecDeque<String>>>,
+}
+
+static SIDECARS: Mutex<Option<HashMap<String, Sidecar>>> = Mutex::new(None);
+
+fn with_sidecars<T>(f: impl FnOnce(&mut HashMap<String, Sidecar>) -> T) -> T {
+    let mut guard = SIDECARS.lock().unwrap_or_else(|e| e.into_inner());
+    f(guard.get_or_insert_with(HashMap::new))
+}
+
+/// 把子进程输出逐行写入日志缓冲
+fn capture<R: Read + Send + 'static>(name: String, reader: R, logs: Arc<Mutex<VecDeque<String>>>) {
+    std::thread::spawn(move || {
+        for line in BufReader::new(reader).lines().map_while(Result::ok) {
+            debug!("[辅助进程:{}] {}", name, line);
+            let mut logs = logs.lock().unwrap_or_else(|e| e.into_inner());
+            if logs.len() >= LOG_LINES {
+                logs.pop_front();
+            }
+            logs.push_back(line);
+        }
+    });
+}
+
+fn spawn_child(spec: &SidecarSpec, logs: &Arc<Mutex<VecDeque<String>>>) -> Result<Child, String> {
+    let mut cmd = Command::new(&spec.program);
+    cmd.args(&spec.args)
+        .envs(platform::profile_env())
+        .envs(spec.env.iter().cloned())
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    #[cfg(not(windows))]
+    cmd.env("PATH", shell::get_extended_path());
+    #[cfg(windows)]
+    cmd.creation_flags(CREATE_NO_WINDOW);
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| format!("启动 {} 失败: {}", spec.name, e))?;
+    if let Some(out) = child.stdout.take() {
+        capture(spec.name.clone(), out, logs.clone());
+    }
+    if let Some(err) = child.stderr.take() {
+        capture(spec.name.clone(), err, logs.clone());
+    }
+    Ok(child)
+}
+
+/// 子进程仍在运行时返回 PID
+fn running_pid(child: &mut Option<Child>) -> Option<u32> {
+    let child = child.as_mut()?;
+    matches!(child.try_wait(), Ok(None)).then(|| child.id())
+}
+
+/// 注册辅助进程（同名的已注册进程会先停止）
+pub fn register(spec: SidecarSpec) {
+    stop(&spec.name);
+    with_sidecars(|sidecars| {
+        sidecars.insert(
+            spec.name.clone(),
+            Sidecar {
+                spec,
+                child: None,
+                started_at: None,
+                restarts: 0,
+                last_exit: None,
+                logs: Arc::new(Mutex::new(VecDeque::new())),
+            },
+        );
+    });
+}
+
+/// 启动已注册的辅助进程，已在运行时直接返回 PID
+pub fn start(name: &str) -> Result<u32, String> {
+    with_sidecars(|sidecars| {
+        let sidecar = sidecars
+            .get_mut(name)
+            .ok_or_else(|| format!("未注册的辅助进程: {}", name))?;
+        if let Some(pid) = running_pid(&mut sidecar.child) {
+            return Ok(pid);
+        }
+        let child = spawn_child(&sidecar.spec, &sidecar.logs)?;
+        let pid = child.id();
+        info!("[辅助进程] ✓ {} 已启动 (PID {})", name, pid);
+        orphans::record_pid(name, pid);
+        sidecar.child = Some(child);
+        sidecar.started_at = Some(Instant::now());
+        Ok(pid)
+    })
+}
+
+/// 停止辅助进程（保留注册信息，不会被自动重启）
+pub fn stop(name: &str) {
+    let child = with_sidecars(|sidecars| {
+        let sidecar = sidecars.get_mut(name)?;
+        sidecar.started_at = None;
+        sidecar.child.take()
+    });
+    if let Some(mut child) = child {
+        info!("[辅助进程] 停止 {} (PID {})", name, child.id());
+        let _ = child.kill();
+        let _ = child.wait();
+        orphans::remove_pid(name, child.id());
+    }
+}
+
+/// 运行中的辅助进程 PID
+pub fn pid(name: &str) -> Option<u32> {
+    with_sidecars(|sidecars| running_pid(&mut sidecars.get_mut(name)?.child))
+}
+
+/// 执行一次健康检查
+pub async fn probe(probe: &HealthProbe) -> bool {
+    match probe {
+        HealthProbe::Http { url } => {
+            let Ok(client) = http::build_client(None, Duration::from_secs(2)) else {
+                return false;
+            };
+            matches!(client.get(url).send().await, Ok(r) if r.status().is_success())
+        }
+        HealthProbe::Tcp { port } => tokio::net::TcpStream::connect(("127.0.0.1", *port))
+            .await
+            .is_ok(),
+    }
+}
+
+/// 等待辅助进程通过健康检查（未配置健康检查时只确认进程仍在运行）
+pub async fn wait_healthy(name: &str, timeout: Duration) -> bool {
+    let health = with_sidecars(|s| s.get(name).and_then(|s| s.spec.health.clone()));
+    let deadline = Instant::now() + timeout;
+    loop {
+        if pid(name).is_none() {
+            return false;
+        }
+        match &health {
+            Some(h) if probe(h).await => return true,
+            None => return true,
+            _ => {}
+        }
+        if Instant::now() >= deadline {
+            return false;
+        }
+        tokio::time::sleep(Duration::from_millis(250)).await;
+    }
+}
+
+/// 是否应该重启已退出的进程
+fn should_restart(policy: RestartPolicy, success: bool, restarts: u32) -> bool {
+    match policy {
+        RestartPolicy::Never => false,
+        RestartPolicy::Always => true,
+        RestartPolicy::OnFailure { max_restarts } => !success && restarts < max_restarts,
+    }
+}
+
+/// 后台监控每轮调用：按重启策略重启意外退出的辅助进程
+pub fn supervise() {
+    if shutdown::is_shutting_down() {
+        return;
+    }
+    with_sidecars(|sidecars| {
+        for (name, sidecar) in sidecars.iter_mut() {
+            if let Some(started) = sidecar.started_at {
+                if started.elapsed() >= STABLE_AFTER {
+                    sidecar.restarts = 0;
+                }
+            }
+            let Some(child) = sidecar.child.as_mut() else {
+                continue;
+            };
+            let status = match child.try_wait() {
+                Ok(Some(status)) => status,
+                _ => continue,
+            };
+            sidecar.child = None;
+            sidecar.last_exit = Some(status.to_string());
+            warn!("[辅助进程] ✗ {} 已退出: {}", name, status);
+            if !should_restart(sidecar.spec.restart, status.success(), sidecar.restarts) {
+                sidecar.started_at = None;
+                continue;
+            }
+            sidecar.restarts += 1;
+            match spawn_child(&sidecar.spec, &sidecar.logs) {
+                Ok(child) => {
+                    info!(
+                        "[辅助进程] ✓ {} 已重启 (第 {} 次, PID {})",
+                        name,
+                        sidecar.restarts,
+                        child.id()
+                    );
+                    sidecar.child = Some(child);
+                    sidecar.started_at = Some(Instant::now());
+                }
+                Err(e) => warn!("[辅助进程] ✗ {}", e),
+            }
+        }
+    });
+}
+
+/// Manager 退出时停止所有辅助进程：先请求退出，超过宽限期后强制结束
+pub fn stop_all(grace: Duration) {
+    let mut children: Vec<(String, Child)> = with_sidecars(|sidecars| {
+        sidecars
+            .iter_mut()
+            .filter_map(|(name, s)| {
+                s.started_at = None;
+                s.child.take().map(|c| (name.clone(), c))
+            })
+            .collect()
+    });
+    for (name, child) in &children {
+        info!("[辅助进程] 停止 {} (PID {})", name, child.id());
+        shell::terminate_process(child.id(), false);
+    }
+    let deadline = Instant::now() + grace;
+    while !children.is_empty() && Instant::now() < deadline {
+        children.retain_mut(|(_, c)| matches!(c.try_wait(), Ok(None)));
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    for (name, mut child) in children {
+        warn!("[辅助进程] {} 未在宽限期内退出，强制结束", name);
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+}
+
+fn status_of(name: &str, sidecar: &mut Sidecar) -> SidecarStatus {
+    let pid = running_pid(&mut sidecar.child);
+    SidecarStatus {
+        name: name.to_string(),
+        program: sidecar.spec.program.clone(),
+        running: pid.is_some(),
+        pid,
+        restarts: sidecar.restarts,
+        uptime_secs: pid.and(sidecar.started_at).map(|t| t.elapsed().as_secs()),
+        last_exit: sidecar.last_exit.clone(),
+        restart: sidecar.spec.restart,
+    }
+}
+
+/// 列出所有辅助进程
+#[guarded]
+#[command]
+pub async fn list_sidecars() -> Result<Vec<SidecarStatus>, String> {
+    let mut list: Vec<SidecarStatus> = with_sidecars(|sidecars| {
+        sidecars
+            .iter_mut()
+            .map(|(name, sidecar)| status_of(name, sidecar))
+            .collect()
+    });
+    list.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(list)
+}
+
+/// 获取辅助进程最近的输出
+#[guarded]
+#[command]
+pub async fn get_sidecar_logs(name: String, lines: Option<usize>) -> Result<Vec<String>, String> {
+    let logs = with_sidecars(|s| s.get(&name).map(|s| s.logs.clone()))
+        .ok_or_else(|| format!("未注册的辅助进程: {}", name))?;
+    let logs = logs.lock().unwrap_or_else(|e| e.into_inner());
+    let n = lines.unwrap_or(100).min(logs.len());
+    Ok(logs.iter().skip(logs.len() - n).cloned().collect())
+}
+
+/// 手动重启辅助进程（重置自动重启计数）
+#[guarded]
+#[command]
+pub async fn restart_sidecar(name: String) -> Result<SidecarStatus, String> {
+    stop(&name);
+    with_sidecars(|s| {
+        if let Some(sidecar) = s.get_mut(&name) {
+            sidecar.restarts = 0;
+        }
+    });
+    start(&name)?;
+    with_sidecars(|s| s.get_mut(&name).map(|sidecar| status_of(&name, sidecar)))
+        .ok_or_else(|| format!("未注册的辅助进程: {}", name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn restart_policy_limits_failures() {
+        let on_failure = RestartPolicy::OnFailure { max_restarts: 3 };
+        assert!(should_restart(on_failure, false, 0));
+        assert!(!should_restart(on_failure, false, 3));
+        assert!(!should_restart(on_failure, true, 0));
+        assert!(should_restart(RestartPolicy::Always, true, 10));
+        assert!(!should_restart(RestartPolicy::Never, false, 0));
+    }
+}
@@ -0,0 +1,271 @@
+use crate::commands::capabilities::{self, Feature};
+use crate::commands::notifications::{self, NotificationTrigger};
+use crate::commands::{events, installer, inventory, privacy, settings};
+use crate::utils::{audit, http, platform, shell};
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::path::Path;
+use openclaw_macros::guarded;
+use tauri::{command, AppHandle};
+
+/// 技能更新进度事件
+pub const SKILL_UPDATE_PROGRESS_EVENT: &str = "skill-update-progress";
+/// 默认技能仓库
+pub const DEFAULT_SKILL_REGISTRY: &str = "https://clawhub.ai";
+
+/// 技能设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SkillSettings {
+    /// 技能仓库地址，按 `{registry}/api/v1/skills/{name}` 查询最新版本
+    pub registry_url: String,
+}
+
+impl Default for SkillSettings {
+    fn default() -> Self {
+        Self {
+            registry_url: DEFAULT_SKILL_REGISTRY.to_string(),
+        }
+    }
+}
+
+/// 有新版本的技能
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SkillUpdate {
+    pub name: String,
+    pub current_version: String,
+    pub latest_version: String,
+    pub changelog: Option<String>,
+}
+
+/// 单个技能的更新状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SkillUpdateStatus {
+    Updating,
+    Updated,
+    Failed,
+}
+
+/// 更新进度（每个技能开始和结束时各推送一次）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkillUpdateProgress {
+    pub name: String,
+    /// 从 1 开始
+    pub index: usize,
+    pub total: usize,
+    pub status: SkillUpdateStatus,
+    pub version: Option<String>,
+    pub error: Option<String>,
+}
+
+/// 已安装技能的目录
+pub fn skills_dir() -> std::path::PathBuf {
+    Path::new(&platform::get_config_dir()).join("skills")
+}
+
+/// 从仓库返回的技能信息中取出最新版本和更新说明
+/// 兼容 `{version, changelog}` 和 `{latest: {version, changelog}}` 两种格式
+fn parse_latest(info: &Value) -> Option<(String, Option<String>)> {
+    let latest = info.get("latest").filter(|l| l.is_object()).unwrap_or(info);
+    let version = latest
+        .get("version")
+        .or_else(|| info.get("latestVersion"))
+        .and_then(|v| v.as_str())
+        .filter(|v| !v.is_empty())?;
+    let changelog = latest
+        .get("changelog")
+        .or_else(|| info.get("changelog"))
+        .and_then(|v| v.as_str())
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty());
+    Some((version.to_string(), changelog))
+}
+
+async fn fetch_latest(
+    client: &reqwest::Client,
+    registry: &str,
+    name: &str,
+) -> Result<Option<(String, Option<String>)>, String> {
+    let url = format!("{}/api/v1/skills/{}", registry.trim_end_matches('/'), name);
+    let resp = client.get(&url).send().await.map_err(|e| e.to_string())?;
+    if resp.status() == reqwest::StatusCode::NOT_FOUND {
+        // 本地开发或私有来源的技能
+        return Ok(None);
+    }
+    let info: Value = resp
+        .error_for_status()
+        .map_err(|e| e.to_string())?
+        .json()
+        .await
+        .map_err(|e| format!("解析技能信息失败: {}", e))?;
+    Ok(parse_latest(&info))
+}
+
+/// 对比已安装技能与仓库中的最新版本（跳过开发链接的技能和无版本号的技能）
+async fn find_updates() -> Result<Vec<SkillUpdate>, String> {
+    privacy::guard("检查技能更新")?;
+    let registry = settings::load_settings().skills.registry_url;
+    let client = http::client()?;
+    let mut updates = Vec::new();
+    for skill in inventory::list_skills(&skills_dir()) {
+        let Some(current) = skill.version.filter(|_| !skill.linked) else {
+            continue;
+        };
+        match fetch_latest(&client, &registry, &skill.name).await {
+            Ok(Some((latest, changelog))) if installer::compare_versions(&current, &latest) => {
+                updates.push(SkillUpdate {
+                    name: skill.name,
+                    current_version: current,
+                    latest_version: latest,
+                    changelog,
+                });
+            }
+            Ok(_) => {}
+            Err(e) => warn!("[技能更新] 查询 {} 失败: {}", skill.name, e),
+        }
+    }
+    Ok(updates)
+}
+
+/// 后台监控定期调用：有技能可更新时通知（同一组版本只通知一次）
+pub async fn check_and_notify(notified: &mut Option<String>) {
+    if privacy::is_enabled() {
+        return;
+    }
+    let updates = match find_updates().await {
+        Ok(updates) => updates,
+        Err(e) => {
+            debug!("[技能更新] 检查失败: {}", e);
+            return;
+        }
+    };
+    if updates.is_empty() {
+        return;
+    }
+    let key = updates
+        .iter()
+        .map(|u| format!("{}@{}", u.name, u.latest_version))
+        .collect::<Vec<_>>()
+        .join(",");
+    if notified.as_deref() == Some(key.as_str()) {
+        return;
+    }
+    info!("[技能更新] 发现 {} 个技能可更新", updates.len());
+    notifications::notify(
+        NotificationTrigger::SkillUpdates,
+        &key,
+        &format!("{} 个技能有新版本", updates.len()),
+        &updates
+            .iter()
+            .map(|u| format!("{} {} → {}", u.name, u.current_version, u.latest_version))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    );
+    *notified = Some(key);
+}
+
+/// 检查已安装技能的更新，返回可更新的技能及更新说明
+#[guarded]
+#[command]
+pub async fn check_skill_updates() -> Result<Vec<SkillUpdate>, String> {
+    info!("[技能更新] 检查技能更新...");
+    let updates = find_updates().await?;
+    info!("[技能更新] ✓ {} 个技能可更新", updates.len());
+    Ok(updates)
+}
+
+/// 更新所有可更新的技能：逐个安装并推送进度，单个失败不影响其它技能
+#[guarded]
+#[command]
+pub async fn update_all_skills(app: AppHandle) -> Result<Vec<SkillUpdateProgress>, String> {
+    let updates = find_updates().await?;
+    let total = updates.len();
+    if total > 0 {
+        tauri::async_runtime::spawn_blocking(|| capabilities::require(Feature::Skills))
+            .await
+            .map_err(|e| e.to_string())??;
+    }
+    info!("[技能更新] 开始更新 {} 个技能", total);
+    let mut results = Vec::with_capacity(total);
+    for (i, update) in updates.into_iter().enumerate() {
+        let mut progress = SkillUpdateProgress {
+            name: update.name.clone(),
+            index: i + 1,
+            total,
+            status: SkillUpdateStatus::Updating,
+            version: Some(update.current_version.clone()),
+            error: None,
+        };
+        let _ = events::emit(&app, SKILL_UPDATE_PROGRESS_EVENT, &progress);
+
+        let name = update.name.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            shell::run_openclaw(&["skill", "install", &name])
+        })
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|r| r);
+        let installed = inventory::list_skills(&skills_dir())
+            .into_iter()
+            .find(|s| s.name == update.name)
+            .and_then(|s| s.version);
+        match result {
+            Ok(_) if installed.as_deref() != Some(update.current_version.as_str()) => {
+                info!(
+                    "[技能更新] ✓ {} 已更新到 {}",
+                    update.name,
+                    installed.as_deref().unwrap_or("?")
+                );
+                progress.status = SkillUpdateStatus::Updated;
+                progress.version = installed;
+            }
+            Ok(_) => {
+                progress.status = SkillUpdateStatus::Failed;
+                progress.error = Some(format!(
+                    "安装完成但版本仍为 {}（最新 {}）",
+                    update.current_version, update.latest_version
+                ));
+            }
+            Err(e) => {
+                progress.status = SkillUpdateStatus::Failed;
+                progress.error = Some(e);
+            }
+        }
+        if let Some(e) = &progress.error {
+            warn!("[技能更新] ✗ {} 更新失败: {}", update.name, e);
+        }
+        audit::record(
+            "update_skill",
+            &update.name,
+            progress.status == SkillUpdateStatus::Updated,
+            json!({
+                "from": update.current_version,
+                "to": progress.version,
+                "error": progress.error,
+            }),
+        );
+        let _ = events::emit(&app, SKILL_UPDATE_PROGRESS_EVENT, &progress);
+        results.push(progress);
+    }
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_latest_version_from_registry_formats() {
+        let flat = json!({"name": "browser", "version": "1.4.0", "changelog": "- 支持多标签页\n"});
+        assert_eq!(
+            parse_latest(&flat),
+            Some(("1.4.0".to_string(), Some("- 支持多标签页".to_string())))
+        );
+        let nested = json!({"name": "files", "latest": {"version": "2.0.1"}});
+        assert_eq!(parse_latest(&nested), Some(("2.0.1".to_string(), None)));
+        assert_eq!(parse_latest(&json!({"name": "x"})), None);
+        assert!(installer::compare_versions("1.3.9", "1.4.0"));
+    }
+}
@@ -0,0 +1,344 @@
+use crate::commands::installer::{self, InstallResult};
+use crate::commands::{events, git, monitor, settings, shutdown};
+use crate::utils::{encoding, file, platform, shell, time};
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use openclaw_macros::guarded;
+use tauri::{command, AppHandle};
+
+/// 构建日志事件名
+pub const SOURCE_BUILD_LOG_EVENT: &str = "source-build-log";
+
+/// 构建日志（逐行推送到前端）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceBuildLog {
+    /// 当前步骤：clone / install / build / link / verify
+    pub step: String,
+    pub line: String,
+}
+
+/// 源码构建安装记录（保存在 Manager 数据目录的 source_build.json）
+/// 存在时表示当前 OpenClaw 是源码构建的，更新时重新拉取并构建而不是从 npm 安装
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceBuildRecord {
+    pub repo: String,
+    pub git_ref: String,
+    pub commit: String,
+    /// 源码目录
+    pub path: String,
+    pub built_at: String,
+}
+
+fn record_path() -> PathBuf {
+    Path::new(&platform::get_manager_data_dir()).join("source_build.json")
+}
+
+/// 读取源码构建记录（不是源码构建时返回 None）
+pub fn load_record() -> Option<SourceBuildRecord> {
+    std::fs::read_to_string(record_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+}
+
+fn save_record(record: &SourceBuildRecord) -> Result<(), String> {
+    let content =
+        serde_json::to_string_pretty(record).map_err(|e| format!("序列化构建记录失败: {}", e))?;
+    file::write_atomic(&record_path(), content.as_bytes())
+        .map_err(|e| format!("写入构建记录失败: {}", e))
+}
+
+/// 清除源码构建记录（改用 npm / GitHub 安装或卸载后调用）
+pub fn clear_record() {
+    let path = record_path();
+    if path.exists() {
+        if let Err(e) = std::fs::remove_file(&path) {
+            warn!("[源码构建] 清除构建记录失败: {}", e);
+        }
+    }
+}
+
+/// 源码目录
+fn source_dir() -> PathBuf {
+    Path::new(&platform::get_manager_data_dir())
+        .join("source")
+        .join("openclaw")
+}
+
+/// Windows 上 npm / pnpm 是 .cmd 脚本，需要带扩展名才能直接启动
+fn node_tool(name: &str) -> String {
+    if platform::is_windows() {
+        format!("{}.cmd", name)
+    } else {
+        name.to_string()
+    }
+}
+
+/// 仓库地址只允许 https 或 git@ 形式
+fn is_valid_repo_url(repo: &str) -> bool {
+    (repo.starts_with("https://") || repo.starts_with("git@"))
+        && !repo
+            .chars()
+            .any(|c| c.is_whitespace() || c == '"' || c == '\'')
+}
+
+/// 根据锁文件选择包管理器（pnpm 锁文件且已安装 pnpm 时使用 pnpm）
+fn package_manager(dir: &Path) -> String {
+    if dir.join("pnpm-lock.yaml").exists() && shell::command_exists("pnpm") {
+        node_tool("pnpm")
+    } else {
+        node_tool("npm")
+    }
+}
+
+/// package.json 中是否定义了 build 脚本
+fn has_build_script(dir: &Path) -> bool {
+    std::fs::read_to_string(dir.join("package.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+        .map(|pkg| pkg.pointer("/scripts/build").is_some())
+        .unwrap_or(false)
+}
+
+/// 构建流水线：执行每一步命令并把输出逐行推送给前端
+struct BuildPipeline {
+    app: Option<AppHandle>,
+    dir: PathBuf,
+}
+
+impl BuildPipeline {
+    fn log(&self, step: &str, line: &str) {
+        info!("[源码构建] [{}] {}", step, line);
+        if let Some(app) = &self.app {
+            let _ = events::emit(
+                app,
+                SOURCE_BUILD_LOG_EVENT,
+                SourceBuildLog {
+                    step: step.to_string(),
+                    line: line.to_string(),
+                },
+            );
+        }
+    }
+
+    /// 执行一条命令，stdout / stderr 实时转发，返回 stdout 全部内容
+    fn run(&self, step: &str, cmd: &str, args: &[&str]) -> Result<String, String> {
+        self.log(step, &format!("$ {} {}", cmd, args.join(" ")));
+        let mut child = shell::spawn_piped(cmd, args, &self.dir)
+            .map_err(|e| format!("启动 {} 失败: {}", cmd, e))?;
+        // Manager 退出时结束构建命令，避免留下孤儿进程
+        let operation = shutdown::begin_operation(&format!("源码构建: {}", step));
+        operation.attach_pid(child.id());
+
+        let stderr = child.stderr.take();
+        let (stdout_lines, stderr_lines) = std::thread::scope(|scope| {
+            let handle = scope.spawn(|| match stderr {
+                Some(stderr) => self.forward(step, stderr),
+                None => Vec::new(),
+            });
+            let stdout_lines = match child.stdout.take() {
+                Some(stdout) => self.forward(step, stdout),
+                None => Vec::new(),
+            };
+            (stdout_lines, handle.join().unwrap_or_default())
+        });
+
+        let status = child
+            .wait()
+            .map_err(|e| format!("等待 {} 结束失败: {}", cmd, e))?;
+        if status.success() {
+            Ok(stdout_lines.join("\n"))
+        } else {
+            let tail = &stderr_lines[stderr_lines.len().saturating_sub(20)..];
+            Err(format!(
+                "{} 失败 (退出码 {:?}): {}",
+                step,
+                status.code(),
+                tail.join("\n")
+            ))
+        }
+    }
+
+    fn forward<R: Read>(&self, step: &str, reader: R) -> Vec<String> {
+        let mut lines = Vec::new();
+        for chunk in BufReader::new(reader).split(b'\n') {
+            let Ok(chunk) = chunk else { break };
+            let line = encoding::decode_output(&chunk).trim_end().to_string();
+            if !line.is_empty() {
+                self.log(step, &line);
+                lines.push(line);
+            }
+        }
+        lines
+    }
+}
+
+/// 从源码构建并安装 OpenClaw：拉取代码 → 安装依赖 → 构建 → npm link → 校验
+async fn build_from_source(
+    app: Option<AppHandle>,
+    repo: &str,
+    git_ref: &str,
+) -> Result<SourceBuildRecord, String> {
+    let settings = settings::load_settings();
+    let dir = source_dir();
+    std::fs::create_dir_all(&dir).map_err(|e| format!("创建源码目录失败: {}", e))?;
+    let pipeline = BuildPipeline {
+        app,
+        dir: dir.clone(),
+    };
+
+    // 1. 拉取代码：统一使用 init + fetch，支持分支、标签和 commit SHA
+    let fetch_url = settings
+        .github_proxied_url(repo)
+        .filter(|_| repo.starts_with("https://github.com/"))
+        .unwrap_or_else(|| repo.to_string());
+    let proxy_config = settings
+        .proxy
+        .as_deref()
+        .filter(|p| !p.trim().is_empty())
+        .map(|p| format!("http.proxy={}", p.trim()));
+    let git = |args: &[&str]| -> Result<String, String> {
+        let mut full: Vec<&str> = Vec::new();
+        if let Some(proxy) = &proxy_config {
+            full.push("-c");
+            full.push(proxy);
+        }
+        full.extend_from_slice(args);
+        pipeline.run("clone", "git", &full)
+    };
+    if !dir.join(".git").exists() {
+        git(&["init", "--quiet"])?;
+    }
+    git(&["fetch", "--depth", "1", &fetch_url, git_ref])?;
+    git(&["checkout", "--force", "FETCH_HEAD"])?;
+    let commit = git(&["rev-parse", "HEAD"])?.trim().to_string();
+    pipeline.log("clone", &format!("已检出 {} ({})", git_ref, commit));
+
+    // 2. 安装依赖
+    let pm = package_manager(&dir);
+    let registry = format!("--registry={}", settings.npm_registry.trim());
+    if settings.npm_registry.trim().is_empty() {
+        pipeline.run("install", &pm, &["install"])?;
+    } else {
+        pipeline.run("install", &pm, &["install", &registry])?;
+    }
+
+    // 3. 构建
+    if has_build_script(&dir) {
+        pipeline.run("build", &pm, &["run", "build"])?;
+    } else {
+        pipeline.log("build", "package.json 中没有 build 脚本，跳过构建");
+    }
+
+    // 4. 全局链接
+    pipeline.run("link", &node_tool("npm"), &["link"])?;
+
+    // 5. 校验
+    let version = installer::get_openclaw_version()
+        .ok_or_else(|| "构建完成但无法运行 openclaw --version".to_string())?;
+    pipeline.log("verify", &format!("openclaw {}", version));
+
+    Ok(SourceBuildRecord {
+        repo: repo.to_string(),
+        git_ref: git_ref.to_string(),
+        commit,
+        path: dir.to_string_lossy().to_string(),
+        built_at: time::now_rfc3339(),
+    })
+}
+
+/// 构建并记录安装（更新 OpenClaw 时也会调用）
+pub async fn build_and_register(
+    app: Option<AppHandle>,
+    repo: &str,
+    git_ref: &str,
+) -> Result<InstallResult, String> {
+    git::ensure_git()?;
+    for tool in ["node", "npm"] {
+        if !shell::command_exists(tool) {
+            return Err(format!("源码构建需要 {}，请先安装", tool));
+        }
+    }
+
+    monitor::expect_gateway_stop();
+    let _ = shell::run_openclaw(&["gateway", "stop"]);
+
+    match build_from_source(app, repo, git_ref).await {
+        Ok(record) => {
+            save_record(&record)?;
+            info!(
+                "[源码构建] ✓ 构建完成: {} ({})",
+                record.git_ref, record.commit
+            );
+            Ok(InstallResult {
+                success: true,
+                message: format!(
+                    "已从源码构建 OpenClaw {} ({})",
+                    record.git_ref, record.commit
+                ),
+                error: None,
+                timings: Vec::new(),
+            })
+        }
+        Err(e) => {
+            error!("[源码构建] ✗ 构建失败: {}", e);
+            Ok(InstallResult {
+                success: false,
+                message: "源码构建失败".to_string(),
+                error: Some(e),
+                timings: Vec::new(),
+            })
+        }
+    }
+}
+
+/// 从源码构建 OpenClaw（用于没有预编译支持的平台）
+/// 构建日志通过 `source-build-log` 事件实时推送
+#[guarded]
+#[command]
+pub async fn build_openclaw_from_source(
+    app: AppHandle,
+    repo: Option<String>,
+    git_ref: Option<String>,
+) -> Result<InstallResult, String> {
+    let repo = repo
+        .map(|r| r.trim().to_string())
+        .filter(|r| !r.is_empty())
+        .unwrap_or_else(|| installer::OPENCLAW_GITHUB_REPO.to_string());
+    let git_ref = git_ref
+        .map(|r| r.trim().to_string())
+        .filter(|r| !r.is_empty())
+        .unwrap_or_else(|| "HEAD".to_string());
+    if !is_valid_repo_url(&repo) {
+        return Err(format!("无效的仓库地址: {}", repo));
+    }
+    if !installer::is_valid_git_ref(&git_ref) {
+        return Err(format!("无效的 Git ref: {}", git_ref));
+    }
+    info!("[源码构建] 开始构建 {} @ {}", repo, git_ref);
+    build_and_register(Some(app), &repo, &git_ref).await
+}
+
+/// 获取源码构建记录
+#[guarded]
+#[command]
+pub async fn get_source_build_info() -> Result<Option<SourceBuildRecord>, String> {
+    Ok(load_record())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repo_url_must_be_https_or_ssh() {
+        assert!(is_valid_repo_url(
+            "https://github.com/openclaw/openclaw.git"
+        ));
+        assert!(is_valid_repo_url("git@github.com:openclaw/openclaw.git"));
+        assert!(!is_valid_repo_url("file:///etc"));
+        assert!(!is_valid_repo_url("https://x.com/a b"));
+        assert!(!is_valid_repo_url("--upload-pack=evil"));
+    }
+}
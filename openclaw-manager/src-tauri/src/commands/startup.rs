@@ -0,0 +1,204 @@
+use crate::commands::{recovery, service, settings, updates};
+use crate::utils::{secrets, time};
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use openclaw_macros::guarded;
+use tauri::command;
+
+/// 就绪检查轮询间隔
+const GATE_POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// 等待超过该时间的条件视为拖慢了启动
+const DELAY_THRESHOLD_MS: u64 = 1000;
+/// 用于检查网络和 DNS 的地址
+const NETWORK_PROBE_ADDR: &str = "223.5.5.5:53";
+const DNS_PROBE_HOST: &str = "registry.npmjs.org:443";
+
+/// 最近一次自动启动的报告
+static LAST_REPORT: Mutex<Option<StartupReport>> = Mutex::new(None);
+
+/// 单个就绪条件的等待结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GateResult {
+    /// network / dns / keychain
+    pub name: String,
+    pub ready: bool,
+    pub waited_ms: u64,
+    pub timed_out: bool,
+}
+
+/// 自动启动报告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StartupReport {
+    pub started_at: String,
+    pub gates: Vec<GateResult>,
+    /// 拖慢启动最多的条件（都很快就绪时为 None）
+    pub delayed_by: Option<String>,
+    pub gateway_started: bool,
+    pub error: Option<String>,
+}
+
+/// 找出等待时间最长且超过阈值的条件
+fn delayed_by(gates: &[GateResult]) -> Option<String> {
+    gates
+        .iter()
+        .filter(|g| g.timed_out || g.waited_ms >= DELAY_THRESHOLD_MS)
+        .max_by_key(|g| g.waited_ms)
+        .map(|g| g.name.clone())
+}
+
+/// 轮询等待某个条件就绪，超时后放弃等待
+async fn wait_for<F, Fut>(name: &str, timeout: Duration, check: F) -> GateResult
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = bool>,
+{
+    let start = Instant::now();
+    loop {
+        if check().await {
+            let waited_ms = start.elapsed().as_millis() as u64;
+            info!("[自动启动] ✓ {} 已就绪 ({}ms)", name, waited_ms);
+            return GateResult {
+                name: name.to_string(),
+                ready: true,
+                waited_ms,
+                timed_out: false,
+            };
+        }
+        if start.elapsed() >= timeout {
+            warn!(
+                "[自动启动] ✗ 等待 {} 超时 ({}秒)，继续启动",
+                name,
+                timeout.as_secs()
+            );
+            return GateResult {
+                name: name.to_string(),
+                ready: false,
+                waited_ms: start.elapsed().as_millis() as u64,
+                timed_out: true,
+            };
+        }
+        tokio::time::sleep(GATE_POLL_INTERVAL).await;
+    }
+}
+
+/// 是否有可用的网络路由（UDP connect 不发送数据，只检查路由）
+async fn network_online() -> bool {
+    std::net::UdpSocket::bind("0.0.0.0:0")
+        .and_then(|socket| socket.connect(NETWORK_PROBE_ADDR))
+        .is_ok()
+}
+
+async fn dns_ready() -> bool {
+    let lookup = tokio::net::lookup_host(DNS_PROBE_HOST);
+    match tokio::time::timeout(Duration::from_secs(5), lookup).await {
+        Ok(Ok(mut addrs)) => addrs.next().is_some(),
+        _ => false,
+    }
+}
+
+async fn keychain_ready() -> bool {
+    secrets::keychain_available()
+}
+
+/// 等待启动条件就绪后启动 Gateway
+async fn run_orchestrator() -> StartupReport {
+    let settings = settings::load_settings();
+    let timeout = Duration::from_secs(settings.timeouts.startup_gate_secs);
+    let started_at = time::now_rfc3339();
+
+    let gates = vec![
+        wait_for("network", timeout, network_online).await,
+        wait_for("dns", timeout, dns_ready).await,
+        wait_for("keychain", timeout, keychain_ready).await,
+    ];
+    let delayed_by = delayed_by(&gates);
+    if let Some(gate) = &delayed_by {
+        info!("[自动启动] 启动被 {} 延迟", gate);
+    }
+
+    // 选择了“下次启动时安装”的更新在 Gateway 启动前安装
+    updates::apply_staged_on_startup().await;
+
+    let (gateway_started, error) = match service::get_service_status().await {
+        Ok(status) if status.running => {
+            info!("[自动启动] Gateway 已在运行");
+            (true, None)
+        }
+        _ => match service::start_service(None).await {
+            Ok(msg) => {
+                info!("[自动启动] ✓ {}", msg);
+                (true, None)
+            }
+            Err(e) => {
+                error!("[自动启动] ✗ 启动 Gateway 失败: {}", e);
+                (false, Some(e.message))
+            }
+        },
+    };
+
+    StartupReport {
+        started_at,
+        gates,
+        delayed_by,
+        gateway_started,
+        error,
+    }
+}
+
+/// Manager 启动时调用：开启自动启动时在后台按顺序等待条件并启动 Gateway
+pub fn start() {
+    if !settings::load_settings().autostart {
+        if updates::apply_on_restart_pending() {
+            tauri::async_runtime::spawn(updates::apply_staged_on_startup());
+        }
+        tauri::async_runtime::spawn_blocking(recovery::check_on_launch);
+        return;
+    }
+    tauri::async_runtime::spawn(async {
+        info!("[自动启动] 等待网络、DNS、钥匙串就绪后启动 Gateway...");
+        let report = run_orchestrator().await;
+        if let Ok(mut last) = LAST_REPORT.lock() {
+            *last = Some(report);
+        }
+        // 自动启动失败也计入更新后的失败次数，之后再检查是否需要恢复
+        let _ = tauri::async_runtime::spawn_blocking(recovery::check_on_launch).await;
+    });
+}
+
+/// 获取最近一次自动启动报告（未开启自动启动或尚未完成时为 None）
+#[guarded]
+#[command]
+pub async fn get_startup_report() -> Result<Option<StartupReport>, String> {
+    Ok(LAST_REPORT.lock().ok().and_then(|r| r.clone()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gate(name: &str, waited_ms: u64, timed_out: bool) -> GateResult {
+        GateResult {
+            name: name.to_string(),
+            ready: !timed_out,
+            waited_ms,
+            timed_out,
+        }
+    }
+
+    #[test]
+    fn slowest_gate_is_reported_as_delay() {
+        assert_eq!(
+            delayed_by(&[gate("network", 10, false), gate("dns", 200, false)]),
+            None
+        );
+        let gates = [
+            gate("network", 3000, false),
+            gate("dns", 20, false),
+            gate("keychain", 60000, true),
+        ];
+        assert_eq!(delayed_by(&gates).as_deref(), Some("keychain"));
+    }
+}
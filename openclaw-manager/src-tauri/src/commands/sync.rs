@@ -0,0 +1,696 @@
+//! 多台电脑之间同步 Manager 设置和 AI Provider 配置（可选，默认关闭）
+//! 同步内容用同步口令加密后保存在 WebDAV / S3 上；默认不包含 API Key，开启 include_secrets 后才上传密钥。
+//! 每台电脑有自己的设备 ID，同步文档带向量时钟：一方的时钟包含另一方时直接采用较新的版本；
+//! 并发修改时按字段（每个设置项、每个 Provider）与上次同步的版本三方合并，
+//! 两边修改了同一字段时保留修改时间较晚的一方，并记录冲突供查看
+use crate::commands::{config, events, secret_handles, settings};
+use crate::utils::remote_storage::{Expected, RemoteTarget};
+use crate::utils::{audit, crypto, file, platform, secrets, time};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use openclaw_macros::guarded;
+use tauri::command;
+
+/// 远程存储中的同步文件
+const REMOTE_KEY: &str = "openclaw-manager/sync.bin";
+/// 同步口令在钥匙串中的账户名
+const PASSPHRASE_ACCOUNT: &str = "sync:passphrase";
+/// 远程存储密钥在钥匙串中的账户名前缀
+const REMOTE_ACCOUNT: &str = "sync:remote";
+/// 同步后推送给前端，前端据此重新读取设置
+pub const SYNC_APPLIED_EVENT: &str = "sync://applied";
+/// 不同步的设置字段（与本机环境相关、其密钥保存在本机钥匙串中，
+/// 或是安全策略、隐私、本机脚本和路径等不应由其他电脑修改的设置）
+const LOCAL_ONLY_FIELDS: &[&str] = &[
+    "version",
+    "sync",
+    "autostart",
+    "proxy",
+    "ca_certificate",
+    "windows_package_manager",
+    "notifications",
+    "oauth",
+    "policy",
+    "telemetry",
+    "privacy_mode",
+    "hooks",
+    "knowledge",
+    "workspace",
+    "env_overrides",
+    "backup",
+];
+
+static SYNC_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+/// 同步设置（保存在 Manager 设置中）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SyncSettings {
+    pub enabled: bool,
+    pub target: Option<RemoteTarget>,
+    /// 同步口令（钥匙串引用），所有电脑需使用相同的口令
+    pub passphrase: String,
+    /// 同步 Provider 的 API Key（默认只同步地址和模型列表）
+    pub include_secrets: bool,
+    /// 自动同步间隔（分钟，0 表示只手动同步）
+    pub interval_minutes: u64,
+}
+
+impl Default for SyncSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            target: None,
+            passphrase: String::new(),
+            include_secrets: false,
+            interval_minutes: 30,
+        }
+    }
+}
+
+/// 向量时钟：设备 ID → 该设备修改的次数
+type VectorClock = BTreeMap<String, u64>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ClockOrder {
+    Equal,
+    /// 本机版本落后于远程
+    Before,
+    /// 本机版本包含远程的全部修改
+    After,
+    /// 两边各有对方没有的修改
+    Concurrent,
+}
+
+fn compare_clocks(local: &VectorClock, remote: &VectorClock) -> ClockOrder {
+    let devices: BTreeSet<&String> = local.keys().chain(remote.keys()).collect();
+    let (mut ahead, mut behind) = (false, false);
+    for device in devices {
+        let l = local.get(device).copied().unwrap_or(0);
+        let r = remote.get(device).copied().unwrap_or(0);
+        ahead |= l > r;
+        behind |= l < r;
+    }
+    match (ahead, behind) {
+        (false, false) => ClockOrder::Equal,
+        (false, true) => ClockOrder::Before,
+        (true, false) => ClockOrder::After,
+        (true, true) => ClockOrder::Concurrent,
+    }
+}
+
+fn merge_clocks(local: &VectorClock, remote: &VectorClock) -> VectorClock {
+    let mut merged = local.clone();
+    for (device, count) in remote {
+        let entry = merged.entry(device.clone()).or_insert(0);
+        *entry = (*entry).max(*count);
+    }
+    merged
+}
+
+/// 保存在远程存储中的同步文档（加密前）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SyncDocument {
+    clock: VectorClock,
+    device_id: String,
+    updated_at: String,
+    /// `settings.<字段>` / `providers.<名称>` → 值
+    fields: BTreeMap<String, Value>,
+}
+
+/// 两边都修改过的字段
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncConflict {
+    pub field: String,
+    /// 保留的一方：local / remote
+    pub kept: String,
+    pub detected_at: String,
+}
+
+/// 本机同步状态（保存在 Manager 数据目录的 sync_state.json，不参与同步）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct SyncState {
+    device_id: String,
+    clock: VectorClock,
+    /// 上次同步时各字段的哈希（三方合并的基准）
+    base: BTreeMap<String, String>,
+    last_synced_at: Option<String>,
+    last_error: Option<String>,
+    conflicts: Vec<SyncConflict>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncAction {
+    /// 本机较新，已上传
+    Uploaded,
+    /// 远程较新，已应用到本机
+    Downloaded,
+    /// 两边都有修改，已合并
+    Merged,
+    /// 没有需要同步的修改
+    UpToDate,
+}
+
+/// 一次同步的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncReport {
+    pub action: SyncAction,
+    pub conflicts: Vec<SyncConflict>,
+    pub synced_at: String,
+}
+
+/// 同步状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncStatus {
+    pub settings: SyncSettings,
+    /// 远程存储描述（不含密钥）
+    pub target: Option<String>,
+    pub device_id: String,
+    pub last_synced_at: Option<String>,
+    pub last_error: Option<String>,
+    /// 最近一次合并时的冲突
+    pub conflicts: Vec<SyncConflict>,
+}
+
+fn state_path() -> PathBuf {
+    Path::new(&platform::get_manager_data_dir()).join("sync_state.json")
+}
+
+fn load_state() -> SyncState {
+    let mut state: SyncState = std::fs::read_to_string(state_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+    if state.device_id.is_empty() {
+        state.device_id = uuid::Uuid::new_v4().simple().to_string();
+    }
+    state
+}
+
+fn save_state(state: &SyncState) -> Result<(), String> {
+    let content = serde_json::to_vec_pretty(state).map_err(|e| e.to_string())?;
+    file::write_atomic(&state_path(), &content).map_err(|e| format!("保存同步状态失败: {}", e))
+}
+
+/// 对象键排序后的哈希（不受字段顺序影响）
+fn hash_value(value: &Value) -> String {
+    fn canonical(value: &Value) -> Value {
+        match value {
+            Value::Object(map) => {
+                let sorted: BTreeMap<&String, Value> =
+                    map.iter().map(|(k, v)| (k, canonical(v))).collect();
+                Value::Object(sorted.into_iter().map(|(k, v)| (k.clone(), v)).collect())
+            }
+            Value::Array(items) => Value::Array(items.iter().map(canonical).collect()),
+            other => other.clone(),
+        }
+    }
+    hex::encode(Sha256::digest(canonical(value).to_string().as_bytes()))
+}
+
+fn hashes(fields: &BTreeMap<String, Value>) -> BTreeMap<String, String> {
+    fields
+        .iter()
+        .map(|(key, value)| (key.clone(), hash_value(value)))
+        .collect()
+}
+
+/// 移除 Provider 中的密钥字段
+fn strip_secrets(provider: &mut Value) {
+    if let Some(obj) = provider.as_object_mut() {
+        obj.retain(|key, _| !secret_handles::is_secret_key(key));
+    }
+}
+
+/// 本机当前需要同步的字段
+fn local_fields(include_secrets: bool) -> Result<BTreeMap<String, Value>, String> {
+    let mut fields = BTreeMap::new();
+    let current = serde_json::to_value(settings::load_settings()).map_err(|e| e.to_string())?;
+    if let Value::Object(map) = current {
+        for (key, value) in map {
+            if !LOCAL_ONLY_FIELDS.contains(&key.as_str()) {
+                fields.insert(format!("settings.{}", key), value);
+            }
+        }
+    }
+    let config = config::load_openclaw_config()?;
+    if let Some(providers) = config
+        .pointer("/models/providers")
+        .and_then(|p| p.as_object())
+    {
+        for (name, provider) in providers {
+            let mut provider = provider.clone();
+            if !include_secrets {
+                strip_secrets(&mut provider);
+            }
+            fields.insert(format!("providers.{}", name), provider);
+        }
+    }
+    Ok(fields)
+}
+
+/// 本机设置和 openclaw.json 最后修改的时间（并发修改时据此判断哪一方较新）
+fn local_modified_at() -> Option<chrono::DateTime<chrono::Utc>> {
+    let data_dir = platform::get_manager_data_dir();
+    [
+        settings::settings_path(Path::new(&data_dir)),
+        PathBuf::from(platform::get_config_file_path()),
+    ]
+    .iter()
+    .filter_map(|path| std::fs::metadata(path).and_then(|m| m.modified()).ok())
+    .max()
+    .map(chrono::DateTime::<chrono::Utc>::from)
+}
+
+/// 按字段三方合并：只有一方相对基准有修改时采用该方；两方都修改且不同则采用较新的一方并记录冲突
+fn merge_fields(
+    local: &BTreeMap<String, Value>,
+    remote: &BTreeMap<String, Value>,
+    base: &BTreeMap<String, String>,
+    remote_newer: bool,
+) -> (BTreeMap<String, Value>, Vec<SyncConflict>) {
+    let keys: BTreeSet<&String> = local.keys().chain(remote.keys()).collect();
+    let mut merged = BTreeMap::new();
+    let mut conflicts = Vec::new();
+    for key in keys {
+        let l = local.get(key);
+        let r = remote.get(key);
+        let l_hash = l.map(hash_value);
+        let r_hash = r.map(hash_value);
+        let b_hash = base.get(key).cloned();
+        let chosen = if l_hash == r_hash || r_hash == b_hash {
+            l
+        } else if l_hash == b_hash {
+            r
+        } else {
+            conflicts.push(SyncConflict {
+                field: key.clone(),
+                kept: if remote_newer { "remote" } else { "local" }.to_string(),
+                detected_at: time::now_rfc3339(),
+            });
+            if remote_newer {
+                r
+            } else {
+                l
+            }
+        };
+        if let Some(value) = chosen {
+            merged.insert(key.clone(), value.clone());
+        }
+    }
+    (merged, conflicts)
+}
+
+/// 将同步字段写回 Manager 设置和 openclaw.json
+fn apply_fields(fields: &BTreeMap<String, Value>, include_secrets: bool) -> Result<(), String> {
+    settings::update(|current| {
+        let mut value = serde_json::to_value(&*current).map_err(|e| e.to_string())?;
+        if let Some(map) = value.as_object_mut() {
+            for (key, value) in fields {
+                if let Some(field) = key.strip_prefix("settings.") {
+                    if !LOCAL_ONLY_FIELDS.contains(&field) {
+                        map.insert(field.to_string(), value.clone());
+                    }
+                }
+            }
+        }
+        *current =
+            serde_json::from_value(value).map_err(|e| format!("同步的设置格式不正确: {}", e))?;
+        Ok(())
+    })?;
+
+    let mut config = config::load_openclaw_config()?;
+    let existing = config
+        .pointer("/models/providers")
+        .and_then(|p| p.as_object())
+        .cloned()
+        .unwrap_or_default();
+    let mut providers = Map::new();
+    for (key, value) in fields {
+        let Some(name) = key.strip_prefix("providers.") else {
+            continue;
+        };
+        let mut provider = value.clone();
+        // 远程不含密钥时保留本机的 API Key
+        if !include_secrets {
+            if let (Some(target), Some(Value::Object(local))) =
+                (provider.as_object_mut(), existing.get(name))
+            {
+                for (k, v) in local {
+                    if secret_handles::is_secret_key(k) {
+                        target.insert(k.clone(), v.clone());
+                    }
+                }
+            }
+        }
+        providers.insert(name.to_string(), provider);
+    }
+    if providers.is_empty() && existing.is_empty() {
+        return Ok(());
+    }
+    if !config.is_object() {
+        config = json!({});
+    }
+    if !config.get("models").is_some_and(|m| m.is_object()) {
+        config["models"] = json!({});
+    }
+    config["models"]["providers"] = Value::Object(providers);
+    config::write_openclaw_config(&config)
+}
+
+/// 读取同步设置并解析口令
+fn load_sync_settings() -> Result<(SyncSettings, RemoteTarget, String), String> {
+    let sync = settings::load_settings().sync;
+    let target = sync.target.clone().ok_or("尚未配置同步存储位置")?;
+    if sync.passphrase.is_empty() {
+        return Err("尚未设置同步口令".to_string());
+    }
+    let passphrase = secrets::resolve_secret(&sync.passphrase)?;
+    Ok((sync, target, passphrase))
+}
+
+/// 远程同步数据被其他电脑同时修改时的最多重试次数
+const MAX_SYNC_ATTEMPTS: usize = 3;
+
+/// 执行一次同步（上传时远程数据已被其他电脑更新则重新合并）
+async fn run_sync() -> Result<SyncReport, String> {
+    let _guard = SYNC_LOCK.lock().await;
+    let (sync, target, passphrase) = load_sync_settings()?;
+    for _ in 0..MAX_SYNC_ATTEMPTS {
+        if let Some(report) = sync_once(&sync, &target, &passphrase).await? {
+            return Ok(report);
+        }
+        warn!("[设置同步] 远程同步数据已被其他电脑更新，重新同步");
+    }
+    Err("远程同步数据持续被其他电脑更新，请稍后重试".to_string())
+}
+
+/// 同步一次，上传时远程数据已被修改（条件写入失败）返回 None，本机数据保持不变
+async fn sync_once(
+    sync: &SyncSettings,
+    target: &RemoteTarget,
+    passphrase: &str,
+) -> Result<Option<SyncReport>, String> {
+    let mut state = load_state();
+    let now = time::now_rfc3339();
+
+    let local = local_fields(sync.include_secrets)?;
+    let local_changed = hashes(&local) != state.base;
+    let mut clock = state.clock.clone();
+    if local_changed || clock.is_empty() {
+        *clock.entry(state.device_id.clone()).or_insert(0) += 1;
+    }
+
+    let (remote, version) = match target.get_versioned(REMOTE_KEY).await? {
+        Some((data, version)) => {
+            let passphrase = passphrase.to_string();
+            let plaintext =
+                tokio::task::spawn_blocking(move || crypto::decrypt(&passphrase, &data))
+                    .await
+                    .map_err(|e| e.to_string())?
+                    .map_err(|e| {
+                        format!("无法解密同步数据（{}），请确认各电脑使用相同的同步口令", e)
+                    })?;
+            let document = serde_json::from_slice::<SyncDocument>(&plaintext)
+                .map_err(|e| format!("同步数据格式不正确: {}", e))?;
+            (Some(document), version)
+        }
+        None => (None, Expected::Absent),
+    };
+
+    let (action, fields, clock, conflicts) = match &remote {
+        None => (SyncAction::Uploaded, local.clone(), clock, Vec::new()),
+        Some(remote) => match compare_clocks(&clock, &remote.clock) {
+            ClockOrder::Equal => (SyncAction::UpToDate, local.clone(), clock, Vec::new()),
+            ClockOrder::After => (SyncAction::Uploaded, local.clone(), clock, Vec::new()),
+            ClockOrder::Before => (
+                SyncAction::Downloaded,
+                remote.fields.clone(),
+                remote.clock.clone(),
+                Vec::new(),
+            ),
+            ClockOrder::Concurrent => {
+                let remote_newer = chrono::DateTime::parse_from_rfc3339(&remote.updated_at)
+                    .ok()
+                    .zip(local_modified_at())
+                    .is_some_and(|(remote_at, local_at)| remote_at > local_at);
+                let (merged, conflicts) =
+                    merge_fields(&local, &remote.fields, &state.base, remote_newer);
+                let mut clock = merge_clocks(&clock, &remote.clock);
+                *clock.entry(state.device_id.clone()).or_insert(0) += 1;
+                (SyncAction::Merged, merged, clock, conflicts)
+            }
+        },
+    };
+
+    // 先上传再应用到本机：条件写入失败时本机不做任何修改，重新同步即可
+    if matches!(action, SyncAction::Uploaded | SyncAction::Merged) {
+        // 上传本机版本时记录本机的修改时间，合并结果记录当前时间
+        let updated_at = match action {
+            SyncAction::Uploaded => local_modified_at().map(|t| t.to_rfc3339()),
+            _ => None,
+        };
+        let document = SyncDocument {
+            clock: clock.clone(),
+            device_id: state.device_id.clone(),
+            updated_at: updated_at.unwrap_or_else(|| now.clone()),
+            fields: fields.clone(),
+        };
+        let plaintext = serde_json::to_vec(&document).map_err(|e| e.to_string())?;
+        let passphrase = passphrase.to_string();
+        let sealed = tokio::task::spawn_blocking(move || crypto::encrypt(&passphrase, &plaintext))
+            .await
+            .map_err(|e| e.to_string())?;
+        if !target.put_if(REMOTE_KEY, sealed, &version).await? {
+            return Ok(None);
+        }
+    }
+    if hashes(&fields) != hashes(&local) {
+        apply_fields(&fields, sync.include_secrets)?;
+    }
+
+    state.clock = clock;
+    state.base = hashes(&fields);
+    state.last_synced_at = Some(now.clone());
+    state.last_error = None;
+    if !conflicts.is_empty() {
+        state.conflicts = conflicts.clone();
+    }
+    save_state(&state)?;
+
+    if matches!(action, SyncAction::Downloaded | SyncAction::Merged) {
+        audit::record(
+            "sync_apply",
+            &target.describe(),
+            true,
+            json!({ "action": action, "conflicts": conflicts.len() }),
+        );
+    }
+    let report = SyncReport {
+        action,
+        conflicts,
+        synced_at: now,
+    };
+    if action != SyncAction::UpToDate {
+        events::publish(SYNC_APPLIED_EVENT, &report);
+    }
+    info!("[设置同步] ✓ 同步完成: {:?}", action);
+    Ok(Some(report))
+}
+
+/// 同步并记录失败原因
+async fn sync_and_record() -> Result<SyncReport, String> {
+    let result = run_sync().await;
+    if let Err(e) = &result {
+        warn!("[设置同步] 同步失败: {}", e);
+        let mut state = load_state();
+        state.last_error = Some(e.clone());
+        let _ = save_state(&state);
+    }
+    result
+}
+
+/// 后台监控定期调用：开启自动同步且距上次同步超过间隔时执行同步
+pub async fn tick() {
+    let sync = settings::load_settings().sync;
+    if !sync.enabled || sync.interval_minutes == 0 {
+        return;
+    }
+    let due = load_state()
+        .last_synced_at
+        .and_then(|t| chrono::DateTime::parse_from_rfc3339(&t).ok())
+        .is_none_or(|last| {
+            time::now().signed_duration_since(last).num_minutes() >= sync.interval_minutes as i64
+        });
+    if due {
+        let _ = sync_and_record().await;
+    }
+}
+
+fn status() -> SyncStatus {
+    let settings = settings::load_settings().sync;
+    let state = load_state();
+    SyncStatus {
+        target: settings.target.as_ref().map(RemoteTarget::describe),
+        settings,
+        device_id: state.device_id,
+        last_synced_at: state.last_synced_at,
+        last_error: state.last_error,
+        conflicts: state.conflicts,
+    }
+}
+
+/// 获取设置同步状态
+#[guarded]
+#[command]
+pub async fn get_sync_status() -> Result<SyncStatus, String> {
+    Ok(status())
+}
+
+/// 保存同步设置；`passphrase` 不为空时更新同步口令（保存在系统钥匙串中）
+#[guarded]
+#[command]
+pub async fn configure_sync(
+    mut sync: SyncSettings,
+    passphrase: Option<String>,
+) -> Result<SyncStatus, String> {
+    let current = settings::load_settings();
+    match passphrase.filter(|p| !p.is_empty()) {
+        Some(passphrase) => {
+            if passphrase.chars().count() < 8 {
+                return Err("同步口令至少需要 8 个字符".to_string());
+            }
+            sync.passphrase = secrets::store_secret(PASSPHRASE_ACCOUNT, &passphrase)?;
+        }
+        None => sync.passphrase = current.sync.passphrase.clone(),
+    }
+    if sync.enabled {
+        if sync.target.is_none() {
+            return Err("开启同步前请先配置 WebDAV 或 S3 存储".to_string());
+        }
+        if sync.passphrase.is_empty() {
+            return Err("开启同步需要设置同步口令".to_string());
+        }
+    }
+    if let Some(target) = sync.target.as_mut() {
+        target.secure_secrets(REMOTE_ACCOUNT);
+    }
+    // 切换存储位置后重新开始，避免与旧位置的向量时钟比较
+    if sync.target != current.sync.target {
+        let mut state = load_state();
+        state.clock.clear();
+        state.base.clear();
+        state.conflicts.clear();
+        state.last_synced_at = None;
+        save_state(&state)?;
+    }
+    let description = sync.target.as_ref().map(RemoteTarget::describe);
+    let details = json!({
+        "enabled": sync.enabled,
+        "include_secrets": sync.include_secrets,
+    });
+    settings::update(|current| {
+        current.sync = sync;
+        Ok(())
+    })?;
+    audit::record(
+        "configure_sync",
+        description.as_deref().unwrap_or("-"),
+        true,
+        details,
+    );
+    info!("[设置同步] ✓ 同步设置已保存");
+    Ok(status())
+}
+
+/// 立即同步
+#[guarded]
+#[command]
+pub async fn sync_now() -> Result<SyncReport, String> {
+    info!("[设置同步] 开始同步...");
+    sync_and_record().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn local_only_fields_exist_in_settings() {
+        let value = serde_json::to_value(settings::ManagerSettings::default()).unwrap();
+        for field in LOCAL_ONLY_FIELDS {
+            assert!(value.get(*field).is_some(), "设置中没有字段 {}", field);
+        }
+    }
+
+    #[test]
+    fn orders_clocks_and_merges_fields() {
+        let clock = |pairs: &[(&str, u64)]| -> VectorClock {
+            pairs.iter().map(|(d, n)| (d.to_string(), *n)).collect()
+        };
+        let a = clock(&[("mac", 2), ("pc", 1)]);
+        assert_eq!(compare_clocks(&a, &a), ClockOrder::Equal);
+        assert_eq!(
+            compare_clocks(&a, &clock(&[("mac", 2), ("pc", 3)])),
+            ClockOrder::Before
+        );
+        assert_eq!(compare_clocks(&a, &clock(&[("mac", 1)])), ClockOrder::After);
+        let b = clock(&[("mac", 1), ("pc", 2)]);
+        assert_eq!(compare_clocks(&a, &b), ClockOrder::Concurrent);
+        assert_eq!(merge_clocks(&a, &b), clock(&[("mac", 2), ("pc", 2)]));
+
+        let fields = |pairs: &[(&str, Value)]| -> BTreeMap<String, Value> {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.clone()))
+                .collect()
+        };
+        let base = fields(&[
+            ("settings.locale", json!("zh-CN")),
+            ("settings.update_channel", json!("latest")),
+            ("providers.deepseek", json!({"baseUrl": "a", "models": []})),
+            ("providers.openai", json!({"baseUrl": "o"})),
+        ]);
+        // 本机改了语言、删除了 openai；远程改了更新通道，两边都改了 deepseek
+        let local = fields(&[
+            ("settings.locale", json!("en")),
+            ("settings.update_channel", json!("latest")),
+            (
+                "providers.deepseek",
+                json!({"models": [], "baseUrl": "local"}),
+            ),
+        ]);
+        let remote = fields(&[
+            ("settings.locale", json!("zh-CN")),
+            ("settings.update_channel", json!("beta")),
+            (
+                "providers.deepseek",
+                json!({"baseUrl": "remote", "models": []}),
+            ),
+            ("providers.openai", json!({"baseUrl": "o"})),
+        ]);
+        let (merged, conflicts) = merge_fields(&local, &remote, &hashes(&base), true);
+        assert_eq!(merged["settings.locale"], json!("en"));
+        assert_eq!(merged["settings.update_channel"], json!("beta"));
+        assert!(!merged.contains_key("providers.openai"));
+        assert_eq!(merged["providers.deepseek"]["baseUrl"], json!("remote"));
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].field, "providers.deepseek");
+        assert_eq!(conflicts[0].kept, "remote");
+
+        // 字段顺序不同不算修改
+        assert_eq!(
+            hash_value(&json!({"a": 1, "b": [1, {"c": 2, "d": 3}]})),
+            hash_value(&json!({"b": [1, {"d": 3, "c": 2}], "a": 1}))
+        );
+        let mut provider = json!({"baseUrl": "x", "apiKey": "sk-1"});
+        strip_secrets(&mut provider);
+        assert_eq!(provider, json!({"baseUrl": "x"}));
+    }
+}
@@ -0,0 +1,281 @@
+use crate::commands::installer::InstallResult;
+use crate::commands::{privacy, settings};
+use crate::utils::{audit, file, http, platform, time};
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Instant;
+use openclaw_macros::guarded;
+use tauri::command;
+
+/// 本地最多保留的未发送事件
+const MAX_QUEUE: usize = 200;
+/// 每次上报的最大事件数
+const BATCH_SIZE: usize = 50;
+
+/// 保护 telemetry.json 的读写
+static STATE_LOCK: Mutex<()> = Mutex::new(());
+
+/// 匿名使用统计设置（默认关闭，需用户主动开启）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TelemetrySettings {
+    pub enabled: bool,
+    /// 上报地址（为空时只在本地记录，不发送）
+    pub endpoint: String,
+}
+
+/// 一条匿名事件：只包含结果、错误类别、系统类型和耗时，不含路径、用户名或配置内容
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TelemetryEvent {
+    /// install_nodejs / install_openclaw / update_openclaw
+    pub event: String,
+    pub success: bool,
+    /// 错误类别（见 [`error_code`]），不含原始错误信息
+    pub error_code: Option<String>,
+    pub duration_ms: u64,
+    pub os: String,
+    pub arch: String,
+    pub manager_version: String,
+    /// 日期（只精确到天）
+    pub date: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct TelemetryState {
+    /// 随机生成的匿名 ID，与设备和用户无关，关闭统计时删除
+    install_id: String,
+    queue: Vec<TelemetryEvent>,
+    last_sent: Option<String>,
+}
+
+/// 将要发送的内容预览
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TelemetryPreview {
+    pub enabled: bool,
+    pub endpoint: String,
+    /// 隐私模式下不会发送
+    pub blocked_by_privacy: bool,
+    pub pending: usize,
+    pub last_sent: Option<String>,
+    /// 下一次上报的完整请求体
+    pub payload: Value,
+}
+
+fn state_path() -> PathBuf {
+    PathBuf::from(platform::get_manager_data_dir()).join("telemetry.json")
+}
+
+fn load_state() -> TelemetryState {
+    std::fs::read_to_string(state_path())
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+fn save_state(state: &TelemetryState) -> Result<(), String> {
+    let content = serde_json::to_vec_pretty(state).map_err(|e| e.to_string())?;
+    file::write_atomic(&state_path(), &content).map_err(|e| format!("保存统计数据失败: {}", e))
+}
+
+/// 把错误信息归类为固定的错误码，避免上报路径、用户名等信息
+pub fn error_code(message: &str) -> String {
+    let lower = message.to_lowercase();
+    let patterns: &[(&[&str], &str)] = &[
+        (
+            &["eacces", "eperm", "permission denied", "权限"],
+            "permission_denied",
+        ),
+        (&["enotfound", "eai_again", "getaddrinfo"], "dns"),
+        (&["etimedout", "timed out", "timeout", "超时"], "timeout"),
+        (
+            &["econnrefused", "econnreset", "socket hang up"],
+            "connection",
+        ),
+        (
+            &["certificate", "self_signed", "unable_to_verify", "cert_"],
+            "tls_certificate",
+        ),
+        (&["enospc", "no space left", "磁盘空间"], "disk_full"),
+        (&["eintegrity", "checksum", "校验"], "integrity"),
+        (&["e404", "404 not found"], "not_found"),
+        (&["hook", "钩子"], "hook_failed"),
+        (&["不支持的操作系统"], "unsupported_os"),
+    ];
+    patterns
+        .iter()
+        .find(|(needles, _)| needles.iter().any(|n| lower.contains(n)))
+        .map(|(_, code)| code.to_string())
+        .unwrap_or_else(|| "other".to_string())
+}
+
+/// 统计是否开启（隐私模式下始终关闭）
+fn active(telemetry: &TelemetrySettings) -> bool {
+    telemetry.enabled && !privacy::is_enabled()
+}
+
+/// 记录一次安装 / 更新的结果（未开启统计时不做任何事）
+pub fn record_install(event: &str, started: Instant, result: &Result<InstallResult, String>) {
+    if !active(&settings::load_settings().telemetry) {
+        return;
+    }
+    let (success, error) = match result {
+        Ok(r) if r.success => (true, None),
+        Ok(r) => (
+            false,
+            Some(r.error.clone().unwrap_or_else(|| r.message.clone())),
+        ),
+        Err(e) => (false, Some(e.clone())),
+    };
+    let event = TelemetryEvent {
+        event: event.to_string(),
+        success,
+        error_code: error.as_deref().map(error_code),
+        duration_ms: started.elapsed().as_millis() as u64,
+        os: platform::get_os(),
+        arch: std::env::consts::ARCH.to_string(),
+        manager_version: env!("CARGO_PKG_VERSION").to_string(),
+        date: chrono::Utc::now().format("%Y-%m-%d").to_string(),
+    };
+    let _guard = STATE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut state = load_state();
+    if state.install_id.is_empty() {
+        state.install_id = uuid::Uuid::new_v4().to_string();
+    }
+    state.queue.push(event);
+    let overflow = state.queue.len().saturating_sub(MAX_QUEUE);
+    state.queue.drain(..overflow);
+    if let Err(e) = save_state(&state) {
+        warn!("[使用统计] {}", e);
+    }
+}
+
+/// 上报请求体
+fn payload(state: &TelemetryState) -> Value {
+    let batch: Vec<&TelemetryEvent> = state.queue.iter().take(BATCH_SIZE).collect();
+    json!({
+        "schema": 1,
+        "install_id": state.install_id,
+        "events": batch,
+    })
+}
+
+/// 发送排队的事件（后台监控定期调用）
+pub async fn flush() {
+    let telemetry = settings::load_settings().telemetry;
+    let endpoint = telemetry.endpoint.trim().to_string();
+    if !active(&telemetry) || endpoint.is_empty() {
+        return;
+    }
+    let state = {
+        let _guard = STATE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        load_state()
+    };
+    if state.queue.is_empty() {
+        return;
+    }
+    let body = payload(&state);
+    let sent = body["events"].as_array().map(|e| e.len()).unwrap_or(0);
+    let client = match http::client() {
+        Ok(c) => c,
+        Err(e) => {
+            warn!("[使用统计] {}", e);
+            return;
+        }
+    };
+    match client.post(&endpoint).json(&body).send().await {
+        Ok(resp) if resp.status().is_success() => {
+            let _guard = STATE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+            let mut state = load_state();
+            let n = sent.min(state.queue.len());
+            state.queue.drain(..n);
+            state.last_sent = Some(time::now_rfc3339());
+            match save_state(&state) {
+                Ok(()) => info!("[使用统计] ✓ 已发送 {} 条事件", n),
+                Err(e) => warn!("[使用统计] {}", e),
+            }
+        }
+        Ok(resp) => debug!("[使用统计] 上报失败: HTTP {}", resp.status()),
+        Err(e) => debug!("[使用统计] 上报失败: {}", e),
+    }
+}
+
+/// 查看使用统计设置和下一次将发送的完整内容
+#[guarded]
+#[command]
+pub async fn get_telemetry_preview() -> Result<TelemetryPreview, String> {
+    let telemetry = settings::load_settings().telemetry;
+    let state = load_state();
+    Ok(TelemetryPreview {
+        enabled: telemetry.enabled,
+        endpoint: telemetry.endpoint,
+        blocked_by_privacy: privacy::is_enabled(),
+        pending: state.queue.len(),
+        last_sent: state.last_sent.clone(),
+        payload: payload(&state),
+    })
+}
+
+/// 开启 / 关闭匿名使用统计；关闭时删除本地记录和匿名 ID
+#[guarded]
+#[command]
+pub async fn set_telemetry(
+    enabled: bool,
+    endpoint: Option<String>,
+) -> Result<TelemetryPreview, String> {
+    info!(
+        "[使用统计] {}匿名使用统计",
+        if enabled { "开启" } else { "关闭" }
+    );
+    let endpoint = endpoint.map(|e| e.trim().to_string());
+    if let Some(endpoint) = endpoint.as_deref() {
+        if !endpoint.is_empty() && reqwest::Url::parse(endpoint).is_err() {
+            return Err(format!("上报地址无效: {}", endpoint));
+        }
+    }
+    settings::update(|settings| {
+        settings.telemetry.enabled = enabled;
+        if let Some(endpoint) = endpoint {
+            settings.telemetry.endpoint = endpoint;
+        }
+        Ok(())
+    })?;
+    if !enabled {
+        let _guard = STATE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let _ = std::fs::remove_file(state_path());
+    }
+    audit::record(
+        "set_telemetry",
+        "telemetry",
+        true,
+        json!({ "enabled": enabled }),
+    );
+    Ok(get_telemetry_preview().await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn errors_are_reduced_to_codes() {
+        assert_eq!(
+            error_code("npm ERR! code EACCES\nnpm ERR! path /Users/alice/.npm"),
+            "permission_denied"
+        );
+        assert_eq!(
+            error_code(
+                "request to https://registry.npmjs.org failed, reason: getaddrinfo ENOTFOUND"
+            ),
+            "dns"
+        );
+        assert_eq!(
+            error_code("unable to get local issuer certificate"),
+            "tls_certificate"
+        );
+        assert_eq!(error_code("C:\\Users\\bob\\secret.txt 出错"), "other");
+    }
+}
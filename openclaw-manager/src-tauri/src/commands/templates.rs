@@ -0,0 +1,401 @@
+use crate::commands::settings;
+use crate::utils::audit;
+use log::info;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::{BTreeMap, HashMap};
+use openclaw_macros::guarded;
+use tauri::command;
+
+/// 模板用途
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TemplateKind {
+    /// 通知（如网关异常、任务完成）
+    Notification,
+    /// 自动回复
+    AutoReply,
+}
+
+/// 渠道消息模板：按语言保存内容，`{{name}}` 为变量占位符
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MessageTemplate {
+    pub id: String,
+    pub kind: TemplateKind,
+    /// 渠道（telegram / feishu 等，`*` 表示所有渠道）
+    pub channel: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// 声明的变量，内容中只能使用这些变量
+    #[serde(default)]
+    pub variables: Vec<String>,
+    /// 语言 → 模板内容
+    pub locales: BTreeMap<String, String>,
+    /// 请求的语言没有对应内容时使用的语言
+    pub default_locale: String,
+}
+
+/// 模板检查结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TemplateIssue {
+    /// 所在语言（None 表示模板本身的问题）
+    pub locale: Option<String>,
+    /// error 阻止保存，warning 只提示
+    pub severity: String,
+    pub message: String,
+}
+
+/// 预览结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplatePreview {
+    /// 实际使用的语言
+    pub locale: String,
+    pub text: String,
+    /// 没有提供值的变量（在预览中保留占位符）
+    pub missing: Vec<String>,
+}
+
+/// 模板内容的片段
+#[derive(Debug, PartialEq)]
+enum Segment<'a> {
+    Text(&'a str),
+    Var(&'a str),
+}
+
+fn valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+}
+
+/// 解析 `{{ name }}` 占位符
+fn parse(body: &str) -> Result<Vec<Segment<'_>>, String> {
+    let mut segments = Vec::new();
+    let mut rest = body;
+    while let Some(start) = rest.find("{{") {
+        if start > 0 {
+            segments.push(Segment::Text(&rest[..start]));
+        }
+        let after = &rest[start + 2..];
+        let end = after.find("}}").ok_or_else(|| {
+            format!(
+                "第 {} 个字符处的 {{{{ 没有闭合",
+                body[..body.len() - rest.len() + start].chars().count() + 1
+            )
+        })?;
+        let name = after[..end].trim();
+        if !valid_name(name) {
+            return Err(format!(
+                "变量名 \"{}\" 无效（只能包含字母、数字、_ 和 .）",
+                name
+            ));
+        }
+        segments.push(Segment::Var(name));
+        rest = &after[end + 2..];
+    }
+    if !rest.is_empty() {
+        segments.push(Segment::Text(rest));
+    }
+    Ok(segments)
+}
+
+/// 检查模板：变量未声明、占位符语法错误为 error，声明了但某个语言没有使用为 warning
+pub fn validate(template: &MessageTemplate) -> Vec<TemplateIssue> {
+    let mut issues = Vec::new();
+    let mut error = |locale: Option<&str>, message: String| {
+        issues.push(TemplateIssue {
+            locale: locale.map(String::from),
+            severity: "error".to_string(),
+            message,
+        })
+    };
+    if !valid_name(&template.id) {
+        error(None, "模板 ID 只能包含字母、数字、_ 和 .".to_string());
+    }
+    if template.channel.trim().is_empty() {
+        error(None, "请选择渠道".to_string());
+    }
+    for (i, name) in template.variables.iter().enumerate() {
+        if !valid_name(name) {
+            error(None, format!("变量名 \"{}\" 无效", name));
+        } else if template.variables[..i].contains(name) {
+            error(None, format!("变量 {} 重复声明", name));
+        }
+    }
+    if template.locales.is_empty() {
+        error(None, "至少需要一种语言的内容".to_string());
+    } else if !template.locales.contains_key(&template.default_locale) {
+        error(
+            None,
+            format!("默认语言 {} 没有对应的内容", template.default_locale),
+        );
+    }
+
+    let mut warnings = Vec::new();
+    for (locale, body) in &template.locales {
+        if body.trim().is_empty() {
+            error(Some(locale), "内容为空".to_string());
+            continue;
+        }
+        let segments = match parse(body) {
+            Ok(segments) => segments,
+            Err(e) => {
+                error(Some(locale), e);
+                continue;
+            }
+        };
+        let used: Vec<&str> = segments
+            .iter()
+            .filter_map(|s| match s {
+                Segment::Var(name) => Some(*name),
+                Segment::Text(_) => None,
+            })
+            .collect();
+        for name in &used {
+            if !template.variables.iter().any(|v| v == name) {
+                error(Some(locale), format!("使用了未声明的变量 {}", name));
+            }
+        }
+        for name in &template.variables {
+            if !used.contains(&name.as_str()) {
+                warnings.push(TemplateIssue {
+                    locale: Some(locale.clone()),
+                    severity: "warning".to_string(),
+                    message: format!("没有使用变量 {}", name),
+                });
+            }
+        }
+    }
+    issues.extend(warnings);
+    issues
+}
+
+/// 选择语言：完全匹配 → 同一语种（zh-TW 使用 zh / zh-CN）→ 默认语言
+fn pick_locale<'a>(template: &'a MessageTemplate, requested: &str) -> Option<&'a str> {
+    let language = requested.split(['-', '_']).next().unwrap_or_default();
+    template
+        .locales
+        .keys()
+        .find(|l| l.eq_ignore_ascii_case(requested))
+        .or_else(|| {
+            template.locales.keys().find(|l| {
+                l.split(['-', '_'])
+                    .next()
+                    .is_some_and(|lang| lang.eq_ignore_ascii_case(language))
+            })
+        })
+        .or_else(|| {
+            template
+                .locales
+                .get_key_value(&template.default_locale)
+                .map(|(k, _)| k)
+        })
+        .map(String::as_str)
+}
+
+/// 用变量渲染模板，缺少值的变量保留占位符
+pub fn render(
+    template: &MessageTemplate,
+    locale: &str,
+    vars: &HashMap<String, String>,
+) -> Result<TemplatePreview, String> {
+    let locale = pick_locale(template, locale)
+        .ok_or_else(|| format!("模板 {} 没有可用的语言", template.id))?;
+    let segments = parse(&template.locales[locale])?;
+    let mut text = String::new();
+    let mut missing: Vec<String> = Vec::new();
+    for segment in segments {
+        match segment {
+            Segment::Text(t) => text.push_str(t),
+            Segment::Var(name) => match vars.get(name) {
+                Some(value) => text.push_str(value),
+                None => {
+                    text.push_str(&format!("{{{{{}}}}}", name));
+                    if !missing.iter().any(|m| m == name) {
+                        missing.push(name.to_string());
+                    }
+                }
+            },
+        }
+    }
+    Ok(TemplatePreview {
+        locale: locale.to_string(),
+        text,
+        missing,
+    })
+}
+
+/// 查找模板（渠道专用模板优先于 `*`）
+pub fn find(id: &str, channel: &str) -> Option<MessageTemplate> {
+    let templates = settings::load_settings().templates;
+    templates
+        .iter()
+        .find(|t| t.id == id && t.channel == channel)
+        .or_else(|| templates.iter().find(|t| t.id == id && t.channel == "*"))
+        .cloned()
+}
+
+/// 获取所有消息模板
+#[guarded]
+#[command]
+pub async fn list_message_templates() -> Result<Vec<MessageTemplate>, String> {
+    Ok(settings::load_settings().templates)
+}
+
+/// 检查模板（界面编辑时实时提示）
+#[guarded]
+#[command]
+pub async fn validate_message_template(
+    template: MessageTemplate,
+) -> Result<Vec<TemplateIssue>, String> {
+    Ok(validate(&template))
+}
+
+/// 保存模板（同一 ID 和渠道的模板会被替换），存在错误时拒绝保存
+#[guarded]
+#[command]
+pub async fn save_message_template(template: MessageTemplate) -> Result<String, String> {
+    let errors: Vec<String> = validate(&template)
+        .into_iter()
+        .filter(|i| i.severity == "error")
+        .map(|i| match i.locale {
+            Some(locale) => format!("[{}] {}", locale, i.message),
+            None => i.message,
+        })
+        .collect();
+    if !errors.is_empty() {
+        return Err(format!("模板有误: {}", errors.join("；")));
+    }
+    settings::update(|current| {
+        current
+            .templates
+            .retain(|t| !(t.id == template.id && t.channel == template.channel));
+        current.templates.push(template.clone());
+        Ok(())
+    })?;
+    audit::record(
+        "save_message_template",
+        &template.id,
+        true,
+        json!({ "channel": template.channel, "locales": template.locales.keys().collect::<Vec<_>>() }),
+    );
+    info!("[消息模板] ✓ 已保存模板 {} ({})", template.id, template.channel);
+    Ok(format!("模板 {} 已保存", template.id))
+}
+
+/// 删除模板
+#[guarded]
+#[command]
+pub async fn delete_message_template(id: String, channel: String) -> Result<String, String> {
+    settings::update(|current| {
+        let before = current.templates.len();
+        current
+            .templates
+            .retain(|t| !(t.id == id && t.channel == channel));
+        if current.templates.len() == before {
+            return Err(format!("模板 {} ({}) 不存在", id, channel));
+        }
+        Ok(())
+    })?;
+    audit::record(
+        "delete_message_template",
+        &id,
+        true,
+        json!({ "channel": channel }),
+    );
+    Ok(format!("模板 {} 已删除", id))
+}
+
+/// 预览模板渲染结果：`draft` 为正在编辑、尚未保存的模板，
+/// 未提供时按 ID 和渠道读取已保存的模板；`locale` 为空时使用界面语言
+#[guarded]
+#[command]
+pub async fn render_template_preview(
+    id: String,
+    vars: HashMap<String, String>,
+    channel: Option<String>,
+    locale: Option<String>,
+    draft: Option<MessageTemplate>,
+) -> Result<TemplatePreview, String> {
+    let template = match draft {
+        Some(draft) => draft,
+        None => find(&id, channel.as_deref().unwrap_or("*"))
+            .ok_or_else(|| format!("模板 {} 不存在", id))?,
+    };
+    let locale = locale.unwrap_or_else(|| settings::load_settings().locale);
+    render(&template, &locale, &vars)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn template() -> MessageTemplate {
+        MessageTemplate {
+            id: "gateway_down".to_string(),
+            kind: TemplateKind::Notification,
+            channel: "telegram".to_string(),
+            description: None,
+            variables: vec!["host".to_string(), "minutes".to_string()],
+            locales: BTreeMap::from([
+                (
+                    "zh-CN".to_string(),
+                    "{{host}} 上的网关已停止 {{ minutes }} 分钟".to_string(),
+                ),
+                (
+                    "en".to_string(),
+                    "Gateway on {{host}} has been down for {{minutes}} min".to_string(),
+                ),
+            ]),
+            default_locale: "zh-CN".to_string(),
+        }
+    }
+
+    #[test]
+    fn validates_and_renders_with_locale_fallback() {
+        let t = template();
+        assert!(validate(&t).is_empty());
+
+        let vars = HashMap::from([("host".to_string(), "mac-mini".to_string())]);
+        let preview = render(&t, "en-US", &vars).unwrap();
+        assert_eq!(preview.locale, "en");
+        assert_eq!(
+            preview.text,
+            "Gateway on mac-mini has been down for {{minutes}} min"
+        );
+        assert_eq!(preview.missing, vec!["minutes"]);
+        assert_eq!(render(&t, "zh-TW", &vars).unwrap().locale, "zh-CN");
+        assert_eq!(render(&t, "ja", &vars).unwrap().locale, "zh-CN");
+
+        let mut broken = template();
+        broken.default_locale = "fr".to_string();
+        broken.variables.push("unused".to_string());
+        broken.locales.insert(
+            "ja".to_string(),
+            "{{host}} {{ port }} {{minutes".to_string(),
+        );
+        broken.locales.insert(
+            "ko".to_string(),
+            "{{host}} {{port}} {{minutes}}".to_string(),
+        );
+        let issues: Vec<(Option<String>, String, String)> = validate(&broken)
+            .into_iter()
+            .map(|i| (i.locale, i.severity, i.message))
+            .collect();
+        assert!(issues.contains(&(None, "error".into(), "默认语言 fr 没有对应的内容".into())));
+        assert!(issues
+            .iter()
+            .any(|(l, s, m)| l.as_deref() == Some("ja") && s == "error" && m.contains("没有闭合")));
+        assert!(issues.contains(&(
+            Some("ko".into()),
+            "error".into(),
+            "使用了未声明的变量 port".into()
+        )));
+        assert!(issues.contains(&(
+            Some("en".into()),
+            "warning".into(),
+            "没有使用变量 unused".into()
+        )));
+    }
+}
@@ -0,0 +1,292 @@
+use crate::commands::logs::{self, LogEntry, LogLevel};
+use crate::commands::{analytics, sessions};
+use crate::utils::platform;
+use log::info;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::Path;
+use openclaw_macros::guarded;
+use tauri::command;
+
+/// 回复结束后仍纳入时间线的日志时间（毫秒）
+const LOG_GRACE_MS: i64 = 2_000;
+
+/// 时间线步骤类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TraceStepKind {
+    /// 用户消息（时间线起点）
+    UserMessage,
+    /// 调用 AI Provider 生成回复
+    ProviderCall,
+    /// 技能 / 工具调用
+    SkillCall,
+    /// 相关的 Gateway 日志
+    Log,
+}
+
+/// 时间线中的一步
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TraceStep {
+    pub kind: TraceStepKind,
+    pub label: String,
+    /// 相对用户消息的开始时间
+    pub offset_ms: i64,
+    /// 耗时（日志和起点为 None）
+    pub duration_ms: Option<i64>,
+    pub is_error: bool,
+    pub detail: Option<String>,
+}
+
+/// 单条消息的处理过程
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RequestTrace {
+    pub session_id: String,
+    pub message_id: String,
+    pub started_at: String,
+    /// 从用户消息到最后一条回复的总耗时
+    pub total_ms: i64,
+    /// 按开始时间排序的步骤
+    pub steps: Vec<TraceStep>,
+    /// 耗时最长的一步
+    pub slowest: Option<String>,
+}
+
+fn event_id(event: &Value) -> Option<&str> {
+    event
+        .get("id")
+        .or_else(|| event.get("message").and_then(|m| m.get("id")))
+        .and_then(|v| v.as_str())
+}
+
+fn event_ts(event: &Value) -> Option<i64> {
+    let message = event.get("message").unwrap_or(event);
+    analytics::parse_ts(event.get("timestamp"))
+        .or_else(|| analytics::parse_ts(message.get("timestamp")))
+}
+
+fn str_field<'a>(value: &'a Value, key: &str) -> Option<&'a str> {
+    value.get(key).and_then(|v| v.as_str())
+}
+
+/// 根据会话事件和 Gateway 日志生成时间线
+fn build_timeline(
+    events: &[Value],
+    session_id: &str,
+    message_id: &str,
+    log_entries: &[LogEntry],
+) -> Result<RequestTrace, String> {
+    let start = events
+        .iter()
+        .position(|e| event_id(e) == Some(message_id))
+        .ok_or_else(|| format!("会话中未找到消息 {}", message_id))?;
+    let t0 = event_ts(&events[start]).ok_or("消息缺少时间戳")?;
+
+    let mut steps = vec![TraceStep {
+        kind: TraceStepKind::UserMessage,
+        label: "收到消息".to_string(),
+        offset_ms: 0,
+        duration_ms: None,
+        is_error: false,
+        detail: None,
+    }];
+    // toolCallId -> (技能名, 调用时间)
+    let mut pending_tools: HashMap<String, (String, i64)> = HashMap::new();
+    let mut prev = t0;
+    let mut end = t0;
+
+    for event in &events[start + 1..] {
+        let message = event.get("message").unwrap_or(event);
+        let Some(role) = str_field(message, "role") else {
+            continue;
+        };
+        if role == "user" {
+            break;
+        }
+        let Some(ts) = event_ts(event) else {
+            continue;
+        };
+        match role {
+            "assistant" => {
+                let label = match (str_field(message, "provider"), str_field(message, "model")) {
+                    (Some(p), Some(m)) => format!("{}/{}", p, m),
+                    (p, m) => p.or(m).unwrap_or("AI 回复").to_string(),
+                };
+                let error = str_field(message, "errorMessage").map(|s| s.to_string());
+                steps.push(TraceStep {
+                    kind: TraceStepKind::ProviderCall,
+                    label,
+                    offset_ms: prev - t0,
+                    duration_ms: Some(ts - prev),
+                    is_error: error.is_some() || str_field(message, "stopReason") == Some("error"),
+                    detail: error,
+                });
+                for part in message
+                    .get("content")
+                    .and_then(|c| c.as_array())
+                    .into_iter()
+                    .flatten()
+                {
+                    if !matches!(str_field(part, "type"), Some("toolCall") | Some("tool_use")) {
+                        continue;
+                    }
+                    if let (Some(id), Some(name)) = (str_field(part, "id"), str_field(part, "name"))
+                    {
+                        pending_tools.insert(id.to_string(), (name.to_string(), ts));
+                    }
+                }
+            }
+            "toolResult" | "tool" => {
+                let call_id = str_field(message, "toolCallId").unwrap_or("");
+                let (name, started) = pending_tools.remove(call_id).unwrap_or_else(|| {
+                    let name = str_field(message, "toolName").unwrap_or("工具");
+                    (name.to_string(), prev)
+                });
+                steps.push(TraceStep {
+                    kind: TraceStepKind::SkillCall,
+                    label: name,
+                    offset_ms: started - t0,
+                    duration_ms: Some(ts - started),
+                    is_error: message.get("isError").and_then(|v| v.as_bool()) == Some(true),
+                    detail: None,
+                });
+            }
+            _ => {}
+        }
+        prev = ts;
+        end = ts;
+    }
+
+    // 未返回结果的技能调用
+    for (name, started) in pending_tools.into_values() {
+        steps.push(TraceStep {
+            kind: TraceStepKind::SkillCall,
+            label: name,
+            offset_ms: started - t0,
+            duration_ms: None,
+            is_error: true,
+            detail: Some("未返回结果".to_string()),
+        });
+    }
+
+    // 相关日志：提到该会话 / 消息，或处理期间的警告和错误
+    for entry in log_entries {
+        let Some(ms) = logs::entry_millis(entry) else {
+            continue;
+        };
+        if ms < t0 || ms > end + LOG_GRACE_MS {
+            continue;
+        }
+        let related = entry.message.contains(session_id)
+            || entry.message.contains(message_id)
+            || entry.request_id.as_deref() == Some(session_id);
+        if !related && entry.level < LogLevel::Warn {
+            continue;
+        }
+        steps.push(TraceStep {
+            kind: TraceStepKind::Log,
+            label: entry
+                .module
+                .clone()
+                .unwrap_or_else(|| "gateway".to_string()),
+            offset_ms: ms - t0,
+            duration_ms: None,
+            is_error: entry.level >= LogLevel::Error,
+            detail: Some(entry.message.clone()),
+        });
+    }
+
+    steps.sort_by_key(|s| s.offset_ms);
+    let slowest = steps
+        .iter()
+        .filter(|s| s.duration_ms.is_some())
+        .max_by_key(|s| s.duration_ms)
+        .map(|s| s.label.clone());
+    let started_at = chrono::DateTime::from_timestamp_millis(t0)
+        .map(|t| t.to_rfc3339())
+        .unwrap_or_default();
+    Ok(RequestTrace {
+        session_id: session_id.to_string(),
+        message_id: message_id.to_string(),
+        started_at,
+        total_ms: end - t0,
+        steps,
+        slowest,
+    })
+}
+
+/// 追踪一条消息的处理过程：AI 调用、技能调用和相关 Gateway 日志
+#[guarded]
+#[command]
+pub async fn trace_request(session_id: String, message_id: String) -> Result<RequestTrace, String> {
+    info!("[请求追踪] 追踪会话 {} 的消息 {}", session_id, message_id);
+    let config_dir = platform::get_config_dir();
+    let path = sessions::find_session_file(Path::new(&config_dir), &session_id)
+        .ok_or_else(|| format!("未找到会话: {}", session_id))?;
+    let content = std::fs::read_to_string(&path).map_err(|e| format!("读取会话失败: {}", e))?;
+    let events: Vec<Value> = content
+        .lines()
+        .filter_map(|l| serde_json::from_str(l).ok())
+        .collect();
+    let (_, entries) = tokio::task::spawn_blocking(logs::read_entries)
+        .await
+        .map_err(|e| format!("读取日志失败: {}", e))?;
+    let trace = build_timeline(&events, &session_id, &message_id, &entries)?;
+    info!(
+        "[请求追踪] ✓ {} 步，总耗时 {}ms",
+        trace.steps.len(),
+        trace.total_ms
+    );
+    Ok(trace)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn timeline_covers_provider_calls_skills_and_logs() {
+        let events = vec![
+            json!({"type": "session", "id": "s1"}),
+            json!({"id": "m1", "timestamp": "2026-03-01T10:00:00Z",
+                   "message": {"role": "user", "content": "weather?"}}),
+            json!({"id": "m2", "timestamp": "2026-03-01T10:00:02Z",
+                   "message": {"role": "assistant", "provider": "anthropic", "model": "claude",
+                               "content": [{"type": "toolCall", "id": "t1", "name": "weather"}]}}),
+            json!({"id": "m3", "timestamp": "2026-03-01T10:00:07Z",
+                   "message": {"role": "toolResult", "toolCallId": "t1", "content": []}}),
+            json!({"id": "m4", "timestamp": "2026-03-01T10:00:08Z",
+                   "message": {"role": "assistant", "provider": "anthropic", "model": "claude",
+                               "content": [{"type": "text", "text": "sunny"}]}}),
+            json!({"id": "m5", "timestamp": "2026-03-01T10:05:00Z",
+                   "message": {"role": "user", "content": "thanks"}}),
+        ];
+        let log_entries: Vec<LogEntry> = [
+            "2026-03-01T10:00:03Z [skills] warn: weather api slow",
+            "2026-03-01T10:00:04Z [gateway] info: heartbeat",
+            "2026-03-01T10:04:00Z [gateway] error: unrelated later failure",
+        ]
+        .iter()
+        .filter_map(|l| logs::parse_line(l))
+        .collect();
+
+        let trace = build_timeline(&events, "s1", "m1", &log_entries).unwrap();
+        assert_eq!(trace.total_ms, 8_000);
+        let kinds: Vec<TraceStepKind> = trace.steps.iter().map(|s| s.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                TraceStepKind::UserMessage,
+                TraceStepKind::ProviderCall,
+                TraceStepKind::SkillCall,
+                TraceStepKind::Log,
+                TraceStepKind::ProviderCall,
+            ]
+        );
+        assert_eq!(trace.steps[2].duration_ms, Some(5_000));
+        assert_eq!(trace.slowest.as_deref(), Some("weather"));
+        assert!(build_timeline(&events, "s1", "missing", &[]).is_err());
+    }
+}
@@ -0,0 +1,186 @@
+//! 界面偏好：主题、字体缩放和减少动画保存在 Manager 设置中，
+//! 修改后推送给所有窗口，主窗口和独立控制台保持一致；跟随系统主题时由后端检测系统主题变化
+use crate::commands::{events, settings, window_state};
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use openclaw_macros::guarded;
+use tauri::{command, AppHandle, Manager, Theme, Window, WindowEvent};
+
+/// 界面偏好变化事件（发送给所有窗口）
+pub const UI_PREFERENCES_EVENT: &str = "ui-preferences-changed";
+
+/// 字体缩放范围
+const FONT_SCALE_RANGE: (f64, f64) = (0.75, 2.0);
+
+/// 主题
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ThemePreference {
+    Light,
+    Dark,
+    /// 跟随系统
+    #[default]
+    System,
+}
+
+/// 实际使用的主题
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResolvedTheme {
+    Light,
+    Dark,
+}
+
+/// 界面偏好（保存在 settings.json 的 ui 字段）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UiPreferences {
+    pub theme: ThemePreference,
+    /// 字体缩放（1.0 为默认大小）
+    pub font_scale: f64,
+    /// 减少动画
+    pub reduced_motion: bool,
+}
+
+impl Default for UiPreferences {
+    fn default() -> Self {
+        Self {
+            theme: ThemePreference::System,
+            font_scale: 1.0,
+            reduced_motion: false,
+        }
+    }
+}
+
+/// 推送给前端的界面偏好（附带实际使用的主题）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UiPreferencesState {
+    #[serde(flatten)]
+    pub preferences: UiPreferences,
+    pub resolved_theme: ResolvedTheme,
+    /// 检测到的系统主题（尚未检测到时为空）
+    pub system_theme: Option<ResolvedTheme>,
+}
+
+/// 最近检测到的系统主题
+static SYSTEM_THEME: Mutex<Option<ResolvedTheme>> = Mutex::new(None);
+
+fn system_theme() -> Option<ResolvedTheme> {
+    *SYSTEM_THEME.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn to_resolved(theme: Theme) -> ResolvedTheme {
+    match theme {
+        Theme::Dark => ResolvedTheme::Dark,
+        _ => ResolvedTheme::Light,
+    }
+}
+
+fn resolve(theme: ThemePreference, system: Option<ResolvedTheme>) -> ResolvedTheme {
+    match theme {
+        ThemePreference::Light => ResolvedTheme::Light,
+        ThemePreference::Dark => ResolvedTheme::Dark,
+        ThemePreference::System => system.unwrap_or(ResolvedTheme::Light),
+    }
+}
+
+fn validate(preferences: &UiPreferences) -> Result<(), String> {
+    let (min, max) = FONT_SCALE_RANGE;
+    if !(min..=max).contains(&preferences.font_scale) {
+        return Err(format!("字体缩放需在 {} 到 {} 之间", min, max));
+    }
+    Ok(())
+}
+
+fn current_state() -> UiPreferencesState {
+    let preferences = settings::load_settings().ui;
+    let system = system_theme();
+    UiPreferencesState {
+        resolved_theme: resolve(preferences.theme, system),
+        preferences,
+        system_theme: system,
+    }
+}
+
+/// 启动时读取系统主题
+pub fn init(app: &AppHandle) {
+    if let Some(theme) = app
+        .get_webview_window(window_state::MAIN_WINDOW)
+        .and_then(|w| w.theme().ok())
+    {
+        *SYSTEM_THEME.lock().unwrap_or_else(|e| e.into_inner()) = Some(to_resolved(theme));
+    }
+}
+
+/// 窗口事件：系统主题变化时更新，跟随系统主题时通知所有窗口
+pub fn on_window_event(window: &Window, event: &WindowEvent) {
+    let WindowEvent::ThemeChanged(theme) = event else {
+        return;
+    };
+    let theme = to_resolved(*theme);
+    {
+        let mut system = SYSTEM_THEME.lock().unwrap_or_else(|e| e.into_inner());
+        if *system == Some(theme) {
+            return;
+        }
+        *system = Some(theme);
+    }
+    let state = current_state();
+    if state.preferences.theme == ThemePreference::System {
+        info!("[界面偏好] 系统主题已切换为 {:?}", theme);
+        let _ = events::emit(window.app_handle(), UI_PREFERENCES_EVENT, &state);
+    }
+}
+
+/// 获取界面偏好
+#[guarded]
+#[command]
+pub async fn get_ui_preferences() -> Result<UiPreferencesState, String> {
+    Ok(current_state())
+}
+
+/// 保存界面偏好并通知所有窗口
+#[guarded]
+#[command]
+pub async fn set_ui_preferences(
+    app: AppHandle,
+    preferences: UiPreferences,
+) -> Result<UiPreferencesState, String> {
+    validate(&preferences)?;
+    settings::update(|current| {
+        current.ui = preferences;
+        Ok(())
+    })?;
+    let state = current_state();
+    let _ = events::emit(&app, UI_PREFERENCES_EVENT, &state);
+    info!(
+        "[界面偏好] ✓ 已保存: 主题 {:?}，字体缩放 {}，减少动画 {}",
+        state.preferences.theme, state.preferences.font_scale, state.preferences.reduced_motion
+    );
+    Ok(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_theme_and_validates_font_scale() {
+        let dark = Some(ResolvedTheme::Dark);
+        assert_eq!(resolve(ThemePreference::System, dark), ResolvedTheme::Dark);
+        assert_eq!(resolve(ThemePreference::System, None), ResolvedTheme::Light);
+        assert_eq!(resolve(ThemePreference::Light, dark), ResolvedTheme::Light);
+
+        let mut preferences = UiPreferences::default();
+        assert!(validate(&preferences).is_ok());
+        preferences.font_scale = 3.0;
+        assert!(validate(&preferences).is_err());
+        preferences.font_scale = f64::NAN;
+        assert!(validate(&preferences).is_err());
+
+        let parsed: UiPreferences = serde_json::from_str(r#"{"theme":"dark"}"#).unwrap();
+        assert_eq!(parsed.theme, ThemePreference::Dark);
+        assert_eq!(parsed.font_scale, 1.0);
+    }
+}
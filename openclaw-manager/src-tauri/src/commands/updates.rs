@@ -0,0 +1,527 @@
+use crate::commands::hooks::{self, HookEvent};
+use crate::commands::installer::{self, InstallResult};
+use crate::commands::jobs::{self, JobKind};
+use crate::commands::notifications::{self, NotificationTrigger};
+use crate::commands::{
+    compat, downloads, events, install_timings, monitor, privacy, service, settings,
+    source_build,
+};
+use crate::utils::{audit, file, http, platform, shell, time};
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use openclaw_macros::guarded;
+use tauri::{command, AppHandle};
+
+/// 更新后健康检查失败并回滚时发送的事件
+pub const UPDATE_ROLLED_BACK_EVENT: &str = "update://rolled-back";
+
+/// 已下载、等待安装的更新（保存在 Manager 数据目录的 staged_update.json）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StagedUpdate {
+    pub version: String,
+    /// npm 包的 tarball 地址
+    pub tarball_url: String,
+    /// 下载缓存中的 tarball
+    pub path: String,
+    pub downloaded_at: String,
+    /// 下次启动 Manager 时（Gateway 启动前）自动安装
+    pub apply_on_restart: bool,
+}
+
+fn staged_path() -> PathBuf {
+    Path::new(&platform::get_manager_data_dir()).join("staged_update.json")
+}
+
+/// 读取已下载的更新（tarball 已被清理时视为没有）
+pub fn load_staged() -> Option<StagedUpdate> {
+    std::fs::read_to_string(staged_path())
+        .ok()
+        .and_then(|content| serde_json::from_str::<StagedUpdate>(&content).ok())
+        .filter(|staged| Path::new(&staged.path).exists())
+}
+
+fn save_staged(staged: &StagedUpdate) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(staged).map_err(|e| e.to_string())?;
+    file::write_atomic(&staged_path(), content.as_bytes())
+        .map_err(|e| format!("保存更新状态失败: {}", e))
+}
+
+fn clear_staged() {
+    let _ = std::fs::remove_file(staged_path());
+}
+
+/// 解析 `npm view <pkg> version dist.tarball --json` 的输出
+fn parse_npm_view(output: &str) -> Option<(String, String)> {
+    let value: serde_json::Value = serde_json::from_str(output.trim()).ok()?;
+    let version = value.get("version")?.as_str()?.to_string();
+    let tarball = value.get("dist.tarball")?.as_str()?.to_string();
+    Some((version, tarball))
+}
+
+/// 当前安装的版本是否就是目标版本（`openclaw --version` 的输出可能带前缀）
+pub fn is_installed_version(installed: Option<&str>, version: &str) -> bool {
+    installed
+        .map(|v| {
+            v.split_whitespace()
+                .any(|part| part.trim_start_matches('v') == version)
+        })
+        .unwrap_or(false)
+}
+
+/// 下载最新版本的 npm tarball 到下载缓存（Gateway 继续运行），已是最新版本时返回 None
+pub async fn stage() -> Result<Option<StagedUpdate>, String> {
+    privacy::guard("下载更新")?;
+    let settings = settings::load_settings();
+    let package = settings.openclaw_package();
+    let cmd = format!(
+        "npm view {} version dist.tarball --json {}",
+        package,
+        settings.npm_args()
+    );
+    info!("[下载更新] 执行 {}", cmd);
+    let overlay = settings.env_overrides.npm;
+    let output = tokio::task::spawn_blocking(move || shell::run_script_with_env(&cmd, &overlay))
+        .await
+        .map_err(|e| format!("查询最新版本失败: {}", e))?
+        .map_err(|e| format!("查询最新版本失败: {}", e))?;
+    let (version, tarball_url) =
+        parse_npm_view(&output).ok_or_else(|| format!("无法解析 npm 输出: {}", output.trim()))?;
+
+    if is_installed_version(installer::get_openclaw_version().as_deref(), &version) {
+        info!("[下载更新] 已是最新版本 {}", version);
+        clear_staged();
+        return Ok(None);
+    }
+    // 当前 Manager 无法驱动的版本不下载
+    compat::ensure_supported(&version).await?;
+    if let Some(staged) = load_staged().filter(|s| s.version == version) {
+        info!("[下载更新] {} 已下载", version);
+        return Ok(Some(staged));
+    }
+
+    let client = http::download_client()?;
+    let file_name = format!("openclaw-{}.tgz", version);
+    let timer = install_timings::step(install_timings::UPDATE, "download");
+    let downloaded = downloads::fetch(&client, &tarball_url, &file_name, None).await;
+    timer.finish(downloaded.is_ok());
+    let path = downloaded?;
+    let staged = StagedUpdate {
+        version,
+        tarball_url,
+        path: path.to_string_lossy().to_string(),
+        downloaded_at: time::now_rfc3339(),
+        apply_on_restart: false,
+    };
+    save_staged(&staged)?;
+    info!("[下载更新] ✓ 已下载 OpenClaw {}", staged.version);
+    Ok(Some(staged))
+}
+
+/// 最近一次更新（保存在 Manager 数据目录的 last_update.json）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateRecord {
+    /// 更新前的 npm 包版本，回滚时重新安装该版本
+    pub from_version: Option<String>,
+    pub to_version: String,
+    pub applied_at: String,
+    pub rolled_back: bool,
+    /// 回滚原因
+    pub reason: Option<String>,
+    /// 更新前的 openclaw.json 副本（新版本迁移配置后无法启动时用于恢复）
+    #[serde(default)]
+    pub config_backup: Option<String>,
+}
+
+fn record_path() -> PathBuf {
+    Path::new(&platform::get_manager_data_dir()).join("last_update.json")
+}
+
+pub fn load_record() -> Option<UpdateRecord> {
+    std::fs::read_to_string(record_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+}
+
+pub fn save_record(record: &UpdateRecord) {
+    let result = serde_json::to_string_pretty(record)
+        .map_err(|e| e.to_string())
+        .and_then(|content| {
+            file::write_atomic(&record_path(), content.as_bytes()).map_err(|e| e.to_string())
+        });
+    if let Err(e) = result {
+        warn!("[应用更新] 记录更新失败: {}", e);
+    }
+}
+
+/// 更新前保存 openclaw.json 的副本，返回副本路径
+fn backup_config() -> Option<String> {
+    let source = PathBuf::from(platform::get_config_file_path());
+    let content = std::fs::read(&source).ok()?;
+    let backup = Path::new(&platform::get_manager_data_dir())
+        .join("pre_update")
+        .join("openclaw.json");
+    match file::write_atomic(&backup, &content) {
+        Ok(()) => Some(backup.to_string_lossy().to_string()),
+        Err(e) => {
+            warn!("[应用更新] 备份 openclaw.json 失败: {}", e);
+            None
+        }
+    }
+}
+
+/// 从 `openclaw --version` 的输出中提取版本号
+fn extract_version(output: &str) -> Option<String> {
+    output
+        .split_whitespace()
+        .map(|part| part.trim_start_matches('v'))
+        .find(|part| part.starts_with(|c: char| c.is_ascii_digit()))
+        .map(|part| part.to_string())
+}
+
+/// 健康检查间隔
+const HEALTH_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// 启动 Gateway 并在观察窗口内持续检查，连续两次失败视为不健康
+pub async fn watch_health(window: Duration) -> Result<(), String> {
+    tokio::task::spawn_blocking(service::spawn_gateway_and_wait)
+        .await
+        .map_err(|e| e.to_string())??;
+    let client = http::build_client(None, Duration::from_secs(3))?;
+    let url = format!("http://127.0.0.1:{}", service::SERVICE_PORT);
+    let start = Instant::now();
+    let mut failures = 0;
+    while start.elapsed() < window {
+        tokio::time::sleep(HEALTH_POLL_INTERVAL).await;
+        let healthy = service::gateway_pid().is_some() && client.get(&url).send().await.is_ok();
+        failures = if healthy { 0 } else { failures + 1 };
+        if failures >= 2 {
+            return Err(format!(
+                "Gateway 在更新后 {} 秒内停止响应",
+                start.elapsed().as_secs()
+            ));
+        }
+    }
+    Ok(())
+}
+
+pub fn stop_gateway() {
+    monitor::expect_gateway_stop();
+    let _ = shell::run_openclaw(&["gateway", "stop"]);
+    std::thread::sleep(Duration::from_millis(500));
+}
+
+/// 执行全局 npm 命令（`npm <action> -g <spec>`）
+async fn npm_global(action: &str, spec: String) -> Result<String, String> {
+    let settings = settings::load_settings();
+    let cmd = format!("npm {} -g {} {}", action, spec, settings.npm_args());
+    info!("[应用更新] 执行 {}", cmd);
+    let overlay = settings.env_overrides.npm;
+    tokio::task::spawn_blocking(move || shell::run_script_with_env(&cmd, &overlay))
+        .await
+        .map_err(|e| format!("npm {} 失败: {}", action, e))?
+}
+
+pub async fn npm_install_global(spec: String) -> Result<String, String> {
+    npm_global("install", spec).await
+}
+
+pub async fn npm_uninstall_global(spec: String) -> Result<String, String> {
+    npm_global("uninstall", spec).await
+}
+
+/// 重新安装更新前的版本
+pub async fn rollback(from_version: &str) -> Result<(), String> {
+    warn!("[应用更新] 回滚到 OpenClaw {}...", from_version);
+    stop_gateway();
+    npm_install_global(format!("openclaw@{}", from_version)).await?;
+    let current = installer::get_openclaw_version();
+    if !is_installed_version(current.as_deref(), from_version) {
+        return Err(format!(
+            "回滚后版本为 {}",
+            current.unwrap_or_else(|| "未知".to_string())
+        ));
+    }
+    Ok(())
+}
+
+/// 从下载缓存安装更新：停止 Gateway → 安装 → 校验版本 → 启动并观察健康状态
+/// 观察窗口内 Gateway 异常时自动重新安装更新前的版本，并发送 update://rolled-back 事件
+/// pre_update 钩子由调用方执行
+pub async fn install_staged(
+    app: Option<AppHandle>,
+    staged: &StagedUpdate,
+) -> Result<InstallResult, String> {
+    let was_running = service::gateway_pid().is_some();
+    let from_version = installer::get_openclaw_version().and_then(|v| extract_version(&v));
+    info!(
+        "[应用更新] 安装 OpenClaw {} (当前 {:?})...",
+        staged.version, from_version
+    );
+
+    let config_backup = backup_config();
+    let timer = install_timings::step(install_timings::UPDATE, "stop_gateway");
+    stop_gateway();
+    timer.finish(true);
+    let timer = install_timings::step(install_timings::UPDATE, "npm");
+    let installed = npm_install_global(format!("\"{}\"", staged.path)).await;
+    timer.finish(installed.is_ok());
+
+    let mut result = match installed {
+        Err(e) => InstallResult {
+            success: false,
+            message: "OpenClaw 更新失败".to_string(),
+            error: Some(e),
+            timings: Vec::new(),
+        },
+        Ok(_) => {
+            let timer = install_timings::step(install_timings::UPDATE, "verification");
+            let current = installer::get_openclaw_version();
+            let verified = is_installed_version(current.as_deref(), &staged.version);
+            timer.finish(verified);
+            if verified {
+                clear_staged();
+                InstallResult {
+                    success: true,
+                    message: format!("OpenClaw 已更新到 {}", staged.version),
+                    error: None,
+                    timings: Vec::new(),
+                }
+            } else {
+                InstallResult {
+                    success: false,
+                    message: "OpenClaw 更新失败".to_string(),
+                    error: Some(format!(
+                        "安装后版本为 {}，期望 {}",
+                        current.unwrap_or_else(|| "未知".to_string()),
+                        staged.version
+                    )),
+                    timings: Vec::new(),
+                }
+            }
+        }
+    };
+
+    let window = Duration::from_secs(settings::load_settings().timeouts.update_health_secs);
+    let mut record = UpdateRecord {
+        from_version: from_version.clone(),
+        to_version: staged.version.clone(),
+        applied_at: time::now_rfc3339(),
+        rolled_back: false,
+        reason: None,
+        config_backup,
+    };
+    let mut gateway_running = false;
+    if result.success && !window.is_zero() {
+        info!(
+            "[应用更新] 观察 Gateway 健康状态 {} 秒...",
+            window.as_secs()
+        );
+        let timer = install_timings::step(install_timings::UPDATE, "health_check");
+        let health = watch_health(window).await;
+        timer.finish(health.is_ok());
+        match health {
+            Ok(()) => gateway_running = true,
+            Err(reason) => {
+                warn!("[应用更新] ✗ 健康检查失败: {}", reason);
+                let timer = install_timings::step(install_timings::UPDATE, "rollback");
+                let rolled_back = match from_version.as_deref() {
+                    Some(from) => rollback(from).await,
+                    None => Err("未记录更新前的版本".to_string()),
+                };
+                timer.finish(rolled_back.is_ok());
+                result = match &rolled_back {
+                    Ok(()) => InstallResult {
+                        success: false,
+                        message: format!(
+                            "更新后 Gateway 健康检查失败，已回滚到 {}",
+                            from_version.clone().unwrap_or_default()
+                        ),
+                        error: Some(reason.clone()),
+                        timings: Vec::new(),
+                    },
+                    Err(e) => InstallResult {
+                        success: false,
+                        message: "更新后 Gateway 健康检查失败，回滚失败".to_string(),
+                        error: Some(format!("{}；{}", reason, e)),
+                        timings: Vec::new(),
+                    },
+                };
+                record.rolled_back = rolled_back.is_ok();
+                record.reason = Some(reason);
+                if let Some(app) = &app {
+                    let _ = events::emit(app, UPDATE_ROLLED_BACK_EVENT, &record);
+                }
+                notifications::notify(
+                    NotificationTrigger::UpdateApplied,
+                    "openclaw-update",
+                    "OpenClaw 更新已回滚",
+                    &result.message,
+                );
+                audit::record(
+                    "rollback_update",
+                    &staged.version,
+                    record.rolled_back,
+                    json!({ "from": from_version, "reason": record.reason }),
+                );
+            }
+        }
+    }
+    if result.success || record.reason.is_some() {
+        save_record(&record);
+    }
+
+    // 观察窗口结束后恢复更新前的运行状态
+    if was_running && !gateway_running && service::gateway_pid().is_none() {
+        info!("[应用更新] 恢复 Gateway 运行...");
+        if let Err(e) = tokio::task::spawn_blocking(service::spawn_gateway_and_wait)
+            .await
+            .map_err(|e| e.to_string())
+            .and_then(|r| r)
+        {
+            warn!("[应用更新] 启动 Gateway 失败: {}", e);
+        }
+    } else if !was_running && service::gateway_pid().is_some() {
+        stop_gateway();
+    }
+
+    if result.success {
+        info!("[应用更新] ✓ {}", result.message);
+        notifications::notify(
+            NotificationTrigger::UpdateApplied,
+            "openclaw-update",
+            "OpenClaw 更新完成",
+            &result.message,
+        );
+    } else {
+        error!(
+            "[应用更新] ✗ {}: {}",
+            result.message,
+            result.error.clone().unwrap_or_default()
+        );
+    }
+    Ok(result)
+}
+
+/// Manager 启动时安装标记为“下次启动时安装”的更新（在 Gateway 自动启动之前调用）
+pub async fn apply_staged_on_startup() {
+    let Some(staged) = load_staged().filter(|s| s.apply_on_restart) else {
+        return;
+    };
+    info!("[应用更新] 启动时安装已下载的 OpenClaw {}", staged.version);
+    if let Err(e) = apply(None, &staged).await {
+        error!("[应用更新] ✗ {}", e);
+    }
+}
+
+/// 执行 pre_update 钩子后安装已下载的更新（钩子失败时取消）
+pub async fn apply(app: Option<AppHandle>, staged: &StagedUpdate) -> Result<InstallResult, String> {
+    let current_version = installer::get_openclaw_version().unwrap_or_default();
+    if let Err(e) = hooks::run_hooks(
+        HookEvent::PreUpdate,
+        &[("OPENCLAW_VERSION", current_version)],
+    ) {
+        warn!("[应用更新] pre_update 钩子执行失败，已取消更新: {}", e);
+        return Ok(InstallResult {
+            success: false,
+            message: "pre_update 钩子执行失败，已取消更新".to_string(),
+            error: Some(e),
+            timings: Vec::new(),
+        });
+    }
+    install_timings::begin(install_timings::UPDATE);
+    let result = install_staged(app, staged).await;
+    install_timings::attach(install_timings::UPDATE, result)
+}
+
+/// 是否有等待下次启动时安装的更新
+pub fn apply_on_restart_pending() -> bool {
+    load_staged().map(|s| s.apply_on_restart).unwrap_or(false)
+}
+
+/// 获取已下载、等待安装的更新
+#[guarded]
+#[command]
+pub async fn get_staged_update() -> Result<Option<StagedUpdate>, String> {
+    Ok(load_staged())
+}
+
+/// 下载更新（不停止 Gateway），之后通过 apply_update 安装；已是最新版本时返回 None
+#[guarded]
+#[command]
+pub async fn download_update() -> Result<Option<StagedUpdate>, String> {
+    info!("[下载更新] 开始下载 OpenClaw 更新...");
+    if source_build::load_record().is_some() {
+        return Err("当前为源码构建的安装，请直接使用更新".to_string());
+    }
+    stage().await
+}
+
+/// 获取最近一次更新记录（包括是否被自动回滚）
+#[guarded]
+#[command]
+pub async fn get_last_update() -> Result<Option<UpdateRecord>, String> {
+    Ok(load_record())
+}
+
+/// 安装已下载的更新
+#[guarded]
+#[command]
+pub async fn apply_update(app: AppHandle) -> Result<InstallResult, String> {
+    let staged = load_staged().ok_or_else(|| "没有已下载的更新".to_string())?;
+    let Some(_exclusive) = jobs::try_exclusive("安装 OpenClaw 更新") else {
+        return jobs::defer(JobKind::ApplyUpdate {
+            version: staged.version,
+        });
+    };
+    apply(Some(app), &staged).await
+}
+
+/// 设置已下载的更新是否在下次启动 Manager 时安装
+#[guarded]
+#[command]
+pub async fn set_update_on_restart(enabled: bool) -> Result<StagedUpdate, String> {
+    let mut staged = load_staged().ok_or_else(|| "没有已下载的更新".to_string())?;
+    staged.apply_on_restart = enabled;
+    save_staged(&staged)?;
+    info!(
+        "[下载更新] OpenClaw {} {}",
+        staged.version,
+        if enabled {
+            "将在下次启动时安装"
+        } else {
+            "取消下次启动时安装"
+        }
+    );
+    Ok(staged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn npm_view_output_and_installed_version_are_parsed() {
+        let output = r#"{
+  "version": "2026.2.1",
+  "dist.tarball": "https://registry.npmmirror.com/openclaw/-/openclaw-2026.2.1.tgz"
+}"#;
+        let (version, tarball) = parse_npm_view(output).unwrap();
+        assert_eq!(version, "2026.2.1");
+        assert!(tarball.ends_with("openclaw-2026.2.1.tgz"));
+        assert_eq!(parse_npm_view("\"2026.2.1\""), None);
+
+        assert!(is_installed_version(Some("2026.2.1"), "2026.2.1"));
+        assert!(is_installed_version(Some("openclaw v2026.2.1"), "2026.2.1"));
+        assert!(!is_installed_version(Some("2026.2.10"), "2026.2.1"));
+        assert!(!is_installed_version(None, "2026.2.1"));
+
+        assert_eq!(
+            extract_version("openclaw v2026.1.29\n").as_deref(),
+            Some("2026.1.29")
+        );
+        assert_eq!(extract_version("openclaw"), None);
+    }
+}
@@ -0,0 +1,301 @@
+use crate::commands::{installer, privacy, settings};
+use crate::utils::{encoding, file, platform, shell, temp, time};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use openclaw_macros::guarded;
+use tauri::command;
+
+/// npm audit 使用官方源（国内镜像大多未实现安全审计接口）
+const AUDIT_REGISTRY: &str = "https://registry.npmjs.org";
+
+/// 漏洞严重程度（npm audit 的分级）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Info,
+    Low,
+    Moderate,
+    High,
+    Critical,
+}
+
+/// 各严重程度的漏洞数量
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SeverityCounts {
+    pub info: u32,
+    pub low: u32,
+    pub moderate: u32,
+    pub high: u32,
+    pub critical: u32,
+    pub total: u32,
+}
+
+impl SeverityCounts {
+    /// 最高的严重程度（没有漏洞时为 None）
+    pub fn highest(&self) -> Option<Severity> {
+        [
+            (self.critical, Severity::Critical),
+            (self.high, Severity::High),
+            (self.moderate, Severity::Moderate),
+            (self.low, Severity::Low),
+            (self.info, Severity::Info),
+        ]
+        .into_iter()
+        .find(|(n, _)| *n > 0)
+        .map(|(_, s)| s)
+    }
+}
+
+/// 一条安全公告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Advisory {
+    /// 存在漏洞的依赖包
+    pub package: String,
+    pub severity: Severity,
+    pub title: String,
+    pub url: Option<String>,
+    /// 受影响的版本范围
+    pub range: Option<String>,
+    pub fix_available: bool,
+}
+
+/// 一次扫描的结果（保存在本地，供仪表盘和更新提示显示）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VulnerabilityScan {
+    pub scanned_at: String,
+    pub openclaw_version: Option<String>,
+    pub counts: SeverityCounts,
+    pub highest: Option<Severity>,
+    pub advisories: Vec<Advisory>,
+}
+
+fn scan_path() -> PathBuf {
+    PathBuf::from(platform::get_manager_data_dir()).join("vulnerability_scan.json")
+}
+
+/// 最近一次扫描结果
+pub fn last_scan() -> Option<VulnerabilityScan> {
+    std::fs::read_to_string(scan_path())
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+}
+
+fn save_scan(scan: &VulnerabilityScan) -> Result<(), String> {
+    let content = serde_json::to_vec_pretty(scan).map_err(|e| e.to_string())?;
+    file::write_atomic(&scan_path(), &content).map_err(|e| format!("保存扫描结果失败: {}", e))
+}
+
+/// 解析 `npm audit --json`（npm 7+ 格式）
+fn parse_audit(report: &Value) -> (SeverityCounts, Vec<Advisory>) {
+    let counts: SeverityCounts = report
+        .pointer("/metadata/vulnerabilities")
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+        .unwrap_or_default();
+    let mut advisories: Vec<Advisory> = Vec::new();
+    let vulnerabilities = report.get("vulnerabilities").and_then(|v| v.as_object());
+    for (name, vuln) in vulnerabilities.into_iter().flatten() {
+        let fix_available = vuln
+            .get("fixAvailable")
+            .is_some_and(|f| f.as_bool() != Some(false));
+        // via 中的字符串表示经由其它包间接受影响，只收集对象形式的公告
+        for via in vuln
+            .get("via")
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flatten()
+        {
+            let Some(severity) = via
+                .get("severity")
+                .and_then(|s| serde_json::from_value::<Severity>(s.clone()).ok())
+            else {
+                continue;
+            };
+            let url = via.get("url").and_then(|v| v.as_str()).map(String::from);
+            if url.is_some() && advisories.iter().any(|a| a.url == url) {
+                continue;
+            }
+            advisories.push(Advisory {
+                package: via
+                    .get("name")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or(name)
+                    .to_string(),
+                severity,
+                title: via
+                    .get("title")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("")
+                    .to_string(),
+                url,
+                range: via.get("range").and_then(|v| v.as_str()).map(String::from),
+                fix_available,
+            });
+        }
+    }
+    advisories.sort_by(|a, b| b.severity.cmp(&a.severity).then(a.package.cmp(&b.package)));
+    (counts, advisories)
+}
+
+fn npm_program() -> &'static str {
+    if platform::is_windows() {
+        "npm.cmd"
+    } else {
+        "npm"
+    }
+}
+
+/// 在指定目录执行 npm，返回 stdout（npm audit 发现漏洞时退出码非 0，不视为失败）
+fn run_npm(args: &[&str], cwd: &Path) -> Result<String, String> {
+    let settings = settings::load_settings();
+    let mut command = shell::piped_command(npm_program(), args, cwd);
+    shell::apply_overlay(&mut command, &settings.env_overrides.npm);
+    if let Some(proxy) = settings.proxy.as_deref().filter(|p| !p.is_empty()) {
+        command
+            .env("npm_config_proxy", proxy)
+            .env("npm_config_https_proxy", proxy);
+    }
+    let output = command
+        .output()
+        .map_err(|e| format!("执行 npm 失败: {}", e))?;
+    Ok(encoding::decode_output(&output.stdout).to_string())
+}
+
+/// 全局安装的 openclaw 包目录
+fn global_package_dir() -> Result<PathBuf, String> {
+    let settings = settings::load_settings();
+    let root = shell::run_script_with_env("npm root -g", &settings.env_overrides.npm)?;
+    let dir = Path::new(root.trim()).join("openclaw");
+    if !dir.join("package.json").is_file() {
+        return Err("未找到全局安装的 openclaw，请先安装 OpenClaw".to_string());
+    }
+    Ok(dir)
+}
+
+fn audit_in(dir: &Path) -> Result<Value, String> {
+    let registry = format!("--registry={}", AUDIT_REGISTRY);
+    let stdout = run_npm(&["audit", "--json", &registry], dir)?;
+    serde_json::from_str(stdout.trim()).map_err(|e| format!("解析 npm audit 输出失败: {}", e))
+}
+
+/// 运行 npm audit；全局包目录没有锁文件时，在临时目录中按 package.json 生成锁文件后审计
+fn run_audit(dir: &Path) -> Result<Value, String> {
+    let report = audit_in(dir)?;
+    if report.pointer("/error/code").and_then(|c| c.as_str()) != Some("ENOLOCK") {
+        return Ok(report);
+    }
+    info!("[漏洞扫描] 全局包没有锁文件，在临时目录中生成");
+    let work = temp::run_dir()
+        .map_err(|e| format!("创建临时目录失败: {}", e))?
+        .join(format!("audit-{}", uuid::Uuid::new_v4().simple()));
+    std::fs::create_dir_all(&work).map_err(|e| format!("创建临时目录失败: {}", e))?;
+    for name in ["package.json", "npm-shrinkwrap.json"] {
+        if dir.join(name).is_file() {
+            std::fs::copy(dir.join(name), work.join(name))
+                .map_err(|e| format!("复制 {} 失败: {}", name, e))?;
+        }
+    }
+    let registry = format!("--registry={}", AUDIT_REGISTRY);
+    run_npm(
+        &[
+            "install",
+            "--package-lock-only",
+            "--ignore-scripts",
+            "--no-audit",
+            "--no-fund",
+            &registry,
+        ],
+        &work,
+    )?;
+    let report = audit_in(&work);
+    let _ = std::fs::remove_dir_all(&work);
+    report
+}
+
+/// 对全局安装的 OpenClaw 依赖树运行 npm audit，按严重程度汇总并保存结果
+#[guarded]
+#[command]
+pub async fn scan_openclaw_vulnerabilities() -> Result<VulnerabilityScan, String> {
+    // npm audit 会把依赖列表发送到 npm 官方源
+    privacy::guard("漏洞扫描")?;
+    info!("[漏洞扫描] 开始扫描 OpenClaw 依赖...");
+    let report = tokio::task::spawn_blocking(|| {
+        let dir = global_package_dir()?;
+        run_audit(&dir)
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    if let Some(message) = report.pointer("/error/summary").and_then(|v| v.as_str()) {
+        warn!("[漏洞扫描] ✗ npm audit 失败: {}", message);
+        return Err(format!("npm audit 失败: {}", message));
+    }
+    let (counts, advisories) = parse_audit(&report);
+    let scan = VulnerabilityScan {
+        scanned_at: time::now_rfc3339(),
+        openclaw_version: installer::get_openclaw_version(),
+        highest: counts.highest(),
+        counts,
+        advisories,
+    };
+    save_scan(&scan)?;
+    info!(
+        "[漏洞扫描] ✓ 发现 {} 个漏洞（严重 {}，高危 {}）",
+        scan.counts.total, scan.counts.critical, scan.counts.high
+    );
+    Ok(scan)
+}
+
+/// 最近一次扫描结果（仪表盘角标使用，不重新扫描）
+#[guarded]
+#[command]
+pub async fn get_vulnerability_scan() -> Result<Option<VulnerabilityScan>, String> {
+    Ok(last_scan())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parses_npm_audit_into_buckets_and_advisories() {
+        let report = json!({
+            "auditReportVersion": 2,
+            "vulnerabilities": {
+                "ws": {
+                    "name": "ws", "severity": "high", "isDirect": false,
+                    "via": [{
+                        "source": 1098392, "name": "ws", "dependency": "ws",
+                        "title": "ws affected by a DoS when handling a request with many HTTP headers",
+                        "url": "https://github.com/advisories/GHSA-3h5v-q93c-6h6q",
+                        "severity": "high", "range": ">=8.0.0 <8.17.1"
+                    }],
+                    "fixAvailable": true
+                },
+                "socket.io-adapter": {
+                    "name": "socket.io-adapter", "severity": "high",
+                    "via": ["ws"], "fixAvailable": false
+                },
+                "debug": {
+                    "name": "debug", "severity": "low",
+                    "via": [{"name": "debug", "title": "ReDoS", "severity": "low",
+                             "url": "https://github.com/advisories/GHSA-x", "range": "<2.6.9"}],
+                    "fixAvailable": {"name": "express", "version": "4.21.0", "isSemVerMajor": false}
+                }
+            },
+            "metadata": {"vulnerabilities": {
+                "info": 0, "low": 1, "moderate": 0, "high": 2, "critical": 0, "total": 3
+            }}
+        });
+        let (counts, advisories) = parse_audit(&report);
+        assert_eq!(counts.total, 3);
+        assert_eq!(counts.highest(), Some(Severity::High));
+        assert_eq!(advisories.len(), 2);
+        assert_eq!(advisories[0].package, "ws");
+        assert!(advisories[0].fix_available);
+        assert_eq!(advisories[1].severity, Severity::Low);
+        assert_eq!(SeverityCounts::default().highest(), None);
+    }
+}
@@ -0,0 +1,201 @@
+use crate::commands::events;
+use crate::utils::platform;
+use log::{debug, info, warn};
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+
+/// 配置变更事件名
+pub const CONFIG_CHANGED_EVENT: &str = "config://changed";
+/// 合并连续变更的等待时间（编辑器保存时通常会产生多个事件）
+const DEBOUNCE: Duration = Duration::from_millis(500);
+/// Manager 自身写入后忽略该文件变更事件的时间窗口
+const INTERNAL_WRITE_WINDOW: Duration = Duration::from_secs(2);
+
+/// 每次开始监听时递增，切换配置目录后旧的监听线程据此退出
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Manager 自身最近写入的文件
+static INTERNAL_WRITES: Mutex<Option<HashMap<PathBuf, Instant>>> = Mutex::new(None);
+
+/// 外部配置变更摘要
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigChangeEvent {
+    /// 变更类别：config / env / credentials / skills
+    pub categories: Vec<String>,
+    /// 变更的文件（相对配置目录）
+    pub paths: Vec<String>,
+    /// openclaw.json 变更后是否仍是有效 JSON（未变更时为 None）
+    pub config_valid: Option<bool>,
+    pub error: Option<String>,
+}
+
+/// Manager 写入配置文件前调用，避免把自己的修改当成外部变更
+pub fn note_internal_write(path: &str) {
+    if let Ok(mut writes) = INTERNAL_WRITES.lock() {
+        let writes = writes.get_or_insert_with(HashMap::new);
+        let now = Instant::now();
+        writes.retain(|_, t| now.duration_since(*t) < INTERNAL_WRITE_WINDOW);
+        writes.insert(PathBuf::from(path), now);
+    }
+}
+
+fn is_internal_write(path: &Path) -> bool {
+    INTERNAL_WRITES
+        .lock()
+        .ok()
+        .and_then(|writes| writes.as_ref().and_then(|w| w.get(path).copied()))
+        .map(|t| t.elapsed() < INTERNAL_WRITE_WINDOW)
+        .unwrap_or(false)
+}
+
+/// 判断变更文件属于哪一类（日志、会话等运行时文件返回 None）
+fn classify(config_dir: &Path, path: &Path) -> Option<&'static str> {
+    let relative = path.strip_prefix(config_dir).ok()?;
+    let mut components = relative.components();
+    let first = components.next()?.as_os_str().to_string_lossy().to_string();
+    match first.as_str() {
+        "openclaw.json" => Some("config"),
+        ".env" => Some("env"),
+        "credentials" => Some("credentials"),
+        "skills" => Some("skills"),
+        _ => None,
+    }
+}
+
+/// 重新校验 openclaw.json
+fn validate_config() -> (bool, Option<String>) {
+    match std::fs::read_to_string(platform::get_config_file_path()) {
+        Ok(content) => match serde_json::from_str::<serde_json::Value>(&content) {
+            Ok(_) => (true, None),
+            Err(e) => (false, Some(format!("openclaw.json 不是有效的 JSON: {}", e))),
+        },
+        Err(e) => (false, Some(format!("读取 openclaw.json 失败: {}", e))),
+    }
+}
+
+/// 汇总一批文件事件
+fn summarize(config_dir: &Path, events: &[Event]) -> Option<ConfigChangeEvent> {
+    let mut categories = BTreeSet::new();
+    let mut paths = BTreeSet::new();
+    for event in events {
+        if matches!(event.kind, EventKind::Access(_)) {
+            continue;
+        }
+        for path in &event.paths {
+            if is_internal_write(path) {
+                continue;
+            }
+            if let Some(category) = classify(config_dir, path) {
+                categories.insert(category.to_string());
+                if let Ok(relative) = path.strip_prefix(config_dir) {
+                    paths.insert(relative.to_string_lossy().to_string());
+                }
+            }
+        }
+    }
+    if categories.is_empty() {
+        return None;
+    }
+
+    let (config_valid, error) = if categories.contains("config") {
+        let (valid, error) = validate_config();
+        (Some(valid), error)
+    } else {
+        (None, None)
+    };
+    Some(ConfigChangeEvent {
+        categories: categories.into_iter().collect(),
+        paths: paths.into_iter().collect(),
+        config_valid,
+        error,
+    })
+}
+
+/// 启动配置目录监听：检测到外部修改后发送 `config://changed` 事件（切换配置档案后重新调用）
+pub fn start(app: AppHandle) {
+    let generation = GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    let config_dir = PathBuf::from(platform::get_config_dir());
+    if !config_dir.exists() {
+        info!("[配置监听] 配置目录不存在，跳过监听: {:?}", config_dir);
+        return;
+    }
+
+    let (tx, rx) = mpsc::channel::<notify::Result<Event>>();
+    let mut watcher = match notify::recommended_watcher(tx) {
+        Ok(w) => w,
+        Err(e) => {
+            warn!("[配置监听] 创建文件监听失败: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = watcher.watch(&config_dir, RecursiveMode::Recursive) {
+        warn!("[配置监听] 监听 {:?} 失败: {}", config_dir, e);
+        return;
+    }
+    info!("[配置监听] 开始监听 {:?}", config_dir);
+
+    std::thread::spawn(move || {
+        // watcher 需要在线程存活期间保持
+        let _watcher = watcher;
+        while let Ok(first) = rx.recv() {
+            let mut events: Vec<Event> = first.into_iter().collect();
+            while let Ok(next) = rx.recv_timeout(DEBOUNCE) {
+                events.extend(next);
+            }
+            if GENERATION.load(Ordering::SeqCst) != generation {
+                debug!("[配置监听] 停止监听 {:?}", config_dir);
+                break;
+            }
+
+            let Some(change) = summarize(&config_dir, &events) else {
+                continue;
+            };
+            info!(
+                "[配置监听] 检测到外部修改: {} ({})",
+                change.categories.join(", "),
+                change.paths.join(", ")
+            );
+            if let Some(e) = &change.error {
+                warn!("[配置监听] ✗ {}", e);
+            }
+            if let Err(e) = events::emit(&app, CONFIG_CHANGED_EVENT, change) {
+                debug!("[配置监听] 发送事件失败: {}", e);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_config_credentials_and_skills_are_tracked() {
+        let dir = Path::new("/home/u/.openclaw");
+        assert_eq!(classify(dir, &dir.join("openclaw.json")), Some("config"));
+        assert_eq!(classify(dir, &dir.join(".env")), Some("env"));
+        assert_eq!(
+            classify(
+                dir,
+                &dir.join("credentials").join("whatsapp").join("creds.json")
+            ),
+            Some("credentials")
+        );
+        assert_eq!(
+            classify(dir, &dir.join("skills").join("a").join("SKILL.md")),
+            Some("skills")
+        );
+        assert_eq!(classify(dir, &dir.join("logs").join("gateway.log")), None);
+        assert_eq!(
+            classify(dir, &dir.join("agents").join("main").join("sessions")),
+            None
+        );
+    }
+}
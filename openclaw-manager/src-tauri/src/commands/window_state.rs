@@ -0,0 +1,193 @@
+//! 主窗口状态：记住窗口位置、大小、最大化状态和最后打开的页面，下次启动时恢复
+//! （保存在 Manager 数据目录的 window-state.json）
+use crate::utils::{file, platform};
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use openclaw_macros::guarded;
+use tauri::{command, AppHandle, Manager, PhysicalPosition, PhysicalSize, Window, WindowEvent};
+
+/// 主窗口标签（见 tauri.conf.json）
+pub const MAIN_WINDOW: &str = "main";
+
+/// 恢复位置时窗口左上角至少要有这么大的区域（像素）落在某个显示器内，
+/// 避免显示器断开后窗口出现在屏幕外
+const MIN_VISIBLE: (i32, i32) = (120, 40);
+
+/// 窗口状态（位置和大小为物理像素，位置是窗口外框左上角，大小是内容区域）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WindowState {
+    pub x: Option<i32>,
+    pub y: Option<i32>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub maximized: bool,
+    /// 最后打开的页面（由前端报告）
+    pub last_view: Option<String>,
+}
+
+/// 内存中的状态，首次访问时从磁盘加载
+static STATE: Mutex<Option<WindowState>> = Mutex::new(None);
+
+fn state_path() -> PathBuf {
+    Path::new(&platform::get_manager_data_dir()).join("window-state.json")
+}
+
+fn load_disk() -> WindowState {
+    std::fs::read(state_path())
+        .ok()
+        .and_then(|content| serde_json::from_slice(&content).ok())
+        .unwrap_or_default()
+}
+
+fn with_state<R>(f: impl FnOnce(&mut WindowState) -> R) -> R {
+    let mut guard = STATE.lock().unwrap_or_else(|e| e.into_inner());
+    f(guard.get_or_insert_with(load_disk))
+}
+
+/// 写回磁盘
+pub fn save() {
+    let state = with_state(|state| state.clone());
+    let result = serde_json::to_vec_pretty(&state)
+        .map_err(|e| e.to_string())
+        .and_then(|content| file::write_atomic(&state_path(), &content).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+        warn!("[窗口状态] 保存窗口状态失败: {}", e);
+    }
+}
+
+/// 窗口左上角区域是否落在某个显示器内（显示器为 x, y, 宽, 高）
+fn is_visible(x: i32, y: i32, monitors: &[(i32, i32, u32, u32)]) -> bool {
+    monitors.iter().any(|&(mx, my, mw, mh)| {
+        x + MIN_VISIBLE.0 > mx
+            && y >= my
+            && x < mx + mw as i32 - MIN_VISIBLE.0
+            && y < my + mh as i32 - MIN_VISIBLE.1
+    })
+}
+
+/// 记录主窗口当前的位置和大小（最大化或最小化时保留之前的位置和大小）
+fn track(window: &Window) {
+    if window.label() != MAIN_WINDOW || window.is_minimized().unwrap_or(false) {
+        return;
+    }
+    let maximized = window.is_maximized().unwrap_or(false);
+    let geometry = (!maximized)
+        .then(|| Some((window.outer_position().ok()?, window.inner_size().ok()?)))
+        .flatten();
+    with_state(|state| {
+        state.maximized = maximized;
+        if let Some((position, size)) = geometry {
+            state.x = Some(position.x);
+            state.y = Some(position.y);
+            state.width = Some(size.width);
+            state.height = Some(size.height);
+        }
+    });
+}
+
+/// 窗口事件：移动、缩放时更新内存中的状态，关闭主窗口时写回磁盘
+pub fn on_window_event(window: &Window, event: &WindowEvent) {
+    match event {
+        WindowEvent::Moved(_) | WindowEvent::Resized(_) => track(window),
+        WindowEvent::CloseRequested { .. } if window.label() == MAIN_WINDOW => {
+            track(window);
+            save();
+        }
+        _ => {}
+    }
+}
+
+/// 启动时恢复主窗口的位置和大小（保存的位置已不在任何显示器内时保持居中）
+pub fn restore(app: &AppHandle) {
+    let Some(window) = app.get_webview_window(MAIN_WINDOW) else {
+        return;
+    };
+    let state = with_state(|state| state.clone());
+    if let (Some(width), Some(height)) = (state.width, state.height) {
+        let _ = window.set_size(PhysicalSize::new(width, height));
+    }
+    if let (Some(x), Some(y)) = (state.x, state.y) {
+        let monitors: Vec<(i32, i32, u32, u32)> = window
+            .available_monitors()
+            .unwrap_or_default()
+            .iter()
+            .map(|m| {
+                (
+                    m.position().x,
+                    m.position().y,
+                    m.size().width,
+                    m.size().height,
+                )
+            })
+            .collect();
+        if is_visible(x, y, &monitors) {
+            let _ = window.set_position(PhysicalPosition::new(x, y));
+        } else {
+            debug!(
+                "[窗口状态] 保存的位置 ({}, {}) 不在任何显示器内，保持居中",
+                x, y
+            );
+        }
+    }
+    if state.maximized {
+        let _ = window.maximize();
+    }
+}
+
+/// 显示并聚焦主窗口（深度链接、再次启动 Manager 时调用）
+pub fn show_main(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window(MAIN_WINDOW) {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+/// 获取保存的窗口状态（前端启动时据此打开最后的页面）
+#[guarded]
+#[command]
+pub async fn get_window_state() -> Result<WindowState, String> {
+    Ok(with_state(|state| state.clone()))
+}
+
+/// 记录最后打开的页面
+#[guarded]
+#[command]
+pub async fn set_last_view(view: String) -> Result<(), String> {
+    let view = view.trim().to_string();
+    if view.is_empty() || view.len() > 64 {
+        return Err("页面名称无效".to_string());
+    }
+    let changed = with_state(|state| {
+        let changed = state.last_view.as_deref() != Some(view.as_str());
+        state.last_view = Some(view.clone());
+        changed
+    });
+    if changed {
+        save();
+        info!("[窗口状态] ✓ 最后打开的页面: {}", view);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn restores_only_positions_on_a_connected_monitor() {
+        let monitors = [(0, 0, 1920, 1080), (1920, 0, 2560, 1440)];
+        assert!(is_visible(100, 100, &monitors));
+        assert!(is_visible(3000, 200, &monitors));
+        // 左侧略微超出屏幕仍可拖回
+        assert!(is_visible(-50, 10, &monitors));
+        // 上次在已断开的第三块显示器上
+        assert!(!is_visible(5000, 100, &monitors));
+        assert!(!is_visible(100, -300, &monitors));
+        assert!(!is_visible(100, 1070, &monitors[..1]));
+        assert!(!is_visible(100, 100, &[]));
+    }
+}
@@ -0,0 +1,195 @@
+use crate::commands::settings;
+use crate::utils::{platform, shell};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use openclaw_macros::guarded;
+use tauri::command;
+
+/// Windows 包管理器
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WindowsPackageManager {
+    Winget,
+    Choco,
+    Scoop,
+}
+
+/// 可通过包管理器安装的软件
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WindowsPackage {
+    Node,
+    Git,
+    Cloudflared,
+    Tailscale,
+    Ngrok,
+    Ffmpeg,
+}
+
+impl WindowsPackageManager {
+    /// 自动选择时的优先顺序
+    pub const ALL: [WindowsPackageManager; 3] = [Self::Winget, Self::Choco, Self::Scoop];
+
+    pub fn executable(self) -> &'static str {
+        match self {
+            Self::Winget => "winget",
+            Self::Choco => "choco",
+            Self::Scoop => "scoop",
+        }
+    }
+
+    /// 软件在该包管理器中的包名（不支持时返回 None）
+    pub fn package_id(self, package: WindowsPackage) -> Option<&'static str> {
+        use WindowsPackage::*;
+        match (self, package) {
+            (Self::Winget, Node) => Some("OpenJS.NodeJS.LTS"),
+            (Self::Winget, Git) => Some("Git.Git"),
+            (Self::Winget, Cloudflared) => Some("Cloudflare.cloudflared"),
+            (Self::Winget, Tailscale) => Some("tailscale.tailscale"),
+            (Self::Winget, Ngrok) => Some("Ngrok.Ngrok"),
+            (Self::Winget, Ffmpeg) => Some("Gyan.FFmpeg"),
+            (Self::Choco, Node) => Some("nodejs-lts"),
+            (Self::Choco, Git) => Some("git"),
+            (Self::Choco, Cloudflared) => Some("cloudflared"),
+            (Self::Choco, Tailscale) => Some("tailscale"),
+            (Self::Choco, Ngrok) => Some("ngrok"),
+            (Self::Choco, Ffmpeg) => Some("ffmpeg"),
+            (Self::Scoop, Node) => Some("nodejs-lts"),
+            (Self::Scoop, Git) => Some("git"),
+            (Self::Scoop, Cloudflared) => Some("cloudflared"),
+            (Self::Scoop, Ffmpeg) => Some("ffmpeg"),
+            (Self::Scoop, Tailscale) | (Self::Scoop, Ngrok) => None,
+        }
+    }
+
+    /// 安装命令（PowerShell 命令行，scoop 是 ps1 脚本只能在 PowerShell 中调用）
+    pub fn install_command(self, package: WindowsPackage) -> Option<String> {
+        let id = self.package_id(package)?;
+        Some(match self {
+            Self::Winget => format!(
+                "winget install --id {} -e --silent --accept-source-agreements --accept-package-agreements",
+                id
+            ),
+            Self::Choco => format!("choco install {} -y --no-progress", id),
+            Self::Scoop => format!("scoop install {}", id),
+        })
+    }
+}
+
+/// 检测已安装的包管理器
+pub fn detect() -> Vec<WindowsPackageManager> {
+    if !platform::is_windows() {
+        return Vec::new();
+    }
+    WindowsPackageManager::ALL
+        .into_iter()
+        .filter(|pm| shell::command_exists(pm.executable()))
+        .collect()
+}
+
+/// 选择安装某个软件使用的包管理器：优先使用设置中的首选项，否则按默认顺序
+fn select(
+    preferred: Option<WindowsPackageManager>,
+    available: &[WindowsPackageManager],
+    package: WindowsPackage,
+) -> Option<WindowsPackageManager> {
+    let supports = |pm: &WindowsPackageManager| pm.package_id(package).is_some();
+    preferred
+        .filter(|pm| available.contains(pm) && supports(pm))
+        .or_else(|| available.iter().copied().find(supports))
+}
+
+/// 当前设置下安装某个软件会使用的包管理器
+pub fn selected(package: WindowsPackage) -> Option<WindowsPackageManager> {
+    let preferred = settings::load_settings().windows_package_manager;
+    select(preferred, &detect(), package)
+}
+
+/// 当前设置下安装某个软件的命令（打开安装终端时使用），没有可用包管理器时返回 None
+pub fn install_command(package: WindowsPackage) -> Option<String> {
+    selected(package).and_then(|pm| pm.install_command(package))
+}
+
+/// 通过包管理器安装软件
+pub fn install(package: WindowsPackage) -> Result<String, String> {
+    let preferred = settings::load_settings().windows_package_manager;
+    let pm = select(preferred, &detect(), package)
+        .ok_or_else(|| "没有可用的包管理器（winget / choco / scoop）".to_string())?;
+    let cmd = pm
+        .install_command(package)
+        .ok_or_else(|| format!("{} 不支持安装 {:?}", pm.executable(), package))?;
+    info!("[包管理器] {}", cmd);
+    shell::run_powershell_output(&cmd)
+}
+
+/// 包管理器状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackageManagerStatus {
+    pub available: Vec<WindowsPackageManager>,
+    /// 设置中的首选项（None 表示自动）
+    pub preferred: Option<WindowsPackageManager>,
+    /// 安装 Node.js 时实际会使用的包管理器
+    pub active: Option<WindowsPackageManager>,
+}
+
+/// 获取 Windows 包管理器状态
+#[guarded]
+#[command]
+pub async fn get_package_managers() -> Result<PackageManagerStatus, String> {
+    let available = detect();
+    let preferred = settings::load_settings().windows_package_manager;
+    let active = select(preferred, &available, WindowsPackage::Node);
+    Ok(PackageManagerStatus {
+        available,
+        preferred,
+        active,
+    })
+}
+
+/// 通过首选包管理器安装软件（Node.js / Git / ffmpeg / 内网穿透客户端）
+#[guarded]
+#[command]
+pub async fn install_windows_package(package: WindowsPackage) -> Result<String, String> {
+    if !platform::is_windows() {
+        return Err("仅支持 Windows".to_string());
+    }
+    info!("[包管理器] 安装 {:?}...", package);
+    let result = tokio::task::spawn_blocking(move || install(package))
+        .await
+        .map_err(|e| format!("安装失败: {}", e))?;
+    match &result {
+        Ok(_) => info!("[包管理器] ✓ {:?} 安装完成", package),
+        Err(e) => warn!("[包管理器] ✗ {:?} 安装失败: {}", package, e),
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use WindowsPackageManager::*;
+
+    #[test]
+    fn preferred_manager_is_used_only_when_available_and_supported() {
+        let available = [Winget, Scoop];
+        assert_eq!(
+            select(Some(Scoop), &available, WindowsPackage::Node),
+            Some(Scoop)
+        );
+        // 首选项未安装时按默认顺序回退
+        assert_eq!(
+            select(Some(Choco), &available, WindowsPackage::Node),
+            Some(Winget)
+        );
+        // scoop 没有 tailscale
+        assert_eq!(
+            select(Some(Scoop), &available, WindowsPackage::Tailscale),
+            Some(Winget)
+        );
+        assert_eq!(select(None, &[Scoop], WindowsPackage::Ngrok), None);
+        assert_eq!(
+            Choco.install_command(WindowsPackage::Git).as_deref(),
+            Some("choco install git -y --no-progress")
+        );
+    }
+}
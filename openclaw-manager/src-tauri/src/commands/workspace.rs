@@ -0,0 +1,764 @@
+use crate::commands::notifications::{self, NotificationTrigger};
+use crate::commands::{config, events, policy, service, settings};
+use crate::utils::{audit, file, platform, time};
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+use openclaw_macros::guarded;
+use tauri::{command, AppHandle};
+
+/// 工作区告警事件名
+pub const WORKSPACE_ALERT_EVENT: &str = "workspace://alert";
+/// 预览文件内容的上限
+const MAX_READ_BYTES: u64 = 1024 * 1024;
+/// 默认 Agent
+const DEFAULT_AGENT: &str = "main";
+/// 单个工作区最多统计的文件数
+const MAX_SCAN_ENTRIES: usize = 200_000;
+const MB: u64 = 1024 * 1024;
+/// 视为可执行文件的扩展名
+const EXECUTABLE_EXTENSIONS: &[&str] = &[
+    "exe", "dll", "msi", "bat", "cmd", "com", "scr", "ps1", "vbs", "jar", "dmg", "pkg", "so",
+    "dylib",
+];
+/// 压缩包扩展名
+const ARCHIVE_EXTENSIONS: &[&str] = &[
+    "zip", "7z", "rar", "tar", "gz", "tgz", "bz2", "xz", "zst", "iso",
+];
+/// 原生可执行文件头（PE、ELF、Mach-O）
+const EXECUTABLE_MAGIC: &[&[u8]] = &[
+    b"MZ",
+    b"\x7fELF",
+    &[0xfe, 0xed, 0xfa, 0xce],
+    &[0xfe, 0xed, 0xfa, 0xcf],
+    &[0xce, 0xfa, 0xed, 0xfe],
+    &[0xcf, 0xfa, 0xed, 0xfe],
+    &[0xca, 0xfe, 0xba, 0xbe],
+];
+
+/// 工作区防护设置（Agent 开启文件 / Shell 技能时防止失控写入）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WorkspaceSettings {
+    /// 每个工作区的容量上限（MB，0 表示不限制）
+    pub quota_mb: u64,
+    /// 超出容量上限时停止 Gateway，需要用户明确开启
+    pub stop_at_quota: bool,
+    /// 两次检查之间增长超过该值（MB）时告警，0 表示不检查
+    pub growth_alert_mb: u64,
+    /// 超过该大小（MB）的压缩包视为可疑，0 表示不检查
+    pub large_archive_mb: u64,
+}
+
+impl Default for WorkspaceSettings {
+    fn default() -> Self {
+        Self {
+            quota_mb: 0,
+            stop_at_quota: false,
+            growth_alert_mb: 500,
+            large_archive_mb: 100,
+        }
+    }
+}
+
+/// 工作区中的一个文件或目录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceEntry {
+    pub name: String,
+    /// 相对工作区根目录的路径（使用 `/` 分隔）
+    pub path: String,
+    pub is_dir: bool,
+    /// 文件大小（目录为 0）
+    pub size: u64,
+    pub modified: Option<String>,
+}
+
+/// 文件预览
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceFile {
+    pub path: String,
+    pub size: u64,
+    pub modified: Option<String>,
+    /// 二进制文件为 None
+    pub content: Option<String>,
+    pub is_binary: bool,
+    /// 文件超过预览上限，只返回了开头部分
+    pub truncated: bool,
+}
+
+/// 可疑文件类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SuspiciousKind {
+    /// 可执行文件 / 脚本 / 动态库
+    Executable,
+    /// 超大压缩包
+    LargeArchive,
+}
+
+/// 工作区中的可疑文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SuspiciousFile {
+    pub path: String,
+    pub kind: SuspiciousKind,
+    pub size: u64,
+    pub modified: Option<String>,
+}
+
+/// 工作区占用情况
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceUsage {
+    pub agent: String,
+    pub root: String,
+    pub total_bytes: u64,
+    pub file_count: u64,
+    pub quota_bytes: Option<u64>,
+    pub over_quota: bool,
+    pub suspicious: Vec<SuspiciousFile>,
+    /// 文件过多，只统计了一部分
+    pub partial: bool,
+}
+
+/// 告警类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkspaceAlertKind {
+    QuotaExceeded,
+    RapidGrowth,
+    DangerousFile,
+}
+
+/// 工作区告警（通过 workspace://alert 事件推送）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceAlert {
+    pub agent: String,
+    pub kind: WorkspaceAlertKind,
+    pub message: String,
+    /// 可疑文件的相对路径
+    pub path: Option<String>,
+    pub total_bytes: u64,
+}
+
+/// 后台检查状态
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct WorkspaceState {
+    /// Agent -> 上次检查时的工作区大小
+    #[serde(default)]
+    sizes: HashMap<String, u64>,
+    /// 仍然成立的告警（避免每次检查重复提醒）
+    #[serde(default)]
+    alerted: HashSet<String>,
+}
+
+pub fn validate_agent(agent: &str) -> Result<&str, String> {
+    let agent = agent.trim();
+    if agent.is_empty() {
+        return Ok(DEFAULT_AGENT);
+    }
+    if agent.contains(['/', '\\']) || agent.contains("..") {
+        return Err(format!("无效的 Agent ID: {}", agent));
+    }
+    Ok(agent)
+}
+
+pub fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest.trim_start_matches(['/', '\\'])),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Agent 的工作区目录：优先 `agents.list[].workspace`，其次默认 Agent 的
+/// `agents.defaults.workspace`，否则按 OpenClaw 约定使用 `workspace` / `workspace-<agent>`
+pub fn resolve_workspace(cfg: &Value, config_dir: &Path, agent: &str) -> PathBuf {
+    let configured = cfg
+        .pointer("/agents/list")
+        .and_then(|l| l.as_array())
+        .and_then(|list| {
+            list.iter()
+                .find(|a| a.get("id").and_then(|v| v.as_str()) == Some(agent))
+        })
+        .and_then(|a| a.get("workspace"))
+        .or_else(|| {
+            (agent == DEFAULT_AGENT)
+                .then(|| cfg.pointer("/agents/defaults/workspace"))
+                .flatten()
+        })
+        .and_then(|v| v.as_str())
+        .filter(|w| !w.trim().is_empty());
+    match configured {
+        Some(workspace) => expand_home(workspace.trim()),
+        None if agent == DEFAULT_AGENT => config_dir.join("workspace"),
+        None => config_dir.join(format!("workspace-{}", agent)),
+    }
+}
+
+/// 指定 Agent 的工作区根目录（必须已存在）
+pub fn workspace_root(agent: &str) -> Result<PathBuf, String> {
+    let agent = validate_agent(agent)?;
+    let cfg = config::load_openclaw_config().unwrap_or(Value::Null);
+    let root = resolve_workspace(&cfg, Path::new(&platform::get_config_dir()), agent);
+    root.canonicalize()
+        .map_err(|_| format!("Agent {} 的工作区不存在: {}", agent, root.display()))
+}
+
+/// 把前端传入的相对路径解析到工作区内，拒绝绝对路径、`..` 和指向工作区外的符号链接
+pub fn resolve_in_workspace(root: &Path, relative: &str) -> Result<PathBuf, String> {
+    let relative = relative.trim().replace('\\', "/");
+    let mut path = root.to_path_buf();
+    for component in Path::new(relative.trim_start_matches('/')).components() {
+        match component {
+            Component::Normal(part) => path.push(part),
+            Component::CurDir => {}
+            _ => return Err(format!("路径不允许越出工作区: {}", relative)),
+        }
+    }
+    let resolved = path
+        .canonicalize()
+        .map_err(|_| format!("文件不存在: {}", relative))?;
+    if !resolved.starts_with(root) {
+        warn!("[工作区] 拒绝访问工作区外的路径: {}", resolved.display());
+        return Err(format!("路径不允许越出工作区: {}", relative));
+    }
+    Ok(resolved)
+}
+
+/// 默认 Agent 和 `agents.list` 中配置的 Agent
+fn agent_ids(cfg: &Value) -> Vec<String> {
+    let mut ids = vec![DEFAULT_AGENT.to_string()];
+    let configured = cfg
+        .pointer("/agents/list")
+        .and_then(|l| l.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|a| a.get("id").and_then(|v| v.as_str()));
+    for id in configured.filter_map(|id| validate_agent(id).ok()) {
+        if !ids.iter().any(|i| i == id) {
+            ids.push(id.to_string());
+        }
+    }
+    ids
+}
+
+fn relative_path(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn modified_time(meta: &std::fs::Metadata) -> Option<String> {
+    meta.modified()
+        .ok()
+        .map(|t| time::from_system_time(t).to_rfc3339())
+}
+
+/// 列出 Agent 工作区中的文件（目录在前，按名称排序）
+#[guarded]
+#[command]
+pub async fn list_workspace_files(
+    agent: String,
+    path: Option<String>,
+) -> Result<Vec<WorkspaceEntry>, String> {
+    let root = workspace_root(&agent)?;
+    let dir = resolve_in_workspace(&root, path.as_deref().unwrap_or(""))?;
+    let read_dir = std::fs::read_dir(&dir).map_err(|e| format!("读取目录失败: {}", e))?;
+    let mut entries: Vec<WorkspaceEntry> = read_dir
+        .flatten()
+        .filter_map(|entry| {
+            let meta = entry.metadata().ok()?;
+            Some(WorkspaceEntry {
+                name: entry.file_name().to_string_lossy().to_string(),
+                path: relative_path(&root, &entry.path()),
+                is_dir: meta.is_dir(),
+                size: if meta.is_dir() { 0 } else { meta.len() },
+                modified: modified_time(&meta),
+            })
+        })
+        .collect();
+    entries.sort_by(|a, b| b.is_dir.cmp(&a.is_dir).then(a.name.cmp(&b.name)));
+    Ok(entries)
+}
+
+/// 预览工作区中的文件（文本文件最多返回 1MB，二进制文件只返回元数据）
+#[guarded]
+#[command]
+pub async fn read_workspace_file(agent: String, path: String) -> Result<WorkspaceFile, String> {
+    let root = workspace_root(&agent)?;
+    let file = resolve_in_workspace(&root, &path)?;
+    let meta = std::fs::metadata(&file).map_err(|e| format!("读取文件失败: {}", e))?;
+    if meta.is_dir() {
+        return Err(format!("{} 是目录", path));
+    }
+    let mut bytes = Vec::new();
+    std::fs::File::open(&file)
+        .and_then(|f| f.take(MAX_READ_BYTES).read_to_end(&mut bytes))
+        .map_err(|e| format!("读取文件失败: {}", e))?;
+    let truncated = meta.len() > MAX_READ_BYTES;
+    let content = if bytes.contains(&0) {
+        None
+    } else {
+        match String::from_utf8(bytes) {
+            Ok(text) => Some(text),
+            // 截断处可能切开了一个多字节字符
+            Err(e) if truncated && e.utf8_error().error_len().is_none() => {
+                let valid = e.utf8_error().valid_up_to();
+                let mut bytes = e.into_bytes();
+                bytes.truncate(valid);
+                String::from_utf8(bytes).ok()
+            }
+            Err(_) => None,
+        }
+    };
+    Ok(WorkspaceFile {
+        path: relative_path(&root, &file),
+        size: meta.len(),
+        modified: modified_time(&meta),
+        is_binary: content.is_none(),
+        content,
+        truncated,
+    })
+}
+
+/// 删除工作区中的文件或目录
+#[guarded]
+#[command]
+pub async fn delete_workspace_file(
+    agent: String,
+    path: String,
+    confirm_token: Option<String>,
+) -> Result<(), String> {
+    policy::require_confirmation("delete_workspace_file", confirm_token.as_deref())?;
+    let root = workspace_root(&agent)?;
+    let target = resolve_in_workspace(&root, &path)?;
+    if target == root {
+        return Err("不能删除工作区根目录".to_string());
+    }
+    let relative = relative_path(&root, &target);
+    let result = if target.is_dir() {
+        std::fs::remove_dir_all(&target)
+    } else {
+        std::fs::remove_file(&target)
+    }
+    .map_err(|e| format!("删除失败: {}", e));
+    audit::record(
+        "delete_workspace_file",
+        &format!("{}:{}", validate_agent(&agent)?, relative),
+        result.is_ok(),
+        json!({ "error": result.as_ref().err() }),
+    );
+    result?;
+    info!("[工作区] ✓ 已删除 {}", relative);
+    Ok(())
+}
+
+/// 在系统文件管理器中打开工作区目录（传入文件时打开其所在目录）
+#[guarded]
+#[command]
+pub async fn open_in_file_manager(agent: String, path: Option<String>) -> Result<(), String> {
+    let root = workspace_root(&agent)?;
+    let target = resolve_in_workspace(&root, path.as_deref().unwrap_or(""))?;
+    let dir = if target.is_dir() {
+        target
+    } else {
+        target.parent().map(Path::to_path_buf).unwrap_or(root)
+    };
+    info!("[工作区] 打开目录: {}", dir.display());
+    open::that(&dir).map_err(|e| format!("打开文件管理器失败: {}", e))
+}
+
+/// 判断文件是否可疑：可执行文件（按扩展名或文件头）和超大压缩包
+fn classify(
+    name: &str,
+    size: u64,
+    header: &[u8],
+    large_archive_bytes: u64,
+) -> Option<SuspiciousKind> {
+    let ext = Path::new(name)
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    if EXECUTABLE_EXTENSIONS.contains(&ext.as_str())
+        || EXECUTABLE_MAGIC.iter().any(|m| header.starts_with(m))
+    {
+        return Some(SuspiciousKind::Executable);
+    }
+    if large_archive_bytes > 0
+        && size >= large_archive_bytes
+        && ARCHIVE_EXTENSIONS.contains(&ext.as_str())
+    {
+        return Some(SuspiciousKind::LargeArchive);
+    }
+    None
+}
+
+fn read_header(path: &Path) -> Vec<u8> {
+    let mut header = Vec::with_capacity(4);
+    let _ = std::fs::File::open(path).and_then(|f| f.take(4).read_to_end(&mut header));
+    header
+}
+
+/// 统计工作区大小并找出可疑文件（不跟随符号链接）
+fn scan_workspace(agent: &str, root: &Path, settings: &WorkspaceSettings) -> WorkspaceUsage {
+    let mut usage = WorkspaceUsage {
+        agent: agent.to_string(),
+        root: root.to_string_lossy().to_string(),
+        total_bytes: 0,
+        file_count: 0,
+        quota_bytes: (settings.quota_mb > 0).then(|| settings.quota_mb * MB),
+        over_quota: false,
+        suspicious: Vec::new(),
+        partial: false,
+    };
+    let mut pending = vec![root.to_path_buf()];
+    let mut seen = 0usize;
+    while let Some(dir) = pending.pop() {
+        for entry in std::fs::read_dir(&dir).into_iter().flatten().flatten() {
+            seen += 1;
+            if seen > MAX_SCAN_ENTRIES {
+                usage.partial = true;
+                pending.clear();
+                break;
+            }
+            // DirEntry::metadata 不跟随符号链接
+            let Ok(meta) = entry.metadata() else {
+                continue;
+            };
+            if meta.is_dir() {
+                pending.push(entry.path());
+                continue;
+            }
+            if !meta.is_file() {
+                continue;
+            }
+            usage.total_bytes += meta.len();
+            usage.file_count += 1;
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            if let Some(kind) = classify(
+                &name,
+                meta.len(),
+                &read_header(&path),
+                settings.large_archive_mb * MB,
+            ) {
+                usage.suspicious.push(SuspiciousFile {
+                    path: relative_path(root, &path),
+                    kind,
+                    size: meta.len(),
+                    modified: modified_time(&meta),
+                });
+            }
+        }
+    }
+    usage.over_quota = usage.quota_bytes.is_some_and(|q| usage.total_bytes > q);
+    usage.suspicious.sort_by(|a, b| a.path.cmp(&b.path));
+    usage
+}
+
+/// 统计所有 Agent 的工作区（跳过不存在的工作区）
+fn collect_usage(settings: &WorkspaceSettings) -> Vec<WorkspaceUsage> {
+    let cfg = config::load_openclaw_config().unwrap_or(Value::Null);
+    let config_dir = platform::get_config_dir();
+    agent_ids(&cfg)
+        .into_iter()
+        .filter_map(|agent| {
+            let root = resolve_workspace(&cfg, Path::new(&config_dir), &agent)
+                .canonicalize()
+                .ok()?;
+            Some(scan_workspace(&agent, &root, settings))
+        })
+        .collect()
+}
+
+fn format_mb(bytes: u64) -> String {
+    format!("{:.1}MB", bytes as f64 / MB as f64)
+}
+
+/// 根据本次统计生成告警，返回 (告警标识, 告警)
+/// `active` 收集本次仍然成立的告警标识，已在 `alerted` 中的不重复提醒；增长告警每次都会提醒
+fn evaluate_alerts(
+    usage: &WorkspaceUsage,
+    previous_bytes: Option<u64>,
+    settings: &WorkspaceSettings,
+    alerted: &HashSet<String>,
+    active: &mut HashSet<String>,
+) -> Vec<(String, WorkspaceAlert)> {
+    let mut alerts = Vec::new();
+    let mut push = |key: String, kind, message: String, path: Option<String>| {
+        if active.insert(key.clone()) && !alerted.contains(&key) {
+            alerts.push((
+                key,
+                WorkspaceAlert {
+                    agent: usage.agent.clone(),
+                    kind,
+                    message,
+                    path,
+                    total_bytes: usage.total_bytes,
+                },
+            ));
+        }
+    };
+    if let (true, Some(quota)) = (usage.over_quota, usage.quota_bytes) {
+        push(
+            format!("quota:{}", usage.agent),
+            WorkspaceAlertKind::QuotaExceeded,
+            format!(
+                "Agent {} 的工作区已占用 {}，超出上限 {}",
+                usage.agent,
+                format_mb(usage.total_bytes),
+                format_mb(quota)
+            ),
+            None,
+        );
+    }
+    for file in &usage.suspicious {
+        let what = match file.kind {
+            SuspiciousKind::Executable => "可执行文件",
+            SuspiciousKind::LargeArchive => "超大压缩包",
+        };
+        push(
+            format!("file:{}:{}:{}", usage.agent, file.path, file.size),
+            WorkspaceAlertKind::DangerousFile,
+            format!(
+                "Agent {} 的工作区中出现{}: {}（{}）",
+                usage.agent,
+                what,
+                file.path,
+                format_mb(file.size)
+            ),
+            Some(file.path.clone()),
+        );
+    }
+    let growth = usage
+        .total_bytes
+        .saturating_sub(previous_bytes.unwrap_or(usage.total_bytes));
+    if settings.growth_alert_mb > 0 && growth > settings.growth_alert_mb * MB {
+        alerts.push((
+            format!("growth:{}", usage.agent),
+            WorkspaceAlert {
+                agent: usage.agent.clone(),
+                kind: WorkspaceAlertKind::RapidGrowth,
+                message: format!(
+                    "Agent {} 的工作区短时间内增长了 {}，当前 {}",
+                    usage.agent,
+                    format_mb(growth),
+                    format_mb(usage.total_bytes)
+                ),
+                path: None,
+                total_bytes: usage.total_bytes,
+            },
+        ));
+    }
+    alerts
+}
+
+fn state_path() -> PathBuf {
+    Path::new(&platform::get_manager_data_dir()).join("workspace_state.json")
+}
+
+fn load_state() -> WorkspaceState {
+    std::fs::read_to_string(state_path())
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+fn save_state(state: &WorkspaceState) -> Result<(), String> {
+    let content = serde_json::to_vec_pretty(state).map_err(|e| e.to_string())?;
+    file::write_atomic(&state_path(), &content).map_err(|e| format!("保存工作区状态失败: {}", e))
+}
+
+/// 后台监控定期调用：检查工作区容量、增长速度和可疑文件，必要时停止 Gateway
+pub async fn check(app: &AppHandle) {
+    let settings = settings::load_settings().workspace;
+    let scan_settings = settings.clone();
+    let usages = match tokio::task::spawn_blocking(move || collect_usage(&scan_settings)).await {
+        Ok(usages) => usages,
+        Err(e) => {
+            debug!("[工作区] 统计失败: {}", e);
+            return;
+        }
+    };
+    let mut state = load_state();
+    let mut active = HashSet::new();
+    let mut alerts = Vec::new();
+    for usage in &usages {
+        let previous = state.sizes.get(&usage.agent).copied();
+        alerts.extend(evaluate_alerts(
+            usage,
+            previous,
+            &settings,
+            &state.alerted,
+            &mut active,
+        ));
+        state.sizes.insert(usage.agent.clone(), usage.total_bytes);
+    }
+    state.alerted = active;
+    if let Err(e) = save_state(&state) {
+        warn!("[工作区] {}", e);
+    }
+
+    for (key, alert) in &alerts {
+        warn!("[工作区] {}", alert.message);
+        let _ = events::emit(app, WORKSPACE_ALERT_EVENT, alert);
+        let title = match alert.kind {
+            WorkspaceAlertKind::QuotaExceeded => "Agent 工作区超出容量上限",
+            WorkspaceAlertKind::RapidGrowth => "Agent 工作区增长过快",
+            WorkspaceAlertKind::DangerousFile => "Agent 工作区出现可疑文件",
+        };
+        notifications::notify(
+            NotificationTrigger::WorkspaceAlert,
+            key,
+            title,
+            &alert.message,
+        );
+    }
+
+    let exceeded: Vec<&str> = alerts
+        .iter()
+        .filter(|(_, a)| a.kind == WorkspaceAlertKind::QuotaExceeded)
+        .map(|(_, a)| a.agent.as_str())
+        .collect();
+    if !settings.stop_at_quota || exceeded.is_empty() {
+        return;
+    }
+    let running = service::get_service_status()
+        .await
+        .map(|s| s.running)
+        .unwrap_or(false);
+    if running {
+        warn!("[工作区] 工作区超出容量上限，停止 Gateway");
+        let result = service::stop_service().await;
+        audit::record(
+            "workspace_quota_stop",
+            &exceeded.join(","),
+            result.is_ok(),
+            json!({ "quota_mb": settings.quota_mb, "error": result.err() }),
+        );
+    }
+}
+
+/// 各 Agent 工作区的占用情况和可疑文件
+#[guarded]
+#[command]
+pub async fn get_workspace_usage() -> Result<Vec<WorkspaceUsage>, String> {
+    let settings = settings::load_settings().workspace;
+    tokio::task::spawn_blocking(move || collect_usage(&settings))
+        .await
+        .map_err(|e| format!("统计工作区失败: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_workspaces_and_jails_paths() {
+        let config_dir = Path::new("/home/u/.openclaw");
+        let cfg = json!({"agents": {
+            "defaults": {"workspace": "/srv/agents/main"},
+            "list": [{"id": "coder", "workspace": "/srv/agents/coder"}, {"id": "writer"}]
+        }});
+        assert_eq!(
+            resolve_workspace(&cfg, config_dir, "main"),
+            PathBuf::from("/srv/agents/main")
+        );
+        assert_eq!(
+            resolve_workspace(&cfg, config_dir, "coder"),
+            PathBuf::from("/srv/agents/coder")
+        );
+        assert_eq!(
+            resolve_workspace(&cfg, config_dir, "writer"),
+            config_dir.join("workspace-writer")
+        );
+        assert!(validate_agent("../main").is_err());
+
+        let root = std::env::temp_dir()
+            .join(format!("workspace-test-{}", uuid::Uuid::new_v4().simple()))
+            .join("ws");
+        std::fs::create_dir_all(root.join("notes")).unwrap();
+        std::fs::write(root.join("notes/todo.md"), "- [ ] ship").unwrap();
+        std::fs::write(root.parent().unwrap().join("secret.txt"), "x").unwrap();
+        let root = root.canonicalize().unwrap();
+
+        let file = resolve_in_workspace(&root, "notes/todo.md").unwrap();
+        assert_eq!(relative_path(&root, &file), "notes/todo.md");
+        assert_eq!(resolve_in_workspace(&root, "").unwrap(), root);
+        assert!(resolve_in_workspace(&root, "../secret.txt").is_err());
+        assert!(resolve_in_workspace(&root, "notes/../../secret.txt").is_err());
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(
+                root.parent().unwrap().join("secret.txt"),
+                root.join("link"),
+            )
+            .unwrap();
+            assert!(resolve_in_workspace(&root, "link").is_err());
+        }
+        let _ = std::fs::remove_dir_all(root.parent().unwrap());
+    }
+
+    #[test]
+    fn flags_dangerous_files_and_alerts_once() {
+        let limit = 100 * MB;
+        assert_eq!(
+            classify("payload.EXE", 10, b"", limit),
+            Some(SuspiciousKind::Executable)
+        );
+        assert_eq!(
+            classify("a.out", 10, b"\x7fELF", limit),
+            Some(SuspiciousKind::Executable)
+        );
+        assert_eq!(
+            classify("dump.tar.gz", 200 * MB, b"\x1f\x8b", limit),
+            Some(SuspiciousKind::LargeArchive)
+        );
+        assert_eq!(classify("small.zip", MB, b"PK", limit), None);
+        assert_eq!(classify("notes.md", 10, b"# To", limit), None);
+
+        let settings = WorkspaceSettings {
+            quota_mb: 1024,
+            ..Default::default()
+        };
+        let usage = WorkspaceUsage {
+            agent: "main".to_string(),
+            root: "/ws".to_string(),
+            total_bytes: 2048 * MB,
+            file_count: 3,
+            quota_bytes: Some(1024 * MB),
+            over_quota: true,
+            suspicious: vec![SuspiciousFile {
+                path: "bin/tool.exe".to_string(),
+                kind: SuspiciousKind::Executable,
+                size: 10,
+                modified: None,
+            }],
+            partial: false,
+        };
+        let mut active = HashSet::new();
+        let alerts = evaluate_alerts(&usage, Some(MB), &settings, &HashSet::new(), &mut active);
+        let kinds: Vec<_> = alerts.iter().map(|(_, a)| a.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                WorkspaceAlertKind::QuotaExceeded,
+                WorkspaceAlertKind::DangerousFile,
+                WorkspaceAlertKind::RapidGrowth
+            ]
+        );
+        // 再次检查时已提醒过的容量和文件告警不重复，工作区未增长也不再提醒
+        let again = evaluate_alerts(
+            &usage,
+            Some(usage.total_bytes),
+            &settings,
+            &active.clone(),
+            &mut HashSet::new(),
+        );
+        assert!(again.is_empty());
+    }
+}
@@ -8,28 +8,100 @@ mod commands;
 mod models;
 mod utils;
 
-use commands::{config, diagnostics, installer, process, service};
+use commands::{
+    allowlist, analytics, backups, benchmark, browser, budgets, cache_proxy, capabilities,
+    certs, clipboard, compat, config, config_conflict, config_lint, consoles, crash, dashboard,
+    deep_link, dependencies, diagnostics, digest, downloads, embeddings, events, ffmpeg,
+    gateway_lock, git, hooks, install_plan, install_state, install_timings, installer,
+    inventory, jobs, knowledge, legacy, logs, maintenance, memory, migrations, monitor, network,
+    notifications, oauth, orphans, pairing, permissions, policy, presets, privacy, probe_cache,
+    process, profiles, provisioning, python, rate_limit, recovery, reports, requests, resources,
+    secret_handles, service, sessions, settings, shell_policy, shutdown, sidecar, skills,
+    source_build, startup, sync, telemetry, templates, trace, ui_preferences, updates,
+    vulnerabilities, watcher, window_state, winpkg, workspace,
+};
+use tauri::Manager;
 
 fn main() {
-    // 初始化日志 - 默认显示 info 级别日志
+    // 初始化日志 - 默认显示 info 级别日志，同时保留最近日志供崩溃报告使用
     env_logger::Builder::from_env(
         env_logger::Env::default().default_filter_or("info")
-    ).init();
+    )
+    .format(requests::format_record)
+    .target(env_logger::Target::Pipe(Box::new(crash::LogTee)))
+    .init();
+
+    // 以缓存代理子进程运行（由 Manager 自身启动）
+    if let Some(code) = cache_proxy::run_from_args() {
+        std::process::exit(code);
+    }
+    crash::install();
     
     log::info!("🦞 OpenClaw Manager 启动");
 
-    tauri::Builder::default()
+    let builder = tauri::Builder::default();
+    // 再次启动 Manager（包括打开深度链接）时聚焦已运行的实例，链接转发给深度链接插件
+    #[cfg(any(windows, target_os = "linux"))]
+    let builder = builder.plugin(tauri_plugin_single_instance::init(|app, _argv, _cwd| {
+        window_state::show_main(app);
+    }));
+    builder
+        .plugin(tauri_plugin_deep_link::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_process::init())
         .plugin(tauri_plugin_notification::init())
-        .invoke_handler(tauri::generate_handler![
+        .plugin(tauri_plugin_dialog::init())
+        .manage(utils::executor::ExecutorState::from_env())
+        .setup(|app| {
+            // 子进程执行后端（调试时可替换为脚本化的模拟执行）
+            utils::executor::install(&app.state::<utils::executor::ExecutorState>());
+            // 升级后先迁移 Manager 设置，再启动依赖设置的后台任务
+            migrations::run_startup_migrations();
+            // 切换到上次使用的配置档案（之后读取配置目录的任务都使用该档案）
+            profiles::init();
+            // 尽早注入 AppHandle，启动阶段的事件也能推送并记录供前端回放
+            events::init(app.handle());
+            // 恢复主窗口位置和大小，处理启动 Manager 的深度链接
+            window_state::restore(app.handle());
+            deep_link::init(app.handle());
+            // 读取系统主题（界面偏好跟随系统时使用）
+            ui_preferences::init(app.handle());
+            // 上次退出时中断的安装 / 更新任务重新排队，由后台监控继续执行
+            jobs::resume_interrupted();
+            // 让 npm / openclaw 子进程信任导入的企业根证书
+            certs::apply_env();
+            // 后台获取登录 shell 环境（PATH 中的 nvm / fnm / asdf / mise 等）
+            utils::login_env::warm_up();
+            // 启动后台监控（Gateway 崩溃、版本更新、渠道异常通知）
+            monitor::start(app.handle().clone());
+            // 监听配置目录的外部修改（如在编辑器中修改 openclaw.json）
+            watcher::start(app.handle().clone());
+            // 跟随 Gateway 日志，出现严重错误时实时提醒
+            logs::start(app.handle().clone());
+            // 已开启缓存代理时重新启动代理进程
+            cache_proxy::start_on_launch();
+            // 开启自动启动时，等待网络、DNS、钥匙串就绪后再启动 Gateway
+            startup::start();
+            Ok(())
+        })
+        .on_window_event(|window, event| {
+            window_state::on_window_event(window, event);
+            consoles::on_window_event(window, event);
+            ui_preferences::on_window_event(window, event);
+        })
+        .invoke_handler(policy::guard_invoke_handler(tauri::generate_handler![
             // 服务管理
             service::start_service,
             service::stop_service,
             service::restart_service,
+            service::adopt_gateway,
             service::get_service_status,
             service::get_logs,
+            logs::get_log_insights,
+            trace::trace_request,
+            startup::get_startup_report,
             service::send_agent_message,
             // 进程管理
             process::check_openclaw_installed,
@@ -38,6 +110,10 @@ fn main() {
             // 配置管理
             config::get_config,
             config::save_config,
+            config_conflict::get_config_conflict,
+            config_conflict::resolve_config_conflict,
+            config_lint::lint_config,
+            config_lint::migrate_config_keys,
             config::get_env_value,
             config::save_env_value,
             config::backup_user_config,
@@ -47,7 +123,7 @@ fn main() {
             config::clear_channel_config,
             // Gateway Token
             config::get_or_create_gateway_token,
-            config::get_dashboard_url,
+            config::open_dashboard,
             // AI 配置管理
             config::get_official_providers,
             config::get_ai_config,
@@ -61,22 +137,234 @@ fn main() {
             config::install_feishu_plugin,
             // 诊断测试
             diagnostics::run_doctor,
+            diagnostics::apply_fix,
             diagnostics::test_ai_connection,
             diagnostics::test_channel,
             diagnostics::get_system_info,
             diagnostics::start_channel_login,
+            network::run_network_diagnostics,
+            sessions::redact_session,
+            analytics::get_conversation_stats,
+            budgets::get_budget_status,
+            budgets::resume_budget_provider,
+            cache_proxy::get_cache_proxy_status,
+            cache_proxy::set_cache_proxy,
+            cache_proxy::clear_cache_proxy,
+            // 辅助进程
+            sidecar::list_sidecars,
+            sidecar::get_sidecar_logs,
+            sidecar::restart_sidecar,
+            network::select_fastest_mirrors,
+            certs::get_ca_certificate,
+            certs::import_ca_certificate,
+            certs::remove_ca_certificate,
+            downloads::get_download_cache_usage,
+            downloads::clear_download_cache,
+            downloads::list_downloads,
+            downloads::pause_download,
+            downloads::resume_download,
             // 安装器
             installer::check_environment,
+            inventory::get_inventory,
+            dependencies::check_uninstall,
+            legacy::detect_legacy_install,
+            legacy::migrate_legacy_install,
+            winpkg::get_package_managers,
+            winpkg::install_windows_package,
             installer::install_nodejs,
+            git::install_git,
+            ffmpeg::install_ffmpeg,
             installer::install_openclaw,
+            install_plan::plan_install,
+            install_state::get_install_state,
             installer::init_openclaw_config,
             installer::open_install_terminal,
             installer::uninstall_openclaw,
             // 版本更新
             installer::check_openclaw_update,
             installer::update_openclaw,
+            updates::get_staged_update,
+            updates::get_last_update,
+            updates::download_update,
+            updates::apply_update,
+            updates::set_update_on_restart,
+            maintenance::get_pending_maintenance_actions,
+            maintenance::schedule_gateway_restart,
+            maintenance::clear_maintenance_actions,
+            jobs::list_jobs,
+            jobs::cancel_job,
             installer::sync_openclaw_github,
-        ])
-        .run(tauri::generate_context!())
-        .expect("运行 Tauri 应用时发生错误");
+            installer::get_github_sync_state,
+            source_build::build_openclaw_from_source,
+            source_build::get_source_build_info,
+            // 批量部署
+            provisioning::apply_provisioning,
+            // 配置预设
+            presets::list_config_presets,
+            presets::apply_config_preset,
+            // Manager 设置
+            settings::get_settings,
+            settings::update_settings,
+            settings::list_timezones,
+            migrations::get_migration_status,
+            // 生命周期钩子
+            hooks::list_hooks,
+            hooks::register_hook,
+            hooks::remove_hook,
+            // 安全策略
+            policy::request_confirmation,
+            policy::get_policy_status,
+            permissions::audit_permissions,
+            permissions::fix_permissions,
+            privacy::get_privacy_status,
+            privacy::set_privacy_mode,
+            telemetry::get_telemetry_preview,
+            telemetry::set_telemetry,
+            crash::list_crash_reports,
+            crash::get_crash_report,
+            crash::delete_crash_report,
+            crash::submit_crash_report,
+            requests::get_request_trace,
+            benchmark::benchmark_provider,
+            benchmark::list_benchmark_results,
+            network::run_speed_test,
+            vulnerabilities::scan_openclaw_vulnerabilities,
+            vulnerabilities::get_vulnerability_scan,
+            skills::check_skill_updates,
+            skills::update_all_skills,
+            workspace::list_workspace_files,
+            workspace::read_workspace_file,
+            workspace::delete_workspace_file,
+            workspace::open_in_file_manager,
+            workspace::get_workspace_usage,
+            shell_policy::get_shell_policy,
+            shell_policy::save_shell_policy,
+            shell_policy::test_policy,
+            browser::detect_browser_runtimes,
+            browser::install_browser_runtime,
+            browser::set_browser_runtime,
+            python::detect_python,
+            python::get_skill_python_env,
+            python::create_skill_venv,
+            python::install_python_requirements,
+            python::set_skill_python,
+            // 系统通知
+            notifications::get_notification_settings,
+            notifications::save_notification_settings,
+            notifications::send_test_notification,
+            notifications::test_notification_target,
+            templates::list_message_templates,
+            templates::validate_message_template,
+            templates::save_message_template,
+            templates::delete_message_template,
+            templates::render_template_preview,
+            // OAuth 授权
+            oauth::list_oauth_presets,
+            oauth::list_oauth_connections,
+            oauth::start_oauth_flow,
+            oauth::refresh_oauth_token,
+            oauth::check_oauth_connection,
+            oauth::set_oauth_sync_targets,
+            oauth::disconnect_oauth,
+            // 扫码配对
+            pairing::list_qr_channels,
+            pairing::start_channel_pairing,
+            pairing::get_channel_pairing,
+            pairing::cancel_channel_pairing,
+            // 渠道访问控制
+            allowlist::list_channel_contacts,
+            allowlist::get_channel_access,
+            allowlist::validate_channel_access,
+            allowlist::save_channel_access,
+            // 渠道限流
+            rate_limit::get_rate_limits,
+            rate_limit::save_rate_limit,
+            rate_limit::simulate_rate_limit,
+            // Agent 记忆
+            memory::get_agent_memory,
+            memory::save_memory_settings,
+            memory::clear_agent_memory,
+            // 知识库
+            knowledge::list_knowledge_sources,
+            knowledge::add_knowledge_source,
+            knowledge::remove_knowledge_source,
+            knowledge::reindex_knowledge,
+            // Embedding Provider
+            embeddings::list_embedding_presets,
+            embeddings::get_embedding_config,
+            embeddings::test_embedding_provider,
+            embeddings::save_embedding_config,
+            // 报表导出
+            reports::export_report,
+            // 首页概览
+            dashboard::get_dashboard_snapshot,
+            // 探测缓存
+            probe_cache::list_probe_cache,
+            probe_cache::invalidate_cache,
+            // 事件回放
+            events::replay_events,
+            // 窗口状态与深度链接
+            window_state::get_window_state,
+            window_state::set_last_view,
+            deep_link::take_pending_deep_link,
+            // 独立控制台窗口
+            consoles::open_console,
+            consoles::list_consoles,
+            consoles::update_console_options,
+            consoles::close_console,
+            consoles::console_send_message,
+            // 界面偏好
+            ui_preferences::get_ui_preferences,
+            ui_preferences::set_ui_preferences,
+            // 剪贴板（密钥、脱敏配置）
+            clipboard::list_secrets,
+            clipboard::copy_secret_to_clipboard,
+            clipboard::export_sanitized_config,
+            // 密钥句柄
+            secret_handles::stage_secret,
+            // OpenClaw 能力检测
+            capabilities::get_capabilities,
+            // 版本兼容表
+            compat::get_compatibility_matrix,
+            compat::check_openclaw_compatibility,
+            // 安装耗时
+            install_timings::get_install_estimate,
+            // 磁盘 / 内存防护
+            resources::get_resource_status,
+            // 通知摘要
+            digest::get_daily_digest,
+            // 更新失败恢复
+            recovery::get_recovery_options,
+            recovery::execute_recovery,
+            // 残留进程清理
+            orphans::get_orphan_processes,
+            orphans::cleanup_orphans,
+            // Gateway 锁文件
+            gateway_lock::get_gateway_lock,
+            // 配置档案
+            profiles::list_profiles,
+            profiles::create_profile,
+            profiles::delete_profile,
+            profiles::switch_profile,
+            // 设置同步
+            sync::get_sync_status,
+            sync::configure_sync,
+            sync::sync_now,
+            // 远程备份
+            backups::get_backup_status,
+            backups::configure_remote_backup,
+            backups::list_remote_backups,
+            backups::create_remote_backup,
+            backups::restore_remote_backup,
+        ]))
+        .build(tauri::generate_context!())
+        .expect("运行 Tauri 应用时发生错误")
+        .run(|app, event| {
+            // 退出时结束辅助进程和进行中的操作，删除临时文件，清除仍在剪贴板中的密钥
+            if let tauri::RunEvent::Exit = event {
+                window_state::save();
+                clipboard::clear_on_exit(app);
+                shutdown::run();
+            }
+        });
 }
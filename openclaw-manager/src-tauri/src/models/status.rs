@@ -15,6 +15,19 @@ pub struct ServiceStatus {
     pub memory_mb: Option<f64>,
     /// CPU 使用率
     pub cpu_percent: Option<f64>,
+    /// Gateway 的启动方（未运行时为 None）
+    #[serde(default)]
+    pub managed_by: Option<GatewayOwner>,
+}
+
+/// Gateway 的启动方
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GatewayOwner {
+    /// 由 Manager 启动或已被 Manager 接管
+    Manager,
+    /// 在 Manager 之外启动（如终端中执行 openclaw gateway start），只监控状态，不按崩溃处理退出
+    External,
 }
 
 impl Default for ServiceStatus {
@@ -26,6 +39,7 @@ impl Default for ServiceStatus {
             uptime_seconds: None,
             memory_mb: None,
             cpu_percent: None,
+            managed_by: None,
         }
     }
 }
@@ -60,6 +74,9 @@ pub struct DiagnosticResult {
     pub message: String,
     /// 修复建议
     pub suggestion: Option<String>,
+    /// 可自动修复时的修复项 ID（传给 apply_fix）
+    #[serde(default)]
+    pub fix: Option<String>,
 }
 
 /// AI 连接测试结果
@@ -0,0 +1,58 @@
+use crate::utils::{file, panic_guard, platform, time};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+/// 审计日志条目（audit.log 中每行一条 JSON）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub timestamp: String,
+    /// 操作类型，如 hook
+    pub action: String,
+    /// 操作对象，如 pre_install/10-proxy.sh
+    pub target: String,
+    pub success: bool,
+    pub detail: Value,
+    /// 触发该操作的前端请求编号（后台任务为空）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+}
+
+/// 审计日志路径
+pub fn log_path() -> PathBuf {
+    Path::new(&platform::get_manager_data_dir()).join("audit.log")
+}
+
+/// 读取全部审计记录（跳过无法解析的行）
+pub fn read_entries() -> Vec<AuditEntry> {
+    std::fs::read_to_string(log_path())
+        .map(|c| {
+            c.lines()
+                .filter_map(|l| serde_json::from_str(l).ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// 追加一条审计记录（写入失败只记录警告，不影响调用方）
+pub fn record(action: &str, target: &str, success: bool, detail: Value) {
+    let entry = AuditEntry {
+        timestamp: time::now_rfc3339(),
+        action: action.to_string(),
+        target: target.to_string(),
+        success,
+        detail,
+        request_id: panic_guard::request_id(),
+    };
+    let _ = std::fs::create_dir_all(platform::get_manager_data_dir());
+    let path = log_path();
+    let result = serde_json::to_string(&entry)
+        .map_err(|e| e.to_string())
+        .and_then(|line| {
+            file::append_file(&path.to_string_lossy(), &line).map_err(|e| e.to_string())
+        });
+    if let Err(e) = result {
+        warn!("[审计] 写入审计日志失败: {}", e);
+    }
+}
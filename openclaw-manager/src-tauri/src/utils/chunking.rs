@@ -0,0 +1,120 @@
+//! 按内容分块（Gear 滚动哈希，差异备份使用）
+//! 分块边界只取决于附近的内容：文件中间插入或删除数据时，只有附近的块会变化，
+//! 其余块的哈希保持不变，差异备份据此只保存变化的块
+use std::io::{self, Read};
+use std::sync::OnceLock;
+
+/// 最小块大小
+const MIN_CHUNK: usize = 16 * 1024;
+/// 最大块大小（没有找到边界时强制切分）
+const MAX_CHUNK: usize = 256 * 1024;
+/// 哈希高位全为 0 时切分，平均块大小约为 MIN_CHUNK + 2^BOUNDARY_BITS
+const BOUNDARY_BITS: u32 = 16;
+
+/// Gear 表：固定种子生成的 256 个伪随机数，保证不同版本、不同电脑的分块结果一致
+fn gear_table() -> &'static [u64; 256] {
+    static TABLE: OnceLock<[u64; 256]> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        // splitmix64
+        let mut state: u64 = 0x6f70_656e_636c_6177;
+        let mut table = [0u64; 256];
+        for value in table.iter_mut() {
+            state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+            let mut z = state;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+            *value = z ^ (z >> 31);
+        }
+        table
+    })
+}
+
+/// 下一个块的长度
+fn next_boundary(data: &[u8]) -> usize {
+    if data.len() <= MIN_CHUNK {
+        return data.len();
+    }
+    let gear = gear_table();
+    let limit = data.len().min(MAX_CHUNK);
+    let mut hash: u64 = 0;
+    for (i, byte) in data.iter().enumerate().take(limit).skip(MIN_CHUNK) {
+        hash = (hash << 1).wrapping_add(gear[*byte as usize]);
+        if hash >> (64 - BOUNDARY_BITS) == 0 {
+            return i + 1;
+        }
+    }
+    limit
+}
+
+/// 从 reader 逐块读取的迭代器，见 `split_reader`
+pub struct Chunks<R> {
+    reader: R,
+    buf: Vec<u8>,
+    eof: bool,
+}
+
+/// 将 reader 中的数据切分为若干块（空数据不产生块）
+///
+/// 边界只取决于接下来最多 MAX_CHUNK 字节的内容，因此只需缓冲一个最大块，大文件无需整个读入内存
+pub fn split_reader<R: Read>(reader: R) -> Chunks<R> {
+    Chunks {
+        reader,
+        buf: Vec::with_capacity(MAX_CHUNK),
+        eof: false,
+    }
+}
+
+impl<R: Read> Iterator for Chunks<R> {
+    type Item = io::Result<Vec<u8>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut block = [0u8; 64 * 1024];
+        while !self.eof && self.buf.len() < MAX_CHUNK {
+            match self.reader.read(&mut block) {
+                Ok(0) => self.eof = true,
+                Ok(n) => self.buf.extend_from_slice(&block[..n]),
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Some(Err(e)),
+            }
+        }
+        if self.buf.is_empty() {
+            return None;
+        }
+        let len = next_boundary(&self.buf);
+        Some(Ok(self.buf.drain(..len).collect()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn split(data: &[u8]) -> Vec<Vec<u8>> {
+        split_reader(data).collect::<io::Result<_>>().unwrap()
+    }
+
+    #[test]
+    fn boundaries_follow_content() {
+        let mut state: u32 = 1;
+        let data: Vec<u8> = (0..2 * 1024 * 1024)
+            .map(|_| {
+                state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+                (state >> 16) as u8
+            })
+            .collect();
+        let chunks = split(&data);
+        assert_eq!(chunks.concat(), data);
+        assert!(chunks.len() > 4);
+        assert!(chunks[..chunks.len() - 1]
+            .iter()
+            .all(|c| c.len() >= MIN_CHUNK && c.len() <= MAX_CHUNK));
+
+        // 开头插入数据后，除第一个块外的其余块保持不变
+        let mut edited = b"inserted".to_vec();
+        edited.extend_from_slice(&data);
+        let edited_chunks = split(&edited);
+        let shared = chunks.iter().filter(|c| edited_chunks.contains(c)).count();
+        assert!(shared >= chunks.len() - 2);
+        assert!(split(&[]).is_empty());
+    }
+}
@@ -0,0 +1,188 @@
+//! 口令加密（设置同步、远程备份上传前使用）
+//! 口令经 PBKDF2-HMAC-SHA256 派生密钥，内容使用 XChaCha20-Poly1305 加密，头部作为附加认证数据；
+//! 随机数取自操作系统随机源
+//!
+//! 格式：`OCE2` | 迭代次数 (u32 BE) | salt (16) | nonce (24) | 密文 + tag (16)
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use sha2::Sha256;
+use std::collections::HashMap;
+
+const MAGIC: &[u8; 4] = b"OCE2";
+/// 默认的 PBKDF2 迭代次数
+pub const DEFAULT_ITERATIONS: u32 = 200_000;
+/// 解密时接受的最大迭代次数，避免损坏的头部导致长时间计算
+const MAX_ITERATIONS: u32 = 10_000_000;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+const TAG_LEN: usize = 16;
+const HEADER_LEN: usize = MAGIC.len() + 4 + SALT_LEN + NONCE_LEN;
+
+/// PBKDF2-HMAC-SHA256，输出 32 字节
+fn derive_key(passphrase: &str, salt: &[u8], iterations: u32) -> [u8; 32] {
+    let mut key = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), salt, iterations, &mut key);
+    key
+}
+
+/// 随机字节（操作系统随机源）
+pub fn random_bytes(len: usize) -> Vec<u8> {
+    let mut bytes = vec![0u8; len];
+    getrandom::getrandom(&mut bytes).expect("系统随机源不可用");
+    bytes
+}
+
+/// 数据是否为本模块加密的格式
+pub fn is_encrypted(data: &[u8]) -> bool {
+    data.starts_with(MAGIC)
+}
+
+/// 头部中的迭代次数和 salt
+fn parse_params(data: &[u8]) -> Result<(u32, &[u8]), String> {
+    let iterations = u32::from_be_bytes([data[4], data[5], data[6], data[7]]);
+    if iterations == 0 || iterations > MAX_ITERATIONS {
+        return Err("加密数据头部已损坏".to_string());
+    }
+    Ok((iterations, &data[8..8 + SALT_LEN]))
+}
+
+/// 加密器：口令只派生一次密钥，之后每段数据使用新的随机 nonce
+///
+/// 远程备份分多个对象上传，逐个加密时不必重复 PBKDF2 计算
+pub struct Sealer {
+    cipher: XChaCha20Poly1305,
+    iterations: u32,
+    salt: Vec<u8>,
+}
+
+impl Sealer {
+    pub fn new(passphrase: &str) -> Self {
+        Self::with_iterations(passphrase, DEFAULT_ITERATIONS)
+    }
+
+    fn with_iterations(passphrase: &str, iterations: u32) -> Self {
+        let salt = random_bytes(SALT_LEN);
+        let key = derive_key(passphrase, &salt, iterations);
+        Self {
+            cipher: XChaCha20Poly1305::new(&key.into()),
+            iterations,
+            salt,
+        }
+    }
+
+    pub fn seal(&self, plaintext: &[u8]) -> Vec<u8> {
+        let mut header = Vec::with_capacity(HEADER_LEN);
+        header.extend_from_slice(MAGIC);
+        header.extend_from_slice(&self.iterations.to_be_bytes());
+        header.extend_from_slice(&self.salt);
+        header.extend_from_slice(&random_bytes(NONCE_LEN));
+        let nonce = XNonce::clone_from_slice(&header[HEADER_LEN - NONCE_LEN..]);
+        let ciphertext = self
+            .cipher
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: plaintext,
+                    aad: &header,
+                },
+            )
+            .expect("XChaCha20-Poly1305 加密不会失败");
+        header.extend_from_slice(&ciphertext);
+        header
+    }
+}
+
+/// 解密器：按 (迭代次数, salt) 缓存派生的密钥，同一 Sealer 加密的多段数据只计算一次 PBKDF2
+pub struct Opener {
+    passphrase: String,
+    keys: HashMap<(u32, Vec<u8>), [u8; 32]>,
+}
+
+impl Opener {
+    pub fn new(passphrase: &str) -> Self {
+        Self {
+            passphrase: passphrase.to_string(),
+            keys: HashMap::new(),
+        }
+    }
+
+    fn key(&mut self, iterations: u32, salt: &[u8]) -> [u8; 32] {
+        *self
+            .keys
+            .entry((iterations, salt.to_vec()))
+            .or_insert_with(|| derive_key(&self.passphrase, salt, iterations))
+    }
+
+    /// 解密；口令错误或数据被篡改时返回错误
+    pub fn open(&mut self, data: &[u8]) -> Result<Vec<u8>, String> {
+        if !is_encrypted(data) || data.len() < HEADER_LEN + TAG_LEN {
+            return Err("不是有效的加密数据".to_string());
+        }
+        let (iterations, salt) = parse_params(data)?;
+        let key = self.key(iterations, salt);
+        let (header, ciphertext) = data.split_at(HEADER_LEN);
+        let nonce = XNonce::from_slice(&header[HEADER_LEN - NONCE_LEN..]);
+        XChaCha20Poly1305::new(&key.into())
+            .decrypt(
+                nonce,
+                Payload {
+                    msg: ciphertext,
+                    aad: header,
+                },
+            )
+            .map_err(|_| "口令错误或数据已损坏".to_string())
+    }
+}
+
+/// 使用口令加密
+pub fn encrypt(passphrase: &str, plaintext: &[u8]) -> Vec<u8> {
+    Sealer::new(passphrase).seal(plaintext)
+}
+
+/// 使用口令解密；口令错误或数据被篡改时返回错误
+pub fn decrypt(passphrase: &str, data: &[u8]) -> Result<Vec<u8>, String> {
+    Opener::new(passphrase).open(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_and_rejects_tampering() {
+        // PBKDF2-HMAC-SHA256 公开测试向量
+        assert_eq!(
+            hex::encode(derive_key("password", b"salt", 2)),
+            "ae4d0c95af6b46d32d0adff928f06dd02a303f8ef3c251dfd6e2d85a95474c43"
+        );
+
+        let plaintext = "设置同步 sync payload ".repeat(10);
+        let sealer = Sealer::with_iterations("correct horse", 10);
+        let sealed = sealer.seal(plaintext.as_bytes());
+        assert!(is_encrypted(&sealed));
+        assert_ne!(
+            &sealed[HEADER_LEN..HEADER_LEN + 16],
+            &plaintext.as_bytes()[..16]
+        );
+        assert_eq!(
+            decrypt("correct horse", &sealed).unwrap(),
+            plaintext.as_bytes()
+        );
+        assert!(decrypt("wrong", &sealed).is_err());
+
+        let mut tampered = sealed.clone();
+        tampered[HEADER_LEN + 3] ^= 1;
+        assert!(decrypt("correct horse", &tampered).is_err());
+        // 头部也受认证保护
+        let mut tampered = sealed.clone();
+        tampered[HEADER_LEN - 1] ^= 1;
+        assert!(decrypt("correct horse", &tampered).is_err());
+        assert!(decrypt("correct horse", b"plain text").is_err());
+        // 相同内容每次加密结果不同，同一 Opener 可以解密同一 Sealer 的多段数据
+        let second = sealer.seal(plaintext.as_bytes());
+        assert_ne!(second, sealed);
+        let mut opener = Opener::new("correct horse");
+        assert_eq!(opener.open(&sealed).unwrap(), plaintext.as_bytes());
+        assert_eq!(opener.open(&second).unwrap(), plaintext.as_bytes());
+    }
+}
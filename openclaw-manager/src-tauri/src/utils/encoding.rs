@@ -0,0 +1,83 @@
+use encoding_rs::Encoding;
+
+#[cfg(windows)]
+#[link(name = "kernel32")]
+extern "system" {
+    fn GetOEMCP() -> u32;
+    fn GetACP() -> u32;
+}
+
+/// 控制台程序（cmd、npm、node 等）输出使用的系统代码页
+/// 非 Windows 系统统一视为 UTF-8 (65001)
+pub fn system_code_page() -> u32 {
+    #[cfg(windows)]
+    {
+        // 控制台输出使用 OEM 代码页，取不到时退回 ANSI 代码页
+        let oem = unsafe { GetOEMCP() };
+        if oem != 0 {
+            oem
+        } else {
+            unsafe { GetACP() }
+        }
+    }
+
+    #[cfg(not(windows))]
+    {
+        65001
+    }
+}
+
+/// 代码页对应的编码
+fn encoding_for_code_page(code_page: u32) -> Option<&'static Encoding> {
+    let label = match code_page {
+        65001 => "utf-8",
+        936 | 54936 => "gb18030",
+        950 => "big5",
+        932 => "shift_jis",
+        949 => "euc-kr",
+        866 => "ibm866",
+        874 => "windows-874",
+        1250..=1258 => return Encoding::for_label(format!("windows-{}", code_page).as_bytes()),
+        // 437/850 等 OEM 代码页中 ASCII 部分与 windows-1252 一致
+        437 | 850 => "windows-1252",
+        _ => return None,
+    };
+    Encoding::for_label(label.as_bytes())
+}
+
+/// 按指定代码页解码命令输出
+/// 合法 UTF-8（如 PowerShell 设置了 UTF-8 输出、chcp 65001）直接返回，否则按代码页转换
+pub fn decode_with_code_page(bytes: &[u8], code_page: u32) -> String {
+    let bytes = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes);
+    if let Ok(s) = std::str::from_utf8(bytes) {
+        return s.to_string();
+    }
+    match encoding_for_code_page(code_page) {
+        Some(encoding) => encoding.decode(bytes).0.into_owned(),
+        None => String::from_utf8_lossy(bytes).into_owned(),
+    }
+}
+
+/// 将命令输出解码为 UTF-8 字符串（自动处理 Windows 中文系统的 GBK 输出）
+pub fn decode_output(bytes: &[u8]) -> String {
+    decode_with_code_page(bytes, system_code_page())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gbk_output_is_decoded_on_cp936() {
+        // "找不到文件" 的 GBK 编码
+        let gbk = [0xD5, 0xD2, 0xB2, 0xBB, 0xB5, 0xBD, 0xCE, 0xC4, 0xBC, 0xFE];
+        assert_eq!(decode_with_code_page(&gbk, 936), "找不到文件");
+    }
+
+    #[test]
+    fn utf8_output_is_kept_regardless_of_code_page() {
+        let text = "v22.12.0 安装成功";
+        assert_eq!(decode_with_code_page(text.as_bytes(), 936), text);
+        assert_eq!(decode_with_code_page(b"\xEF\xBB\xBFok", 65001), "ok");
+    }
+}
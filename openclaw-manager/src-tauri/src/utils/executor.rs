@@ -0,0 +1,358 @@
+use log::{info, warn};
+use serde::Deserialize;
+use std::io;
+use std::path::Path;
+use std::process::{Command, ExitStatus, Output};
+use std::sync::{Arc, Mutex, RwLock};
+
+/// 调试版本中通过该环境变量指定模拟脚本（JSON），所有子进程调用改为返回脚本中的输出，
+/// 用于界面端到端测试安装 / 更新 / 卸载流程
+pub const SCRIPT_ENV: &str = "OPENCLAW_MANAGER_EXEC_SCRIPT";
+
+/// 子进程执行后端：shell 模块构造好命令后交给它执行
+pub trait Executor: Send + Sync {
+    fn output(&self, command: &mut Command) -> io::Result<Output>;
+}
+
+/// 真实执行
+pub struct SystemExecutor;
+
+impl Executor for SystemExecutor {
+    fn output(&self, command: &mut Command) -> io::Result<Output> {
+        command.output()
+    }
+}
+
+/// 模拟执行的匹配规则
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Matcher {
+    /// 程序名（不含目录和扩展名）加完整参数，如 `["npm", "root", "-g"]`
+    Argv(Vec<String>),
+    /// bash / cmd / PowerShell 执行的脚本包含该文本
+    Script(String),
+}
+
+const SCRIPT_HOSTS: &[&str] = &["bash", "sh", "cmd", "powershell", "pwsh"];
+
+impl Matcher {
+    pub fn matches(&self, argv: &[String]) -> bool {
+        match self {
+            Matcher::Argv(expected) => expected == argv,
+            Matcher::Script(text) => {
+                argv.first()
+                    .is_some_and(|program| SCRIPT_HOSTS.contains(&program.as_str()))
+                    && argv
+                        .last()
+                        .is_some_and(|script| script.contains(text.as_str()))
+            }
+        }
+    }
+}
+
+/// 预设的调用和返回结果
+#[derive(Debug, Clone, Deserialize)]
+pub struct Expectation {
+    #[serde(rename = "match")]
+    pub matcher: Matcher,
+    #[serde(default)]
+    pub stdout: String,
+    #[serde(default)]
+    pub stderr: String,
+    #[serde(default)]
+    pub code: i32,
+    /// 可匹配的次数（None 表示不限），用完后由后面的同名规则接替
+    #[serde(default)]
+    pub times: Option<usize>,
+}
+
+#[cfg(unix)]
+fn exit_status(code: i32) -> ExitStatus {
+    use std::os::unix::process::ExitStatusExt;
+    ExitStatus::from_raw((code & 0xff) << 8)
+}
+
+#[cfg(windows)]
+fn exit_status(code: i32) -> ExitStatus {
+    use std::os::windows::process::ExitStatusExt;
+    ExitStatus::from_raw(code as u32)
+}
+
+/// 命令的 argv：程序名只保留文件名（去掉 .exe / .cmd），便于匹配绝对路径调用
+pub fn argv(command: &Command) -> Vec<String> {
+    let program = Path::new(command.get_program())
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    std::iter::once(program)
+        .chain(command.get_args().map(|a| a.to_string_lossy().to_string()))
+        .collect()
+}
+
+/// 脚本化的模拟执行：按顺序匹配预设规则返回输出，记录所有调用；
+/// 没有匹配的规则时返回 NotFound（等同于命令不存在）
+#[derive(Default)]
+pub struct MockExecutor {
+    expectations: Mutex<Vec<Expectation>>,
+    calls: Mutex<Vec<Vec<String>>>,
+}
+
+impl MockExecutor {
+    /// 从 JSON 脚本加载（`[{"match": {"argv": [...]}, "stdout": "...", "code": 0}]`）
+    pub fn from_json(content: &str) -> Result<Self, String> {
+        let expectations: Vec<Expectation> =
+            serde_json::from_str(content).map_err(|e| format!("解析模拟脚本失败: {}", e))?;
+        Ok(Self {
+            expectations: Mutex::new(expectations),
+            calls: Mutex::default(),
+        })
+    }
+}
+
+/// 测试中链式预设规则
+#[cfg(test)]
+impl MockExecutor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn push(&self, matcher: Matcher) -> ExpectationBuilder<'_> {
+        ExpectationBuilder {
+            mock: self,
+            expectation: Some(Expectation {
+                matcher,
+                stdout: String::new(),
+                stderr: String::new(),
+                code: 0,
+                times: None,
+            }),
+        }
+    }
+
+    /// 预设一条完整命令的返回结果
+    pub fn expect(&self, argv: &[&str]) -> ExpectationBuilder<'_> {
+        self.push(Matcher::Argv(argv.iter().map(|a| a.to_string()).collect()))
+    }
+
+    /// 预设包含指定文本的脚本的返回结果
+    pub fn expect_script(&self, contains: &str) -> ExpectationBuilder<'_> {
+        self.push(Matcher::Script(contains.to_string()))
+    }
+
+    /// 所有调用（按执行顺序）
+    pub fn calls(&self) -> Vec<Vec<String>> {
+        self.calls.lock().map(|c| c.clone()).unwrap_or_default()
+    }
+
+    /// 是否执行过匹配该规则的命令
+    pub fn called(&self, matcher: &Matcher) -> bool {
+        self.calls().iter().any(|argv| matcher.matches(argv))
+    }
+}
+
+/// 链式设置返回结果，离开作用域时加入模拟执行的规则列表
+#[cfg(test)]
+pub struct ExpectationBuilder<'a> {
+    mock: &'a MockExecutor,
+    expectation: Option<Expectation>,
+}
+
+#[cfg(test)]
+impl ExpectationBuilder<'_> {
+    fn with(mut self, f: impl FnOnce(&mut Expectation)) -> Self {
+        if let Some(expectation) = self.expectation.as_mut() {
+            f(expectation);
+        }
+        self
+    }
+
+    pub fn stdout(self, stdout: &str) -> Self {
+        self.with(|e| e.stdout = stdout.to_string())
+    }
+
+    pub fn stderr(self, stderr: &str) -> Self {
+        self.with(|e| e.stderr = stderr.to_string())
+    }
+
+    pub fn code(self, code: i32) -> Self {
+        self.with(|e| e.code = code)
+    }
+
+    /// 只匹配一次
+    pub fn once(self) -> Self {
+        self.with(|e| e.times = Some(1))
+    }
+}
+
+#[cfg(test)]
+impl Drop for ExpectationBuilder<'_> {
+    fn drop(&mut self) {
+        if let (Some(expectation), Ok(mut list)) =
+            (self.expectation.take(), self.mock.expectations.lock())
+        {
+            list.push(expectation);
+        }
+    }
+}
+
+impl Executor for MockExecutor {
+    fn output(&self, command: &mut Command) -> io::Result<Output> {
+        let argv = argv(command);
+        if let Ok(mut calls) = self.calls.lock() {
+            calls.push(argv.clone());
+        }
+        let mut expectations = self
+            .expectations
+            .lock()
+            .map_err(|_| io::Error::other("模拟执行状态已损坏"))?;
+        let Some(expectation) = expectations
+            .iter_mut()
+            .find(|e| e.times != Some(0) && e.matcher.matches(&argv))
+        else {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("模拟执行没有匹配的规则: {:?}", argv),
+            ));
+        };
+        if let Some(times) = expectation.times.as_mut() {
+            *times -= 1;
+        }
+        Ok(Output {
+            status: exit_status(expectation.code),
+            stdout: expectation.stdout.clone().into_bytes(),
+            stderr: expectation.stderr.clone().into_bytes(),
+        })
+    }
+}
+
+/// 注入到 Tauri 状态中的执行后端
+#[derive(Clone)]
+pub struct ExecutorState(pub Arc<dyn Executor>);
+
+impl ExecutorState {
+    /// 默认真实执行；调试版本设置了 [`SCRIPT_ENV`] 时改用脚本化的模拟执行
+    pub fn from_env() -> Self {
+        let script = std::env::var(SCRIPT_ENV)
+            .ok()
+            .filter(|path| cfg!(debug_assertions) && !path.is_empty());
+        if let Some(path) = script {
+            match std::fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|content| MockExecutor::from_json(&content))
+            {
+                Ok(mock) => {
+                    info!("[执行后端] 使用模拟执行脚本: {}", path);
+                    return Self(Arc::new(mock));
+                }
+                Err(e) => warn!("[执行后端] 加载模拟执行脚本 {} 失败: {}", path, e),
+            }
+        }
+        Self(Arc::new(SystemExecutor))
+    }
+}
+
+static CURRENT: RwLock<Option<Arc<dyn Executor>>> = RwLock::new(None);
+
+/// 设置全局执行后端（启动时从 Tauri 状态取出）
+pub fn install(state: &ExecutorState) {
+    if let Ok(mut current) = CURRENT.write() {
+        *current = Some(state.0.clone());
+    }
+}
+
+/// 当前的执行后端（未设置时为真实执行）
+pub fn current() -> Arc<dyn Executor> {
+    CURRENT
+        .read()
+        .ok()
+        .and_then(|current| current.clone())
+        .unwrap_or_else(|| Arc::new(SystemExecutor))
+}
+
+/// 测试中替换执行后端：持有期间其他替换（以及需要真实执行的测试）等待，离开作用域时恢复
+#[cfg(test)]
+pub struct Scoped {
+    _lock: std::sync::MutexGuard<'static, ()>,
+}
+
+#[cfg(test)]
+static TEST_LOCK: Mutex<()> = Mutex::new(());
+
+#[cfg(test)]
+fn replace(executor: Option<Arc<dyn Executor>>) {
+    if let Ok(mut current) = CURRENT.write() {
+        *current = executor;
+    }
+}
+
+#[cfg(test)]
+pub fn scoped(executor: Arc<dyn Executor>) -> Scoped {
+    let lock = TEST_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    replace(Some(executor));
+    Scoped { _lock: lock }
+}
+
+/// 测试中确保使用真实执行（不被并行运行的模拟测试替换）
+#[cfg(test)]
+pub fn scoped_system() -> Scoped {
+    scoped(Arc::new(SystemExecutor))
+}
+
+#[cfg(test)]
+impl Drop for Scoped {
+    fn drop(&mut self) {
+        replace(None);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mock_matches_argv_and_scripts_in_order() {
+        let mock = MockExecutor::new();
+        mock.expect(&["which", "openclaw"]).code(1).once();
+        mock.expect(&["which", "openclaw"])
+            .stdout("/usr/local/bin/openclaw");
+        mock.expect_script("npm root -g")
+            .stdout("/usr/lib/node_modules\n");
+
+        let mut which = Command::new("/usr/bin/which");
+        which.arg("openclaw");
+        assert_eq!(mock.output(&mut which).unwrap().status.code(), Some(1));
+        let second = mock.output(&mut which).unwrap();
+        assert!(second.status.success());
+        assert_eq!(second.stdout, b"/usr/local/bin/openclaw");
+
+        let mut bash = Command::new("bash");
+        bash.args(["-c", "npm root -g 2>/dev/null"]);
+        assert_eq!(
+            mock.output(&mut bash).unwrap().stdout,
+            b"/usr/lib/node_modules\n"
+        );
+
+        let mut npm = Command::new("npm");
+        npm.args(["install", "-g", "openclaw"]);
+        let err = mock.output(&mut npm).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+        assert_eq!(mock.calls().len(), 4);
+        assert!(mock.called(&Matcher::Argv(vec![
+            "npm".into(),
+            "install".into(),
+            "-g".into(),
+            "openclaw".into()
+        ])));
+
+        let scripted = MockExecutor::from_json(
+            r#"[{"match": {"script": "npm view"}, "stdout": "2.0.0"},
+                {"match": {"argv": ["openclaw", "--version"]}, "code": 2, "stderr": "boom"}]"#,
+        )
+        .unwrap();
+        let mut version = Command::new("openclaw.cmd");
+        version.arg("--version");
+        let output = scripted.output(&mut version).unwrap();
+        assert_eq!(output.status.code(), Some(2));
+        assert_eq!(output.stderr, b"boom");
+    }
+}
@@ -1,25 +1,107 @@
 use std::fs;
-use std::io::{self, BufRead, BufReader};
-use std::path::Path;
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// 临时文件序号：同一进程内并发写入同一文件时各自使用不同的临时文件
+static TMP_SEQ: AtomicU64 = AtomicU64::new(0);
 
 /// 读取文件内容
 pub fn read_file(path: &str) -> io::Result<String> {
     fs::read_to_string(path)
 }
 
-/// 写入文件内容
+/// 写入文件内容（原子写入，见 [`write_atomic`]）
 pub fn write_file(path: &str, content: &str) -> io::Result<()> {
+    write_atomic(Path::new(path), content.as_bytes())
+}
+
+/// 原子写入：先写同目录下的临时文件并 fsync，再 rename 覆盖目标文件
+/// 写入过程中崩溃或断电时，目标文件要么是旧内容，要么是完整的新内容。
+/// 临时文件以 600 权限创建；覆盖已有文件时沿用原文件权限，新文件保持 600。
+/// 目标是符号链接时（如 dotfiles 管理的 openclaw.json）写入链接指向的文件，链接本身保持不变
+pub fn write_atomic(path: &Path, content: &[u8]) -> io::Result<()> {
+    let resolved;
+    let path = if fs::symlink_metadata(path).is_ok_and(|m| m.file_type().is_symlink()) {
+        resolved = resolve_symlink(path)?;
+        resolved.as_path()
+    } else {
+        path
+    };
+
     // 确保父目录存在
-    if let Some(parent) = Path::new(path).parent() {
-        fs::create_dir_all(parent)?;
+    let parent = match path.parent() {
+        Some(p) if !p.as_os_str().is_empty() => p,
+        _ => Path::new("."),
+    };
+    fs::create_dir_all(parent)?;
+
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "无效的文件路径"))?;
+    let tmp_path = parent.join(format!(
+        ".{}.tmp-{}-{}",
+        file_name.to_string_lossy(),
+        std::process::id(),
+        TMP_SEQ.fetch_add(1, Ordering::Relaxed)
+    ));
+
+    let result = (|| {
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut tmp = options.open(&tmp_path)?;
+        tmp.write_all(content)?;
+        // 保留原文件权限（如 .env、凭据文件的 600）
+        if let Ok(meta) = fs::metadata(path) {
+            tmp.set_permissions(meta.permissions())?;
+        }
+        tmp.sync_all()?;
+        drop(tmp);
+        fs::rename(&tmp_path, path)
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp_path);
+        return result;
+    }
+
+    // rename 本身也需要落盘（Windows 不支持对目录 fsync）
+    #[cfg(unix)]
+    if let Ok(dir) = fs::File::open(parent) {
+        let _ = dir.sync_all();
     }
-    fs::write(path, content)
+    Ok(())
+}
+
+/// 符号链接指向的真实路径（链接目标还不存在时按链接内容计算）
+fn resolve_symlink(path: &Path) -> io::Result<PathBuf> {
+    match fs::canonicalize(path) {
+        Ok(real) => Ok(real),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            let target = fs::read_link(path)?;
+            Ok(match path.parent() {
+                Some(parent) => parent.join(target),
+                None => target,
+            })
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// 原子复制文件（用于备份），保留源文件权限
+pub fn copy_atomic(src: &Path, dst: &Path) -> io::Result<()> {
+    let content = fs::read(src)?;
+    write_atomic(dst, &content)?;
+    fs::set_permissions(dst, fs::metadata(src)?.permissions())
 }
 
 /// 追加文件内容
 pub fn append_file(path: &str, content: &str) -> io::Result<()> {
     use std::fs::OpenOptions;
-    use std::io::Write;
     
     let mut file = OpenOptions::new()
         .create(true)
@@ -96,3 +178,105 @@ pub fn remove_env_value(env_file: &str, key: &str) -> io::Result<()> {
     
     write_file(env_file, &lines.join("\n"))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn make_temp_dir(prefix: &str) -> PathBuf {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!("{}-{}", prefix, nanos));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn write_atomic_replaces_content_without_leaving_temp_files() {
+        let dir = make_temp_dir("openclaw-atomic-write");
+        let path = dir.join("nested").join("openclaw.json");
+
+        write_atomic(&path, b"{\"a\":1}").unwrap();
+        write_atomic(&path, b"{\"a\":2}").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "{\"a\":2}");
+        let entries: Vec<_> = fs::read_dir(path.parent().unwrap()).unwrap().collect();
+        assert_eq!(entries.len(), 1);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o600)).unwrap();
+            write_atomic(&path, b"{}").unwrap();
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn write_atomic_keeps_symlinked_target() {
+        let dir = make_temp_dir("openclaw-atomic-symlink");
+        let real = dir.join("dotfiles").join("openclaw.json");
+        fs::create_dir_all(real.parent().unwrap()).unwrap();
+        fs::write(&real, "{}").unwrap();
+        let link = dir.join("openclaw.json");
+        std::os::unix::fs::symlink(&real, &link).unwrap();
+        let is_link = |p: &Path| fs::symlink_metadata(p).unwrap().file_type().is_symlink();
+
+        write_atomic(&link, b"{\"a\":1}").unwrap();
+        assert!(is_link(&link));
+        assert_eq!(fs::read_to_string(&real).unwrap(), "{\"a\":1}");
+
+        // 链接目标还不存在时（相对路径）创建目标文件
+        let dangling = dir.join("settings.json");
+        std::os::unix::fs::symlink("dotfiles/settings.json", &dangling).unwrap();
+        write_atomic(&dangling, b"{}").unwrap();
+        assert!(is_link(&dangling));
+        assert_eq!(
+            fs::read_to_string(dir.join("dotfiles").join("settings.json")).unwrap(),
+            "{}"
+        );
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn concurrent_writers_use_separate_temp_files() {
+        let dir = make_temp_dir("openclaw-atomic-concurrent");
+        let path = dir.join("settings.json");
+        let writers: Vec<_> = (0..8)
+            .map(|i| {
+                let path = path.clone();
+                std::thread::spawn(move || {
+                    let content = format!("{{\"writer\":{}}}", i).repeat(2000);
+                    for _ in 0..20 {
+                        write_atomic(&path, content.as_bytes()).unwrap();
+                    }
+                    content
+                })
+            })
+            .collect();
+        let contents: Vec<String> = writers.into_iter().map(|w| w.join().unwrap()).collect();
+
+        // 最终内容是某一个写入者的完整内容，且没有残留的临时文件
+        assert!(contents.contains(&fs::read_to_string(&path).unwrap()));
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let secret = dir.join("credentials.json");
+            write_atomic(&secret, b"{}").unwrap();
+            let mode = fs::metadata(&secret).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
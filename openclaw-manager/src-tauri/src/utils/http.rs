@@ -0,0 +1,51 @@
+use crate::commands::{certs, settings};
+use std::time::Duration;
+
+fn builder(timeout: Duration) -> reqwest::ClientBuilder {
+    let builder = reqwest::Client::builder()
+        .user_agent(concat!("openclaw-manager/", env!("CARGO_PKG_VERSION")))
+        .timeout(timeout);
+    // 信任导入的企业根证书
+    certs::root_certificates()
+        .into_iter()
+        .fold(builder, |b, cert| b.add_root_certificate(cert))
+}
+
+fn build(builder: reqwest::ClientBuilder) -> Result<reqwest::Client, String> {
+    builder
+        .build()
+        .map_err(|e| format!("创建 HTTP 客户端失败: {}", e))
+}
+
+/// 创建 HTTP 客户端（统一 User-Agent，超时和代理读取 Manager 设置）
+pub fn client() -> Result<reqwest::Client, String> {
+    let settings = settings::load_settings();
+    let timeout = Duration::from_secs(settings.timeouts.http_secs.max(1));
+    match settings.proxy.as_deref().filter(|p| !p.is_empty()) {
+        Some(proxy) => build_client(Some(proxy), timeout),
+        None => build(builder(timeout)),
+    }
+}
+
+/// 下载大文件用的 HTTP 客户端（超时使用 download_secs）
+pub fn download_client() -> Result<reqwest::Client, String> {
+    let settings = settings::load_settings();
+    let timeout = Duration::from_secs(settings.timeouts.download_secs.max(1));
+    match settings.proxy.as_deref().filter(|p| !p.is_empty()) {
+        Some(proxy) => build_client(Some(proxy), timeout),
+        None => build(builder(timeout)),
+    }
+}
+
+/// 创建指定代理和超时的 HTTP 客户端
+/// `proxy` 为 None 时强制直连（忽略 HTTP_PROXY 等环境变量），用于网络诊断对比
+pub fn build_client(proxy: Option<&str>, timeout: Duration) -> Result<reqwest::Client, String> {
+    let builder = match proxy {
+        Some(proxy) => {
+            let proxy = reqwest::Proxy::all(proxy).map_err(|e| format!("代理地址无效: {}", e))?;
+            builder(timeout).proxy(proxy)
+        }
+        None => builder(timeout).no_proxy(),
+    };
+    build(builder)
+}
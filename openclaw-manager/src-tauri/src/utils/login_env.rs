@@ -0,0 +1,167 @@
+use log::{info, warn};
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+/// 登录 shell 输出中环境变量的起止标记（rc 文件可能打印欢迎信息等内容）
+const BEGIN_MARKER: &str = "__OPENCLAW_ENV_BEGIN__";
+const END_MARKER: &str = "__OPENCLAW_ENV_END__";
+/// 等待登录 shell 的最长时间（rc 文件中可能有交互提示或很慢的初始化）
+const CAPTURE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 登录 shell 的环境变量（首次使用时获取，之后复用）
+static LOGIN_ENV: OnceLock<Option<HashMap<String, String>>> = OnceLock::new();
+
+fn is_env_key(key: &str) -> bool {
+    !key.is_empty()
+        && !key.starts_with(|c: char| c.is_ascii_digit())
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// 解析 `env` 的输出；不含合法 `KEY=` 前缀的行视为上一个值的续行（多行值）
+fn parse_env(output: &str) -> HashMap<String, String> {
+    let body = match (output.find(BEGIN_MARKER), output.rfind(END_MARKER)) {
+        (Some(start), Some(end)) if start < end => &output[start + BEGIN_MARKER.len()..end],
+        _ => output,
+    };
+    let mut vars = HashMap::new();
+    let mut last: Option<String> = None;
+    for line in body.lines() {
+        match line.split_once('=') {
+            Some((key, value)) if is_env_key(key) => {
+                vars.insert(key.to_string(), value.to_string());
+                last = Some(key.to_string());
+            }
+            _ => {
+                if let Some(value) = last.as_ref().and_then(|k| vars.get_mut(k)) {
+                    value.push('\n');
+                    value.push_str(line);
+                }
+            }
+        }
+    }
+    vars
+}
+
+/// 运行 `$SHELL -ilc env`，超时或失败时返回 None
+fn capture() -> Option<HashMap<String, String>> {
+    let shell = std::env::var("SHELL")
+        .ok()
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| "/bin/sh".to_string());
+    let script = format!("echo {}; env; echo {}", BEGIN_MARKER, END_MARKER);
+    let started = Instant::now();
+    let mut child = match Command::new(&shell)
+        .args(["-ilc", &script])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(e) => {
+            warn!("[环境] 启动登录 shell {} 失败: {}", shell, e);
+            return None;
+        }
+    };
+
+    // 另起线程读取输出，避免管道写满导致 shell 阻塞
+    let mut stdout = child.stdout.take()?;
+    let reader = std::thread::spawn(move || {
+        let mut buf = Vec::new();
+        let _ = stdout.read_to_end(&mut buf);
+        buf
+    });
+    loop {
+        match child.try_wait() {
+            Ok(Some(_)) => break,
+            Ok(None) if started.elapsed() < CAPTURE_TIMEOUT => {
+                std::thread::sleep(Duration::from_millis(50))
+            }
+            _ => {
+                let _ = child.kill();
+                let _ = child.wait();
+                warn!(
+                    "[环境] 登录 shell {} 超过 {} 秒未返回，使用默认环境",
+                    shell,
+                    CAPTURE_TIMEOUT.as_secs()
+                );
+                return None;
+            }
+        }
+    }
+    let output = String::from_utf8_lossy(&reader.join().ok()?).to_string();
+    if !output.contains(END_MARKER) {
+        warn!("[环境] 登录 shell {} 输出不完整，使用默认环境", shell);
+        return None;
+    }
+    let vars = parse_env(&output);
+    info!(
+        "[环境] ✓ 已获取登录 shell 环境（{}，{} 个变量，耗时 {}ms）",
+        shell,
+        vars.len(),
+        started.elapsed().as_millis()
+    );
+    Some(vars)
+}
+
+/// 登录 shell 的环境变量（macOS / Linux），Windows 或获取失败时返回 None
+/// 包含用户在 .zshrc / .bash_profile 等文件中配置的 PATH（nvm、fnm、asdf、mise 等）
+pub fn vars() -> Option<&'static HashMap<String, String>> {
+    if cfg!(windows) {
+        return None;
+    }
+    LOGIN_ENV.get_or_init(capture).as_ref()
+}
+
+/// 登录 shell 中的 PATH 目录
+pub fn path_dirs() -> Vec<String> {
+    vars()
+        .and_then(|v| v.get("PATH"))
+        .map(|p| {
+            p.split(':')
+                .filter(|d| !d.is_empty())
+                .map(|d| d.to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// 在登录 shell 的 PATH 中查找可执行文件
+pub fn find_executable(name: &str) -> Option<PathBuf> {
+    path_dirs()
+        .iter()
+        .map(|dir| Path::new(dir).join(name))
+        .find(|p| p.is_file())
+}
+
+/// 后台预先获取（启动时调用，避免首次执行命令时等待登录 shell）
+pub fn warm_up() {
+    if cfg!(not(windows)) {
+        std::thread::spawn(vars);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_env_between_markers_with_multiline_values() {
+        let output = format!(
+            "Welcome back!\n{}\nPATH=/Users/me/.local/share/fnm/node-versions/v22/bin:/usr/bin\nMULTI=line one\nline two\nHOME=/Users/me\n{}\n",
+            BEGIN_MARKER, END_MARKER
+        );
+        let vars = parse_env(&output);
+        assert_eq!(vars.len(), 3);
+        assert_eq!(
+            vars["PATH"],
+            "/Users/me/.local/share/fnm/node-versions/v22/bin:/usr/bin"
+        );
+        assert_eq!(vars["MULTI"], "line one\nline two");
+        assert!(!vars.contains_key("Welcome back!"));
+    }
+}
@@ -1,3 +1,16 @@
+pub mod audit;
+pub mod chunking;
+pub mod crypto;
+pub mod encoding;
+pub mod executor;
 pub mod file;
+pub mod http;
+pub mod login_env;
+pub mod node_paths;
+pub mod panic_guard;
 pub mod platform;
+pub mod remote_storage;
+pub mod secrets;
 pub mod shell;
+pub mod temp;
+pub mod time;
@@ -0,0 +1,637 @@
+use std::path::Path;
+
+/// 路径探测依赖的文件系统和环境变量，测试中用虚拟目录树代替
+pub trait PathEnv {
+    fn home_dir(&self) -> Option<String>;
+    /// 环境变量（未设置或为空时返回 None）
+    fn var(&self, key: &str) -> Option<String>;
+    fn exists(&self, path: &str) -> bool;
+    fn read_to_string(&self, path: &str) -> Option<String>;
+    /// 目录下的条目名称
+    fn list_dir(&self, path: &str) -> Vec<String>;
+}
+
+/// 真实文件系统和进程环境变量
+pub struct SystemEnv;
+
+impl PathEnv for SystemEnv {
+    fn home_dir(&self) -> Option<String> {
+        dirs::home_dir().map(|home| home.display().to_string())
+    }
+
+    fn var(&self, key: &str) -> Option<String> {
+        std::env::var(key).ok().filter(|v| !v.is_empty())
+    }
+
+    fn exists(&self, path: &str) -> bool {
+        Path::new(path).exists()
+    }
+
+    fn read_to_string(&self, path: &str) -> Option<String> {
+        std::fs::read_to_string(path).ok()
+    }
+
+    fn list_dir(&self, path: &str) -> Vec<String> {
+        std::fs::read_dir(path)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .collect()
+    }
+}
+
+/// 解析 `v22.12.0` / `22.12.0` 为可比较的版本号
+fn parse_version(name: &str) -> Option<Vec<u32>> {
+    name.trim()
+        .trim_start_matches('v')
+        .split('.')
+        .map(|part| part.parse().ok())
+        .collect()
+}
+
+/// Node.js 和 openclaw 可执行文件的候选路径（按优先顺序）
+/// GUI 应用不继承用户 shell 的 PATH，需要按各种版本管理器的目录布局逐个检查
+pub struct PathResolver<E: PathEnv> {
+    env: E,
+    windows: bool,
+}
+
+/// 当前系统的路径探测
+pub fn system() -> PathResolver<SystemEnv> {
+    PathResolver::new(SystemEnv, cfg!(windows))
+}
+
+impl<E: PathEnv> PathResolver<E> {
+    pub fn new(env: E, windows: bool) -> Self {
+        Self { env, windows }
+    }
+
+    fn join(&self, base: &str, parts: &[&str]) -> String {
+        let sep = if self.windows { '\\' } else { '/' };
+        let mut path = base.trim_end_matches(['/', '\\']).to_string();
+        for part in parts {
+            path.push(sep);
+            path.push_str(part);
+        }
+        path
+    }
+
+    fn home(&self, parts: &[&str]) -> Option<String> {
+        self.env.home_dir().map(|home| self.join(&home, parts))
+    }
+
+    /// 环境变量指定的目录，未设置时使用用户目录下的默认位置
+    fn var_or_home(&self, key: &str, default: &[&str]) -> Option<String> {
+        self.env.var(key).or_else(|| self.home(default))
+    }
+
+    /// 目录下已安装的版本（`v22.12.0` 形式的子目录），新版本在前
+    fn installed_versions(&self, dir: &str) -> Vec<String> {
+        let mut versions: Vec<(Vec<u32>, String)> = self
+            .env
+            .list_dir(dir)
+            .into_iter()
+            .filter(|name| name.starts_with('v'))
+            .filter_map(|name| parse_version(&name).map(|v| (v, name)))
+            .collect();
+        versions.sort_by(|a, b| b.0.cmp(&a.0));
+        versions.into_iter().map(|(_, name)| name).collect()
+    }
+
+    /// nvm 的默认版本：`alias/default` 可能是完整版本（`v22.12.0`）、
+    /// 主版本（`22`）或 `node` / `lts/*` 等别名，取匹配的最新已安装版本
+    fn nvm_default(&self, nvm_dir: &str, installed: &[String]) -> Option<String> {
+        let alias = self
+            .env
+            .read_to_string(&self.join(nvm_dir, &["alias", "default"]))?;
+        let alias = alias.trim().trim_start_matches('v');
+        if alias.is_empty() {
+            return None;
+        }
+        if parse_version(alias).is_none() {
+            return installed.first().cloned();
+        }
+        installed
+            .iter()
+            .find(|name| {
+                let name = name.trim_start_matches('v');
+                name == alias || name.starts_with(&format!("{}.", alias))
+            })
+            .cloned()
+    }
+
+    /// Unix：可能包含 node 的 bin 目录
+    fn unix_node_dirs(&self) -> Vec<String> {
+        let mut dirs = Vec::new();
+        let nvm = self.var_or_home("NVM_DIR", &[".nvm"]).map(|nvm_dir| {
+            let versions_dir = self.join(&nvm_dir, &["versions", "node"]);
+            let installed = self.installed_versions(&versions_dir);
+            let default = self.nvm_default(&nvm_dir, &installed);
+            (versions_dir, installed, default)
+        });
+
+        // nvm 默认版本与用户在终端中的 `node` 一致，优先于系统安装
+        if let Some((versions_dir, _, Some(default))) = &nvm {
+            dirs.push(self.join(versions_dir, &[default, "bin"]));
+        }
+        // Homebrew（Apple Silicon / Intel）和系统安装
+        dirs.push("/opt/homebrew/bin".to_string());
+        dirs.push("/usr/local/bin".to_string());
+        dirs.push("/usr/bin".to_string());
+        // nvm 其他已安装版本
+        if let Some((versions_dir, installed, _)) = &nvm {
+            for version in installed {
+                dirs.push(self.join(versions_dir, &[version, "bin"]));
+            }
+        }
+        // fnm：FNM_DIR，旧版的 ~/.fnm，新版 Linux 的 XDG 目录和 macOS 的 Application Support
+        let fnm_dirs = [
+            self.env.var("FNM_DIR"),
+            self.home(&[".fnm"]),
+            self.env
+                .var("XDG_DATA_HOME")
+                .map(|data| self.join(&data, &["fnm"]))
+                .or_else(|| self.home(&[".local", "share", "fnm"])),
+            self.home(&["Library", "Application Support", "fnm"]),
+        ];
+        for fnm in fnm_dirs.into_iter().flatten() {
+            dirs.push(self.join(&fnm, &["aliases", "default", "bin"]));
+        }
+        // volta
+        if let Some(volta) = self.var_or_home("VOLTA_HOME", &[".volta"]) {
+            dirs.push(self.join(&volta, &["bin"]));
+        }
+        // asdf / mise 的 shim
+        dirs.extend(self.home(&[".asdf", "shims"]));
+        dirs.extend(self.home(&[".local", "share", "mise", "shims"]));
+        dirs
+    }
+
+    /// Windows：可能包含 node.exe 的目录
+    fn windows_node_dirs(&self) -> Vec<String> {
+        let mut dirs = Vec::new();
+        // nvm-windows：NVM_SYMLINK 指向当前版本
+        dirs.extend(self.env.var("NVM_SYMLINK"));
+        let nvm_home = self.env.var("NVM_HOME");
+        if let Some(nvm_home) = &nvm_home {
+            let settings = self
+                .env
+                .read_to_string(&self.join(nvm_home, &["settings.txt"]))
+                .unwrap_or_default();
+            for line in settings.lines() {
+                if let Some(version) = line.strip_prefix("current:").map(str::trim) {
+                    if !version.is_empty() {
+                        let version = format!("v{}", version.trim_start_matches('v'));
+                        dirs.push(self.join(nvm_home, &[&version]));
+                    }
+                }
+            }
+        }
+
+        // 官方安装包
+        dirs.extend(
+            self.env
+                .var("ProgramFiles")
+                .map(|p| self.join(&p, &["nodejs"])),
+        );
+        dirs.push("C:\\Program Files\\nodejs".to_string());
+        dirs.extend(
+            self.env
+                .var("ProgramFiles(x86)")
+                .map(|p| self.join(&p, &["nodejs"])),
+        );
+        dirs.push("C:\\Program Files (x86)\\nodejs".to_string());
+
+        // nvm for Windows（nvm4w 默认位置、旧版用户目录安装、NVM_HOME 下的其他版本）
+        dirs.push("C:\\nvm4w\\nodejs".to_string());
+        dirs.extend(self.home(&["AppData", "Roaming", "nvm", "current"]));
+        if let Some(nvm_home) = &nvm_home {
+            for version in self.installed_versions(nvm_home) {
+                dirs.push(self.join(nvm_home, &[&version]));
+            }
+        }
+
+        // fnm
+        let fnm_dirs = [
+            self.env.var("FNM_DIR"),
+            self.env.var("APPDATA").map(|p| self.join(&p, &["fnm"])),
+            self.home(&["AppData", "Roaming", "fnm"]),
+            self.env
+                .var("LOCALAPPDATA")
+                .map(|p| self.join(&p, &["fnm"])),
+            self.home(&["AppData", "Local", "fnm"]),
+            self.home(&[".fnm"]),
+        ];
+        for fnm in fnm_dirs.into_iter().flatten() {
+            dirs.push(self.join(&fnm, &["aliases", "default"]));
+        }
+
+        // volta
+        let volta = self.env.var("VOLTA_HOME").or_else(|| {
+            self.env
+                .var("LOCALAPPDATA")
+                .map(|p| self.join(&p, &["Volta"]))
+                .or_else(|| self.home(&["AppData", "Local", "Volta"]))
+        });
+        if let Some(volta) = volta {
+            dirs.push(self.join(&volta, &["bin"]));
+        }
+
+        // scoop（SCOOP 为自定义安装目录）
+        if let Some(scoop) = self.var_or_home("SCOOP", &["scoop"]) {
+            for app in ["nodejs", "nodejs-lts"] {
+                dirs.push(self.join(&scoop, &["apps", app, "current"]));
+            }
+        }
+
+        // chocolatey（便携版 nodejs 包和 shim 目录）
+        let choco = self
+            .env
+            .var("ChocolateyInstall")
+            .unwrap_or_else(|| "C:\\ProgramData\\chocolatey".to_string());
+        dirs.push(self.join(&choco, &["lib", "nodejs", "tools"]));
+        dirs.push(self.join(&choco, &["bin"]));
+        dirs
+    }
+
+    fn node_dirs(&self) -> Vec<String> {
+        if self.windows {
+            self.windows_node_dirs()
+        } else {
+            self.unix_node_dirs()
+        }
+    }
+
+    /// npm 全局安装目录（不在 node 所在目录时）
+    fn npm_global_dirs(&self) -> Vec<String> {
+        if self.windows {
+            let mut dirs: Vec<String> = self
+                .env
+                .var("APPDATA")
+                .map(|p| self.join(&p, &["npm"]))
+                .into_iter()
+                .collect();
+            dirs.extend(self.home(&["AppData", "Roaming", "npm"]));
+            // scoop 的 nodejs 把 npm 全局目录设在 current\bin
+            if let Some(scoop) = self.var_or_home("SCOOP", &["scoop"]) {
+                for app in ["nodejs", "nodejs-lts"] {
+                    dirs.push(self.join(&scoop, &["apps", app, "current", "bin"]));
+                }
+            }
+            dirs
+        } else {
+            [
+                &[".npm-global", "bin"][..],
+                &[".pnpm", "bin"],
+                &["Library", "pnpm"],
+                &[".yarn", "bin"],
+                &[".config", "yarn", "global", "node_modules", ".bin"],
+            ]
+            .iter()
+            .filter_map(|parts| self.home(parts))
+            .collect()
+        }
+    }
+
+    fn candidates(dirs: Vec<String>, file: impl Fn(&str) -> String) -> Vec<String> {
+        let mut paths: Vec<String> = Vec::new();
+        for path in dirs.iter().map(|dir| file(dir)) {
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+        paths
+    }
+
+    /// node 可执行文件的候选路径
+    pub fn node_candidates(&self) -> Vec<String> {
+        let exe = if self.windows { "node.exe" } else { "node" };
+        Self::candidates(self.node_dirs(), |dir| self.join(dir, &[exe]))
+    }
+
+    /// openclaw 的候选路径：npm 全局安装的命令与 node 在同一目录（nvm / fnm / 官方安装包），
+    /// 或在单独的 npm 全局目录中
+    pub fn openclaw_candidates(&self) -> Vec<String> {
+        let exe = if self.windows {
+            "openclaw.cmd"
+        } else {
+            "openclaw"
+        };
+        let mut dirs = self.node_dirs();
+        dirs.extend(self.npm_global_dirs());
+        Self::candidates(dirs, |dir| self.join(dir, &[exe]))
+    }
+
+    /// 包含 node 的 bin 目录（用于扩展子进程的 PATH）
+    pub fn existing_node_dirs(&self) -> Vec<String> {
+        Self::candidates(self.node_dirs(), |dir| dir.to_string())
+            .into_iter()
+            .filter(|dir| self.env.exists(dir))
+            .collect()
+    }
+
+    pub fn find_node(&self) -> Option<String> {
+        self.node_candidates()
+            .into_iter()
+            .find(|p| self.env.exists(p))
+    }
+
+    pub fn find_openclaw(&self) -> Option<String> {
+        self.openclaw_candidates()
+            .into_iter()
+            .find(|p| self.env.exists(p))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::{BTreeSet, HashMap};
+
+    /// 虚拟目录树：只记录文件，目录由文件路径推出
+    #[derive(Default)]
+    struct FakeEnv {
+        home: String,
+        vars: HashMap<String, String>,
+        files: HashMap<String, String>,
+    }
+
+    impl FakeEnv {
+        fn new(home: &str) -> Self {
+            Self {
+                home: home.to_string(),
+                ..Self::default()
+            }
+        }
+
+        fn env_var(mut self, key: &str, value: &str) -> Self {
+            self.vars.insert(key.to_string(), value.to_string());
+            self
+        }
+
+        fn file(mut self, path: &str, content: &str) -> Self {
+            self.files.insert(path.to_string(), content.to_string());
+            self
+        }
+
+        fn files(self, paths: &[&str]) -> Self {
+            paths.iter().fold(self, |env, path| env.file(path, ""))
+        }
+
+        /// path 下的相对路径
+        fn children<'a>(&'a self, path: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+            self.files.keys().filter_map(move |file| {
+                file.strip_prefix(path)
+                    .and_then(|rest| rest.strip_prefix(['/', '\\']))
+            })
+        }
+    }
+
+    impl PathEnv for FakeEnv {
+        fn home_dir(&self) -> Option<String> {
+            Some(self.home.clone())
+        }
+
+        fn var(&self, key: &str) -> Option<String> {
+            self.vars.get(key).cloned()
+        }
+
+        fn exists(&self, path: &str) -> bool {
+            self.files.contains_key(path) || self.children(path).next().is_some()
+        }
+
+        fn read_to_string(&self, path: &str) -> Option<String> {
+            self.files.get(path).cloned()
+        }
+
+        fn list_dir(&self, path: &str) -> Vec<String> {
+            self.children(path)
+                .filter_map(|rest| rest.split(['/', '\\']).next())
+                .map(String::from)
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect()
+        }
+    }
+
+    fn unix(env: FakeEnv) -> PathResolver<FakeEnv> {
+        PathResolver::new(env, false)
+    }
+
+    fn windows(env: FakeEnv) -> PathResolver<FakeEnv> {
+        PathResolver::new(env, true)
+    }
+
+    #[test]
+    fn resolves_nvm_default_alias_on_unix() {
+        let tree = |alias: &str| {
+            FakeEnv::new("/home/dev")
+                .files(&[
+                    "/home/dev/.nvm/versions/node/v18.20.4/bin/node",
+                    "/home/dev/.nvm/versions/node/v22.9.0/bin/node",
+                    "/home/dev/.nvm/versions/node/v22.12.0/bin/node",
+                    "/home/dev/.nvm/versions/node/v22.12.0/bin/openclaw",
+                    "/usr/bin/node",
+                ])
+                .file("/home/dev/.nvm/alias/default", alias)
+        };
+        // 主版本别名取最新的 22.x，优先于系统安装
+        assert_eq!(
+            unix(tree("22\n")).find_node().as_deref(),
+            Some("/home/dev/.nvm/versions/node/v22.12.0/bin/node")
+        );
+        assert_eq!(
+            unix(tree("v18.20.4")).find_node().as_deref(),
+            Some("/home/dev/.nvm/versions/node/v18.20.4/bin/node")
+        );
+        assert_eq!(
+            unix(tree("lts/*")).find_node().as_deref(),
+            Some("/home/dev/.nvm/versions/node/v22.12.0/bin/node")
+        );
+        // 默认版本未安装时使用系统 node，openclaw 仍能在 nvm 版本目录中找到
+        let resolver = unix(tree("20"));
+        assert_eq!(resolver.find_node().as_deref(), Some("/usr/bin/node"));
+        assert_eq!(
+            resolver.find_openclaw().as_deref(),
+            Some("/home/dev/.nvm/versions/node/v22.12.0/bin/openclaw")
+        );
+
+        // NVM_DIR 指定的自定义目录
+        let custom = FakeEnv::new("/home/dev")
+            .env_var("NVM_DIR", "/opt/nvm")
+            .file("/opt/nvm/alias/default", "v20.18.1")
+            .files(&["/opt/nvm/versions/node/v20.18.1/bin/node"]);
+        assert_eq!(
+            unix(custom).find_node().as_deref(),
+            Some("/opt/nvm/versions/node/v20.18.1/bin/node")
+        );
+    }
+
+    #[test]
+    fn resolves_fnm_volta_and_npm_prefix_on_unix() {
+        let fnm_xdg = FakeEnv::new("/home/dev").files(&[
+            "/home/dev/.local/share/fnm/aliases/default/bin/node",
+            "/home/dev/.npm-global/bin/openclaw",
+        ]);
+        let resolver = unix(fnm_xdg);
+        assert_eq!(
+            resolver.find_node().as_deref(),
+            Some("/home/dev/.local/share/fnm/aliases/default/bin/node")
+        );
+        assert_eq!(
+            resolver.find_openclaw().as_deref(),
+            Some("/home/dev/.npm-global/bin/openclaw")
+        );
+        assert_eq!(
+            resolver.existing_node_dirs(),
+            vec!["/home/dev/.local/share/fnm/aliases/default/bin"]
+        );
+
+        let fnm_macos = FakeEnv::new("/Users/dev")
+            .files(&["/Users/dev/Library/Application Support/fnm/aliases/default/bin/node"]);
+        assert_eq!(
+            unix(fnm_macos).find_node().as_deref(),
+            Some("/Users/dev/Library/Application Support/fnm/aliases/default/bin/node")
+        );
+
+        let volta = FakeEnv::new("/Users/dev")
+            .env_var("VOLTA_HOME", "/Users/dev/.tools/volta")
+            .files(&[
+                "/Users/dev/.tools/volta/bin/node",
+                "/Users/dev/.tools/volta/bin/openclaw",
+            ]);
+        let resolver = unix(volta);
+        assert_eq!(
+            resolver.find_node().as_deref(),
+            Some("/Users/dev/.tools/volta/bin/node")
+        );
+        assert_eq!(
+            resolver.find_openclaw().as_deref(),
+            Some("/Users/dev/.tools/volta/bin/openclaw")
+        );
+
+        // Homebrew 优先于 nvm 的非默认版本
+        let brew = FakeEnv::new("/Users/dev").files(&[
+            "/opt/homebrew/bin/node",
+            "/Users/dev/.nvm/versions/node/v22.12.0/bin/node",
+        ]);
+        assert_eq!(
+            unix(brew).find_node().as_deref(),
+            Some("/opt/homebrew/bin/node")
+        );
+        assert_eq!(unix(FakeEnv::new("/home/dev")).find_node(), None);
+    }
+
+    #[test]
+    fn resolves_nvm_windows_layouts() {
+        let home = "C:\\Users\\dev";
+        // NVM_SYMLINK 指向当前版本
+        let symlink = FakeEnv::new(home)
+            .env_var("NVM_HOME", "C:\\Users\\dev\\AppData\\Local\\nvm")
+            .env_var("NVM_SYMLINK", "C:\\nvm4w\\nodejs")
+            .files(&[
+                "C:\\nvm4w\\nodejs\\node.exe",
+                "C:\\nvm4w\\nodejs\\openclaw.cmd",
+                "C:\\Users\\dev\\AppData\\Local\\nvm\\v20.18.1\\node.exe",
+            ]);
+        let resolver = windows(symlink);
+        assert_eq!(
+            resolver.find_node().as_deref(),
+            Some("C:\\nvm4w\\nodejs\\node.exe")
+        );
+        assert_eq!(
+            resolver.find_openclaw().as_deref(),
+            Some("C:\\nvm4w\\nodejs\\openclaw.cmd")
+        );
+
+        // 没有符号链接时按 settings.txt 的当前版本，其次 NVM_HOME 下最新的版本
+        let nvm_home = "D:\\tools\\nvm";
+        let tree = FakeEnv::new(home).env_var("NVM_HOME", nvm_home).files(&[
+            "D:\\tools\\nvm\\v18.20.4\\node.exe",
+            "D:\\tools\\nvm\\v22.12.0\\node.exe",
+        ]);
+        assert_eq!(
+            windows(tree).find_node().as_deref(),
+            Some("D:\\tools\\nvm\\v22.12.0\\node.exe")
+        );
+        let current = FakeEnv::new(home)
+            .env_var("NVM_HOME", nvm_home)
+            .file(
+                "D:\\tools\\nvm\\settings.txt",
+                "root: D:\\tools\\nvm\ncurrent: 18.20.4\n",
+            )
+            .files(&[
+                "D:\\tools\\nvm\\v18.20.4\\node.exe",
+                "D:\\tools\\nvm\\v22.12.0\\node.exe",
+            ]);
+        assert_eq!(
+            windows(current).find_node().as_deref(),
+            Some("D:\\tools\\nvm\\v18.20.4\\node.exe")
+        );
+    }
+
+    #[test]
+    fn resolves_scoop_and_chocolatey_on_windows() {
+        let home = "C:\\Users\\dev";
+        let scoop = FakeEnv::new(home).files(&[
+            "C:\\Users\\dev\\scoop\\apps\\nodejs-lts\\current\\node.exe",
+            "C:\\Users\\dev\\scoop\\apps\\nodejs-lts\\current\\bin\\openclaw.cmd",
+        ]);
+        let resolver = windows(scoop);
+        assert_eq!(
+            resolver.find_node().as_deref(),
+            Some("C:\\Users\\dev\\scoop\\apps\\nodejs-lts\\current\\node.exe")
+        );
+        assert_eq!(
+            resolver.find_openclaw().as_deref(),
+            Some("C:\\Users\\dev\\scoop\\apps\\nodejs-lts\\current\\bin\\openclaw.cmd")
+        );
+
+        let custom_scoop = FakeEnv::new(home)
+            .env_var("SCOOP", "D:\\scoop")
+            .files(&["D:\\scoop\\apps\\nodejs\\current\\node.exe"]);
+        assert_eq!(
+            windows(custom_scoop).find_node().as_deref(),
+            Some("D:\\scoop\\apps\\nodejs\\current\\node.exe")
+        );
+
+        let choco = FakeEnv::new(home)
+            .env_var("APPDATA", "C:\\Users\\dev\\AppData\\Roaming")
+            .files(&[
+                "C:\\ProgramData\\chocolatey\\lib\\nodejs\\tools\\node.exe",
+                "C:\\Users\\dev\\AppData\\Roaming\\npm\\openclaw.cmd",
+            ]);
+        let resolver = windows(choco);
+        assert_eq!(
+            resolver.find_node().as_deref(),
+            Some("C:\\ProgramData\\chocolatey\\lib\\nodejs\\tools\\node.exe")
+        );
+        assert_eq!(
+            resolver.find_openclaw().as_deref(),
+            Some("C:\\Users\\dev\\AppData\\Roaming\\npm\\openclaw.cmd")
+        );
+
+        // 官方安装包优先于包管理器
+        let both = FakeEnv::new(home)
+            .env_var("ProgramFiles", "C:\\Program Files")
+            .files(&[
+                "C:\\Program Files\\nodejs\\node.exe",
+                "C:\\ProgramData\\chocolatey\\bin\\node.exe",
+            ]);
+        let resolver = windows(both);
+        assert_eq!(
+            resolver.find_node().as_deref(),
+            Some("C:\\Program Files\\nodejs\\node.exe")
+        );
+        // 同一目录只出现一次
+        let candidates = resolver.node_candidates();
+        assert_eq!(
+            candidates
+                .iter()
+                .filter(|p| *p == "C:\\Program Files\\nodejs\\node.exe")
+                .count(),
+            1
+        );
+    }
+}
@@ -0,0 +1,155 @@
+use log::{debug, error, warn};
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::future::Future;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::task::Poll;
+
+thread_local! {
+    /// 当前线程正在执行的命令（命令名, 请求编号），供日志格式、审计和 panic 钩子使用
+    static CURRENT: RefCell<Option<(&'static str, String)>> = const { RefCell::new(None) };
+}
+
+/// 命令错误的类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorKind {
+    /// 命令返回的错误
+    Failed,
+    /// 命令内部 panic
+    Internal,
+    /// 安全策略拒绝执行（只读模式）
+    PolicyDenied,
+}
+
+/// 返回给前端的命令错误
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommandError {
+    pub kind: ErrorKind,
+    pub command: String,
+    pub message: String,
+    /// 请求编号（同时写入日志、审计记录和崩溃报告，可用 get_request_trace 查看完整记录）
+    pub request_id: String,
+}
+
+impl std::fmt::Display for CommandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// 命令之间互相调用时按普通错误信息处理
+impl From<CommandError> for String {
+    fn from(e: CommandError) -> Self {
+        e.message
+    }
+}
+
+/// 当前线程正在执行的命令和请求编号
+pub fn current() -> Option<(&'static str, String)> {
+    CURRENT.with(|c| c.try_borrow().ok().and_then(|c| c.clone()))
+}
+
+/// 当前请求编号（命令中 spawn 出的线程 / 任务不继承，需要时显式传递）
+pub fn request_id() -> Option<String> {
+    current().map(|(_, id)| id)
+}
+
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "未知 panic".to_string())
+}
+
+/// 执行一次前端调用：分配请求编号、捕获 panic，失败时在错误中带上请求编号
+/// （命令通过 `#[guarded]` 属性调用）
+///
+/// 每次 poll 时记录当前命令，日志、审计记录和崩溃报告据此关联到同一次调用；
+/// panic 转换为带编号的内部错误。命令内部调用其他命令时沿用外层的请求编号
+pub async fn guard<T, F>(command: &'static str, fut: F) -> Result<T, CommandError>
+where
+    F: Future<Output = Result<T, String>>,
+{
+    if current().is_some() {
+        return fut.await.map_err(|message| CommandError {
+            kind: ErrorKind::Failed,
+            command: command.to_string(),
+            message,
+            request_id: request_id().unwrap_or_default(),
+        });
+    }
+    let correlation_id = uuid::Uuid::new_v4().to_string();
+    let mut fut = Box::pin(fut);
+    std::future::poll_fn(move |cx| {
+        CURRENT.with(|c| *c.borrow_mut() = Some((command, correlation_id.clone())));
+        let result = match catch_unwind(AssertUnwindSafe(|| fut.as_mut().poll(cx))) {
+            Ok(Poll::Pending) => Poll::Pending,
+            Ok(Poll::Ready(Ok(value))) => {
+                debug!("[命令] ✓ {}", command);
+                Poll::Ready(Ok(value))
+            }
+            Ok(Poll::Ready(Err(e))) => {
+                warn!("[命令] ✗ {} 失败: {}", command, e);
+                Poll::Ready(Err(CommandError {
+                    kind: ErrorKind::Failed,
+                    command: command.to_string(),
+                    message: e,
+                    request_id: correlation_id.clone(),
+                }))
+            }
+            Err(payload) => {
+                error!(
+                    "[命令] ✗ {} panic: {}",
+                    command,
+                    panic_message(payload.as_ref())
+                );
+                Poll::Ready(Err(CommandError {
+                    kind: ErrorKind::Internal,
+                    command: command.to_string(),
+                    message: format!(
+                        "执行 {} 时发生内部错误，请将错误编号提供给技术支持",
+                        command
+                    ),
+                    request_id: correlation_id.clone(),
+                }))
+            }
+        };
+        CURRENT.with(|c| *c.borrow_mut() = None);
+        result
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn panics_become_internal_errors_with_id() {
+        let ok = guard("ok_command", async { Ok::<_, String>(1) }).await;
+        assert_eq!(ok, Ok(1));
+
+        let failed = guard("failing_command", async {
+            assert!(request_id().is_some());
+            Err::<(), _>("配置文件不存在".to_string())
+        })
+        .await
+        .unwrap_err();
+        assert_eq!(failed.kind, ErrorKind::Failed);
+        assert_eq!(failed.message, "配置文件不存在");
+        assert!(!failed.request_id.is_empty());
+
+        let err = guard("broken_command", async {
+            let values: Vec<u32> = Vec::new();
+            Ok::<u32, String>(values[3])
+        })
+        .await
+        .unwrap_err();
+        assert_eq!(err.kind, ErrorKind::Internal);
+        assert_eq!(err.command, "broken_command");
+        assert_ne!(err.request_id, failed.request_id);
+        assert!(current().is_none());
+    }
+}
@@ -1,4 +1,7 @@
 use std::env;
+use std::ffi::OsString;
+use std::path::PathBuf;
+use std::sync::RwLock;
 
 /// 获取操作系统类型
 pub fn get_os() -> String {
@@ -10,8 +13,51 @@ pub fn get_arch() -> String {
     env::consts::ARCH.to_string()
 }
 
-/// 获取配置目录路径
+/// OpenClaw 状态目录的环境变量（OpenClaw 自身同样读取，非默认配置档案时传给子进程）
+pub const STATE_DIR_ENV: &str = "OPENCLAW_STATE_DIR";
+/// OpenClaw 配置文件路径的环境变量
+pub const CONFIG_PATH_ENV: &str = "OPENCLAW_CONFIG_PATH";
+
+/// 当前配置档案的目录（None 为默认档案）。切换档案时只修改这里，
+/// 不修改进程的环境变量（其他线程可能正在读取环境变量）
+static PROFILE_DIR: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+/// 设置当前配置档案的目录（None 恢复默认档案）
+pub fn set_profile_dir(dir: Option<PathBuf>) {
+    *PROFILE_DIR.write().unwrap_or_else(|e| e.into_inner()) = dir;
+}
+
+fn profile_dir() -> Option<PathBuf> {
+    PROFILE_DIR
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+}
+
+/// 子进程需要的配置档案环境变量：默认档案为空（子进程沿用继承的环境变量）
+pub fn profile_env() -> Vec<(&'static str, OsString)> {
+    match profile_dir() {
+        Some(dir) => vec![
+            (STATE_DIR_ENV, dir.clone().into_os_string()),
+            (CONFIG_PATH_ENV, dir.join("openclaw.json").into_os_string()),
+        ],
+        None => Vec::new(),
+    }
+}
+
+/// 获取配置目录路径（当前配置档案的目录）
 pub fn get_config_dir() -> String {
+    if let Some(dir) = profile_dir() {
+        return dir.display().to_string();
+    }
+    if let Some(dir) = env::var_os(STATE_DIR_ENV).filter(|d| !d.is_empty()) {
+        return std::path::PathBuf::from(dir).display().to_string();
+    }
+    get_default_config_dir()
+}
+
+/// 默认的配置目录（~/.openclaw）
+pub fn get_default_config_dir() -> String {
     if let Some(home) = dirs::home_dir() {
         if is_windows() {
             format!("{}\\.openclaw", home.display())
@@ -50,6 +96,48 @@ pub fn get_log_file_path() -> String {
     }
 }
 
+/// 覆盖 Manager 数据目录的环境变量
+pub const DATA_DIR_ENV: &str = "OPENCLAW_MANAGER_DATA_DIR";
+
+/// 获取 Manager 自身的数据目录（与 OpenClaw 配置目录分开）
+pub fn get_manager_data_dir() -> String {
+    // 测试和多实例调试时可指定独立的数据目录
+    if let Some(dir) = env::var_os(DATA_DIR_ENV).filter(|d| !d.is_empty()) {
+        return std::path::PathBuf::from(dir).display().to_string();
+    }
+    match dirs::data_dir() {
+        Some(dir) => dir.join("openclaw-manager").display().to_string(),
+        None => {
+            if is_windows() {
+                format!("{}\\manager", get_config_dir())
+            } else {
+                format!("{}/manager", get_config_dir())
+            }
+        }
+    }
+}
+
+/// Manager 下载的命令行工具目录（如 ffmpeg 静态构建），加入子进程的扩展 PATH
+pub fn get_tools_bin_dir() -> String {
+    std::path::Path::new(&get_manager_data_dir())
+        .join("tools")
+        .join("bin")
+        .display()
+        .to_string()
+}
+
+/// 去掉 Windows 扩展长度路径前缀（\\?\），供 msiexec、cmd 等不支持该前缀的程序使用
+/// 长路径本身由应用清单中的 longPathAware 启用
+pub fn strip_verbatim_prefix(path: &str) -> String {
+    if let Some(rest) = path.strip_prefix(r"\\?\UNC\") {
+        format!(r"\\{}", rest)
+    } else if let Some(rest) = path.strip_prefix(r"\\?\") {
+        rest.to_string()
+    } else {
+        path.to_string()
+    }
+}
+
 /// 检测当前平台是否为 macOS
 pub fn is_macos() -> bool {
     env::consts::OS == "macos"
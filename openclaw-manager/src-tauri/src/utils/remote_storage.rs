@@ -0,0 +1,656 @@
+//! 远程存储：WebDAV 或 S3 兼容对象存储（AWS S3、MinIO、Cloudflare R2、阿里云 OSS 等）
+//! S3 使用路径风格地址（`<endpoint>/<bucket>/<key>`）和 AWS Signature V4 签名
+use crate::utils::{http, secrets};
+use hmac::{Hmac, Mac};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// 远程存储位置（密码和密钥保存在系统钥匙串中，设置中只保留引用）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RemoteTarget {
+    /// WebDAV 目录（如坚果云、Nextcloud），文件保存在 url 下
+    WebDav {
+        url: String,
+        username: String,
+        password: String,
+    },
+    /// S3 兼容对象存储，文件保存在 bucket 的 prefix 下
+    S3 {
+        endpoint: String,
+        bucket: String,
+        region: String,
+        access_key_id: String,
+        secret_access_key: String,
+        #[serde(default)]
+        prefix: String,
+    },
+}
+
+impl RemoteTarget {
+    /// 用于日志和界面的简短描述（不含密钥）
+    pub fn describe(&self) -> String {
+        match self {
+            RemoteTarget::WebDav { url, .. } => format!("WebDAV {}", url),
+            RemoteTarget::S3 {
+                endpoint, bucket, ..
+            } => format!("S3 {}/{}", endpoint.trim_end_matches('/'), bucket),
+        }
+    }
+
+    /// 需要保存在钥匙串中的敏感字段
+    fn secret_fields_mut(&mut self) -> Vec<(&'static str, &mut String)> {
+        match self {
+            RemoteTarget::WebDav { password, .. } => vec![("password", password)],
+            RemoteTarget::S3 {
+                secret_access_key, ..
+            } => vec![("secret_access_key", secret_access_key)],
+        }
+    }
+
+    /// 将明文密钥移入系统钥匙串（账户名为 `<account>:<字段>`）
+    /// 钥匙串不可用时保留明文并记录警告
+    pub fn secure_secrets(&mut self, account: &str) {
+        for (field, value) in self.secret_fields_mut() {
+            if value.is_empty() || secrets::is_keychain_ref(value) {
+                continue;
+            }
+            match secrets::store_secret(&format!("{}:{}", account, field), value) {
+                Ok(reference) => *value = reference,
+                Err(e) => warn!("[远程存储] 无法将 {} 存入钥匙串: {}", field, e),
+            }
+        }
+    }
+
+    /// 将钥匙串引用替换为真实密钥（仅用于发送请求）
+    fn with_resolved_secrets(&self) -> Result<RemoteTarget, String> {
+        let mut target = self.clone();
+        for (_, value) in target.secret_fields_mut() {
+            *value = secrets::resolve_secret(value)?;
+        }
+        Ok(target)
+    }
+
+    /// 上传文件（已存在时覆盖）
+    pub async fn put(&self, key: &str, body: Vec<u8>) -> Result<(), String> {
+        self.put_with(key, body, None).await.map(|_| ())
+    }
+
+    /// 条件上传：远程文件仍为 `expected` 时才写入，返回 false 表示文件已被其他电脑修改
+    pub async fn put_if(
+        &self,
+        key: &str,
+        body: Vec<u8>,
+        expected: &Expected,
+    ) -> Result<bool, String> {
+        let condition = match expected {
+            Expected::Absent => Some(("If-None-Match", "*".to_string())),
+            Expected::ETag(etag) => Some(("If-Match", etag.clone())),
+            Expected::Any => None,
+        };
+        self.put_with(key, body, condition).await
+    }
+
+    async fn put_with(
+        &self,
+        key: &str,
+        body: Vec<u8>,
+        condition: Option<(&'static str, String)>,
+    ) -> Result<bool, String> {
+        let target = self.with_resolved_secrets()?;
+        let client = http::download_client()?;
+        let request = match &target {
+            RemoteTarget::WebDav {
+                url,
+                username,
+                password,
+            } => {
+                ensure_collections(&client, url, username, password, key).await?;
+                client
+                    .put(webdav_url(url, key))
+                    .basic_auth(username, Some(password))
+                    .body(body)
+            }
+            RemoteTarget::S3 { .. } => s3_request(&client, &target, "PUT", Some(key), "", body)?,
+        };
+        let conditional = condition.is_some();
+        let request = match condition {
+            Some((header, value)) => request.header(header, value),
+            None => request,
+        };
+        let response = request
+            .send()
+            .await
+            .map_err(|e| format!("上传 {} 失败: {}", key, e))?;
+        // S3 并发的条件写入可能返回 409 ConditionalRequestConflict
+        if conditional
+            && matches!(
+                response.status(),
+                reqwest::StatusCode::PRECONDITION_FAILED | reqwest::StatusCode::CONFLICT
+            )
+        {
+            return Ok(false);
+        }
+        check_status(response, key).await.map(|_| true)
+    }
+
+    /// 下载文件（不存在时返回 None）
+    pub async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, String> {
+        Ok(self.get_versioned(key).await?.map(|(body, _)| body))
+    }
+
+    /// 下载文件及其 ETag（不存在时返回 None），ETag 用于之后的条件上传
+    pub async fn get_versioned(&self, key: &str) -> Result<Option<(Vec<u8>, Expected)>, String> {
+        let target = self.with_resolved_secrets()?;
+        let client = http::download_client()?;
+        let response = match &target {
+            RemoteTarget::WebDav {
+                url,
+                username,
+                password,
+            } => {
+                client
+                    .get(webdav_url(url, key))
+                    .basic_auth(username, Some(password))
+                    .send()
+                    .await
+            }
+            RemoteTarget::S3 { .. } => {
+                s3_request(&client, &target, "GET", Some(key), "", Vec::new())?
+                    .send()
+                    .await
+            }
+        }
+        .map_err(|e| format!("下载 {} 失败: {}", key, e))?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let response = check_status(response, key).await?;
+        // 服务器不返回 ETag 时无法条件上传，只能直接覆盖
+        let version = response
+            .headers()
+            .get(reqwest::header::ETAG)
+            .and_then(|v| v.to_str().ok())
+            .map_or(Expected::Any, |etag| Expected::ETag(etag.to_string()));
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|e| format!("下载 {} 失败: {}", key, e))?;
+        Ok(Some((bytes.to_vec(), version)))
+    }
+
+    /// 删除文件（不存在时忽略）
+    pub async fn delete(&self, key: &str) -> Result<(), String> {
+        let target = self.with_resolved_secrets()?;
+        let client = http::client()?;
+        let response = match &target {
+            RemoteTarget::WebDav {
+                url,
+                username,
+                password,
+            } => {
+                client
+                    .delete(webdav_url(url, key))
+                    .basic_auth(username, Some(password))
+                    .send()
+                    .await
+            }
+            RemoteTarget::S3 { .. } => {
+                s3_request(&client, &target, "DELETE", Some(key), "", Vec::new())?
+                    .send()
+                    .await
+            }
+        }
+        .map_err(|e| format!("删除 {} 失败: {}", key, e))?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(());
+        }
+        check_status(response, key).await.map(|_| ())
+    }
+
+    /// 列出目录下的文件（`dir` 为相对路径，如 `backups`；不递归，S3 分页时逐页读取）
+    pub async fn list(&self, dir: &str) -> Result<Vec<RemoteObject>, String> {
+        let target = self.with_resolved_secrets()?;
+        let client = http::client()?;
+        let dir = dir.trim_matches('/');
+        match &target {
+            RemoteTarget::WebDav {
+                url,
+                username,
+                password,
+            } => {
+                let propfind =
+                    reqwest::Method::from_bytes(b"PROPFIND").map_err(|e| e.to_string())?;
+                let response = client
+                    .request(propfind, webdav_url(url, &format!("{}/", dir)))
+                    .basic_auth(username, Some(password))
+                    .header("Depth", "1")
+                    .send()
+                    .await
+                    .map_err(|e| format!("列出 {} 失败: {}", dir, e))?;
+                if response.status() == reqwest::StatusCode::NOT_FOUND {
+                    return Ok(Vec::new());
+                }
+                let body = check_status(response, dir)
+                    .await?
+                    .text()
+                    .await
+                    .map_err(|e| format!("列出 {} 失败: {}", dir, e))?;
+                Ok(parse_webdav_listing(&body, dir))
+            }
+            RemoteTarget::S3 { prefix, .. } => {
+                let full_prefix = format!("{}/", s3_key(prefix, dir));
+                let mut objects = Vec::new();
+                let mut token: Option<String> = None;
+                loop {
+                    // 签名要求查询参数按名称排序
+                    let query = match &token {
+                        Some(token) => format!(
+                            "continuation-token={}&list-type=2&prefix={}",
+                            uri_encode(token, false),
+                            uri_encode(&full_prefix, false)
+                        ),
+                        None => format!("list-type=2&prefix={}", uri_encode(&full_prefix, false)),
+                    };
+                    let response = s3_request(&client, &target, "GET", None, &query, Vec::new())?
+                        .send()
+                        .await
+                        .map_err(|e| format!("列出 {} 失败: {}", dir, e))?;
+                    let body = check_status(response, dir)
+                        .await?
+                        .text()
+                        .await
+                        .map_err(|e| format!("列出 {} 失败: {}", dir, e))?;
+                    objects.extend(parse_s3_listing(&body, &full_prefix, dir));
+                    token = match s3_next_token(&body) {
+                        Some(next) if token.as_ref() != Some(&next) => Some(next),
+                        Some(_) => return Err(format!("列出 {} 失败: 分页标记未变化", dir)),
+                        None => return Ok(objects),
+                    };
+                }
+            }
+        }
+    }
+}
+
+/// 条件上传时远程文件应处的状态
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expected {
+    /// 文件不存在
+    Absent,
+    /// 文件的 ETag 未变化
+    ETag(String),
+    /// 不检查（服务器不支持 ETag）
+    Any,
+}
+
+/// 远程目录中的文件
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RemoteObject {
+    /// 相对于存储根目录的路径（可直接传给 get / delete）
+    pub key: String,
+    pub size: Option<u64>,
+}
+
+/// 取 XML 元素的文本（忽略命名空间前缀，如 `<d:href>`）
+fn xml_values<'a>(xml: &'a str, tag: &str) -> Vec<&'a str> {
+    let pattern = format!(r"(?s)<(?:\w+:)?{tag}(?:\s[^>]*)?>(.*?)</(?:\w+:)?{tag}>");
+    regex::Regex::new(&pattern)
+        .map(|re| {
+            re.captures_iter(xml)
+                .filter_map(|c| c.get(1).map(|m| m.as_str()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// 还原 XML 文本中的实体
+fn xml_unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// URL 百分号解码（无效的转义原样保留）
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                out.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                out.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// 解析 PROPFIND 的结果（跳过目录本身和子目录）
+/// href 是百分号编码的 URL 路径，文件名需解码后才能作为 key 使用
+fn parse_webdav_listing(xml: &str, dir: &str) -> Vec<RemoteObject> {
+    xml_values(xml, "response")
+        .into_iter()
+        .filter_map(|response| {
+            let href = xml_unescape(xml_values(response, "href").into_iter().next()?.trim());
+            if href.ends_with('/') {
+                return None;
+            }
+            let name = percent_decode(href.rsplit('/').next().filter(|n| !n.is_empty())?);
+            let size = xml_values(response, "getcontentlength")
+                .into_iter()
+                .next()
+                .and_then(|s| s.trim().parse().ok());
+            Some(RemoteObject {
+                key: format!("{}/{}", dir, name),
+                size,
+            })
+        })
+        .collect()
+}
+
+/// 解析 ListObjectsV2 的结果，去掉 prefix 后返回相对路径
+fn parse_s3_listing(xml: &str, full_prefix: &str, dir: &str) -> Vec<RemoteObject> {
+    xml_values(xml, "Contents")
+        .into_iter()
+        .filter_map(|contents| {
+            let key = xml_unescape(xml_values(contents, "Key").into_iter().next()?);
+            let name = key.strip_prefix(full_prefix)?;
+            if name.is_empty() || name.contains('/') {
+                return None;
+            }
+            let size = xml_values(contents, "Size")
+                .into_iter()
+                .next()
+                .and_then(|s| s.trim().parse().ok());
+            Some(RemoteObject {
+                key: format!("{}/{}", dir, name),
+                size,
+            })
+        })
+        .collect()
+}
+
+/// 列表被截断时下一页的分页标记
+fn s3_next_token(xml: &str) -> Option<String> {
+    let truncated = xml_values(xml, "IsTruncated")
+        .into_iter()
+        .next()
+        .is_some_and(|v| v.trim() == "true");
+    if !truncated {
+        return None;
+    }
+    xml_values(xml, "NextContinuationToken")
+        .into_iter()
+        .next()
+        .map(|token| xml_unescape(token.trim()))
+}
+
+async fn check_status(response: reqwest::Response, key: &str) -> Result<reqwest::Response, String> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let body = response.text().await.unwrap_or_default();
+    let detail: String = body.chars().take(200).collect();
+    Err(match status.as_u16() {
+        401 | 403 => format!(
+            "远程存储拒绝访问 {}（HTTP {}），请检查账号和密钥",
+            key, status
+        ),
+        _ => format!("远程存储请求 {} 失败（HTTP {}）: {}", key, status, detail),
+    })
+}
+
+/// URI 编码（保留 AWS 规定的非保留字符，`keep_slash` 时保留路径分隔符）
+fn uri_encode(value: &str, keep_slash: bool) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            b'/' if keep_slash => "/".to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+fn webdav_url(base: &str, key: &str) -> String {
+    format!("{}/{}", base.trim_end_matches('/'), uri_encode(key, true))
+}
+
+/// WebDAV 上传前逐级创建父目录（目录已存在时服务器返回 405，忽略）
+async fn ensure_collections(
+    client: &reqwest::Client,
+    base: &str,
+    username: &str,
+    password: &str,
+    key: &str,
+) -> Result<(), String> {
+    let mkcol = reqwest::Method::from_bytes(b"MKCOL").map_err(|e| e.to_string())?;
+    let segments: Vec<&str> = key.split('/').collect();
+    for depth in 1..segments.len() {
+        let dir = format!("{}/", segments[..depth].join("/"));
+        client
+            .request(mkcol.clone(), webdav_url(base, &dir))
+            .basic_auth(username, Some(password))
+            .send()
+            .await
+            .map_err(|e| format!("创建 WebDAV 目录 {} 失败: {}", dir, e))?;
+    }
+    Ok(())
+}
+
+/// S3 对象键（加上 prefix）
+fn s3_key(prefix: &str, key: &str) -> String {
+    let prefix = prefix.trim_matches('/');
+    if prefix.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}/{}", prefix, key)
+    }
+}
+
+fn hmac_sha256(key: &[u8], data: &str) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC 接受任意长度的密钥");
+    mac.update(data.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// AWS Signature V4 的 Authorization 头
+#[allow(clippy::too_many_arguments)]
+fn sign_v4(
+    method: &str,
+    host: &str,
+    canonical_uri: &str,
+    canonical_query: &str,
+    payload_hash: &str,
+    amz_date: &str,
+    region: &str,
+    access_key_id: &str,
+    secret_access_key: &str,
+) -> String {
+    let date = &amz_date[..8];
+    let signed_headers = "host;x-amz-content-sha256;x-amz-date";
+    let canonical_request = format!(
+        "{}\n{}\n{}\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\n{}\n{}",
+        method,
+        canonical_uri,
+        canonical_query,
+        host,
+        payload_hash,
+        amz_date,
+        signed_headers,
+        payload_hash
+    );
+    let scope = format!("{}/{}/s3/aws4_request", date, region);
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        amz_date,
+        scope,
+        hex::encode(Sha256::digest(canonical_request.as_bytes()))
+    );
+    let key = [date, region, "s3", "aws4_request"].iter().fold(
+        format!("AWS4{}", secret_access_key).into_bytes(),
+        |key, part| hmac_sha256(&key, part),
+    );
+    format!(
+        "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+        access_key_id,
+        scope,
+        signed_headers,
+        hex::encode(hmac_sha256(&key, &string_to_sign))
+    )
+}
+
+/// 构造签名后的 S3 请求（`target` 中的密钥已解析）；`key` 为 None 时请求 bucket 本身，
+/// `query` 为已按参数名排序并编码的查询字符串
+fn s3_request(
+    client: &reqwest::Client,
+    target: &RemoteTarget,
+    method: &str,
+    key: Option<&str>,
+    query: &str,
+    body: Vec<u8>,
+) -> Result<reqwest::RequestBuilder, String> {
+    let RemoteTarget::S3 {
+        endpoint,
+        bucket,
+        region,
+        access_key_id,
+        secret_access_key,
+        prefix,
+    } = target
+    else {
+        return Err("不是 S3 存储".to_string());
+    };
+    let canonical_uri = match key {
+        Some(key) => format!(
+            "/{}/{}",
+            uri_encode(bucket, false),
+            uri_encode(&s3_key(prefix, key), true)
+        ),
+        None => format!("/{}", uri_encode(bucket, false)),
+    };
+    let mut url = format!("{}{}", endpoint.trim_end_matches('/'), canonical_uri);
+    if !query.is_empty() {
+        url.push('?');
+        url.push_str(query);
+    }
+    let url = reqwest::Url::parse(&url).map_err(|e| format!("S3 地址无效: {}", e))?;
+    let host = match (url.host_str(), url.port()) {
+        (Some(host), Some(port)) => format!("{}:{}", host, port),
+        (Some(host), None) => host.to_string(),
+        (None, _) => return Err("S3 地址缺少主机名".to_string()),
+    };
+    let region = if region.trim().is_empty() {
+        "us-east-1"
+    } else {
+        region.trim()
+    };
+    let payload_hash = hex::encode(Sha256::digest(&body));
+    let amz_date = chrono::Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+    let authorization = sign_v4(
+        method,
+        &host,
+        &canonical_uri,
+        query,
+        &payload_hash,
+        &amz_date,
+        region,
+        access_key_id,
+        secret_access_key,
+    );
+    let method = reqwest::Method::from_bytes(method.as_bytes()).map_err(|e| e.to_string())?;
+    Ok(client
+        .request(method, url)
+        .header("x-amz-date", amz_date)
+        .header("x-amz-content-sha256", payload_hash)
+        .header("authorization", authorization)
+        .body(body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_keys_and_signatures() {
+        assert_eq!(s3_key("/backups/", "sync.bin"), "backups/sync.bin");
+        assert_eq!(s3_key("", "sync.bin"), "sync.bin");
+        assert_eq!(uri_encode("a b/中.json", true), "a%20b/%E4%B8%AD.json");
+        assert_eq!(
+            webdav_url("https://dav.example.com/openclaw/", "manager/sync.bin"),
+            "https://dav.example.com/openclaw/manager/sync.bin"
+        );
+
+        let auth = sign_v4(
+            "GET",
+            "s3.example.com",
+            "/bucket/sync.bin",
+            "",
+            &hex::encode(Sha256::digest(b"")),
+            "20261016T080000Z",
+            "us-east-1",
+            "AKIDEXAMPLE",
+            "secret",
+        );
+        assert!(auth.starts_with(
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20261016/us-east-1/s3/aws4_request, \
+             SignedHeaders=host;x-amz-content-sha256;x-amz-date, Signature="
+        ));
+        assert_eq!(auth.rsplit('=').next().map(str::len), Some(64));
+
+        let webdav = r#"<?xml version="1.0"?><d:multistatus xmlns:d="DAV:">
+            <d:response><d:href>/dav/backups/</d:href></d:response>
+            <d:response><d:href>/dav/backups/a.ocb</d:href>
+              <d:propstat><d:prop><d:getcontentlength>42</d:getcontentlength></d:prop></d:propstat>
+            </d:response>
+            <d:response><d:href>https://dav.example.com/dav/backups/%E5%A4%87%E4%BB%BD%20a&amp;b.ocb</d:href>
+            </d:response></d:multistatus>"#;
+        assert_eq!(
+            parse_webdav_listing(webdav, "backups"),
+            vec![
+                RemoteObject {
+                    key: "backups/a.ocb".to_string(),
+                    size: Some(42)
+                },
+                RemoteObject {
+                    key: "backups/备份 a&b.ocb".to_string(),
+                    size: None
+                }
+            ]
+        );
+        assert_eq!(percent_decode("100%25%zz"), "100%%zz");
+        let s3 = "<ListBucketResult><Contents><Key>oc/backups/b.ocb</Key><Size>7</Size></Contents>\
+                  <Contents><Key>oc/backups/old/c.ocb</Key><Size>1</Size></Contents></ListBucketResult>";
+        assert_eq!(
+            parse_s3_listing(s3, "oc/backups/", "backups"),
+            vec![RemoteObject {
+                key: "backups/b.ocb".to_string(),
+                size: Some(7)
+            }]
+        );
+        assert_eq!(s3_next_token(s3), None);
+        let page = "<ListBucketResult><IsTruncated>true</IsTruncated>\
+                    <NextContinuationToken>1ueGcxLPRx1Tr/XYExHnhbYLgveDs2J/wm36Hy4vbOwM=</NextContinuationToken>\
+                    </ListBucketResult>";
+        assert_eq!(
+            s3_next_token(page).as_deref(),
+            Some("1ueGcxLPRx1Tr/XYExHnhbYLgveDs2J/wm36Hy4vbOwM=")
+        );
+    }
+}
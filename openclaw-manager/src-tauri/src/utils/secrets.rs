@@ -0,0 +1,66 @@
+use crate::commands::secret_handles;
+use keyring::Entry;
+
+/// 系统钥匙串中的服务名
+const KEYCHAIN_SERVICE: &str = "openclaw-manager";
+/// 设置文件中引用钥匙串条目的前缀，如 "keychain:notify:ops:secret"
+pub const KEYCHAIN_PREFIX: &str = "keychain:";
+
+/// 值是否为钥匙串引用
+pub fn is_keychain_ref(value: &str) -> bool {
+    value.starts_with(KEYCHAIN_PREFIX)
+}
+
+/// 将密钥保存到系统钥匙串，返回写入设置文件的引用
+pub fn store_secret(account: &str, secret: &str) -> Result<String, String> {
+    let entry = Entry::new(KEYCHAIN_SERVICE, account)
+        .map_err(|e| format!("打开钥匙串失败: {}", e))?;
+    entry
+        .set_password(secret)
+        .map_err(|e| format!("写入钥匙串失败: {}", e))?;
+    Ok(format!("{}{}", KEYCHAIN_PREFIX, account))
+}
+
+/// 解析设置中的值：钥匙串引用则读取真实密钥，否则原样返回
+pub fn resolve_secret(value: &str) -> Result<String, String> {
+    match value.strip_prefix(KEYCHAIN_PREFIX) {
+        Some(account) => Entry::new(KEYCHAIN_SERVICE, account)
+            .and_then(|entry| entry.get_password())
+            .map_err(|e| format!("读取钥匙串失败 ({}): {}", account, e)),
+        None => Ok(value.to_string()),
+    }
+}
+
+/// 检查前端提交的密钥值：钥匙串引用只能指向 `account` 自己的条目，也不接受密钥句柄
+/// 否则页面脚本可以让后端读出任意已保存的密钥，再发送到它指定的地址
+pub fn check_ref(value: &str, account: &str) -> Result<(), String> {
+    if value.starts_with(secret_handles::HANDLE_PREFIX) {
+        return Err(format!("{} 不接受密钥句柄，请直接输入密钥", account));
+    }
+    match value.strip_prefix(KEYCHAIN_PREFIX) {
+        Some(referenced) if referenced != account => {
+            Err(format!("钥匙串引用 {} 不能用于 {}", value, account))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// 删除钥匙串引用对应的条目（不是引用或条目不存在时忽略）
+pub fn delete_secret(value: &str) -> Result<(), String> {
+    let Some(account) = value.strip_prefix(KEYCHAIN_PREFIX) else {
+        return Ok(());
+    };
+    match Entry::new(KEYCHAIN_SERVICE, account).and_then(|entry| entry.delete_credential()) {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(format!("删除钥匙串条目失败 ({}): {}", account, e)),
+    }
+}
+
+/// 系统钥匙串当前是否可用（已解锁）
+/// 读取一个不存在的条目：返回 NoEntry 说明钥匙串可以正常访问
+pub fn keychain_available() -> bool {
+    match Entry::new(KEYCHAIN_SERVICE, "__availability_probe__").and_then(|e| e.get_password()) {
+        Ok(_) | Err(keyring::Error::NoEntry) => true,
+        Err(_) => false,
+    }
+}
@@ -1,8 +1,15 @@
 use std::process::{Command, Output};
 use std::io;
 use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use crate::commands::{gateway_lock, settings};
 use crate::utils::platform;
 use crate::utils::file;
+use crate::utils::encoding;
+use crate::utils::executor;
+use crate::utils::login_env;
+use crate::utils::node_paths;
 use log::{info, debug, warn};
 
 #[cfg(windows)]
@@ -10,12 +17,14 @@ use std::os::windows::process::CommandExt;
 
 /// Windows CREATE_NO_WINDOW 标志，用于隐藏控制台窗口
 #[cfg(windows)]
-const CREATE_NO_WINDOW: u32 = 0x08000000;
+pub const CREATE_NO_WINDOW: u32 = 0x08000000;
 
 /// 获取扩展的 PATH 环境变量
-/// GUI 应用启动时可能没有继承用户 shell 的 PATH，需要手动添加常见路径
+/// GUI 应用启动时可能没有继承用户 shell 的 PATH：优先使用登录 shell 的 PATH，
+/// 再补充常见路径
 pub fn get_extended_path() -> String {
-    let mut paths = Vec::new();
+    // nvm / fnm / volta / asdf / mise 等版本管理器中实际存在的 node 目录（nvm 默认版本在最前）
+    let mut paths = node_paths::system().existing_node_dirs();
     
     // 添加常见的可执行文件路径
     paths.push("/opt/homebrew/bin".to_string());  // Homebrew on Apple Silicon
@@ -23,38 +32,8 @@ pub fn get_extended_path() -> String {
     paths.push("/usr/bin".to_string());
     paths.push("/bin".to_string());
     
-    if let Some(home) = dirs::home_dir() {
-        let home_str = home.display().to_string();
-        
-        // nvm 路径（尝试获取当前版本）
-        let nvm_default = format!("{}/.nvm/alias/default", home_str);
-        if let Ok(version) = std::fs::read_to_string(&nvm_default) {
-            let version = version.trim();
-            if !version.is_empty() {
-                paths.insert(0, format!("{}/.nvm/versions/node/v{}/bin", home_str, version));
-            }
-        }
-        // 也添加常见 nvm 版本路径
-        for version in ["v22.22.0", "v22.12.0", "v22.11.0", "v22.0.0", "v23.0.0"] {
-            let nvm_bin = format!("{}/.nvm/versions/node/{}/bin", home_str, version);
-            if std::path::Path::new(&nvm_bin).exists() {
-                paths.insert(0, nvm_bin);
-                break; // 只添加第一个存在的
-            }
-        }
-        
-        // fnm
-        paths.push(format!("{}/.fnm/aliases/default/bin", home_str));
-        
-        // volta
-        paths.push(format!("{}/.volta/bin", home_str));
-        
-        // asdf
-        paths.push(format!("{}/.asdf/shims", home_str));
-        
-        // mise
-        paths.push(format!("{}/.local/share/mise/shims", home_str));
-    }
+    // Manager 下载的工具（ffmpeg 等）
+    paths.push(platform::get_tools_bin_dir());
     
     // 获取当前 PATH 并合并
     let current_path = std::env::var("PATH").unwrap_or_default();
@@ -62,12 +41,46 @@ pub fn get_extended_path() -> String {
         paths.push(current_path);
     }
     
-    paths.join(":")
+    // 登录 shell 的 PATH 放在最前（与用户在终端中的解析结果一致），并去除重复目录
+    let mut merged: Vec<String> = login_env::path_dirs();
+    for dir in paths.iter().flat_map(|p| p.split(':')) {
+        if !dir.is_empty() && !merged.iter().any(|d| d == dir) {
+            merged.push(dir.to_string());
+        }
+    }
+    merged.join(":")
 }
 
 /// 执行 Shell 命令（带扩展 PATH）
 pub fn run_command(cmd: &str, args: &[&str]) -> io::Result<Output> {
-    let mut command = Command::new(cmd);
+    run_with_env(cmd, args, std::iter::empty::<(&str, &str)>())
+}
+
+/// 为子进程追加环境变量（值为空表示从子进程环境中移除该变量）
+pub fn apply_overlay<I, K, V>(command: &mut Command, overlay: I)
+where
+    I: IntoIterator<Item = (K, V)>,
+    K: AsRef<OsStr>,
+    V: AsRef<OsStr>,
+{
+    for (key, value) in overlay {
+        if value.as_ref().is_empty() {
+            command.env_remove(key);
+        } else {
+            command.env(key, value);
+        }
+    }
+}
+
+/// 执行命令（带扩展 PATH），`overlay` 中的环境变量只作用于该子进程，
+/// 不修改 Manager 自身的环境（如 npm 走代理、Gateway 设置 NODE_OPTIONS）
+pub fn run_with_env<I, K, V>(program: &str, args: &[&str], overlay: I) -> io::Result<Output>
+where
+    I: IntoIterator<Item = (K, V)>,
+    K: AsRef<OsStr>,
+    V: AsRef<OsStr>,
+{
+    let mut command = Command::new(program);
     command.args(args);
     
     // 在非 Windows 系统上使用扩展的 PATH
@@ -76,11 +89,13 @@ pub fn run_command(cmd: &str, args: &[&str]) -> io::Result<Output> {
         let extended_path = get_extended_path();
         command.env("PATH", extended_path);
     }
+    command.envs(platform::profile_env());
+    apply_overlay(&mut command, overlay);
     
     #[cfg(windows)]
     command.creation_flags(CREATE_NO_WINDOW);
     
-    command.output()
+    executor::current().output(&mut command)
 }
 
 /// 执行 Shell 命令并获取输出字符串
@@ -88,19 +103,147 @@ pub fn run_command_output(cmd: &str, args: &[&str]) -> Result<String, String> {
     match run_command(cmd, args) {
         Ok(output) => {
             if output.status.success() {
-                Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+                Ok(encoding::decode_output(&output.stdout).trim().to_string())
             } else {
-                Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+                Err(encoding::decode_output(&output.stderr).trim().to_string())
             }
         }
         Err(e) => Err(e.to_string()),
     }
 }
 
-/// 执行 Bash 命令（带扩展 PATH）
-pub fn run_bash(script: &str) -> io::Result<Output> {
+/// 结束进程：force 为 false 时请求进程自行退出（SIGTERM），为 true 时强制结束
+pub fn terminate_process(pid: u32, force: bool) -> bool {
+    let pid = pid.to_string();
+    let result = if platform::is_windows() {
+        let mut args = vec!["/PID", pid.as_str(), "/T"];
+        if force {
+            args.push("/F");
+        }
+        run_command("taskkill", &args)
+    } else {
+        run_command("kill", &[if force { "-KILL" } else { "-TERM" }, pid.as_str()])
+    };
+    result.map(|o| o.status.success()).unwrap_or(false)
+}
+
+/// 子进程及其启动的所有后代进程，超时等情况下一并结束
+///
+/// Unix 上子进程在新的进程组中运行，结束时向整个进程组发送 SIGKILL；
+/// Windows 上子进程加入 Job Object，结束时终止 Job 中的所有进程
+pub struct ProcessTree {
+    #[cfg(unix)]
+    pgid: u32,
+    #[cfg(windows)]
+    job: windows_sys::Win32::Foundation::HANDLE,
+}
+
+impl ProcessTree {
+    /// 在 spawn 前调用：Unix 上让子进程成为新进程组的组长
+    pub fn prepare(command: &mut Command) {
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::CommandExt;
+            command.process_group(0);
+        }
+        #[cfg(not(unix))]
+        let _ = command;
+    }
+
+    /// 在 spawn 后调用
+    pub fn attach(child: &std::process::Child) -> Self {
+        #[cfg(unix)]
+        {
+            Self { pgid: child.id() }
+        }
+        #[cfg(windows)]
+        {
+            use std::os::windows::io::AsRawHandle;
+            use windows_sys::Win32::System::JobObjects::{
+                AssignProcessToJobObject, CreateJobObjectW,
+            };
+            // SAFETY: 参数为空指针表示默认安全属性、匿名 Job；进程句柄在 child 存活期间有效
+            let job = unsafe { CreateJobObjectW(std::ptr::null(), std::ptr::null()) };
+            if job.is_null() {
+                warn!(
+                    "[Shell] 创建 Job Object 失败，超时时只能结束 {} 本身",
+                    child.id()
+                );
+            } else if unsafe { AssignProcessToJobObject(job, child.as_raw_handle() as _) } == 0 {
+                warn!("[Shell] 进程 {} 加入 Job Object 失败", child.id());
+            }
+            Self { job }
+        }
+    }
+
+    /// 强制结束整个进程树（直接执行，不经过模拟执行后端）
+    pub fn kill(&self) {
+        #[cfg(unix)]
+        {
+            let _ = Command::new("kill")
+                .args(["-KILL", "--", &format!("-{}", self.pgid)])
+                .stdout(std::process::Stdio::null())
+                .stderr(std::process::Stdio::null())
+                .status();
+        }
+        #[cfg(windows)]
+        if !self.job.is_null() {
+            // SAFETY: job 为 attach 中创建且尚未关闭的句柄
+            unsafe {
+                windows_sys::Win32::System::JobObjects::TerminateJobObject(self.job, 1);
+            }
+        }
+    }
+}
+
+#[cfg(windows)]
+impl Drop for ProcessTree {
+    fn drop(&mut self) {
+        if !self.job.is_null() {
+            // SAFETY: job 为 attach 中创建的句柄，只在这里关闭一次
+            unsafe {
+                windows_sys::Win32::Foundation::CloseHandle(self.job);
+            }
+        }
+    }
+}
+
+/// 构造接管 stdout / stderr 的命令（带扩展 PATH），调用方可继续追加环境变量后启动
+pub fn piped_command(cmd: &str, args: &[&str], cwd: &std::path::Path) -> Command {
+    let mut command = Command::new(cmd);
+    command
+        .args(args)
+        .current_dir(cwd)
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .envs(platform::profile_env());
+
+    #[cfg(not(windows))]
+    {
+        let extended_path = get_extended_path();
+        command.env("PATH", extended_path);
+    }
+
+    #[cfg(windows)]
+    command.creation_flags(CREATE_NO_WINDOW);
+
+    command
+}
+
+/// 启动命令并接管 stdout / stderr（带扩展 PATH），用于需要实时输出日志的长时间任务
+pub fn spawn_piped(
+    cmd: &str,
+    args: &[&str],
+    cwd: &std::path::Path,
+) -> io::Result<std::process::Child> {
+    piped_command(cmd, args, cwd).spawn()
+}
+
+/// 构造 Bash 命令（带扩展 PATH）
+fn bash_command(script: &str) -> Command {
     let mut command = Command::new("bash");
-    command.arg("-c").arg(script);
+    command.arg("-c").arg(script).envs(platform::profile_env());
     
     // 在非 Windows 系统上使用扩展的 PATH
     #[cfg(not(windows))]
@@ -112,7 +255,12 @@ pub fn run_bash(script: &str) -> io::Result<Output> {
     #[cfg(windows)]
     command.creation_flags(CREATE_NO_WINDOW);
     
-    command.output()
+    command
+}
+
+/// 执行 Bash 命令（带扩展 PATH）
+pub fn run_bash(script: &str) -> io::Result<Output> {
+    executor::current().output(&mut bash_command(script))
 }
 
 /// 执行 Bash 命令并获取输出
@@ -120,9 +268,9 @@ pub fn run_bash_output(script: &str) -> Result<String, String> {
     match run_bash(script) {
         Ok(output) => {
             if output.status.success() {
-                Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+                Ok(encoding::decode_output(&output.stdout).trim().to_string())
             } else {
-                let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+                let stderr = encoding::decode_output(&output.stderr).trim().to_string();
                 if stderr.is_empty() {
                     Err(format!("Command failed with exit code: {:?}", output.status.code()))
                 } else {
@@ -134,27 +282,41 @@ pub fn run_bash_output(script: &str) -> Result<String, String> {
     }
 }
 
-/// 执行 cmd.exe 命令（Windows）- 避免 PowerShell 执行策略问题
-pub fn run_cmd(script: &str) -> io::Result<Output> {
+/// 构造 cmd.exe 命令（Windows）
+fn cmd_command(script: &str) -> Command {
     let mut cmd = Command::new("cmd");
-    cmd.args(["/c", script]);
+    cmd.arg("/c").envs(platform::profile_env());
     
+    // cmd.exe 不按 CreateProcess 规则解析引号，脚本原样传入，
+    // 避免标准库额外加的引号导致 "C:\路径 含空格\xx" 被拆开
     #[cfg(windows)]
-    cmd.creation_flags(CREATE_NO_WINDOW);
+    cmd.raw_arg(script).creation_flags(CREATE_NO_WINDOW);
+    #[cfg(not(windows))]
+    cmd.arg(script);
     
-    cmd.output()
+    cmd
+}
+
+/// 执行 cmd.exe 命令（Windows）- 避免 PowerShell 执行策略问题
+pub fn run_cmd(script: &str) -> io::Result<Output> {
+    executor::current().output(&mut cmd_command(script))
 }
 
 /// 执行 cmd.exe 命令并获取输出（Windows）
 pub fn run_cmd_output(script: &str) -> Result<String, String> {
-    match run_cmd(script) {
+    script_result(run_cmd(script))
+}
+
+/// 脚本执行结果转为输出字符串：失败时优先返回 stderr，其次 stdout
+fn script_result(output: io::Result<Output>) -> Result<String, String> {
+    match output {
         Ok(output) => {
             if output.status.success() {
-                Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+                Ok(encoding::decode_output(&output.stdout).trim().to_string())
             } else {
-                let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+                let stderr = encoding::decode_output(&output.stderr).trim().to_string();
                 if stderr.is_empty() {
-                    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+                    let stdout = encoding::decode_output(&output.stdout).trim().to_string();
                     if stdout.is_empty() {
                         Err(format!("Command failed with exit code: {:?}", output.status.code()))
                     } else {
@@ -174,12 +336,13 @@ pub fn run_cmd_output(script: &str) -> Result<String, String> {
 pub fn run_powershell(script: &str) -> io::Result<Output> {
     let mut cmd = Command::new("powershell");
     // 使用 -ExecutionPolicy Bypass 绕过执行策略限制
-    cmd.args(["-NoProfile", "-NonInteractive", "-ExecutionPolicy", "Bypass", "-Command", script]);
+    cmd.args(["-NoProfile", "-NonInteractive", "-ExecutionPolicy", "Bypass", "-Command", script])
+        .envs(platform::profile_env());
     
     #[cfg(windows)]
     cmd.creation_flags(CREATE_NO_WINDOW);
     
-    cmd.output()
+    executor::current().output(&mut cmd)
 }
 
 /// 执行 PowerShell 命令并获取输出（Windows）
@@ -187,11 +350,11 @@ pub fn run_powershell_output(script: &str) -> Result<String, String> {
     match run_powershell(script) {
         Ok(output) => {
             if output.status.success() {
-                Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+                Ok(encoding::decode_output(&output.stdout).trim().to_string())
             } else {
-                let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+                let stderr = encoding::decode_output(&output.stderr).trim().to_string();
                 if stderr.is_empty() {
-                    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+                    let stdout = encoding::decode_output(&output.stdout).trim().to_string();
                     if stdout.is_empty() {
                         Err(format!("Command failed with exit code: {:?}", output.status.code()))
                     } else {
@@ -206,6 +369,11 @@ pub fn run_powershell_output(script: &str) -> Result<String, String> {
     }
 }
 
+/// 将字符串转为 PowerShell 单引号字面量（单引号内只需把 ' 写成 ''）
+pub fn powershell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
+
 /// 跨平台执行脚本命令
 /// Windows 上使用 cmd.exe（避免 PowerShell 执行策略问题）
 pub fn run_script_output(script: &str) -> Result<String, String> {
@@ -216,20 +384,39 @@ pub fn run_script_output(script: &str) -> Result<String, String> {
     }
 }
 
+/// 跨平台执行脚本命令，`overlay` 中的环境变量只作用于该脚本（见 [`run_with_env`]）
+pub fn run_script_with_env<I, K, V>(script: &str, overlay: I) -> Result<String, String>
+where
+    I: IntoIterator<Item = (K, V)>,
+    K: AsRef<OsStr>,
+    V: AsRef<OsStr>,
+{
+    let mut command = if platform::is_windows() {
+        cmd_command(script)
+    } else {
+        bash_command(script)
+    };
+    apply_overlay(&mut command, overlay);
+    script_result(executor::current().output(&mut command))
+}
+
 /// 后台执行命令（不等待结果）
 pub fn spawn_background(script: &str) -> io::Result<()> {
     if platform::is_windows() {
         let mut cmd = Command::new("cmd");
-        cmd.args(["/c", script]);
+        cmd.arg("/c").envs(platform::profile_env());
         
         #[cfg(windows)]
-        cmd.creation_flags(CREATE_NO_WINDOW);
+        cmd.raw_arg(script).creation_flags(CREATE_NO_WINDOW);
+        #[cfg(not(windows))]
+        cmd.arg(script);
         
         cmd.spawn()?;
     } else {
         Command::new("bash")
             .arg("-c")
             .arg(script)
+            .envs(platform::profile_env())
             .spawn()?;
     }
     Ok(())
@@ -238,24 +425,10 @@ pub fn spawn_background(script: &str) -> io::Result<()> {
 /// 获取 openclaw 可执行文件路径
 /// 检测多个可能的安装路径，因为 GUI 应用不继承用户 shell 的 PATH
 pub fn get_openclaw_path() -> Option<String> {
-    // Windows: 检查常见的 npm 全局安装路径
-    if platform::is_windows() {
-        let possible_paths = get_windows_openclaw_paths();
-        for path in possible_paths {
-            if std::path::Path::new(&path).exists() {
-                info!("[Shell] 在 {} 找到 openclaw", path);
-                return Some(path);
-            }
-        }
-    } else {
-        // Unix: 检查常见的 npm 全局安装路径
-        let possible_paths = get_unix_openclaw_paths();
-        for path in possible_paths {
-            if std::path::Path::new(&path).exists() {
-                info!("[Shell] 在 {} 找到 openclaw", path);
-                return Some(path);
-            }
-        }
+    // 检查 npm 全局安装路径和各种 Node 版本管理器的目录
+    if let Some(path) = node_paths::system().find_openclaw() {
+        info!("[Shell] 在 {} 找到 openclaw", path);
+        return Some(path);
     }
     
     // 回退：检查是否在 PATH 中
@@ -263,90 +436,62 @@ pub fn get_openclaw_path() -> Option<String> {
         return Some("openclaw".to_string());
     }
     
-    // 最后尝试：通过用户 shell 查找
-    if !platform::is_windows() {
-        if let Ok(path) = run_bash_output("source ~/.zshrc 2>/dev/null || source ~/.bashrc 2>/dev/null; which openclaw 2>/dev/null") {
-            if !path.is_empty() && std::path::Path::new(&path).exists() {
-                info!("[Shell] 通过用户 shell 找到 openclaw: {}", path);
-                return Some(path);
-            }
-        }
+    // 最后尝试：在登录 shell 的 PATH 中查找（fnm / asdf / mise 等）
+    if let Some(path) = login_env::find_executable("openclaw") {
+        info!("[Shell] 通过登录 shell 环境找到 openclaw: {}", path.display());
+        return Some(path.display().to_string());
     }
     
     None
 }
 
-/// 获取 Unix 系统上可能的 openclaw 安装路径
-fn get_unix_openclaw_paths() -> Vec<String> {
-    let mut paths = Vec::new();
-    
-    // npm 全局安装路径
-    paths.push("/usr/local/bin/openclaw".to_string());
-    paths.push("/opt/homebrew/bin/openclaw".to_string()); // Homebrew on Apple Silicon
-    paths.push("/usr/bin/openclaw".to_string());
-    
-    if let Some(home) = dirs::home_dir() {
-        let home_str = home.display().to_string();
-        
-        // npm 全局安装到用户目录
-        paths.push(format!("{}/.npm-global/bin/openclaw", home_str));
-        
-        // nvm 安装的 npm 全局包（需要找到正确的 node 版本目录）
-        // 先检查常见版本
-        for version in ["v22.0.0", "v22.1.0", "v22.2.0", "v22.11.0", "v22.12.0", "v23.0.0"] {
-            paths.push(format!("{}/.nvm/versions/node/{}/bin/openclaw", home_str, version));
-        }
-        
-        // 检查 nvm current（尝试读取 .nvmrc 或 default）
-        let nvm_default = format!("{}/.nvm/alias/default", home_str);
-        if let Ok(version) = std::fs::read_to_string(&nvm_default) {
-            let version = version.trim();
-            if !version.is_empty() {
-                paths.insert(0, format!("{}/.nvm/versions/node/v{}/bin/openclaw", home_str, version));
-            }
-        }
-        
-        // fnm
-        paths.push(format!("{}/.fnm/aliases/default/bin/openclaw", home_str));
-        
-        // volta
-        paths.push(format!("{}/.volta/bin/openclaw", home_str));
-        
-        // pnpm 全局安装
-        paths.push(format!("{}/.pnpm/bin/openclaw", home_str));
-        paths.push(format!("{}/Library/pnpm/openclaw", home_str)); // macOS pnpm 默认路径
-        
-        // asdf
-        paths.push(format!("{}/.asdf/shims/openclaw", home_str));
-        
-        // mise (formerly rtx)
-        paths.push(format!("{}/.local/share/mise/shims/openclaw", home_str));
-        
-        // yarn 全局安装
-        paths.push(format!("{}/.yarn/bin/openclaw", home_str));
-        paths.push(format!("{}/.config/yarn/global/node_modules/.bin/openclaw", home_str));
+/// 是否为批处理文件（.cmd / .bat）
+fn is_batch_file(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("cmd") || ext.eq_ignore_ascii_case("bat"))
+}
+
+/// npm 在 Windows 上生成的 openclaw.cmd 对应的包入口（`<prefix>\node_modules\openclaw\openclaw.mjs`）
+fn npm_shim_entry(openclaw_path: &Path) -> Option<PathBuf> {
+    if !is_batch_file(openclaw_path) {
+        return None;
     }
-    
-    paths
+    let entry = openclaw_path
+        .parent()?
+        .join("node_modules")
+        .join("openclaw")
+        .join("openclaw.mjs");
+    entry.is_file().then_some(entry)
 }
 
-/// 获取 Windows 上可能的 openclaw 安装路径
-fn get_windows_openclaw_paths() -> Vec<String> {
-    let mut paths = Vec::new();
-    
-    // 1. nvm4w 安装路径
-    paths.push("C:\\nvm4w\\nodejs\\openclaw.cmd".to_string());
-    
-    // 2. 用户目录下的 npm 全局路径
-    if let Some(home) = dirs::home_dir() {
-        let npm_path = format!("{}\\AppData\\Roaming\\npm\\openclaw.cmd", home.display());
-        paths.push(npm_path);
+/// 构造执行 openclaw 的命令（参数按数组传递）
+///
+/// Windows 上 openclaw.cmd 是批处理 shim，标准库无法安全转义含换行的批处理参数并会拒绝执行，
+/// 因此找得到包入口时改用 node 直接运行；找不到时对这类参数给出明确的错误
+fn openclaw_command(openclaw_path: &str, args: &[&str]) -> io::Result<Command> {
+    let path = Path::new(openclaw_path);
+    if let Some(entry) = npm_shim_entry(path) {
+        if let Some(node) = node_paths::system().find_node() {
+            debug!("[Shell] 通过 node 运行 {}", entry.display());
+            let mut cmd = Command::new(node);
+            cmd.arg(entry).args(args);
+            return Ok(cmd);
+        }
     }
-    
-    // 3. Program Files 下的 nodejs
-    paths.push("C:\\Program Files\\nodejs\\openclaw.cmd".to_string());
-    
-    paths
+    if is_batch_file(path) {
+        if let Some(arg) = args.iter().find(|a| a.contains(['\r', '\n', '\0'])) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "参数包含换行或空字符，无法通过 {} 传递: {:?}",
+                    openclaw_path, arg
+                ),
+            ));
+        }
+    }
+    let mut cmd = Command::new(openclaw_path);
+    cmd.args(args);
+    Ok(cmd)
 }
 
 /// 执行 openclaw 命令并获取输出
@@ -364,35 +509,21 @@ pub fn run_openclaw(args: &[&str]) -> Result<String, String> {
     let extended_path = get_extended_path();
     debug!("[Shell] 扩展 PATH: {}", extended_path);
     
-    let output = if openclaw_path.ends_with(".cmd") {
-        // Windows: .cmd 文件需要通过 cmd /c 执行
-        let mut cmd_args = vec!["/c", &openclaw_path];
-        cmd_args.extend(args);
-        let mut cmd = Command::new("cmd");
-        cmd.args(&cmd_args)
-            .env("OPENCLAW_GATEWAY_TOKEN", DEFAULT_GATEWAY_TOKEN)
-            .env("PATH", &extended_path);
-        
-        #[cfg(windows)]
-        cmd.creation_flags(CREATE_NO_WINDOW);
-        
-        cmd.output()
-    } else {
-        let mut cmd = Command::new(&openclaw_path);
-        cmd.args(args)
-            .env("OPENCLAW_GATEWAY_TOKEN", DEFAULT_GATEWAY_TOKEN)
-            .env("PATH", &extended_path);
-        
-        #[cfg(windows)]
-        cmd.creation_flags(CREATE_NO_WINDOW);
-        
-        cmd.output()
-    };
+    // 参数按数组传递（CreateProcess 语义），路径含空格或中文时不会被 cmd /c 拆开
+    let mut cmd = openclaw_command(&openclaw_path, args).map_err(|e| e.to_string())?;
+    cmd.env("OPENCLAW_GATEWAY_TOKEN", DEFAULT_GATEWAY_TOKEN)
+        .env("PATH", &extended_path)
+        .envs(platform::profile_env());
+    
+    #[cfg(windows)]
+    cmd.creation_flags(CREATE_NO_WINDOW);
+    
+    let output = executor::current().output(&mut cmd);
     
     match output {
         Ok(out) => {
-            let stdout = String::from_utf8_lossy(&out.stdout).to_string();
-            let stderr = String::from_utf8_lossy(&out.stderr).to_string();
+            let stdout = encoding::decode_output(&out.stdout);
+            let stderr = encoding::decode_output(&out.stderr);
             debug!("[Shell] 命令退出码: {:?}", out.status.code());
             if out.status.success() {
                 debug!("[Shell] 命令执行成功, stdout 长度: {}", stdout.len());
@@ -468,37 +599,29 @@ pub fn spawn_openclaw_gateway_with_args(args: &[&str]) -> io::Result<()> {
     // 获取扩展的 PATH，确保能找到 node
     let extended_path = get_extended_path();
     
-    // 构造命令
-    let mut cmd = if openclaw_path.ends_with(".cmd") {
-        info!("[Shell] Windows 模式: 使用 cmd /c 执行");
-        let mut c = Command::new("cmd");
-        let mut cmd_args = vec!["/c", &openclaw_path];
-        if args.is_empty() {
-             cmd_args.extend_from_slice(&["gateway", "--port", "18789"]);
-        } else {
-             cmd_args.extend_from_slice(args);
-        }
-        c.args(cmd_args);
-        c
+    // 构造命令（参数按数组传递）
+    let args = if args.is_empty() {
+        &["gateway", "--port", "18789"][..]
     } else {
-        info!("[Shell] Unix 模式: 直接执行");
-        let mut c = Command::new(&openclaw_path);
-        if args.is_empty() {
-            c.args(["gateway", "--port", "18789"]);
-        } else {
-            c.args(args);
-        }
-        c
+        args
     };
+    let mut cmd = openclaw_command(&openclaw_path, args)?;
     
     // 注入用户的环境变量
     for (key, value) in &user_env_vars {
         cmd.env(key, value);
     }
+    // Manager 设置中针对 Gateway 的环境变量（如 NODE_OPTIONS）
+    let overrides = settings::load_settings().env_overrides.gateway;
+    if !overrides.is_empty() {
+        info!("[Shell] 应用 Gateway 环境变量: {:?}", overrides.keys().collect::<Vec<_>>());
+    }
+    apply_overlay(&mut cmd, &overrides);
     
-    // 设置 PATH 和 gateway token
+    // 设置 PATH、gateway token 和当前配置档案的目录
     cmd.env("PATH", &extended_path);
     cmd.env("OPENCLAW_GATEWAY_TOKEN", DEFAULT_GATEWAY_TOKEN);
+    cmd.envs(platform::profile_env());
     
     // Windows: 隐藏控制台窗口
     #[cfg(windows)]
@@ -508,8 +631,14 @@ pub fn spawn_openclaw_gateway_with_args(args: &[&str]) -> io::Result<()> {
     let child = cmd.spawn();
     
     match child {
-        Ok(c) => {
-            info!("[Shell] ✓ Gateway 进程已启动, PID: {}", c.id());
+        Ok(mut c) => {
+            let pid = c.id();
+            info!("[Shell] ✓ Gateway 进程已启动, PID: {}", pid);
+            // 在后台等待进程退出，避免留下僵尸进程，退出后删除其锁文件
+            std::thread::spawn(move || {
+                let _ = c.wait();
+                gateway_lock::release_if(pid);
+            });
             Ok(())
         }
         Err(e) => {
@@ -532,14 +661,14 @@ pub fn command_exists(cmd: &str) -> bool {
         #[cfg(windows)]
         command.creation_flags(CREATE_NO_WINDOW);
         
-        command.output()
+        executor::current()
+            .output(&mut command)
             .map(|o| o.status.success())
             .unwrap_or(false)
     } else {
         // Unix: 使用 which 命令
-        Command::new("which")
-            .arg(cmd)
-            .output()
+        executor::current()
+            .output(Command::new("which").arg(cmd))
             .map(|o| o.status.success())
             .unwrap_or(false)
     }
@@ -0,0 +1,139 @@
+use crate::commands::shutdown;
+use log::{info, warn};
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// 临时目录名前缀（位于系统临时目录下）
+const DIR_PREFIX: &str = "openclaw-manager-";
+/// 超过该时间的遗留目录（上次崩溃未清理）在启动时删除
+const STALE_AFTER: Duration = Duration::from_secs(24 * 3600);
+
+/// 本次运行的临时目录（首次使用时创建）
+static RUN_DIR: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+/// 新建仅当前用户可访问的目录；目录已存在（包括同名符号链接）时返回错误
+fn create_private_dir(path: &Path) -> io::Result<()> {
+    let mut builder = fs::DirBuilder::new();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::DirBuilderExt;
+        builder.mode(0o700);
+    }
+    builder.create(path)
+}
+
+/// 删除上次运行遗留的临时目录
+fn remove_stale_dirs(base: &Path, current: &Path) {
+    let Ok(entries) = fs::read_dir(base) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name();
+        if path == current || !name.to_string_lossy().starts_with(DIR_PREFIX) {
+            continue;
+        }
+        // 不跟随符号链接，只处理真实目录
+        let Ok(meta) = fs::symlink_metadata(&path) else {
+            continue;
+        };
+        let stale = meta
+            .modified()
+            .ok()
+            .and_then(|t| t.elapsed().ok())
+            .is_some_and(|age| age > STALE_AFTER);
+        if meta.is_dir() && stale {
+            match fs::remove_dir_all(&path) {
+                Ok(()) => info!("[临时文件] 删除遗留目录 {:?}", path),
+                Err(e) => warn!("[临时文件] 删除遗留目录 {:?} 失败: {}", path, e),
+            }
+        }
+    }
+}
+
+/// 获取本次运行的私有临时目录（权限 700，退出时删除）
+pub fn run_dir() -> io::Result<PathBuf> {
+    let mut guard = RUN_DIR.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(dir) = guard.as_ref() {
+        if dir.is_dir() {
+            return Ok(dir.clone());
+        }
+    }
+    let base = std::env::temp_dir();
+    let dir = base.join(format!(
+        "{}{}-{}",
+        DIR_PREFIX,
+        std::process::id(),
+        uuid::Uuid::new_v4().simple()
+    ));
+    create_private_dir(&dir)?;
+    remove_stale_dirs(&base, &dir);
+    shutdown::track_temp_path(&dir);
+    info!("[临时文件] 创建临时目录 {:?}", dir);
+    *guard = Some(dir.clone());
+    Ok(dir)
+}
+
+/// 在 `dir` 中新建文件并写入内容；文件已存在时返回错误，避免覆盖或写入他人创建的文件
+fn create_script_in(dir: &Path, name: &str, content: &[u8]) -> io::Result<PathBuf> {
+    let path = dir.join(name);
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o700);
+    }
+    let mut file = options.open(&path)?;
+    file.write_all(content)?;
+    file.sync_all()?;
+    Ok(path)
+}
+
+/// 在临时目录中创建可执行脚本（仅当前用户可读写执行），文件名加随机后缀以免重名
+/// `name` 形如 `install_nodejs.command`，扩展名保留（macOS 用 .command 在终端中打开）
+pub fn create_script(name: &str, content: &str) -> io::Result<PathBuf> {
+    create_script_in(&run_dir()?, &unique_name(name), content.as_bytes())
+}
+
+fn unique_name(name: &str) -> String {
+    let suffix = &uuid::Uuid::new_v4().simple().to_string()[..8];
+    match name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => format!("{}-{}.{}", stem, suffix, ext),
+        _ => format!("{}-{}", name, suffix),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scripts_are_private_and_never_overwrite() {
+        let dir = std::env::temp_dir().join(format!(
+            "openclaw-temp-test-{}",
+            uuid::Uuid::new_v4().simple()
+        ));
+        create_private_dir(&dir).unwrap();
+        assert!(create_private_dir(&dir).is_err());
+
+        let path = create_script_in(&dir, "login.sh", b"#!/bin/bash\n").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "#!/bin/bash\n");
+        assert!(create_script_in(&dir, "login.sh", b"evil").is_err());
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = |p: &Path| fs::metadata(p).unwrap().permissions().mode() & 0o777;
+            assert_eq!(mode(&dir), 0o700);
+            assert_eq!(mode(&path), 0o700);
+        }
+
+        let name = unique_name("install_nodejs.command");
+        assert!(name.starts_with("install_nodejs-") && name.ends_with(".command"));
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
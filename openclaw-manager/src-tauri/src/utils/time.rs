@@ -0,0 +1,133 @@
+//! 时间与时区：调度计算（维护窗口、预算周期、按天统计）使用设置中的时区，未设置时使用系统时区；
+//! API 返回的时间统一为带偏移量的 RFC3339，界面和导出的报表显示一致
+use crate::commands::settings;
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use std::sync::RwLock;
+use std::time::SystemTime;
+
+/// 缓存的用户时区：外层 None 表示尚未读取设置
+static ZONE: RwLock<Option<Option<Tz>>> = RwLock::new(None);
+
+/// 解析时区设置（IANA 名称，如 Asia/Shanghai），为空表示系统时区
+pub fn parse_timezone(name: &str) -> Result<Option<Tz>, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Ok(None);
+    }
+    name.parse::<Tz>()
+        .map(Some)
+        .map_err(|_| format!("无效的时区: {}（应为 IANA 名称，如 Asia/Shanghai）", name))
+}
+
+/// 设置中的时区（未设置或无效时为 None，使用系统时区）；首次调用时读取设置，之后使用缓存
+pub fn user_timezone() -> Option<Tz> {
+    if let Some(zone) = *ZONE.read().unwrap_or_else(|e| e.into_inner()) {
+        return zone;
+    }
+    // 持有写锁读取设置，避免与 set_user_timezone 交错时写回旧值
+    let mut cached = ZONE.write().unwrap_or_else(|e| e.into_inner());
+    *cached.get_or_insert_with(|| zone_from(settings::load_settings().timezone.as_deref()))
+}
+
+/// 设置保存后更新缓存的时区
+pub fn set_user_timezone(name: Option<&str>) {
+    *ZONE.write().unwrap_or_else(|e| e.into_inner()) = Some(zone_from(name));
+}
+
+fn zone_from(name: Option<&str>) -> Option<Tz> {
+    name.and_then(|name| parse_timezone(name).ok().flatten())
+}
+
+/// UTC 时间转换为指定时区（None 为系统时区）
+pub fn in_zone(tz: Option<Tz>, utc: DateTime<Utc>) -> DateTime<FixedOffset> {
+    match tz {
+        Some(tz) => utc.with_timezone(&tz).fixed_offset(),
+        None => utc.with_timezone(&Local).fixed_offset(),
+    }
+}
+
+/// 指定时区的墙上时间转换为带偏移量的时间（夏令时重复的时刻取较早的一个，跳过的时刻顺延一小时）
+pub fn localize(tz: Option<Tz>, naive: NaiveDateTime) -> Option<DateTime<FixedOffset>> {
+    fn resolve<Z: TimeZone>(zone: &Z, naive: NaiveDateTime) -> Option<DateTime<FixedOffset>> {
+        zone.from_local_datetime(&naive)
+            .earliest()
+            .or_else(|| {
+                zone.from_local_datetime(&(naive + chrono::Duration::hours(1)))
+                    .earliest()
+            })
+            .map(|t| t.fixed_offset())
+    }
+    match tz {
+        Some(tz) => resolve(&tz, naive),
+        None => resolve(&Local, naive),
+    }
+}
+
+/// 指定时区某一天的零点
+pub fn start_of_day(tz: Option<Tz>, date: NaiveDate) -> Option<DateTime<FixedOffset>> {
+    localize(tz, date.and_time(NaiveTime::MIN))
+}
+
+/// 用户时区的当前时间
+pub fn now() -> DateTime<FixedOffset> {
+    in_zone(user_timezone(), Utc::now())
+}
+
+/// 用户时区的当前时间（RFC3339，带偏移量）
+pub fn now_rfc3339() -> String {
+    now().to_rfc3339()
+}
+
+/// 毫秒时间戳转换为用户时区的时间
+pub fn from_millis(ms: i64) -> Option<DateTime<FixedOffset>> {
+    DateTime::from_timestamp_millis(ms).map(|t| in_zone(user_timezone(), t))
+}
+
+/// 系统时间（如文件修改时间）转换为用户时区的时间
+pub fn from_system_time(time: SystemTime) -> DateTime<FixedOffset> {
+    in_zone(user_timezone(), time.into())
+}
+
+/// 用户时区今天零点的毫秒时间戳
+pub fn today_start_ms() -> i64 {
+    let tz = user_timezone();
+    start_of_day(tz, in_zone(tz, Utc::now()).date_naive())
+        .map(|t| t.timestamp_millis())
+        .unwrap_or_default()
+}
+
+/// SQLite 日期函数的偏移修饰符（如 `+480 minutes`），把 UTC 时间戳换算为用户时区的日期
+/// （使用当前偏移量，统计范围跨越夏令时切换时个别记录的日期可能相差一小时）
+pub fn sqlite_offset() -> String {
+    format!("{:+} minutes", now().offset().local_minus_utc() / 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn naive(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[test]
+    fn localize_handles_dst_gap_and_overlap() {
+        let tz = parse_timezone("America/New_York").unwrap();
+        // 跳过的时刻（02:00 → 03:00）顺延一小时
+        let gap = localize(tz, naive("2024-03-10 02:30")).unwrap();
+        assert_eq!(gap.to_rfc3339(), "2024-03-10T03:30:00-04:00");
+        // 重复的时刻（02:00 → 01:00）取较早的一个（仍为夏令时）
+        let overlap = localize(tz, naive("2024-11-03 01:30")).unwrap();
+        assert_eq!(overlap.to_rfc3339(), "2024-11-03T01:30:00-04:00");
+        // 普通时刻不受影响
+        let normal = localize(tz, naive("2024-11-03 03:00")).unwrap();
+        assert_eq!(normal.to_rfc3339(), "2024-11-03T03:00:00-05:00");
+        assert_eq!(
+            start_of_day(tz, NaiveDate::from_ymd_opt(2024, 3, 10).unwrap())
+                .unwrap()
+                .to_rfc3339(),
+            "2024-03-10T00:00:00-05:00"
+        );
+    }
+}
@@ -1,6 +1,5 @@
 import { useState, useEffect, useCallback } from 'react';
 import { motion, AnimatePresence } from 'framer-motion';
-import { invoke } from '@tauri-apps/api/core';
 import { Sidebar } from './components/Layout/Sidebar';
 import { Header } from './components/Layout/Header';
 import { Dashboard } from './components/Dashboard';
@@ -10,7 +9,7 @@ import { Settings } from './components/Settings';
 import { Testing } from './components/Testing';
 import { Logs } from './components/Logs';
 import { appLogger } from './lib/logger';
-import { isTauri } from './lib/tauri';
+import { invoke, isTauri, type CompatibilityCheck, type SeverityCounts } from './lib/tauri';
 import { Download, Loader2, CheckCircle, AlertCircle, ArrowRight } from 'lucide-react';
 
 export type PageType = 'dashboard' | 'ai' | 'channels' | 'testing' | 'logs' | 'settings';
@@ -21,6 +20,11 @@ export interface EnvironmentStatus {
   node_version_ok: boolean;
   openclaw_installed: boolean;
   openclaw_version: string | null;
+  git_installed: boolean;
+  git_version: string | null;
+  ffmpeg_installed: boolean;
+  ffmpeg_version: string | null;
+  ffmpeg_version_ok: boolean;
   config_dir_exists: boolean;
   ready: boolean;
   os: string;
@@ -36,6 +40,8 @@ interface UpdateInfo {
   update_available: boolean;
   current_version: string | null;
   latest_version: string | null;
+  vulnerabilities: SeverityCounts | null;
+  compatibility: CompatibilityCheck | null;
   error: string | null;
 }
 
@@ -273,6 +279,33 @@ function App() {
                   </div>
                 </div>
 
+                {updateInfo.vulnerabilities && updateInfo.vulnerabilities.total > 0 && (
+                  <div className="p-4 bg-red-500/10 border border-red-500/20 rounded-xl">
+                    <div className="flex gap-3">
+                      <div className="mt-0.5">
+                        <AlertCircle size={18} className="text-red-400" />
+                      </div>
+                      <p className="text-xs text-red-300/80 leading-relaxed">
+                        当前版本的依赖中有 {updateInfo.vulnerabilities.total} 个已知漏洞
+                        （严重 {updateInfo.vulnerabilities.critical}，高危 {updateInfo.vulnerabilities.high}），建议更新。
+                      </p>
+                    </div>
+                  </div>
+                )}
+
+                {updateInfo.compatibility?.message && (
+                  <div className="p-4 bg-yellow-500/10 border border-yellow-500/20 rounded-xl">
+                    <div className="flex gap-3">
+                      <div className="mt-0.5">
+                        <AlertCircle size={18} className="text-yellow-400" />
+                      </div>
+                      <p className="text-xs text-yellow-300/80 leading-relaxed">
+                        {updateInfo.compatibility.message}
+                      </p>
+                    </div>
+                  </div>
+                )}
+
                 <div className="p-4 bg-blue-500/10 border border-blue-500/20 rounded-xl">
                   <div className="flex gap-3">
                     <div className="mt-0.5">
@@ -311,7 +344,7 @@ function App() {
                 </button>
                 <button
                   onClick={handleUpdate}
-                  disabled={updating}
+                  disabled={updating || updateInfo.compatibility?.status === 'unsupported'}
                   className="flex-[2] py-3 px-4 rounded-xl bg-brand-600 hover:bg-brand-500 text-white font-medium shadow-lg shadow-brand-500/20 transition-all flex items-center justify-center gap-2 disabled:opacity-50 disabled:cursor-not-allowed"
                 >
                   {updating ? (
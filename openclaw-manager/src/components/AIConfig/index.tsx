@@ -1,6 +1,6 @@
 import { useEffect, useState, useCallback } from 'react';
 import { motion, AnimatePresence } from 'framer-motion';
-import { invoke } from '@tauri-apps/api/core';
+import { invoke, invokeConfirmed, toSecretHandle } from '../../lib/tauri';
 import {
   Check,
   Eye,
@@ -236,7 +236,7 @@ function ProviderDialog({ officialProviders, onClose, onSave, editingProvider }:
       await invoke('save_provider', {
         providerName,
         baseUrl,
-        apiKey: apiKey || null,
+        apiKey: apiKey ? await toSecretHandle(apiKey) : null,
         apiType,
         models,
       });
@@ -659,7 +659,7 @@ function ProviderCard({ provider, officialProviders, onSetPrimary, onRefresh, on
     setDeleting(true);
     setDeleteError(null);
     try {
-      await invoke('delete_provider', { providerName: provider.name });
+      await invokeConfirmed('delete_provider', { providerName: provider.name });
       setShowDeleteConfirm(false);
       onRefresh();
     } catch (e) {
@@ -1,6 +1,6 @@
 import { useEffect, useState } from 'react';
 import { motion } from 'framer-motion';
-import { invoke } from '@tauri-apps/api/core';
+import { invoke, invokeConfirmed, toSecretHandle } from '../../lib/tauri';
 import {
   MessageCircle,
   Hash,
@@ -269,7 +269,7 @@ export function Channels() {
     setShowClearConfirm(false);
     setClearing(true);
     try {
-      await invoke('clear_channel_config', { channelId: selectedChannel });
+      await invokeConfirmed('clear_channel_config', { channelId: selectedChannel });
       // 清空表单
       setConfigForm({});
       // 刷新列表
@@ -429,17 +429,22 @@ export function Channels() {
       const channel = channels.find((c) => c.id === selectedChannel);
       if (!channel) return;
       
-      // 转换表单值
+      // 转换表单值（密钥字段换成句柄提交）
+      const secretKeys = new Set(
+        (channelInfo[channel.channel_type]?.fields ?? [])
+          .filter((f) => f.type === 'password')
+          .map((f) => f.key)
+      );
       const config: Record<string, unknown> = {};
-      Object.entries(configForm).forEach(([key, value]) => {
+      for (const [key, value] of Object.entries(configForm)) {
         if (value === 'true') {
           config[key] = true;
         } else if (value === 'false') {
           config[key] = false;
         } else if (value) {
-          config[key] = value;
+          config[key] = secretKeys.has(key) ? await toSecretHandle(value) : value;
         }
-      });
+      }
       
       await invoke('save_channel_config', {
         channel: {
@@ -1,7 +1,7 @@
 import { useState, useRef, useEffect } from 'react';
 import { motion, AnimatePresence } from 'framer-motion';
 import { Send, Bot, User, Loader2, Sparkles, Terminal } from 'lucide-react';
-import { invoke } from '@tauri-apps/api/core';
+import { invoke } from '../../lib/tauri';
 
 interface Message {
   id: string;
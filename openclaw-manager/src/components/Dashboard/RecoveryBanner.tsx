@@ -0,0 +1,90 @@
+import { useEffect, useState } from 'react';
+import { AlertTriangle, Loader2 } from 'lucide-react';
+import { api, isConfirmationCancelled, isTauri, RecoveryAction, RecoveryState } from '../../lib/tauri';
+
+interface RecoveryBannerProps {
+  onRecovered: () => void;
+}
+
+// 更新后 Gateway 陷入崩溃循环时显示恢复方案
+export function RecoveryBanner({ onRecovered }: RecoveryBannerProps) {
+  const [state, setState] = useState<RecoveryState | null>(null);
+  const [running, setRunning] = useState<RecoveryAction | null>(null);
+  const [message, setMessage] = useState<string | null>(null);
+
+  const fetchState = async () => {
+    if (!isTauri()) return;
+    try {
+      setState(await api.getRecoveryOptions());
+    } catch {
+      // 静默处理
+    }
+  };
+
+  useEffect(() => {
+    fetchState();
+  }, []);
+
+  const handleRecover = async (action: RecoveryAction) => {
+    setRunning(action);
+    setMessage(null);
+    try {
+      const result = await api.executeRecovery(action);
+      setMessage(result.message);
+      if (result.gateway_healthy) {
+        onRecovered();
+      }
+      await fetchState();
+    } catch (e) {
+      if (!isConfirmationCancelled(e)) setMessage(String(e));
+    } finally {
+      setRunning(null);
+    }
+  };
+
+  if (!state?.needed) {
+    return message ? (
+      <div className="bg-green-500/10 border border-green-500/30 rounded-2xl p-4 text-sm text-green-300">
+        {message}
+      </div>
+    ) : null;
+  }
+
+  const lastError = state.failures[state.failures.length - 1]?.error;
+
+  return (
+    <div className="bg-red-500/10 border border-red-500/30 rounded-2xl p-5">
+      <div className="flex items-start gap-3">
+        <AlertTriangle size={20} className="text-red-400 mt-0.5 shrink-0" />
+        <div className="flex-1 space-y-3">
+          <div>
+            <p className="text-sm font-semibold text-white">
+              更新到 OpenClaw {state.update?.to_version} 后 Gateway 无法正常运行
+            </p>
+            <p className="text-xs text-gray-400 mt-1">
+              更新后已失败 {state.failures.length} 次{lastError ? `，最近一次：${lastError}` : ''}。请选择恢复方式：
+            </p>
+          </div>
+          <div className="grid grid-cols-1 md:grid-cols-3 gap-3">
+            {state.options.map((option) => (
+              <button
+                key={option.action}
+                onClick={() => handleRecover(option.action)}
+                disabled={!option.available || running !== null}
+                title={option.reason ?? undefined}
+                className="text-left bg-dark-600 hover:bg-dark-500 disabled:opacity-50 disabled:cursor-not-allowed rounded-xl p-3 transition-colors"
+              >
+                <div className="flex items-center gap-2 text-sm font-medium text-white">
+                  {running === option.action && <Loader2 size={14} className="animate-spin" />}
+                  {option.label}
+                </div>
+                <p className="text-xs text-gray-400 mt-1">{option.reason ?? option.description}</p>
+              </button>
+            ))}
+          </div>
+          {message && <p className="text-xs text-red-300">{message}</p>}
+        </div>
+      </div>
+    </div>
+  );
+}
@@ -8,14 +8,18 @@ interface ServiceStatus {
   uptime_seconds: number | null;
   memory_mb: number | null;
   cpu_percent: number | null;
+  managed_by?: 'manager' | 'external' | null;
 }
 
 interface StatusCardProps {
   status: ServiceStatus | null;
   loading: boolean;
+  onAdopt?: () => void;
 }
 
-export function StatusCard({ status, loading }: StatusCardProps) {
+export function StatusCard({ status, loading, onAdopt }: StatusCardProps) {
+  const external = status?.running && status.managed_by === 'external';
+
   const formatUptime = (seconds: number | null) => {
     if (!seconds) return '--';
     const hours = Math.floor(seconds / 3600);
@@ -29,6 +33,21 @@ export function StatusCard({ status, loading }: StatusCardProps) {
       <div className="flex items-center justify-between mb-6">
         <h3 className="text-lg font-semibold text-white">服务状态</h3>
         <div className="flex items-center gap-2">
+          {external && (
+            <>
+              <span
+                className="text-xs px-2 py-0.5 rounded-full bg-accent-amber/20 text-accent-amber"
+                title="该 Gateway 在 Manager 之外启动（如终端中执行 openclaw gateway start），Manager 只监控其状态，退出时不会按崩溃处理"
+              >
+                外部启动
+              </span>
+              {onAdopt && (
+                <button onClick={onAdopt} className="text-xs text-claw-400 hover:text-claw-300">
+                  由 Manager 接管
+                </button>
+              )}
+            </>
+          )}
           <div
             className={clsx(
               'status-dot',
@@ -1,11 +1,11 @@
 import { useEffect, useState, useRef } from 'react';
 import { motion } from 'framer-motion';
-import { invoke } from '@tauri-apps/api/core';
 import { StatusCard } from './StatusCard';
 import { QuickActions } from './QuickActions';
 import { SystemInfo } from './SystemInfo';
+import { RecoveryBanner } from './RecoveryBanner';
 import { Setup } from '../Setup';
-import { api, ServiceStatus, isTauri } from '../../lib/tauri';
+import { api, ServiceStatus, isTauri, isLowResources, invoke } from '../../lib/tauri';
 import { Terminal, RefreshCw, ChevronDown, ChevronUp } from 'lucide-react';
 import clsx from 'clsx';
 import { EnvironmentStatus } from '../../App';
@@ -70,11 +70,23 @@ export function Dashboard({ envStatus, onSetupComplete }: DashboardProps) {
     }
   }, [logs, logsExpanded]);
 
+  // 磁盘空间或内存不足时后端拒绝启动，用户确认后强制启动
+  const withResourceOverride = async (action: (force: boolean) => Promise<string>) => {
+    try {
+      return await action(false);
+    } catch (e) {
+      if (isLowResources(e) && window.confirm(`${String(e).replace(/^LowResources:\s*/, '')}\n\n仍要强制启动吗？`)) {
+        return await action(true);
+      }
+      throw e;
+    }
+  };
+
   const handleStart = async () => {
     if (!isTauri()) return;
     setActionLoading(true);
     try {
-      await api.startService();
+      await withResourceOverride(api.startService);
       await fetchStatus();
       await fetchLogs();
     } catch (e) {
@@ -102,7 +114,7 @@ export function Dashboard({ envStatus, onSetupComplete }: DashboardProps) {
     if (!isTauri()) return;
     setActionLoading(true);
     try {
-      await api.restartService();
+      await withResourceOverride(api.restartService);
       await fetchStatus();
       await fetchLogs();
     } catch (e) {
@@ -112,6 +124,17 @@ export function Dashboard({ envStatus, onSetupComplete }: DashboardProps) {
     }
   };
 
+  // 接管在 Manager 之外启动的 Gateway
+  const handleAdopt = async () => {
+    if (!isTauri()) return;
+    try {
+      await api.adoptGateway();
+      await fetchStatus();
+    } catch (e) {
+      console.error('接管失败:', e);
+    }
+  };
+
   const getLogLineClass = (line: string) => {
     if (line.includes('error') || line.includes('Error') || line.includes('ERROR')) {
       return 'text-red-400';
@@ -158,9 +181,14 @@ export function Dashboard({ envStatus, onSetupComplete }: DashboardProps) {
           </motion.div>
         )}
 
+        {/* 更新后恢复（更新后 Gateway 反复崩溃时显示） */}
+        <motion.div variants={itemVariants}>
+          <RecoveryBanner onRecovered={fetchStatus} />
+        </motion.div>
+
         {/* 服务状态卡片 */}
         <motion.div variants={itemVariants}>
-          <StatusCard status={status} loading={loading} />
+          <StatusCard status={status} loading={loading} onAdopt={handleAdopt} />
         </motion.div>
 
         {/* 快捷操作 */}
@@ -1,8 +1,7 @@
 import { useState } from 'react';
 import { PageType } from '../../App';
 import { RefreshCw, ExternalLink, Loader2 } from 'lucide-react';
-import { open } from '@tauri-apps/plugin-shell';
-import { invoke } from '@tauri-apps/api/core';
+import { invoke } from '../../lib/tauri';
 
 interface HeaderProps {
   currentPage: PageType;
@@ -24,9 +23,8 @@ export function Header({ currentPage }: HeaderProps) {
   const handleOpenDashboard = async () => {
     setOpening(true);
     try {
-      // 获取带 token 的 Dashboard URL（如果没有 token 会自动生成）
-      const url = await invoke<string>('get_dashboard_url');
-      await open(url);
+      // 由后端带上 token 打开 Dashboard（如果没有 token 会自动生成）
+      await invoke('open_dashboard');
     } catch (e) {
       console.error('打开 Dashboard 失败:', e);
       // 降级方案：使用 window.open（不带 token）
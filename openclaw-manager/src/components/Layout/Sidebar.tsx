@@ -8,7 +8,7 @@ import {
   ScrollText,
   Settings,
 } from 'lucide-react';
-import { invoke } from '@tauri-apps/api/core';
+import { invoke } from '../../lib/tauri';
 import { PageType } from '../../App';
 import clsx from 'clsx';
 
@@ -0,0 +1,267 @@
+import { useEffect, useState } from 'react';
+import { HardDriveUpload, Loader2, RotateCcw } from 'lucide-react';
+import { api, BackupMode, BackupSettings, BackupStatus, isTauri, RemoteBackup } from '../../lib/tauri';
+import { EMPTY_WEBDAV, RemoteTargetFields } from './RemoteTargetFields';
+
+function formatSize(size: number | null) {
+  if (size === null) return '';
+  if (size >= 1024 * 1024) return `${(size / 1024 / 1024).toFixed(1)} MB`;
+  return `${Math.max(1, Math.round(size / 1024))} KB`;
+}
+
+// 远程备份：配置目录（配置、Agent 记忆、会话）加密后上传到 WebDAV / S3，可从远程恢复
+export function BackupSection() {
+  const [status, setStatus] = useState<BackupStatus | null>(null);
+  const [form, setForm] = useState<BackupSettings | null>(null);
+  const [passphrase, setPassphrase] = useState('');
+  const [backups, setBackups] = useState<RemoteBackup[]>([]);
+  const [busy, setBusy] = useState<string | null>(null);
+  const [message, setMessage] = useState<string | null>(null);
+
+  const fetchBackups = async () => {
+    try {
+      setBackups(await api.listRemoteBackups());
+    } catch (e) {
+      setMessage(String(e));
+    }
+  };
+
+  const fetchStatus = async () => {
+    if (!isTauri()) return;
+    try {
+      const result = await api.getBackupStatus();
+      setStatus(result);
+      setForm(result.settings);
+      if (result.settings.remote) {
+        await fetchBackups();
+      }
+    } catch (e) {
+      setMessage(String(e));
+    }
+  };
+
+  useEffect(() => {
+    fetchStatus();
+  }, []);
+
+  if (!form) return null;
+
+  const target = form.remote ?? EMPTY_WEBDAV;
+
+  const run = async (key: string, action: () => Promise<string>) => {
+    setBusy(key);
+    setMessage(null);
+    try {
+      setMessage(await action());
+    } catch (e) {
+      setMessage(String(e));
+    } finally {
+      setBusy(null);
+    }
+  };
+
+  const handleSave = () =>
+    run('save', async () => {
+      const result = await api.configureRemoteBackup({ ...form, remote: target }, passphrase);
+      setStatus(result);
+      setForm(result.settings);
+      setPassphrase('');
+      await fetchBackups();
+      return '备份设置已保存';
+    });
+
+  const handleBackup = () =>
+    run('backup', async () => {
+      const backup = await api.createRemoteBackup();
+      await fetchStatus();
+      return `已上传 ${backup.name}（${formatSize(backup.size)}）`;
+    });
+
+  const handleRestore = (backup: RemoteBackup) => {
+    if (
+      !confirm(
+        `确定要从 ${backup.name} 恢复吗？\n\n${
+          backup.kind === 'differential' ? '差异备份恢复时会同时下载所依据的完整备份。' : ''
+        }当前配置目录会被替换（原目录保留以便回退），运行中的 Gateway 会重新启动。`
+      )
+    ) {
+      return;
+    }
+    run(backup.key, () => api.restoreRemoteBackup(backup.key));
+  };
+
+  return (
+    <div className="bg-dark-700 rounded-2xl p-6 border border-dark-500">
+      <div className="flex items-center gap-3 mb-6">
+        <div className="w-10 h-10 rounded-xl bg-emerald-500/20 flex items-center justify-center">
+          <HardDriveUpload size={20} className="text-emerald-400" />
+        </div>
+        <div>
+          <h3 className="text-lg font-semibold text-white">远程备份</h3>
+          <p className="text-xs text-gray-500">配置、Agent 记忆和会话加密后备份到 WebDAV / S3</p>
+        </div>
+      </div>
+
+      <div className="space-y-4">
+        <RemoteTargetFields target={target} onChange={(t) => setForm({ ...form, remote: t })} />
+
+        <div>
+          <label className="block text-sm text-gray-400 mb-2">备份口令</label>
+          <input
+            type="password"
+            value={passphrase}
+            onChange={(e) => setPassphrase(e.target.value)}
+            placeholder={form.passphrase ? '已设置，留空保持不变' : '恢复时需要相同的口令，请妥善保管'}
+            className="input-base"
+          />
+        </div>
+
+        <div className="grid grid-cols-3 gap-3">
+          <div>
+            <label className="block text-sm text-gray-400 mb-2">保留最近</label>
+            <input
+              type="number"
+              min={1}
+              value={form.retention.keep_last}
+              onChange={(e) =>
+                setForm({ ...form, retention: { ...form.retention, keep_last: Number(e.target.value) || 1 } })
+              }
+              className="input-base"
+            />
+          </div>
+          <div>
+            <label className="block text-sm text-gray-400 mb-2">保留天数</label>
+            <input
+              type="number"
+              min={0}
+              value={form.retention.keep_days}
+              onChange={(e) =>
+                setForm({ ...form, retention: { ...form.retention, keep_days: Number(e.target.value) || 0 } })
+              }
+              className="input-base"
+            />
+          </div>
+          <div>
+            <label className="block text-sm text-gray-400 mb-2">自动备份（小时）</label>
+            <input
+              type="number"
+              min={0}
+              value={form.interval_hours}
+              onChange={(e) => setForm({ ...form, interval_hours: Number(e.target.value) || 0 })}
+              className="input-base"
+            />
+          </div>
+        </div>
+
+        <div>
+          <label className="block text-sm text-gray-400 mb-2">备份方式</label>
+          <select
+            value={form.mode}
+            onChange={(e) => setForm({ ...form, mode: e.target.value as BackupMode })}
+            className="input-base"
+          >
+            <option value="full">完整备份（每次上传全部文件）</option>
+            <option value="differential">差异备份（只上传相对上次完整备份变化的部分）</option>
+          </select>
+        </div>
+
+        {form.mode === 'differential' && (
+          <div className="grid grid-cols-2 gap-3">
+            <div>
+              <label className="block text-sm text-gray-400 mb-2">每隔几天完整备份（0 为不限）</label>
+              <input
+                type="number"
+                min={0}
+                value={form.full_every_days}
+                onChange={(e) => setForm({ ...form, full_every_days: Number(e.target.value) || 0 })}
+                className="input-base"
+              />
+            </div>
+            <div>
+              <label className="block text-sm text-gray-400 mb-2">每几次差异备份后完整备份（0 为不限）</label>
+              <input
+                type="number"
+                min={0}
+                value={form.full_every_backups}
+                onChange={(e) => setForm({ ...form, full_every_backups: Number(e.target.value) || 0 })}
+                className="input-base"
+              />
+            </div>
+          </div>
+        )}
+
+        {status && (
+          <div className="text-xs text-gray-500 space-y-1">
+            <p>
+              上次备份：{status.last_backup_at ? new Date(status.last_backup_at).toLocaleString() : '从未备份'}
+            </p>
+            {status.settings.mode === 'differential' && status.full_backup && (
+              <p>
+                最近完整备份：{status.full_backup}，之后 {status.differentials} 次差异备份
+              </p>
+            )}
+            {status.last_error && <p className="text-red-400">上次备份失败：{status.last_error}</p>}
+          </div>
+        )}
+
+        <div className="flex gap-3">
+          <button
+            onClick={handleSave}
+            disabled={busy !== null}
+            className="btn-primary flex items-center gap-2"
+          >
+            {busy === 'save' && <Loader2 size={16} className="animate-spin" />}
+            保存
+          </button>
+          <button
+            onClick={handleBackup}
+            disabled={busy !== null || !status?.settings.remote}
+            className="btn-secondary flex items-center gap-2"
+          >
+            {busy === 'backup' && <Loader2 size={16} className="animate-spin" />}
+            立即备份
+          </button>
+        </div>
+
+        {backups.length > 0 && (
+          <div className="space-y-2">
+            {backups.map((backup) => (
+              <div key={backup.key} className="flex items-center gap-3 p-3 bg-dark-600 rounded-lg">
+                <div className="flex-1 min-w-0">
+                  <p className="text-sm text-white truncate">
+                    {backup.name}
+                    <span
+                      className={`ml-2 text-xs px-1.5 py-0.5 rounded ${
+                        backup.kind === 'full' ? 'bg-emerald-500/20 text-emerald-400' : 'bg-dark-500 text-gray-400'
+                      }`}
+                    >
+                      {backup.kind === 'full' ? '完整' : '差异'}
+                    </span>
+                  </p>
+                  <p className="text-xs text-gray-500">
+                    {backup.created_at ? new Date(backup.created_at).toLocaleString() : ''}{' '}
+                    {formatSize(backup.size)}
+                  </p>
+                </div>
+                <button
+                  onClick={() => handleRestore(backup)}
+                  disabled={busy !== null}
+                  className="flex items-center gap-1 text-sm text-amber-400 hover:text-amber-300 disabled:opacity-50"
+                >
+                  {busy === backup.key ? (
+                    <Loader2 size={14} className="animate-spin" />
+                  ) : (
+                    <RotateCcw size={14} />
+                  )}
+                  恢复
+                </button>
+              </div>
+            ))}
+          </div>
+        )}
+
+        {message && <p className="text-xs text-gray-400">{message}</p>}
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,201 @@
+import { useEffect, useState } from 'react';
+import { Layers, Loader2, Plus, Trash2, Check } from 'lucide-react';
+import { api, isTauri, ProfileStatus } from '../../lib/tauri';
+
+interface ProfilesSectionProps {
+  onSwitched?: () => void;
+}
+
+// 配置档案：每个档案使用独立的配置目录，切换后 Gateway 读取新档案的配置
+export function ProfilesSection({ onSwitched }: ProfilesSectionProps) {
+  const [profiles, setProfiles] = useState<ProfileStatus[]>([]);
+  const [busy, setBusy] = useState<string | null>(null);
+  const [creating, setCreating] = useState(false);
+  const [newName, setNewName] = useState('');
+  const [newDescription, setNewDescription] = useState('');
+  const [copyFrom, setCopyFrom] = useState('');
+  const [message, setMessage] = useState<string | null>(null);
+
+  const fetchProfiles = async () => {
+    if (!isTauri()) return;
+    try {
+      setProfiles(await api.listProfiles());
+    } catch (e) {
+      setMessage(String(e));
+    }
+  };
+
+  useEffect(() => {
+    fetchProfiles();
+  }, []);
+
+  const handleSwitch = async (name: string) => {
+    setBusy(name);
+    setMessage(null);
+    try {
+      setMessage(await api.switchProfile(name));
+      await fetchProfiles();
+      onSwitched?.();
+    } catch (e) {
+      setMessage(String(e));
+    } finally {
+      setBusy(null);
+    }
+  };
+
+  const handleCreate = async () => {
+    if (!newName.trim()) return;
+    setBusy('__create__');
+    setMessage(null);
+    try {
+      const profile = await api.createProfile(newName.trim(), newDescription.trim(), copyFrom);
+      setMessage(`已创建档案 ${profile.name}`);
+      setCreating(false);
+      setNewName('');
+      setNewDescription('');
+      setCopyFrom('');
+      await fetchProfiles();
+    } catch (e) {
+      setMessage(String(e));
+    } finally {
+      setBusy(null);
+    }
+  };
+
+  const handleDelete = async (profile: ProfileStatus) => {
+    if (!confirm(`确定要删除档案 ${profile.name} 吗？`)) return;
+    const removeFiles = confirm(
+      `是否同时删除配置目录？\n${profile.config_dir}\n\n选择“取消”将保留目录中的文件。`
+    );
+    setBusy(profile.name);
+    setMessage(null);
+    try {
+      setMessage(await api.deleteProfile(profile.name, removeFiles));
+      await fetchProfiles();
+    } catch (e) {
+      setMessage(String(e));
+    } finally {
+      setBusy(null);
+    }
+  };
+
+  return (
+    <div className="bg-dark-700 rounded-2xl p-6 border border-dark-500">
+      <div className="flex items-center gap-3 mb-6">
+        <div className="w-10 h-10 rounded-xl bg-blue-500/20 flex items-center justify-center">
+          <Layers size={20} className="text-blue-400" />
+        </div>
+        <div className="flex-1">
+          <h3 className="text-lg font-semibold text-white">配置档案</h3>
+          <p className="text-xs text-gray-500">为不同用途使用独立的配置、渠道和模型</p>
+        </div>
+        <button
+          onClick={() => setCreating(!creating)}
+          className="flex items-center gap-1 text-sm text-claw-400 hover:text-claw-300"
+        >
+          <Plus size={16} />
+          新建档案
+        </button>
+      </div>
+
+      {creating && (
+        <div className="space-y-3 mb-4 p-4 bg-dark-600 rounded-lg">
+          <input
+            type="text"
+            value={newName}
+            onChange={(e) => setNewName(e.target.value)}
+            placeholder="档案名称（字母、数字、- 和 _）"
+            className="input-base"
+          />
+          <input
+            type="text"
+            value={newDescription}
+            onChange={(e) => setNewDescription(e.target.value)}
+            placeholder="说明（可选），例如：工作"
+            className="input-base"
+          />
+          <select
+            value={copyFrom}
+            onChange={(e) => setCopyFrom(e.target.value)}
+            className="input-base"
+          >
+            <option value="">空白配置</option>
+            {profiles.map((p) => (
+              <option key={p.name} value={p.name}>
+                复制 {p.name} 的配置
+              </option>
+            ))}
+          </select>
+          <button
+            onClick={handleCreate}
+            disabled={!newName.trim() || busy !== null}
+            className="btn-primary flex items-center gap-2"
+          >
+            {busy === '__create__' && <Loader2 size={16} className="animate-spin" />}
+            创建
+          </button>
+        </div>
+      )}
+
+      <div className="space-y-3">
+        {profiles.map((profile) => (
+          <div
+            key={profile.name}
+            className={`flex items-center gap-3 p-4 rounded-lg ${
+              profile.active ? 'bg-claw-500/10 border border-claw-500/30' : 'bg-dark-600'
+            }`}
+          >
+            <div className="flex-1 min-w-0">
+              <div className="flex items-center gap-2">
+                <p className="text-sm text-white">{profile.name}</p>
+                {profile.active && (
+                  <span className="text-xs px-2 py-0.5 rounded-full bg-claw-500/20 text-claw-300">
+                    当前
+                  </span>
+                )}
+              </div>
+              {profile.description && (
+                <p className="text-xs text-gray-400">{profile.description}</p>
+              )}
+              <p className="text-xs text-gray-500 truncate">
+                {profile.initialized
+                  ? `${profile.providers} 个模型提供商 · ${profile.channels} 个渠道`
+                  : '尚未配置'}
+                {profile.gateway_running !== null &&
+                  ` · Gateway ${profile.gateway_running ? '运行中' : '已停止'}`}
+              </p>
+              <p className="text-xs text-gray-600 truncate">{profile.config_dir}</p>
+            </div>
+            {busy === profile.name ? (
+              <Loader2 size={18} className="text-gray-400 animate-spin" />
+            ) : profile.active ? (
+              <Check size={18} className="text-claw-400" />
+            ) : (
+              <>
+                <button
+                  onClick={() => handleSwitch(profile.name)}
+                  disabled={busy !== null}
+                  className="text-sm text-claw-400 hover:text-claw-300 disabled:opacity-50"
+                >
+                  切换
+                </button>
+                {profile.name !== 'default' && (
+                  <button
+                    onClick={() => handleDelete(profile)}
+                    disabled={busy !== null}
+                    className="text-gray-500 hover:text-red-400 disabled:opacity-50"
+                    title="删除档案"
+                  >
+                    <Trash2 size={16} />
+                  </button>
+                )}
+              </>
+            )}
+          </div>
+        ))}
+      </div>
+
+      {message && <p className="text-xs text-gray-400 mt-3">{message}</p>}
+    </div>
+  );
+}
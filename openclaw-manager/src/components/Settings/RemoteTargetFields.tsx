@@ -0,0 +1,65 @@
+import { RemoteTarget } from '../../lib/tauri';
+
+export const EMPTY_WEBDAV: RemoteTarget = { type: 'web_dav', url: '', username: '', password: '' };
+export const EMPTY_S3: RemoteTarget = {
+  type: 's3',
+  endpoint: '',
+  bucket: '',
+  region: '',
+  access_key_id: '',
+  secret_access_key: '',
+  prefix: '',
+};
+
+interface RemoteTargetFieldsProps {
+  target: RemoteTarget;
+  onChange: (target: RemoteTarget) => void;
+}
+
+// WebDAV / S3 存储位置表单（设置同步和远程备份共用）
+export function RemoteTargetFields({ target, onChange }: RemoteTargetFieldsProps) {
+  const field = (label: string, key: string, type = 'text') => (
+    <div>
+      <label className="block text-sm text-gray-400 mb-2">{label}</label>
+      <input
+        type={type}
+        value={(target as unknown as Record<string, string>)[key] ?? ''}
+        onChange={(e) => onChange({ ...target, [key]: e.target.value } as RemoteTarget)}
+        className="input-base"
+      />
+    </div>
+  );
+
+  return (
+    <>
+      <div>
+        <label className="block text-sm text-gray-400 mb-2">存储位置</label>
+        <select
+          value={target.type}
+          onChange={(e) => onChange(e.target.value === 's3' ? EMPTY_S3 : EMPTY_WEBDAV)}
+          className="input-base"
+        >
+          <option value="web_dav">WebDAV（坚果云、Nextcloud 等）</option>
+          <option value="s3">S3 兼容对象存储</option>
+        </select>
+      </div>
+
+      {target.type === 'web_dav' ? (
+        <>
+          {field('WebDAV 地址', 'url')}
+          {field('用户名', 'username')}
+          {field('密码', 'password', 'password')}
+        </>
+      ) : (
+        <>
+          {field('Endpoint', 'endpoint')}
+          {field('Bucket', 'bucket')}
+          {field('Region', 'region')}
+          {field('Access Key ID', 'access_key_id')}
+          {field('Secret Access Key', 'secret_access_key', 'password')}
+          {field('路径前缀（可选）', 'prefix')}
+        </>
+      )}
+    </>
+  );
+}
@@ -0,0 +1,172 @@
+import { useEffect, useState } from 'react';
+import { Cloud, Loader2, RefreshCw } from 'lucide-react';
+import { api, isTauri, SyncSettings, SyncStatus } from '../../lib/tauri';
+import { EMPTY_WEBDAV, RemoteTargetFields } from './RemoteTargetFields';
+
+const ACTION_LABELS: Record<string, string> = {
+  uploaded: '已上传本机设置',
+  downloaded: '已应用其他电脑的设置',
+  merged: '已合并两边的修改',
+  up_to_date: '已是最新',
+};
+
+// 设置同步：多台电脑之间同步 Manager 设置和 AI Provider 配置（加密后保存在 WebDAV / S3）
+export function SyncSection() {
+  const [status, setStatus] = useState<SyncStatus | null>(null);
+  const [form, setForm] = useState<SyncSettings | null>(null);
+  const [passphrase, setPassphrase] = useState('');
+  const [saving, setSaving] = useState(false);
+  const [syncing, setSyncing] = useState(false);
+  const [message, setMessage] = useState<string | null>(null);
+
+  const fetchStatus = async () => {
+    if (!isTauri()) return;
+    try {
+      const result = await api.getSyncStatus();
+      setStatus(result);
+      setForm(result.settings);
+    } catch (e) {
+      setMessage(String(e));
+    }
+  };
+
+  useEffect(() => {
+    fetchStatus();
+  }, []);
+
+  if (!form) return null;
+
+  const target = form.target ?? EMPTY_WEBDAV;
+
+  const handleSave = async () => {
+    setSaving(true);
+    setMessage(null);
+    try {
+      const result = await api.configureSync({ ...form, target }, passphrase);
+      setStatus(result);
+      setForm(result.settings);
+      setPassphrase('');
+      setMessage('同步设置已保存');
+    } catch (e) {
+      setMessage(String(e));
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  const handleSync = async () => {
+    setSyncing(true);
+    setMessage(null);
+    try {
+      const report = await api.syncNow();
+      setMessage(
+        ACTION_LABELS[report.action] +
+          (report.conflicts.length ? `，${report.conflicts.length} 项冲突` : '')
+      );
+    } catch (e) {
+      setMessage(String(e));
+    } finally {
+      setSyncing(false);
+      await fetchStatus();
+    }
+  };
+
+  return (
+    <div className="bg-dark-700 rounded-2xl p-6 border border-dark-500">
+      <div className="flex items-center gap-3 mb-6">
+        <div className="w-10 h-10 rounded-xl bg-cyan-500/20 flex items-center justify-center">
+          <Cloud size={20} className="text-cyan-400" />
+        </div>
+        <div className="flex-1">
+          <h3 className="text-lg font-semibold text-white">设置同步</h3>
+          <p className="text-xs text-gray-500">在多台电脑之间同步设置和 AI Provider（加密后上传）</p>
+        </div>
+        <label className="relative inline-flex items-center cursor-pointer">
+          <input
+            type="checkbox"
+            className="sr-only peer"
+            checked={form.enabled}
+            onChange={(e) => setForm({ ...form, enabled: e.target.checked })}
+          />
+          <div className="w-11 h-6 bg-dark-500 peer-focus:ring-2 peer-focus:ring-claw-500/50 rounded-full peer peer-checked:after:translate-x-full after:content-[''] after:absolute after:top-[2px] after:left-[2px] after:bg-white after:rounded-full after:h-5 after:w-5 after:transition-all peer-checked:bg-claw-500"></div>
+        </label>
+      </div>
+
+      <div className="space-y-4">
+        <RemoteTargetFields target={target} onChange={(t) => setForm({ ...form, target: t })} />
+
+        <div>
+          <label className="block text-sm text-gray-400 mb-2">同步口令</label>
+          <input
+            type="password"
+            value={passphrase}
+            onChange={(e) => setPassphrase(e.target.value)}
+            placeholder={form.passphrase ? '已设置，留空保持不变' : '所有电脑需使用相同的口令'}
+            className="input-base"
+          />
+        </div>
+
+        <div className="flex items-center justify-between p-4 bg-dark-600 rounded-lg">
+          <div>
+            <p className="text-sm text-white">同步 API Key</p>
+            <p className="text-xs text-gray-500">默认只同步 Provider 的地址和模型，密钥留在本机</p>
+          </div>
+          <input
+            type="checkbox"
+            checked={form.include_secrets}
+            onChange={(e) => setForm({ ...form, include_secrets: e.target.checked })}
+          />
+        </div>
+
+        <div>
+          <label className="block text-sm text-gray-400 mb-2">自动同步间隔（分钟，0 为仅手动）</label>
+          <input
+            type="number"
+            min={0}
+            value={form.interval_minutes}
+            onChange={(e) => setForm({ ...form, interval_minutes: Number(e.target.value) || 0 })}
+            className="input-base"
+          />
+        </div>
+
+        {status && (
+          <div className="text-xs text-gray-500 space-y-1">
+            <p>
+              上次同步：{status.last_synced_at ? new Date(status.last_synced_at).toLocaleString() : '从未同步'}
+            </p>
+            {status.last_error && <p className="text-red-400">上次同步失败：{status.last_error}</p>}
+            {status.conflicts.length > 0 && (
+              <p className="text-amber-400">
+                冲突：
+                {status.conflicts
+                  .map((c) => `${c.field}（保留${c.kept === 'remote' ? '其他电脑' : '本机'}）`)
+                  .join('、')}
+              </p>
+            )}
+          </div>
+        )}
+
+        <div className="flex gap-3">
+          <button
+            onClick={handleSave}
+            disabled={saving}
+            className="btn-primary flex items-center gap-2"
+          >
+            {saving && <Loader2 size={16} className="animate-spin" />}
+            保存
+          </button>
+          <button
+            onClick={handleSync}
+            disabled={syncing || !status?.settings.enabled}
+            className="btn-secondary flex items-center gap-2"
+          >
+            {syncing ? <Loader2 size={16} className="animate-spin" /> : <RefreshCw size={16} />}
+            立即同步
+          </button>
+        </div>
+
+        {message && <p className="text-xs text-gray-400">{message}</p>}
+      </div>
+    </div>
+  );
+}
@@ -1,5 +1,8 @@
 import { useState } from 'react';
-import { invoke } from '@tauri-apps/api/core';
+import { invoke, invokeConfirmed } from '../../lib/tauri';
+import { ProfilesSection } from './ProfilesSection';
+import { SyncSection } from './SyncSection';
+import { BackupSection } from './BackupSection';
 import {
   User,
   Shield,
@@ -38,10 +41,16 @@ export function Settings({ onEnvironmentChange }: SettingsProps) {
     if (!confirm('警告：此操作将从 服务器上 拉取最新的开发代码，可能包含不稳定的功能。\n\n确定要继续吗？')) {
       return;
     }
+    const gitRef = prompt('要同步的 tag 或 commit SHA（留空则使用默认分支最新代码）', '');
+    if (gitRef === null) {
+      return;
+    }
     
     setSyncing(true);
     try {
-      const result = await invoke<InstallResult>('sync_openclaw_github');
+      const result = await invoke<InstallResult>('sync_openclaw_github', {
+        gitRef: gitRef.trim() || null,
+      });
       if (result.success) {
         alert('同步成功！\n' + result.message);
         onEnvironmentChange?.();
@@ -83,7 +92,21 @@ export function Settings({ onEnvironmentChange }: SettingsProps) {
     setUninstalling(true);
     setUninstallResult(null);
     try {
-      const result = await invoke<InstallResult>('uninstall_openclaw');
+      // 存在运行中的网关、守护进程等依赖时，确认后级联清理
+      const check = await invoke<{ blocking: { detail: string }[]; can_cascade: boolean }>(
+        'check_uninstall',
+        { target: 'openclaw' }
+      );
+      let cascade = false;
+      if (check.blocking.length > 0) {
+        const reasons = check.blocking.map((d) => `• ${d.detail}`).join('\n');
+        if (!check.can_cascade || !window.confirm(`以下组件依赖 OpenClaw：\n${reasons}\n\n是否一并清理并继续卸载？`)) {
+          setUninstallResult({ success: false, message: '已取消卸载', error: reasons });
+          return;
+        }
+        cascade = true;
+      }
+      const result = await invokeConfirmed<InstallResult>('uninstall_openclaw', { cascade });
       setUninstallResult(result);
       if (result.success) {
         // 通知环境状态变化，触发重新检查
@@ -212,6 +235,15 @@ export function Settings({ onEnvironmentChange }: SettingsProps) {
           </div>
         </div>
 
+        {/* 配置档案 */}
+        <ProfilesSection onSwitched={onEnvironmentChange} />
+
+        {/* 设置同步 */}
+        <SyncSection />
+
+        {/* 远程备份 */}
+        <BackupSection />
+
         {/* 高级设置 */}
         <div className="bg-dark-700 rounded-2xl p-6 border border-dark-500">
           <div className="flex items-center gap-3 mb-6">
@@ -1,6 +1,5 @@
 import { useState, useEffect } from 'react';
 import { motion, AnimatePresence } from 'framer-motion';
-import { invoke } from '@tauri-apps/api/core';
 import { 
   CheckCircle2,
   Loader2, 
@@ -12,6 +11,8 @@ import {
   Package
 } from 'lucide-react';
 import { setupLogger } from '../../lib/logger';
+import { formatEta, invoke } from '../../lib/tauri';
+import type { InstallEstimate, InstallOperation, StepTiming } from '../../lib/tauri';
 
 interface EnvironmentStatus {
   node_installed: boolean;
@@ -19,6 +20,11 @@ interface EnvironmentStatus {
   node_version_ok: boolean;
   openclaw_installed: boolean;
   openclaw_version: string | null;
+  git_installed: boolean;
+  git_version: string | null;
+  ffmpeg_installed: boolean;
+  ffmpeg_version: string | null;
+  ffmpeg_version_ok: boolean;
   config_dir_exists: boolean;
   ready: boolean;
   os: string;
@@ -28,8 +34,10 @@ interface InstallResult {
   success: boolean;
   message: string;
   error: string | null;
+  timings?: StepTiming[];
 }
 
+
 interface SetupProps {
   onComplete: () => void;
   /** 是否嵌入模式（嵌入到 Dashboard 中显示） */
@@ -42,6 +50,26 @@ export function Setup({ onComplete, embedded = false }: SetupProps) {
   const [installing, setInstalling] = useState<'nodejs' | 'openclaw' | null>(null);
   const [error, setError] = useState<string | null>(null);
   const [step, setStep] = useState<'check' | 'install' | 'complete'>('check');
+  // 根据历史安装耗时估算的剩余时间
+  const [estimate, setEstimate] = useState<{ totalMs: number; startedAt: number } | null>(null);
+  const [now, setNow] = useState(Date.now());
+
+  useEffect(() => {
+    if (!estimate) return;
+    const timer = setInterval(() => setNow(Date.now()), 1000);
+    return () => clearInterval(timer);
+  }, [estimate]);
+
+  const loadEstimate = async (operation: InstallOperation) => {
+    const startedAt = Date.now();
+    setNow(startedAt);
+    try {
+      const result = await invoke<InstallEstimate>('get_install_estimate', { operation });
+      setEstimate(result.total_ms ? { totalMs: result.total_ms, startedAt } : null);
+    } catch {
+      setEstimate(null);
+    }
+  };
 
   const checkEnvironment = async () => {
     setupLogger.info('检查系统环境...');
@@ -92,10 +120,12 @@ export function Setup({ onComplete, embedded = false }: SetupProps) {
     setupLogger.info('开始安装 Node.js...');
     setInstalling('nodejs');
     setError(null);
+    loadEstimate('install_nodejs');
     
     try {
       // 先尝试直接安装
       const result = await invoke<InstallResult>('install_nodejs');
+      setupLogger.state('安装耗时', result.timings);
       
       if (result.success) {
         setupLogger.info('✅ Node.js 安装成功');
@@ -119,6 +149,7 @@ export function Setup({ onComplete, embedded = false }: SetupProps) {
       }
     } finally {
       setInstalling(null);
+      setEstimate(null);
     }
   };
 
@@ -127,9 +158,11 @@ export function Setup({ onComplete, embedded = false }: SetupProps) {
     setupLogger.info('开始安装 OpenClaw...');
     setInstalling('openclaw');
     setError(null);
+    loadEstimate('install_openclaw');
     
     try {
       const result = await invoke<InstallResult>('install_openclaw');
+      setupLogger.state('安装耗时', result.timings);
       
       if (result.success) {
         setupLogger.info('✅ OpenClaw 安装成功，初始化配置...');
@@ -154,6 +187,7 @@ export function Setup({ onComplete, embedded = false }: SetupProps) {
       }
     } finally {
       setInstalling(null);
+      setEstimate(null);
     }
   };
 
@@ -288,6 +322,13 @@ export function Setup({ onComplete, embedded = false }: SetupProps) {
               )}
             </div>
 
+            {/* 预计剩余时间 */}
+            {installing && estimate && (
+              <p className="text-xs text-dark-400 text-center">
+                预计剩余：{formatEta(estimate.totalMs - (now - estimate.startedAt))}
+              </p>
+            )}
+
             {/* 错误信息 */}
             {error && (
               <motion.div
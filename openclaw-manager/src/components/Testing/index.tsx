@@ -1,26 +1,29 @@
 import { useState } from 'react';
 import { motion } from 'framer-motion';
-import { invoke } from '@tauri-apps/api/core';
 import {
   CheckCircle,
   XCircle,
   Play,
   Loader2,
   Stethoscope,
+  Wrench,
 } from 'lucide-react';
 import clsx from 'clsx';
 import { testingLogger } from '../../lib/logger';
+import { invoke, invokeConfirmed, isConfirmationCancelled, OrphanProcess } from '../../lib/tauri';
 
 interface DiagnosticResult {
   name: string;
   passed: boolean;
   message: string;
   suggestion: string | null;
+  fix?: string | null;
 }
 
 export function Testing() {
   const [diagnosticResults, setDiagnosticResults] = useState<DiagnosticResult[]>([]);
   const [loading, setLoading] = useState(false);
+  const [fixing, setFixing] = useState<string | null>(null);
 
   const runDiagnostics = async () => {
     testingLogger.action('运行系统诊断');
@@ -46,6 +49,35 @@ export function Testing() {
     }
   };
 
+  // 结束残留进程前列出进程，由用户确认
+  const confirmOrphanCleanup = async () => {
+    const orphans = await invoke<OrphanProcess[]>('get_orphan_processes');
+    if (orphans.length === 0) return false;
+    const list = orphans.map((o) => `PID ${o.pid}：${o.reason}\n  ${o.command}`).join('\n');
+    return window.confirm(`将结束以下残留进程：\n\n${list}\n\n确定继续吗？`);
+  };
+
+  const applyFix = async (fix: string) => {
+    if (fix === 'cleanup_orphans' && !(await confirmOrphanCleanup())) {
+      await runDiagnostics();
+      return;
+    }
+    testingLogger.action('自动修复', { fix });
+    setFixing(fix);
+    try {
+      const message = await invokeConfirmed<string>('apply_fix', { fix });
+      testingLogger.info(message);
+      alert(message);
+      await runDiagnostics();
+    } catch (e) {
+      if (isConfirmationCancelled(e)) return;
+      testingLogger.error('自动修复失败', e);
+      alert(`修复失败: ${e}`);
+    } finally {
+      setFixing(null);
+    }
+  };
+
   // 统计结果
   const passedCount = diagnosticResults.filter(r => r.passed).length;
   const failedCount = diagnosticResults.filter(r => !r.passed).length;
@@ -132,6 +164,20 @@ export function Testing() {
                         💡 {result.suggestion}
                       </p>
                     )}
+                    {!result.passed && result.fix && (
+                      <button
+                        onClick={() => applyFix(result.fix!)}
+                        disabled={fixing !== null || loading}
+                        className="btn-secondary mt-2 flex items-center gap-1.5 text-xs px-2.5 py-1"
+                      >
+                        {fixing === result.fix ? (
+                          <Loader2 size={12} className="animate-spin" />
+                        ) : (
+                          <Wrench size={12} />
+                        )}
+                        自动修复
+                      </button>
+                    )}
                   </div>
                 </div>
               ))}
@@ -151,7 +197,7 @@ export function Testing() {
         <div className="bg-dark-700/50 rounded-xl p-4 border border-dark-500">
           <h4 className="text-sm font-medium text-gray-400 mb-2">诊断说明</h4>
           <ul className="text-sm text-gray-500 space-y-1">
-            <li>• 系统诊断会检查 Node.js、OpenClaw 安装、配置文件等状态</li>
+            <li>• 系统诊断会检查 Node.js、OpenClaw 安装、配置文件、系统时间等状态</li>
             <li>• AI 连接测试请前往 <span className="text-claw-400">AI 配置</span> 页面进行</li>
             <li>• 渠道测试请前往 <span className="text-claw-400">消息渠道</span> 页面进行</li>
           </ul>
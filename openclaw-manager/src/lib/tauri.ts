@@ -1,4 +1,4 @@
-import { invoke } from '@tauri-apps/api/core';
+import { invoke as tauriInvoke } from '@tauri-apps/api/core';
 import { apiLogger } from './logger';
 
 // 检查是否在 Tauri 环境中运行
@@ -6,6 +6,45 @@ export function isTauri(): boolean {
   return typeof window !== 'undefined' && '__TAURI_INTERNALS__' in window;
 }
 
+export type CommandErrorKind = 'failed' | 'internal' | 'policy_denied';
+
+// 后端命令返回的错误（kind 为 internal 表示命令内部 panic，policy_denied 表示只读模式拒绝执行）
+// requestId 为请求编号，可用 api.getRequestTrace 查看该次调用的日志、审计记录和崩溃报告
+export class CommandError extends Error {
+  kind: CommandErrorKind;
+  command: string;
+  requestId: string;
+
+  constructor(error: { kind: CommandErrorKind; command: string; message: string; request_id: string }) {
+    super(error.message);
+    this.name = 'CommandError';
+    this.kind = error.kind;
+    this.command = error.command;
+    this.requestId = error.request_id;
+  }
+
+  // 直接显示错误时只显示错误信息
+  toString() {
+    return this.message;
+  }
+}
+
+function toCommandError(error: unknown): unknown {
+  if (error && typeof error === 'object' && 'kind' in error && 'message' in error) {
+    return new CommandError(error as ConstructorParameters<typeof CommandError>[0]);
+  }
+  return error;
+}
+
+// 调用后端命令，错误转换为 CommandError
+export async function invoke<T>(cmd: string, args?: Record<string, unknown>): Promise<T> {
+  try {
+    return await tauriInvoke<T>(cmd, args);
+  } catch (error) {
+    throw toCommandError(error);
+  }
+}
+
 // 带日志的 invoke 封装（自动检查 Tauri 环境）
 async function invokeWithLog<T>(cmd: string, args?: Record<string, unknown>): Promise<T> {
   if (!isTauri()) {
@@ -22,6 +61,62 @@ async function invokeWithLog<T>(cmd: string, args?: Record<string, unknown>): Pr
   }
 }
 
+// 危险操作：后端弹出系统确认对话框，用户确认后签发一次性令牌，再携带令牌调用
+export async function invokeConfirmed<T>(cmd: string, args: Record<string, unknown> = {}): Promise<T> {
+  const { token } = await invokeWithLog<{ token: string }>('request_confirmation', { operation: cmd });
+  return invokeWithLog<T>(cmd, { ...args, confirmToken: token });
+}
+
+// 命令内部 panic 时的错误（请求编号即错误编号）
+export function parseInternalError(error: unknown): CommandError | null {
+  return error instanceof CommandError && error.kind === 'internal' ? error : null;
+}
+
+// 只读模式下被安全策略拒绝的调用
+export function isPolicyDenied(error: unknown): boolean {
+  return error instanceof CommandError && error.kind === 'policy_denied';
+}
+
+// 失败调用的请求编号，可用 api.getRequestTrace 查看完整记录
+export function parseRequestId(error: unknown): string | null {
+  return error instanceof CommandError && error.requestId ? error.requestId : null;
+}
+
+// 当前 OpenClaw 版本不支持的功能（后端返回 NotSupportedByVersion 错误）
+export function isNotSupportedByVersion(error: unknown): boolean {
+  return String(error).startsWith('NotSupportedByVersion:');
+}
+
+// 用户在系统确认对话框中取消了危险操作
+export function isConfirmationCancelled(error: unknown): boolean {
+  return String(error).startsWith('Cancelled:');
+}
+
+// 磁盘空间或内存不足，后端拒绝启动 Gateway（可询问用户后强制启动）
+export function isLowResources(error: unknown): boolean {
+  return String(error).startsWith('LowResources:');
+}
+
+// 密钥句柄：读取配置时密钥字段为句柄，新输入的密钥先换成句柄再提交，明文不在前端保存
+export const SECRET_HANDLE_PREFIX = 'secret-handle:';
+
+export function isSecretHandle(value: string): boolean {
+  return value.startsWith(SECRET_HANDLE_PREFIX);
+}
+
+export async function toSecretHandle(value: string): Promise<string> {
+  if (!value || isSecretHandle(value)) return value;
+  return invokeWithLog<string>('stage_secret', { value });
+}
+
+// 剩余时间文案（安装进度和下载事件中的 ETA）
+export function formatEta(ms: number | null | undefined): string | null {
+  if (ms == null) return null;
+  if (ms <= 0) return '即将完成';
+  if (ms < 60_000) return `约 ${Math.ceil(ms / 1000)} 秒`;
+  return `约 ${Math.ceil(ms / 60_000)} 分钟`;
+}
+
 // 服务状态
 export interface ServiceStatus {
   running: boolean;
@@ -30,6 +125,8 @@ export interface ServiceStatus {
   uptime_seconds: number | null;
   memory_mb: number | null;
   cpu_percent: number | null;
+  // Gateway 的启动方：external 表示在 Manager 之外启动（如终端中执行 openclaw gateway start），只监控不接管
+  managed_by?: 'manager' | 'external' | null;
 }
 
 // 系统信息
@@ -133,6 +230,8 @@ export interface DiagnosticResult {
   passed: boolean;
   message: string;
   suggestion: string | null;
+  // 可自动修复时的修复项 ID（如 sync_clock）
+  fix?: string | null;
 }
 
 // AI 测试结果
@@ -145,23 +244,1322 @@ export interface AITestResult {
   latency_ms: number | null;
 }
 
+// 网络探测结果
+export interface ProbeResult {
+  reachable: boolean;
+  status: number | null;
+  latency_ms: number | null;
+  failure:
+    | 'dns'
+    | 'timeout'
+    | 'connect'
+    | 'tls'
+    | 'untrusted_certificate'
+    | 'captive_portal'
+    | 'other'
+    | null;
+  error: string | null;
+}
+
+// 网络诊断（端点 × 直连/代理）
+export interface NetworkDiagnostics {
+  proxy: string | null;
+  captive_portal: boolean;
+  endpoints: {
+    endpoint: { name: string; category: string; url: string };
+    direct: ProbeResult;
+    via_proxy: ProbeResult | null;
+  }[];
+  remediation: string | null;
+}
+
+// 下载测速（npm 源 / GitHub）
+export interface SpeedTestResult {
+  name: string;
+  category: 'npm' | 'github';
+  url: string;
+  latency_ms: number | null;
+  bytes: number;
+  duration_ms: number;
+  throughput_kbps: number | null;
+  error: string | null;
+}
+
+export interface SpeedTest {
+  tested_at: string;
+  results: SpeedTestResult[];
+  summary: string;
+}
+
+// 导入的企业根证书（HTTPS 解密代理环境）
+export interface CaCertificateStatus {
+  installed: boolean;
+  path: string | null;
+  count: number;
+  sha256: string | null;
+}
+
+// 下载缓存（内容寻址，未完成的下载可续传）
+export interface DownloadCacheUsage {
+  path: string;
+  entries: { sha256: string; file_name: string; size: number; modified: string | null }[];
+  partial_bytes: number;
+  total_bytes: number;
+}
+
+// 进行中的下载（限速见设置 download_limit_kbps）
+export interface DownloadInfo {
+  id: string;
+  url: string;
+  file_name: string;
+  downloaded: number;
+  total: number | null;
+  // 下载速度（字节/秒）和预计剩余时间（秒），未知时为 null
+  bytes_per_sec: number | null;
+  eta_secs: number | null;
+  paused: boolean;
+}
+
+// 已下载、等待安装的 OpenClaw 更新
+export interface StagedUpdate {
+  version: string;
+  tarball_url: string;
+  path: string;
+  downloaded_at: string;
+  apply_on_restart: boolean;
+}
+
+// 最近一次更新（健康检查失败时自动回滚，并发送 update://rolled-back 事件）
+export interface UpdateRecord {
+  from_version: string | null;
+  to_version: string;
+  applied_at: string;
+  rolled_back: boolean;
+  reason: string | null;
+  config_backup?: string | null;
+}
+
+// 等待维护窗口执行的操作
+export type MaintenanceAction =
+  | { kind: 'update'; version: string; queued_at: string }
+  | { kind: 'restart_gateway'; reason: string; queued_at: string };
+
+export interface PendingMaintenance {
+  actions: MaintenanceAction[];
+  in_window: boolean;
+  next_window: string | null;
+}
+
+// Provider 费用预算（达到 80% / 100% 时发送 budget://alert 事件）
+export interface BudgetStatus {
+  provider: string;
+  period: 'weekly' | 'monthly';
+  period_start: string;
+  limit_usd: number;
+  spent_usd: number;
+  percent: number;
+  threshold: 80 | 100 | null;
+  paused: boolean;
+}
+
+// 本地缓存代理（测试时缓存相同的 Provider 请求）
+export interface CacheStats {
+  entries: number;
+  hits: number;
+  misses: number;
+}
+
+export interface CacheProxyStatus {
+  enabled: boolean;
+  running: boolean;
+  port: number;
+  ttl_secs: number;
+  pid: number | null;
+  routes: Record<string, string>;
+  stats: CacheStats | null;
+}
+
+// 受 Manager 管理的辅助进程（隧道、缓存代理、Ollama 等）
+export interface SidecarStatus {
+  name: string;
+  program: string;
+  running: boolean;
+  pid: number | null;
+  restarts: number;
+  uptime_secs: number | null;
+  last_exit: string | null;
+  restart:
+    | { type: 'never' }
+    | { type: 'on_failure'; max_restarts: number }
+    | { type: 'always' };
+}
+
+// Gateway 日志分析
+export type LogIssueCategory =
+  | 'provider_auth'
+  | 'provider_quota'
+  | 'channel_webhook'
+  | 'skill_crash'
+  | 'network'
+  | 'other';
+
+export interface LogIssue {
+  category: LogIssueCategory;
+  signature: string;
+  module: string | null;
+  count: number;
+  first_seen: string | null;
+  last_seen: string | null;
+  sample: string;
+}
+
+export interface LogInsights {
+  range: string;
+  files: string[];
+  total_entries: number;
+  errors: number;
+  warnings: number;
+  issues: LogIssue[];
+}
+
+// 源码构建日志（source-build-log 事件）
+export interface SourceBuildLog {
+  step: 'clone' | 'install' | 'build' | 'link' | 'verify';
+  line: string;
+}
+
+// 配置目录外部修改（config://changed 事件）
+export interface ConfigChangeEvent {
+  categories: ('config' | 'env' | 'credentials' | 'skills')[];
+  paths: string[];
+  config_valid: boolean | null;
+  error: string | null;
+}
+
+// 单条消息的处理时间线
+export interface TraceStep {
+  kind: 'user_message' | 'provider_call' | 'skill_call' | 'log';
+  label: string;
+  offset_ms: number;
+  duration_ms: number | null;
+  is_error: boolean;
+  detail: string | null;
+}
+
+export interface RequestTrace {
+  session_id: string;
+  message_id: string;
+  started_at: string;
+  total_ms: number;
+  steps: TraceStep[];
+  slowest: string | null;
+}
+
+// Gateway 严重错误（gateway://error 事件）
+export interface GatewayErrorEvent {
+  category: LogIssueCategory;
+  module: string | null;
+  timestamp: string | null;
+  message: string;
+  cause: string;
+  action:
+    | 'open_provider_settings'
+    | 'open_channel_settings'
+    | 'open_skills'
+    | 'run_network_diagnostics'
+    | 'view_logs';
+  action_label: string;
+}
+
+// 配置写入冲突（save 返回 CONFIG_CONFLICT 错误时查询）
+export interface ConfigDiffEntry {
+  path: string;
+  base: unknown | null;
+  ours: unknown | null;
+  theirs: unknown | null;
+  conflict: boolean;
+}
+
+export interface ConfigConflict {
+  detected_at: string;
+  diff: ConfigDiffEntry[];
+  base: unknown;
+  ours: unknown;
+  theirs: unknown;
+}
+
+// 配置预设（预览时 dryRun 为 true，返回差异和仍需填写的配置项）
+export interface PresetField {
+  key: string;
+  label: string;
+  path: string;
+  secret: boolean;
+  filled: boolean;
+}
+
+export interface ConfigPreset {
+  id: string;
+  name: string;
+  description: string;
+  channels: string[];
+  fields: PresetField[];
+}
+
+export interface PresetPlan {
+  preset: ConfigPreset;
+  changes: { path: string; before: unknown | null; after: unknown | null }[];
+  missing: string[];
+  applied: boolean;
+}
+
+// 隐私模式（关闭遥测、只用本地模型、不检查更新）
+export interface PrivacyStatus {
+  enabled: boolean;
+  remote_providers: string[];
+  outbound: { component: string; pid: number; remote: string }[];
+}
+
+// 匿名使用统计（默认关闭），payload 为下一次上报的完整请求体
+export interface TelemetryPreview {
+  enabled: boolean;
+  endpoint: string;
+  blocked_by_privacy: boolean;
+  pending: number;
+  last_sent: string | null;
+  payload: unknown;
+}
+
+export type CrashKind = 'panic' | 'unclean_exit';
+
+export interface CrashReportSummary {
+  id: string;
+  kind: CrashKind;
+  created_at: string;
+  message: string;
+  submitted: boolean;
+}
+
+export interface CrashReport extends CrashReportSummary {
+  manager_version: string;
+  os: string;
+  arch: string;
+  location: string | null;
+  thread: string | null;
+  command: string | null;
+  correlation_id: string | null;
+  backtrace: string | null;
+  logs: string[];
+}
+
+export interface AuditEntry {
+  timestamp: string;
+  action: string;
+  target: string;
+  success: boolean;
+  detail: unknown;
+  request_id?: string;
+}
+
+export interface RequestTrace {
+  request_id: string;
+  command: string | null;
+  logs: string[];
+  audit: AuditEntry[];
+  crash_report: string | null;
+}
+
+export interface BenchmarkSample {
+  latency_ms: number;
+  output_tokens: number | null;
+  error: string | null;
+}
+
+export interface BenchmarkResult {
+  id: string;
+  profile: string;
+  api: string;
+  created_at: string;
+  iterations: number;
+  succeeded: number;
+  p50_ms: number | null;
+  p95_ms: number | null;
+  tokens_per_sec: number | null;
+  samples: BenchmarkSample[];
+}
+
+export type Severity = 'info' | 'low' | 'moderate' | 'high' | 'critical';
+
+export interface SeverityCounts {
+  info: number;
+  low: number;
+  moderate: number;
+  high: number;
+  critical: number;
+  total: number;
+}
+
+export interface VulnerabilityScan {
+  scanned_at: string;
+  openclaw_version: string | null;
+  counts: SeverityCounts;
+  highest: Severity | null;
+  advisories: {
+    package: string;
+    severity: Severity;
+    title: string;
+    url: string | null;
+    range: string | null;
+    fix_available: boolean;
+  }[];
+}
+
+export interface SkillUpdate {
+  name: string;
+  current_version: string;
+  latest_version: string;
+  changelog: string | null;
+}
+
+// skill-update-progress 事件
+export interface SkillUpdateProgress {
+  name: string;
+  index: number;
+  total: number;
+  status: 'updating' | 'updated' | 'failed';
+  version: string | null;
+  error: string | null;
+}
+
+// Agent 工作区文件
+export interface WorkspaceEntry {
+  name: string;
+  path: string;
+  is_dir: boolean;
+  size: number;
+  modified: string | null;
+}
+
+export interface WorkspaceFile {
+  path: string;
+  size: number;
+  modified: string | null;
+  content: string | null;
+  is_binary: boolean;
+  truncated: boolean;
+}
+
+// Agent 工作区占用情况（超出上限、增长过快或出现可疑文件时发送 workspace://alert 事件）
+export interface SuspiciousFile {
+  path: string;
+  kind: 'executable' | 'large_archive';
+  size: number;
+  modified: string | null;
+}
+
+export interface WorkspaceUsage {
+  agent: string;
+  root: string;
+  total_bytes: number;
+  file_count: number;
+  quota_bytes: number | null;
+  over_quota: boolean;
+  suspicious: SuspiciousFile[];
+  partial: boolean;
+}
+
+export interface WorkspaceAlert {
+  agent: string;
+  kind: 'quota_exceeded' | 'rapid_growth' | 'dangerous_file';
+  message: string;
+  path: string | null;
+  total_bytes: number;
+}
+
+// Shell 技能命令策略（规则支持 * / ? 通配符，拒绝规则优先）
+export interface ShellPolicy {
+  allow: string[];
+  deny: string[];
+  default_action: 'allow' | 'deny';
+}
+
+export interface PolicyDecision {
+  allowed: boolean;
+  segments: { command: string; allowed: boolean; matched_rule: string | null }[];
+}
+
+// 浏览器技能运行时（安装进度通过 browser-runtime-progress 事件推送）
+export interface BrowserRuntime {
+  source: 'system' | 'playwright';
+  name: string;
+  path: string;
+}
+
+export interface BrowserRuntimeStatus {
+  runtimes: BrowserRuntime[];
+  configured_path: string | null;
+  playwright_cache: string;
+}
+
+export interface BrowserRuntimeProgress {
+  line: string;
+  percent: number | null;
+}
+
+// Python 技能运行环境（技能固定的解释器写入 skills.entries.<skill>.env.PYTHON）
+export interface PythonInterpreter {
+  path: string;
+  version: string;
+  source: 'path' | 'system' | 'pyenv' | 'conda' | 'venv';
+  version_ok: boolean;
+}
+
+export interface SkillPythonEnv {
+  skill: string;
+  pinned: string | null;
+  venv: string | null;
+  has_requirements: boolean;
+}
+
+// 任务队列（冲突操作进行中时排队，重启 Manager 后继续执行）
+export type JobKind =
+  | { kind: 'install_openclaw' }
+  | { kind: 'update_openclaw' }
+  | { kind: 'apply_update'; version: string }
+  | { kind: 'restart_gateway'; reason: string };
+
+export type Job = JobKind & {
+  id: number;
+  label: string;
+  priority: number;
+  status: 'queued' | 'running' | 'succeeded' | 'failed' | 'cancelled';
+  maintenance_window: boolean;
+  attempts: number;
+  created_at: string;
+  started_at: string | null;
+  finished_at: string | null;
+  message: string | null;
+  error: string | null;
+};
+
+// 未完成的安装（被中断后重新运行安装会清理残留并跳过已完成的步骤）
+export interface InstallPipelineState {
+  pipeline: 'node' | 'openclaw';
+  started_at: string;
+  attempts: number;
+  steps: {
+    name: string;
+    status: 'running' | 'done' | 'failed';
+    updated_at: string;
+    error: string | null;
+  }[];
+}
+
+// 安装计划（只检测不执行，fallback 步骤只在前面的步骤失败时执行）
+export type InstallComponent = 'nodejs' | 'openclaw' | 'git' | 'ffmpeg';
+
+export interface InstallPlanStep {
+  id: string;
+  description: string;
+  package_manager: string | null;
+  command: string | null;
+  artifact: string | null;
+  elevation: boolean;
+  estimated_seconds: number;
+  fallback: boolean;
+}
+
+export interface InstallPlan {
+  component: InstallComponent;
+  os: string;
+  installed_version: string | null;
+  blocker: string | null;
+  requires_elevation: boolean;
+  estimated_seconds: number;
+  steps: InstallPlanStep[];
+}
+
+// 废弃的配置项
+export interface ConfigLintWarning {
+  path: string;
+  kind: 'renamed' | 'removed';
+  replacement: string | null;
+  since: string;
+  message: string;
+  auto_migrate: boolean;
+}
+
+// 废弃配置自动迁移结果
+export interface ConfigMigrationResult {
+  migrated: string[];
+  remaining: ConfigLintWarning[];
+}
+
+// 渠道消息模板（多语言，{{name}} 为变量占位符）
+export interface MessageTemplate {
+  id: string;
+  kind: 'notification' | 'auto_reply';
+  channel: string;
+  description?: string | null;
+  variables: string[];
+  locales: Record<string, string>;
+  default_locale: string;
+}
+
+// 消息模板检查结果
+export interface TemplateIssue {
+  locale: string | null;
+  severity: 'error' | 'warning';
+  message: string;
+}
+
+// 消息模板预览
+export interface TemplatePreview {
+  locale: string;
+  text: string;
+  missing: string[];
+}
+
+// OAuth 应用配置（client_secret 保存后为钥匙串引用）
+export interface OAuthProviderConfig {
+  id: string;
+  name: string;
+  authorize_url: string;
+  token_url: string;
+  client_id: string;
+  client_secret?: string | null;
+  scopes: string[];
+  extra_params?: Record<string, string>;
+  redirect_port?: number | null;
+  pkce?: boolean;
+  // 刷新后写入 access_token 的位置：openclaw.json 路径或 env:KEY
+  sync_to?: string[];
+}
+
+// 已授权的 OAuth 连接（令牌保存在系统钥匙串中）
+export interface OAuthConnection extends OAuthProviderConfig {
+  tokens: string;
+  expires_at: number | null;
+  has_refresh_token: boolean;
+  scope: string | null;
+  connected_at: number;
+  refreshed_at: number | null;
+  last_error: string | null;
+}
+
+// 扫码配对状态
+export type PairingStatus = 'waiting_for_scan' | 'connected' | 'expired' | 'failed' | 'cancelled';
+
+// 扫码配对成功后登录的账号
+export interface PairedAccount {
+  account_id: string;
+  name: string | null;
+  identity: string | null;
+}
+
+// 扫码配对会话（qr_data_url 为图片，qr_text 为需要前端生成二维码的原始内容）
+export interface PairingSession {
+  id: string;
+  channel: string;
+  account_id: string | null;
+  status: PairingStatus;
+  qr_data_url: string | null;
+  qr_text: string | null;
+  message: string | null;
+  account: PairedAccount | null;
+  started_at: number;
+  expires_at: number;
+}
+
+// 渠道通讯录条目
+export interface ChannelContact {
+  id: string;
+  name: string | null;
+  kind: 'user' | 'group';
+}
+
+// 渠道访问控制（deny_from 优先于允许列表）
+export interface ChannelAccessList {
+  channel: string;
+  dm_policy: 'pairing' | 'allowlist' | 'open' | 'disabled' | null;
+  group_policy: 'open' | 'allowlist' | 'disabled' | null;
+  allow_from: string[];
+  allowed_groups: string[];
+  deny_from: string[];
+}
+
+// 渠道限流策略（字段为 null 表示不限制）
+export interface RateLimitPolicy {
+  channel: string;
+  messages_per_minute: number | null;
+  cooldown_secs: number | null;
+  max_message_length: number | null;
+  duplicate_window_secs: number | null;
+}
+
+// 限流模拟的流量：逐条消息（events）和固定间隔连发（bursts）
+export interface TrafficPattern {
+  events?: { user: string; at_ms: number; length?: number; text?: string | null }[];
+  bursts?: {
+    user: string;
+    count: number;
+    interval_ms: number;
+    start_ms?: number;
+    length?: number;
+    repeat_text?: string | null;
+  }[];
+}
+
+// 限流模拟结果
+export interface RateLimitSimulation {
+  total: number;
+  accepted: number;
+  rejected: {
+    user: string;
+    at_ms: number;
+    reason: 'rate_limited' | 'cooldown' | 'too_long' | 'duplicate';
+  }[];
+  users: Record<string, { accepted: number; rejected: number }>;
+}
+
+// Agent 记忆设置（字段为 null 表示使用 OpenClaw 默认值）
+export interface MemorySettings {
+  context_tokens: number | null;
+  context_pruning: 'off' | 'cache-ttl' | null;
+  compaction_mode: 'default' | 'safeguard' | null;
+  reserve_tokens_floor: number | null;
+  memory_flush: boolean | null;
+  memory_flush_threshold: number | null;
+  persistent_memory: boolean | null;
+}
+
+// 记忆文件统计
+export interface MemoryFiles {
+  files: number;
+  bytes: number;
+}
+
+// Agent 记忆概况（overridden 为 Agent 单独覆盖的字段）
+export interface AgentMemory {
+  agent: string;
+  settings: MemorySettings;
+  overridden: (keyof MemorySettings)[];
+  sessions: MemoryFiles;
+  persistent: MemoryFiles;
+}
+
+// 知识库来源（本地文档或网页）
+export interface KnowledgeSource {
+  id: string;
+  agent: string;
+  kind: 'path' | 'url';
+  location: string;
+  title: string;
+  added_at: number;
+  indexed_at: number | null;
+  files: number;
+  bytes: number;
+  last_error: string | null;
+}
+
+// knowledge-index-progress 事件
+export interface KnowledgeIndexProgress {
+  agent: string;
+  stage: 'fetch' | 'index';
+  step: number;
+  total: number;
+  source: string | null;
+  done: boolean;
+  error: string | null;
+}
+
+// Embedding 预设方案（如 Ollama 本地 bge）
+export interface EmbeddingPreset {
+  id: string;
+  name: string;
+  provider: 'openai' | 'gemini' | 'local';
+  model: string | null;
+  base_url: string | null;
+  needs_api_key: boolean;
+}
+
+// Embedding 配置（provider 为 null 表示自动选择，api_key 为空表示保留原有的 Key）
+export interface EmbeddingConfig {
+  provider: 'openai' | 'gemini' | 'local' | null;
+  model: string | null;
+  base_url: string | null;
+  api_key?: string | null;
+  has_api_key?: boolean;
+  fallback: 'openai' | 'gemini' | 'local' | 'none' | null;
+}
+
+// Embedding 测试结果
+export interface EmbeddingTestResult {
+  success: boolean;
+  dimensions: number | null;
+  latency_ms: number;
+  error: string | null;
+}
+
+// 报表导出结果
+export interface ReportExport {
+  path: string;
+  rows: number;
+}
+
+// 版本更新信息
+export interface UpdateInfo {
+  update_available: boolean;
+  current_version: string | null;
+  latest_version: string | null;
+  vulnerabilities: SeverityCounts | null;
+  compatibility: CompatibilityCheck | null;
+  error: string | null;
+}
+
+// 今日用量
+export interface UsageSummary {
+  sessions: number;
+  user_messages: number;
+  assistant_messages: number;
+  errors: number;
+  cost: number;
+}
+
+// 首页概览的一个板块（updated_at 为数据获取时间，毫秒）
+export interface DashboardSection<T> {
+  data: T | null;
+  error: string | null;
+  updated_at: number;
+}
+
+// 首页概览
+export interface DashboardSnapshot {
+  service: DashboardSection<ServiceStatus>;
+  update: DashboardSection<UpdateInfo>;
+  errors: DashboardSection<{ errors: number; warnings: number; issues: LogIssue[] }>;
+  usage_today: DashboardSection<UsageSummary>;
+  channels: DashboardSection<{ id: string; enabled: boolean; problem: string | null }[]>;
+  jobs: DashboardSection<Job[]>;
+  generated_at: number;
+}
+
+// 探测缓存条目（版本检查等，时间为毫秒）
+export interface ProbeCacheEntry {
+  key: string;
+  stored_at: number;
+  expires_at: number;
+  expired: boolean;
+}
+
+// 可回放的后端事件（seq 为全局递增序号）
+export interface RecordedEvent<T = unknown> {
+  seq: number;
+  channel: string;
+  ts: number;
+  payload: T;
+}
+
+// 保存的主窗口状态（物理像素）
+export interface WindowState {
+  x: number | null;
+  y: number | null;
+  width: number | null;
+  height: number | null;
+  maximized: boolean;
+  last_view: string | null;
+}
+
+// 深度链接（openclaw-manager://<view>[/<action>]）解析后的页面
+export interface DeepLinkRoute {
+  view: string;
+  action: string | null;
+  params: Record<string, string>;
+  url: string;
+}
+
+// 解析后的 Gateway 日志行（日志控制台通过 console-log-lines 事件接收）
+export type LogLevel = 'trace' | 'debug' | 'info' | 'warn' | 'error' | 'fatal';
+
+export interface LogEntry {
+  timestamp: string | null;
+  level: LogLevel;
+  module: string | null;
+  request_id: string | null;
+  message: string;
+}
+
+// 独立控制台窗口
+export type ConsoleKind = 'logs' | 'chat';
+
+export interface ConsoleOptions {
+  min_level?: LogLevel | null;
+  module?: string | null;
+  agent?: string | null;
+  always_on_top?: boolean;
+}
+
+export interface ConsoleWindow {
+  label: string;
+  kind: ConsoleKind;
+  options: ConsoleOptions;
+  opened_at: string;
+}
+
+// 对话控制台的回复（console-chat-reply 事件）
+export interface ConsoleChatReply {
+  request_id: number;
+  reply: string | null;
+  error: string | null;
+}
+
+// 界面偏好（修改后通过 ui-preferences-changed 事件推送给所有窗口）
+export type ThemePreference = 'light' | 'dark' | 'system';
+
+export interface UiPreferences {
+  theme: ThemePreference;
+  font_scale: number;
+  reduced_motion: boolean;
+}
+
+export interface UiPreferencesState extends UiPreferences {
+  resolved_theme: 'light' | 'dark';
+  system_theme: 'light' | 'dark' | null;
+}
+
+// 配置中的密钥（id 为配置路径，只返回掩码）
+export interface SecretInfo {
+  id: string;
+  masked: string;
+}
+
+// 复制脱敏配置的结果
+export interface SanitizedConfigExport {
+  redacted: number;
+  bytes: number;
+}
+
+// OpenClaw 能力检测
+export type OpenClawFeature =
+  | 'agent'
+  | 'agent_local'
+  | 'directory'
+  | 'memory_index'
+  | 'logs'
+  | 'plugins'
+  | 'skills'
+  | 'doctor';
+
+export interface FeatureSupport {
+  feature: OpenClawFeature;
+  supported: boolean;
+  missing: string | null;
+}
+
+export interface Capabilities {
+  version: string;
+  commands: string[];
+  features: FeatureSupport[];
+  detected_at: string;
+}
+
+// Manager 与 OpenClaw 的版本兼容表
+export type CompatibilityStatus = 'compatible' | 'untested' | 'unsupported';
+
+export type MatrixSource = 'remote' | 'cached' | 'bundled';
+
+export interface CompatibilityRange {
+  manager_min: string | null;
+  manager_max: string | null;
+  openclaw_min: string | null;
+  openclaw_tested: string | null;
+  openclaw_max: string | null;
+  notes: string | null;
+}
+
+export interface CompatibilityMatrix {
+  schema: number;
+  updated_at: string;
+  releases: CompatibilityRange[];
+}
+
+export interface LoadedMatrix {
+  source: MatrixSource;
+  matrix: CompatibilityMatrix;
+}
+
+export interface CompatibilityCheck {
+  manager_version: string;
+  openclaw_version: string;
+  status: CompatibilityStatus;
+  message: string | null;
+  range: CompatibilityRange | null;
+  source: MatrixSource;
+  matrix_updated_at: string;
+}
+
+// 安装步骤耗时
+export interface StepTiming {
+  step: string;
+  label: string;
+  started_at: string;
+  duration_ms: number;
+  success: boolean;
+}
+
+// 安装操作（与匿名统计事件名一致）
+export type InstallOperation = 'install_nodejs' | 'install_openclaw' | 'update_openclaw';
+
+// 根据最近几次成功安装估算的耗时
+export interface InstallEstimate {
+  operation: InstallOperation;
+  samples: number;
+  total_ms: number | null;
+  steps: Record<string, number>;
+}
+
+// install-progress 事件（步骤状态变化）
+export interface InstallProgress {
+  pipeline: 'node' | 'openclaw';
+  step: {
+    name: string;
+    status: 'running' | 'done' | 'failed';
+    updated_at: string;
+    error: string | null;
+  };
+  eta_ms: number | null;
+}
+
+// install-timing 事件
+export interface InstallTimingEvent {
+  operation: InstallOperation;
+  step: string;
+  label: string;
+  phase: 'started' | 'finished';
+  duration_ms: number | null;
+  success: boolean | null;
+  elapsed_ms: number;
+  step_estimate_ms: number | null;
+  remaining_ms: number | null;
+}
+
+// 磁盘空间 / 可用内存
+export interface ResourceProblem {
+  kind: 'disk' | 'memory';
+  available_bytes: number;
+  threshold_bytes: number;
+  message: string;
+}
+
+export interface ResourceStatus {
+  disk_path: string;
+  free_disk_bytes: number | null;
+  available_memory_bytes: number | null;
+  problems: ResourceProblem[];
+}
+
+// 每日通知摘要中的一条通知（同一事件、同一对象合并计数）
+export interface DigestEntry {
+  trigger: string;
+  key: string;
+  title: string;
+  body: string;
+  count: number;
+  first_at: string;
+  last_at: string;
+}
+
+// 某一天的通知摘要
+export interface DailyDigest {
+  date: string;
+  entries: DigestEntry[];
+  sent_at: string | null;
+}
+
+// Gateway 的一次崩溃或启动失败
+export interface GatewayFailure {
+  at: string;
+  error: string;
+}
+
+// 更新后的恢复方式：回滚版本、恢复更新前的配置、重新安装
+export type RecoveryAction = 'rollback' | 'revert_config' | 'reinstall';
+
+// 一个恢复方案（不可用时 reason 说明原因）
+export interface RecoveryOption {
+  action: RecoveryAction;
+  label: string;
+  description: string;
+  available: boolean;
+  reason: string | null;
+}
+
+// 更新后的恢复状态（needed 为 true 表示更新后 Gateway 陷入崩溃循环）
+export interface RecoveryState {
+  needed: boolean;
+  update: UpdateRecord | null;
+  installed_version: string | null;
+  failures: GatewayFailure[];
+  options: RecoveryOption[];
+}
+
+// 恢复结果
+export interface RecoveryResult {
+  action: RecoveryAction;
+  success: boolean;
+  message: string;
+  gateway_healthy: boolean;
+}
+
+// 残留进程（之前的 Manager 会话留下的 Gateway / Node 进程、无响应的端口占用进程、僵尸进程）
+export interface OrphanProcess {
+  pid: number;
+  ppid: number;
+  command: string;
+  kind: 'stale_pidfile' | 'port_holder' | 'zombie';
+  reason: string;
+  terminate_pid: number | null;
+}
+
+// 残留进程清理结果
+export interface OrphanCleanup {
+  terminated: number[];
+  errors: string[];
+}
+
+// Gateway 锁文件（~/.openclaw/gateway.lock，Manager 与外部脚本据此判断 Gateway 是否在运行）
+export interface GatewayLock {
+  pid: number;
+  port: number;
+  process_started: string | null;
+  locked_at: string;
+  owner: string;
+  manager_pid?: number | null;
+}
+
+// 锁文件状态（stale 表示进程已退出或 PID 已被复用）
+export interface GatewayLockStatus {
+  state: 'missing' | 'live' | 'stale';
+  lock: GatewayLock | null;
+  lock_path: string;
+  pid_path: string;
+  reason: string | null;
+}
+
+// 配置档案（每个档案使用独立的 OpenClaw 配置目录）
+export interface Profile {
+  name: string;
+  description: string | null;
+  config_dir: string;
+  created_at: string;
+}
+
+// 配置档案及其状态（gateway_running 只对当前档案有值）
+export interface ProfileStatus extends Profile {
+  active: boolean;
+  initialized: boolean;
+  providers: number;
+  channels: number;
+  gateway_running: boolean | null;
+}
+
+// 远程存储（WebDAV / S3 兼容对象存储），密码和密钥保存在系统钥匙串中
+export type RemoteTarget =
+  | { type: 'web_dav'; url: string; username: string; password: string }
+  | {
+      type: 's3';
+      endpoint: string;
+      bucket: string;
+      region: string;
+      access_key_id: string;
+      secret_access_key: string;
+      prefix?: string;
+    };
+
+// 设置同步（passphrase 为钥匙串引用）
+export interface SyncSettings {
+  enabled: boolean;
+  target: RemoteTarget | null;
+  passphrase: string;
+  include_secrets: boolean;
+  interval_minutes: number;
+}
+
+// 两台电脑都修改过的字段（kept 为保留的一方）
+export interface SyncConflict {
+  field: string;
+  kept: 'local' | 'remote';
+  detected_at: string;
+}
+
+// 一次同步的结果
+export interface SyncReport {
+  action: 'uploaded' | 'downloaded' | 'merged' | 'up_to_date';
+  conflicts: SyncConflict[];
+  synced_at: string;
+}
+
+// 设置同步状态
+export interface SyncStatus {
+  settings: SyncSettings;
+  target: string | null;
+  device_id: string;
+  last_synced_at: string | null;
+  last_error: string | null;
+  conflicts: SyncConflict[];
+}
+
+// 远程备份保留规则：始终保留最近 keep_last 个，更早的超过 keep_days 天后删除
+export interface RetentionRules {
+  keep_last: number;
+  keep_days: number;
+}
+
+// 备份方式：完整备份 / 差异备份（只上传相对最近一次完整备份变化的部分）
+export type BackupMode = 'full' | 'differential';
+
+// 远程备份设置（passphrase 为钥匙串引用）
+export interface BackupSettings {
+  remote: RemoteTarget | null;
+  passphrase: string;
+  retention: RetentionRules;
+  interval_hours: number;
+  mode: BackupMode;
+  full_every_days: number;
+  full_every_backups: number;
+}
+
+// 远程备份状态
+export interface BackupStatus {
+  settings: BackupSettings;
+  target: string | null;
+  last_backup_at: string | null;
+  last_error: string | null;
+  full_backup: string | null;
+  differentials: number;
+}
+
+// 远程存储中的一个备份（差异备份的 base 为所依据的完整备份 key）
+export interface RemoteBackup {
+  key: string;
+  name: string;
+  created_at: string | null;
+  size: number | null;
+  kind: BackupMode;
+  base: string | null;
+}
+
+// Manager 管理的组件清单
+export interface Inventory {
+  generated_at: string;
+  node: { path: string | null; version: string; source: string } | null;
+  openclaw: {
+    path: string | null;
+    version: string;
+    install_method: 'npm' | 'git' | 'source';
+    commit: string | null;
+  } | null;
+  skills: { name: string; version: string | null; path: string; linked: boolean }[];
+  daemon: { kind: string; registered: boolean; location: string | null };
+  tunnels: { name: string; path: string | null; version: string | null }[];
+  managed_runtimes: { name: string; path: string; version: string | null }[];
+}
+
+export type WindowsPackageManager = 'winget' | 'choco' | 'scoop';
+
+// install.sh 等脚本安装的遗留物
+export interface LegacyArtifact {
+  id: string;
+  kind: 'profile_snippet' | 'config_dir' | 'stale_binary';
+  path: string;
+  line: number | null;
+  detail: string;
+  action: 'adopt' | 'remove' | 'manual';
+}
+
+// ~/.openclaw 权限审计
+export interface PermissionAudit {
+  root: string;
+  scanned: number;
+  issues: {
+    path: string;
+    kind: 'loose_directory' | 'exposed_credential' | 'world_writable' | 'wrong_owner' | 'broad_acl';
+    detail: string;
+  }[];
+}
+
+// 会话脱敏规则
+export interface RedactionRule {
+  name: string;
+  pattern: string;
+  replacement: string;
+}
+
+// 脱敏后的会话
+export interface RedactedSession {
+  id: string;
+  path: string;
+  markdown: string;
+  replacements: Record<string, number>;
+}
+
+// 会话统计
+export interface ConversationStats {
+  range: string;
+  agents: {
+    agent: string;
+    sessions: number;
+    user_messages: number;
+    assistant_messages: number;
+    avg_latency_ms: number | null;
+    errors: number;
+    error_rate: number;
+  }[];
+  top_skills: { name: string; count: number }[];
+  daily: { date: string; messages: number }[];
+}
+
 // API 封装（带日志）
 export const api = {
   // 服务管理
   getServiceStatus: () => invokeWithLog<ServiceStatus>('get_service_status'),
-  startService: () => invokeWithLog<string>('start_service'),
+  getDashboardSnapshot: () => invokeWithLog<DashboardSnapshot>('get_dashboard_snapshot'),
+  startService: (force = false) => invokeWithLog<string>('start_service', { force }),
   stopService: () => invokeWithLog<string>('stop_service'),
-  restartService: () => invokeWithLog<string>('restart_service'),
+  restartService: (force = false) => invokeWithLog<string>('restart_service', { force }),
+  adoptGateway: () => invokeWithLog<string>('adopt_gateway'),
   getLogs: (lines?: number) => invokeWithLog<string[]>('get_logs', { lines }),
+  getLogInsights: (range: '24h' | '7d' | '30d' | 'all' = '24h') =>
+    invokeWithLog<LogInsights>('get_log_insights', { range }),
+  traceRequest: (sessionId: string, messageId: string) =>
+    invokeWithLog<RequestTrace>('trace_request', { sessionId, messageId }),
 
   // 系统信息
   getSystemInfo: () => invokeWithLog<SystemInfo>('get_system_info'),
   checkOpenclawInstalled: () => invokeWithLog<boolean>('check_openclaw_installed'),
   getOpenclawVersion: () => invokeWithLog<string | null>('get_openclaw_version'),
+  // macOS: auto 不会自动安装 Homebrew；official_pkg 直接下载官方安装包
+  installNodejs: (macosMethod: 'auto' | 'homebrew' | 'official_pkg' = 'auto') =>
+    invokeWithLog<{ success: boolean; message: string; error: string | null }>('install_nodejs', {
+      options: { macos_method: macosMethod },
+    }),
+  // GitHub 同步和源码构建需要 git
+  installGit: () =>
+    invokeWithLog<{ success: boolean; message: string; error: string | null }>('install_git'),
+  installFfmpeg: () =>
+    invokeWithLog<{ success: boolean; message: string; error: string | null }>('install_ffmpeg'),
 
   // 配置管理
   getConfig: () => invokeWithLog<unknown>('get_config'),
   saveConfig: (config: unknown) => invokeWithLog<string>('save_config', { config }),
+  getConfigConflict: () => invokeWithLog<ConfigConflict | null>('get_config_conflict'),
+  resolveConfigConflict: (strategy: 'ours' | 'theirs' | 'manual', merged?: unknown) =>
+    invokeConfirmed<string>('resolve_config_conflict', { strategy, merged }),
+  lintConfig: () => invokeWithLog<ConfigLintWarning[]>('lint_config'),
+  migrateConfigKeys: (paths?: string[]) =>
+    invokeWithLog<ConfigMigrationResult>('migrate_config_keys', { paths: paths ?? null }),
+  listConfigPresets: () => invokeWithLog<ConfigPreset[]>('list_config_presets'),
+  applyConfigPreset: (name: string, values?: Record<string, string>, dryRun = false) =>
+    invokeWithLog<PresetPlan>('apply_config_preset', { name, values: values ?? null, dryRun }),
   getEnvValue: (key: string) => invokeWithLog<string | null>('get_env_value', { key }),
   saveEnvValue: (key: string, value: string) =>
     invokeWithLog<string>('save_env_value', { key, value }),
@@ -187,7 +1585,7 @@ export const api = {
       models,
     }),
   deleteProvider: (providerName: string) =>
-    invokeWithLog<string>('delete_provider', { providerName }),
+    invokeConfirmed<string>('delete_provider', { providerName }),
   setPrimaryModel: (modelId: string) =>
     invokeWithLog<string>('set_primary_model', { modelId }),
   addAvailableModel: (modelId: string) =>
@@ -199,10 +1597,333 @@ export const api = {
   getChannelsConfig: () => invokeWithLog<ChannelConfig[]>('get_channels_config'),
   saveChannelConfig: (channel: ChannelConfig) =>
     invokeWithLog<string>('save_channel_config', { channel }),
+  listMessageTemplates: () => invokeWithLog<MessageTemplate[]>('list_message_templates'),
+  validateMessageTemplate: (template: MessageTemplate) =>
+    invokeWithLog<TemplateIssue[]>('validate_message_template', { template }),
+  saveMessageTemplate: (template: MessageTemplate) =>
+    invokeWithLog<string>('save_message_template', { template }),
+  deleteMessageTemplate: (id: string, channel: string) =>
+    invokeWithLog<string>('delete_message_template', { id, channel }),
+  renderTemplatePreview: (
+    id: string,
+    vars: Record<string, string>,
+    options: { channel?: string; locale?: string; draft?: MessageTemplate } = {}
+  ) =>
+    invokeWithLog<TemplatePreview>('render_template_preview', {
+      id,
+      vars,
+      channel: options.channel ?? null,
+      locale: options.locale ?? null,
+      draft: options.draft ?? null,
+    }),
+
+  // 扫码配对（获取二维码后轮询 getChannelPairing 直到状态不再是 waiting_for_scan）
+  listQrChannels: () => invokeWithLog<string[]>('list_qr_channels'),
+  startChannelPairing: (channel: string, accountId?: string, force = false) =>
+    invokeWithLog<PairingSession>('start_channel_pairing', {
+      channel,
+      accountId: accountId ?? null,
+      force,
+    }),
+  getChannelPairing: (id: string) => invokeWithLog<PairingSession>('get_channel_pairing', { id }),
+  cancelChannelPairing: (id: string) => invokeWithLog<void>('cancel_channel_pairing', { id }),
+
+  // 渠道访问控制
+  listChannelContacts: (channel: string, kind?: 'user' | 'group') =>
+    invokeWithLog<ChannelContact[]>('list_channel_contacts', { channel, kind: kind ?? null }),
+  getChannelAccess: (channel: string) =>
+    invokeWithLog<ChannelAccessList>('get_channel_access', { channel }),
+  validateChannelAccess: (access: ChannelAccessList) =>
+    invokeWithLog<string[]>('validate_channel_access', { access }),
+  saveChannelAccess: (access: ChannelAccessList) =>
+    invokeWithLog<string>('save_channel_access', { access }),
+
+  // 渠道限流
+  getRateLimits: () => invokeWithLog<RateLimitPolicy[]>('get_rate_limits'),
+  saveRateLimit: (policy: RateLimitPolicy) =>
+    invokeWithLog<string>('save_rate_limit', { policy }),
+  simulateRateLimit: (policy: RateLimitPolicy, pattern: TrafficPattern) =>
+    invokeWithLog<RateLimitSimulation>('simulate_rate_limit', { policy, pattern }),
+
+  // Agent 记忆（agent 为空时表示默认 Agent / 所有 Agent 的默认值）
+  getAgentMemory: (agent?: string) => invokeWithLog<AgentMemory>('get_agent_memory', { agent }),
+  saveMemorySettings: (agent: string | null, settings: MemorySettings) =>
+    invokeWithLog<string>('save_memory_settings', { agent, settings }),
+  clearAgentMemory: (agent: string | null, scope: 'sessions' | 'persistent' | 'all') =>
+    invokeConfirmed<MemoryFiles>('clear_agent_memory', { agent, scope }),
+
+  // 知识库（添加或移除来源后需要重建索引）
+  listKnowledgeSources: (agent?: string) =>
+    invokeWithLog<KnowledgeSource[]>('list_knowledge_sources', { agent }),
+  addKnowledgeSource: (agent: string | null, location: string) =>
+    invokeWithLog<KnowledgeSource>('add_knowledge_source', { agent, location }),
+  removeKnowledgeSource: (id: string) => invokeWithLog<string>('remove_knowledge_source', { id }),
+  reindexKnowledge: (agent?: string) =>
+    invokeWithLog<KnowledgeSource[]>('reindex_knowledge', { agent }),
+
+  // Embedding Provider（与对话模型分开配置）
+  listEmbeddingPresets: () => invokeWithLog<EmbeddingPreset[]>('list_embedding_presets'),
+  getEmbeddingConfig: () => invokeWithLog<EmbeddingConfig>('get_embedding_config'),
+  testEmbeddingProvider: (config: EmbeddingConfig) =>
+    invokeWithLog<EmbeddingTestResult>('test_embedding_provider', { config }),
+  saveEmbeddingConfig: (config: EmbeddingConfig) =>
+    invokeWithLog<string>('save_embedding_config', { config }),
+
+  // 报表导出（range: 24h / 7d / 30d / all）
+  exportReport: (
+    kind: 'usage' | 'costs' | 'uptime' | 'errors',
+    range: string,
+    format: 'csv' | 'json',
+    path: string
+  ) => invokeWithLog<ReportExport>('export_report', { kind, range, format, path }),
+
+  // 探测缓存（key 为空时清除全部）
+  listProbeCache: () => invokeWithLog<ProbeCacheEntry[]>('list_probe_cache'),
+  invalidateCache: (key?: string) => invokeWithLog<number>('invalidate_cache', { key }),
+
+  // 事件回放（页面挂载或刷新后补齐错过的事件，since 为已收到的最大序号）
+  replayEvents: <T = unknown>(channel?: string, since?: number) =>
+    invokeWithLog<RecordedEvent<T>[]>('replay_events', { channel, since }),
+
+  // 窗口状态与深度链接（运行期间打开的链接通过 deep-link 事件推送）
+  getWindowState: () => invokeWithLog<WindowState>('get_window_state'),
+  setLastView: (view: string) => invokeWithLog<void>('set_last_view', { view }),
+  takePendingDeepLink: () => invokeWithLog<DeepLinkRoute | null>('take_pending_deep_link'),
+
+  // 独立控制台窗口（日志跟随、测试对话）
+  openConsole: (kind: ConsoleKind, options?: ConsoleOptions) =>
+    invokeWithLog<ConsoleWindow>('open_console', { kind, options }),
+  listConsoles: () => invokeWithLog<ConsoleWindow[]>('list_consoles'),
+  updateConsoleOptions: (label: string, options: ConsoleOptions) =>
+    invokeWithLog<ConsoleWindow>('update_console_options', { label, options }),
+  closeConsole: (label: string) => invokeWithLog<void>('close_console', { label }),
+  consoleSendMessage: (label: string, message: string) =>
+    invokeWithLog<number>('console_send_message', { label, message }),
+
+  // 界面偏好
+  getUiPreferences: () => invokeWithLog<UiPreferencesState>('get_ui_preferences'),
+  setUiPreferences: (preferences: UiPreferences) =>
+    invokeWithLog<UiPreferencesState>('set_ui_preferences', { preferences }),
+
+  // 剪贴板（密钥在后端复制并自动清除，前端不接触明文）
+  listSecrets: () => invokeWithLog<SecretInfo[]>('list_secrets'),
+  copySecretToClipboard: (id: string, clearAfterSecs?: number) =>
+    invokeWithLog<number>('copy_secret_to_clipboard', { id, clearAfterSecs }),
+  exportSanitizedConfig: () => invokeWithLog<SanitizedConfigExport>('export_sanitized_config'),
+
+  // 密钥句柄
+  stageSecret: (value: string) => invokeWithLog<string>('stage_secret', { value }),
+  openDashboard: () => invokeWithLog<void>('open_dashboard'),
+
+  // OpenClaw 能力检测（按版本缓存）
+  getCapabilities: (refresh = false) =>
+    invokeWithLog<Capabilities>('get_capabilities', { refresh }),
+
+  // 版本兼容表（远程获取失败时使用打包的兼容表）
+  getCompatibilityMatrix: (refresh = false) =>
+    invokeWithLog<LoadedMatrix>('get_compatibility_matrix', { refresh }),
+  checkOpenclawCompatibility: (version?: string) =>
+    invokeWithLog<CompatibilityCheck>('check_openclaw_compatibility', { version: version ?? null }),
+
+  // 安装耗时（根据历史记录估算剩余时间）
+  getInstallEstimate: (operation: InstallOperation) =>
+    invokeWithLog<InstallEstimate>('get_install_estimate', { operation }),
+
+  // 磁盘 / 内存防护（低于阈值时拒绝启动 Gateway）
+  getResourceStatus: () => invokeWithLog<ResourceStatus>('get_resource_status'),
+
+  // 时区（Manager 设置的 timezone 字段，为空时使用系统时区）
+  listTimezones: () => invokeWithLog<string[]>('list_timezones'),
+
+  // 通知摘要（非紧急通知汇总为每日摘要）
+  getDailyDigest: (date?: string) =>
+    invokeWithLog<DailyDigest>('get_daily_digest', { date: date ?? null }),
+
+  // 更新失败恢复（更新后 Gateway 无法启动时回滚版本、恢复配置或重新安装）
+  getRecoveryOptions: () => invokeWithLog<RecoveryState>('get_recovery_options'),
+  executeRecovery: (option: RecoveryAction) =>
+    option === 'rollback'
+      ? invokeWithLog<RecoveryResult>('execute_recovery', { option })
+      : invokeConfirmed<RecoveryResult>('execute_recovery', { option }),
+
+  // 残留进程清理（结束前需用户确认，pids 为空时结束全部）
+  getOrphanProcesses: () => invokeWithLog<OrphanProcess[]>('get_orphan_processes'),
+  cleanupOrphans: (pids: number[] = []) =>
+    invokeConfirmed<OrphanCleanup>('cleanup_orphans', { pids }),
+
+  // Gateway 锁文件
+  getGatewayLock: () => invokeWithLog<GatewayLockStatus>('get_gateway_lock'),
+
+  // 配置档案
+  listProfiles: () => invokeWithLog<ProfileStatus[]>('list_profiles'),
+  createProfile: (name: string, description?: string, copyFrom?: string) =>
+    invokeWithLog<Profile>('create_profile', {
+      name,
+      description: description || null,
+      copyFrom: copyFrom || null,
+    }),
+  deleteProfile: (name: string, removeFiles: boolean) =>
+    removeFiles
+      ? invokeConfirmed<string>('delete_profile', { name, removeFiles })
+      : invokeWithLog<string>('delete_profile', { name, removeFiles }),
+  switchProfile: (name: string) => invokeWithLog<string>('switch_profile', { name }),
+
+  // 设置同步
+  getSyncStatus: () => invokeWithLog<SyncStatus>('get_sync_status'),
+  configureSync: (sync: SyncSettings, passphrase?: string) =>
+    invokeWithLog<SyncStatus>('configure_sync', { sync, passphrase: passphrase || null }),
+  syncNow: () => invokeWithLog<SyncReport>('sync_now'),
+
+  // 远程备份
+  getBackupStatus: () => invokeWithLog<BackupStatus>('get_backup_status'),
+  configureRemoteBackup: (backup: BackupSettings, passphrase?: string) =>
+    invokeWithLog<BackupStatus>('configure_remote_backup', { backup, passphrase: passphrase || null }),
+  listRemoteBackups: () => invokeWithLog<RemoteBackup[]>('list_remote_backups'),
+  createRemoteBackup: () => invokeWithLog<RemoteBackup>('create_remote_backup'),
+  restoreRemoteBackup: (key: string) => invokeConfirmed<string>('restore_remote_backup', { key }),
+
+  // OAuth 授权（打开浏览器，本地回调接收授权码）
+  listOAuthPresets: () => invokeWithLog<OAuthProviderConfig[]>('list_oauth_presets'),
+  listOAuthConnections: () => invokeWithLog<OAuthConnection[]>('list_oauth_connections'),
+  startOAuthFlow: (provider: OAuthProviderConfig) =>
+    invokeWithLog<OAuthConnection>('start_oauth_flow', { provider }),
+  refreshOAuthToken: (id: string) => invokeWithLog<OAuthConnection>('refresh_oauth_token', { id }),
+  checkOAuthConnection: (id: string) =>
+    invokeWithLog<OAuthConnection>('check_oauth_connection', { id }),
+  setOAuthSyncTargets: (id: string, targets: string[]) =>
+    invokeWithLog<OAuthConnection>('set_oauth_sync_targets', { id, targets }),
+  disconnectOAuth: (id: string) => invokeConfirmed<string>('disconnect_oauth', { id }),
 
   // 诊断测试
   runDoctor: () => invokeWithLog<DiagnosticResult[]>('run_doctor'),
+  applyFix: (fix: string) => invokeConfirmed<string>('apply_fix', { fix }),
   testAIConnection: () => invokeWithLog<AITestResult>('test_ai_connection'),
   testChannel: (channelType: string) =>
     invokeWithLog<unknown>('test_channel', { channelType }),
+  buildOpenclawFromSource: (repo?: string, gitRef?: string) =>
+    invokeWithLog<{ success: boolean; message: string; error: string | null }>(
+      'build_openclaw_from_source',
+      { repo: repo || null, gitRef: gitRef || null }
+    ),
+  redactSession: (id: string, rules?: RedactionRule[]) =>
+    invokeWithLog<RedactedSession>('redact_session', { id, rules: rules ?? null }),
+  getBudgetStatus: () => invokeWithLog<BudgetStatus[]>('get_budget_status'),
+  resumeBudgetProvider: (provider: string) =>
+    invokeWithLog<string>('resume_budget_provider', { provider }),
+  getCacheProxyStatus: () => invokeWithLog<CacheProxyStatus>('get_cache_proxy_status'),
+  setCacheProxy: (enabled: boolean, ttlSecs?: number) =>
+    invokeWithLog<CacheProxyStatus>('set_cache_proxy', { enabled, ttlSecs }),
+  clearCacheProxy: () => invokeWithLog<CacheStats>('clear_cache_proxy'),
+  listSidecars: () => invokeWithLog<SidecarStatus[]>('list_sidecars'),
+  getSidecarLogs: (name: string, lines?: number) =>
+    invokeWithLog<string[]>('get_sidecar_logs', { name, lines }),
+  restartSidecar: (name: string) => invokeWithLog<SidecarStatus>('restart_sidecar', { name }),
+  getConversationStats: (range: '24h' | '7d' | '30d' | 'all' = '7d') =>
+    invokeWithLog<ConversationStats>('get_conversation_stats', { range }),
+  runNetworkDiagnostics: () => invokeWithLog<NetworkDiagnostics>('run_network_diagnostics'),
+  runSpeedTest: () => invokeWithLog<SpeedTest>('run_speed_test'),
+  scanOpenclawVulnerabilities: () =>
+    invokeWithLog<VulnerabilityScan>('scan_openclaw_vulnerabilities'),
+  getVulnerabilityScan: () => invokeWithLog<VulnerabilityScan | null>('get_vulnerability_scan'),
+  checkSkillUpdates: () => invokeWithLog<SkillUpdate[]>('check_skill_updates'),
+  updateAllSkills: () => invokeWithLog<SkillUpdateProgress[]>('update_all_skills'),
+  listWorkspaceFiles: (agent: string, path?: string) =>
+    invokeWithLog<WorkspaceEntry[]>('list_workspace_files', { agent, path }),
+  readWorkspaceFile: (agent: string, path: string) =>
+    invokeWithLog<WorkspaceFile>('read_workspace_file', { agent, path }),
+  deleteWorkspaceFile: (agent: string, path: string) =>
+    invokeConfirmed<void>('delete_workspace_file', { agent, path }),
+  openInFileManager: (agent: string, path?: string) =>
+    invokeWithLog<void>('open_in_file_manager', { agent, path }),
+  getWorkspaceUsage: () => invokeWithLog<WorkspaceUsage[]>('get_workspace_usage'),
+  getShellPolicy: () => invokeWithLog<ShellPolicy>('get_shell_policy'),
+  saveShellPolicy: (policy: ShellPolicy) =>
+    invokeWithLog<ShellPolicy>('save_shell_policy', { policy }),
+  testPolicy: (command: string, policy?: ShellPolicy) =>
+    invokeWithLog<PolicyDecision>('test_policy', { command, policy }),
+  detectBrowserRuntimes: () => invokeWithLog<BrowserRuntimeStatus>('detect_browser_runtimes'),
+  installBrowserRuntime: () => invokeWithLog<BrowserRuntime>('install_browser_runtime'),
+  setBrowserRuntime: (path: string) => invokeWithLog<void>('set_browser_runtime', { path }),
+  detectPython: () => invokeWithLog<PythonInterpreter[]>('detect_python'),
+  getSkillPythonEnv: (skill: string) =>
+    invokeWithLog<SkillPythonEnv>('get_skill_python_env', { skill }),
+  createSkillVenv: (skill: string, python?: string) =>
+    invokeWithLog<string>('create_skill_venv', { skill, python }),
+  installPythonRequirements: (skill: string) =>
+    invokeWithLog<string>('install_python_requirements', { skill }),
+  setSkillPython: (skill: string, python: string | null) =>
+    invokeWithLog<void>('set_skill_python', { skill, python }),
+  selectFastestMirrors: () => invokeWithLog<unknown>('select_fastest_mirrors'),
+  getPrivacyStatus: () => invokeWithLog<PrivacyStatus>('get_privacy_status'),
+  setPrivacyMode: (enabled: boolean) =>
+    invokeWithLog<PrivacyStatus>('set_privacy_mode', { enabled }),
+  getTelemetryPreview: () => invokeWithLog<TelemetryPreview>('get_telemetry_preview'),
+  setTelemetry: (enabled: boolean, endpoint?: string) =>
+    invokeWithLog<TelemetryPreview>('set_telemetry', { enabled, endpoint: endpoint ?? null }),
+  listCrashReports: () => invokeWithLog<CrashReportSummary[]>('list_crash_reports'),
+  getCrashReport: (id: string) => invokeWithLog<CrashReport>('get_crash_report', { id }),
+  deleteCrashReport: (id: string) => invokeWithLog<void>('delete_crash_report', { id }),
+  submitCrashReport: (id: string) => invokeWithLog<CrashReport>('submit_crash_report', { id }),
+  getRequestTrace: (id: string) => invokeWithLog<RequestTrace>('get_request_trace', { id }),
+  benchmarkProvider: (profile: string, prompt: string, iterations: number) =>
+    invokeWithLog<BenchmarkResult>('benchmark_provider', { profile, prompt, iterations }),
+  listBenchmarkResults: () => invokeWithLog<BenchmarkResult[]>('list_benchmark_results'),
+  getCaCertificate: () => invokeWithLog<CaCertificateStatus>('get_ca_certificate'),
+  importCaCertificate: (path: string) =>
+    invokeWithLog<CaCertificateStatus>('import_ca_certificate', { path }),
+  removeCaCertificate: () => invokeWithLog<string>('remove_ca_certificate'),
+  getDownloadCacheUsage: () => invokeWithLog<DownloadCacheUsage>('get_download_cache_usage'),
+  // 返回释放的字节数
+  clearDownloadCache: () => invokeWithLog<number>('clear_download_cache'),
+  listDownloads: () => invokeWithLog<DownloadInfo[]>('list_downloads'),
+  pauseDownload: (id: string) => invokeWithLog<void>('pause_download', { id }),
+  resumeDownload: (id: string) => invokeWithLog<void>('resume_download', { id }),
+
+  // 分阶段更新：先下载（Gateway 继续运行），再安装或下次启动时安装
+  getStagedUpdate: () => invokeWithLog<StagedUpdate | null>('get_staged_update'),
+  // 已是最新版本时返回 null
+  downloadUpdate: () => invokeWithLog<StagedUpdate | null>('download_update'),
+  applyUpdate: () =>
+    invokeWithLog<{ success: boolean; message: string; error: string | null }>('apply_update'),
+  setUpdateOnRestart: (enabled: boolean) =>
+    invokeWithLog<StagedUpdate>('set_update_on_restart', { enabled }),
+  getLastUpdate: () => invokeWithLog<UpdateRecord | null>('get_last_update'),
+
+  // 维护窗口（自动更新和重启只在窗口内执行）
+  getPendingMaintenanceActions: () =>
+    invokeWithLog<PendingMaintenance>('get_pending_maintenance_actions'),
+  scheduleGatewayRestart: (reason?: string) =>
+    invokeWithLog<void>('schedule_gateway_restart', { reason }),
+  clearMaintenanceActions: () => invokeWithLog<void>('clear_maintenance_actions'),
+  listJobs: (includeFinished?: boolean) =>
+    invokeWithLog<Job[]>('list_jobs', { includeFinished }),
+  cancelJob: (id: number) => invokeWithLog<void>('cancel_job', { id }),
+  getInstallState: () => invokeWithLog<InstallPipelineState[]>('get_install_state'),
+  planInstall: (
+    component: InstallComponent,
+    macosMethod: 'auto' | 'homebrew' | 'official_pkg' = 'auto'
+  ) =>
+    invokeWithLog<InstallPlan>('plan_install', {
+      component,
+      options: { macos_method: macosMethod },
+    }),
+  getInventory: () => invokeWithLog<Inventory>('get_inventory'),
+  detectLegacyInstall: () => invokeWithLog<LegacyArtifact[]>('detect_legacy_install'),
+  migrateLegacyInstall: (ids?: string[]) =>
+    invokeConfirmed<{ applied: string[]; errors: string[]; remaining: LegacyArtifact[] }>(
+      'migrate_legacy_install',
+      { ids: ids ?? null }
+    ),
+  // Windows 包管理器（首选项保存在设置 windows_package_manager 中）
+  getPackageManagers: () =>
+    invokeWithLog<{
+      available: WindowsPackageManager[];
+      preferred: WindowsPackageManager | null;
+      active: WindowsPackageManager | null;
+    }>('get_package_managers'),
+  installWindowsPackage: (pkg: 'node' | 'git' | 'cloudflared' | 'tailscale' | 'ngrok' | 'ffmpeg') =>
+    invokeWithLog<string>('install_windows_package', { package: pkg }),
+  auditPermissions: () => invokeWithLog<PermissionAudit>('audit_permissions'),
+  fixPermissions: () =>
+    invokeConfirmed<{ fixed: number; errors: string[]; audit: PermissionAudit }>('fix_permissions'),
 };